@@ -0,0 +1,690 @@
+// api/src/ab_test_engine.rs
+//
+// Statistics for A/B test analysis: per-variant confidence intervals,
+// two-proportion z-tests for conversion metrics, Welch's t-tests for mean
+// metrics, a chi-square sample-ratio-mismatch check and group-sequential
+// boundaries from an O'Brien-Fleming-type alpha spending function. Pure
+// functions; ab_test_handlers.rs loads the aggregates and records looks.
+
+use shared::{
+    AbMetricComparison, AbMetricKind, AbTestRecommendation, AbVariantStats, SampleRatioCheck,
+    VariantType,
+};
+
+/// Sample-ratio mismatch is flagged below this p-value. Deliberately strict:
+/// with enough traffic, any real assignment bug produces far smaller values.
+pub const SRM_P_THRESHOLD: f64 = 0.001;
+/// Analyses that add less information than this reuse the previous look
+/// instead of spending more alpha.
+pub const MIN_INFORMATION_STEP: f64 = 0.01;
+
+// ─────────────────────────────────────────────────────────
+// Distributions
+// ─────────────────────────────────────────────────────────
+
+/// Complementary error function (Numerical Recipes `erfcc`, fractional
+/// error below 1.2e-7 everywhere, including the far tails).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let ans = t * poly.exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Two-sided p-value of a standard normal statistic.
+pub fn normal_two_sided_p(z: f64) -> f64 {
+    erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0)
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation).
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let p = p.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+fn ln_gamma(x: f64) -> f64 {
+    const COF: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let mut y = x;
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut ser = 1.000_000_000_190_015;
+    for c in COF {
+        y += 1.0;
+        ser += c / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * ser / x).ln()
+}
+
+/// Continued fraction for the incomplete beta function.
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITER: usize = 300;
+    const EPS: f64 = 3e-14;
+    const FPMIN: f64 = 1e-300;
+    let floor = |v: f64| if v.abs() < FPMIN { FPMIN } else { v };
+
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 / floor(1.0 - qab * x / qap);
+    let mut h = d;
+    for m in 1..=MAX_ITER {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 / floor(1.0 + aa * d);
+        c = floor(1.0 + aa / c);
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 / floor(1.0 + aa * d);
+        c = floor(1.0 + aa / c);
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_cf(a, b, x) / a
+    } else {
+        1.0 - front * beta_cf(b, a, 1.0 - x) / b
+    }
+}
+
+/// Two-sided p-value of a Student t statistic; `df` may be fractional.
+pub fn t_two_sided_p(t: f64, df: f64) -> f64 {
+    if !df.is_finite() || df <= 0.0 {
+        return normal_two_sided_p(t);
+    }
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
+/// Critical value `c` with `P(|T| > c) = alpha`.
+pub fn t_critical(alpha: f64, df: f64) -> f64 {
+    let mut hi = normal_quantile(1.0 - alpha / 2.0).max(1.0);
+    while t_two_sided_p(hi, df) > alpha && hi < 1e6 {
+        hi *= 2.0;
+    }
+    let mut lo = 0.0;
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if t_two_sided_p(mid, df) > alpha {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+// ─────────────────────────────────────────────────────────
+// Metric comparisons
+// ─────────────────────────────────────────────────────────
+
+/// Sample moments of one variant's values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    pub n: i64,
+    pub mean: f64,
+    /// Sample variance (n - 1 denominator)
+    pub variance: f64,
+}
+
+/// Wilson score interval, which behaves at rates near 0 or 1 where the
+/// normal approximation does not.
+fn wilson_interval(successes: i64, n: i64, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+    let n = n as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let denom = 1.0 + z2 / n;
+    let centre = (p + z2 / (2.0 * n)) / denom;
+    let half = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+    ((centre - half).max(0.0), (centre + half).min(1.0))
+}
+
+fn relative_lift(control: f64, lift: f64) -> Option<f64> {
+    (control != 0.0).then(|| lift / control.abs() * 100.0)
+}
+
+/// Two-proportion z-test with a pooled standard error; the lift interval
+/// uses the unpooled one.
+pub fn compare_proportions(
+    metric_name: &str,
+    control: (i64, i64),
+    treatment: (i64, i64),
+    alpha: f64,
+) -> AbMetricComparison {
+    let z_crit = normal_quantile(1.0 - alpha / 2.0);
+    let stats = |variant_type, (n, x): (i64, i64)| {
+        let p = if n > 0 { x as f64 / n as f64 } else { 0.0 };
+        let (ci_lower, ci_upper) = wilson_interval(x, n, z_crit);
+        AbVariantStats {
+            variant_type,
+            sample_size: n,
+            mean: p,
+            std_dev: (p * (1.0 - p)).sqrt(),
+            ci_lower,
+            ci_upper,
+        }
+    };
+    let c = stats(VariantType::Control, control);
+    let t = stats(VariantType::Treatment, treatment);
+
+    let lift = t.mean - c.mean;
+    let (n1, n2) = (control.0 as f64, treatment.0 as f64);
+    let (statistic, p_value, lift_se) = if n1 > 0.0 && n2 > 0.0 {
+        let pooled = (control.1 + treatment.1) as f64 / (n1 + n2);
+        let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+        let lift_se = (c.mean * (1.0 - c.mean) / n1 + t.mean * (1.0 - t.mean) / n2).sqrt();
+        if se > 0.0 {
+            let z = lift / se;
+            (z, normal_two_sided_p(z), lift_se)
+        } else {
+            (0.0, 1.0, lift_se)
+        }
+    } else {
+        (0.0, 1.0, 0.0)
+    };
+
+    AbMetricComparison {
+        metric_name: metric_name.to_string(),
+        kind: AbMetricKind::Conversion,
+        absolute_lift: lift,
+        relative_lift_pct: relative_lift(c.mean, lift),
+        lift_ci_lower: lift - z_crit * lift_se,
+        lift_ci_upper: lift + z_crit * lift_se,
+        control: c,
+        treatment: t,
+        test: "two_proportion_z".into(),
+        statistic,
+        degrees_of_freedom: None,
+        p_value,
+    }
+}
+
+/// Welch's t-test, which does not assume the variants share a variance.
+pub fn compare_means(
+    metric_name: &str,
+    control: Moments,
+    treatment: Moments,
+    alpha: f64,
+) -> AbMetricComparison {
+    let stats = |variant_type, m: Moments| {
+        let std_dev = m.variance.max(0.0).sqrt();
+        let half = if m.n > 1 {
+            t_critical(alpha, (m.n - 1) as f64) * std_dev / (m.n as f64).sqrt()
+        } else {
+            0.0
+        };
+        AbVariantStats {
+            variant_type,
+            sample_size: m.n,
+            mean: m.mean,
+            std_dev,
+            ci_lower: m.mean - half,
+            ci_upper: m.mean + half,
+        }
+    };
+    let c = stats(VariantType::Control, control);
+    let t = stats(VariantType::Treatment, treatment);
+    let lift = t.mean - c.mean;
+
+    let testable = control.n > 1 && treatment.n > 1;
+    let v1 = control.variance.max(0.0) / control.n.max(1) as f64;
+    let v2 = treatment.variance.max(0.0) / treatment.n.max(1) as f64;
+    let se = (v1 + v2).sqrt();
+    let (statistic, df, p_value, half) = if testable && se > 0.0 {
+        let df = (v1 + v2).powi(2)
+            / (v1 * v1 / (control.n - 1) as f64 + v2 * v2 / (treatment.n - 1) as f64);
+        let stat = lift / se;
+        (
+            stat,
+            Some(df),
+            t_two_sided_p(stat, df),
+            t_critical(alpha, df) * se,
+        )
+    } else {
+        (0.0, None, 1.0, 0.0)
+    };
+
+    AbMetricComparison {
+        metric_name: metric_name.to_string(),
+        kind: AbMetricKind::Mean,
+        absolute_lift: lift,
+        relative_lift_pct: relative_lift(c.mean, lift),
+        lift_ci_lower: lift - half,
+        lift_ci_upper: lift + half,
+        control: c,
+        treatment: t,
+        test: "welch_t".into(),
+        statistic,
+        degrees_of_freedom: df,
+        p_value,
+    }
+}
+
+/// The comparison's evidence on the z scale, signed like the lift, so
+/// Welch results can be held against normal sequential boundaries.
+pub fn z_score(comparison: &AbMetricComparison) -> f64 {
+    match comparison.kind {
+        AbMetricKind::Conversion => comparison.statistic,
+        AbMetricKind::Mean => {
+            let z = -normal_quantile((comparison.p_value / 2.0).max(f64::MIN_POSITIVE));
+            z.max(0.0) * comparison.statistic.signum()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Sample ratio mismatch
+// ─────────────────────────────────────────────────────────
+
+/// Chi-square goodness-of-fit of the observed split against the
+/// configured one (one degree of freedom).
+pub fn sample_ratio(
+    control_n: i64,
+    treatment_n: i64,
+    expected_control_share: f64,
+) -> SampleRatioCheck {
+    let total = (control_n + treatment_n) as f64;
+    let share = expected_control_share.clamp(0.0, 1.0);
+    let expected = [total * share, total * (1.0 - share)];
+    let observed = [control_n as f64, treatment_n as f64];
+    let chi_square: f64 = observed
+        .iter()
+        .zip(expected)
+        .filter(|(_, e)| *e > 0.0)
+        .map(|(o, e)| (o - e).powi(2) / e)
+        .sum();
+    let p_value = if total > 0.0 {
+        normal_two_sided_p(chi_square.sqrt())
+    } else {
+        1.0
+    };
+    SampleRatioCheck {
+        expected_control_share: share,
+        control_n,
+        treatment_n,
+        chi_square,
+        p_value,
+        mismatch: p_value < SRM_P_THRESHOLD,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Sequential testing
+// ─────────────────────────────────────────────────────────
+
+/// Share of the planned sample collected so far, from the smaller variant.
+pub fn information_fraction(control_n: i64, treatment_n: i64, planned_per_variant: i32) -> f64 {
+    if planned_per_variant <= 0 {
+        return 1.0;
+    }
+    (control_n.min(treatment_n).max(0) as f64 / planned_per_variant as f64).min(1.0)
+}
+
+/// Lan-DeMets O'Brien-Fleming-type spending: cumulative alpha allowed at
+/// information fraction `t`. Almost nothing is spent early, and the full
+/// `alpha` is available once the planned sample is reached.
+pub fn alpha_spent(t: f64, alpha: f64) -> f64 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    let z = normal_quantile(1.0 - alpha / 2.0);
+    erfc(z / t.min(1.0).sqrt() / std::f64::consts::SQRT_2).min(alpha)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriorLook {
+    pub information_fraction: f64,
+    pub alpha_spent: f64,
+    pub crossed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookPlan {
+    pub information_fraction: f64,
+    pub alpha_spent: f64,
+    pub nominal_alpha: f64,
+    pub boundary_z: f64,
+}
+
+/// Plan the next interim look, or `None` when the previous one still
+/// stands: it already crossed its boundary, it was the final look, or too
+/// little information has arrived since. Each look may only use the alpha
+/// that the spending function released since the last one. Testing that
+/// increment on its own is conservative (correlation between looks is
+/// ignored), so the overall false-positive rate stays below `alpha` however
+/// often results are checked.
+pub fn plan_look(previous: Option<PriorLook>, t: f64, alpha: f64) -> Option<LookPlan> {
+    let t = t.clamp(0.0, 1.0);
+    let spent_before = match previous {
+        Some(prev) if prev.crossed || prev.information_fraction >= 1.0 => return None,
+        Some(prev) if t < 1.0 && t - prev.information_fraction < MIN_INFORMATION_STEP => {
+            return None
+        }
+        Some(prev) => prev.alpha_spent,
+        None if t <= 0.0 => return None,
+        None => 0.0,
+    };
+    let cumulative = alpha_spent(t, alpha).max(spent_before);
+    let nominal = (cumulative - spent_before).max(0.0);
+    Some(LookPlan {
+        information_fraction: t,
+        alpha_spent: cumulative,
+        nominal_alpha: nominal,
+        boundary_z: -normal_quantile((nominal / 2.0).max(f64::MIN_POSITIVE)),
+    })
+}
+
+// ─────────────────────────────────────────────────────────
+// Recommendation
+// ─────────────────────────────────────────────────────────
+
+/// The sequential look that currently decides the test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookOutcome {
+    pub information_fraction: f64,
+    pub z_statistic: f64,
+    pub crossed: bool,
+}
+
+pub fn recommend(
+    primary: Option<&AbMetricComparison>,
+    look: Option<LookOutcome>,
+    srm: Option<&SampleRatioCheck>,
+    higher_is_better: bool,
+) -> (AbTestRecommendation, String) {
+    if let Some(srm) = srm.filter(|s| s.mismatch) {
+        return (
+            AbTestRecommendation::InvestigateSampleRatio,
+            format!(
+                "observed split {}/{} deviates from the configured {:.0}% control share \
+                 (p = {:.2e}); results are unreliable until assignment is fixed",
+                srm.control_n,
+                srm.treatment_n,
+                srm.expected_control_share * 100.0,
+                srm.p_value
+            ),
+        );
+    }
+    let (Some(primary), Some(look)) = (primary, look) else {
+        return (
+            AbTestRecommendation::ContinueTest,
+            "not enough data in both variants to analyze yet".into(),
+        );
+    };
+
+    let lift = match primary.relative_lift_pct {
+        Some(pct) => format!("{:+.2}%", pct),
+        None => format!("{:+.4}", primary.absolute_lift),
+    };
+    if look.crossed {
+        let improved = (look.z_statistic > 0.0) == higher_is_better;
+        return if improved {
+            (
+                AbTestRecommendation::ShipTreatment,
+                format!(
+                    "treatment changed {} by {} and crossed the sequential boundary",
+                    primary.metric_name, lift
+                ),
+            )
+        } else {
+            (
+                AbTestRecommendation::KeepControl,
+                format!(
+                    "treatment made {} worse by {} and crossed the sequential boundary",
+                    primary.metric_name, lift
+                ),
+            )
+        };
+    }
+    if look.information_fraction >= 1.0 {
+        return (
+            AbTestRecommendation::NoSignificantDifference,
+            format!(
+                "planned sample reached without a significant difference in {} ({})",
+                primary.metric_name, lift
+            ),
+        );
+    }
+    (
+        AbTestRecommendation::ContinueTest,
+        format!(
+            "{:.0}% of the planned sample collected; {} lift {} is not yet conclusive",
+            look.information_fraction * 100.0,
+            primary.metric_name,
+            lift
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() <= tol
+    }
+
+    #[test]
+    fn distributions_match_reference_values() {
+        assert!(close(normal_cdf(1.96), 0.975, 1e-4));
+        assert!(close(normal_quantile(0.975), 1.959_964, 1e-5));
+        assert!(close(normal_quantile(0.001), -3.090_232, 1e-5));
+        // t(10) two-sided 5% critical value is 2.228
+        assert!(close(t_two_sided_p(2.228, 10.0), 0.05, 1e-3));
+        assert!(close(t_critical(0.05, 10.0), 2.228, 1e-3));
+        // Large df approaches the normal distribution
+        assert!(close(t_critical(0.05, 1e6), 1.96, 1e-3));
+    }
+
+    #[test]
+    fn two_proportion_test_detects_a_real_lift() {
+        let r = compare_proportions("conversion", (1000, 200), (1000, 250), 0.05);
+        assert!(close(r.statistic, 2.677, 1e-2));
+        assert!(close(r.p_value, 0.0074, 5e-4));
+        assert!(close(r.relative_lift_pct.unwrap(), 25.0, 1e-9));
+        assert!(r.lift_ci_lower > 0.0);
+        assert!(r.control.ci_lower < 0.2 && r.control.ci_upper > 0.2);
+    }
+
+    #[test]
+    fn welch_test_handles_unequal_variances() {
+        let control = Moments {
+            n: 30,
+            mean: 10.0,
+            variance: 4.0,
+        };
+        let treatment = Moments {
+            n: 50,
+            mean: 11.5,
+            variance: 25.0,
+        };
+        let r = compare_means("fee", control, treatment, 0.05);
+        // se = sqrt(4/30 + 25/50) = 0.7958; df ≈ 70.2
+        assert!(close(r.statistic, 1.885, 1e-2));
+        assert!(close(r.degrees_of_freedom.unwrap(), 70.2, 0.1));
+        assert!(r.p_value > 0.05 && r.p_value < 0.07);
+        assert!(r.lift_ci_lower < 0.0);
+        assert!(close(z_score(&r), 1.87, 0.02));
+    }
+
+    #[test]
+    fn single_observations_are_not_testable() {
+        let one = Moments {
+            n: 1,
+            mean: 3.0,
+            variance: 0.0,
+        };
+        let r = compare_means("fee", one, one, 0.05);
+        assert_eq!(r.p_value, 1.0);
+        assert!(r.degrees_of_freedom.is_none());
+    }
+
+    #[test]
+    fn sample_ratio_mismatch_needs_strong_evidence() {
+        let fine = sample_ratio(5000, 5200, 0.5);
+        assert!(close(fine.chi_square, 3.92, 0.01));
+        assert!(!fine.mismatch);
+        let broken = sample_ratio(5000, 5400, 0.5);
+        assert!(broken.mismatch);
+        assert!(!sample_ratio(2000, 8000, 0.2).mismatch);
+    }
+
+    #[test]
+    fn alpha_spending_is_back_loaded() {
+        assert!(close(alpha_spent(1.0, 0.05), 0.05, 1e-6));
+        assert!(close(alpha_spent(0.5, 0.05), 0.005_57, 1e-4));
+        assert!(alpha_spent(0.1, 0.05) < 1e-6);
+        assert_eq!(alpha_spent(0.0, 0.05), 0.0);
+    }
+
+    #[test]
+    fn looks_only_spend_newly_released_alpha() {
+        let first = plan_look(None, 0.5, 0.05).unwrap();
+        assert!(first.boundary_z > 2.7);
+
+        let prior = PriorLook {
+            information_fraction: first.information_fraction,
+            alpha_spent: first.alpha_spent,
+            crossed: false,
+        };
+        // Peeking again without new data does not spend anything
+        assert!(plan_look(Some(prior), 0.505, 0.05).is_none());
+
+        let last = plan_look(Some(prior), 1.0, 0.05).unwrap();
+        assert!(close(last.alpha_spent, 0.05, 1e-6));
+        assert!(close(last.nominal_alpha, last.alpha_spent - first.alpha_spent, 1e-12));
+        assert!(last.boundary_z > 1.96);
+
+        let done = PriorLook {
+            information_fraction: 1.0,
+            alpha_spent: last.alpha_spent,
+            crossed: false,
+        };
+        assert!(plan_look(Some(done), 1.0, 0.05).is_none());
+    }
+
+    #[test]
+    fn recommendation_respects_metric_direction_and_srm() {
+        let r = compare_proportions("conversion", (1000, 200), (1000, 250), 0.05);
+        let crossed = LookOutcome {
+            information_fraction: 0.8,
+            z_statistic: r.statistic,
+            crossed: true,
+        };
+        assert_eq!(
+            recommend(Some(&r), Some(crossed), None, true).0,
+            AbTestRecommendation::ShipTreatment
+        );
+        assert_eq!(
+            recommend(Some(&r), Some(crossed), None, false).0,
+            AbTestRecommendation::KeepControl
+        );
+
+        let open = LookOutcome {
+            crossed: false,
+            ..crossed
+        };
+        assert_eq!(
+            recommend(Some(&r), Some(open), None, true).0,
+            AbTestRecommendation::ContinueTest
+        );
+        let finished = LookOutcome {
+            information_fraction: 1.0,
+            ..open
+        };
+        assert_eq!(
+            recommend(Some(&r), Some(finished), None, true).0,
+            AbTestRecommendation::NoSignificantDifference
+        );
+
+        let srm = sample_ratio(5000, 5400, 0.5);
+        assert_eq!(
+            recommend(Some(&r), Some(crossed), Some(&srm), true).0,
+            AbTestRecommendation::InvestigateSampleRatio
+        );
+    }
+}
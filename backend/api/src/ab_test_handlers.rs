@@ -0,0 +1,636 @@
+// api/src/ab_test_handlers.rs
+//
+// Axum handlers for A/B tests between two deployments of a contract. The
+// results endpoint runs the statistics in ab_test_engine.rs over the
+// recorded metrics; each analysis of a running test is recorded as a
+// sequential look so repeated checks do not inflate the false-positive rate.
+//
+// Routes (register in ab_test_routes.rs):
+//   POST   /api/ab-tests                  → create_ab_test
+//   GET    /api/ab-tests/:id              → get_ab_test
+//   POST   /api/ab-tests/:id/start        → start_ab_test
+//   POST   /api/ab-tests/:id/pause        → pause_ab_test
+//   POST   /api/ab-tests/:id/complete     → complete_ab_test
+//   POST   /api/ab-tests/:id/assign       → assign_variant
+//   POST   /api/ab-tests/:id/metrics      → record_ab_test_metric
+//   GET    /api/ab-tests/:id/results      → get_ab_test_results
+//   GET    /api/ab-tests/:id/looks        → list_ab_test_looks
+//   GET    /api/contracts/:id/ab-tests    → list_contract_ab_tests
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use shared::{
+    AbMetricComparison, AbMetricKind, AbTest, AbTestAnalysis, AbTestAssignment, AbTestLook,
+    AbTestMetric, AbTestRecommendation, AbTestStatus, CreateAbTestRequest, GetVariantRequest,
+    RecordAbTestMetricRequest, VariantType,
+};
+
+use crate::{
+    ab_test_engine::{self, LookOutcome, Moments, PriorLook},
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+
+fn db_err(op: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = op, error = ?err, "ab test database error");
+    ApiError::internal(format!("Database error while trying to {}", op))
+}
+
+async fn fetch_test(state: &AppState, test_id: Uuid) -> ApiResult<AbTest> {
+    sqlx::query_as("SELECT * FROM ab_tests WHERE id = $1")
+        .bind(test_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("fetch ab test", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "AbTestNotFound",
+                format!("No A/B test found with ID: {}", test_id),
+            )
+        })
+}
+
+fn count_for(rows: &[(VariantType, i64)], variant: VariantType) -> i64 {
+    rows.iter()
+        .find(|(v, _)| *v == variant)
+        .map(|(_, n)| *n)
+        .unwrap_or(0)
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/ab-tests
+// ─────────────────────────────────────────────────────────
+
+pub async fn create_ab_test(
+    State(state): State<AppState>,
+    Json(req): Json<CreateAbTestRequest>,
+) -> ApiResult<(StatusCode, Json<AbTest>)> {
+    let parse = |value: &str, what: &str| {
+        Uuid::parse_str(value)
+            .map_err(|_| ApiError::bad_request("InvalidId", format!("Invalid {}: {}", what, value)))
+    };
+    let contract_id = parse(&req.contract_id, "contract ID")?;
+    let variant_a = parse(&req.variant_a_deployment_id, "variant A deployment ID")?;
+    let variant_b = parse(&req.variant_b_deployment_id, "variant B deployment ID")?;
+
+    if req.name.trim().is_empty() || req.primary_metric.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidAbTest",
+            "name and primary_metric are required",
+        ));
+    }
+    if variant_a == variant_b {
+        return Err(ApiError::bad_request(
+            "InvalidAbTest",
+            "variants must use different deployments",
+        ));
+    }
+    let split = req.traffic_split.unwrap_or(50.0);
+    if !(split > 0.0 && split < 100.0) {
+        return Err(ApiError::bad_request(
+            "InvalidTrafficSplit",
+            "traffic_split (control share) must be between 0 and 100 exclusive",
+        ));
+    }
+    let significance = req.significance_threshold.unwrap_or(95.0);
+    if !(50.0..100.0).contains(&significance) {
+        return Err(ApiError::bad_request(
+            "InvalidSignificance",
+            "significance_threshold must be at least 50 and below 100",
+        ));
+    }
+    let min_sample_size = req.min_sample_size.unwrap_or(1000);
+    if min_sample_size < 1 {
+        return Err(ApiError::bad_request(
+            "InvalidSampleSize",
+            "min_sample_size must be positive",
+        ));
+    }
+
+    let owned: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contract_deployments WHERE contract_id = $1 AND id IN ($2, $3)",
+    )
+    .bind(contract_id)
+    .bind(variant_a)
+    .bind(variant_b)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("check ab test deployments", e))?;
+    if owned != 2 {
+        return Err(ApiError::bad_request(
+            "DeploymentMismatch",
+            "both variant deployments must belong to the contract",
+        ));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+    let test: AbTest = sqlx::query_as(
+        "INSERT INTO ab_tests
+                (contract_id, name, description, traffic_split, variant_a_deployment_id,
+                 variant_b_deployment_id, primary_metric, hypothesis, significance_threshold,
+                 min_sample_size, created_by, primary_metric_kind, higher_is_better)
+         VALUES ($1, $2, $3, $4::numeric, $5, $6, $7, $8, $9::numeric, $10, $11, $12, $13)
+         RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(req.name.trim())
+    .bind(&req.description)
+    .bind(split)
+    .bind(variant_a)
+    .bind(variant_b)
+    .bind(req.primary_metric.trim())
+    .bind(&req.hypothesis)
+    .bind(significance)
+    .bind(min_sample_size)
+    .bind(&req.created_by)
+    .bind(req.primary_metric_kind)
+    .bind(req.higher_is_better.unwrap_or(true))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("create ab test", e))?;
+
+    sqlx::query(
+        "INSERT INTO ab_test_variants (test_id, variant_type, deployment_id, traffic_percentage)
+         VALUES ($1, 'control', $2, $4::numeric), ($1, 'treatment', $3, 100 - $4::numeric)",
+    )
+    .bind(test.id)
+    .bind(variant_a)
+    .bind(variant_b)
+    .bind(split)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_err("create ab test variants", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit transaction", e))?;
+
+    Ok((StatusCode::CREATED, Json(test)))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/ab-tests/:id
+// GET /api/contracts/:id/ab-tests
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_ab_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<AbTest>> {
+    Ok(Json(fetch_test(&state, id).await?))
+}
+
+pub async fn list_contract_ab_tests(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<Vec<AbTest>>> {
+    let tests: Vec<AbTest> =
+        sqlx::query_as("SELECT * FROM ab_tests WHERE contract_id = $1 ORDER BY created_at DESC")
+            .bind(contract_id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list ab tests", e))?;
+    Ok(Json(tests))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/ab-tests/:id/start|pause|complete
+// ─────────────────────────────────────────────────────────
+
+async fn transition(
+    state: &AppState,
+    id: Uuid,
+    from: &[AbTestStatus],
+    to: AbTestStatus,
+) -> ApiResult<Json<AbTest>> {
+    let test = fetch_test(state, id).await?;
+    if !from.contains(&test.status) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "InvalidAbTestState",
+            format!("Cannot move an A/B test from {:?} to {:?}", test.status, to),
+        ));
+    }
+    let updated: AbTest = sqlx::query_as(
+        "UPDATE ab_tests
+            SET status = $2,
+                started_at = CASE WHEN $2 = 'running' THEN COALESCE(started_at, NOW())
+                                  ELSE started_at END,
+                ended_at = CASE WHEN $2 = 'completed' THEN NOW() ELSE ended_at END
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(id)
+    .bind(to)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+            StatusCode::CONFLICT,
+            "AbTestAlreadyRunning",
+            "Another A/B test is already running for this contract",
+        ),
+        _ => db_err("update ab test status", e),
+    })?;
+    Ok(Json(updated))
+}
+
+pub async fn start_ab_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<AbTest>> {
+    transition(
+        &state,
+        id,
+        &[AbTestStatus::Draft, AbTestStatus::Paused],
+        AbTestStatus::Running,
+    )
+    .await
+}
+
+pub async fn pause_ab_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<AbTest>> {
+    transition(&state, id, &[AbTestStatus::Running], AbTestStatus::Paused).await
+}
+
+pub async fn complete_ab_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<AbTest>> {
+    transition(
+        &state,
+        id,
+        &[AbTestStatus::Running, AbTestStatus::Paused],
+        AbTestStatus::Completed,
+    )
+    .await
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/ab-tests/:id/assign
+// ─────────────────────────────────────────────────────────
+
+pub async fn assign_variant(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<GetVariantRequest>,
+) -> ApiResult<Json<AbTestAssignment>> {
+    if req.user_address.is_empty() || req.user_address.len() > 56 {
+        return Err(ApiError::bad_request(
+            "InvalidUserAddress",
+            "user_address must be 1-56 characters",
+        ));
+    }
+    let variant: Option<VariantType> = sqlx::query_scalar("SELECT assign_variant($1, $2)")
+        .bind(id)
+        .bind(&req.user_address)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_err("assign ab test variant", e))?;
+    if variant.is_none() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "AbTestNotRunning",
+            format!("A/B test {} does not exist or is not running", id),
+        ));
+    }
+
+    let assignment: AbTestAssignment = sqlx::query_as(
+        "SELECT * FROM ab_test_assignments WHERE test_id = $1 AND user_address = $2",
+    )
+    .bind(id)
+    .bind(&req.user_address)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("fetch ab test assignment", e))?;
+    Ok(Json(assignment))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/ab-tests/:id/metrics
+// ─────────────────────────────────────────────────────────
+
+pub async fn record_ab_test_metric(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RecordAbTestMetricRequest>,
+) -> ApiResult<(StatusCode, Json<AbTestMetric>)> {
+    if req.metric_name.trim().is_empty() || !req.metric_value.is_finite() {
+        return Err(ApiError::bad_request(
+            "InvalidMetric",
+            "metric_name is required and metric_value must be a finite number",
+        ));
+    }
+    let test = fetch_test(&state, id).await?;
+    if test.status != AbTestStatus::Running {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "AbTestNotRunning",
+            "Metrics can only be recorded while the test is running",
+        ));
+    }
+
+    let assigned: Option<VariantType> = match &req.user_address {
+        Some(user) => sqlx::query_scalar(
+            "SELECT variant_type FROM ab_test_assignments WHERE test_id = $1 AND user_address = $2",
+        )
+        .bind(id)
+        .bind(user)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("fetch ab test assignment", e))?,
+        None => None,
+    };
+    let variant = match (assigned, req.variant_type) {
+        (Some(a), Some(v)) if a != v => {
+            return Err(ApiError::bad_request(
+                "VariantMismatch",
+                format!("user is assigned to {:?}, not {:?}", a, v),
+            ))
+        }
+        (Some(v), _) | (None, Some(v)) => v,
+        (None, None) => {
+            return Err(ApiError::bad_request(
+                "UnknownVariant",
+                "variant_type is required for users without an assignment",
+            ))
+        }
+    };
+
+    let metric: AbTestMetric = sqlx::query_as(
+        "INSERT INTO ab_test_metrics
+                (test_id, variant_type, metric_name, metric_value, user_address, metadata)
+         VALUES ($1, $2, $3, $4::numeric, $5, $6)
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(variant)
+    .bind(req.metric_name.trim())
+    .bind(req.metric_value)
+    .bind(&req.user_address)
+    .bind(&req.metadata)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("record ab test metric", e))?;
+
+    Ok((StatusCode::CREATED, Json(metric)))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/ab-tests/:id/results
+// ─────────────────────────────────────────────────────────
+
+/// Analyze the test. While it runs, an analysis with enough new data is
+/// recorded as a sequential look; the recommendation follows the latest
+/// look rather than the instantaneous p-value.
+pub async fn get_ab_test_results(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<AbTestAnalysis>> {
+    let test = fetch_test(&state, id).await?;
+    let (significance, split): (f64, f64) = sqlx::query_as(
+        "SELECT significance_threshold::float8, traffic_split::float8 FROM ab_tests WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("fetch ab test thresholds", e))?;
+    let alpha = (1.0 - significance / 100.0).clamp(1e-6, 0.5);
+
+    // Units per variant: assignments when the test assigns users, otherwise
+    // distinct users (or anonymous observations) in the metrics.
+    let assigned: Vec<(VariantType, i64)> = sqlx::query_as(
+        "SELECT variant_type, COUNT(*) FROM ab_test_assignments
+          WHERE test_id = $1 GROUP BY variant_type",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("count ab test assignments", e))?;
+    let units = if assigned.iter().any(|(_, n)| *n > 0) {
+        assigned
+    } else {
+        sqlx::query_as(
+            "SELECT variant_type, COUNT(DISTINCT COALESCE(user_address, id::text))
+               FROM ab_test_metrics WHERE test_id = $1 GROUP BY variant_type",
+        )
+        .bind(id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_err("count ab test units", e))?
+    };
+    let control_n = count_for(&units, VariantType::Control);
+    let treatment_n = count_for(&units, VariantType::Treatment);
+
+    let moments: Vec<(String, VariantType, i64, f64, f64)> = sqlx::query_as(
+        "SELECT metric_name, variant_type, COUNT(*), AVG(metric_value)::float8,
+                COALESCE(VAR_SAMP(metric_value), 0)::float8
+           FROM ab_test_metrics
+          WHERE test_id = $1
+          GROUP BY metric_name, variant_type
+          ORDER BY metric_name",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("aggregate ab test metrics", e))?;
+    let moments_for = |name: &str, variant: VariantType| {
+        moments
+            .iter()
+            .find(|(m, v, ..)| m == name && *v == variant)
+            .map(|(_, _, n, mean, variance)| Moments {
+                n: *n,
+                mean: *mean,
+                variance: *variance,
+            })
+    };
+    let compare_means = |name: &str| match (
+        moments_for(name, VariantType::Control),
+        moments_for(name, VariantType::Treatment),
+    ) {
+        (Some(c), Some(t)) => Some(ab_test_engine::compare_means(name, c, t, alpha)),
+        _ => None,
+    };
+
+    let primary = match test.primary_metric_kind {
+        AbMetricKind::Conversion if control_n > 0 && treatment_n > 0 => {
+            let converted: Vec<(VariantType, i64)> = sqlx::query_as(
+                "SELECT variant_type, COUNT(DISTINCT COALESCE(user_address, id::text))
+                   FROM ab_test_metrics
+                  WHERE test_id = $1 AND metric_name = $2 AND metric_value > 0
+                  GROUP BY variant_type",
+            )
+            .bind(id)
+            .bind(&test.primary_metric)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("count ab test conversions", e))?;
+            Some(ab_test_engine::compare_proportions(
+                &test.primary_metric,
+                (
+                    control_n,
+                    count_for(&converted, VariantType::Control).min(control_n),
+                ),
+                (
+                    treatment_n,
+                    count_for(&converted, VariantType::Treatment).min(treatment_n),
+                ),
+                alpha,
+            ))
+        }
+        AbMetricKind::Conversion => None,
+        AbMetricKind::Mean => compare_means(&test.primary_metric)
+            .filter(|c| c.control.sample_size > 1 && c.treatment.sample_size > 1),
+    };
+
+    let mut names: Vec<&str> = moments.iter().map(|(m, ..)| m.as_str()).collect();
+    names.dedup();
+    let secondary: Vec<AbMetricComparison> = names
+        .into_iter()
+        .filter(|name| *name != test.primary_metric)
+        .filter_map(compare_means)
+        .collect();
+
+    let sample_ratio = (control_n + treatment_n > 0)
+        .then(|| ab_test_engine::sample_ratio(control_n, treatment_n, split / 100.0));
+    let srm_mismatch = sample_ratio.as_ref().is_some_and(|s| s.mismatch);
+
+    let mut look: Option<AbTestLook> = sqlx::query_as(
+        "SELECT * FROM ab_test_looks WHERE test_id = $1 ORDER BY look_number DESC LIMIT 1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch ab test look", e))?;
+    if let (Some(primary), AbTestStatus::Running, false) = (&primary, test.status, srm_mismatch) {
+        let prior = look.as_ref().map(|l| PriorLook {
+            information_fraction: l.information_fraction,
+            alpha_spent: l.alpha_spent,
+            crossed: l.crossed,
+        });
+        let t = ab_test_engine::information_fraction(
+            primary.control.sample_size,
+            primary.treatment.sample_size,
+            test.min_sample_size,
+        );
+        if let Some(plan) = ab_test_engine::plan_look(prior, t, alpha) {
+            let z = ab_test_engine::z_score(primary);
+            let recorded: AbTestLook = sqlx::query_as(
+                "INSERT INTO ab_test_looks
+                        (test_id, look_number, information_fraction, control_n, treatment_n,
+                         alpha_spent, nominal_alpha, boundary_z, z_statistic, crossed)
+                 SELECT $1, COALESCE(MAX(look_number), 0) + 1, $2, $3, $4, $5, $6, $7, $8, $9
+                   FROM ab_test_looks WHERE test_id = $1
+                 RETURNING *",
+            )
+            .bind(id)
+            .bind(plan.information_fraction)
+            .bind(primary.control.sample_size)
+            .bind(primary.treatment.sample_size)
+            .bind(plan.alpha_spent)
+            .bind(plan.nominal_alpha)
+            .bind(plan.boundary_z)
+            .bind(z)
+            .bind(z.abs() >= plan.boundary_z)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| db_err("record ab test look", e))?;
+            look = Some(recorded);
+        }
+    }
+
+    let outcome = look.as_ref().map(|l| LookOutcome {
+        information_fraction: l.information_fraction,
+        z_statistic: l.z_statistic.unwrap_or(0.0),
+        crossed: l.crossed,
+    });
+    let (recommendation, rationale) = ab_test_engine::recommend(
+        primary.as_ref(),
+        outcome,
+        sample_ratio.as_ref(),
+        test.higher_is_better,
+    );
+
+    if let Some(primary) = &primary {
+        let winner = match recommendation {
+            AbTestRecommendation::ShipTreatment => Some(VariantType::Treatment),
+            AbTestRecommendation::KeepControl => Some(VariantType::Control),
+            _ => None,
+        };
+        for stats in [&primary.control, &primary.treatment] {
+            sqlx::query(
+                "INSERT INTO ab_test_results
+                        (test_id, variant_type, sample_size, mean_value, std_deviation,
+                         confidence_interval_lower, confidence_interval_upper, p_value,
+                         statistical_significance, is_winner, calculated_at)
+                 VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::numeric, $7::numeric,
+                         $8::numeric, $9::numeric, $10, NOW())
+                 ON CONFLICT (test_id, variant_type) DO UPDATE SET
+                     sample_size = EXCLUDED.sample_size,
+                     mean_value = EXCLUDED.mean_value,
+                     std_deviation = EXCLUDED.std_deviation,
+                     confidence_interval_lower = EXCLUDED.confidence_interval_lower,
+                     confidence_interval_upper = EXCLUDED.confidence_interval_upper,
+                     p_value = EXCLUDED.p_value,
+                     statistical_significance = EXCLUDED.statistical_significance,
+                     is_winner = EXCLUDED.is_winner,
+                     calculated_at = EXCLUDED.calculated_at",
+            )
+            .bind(id)
+            .bind(stats.variant_type)
+            .bind(stats.sample_size.min(i32::MAX as i64) as i32)
+            .bind(stats.mean)
+            .bind(stats.std_dev)
+            .bind(stats.ci_lower)
+            .bind(stats.ci_upper)
+            .bind(primary.p_value)
+            .bind((1.0 - primary.p_value) * 100.0)
+            .bind(winner == Some(stats.variant_type))
+            .execute(&state.db)
+            .await
+            .map_err(|e| db_err("store ab test results", e))?;
+        }
+    }
+
+    Ok(Json(AbTestAnalysis {
+        test_id: id,
+        status: test.status,
+        alpha,
+        primary,
+        secondary,
+        sample_ratio,
+        sequential: look,
+        recommendation,
+        rationale,
+        analyzed_at: Utc::now(),
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/ab-tests/:id/looks
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_ab_test_looks(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Vec<AbTestLook>>> {
+    fetch_test(&state, id).await?;
+    let looks: Vec<AbTestLook> =
+        sqlx::query_as("SELECT * FROM ab_test_looks WHERE test_id = $1 ORDER BY look_number")
+            .bind(id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list ab test looks", e))?;
+    Ok(Json(looks))
+}
@@ -0,0 +1,47 @@
+// ab_test_routes.rs
+// Route definitions for A/B tests and their statistical analysis
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{ab_test_handlers, state::AppState};
+
+pub fn ab_test_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/ab-tests", post(ab_test_handlers::create_ab_test))
+        .route("/api/ab-tests/:id", get(ab_test_handlers::get_ab_test))
+        .route(
+            "/api/ab-tests/:id/start",
+            post(ab_test_handlers::start_ab_test),
+        )
+        .route(
+            "/api/ab-tests/:id/pause",
+            post(ab_test_handlers::pause_ab_test),
+        )
+        .route(
+            "/api/ab-tests/:id/complete",
+            post(ab_test_handlers::complete_ab_test),
+        )
+        .route(
+            "/api/ab-tests/:id/assign",
+            post(ab_test_handlers::assign_variant),
+        )
+        .route(
+            "/api/ab-tests/:id/metrics",
+            post(ab_test_handlers::record_ab_test_metric),
+        )
+        .route(
+            "/api/ab-tests/:id/results",
+            get(ab_test_handlers::get_ab_test_results),
+        )
+        .route(
+            "/api/ab-tests/:id/looks",
+            get(ab_test_handlers::list_ab_test_looks),
+        )
+        .route(
+            "/api/contracts/:id/ab-tests",
+            get(ab_test_handlers::list_contract_ab_tests),
+        )
+}
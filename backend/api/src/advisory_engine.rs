@@ -0,0 +1,676 @@
+// api/src/advisory_engine.rs
+//
+// Advisory matching for dependency scans. Advisories arrive in the OSV
+// format (https://ossf.github.io/osv-schema/), which is also how RustSec
+// publishes its database, and are reduced to per-package affected ranges:
+//
+//   introduced <= version < fixed           (or <= last_affected)
+//
+// plus any explicitly listed versions. Advisories ingested through the
+// older payload, which only carries patched versions, are matched the way
+// RustSec matches `patched`: a version is affected unless it satisfies one
+// of the patched requirements. Versions compare with SemVer precedence, so
+// pre-releases sort before their release. scanner_service.rs loads and
+// stores advisories; everything in here is synchronous.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::{Op, SemVer, VersionReq};
+
+/// Only crates are matched; Soroban contracts are built with Cargo.
+pub const ECOSYSTEM: &str = "crates.io";
+
+// ─────────────────────────────────────────────────────────
+// OSV schema (the fields the scanner uses)
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsvAdvisory {
+    pub id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub published: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub withdrawn: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    pub affected: Vec<OsvAffected>,
+    #[serde(default)]
+    pub database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsvSeverity {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub score: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsvAffected {
+    pub package: OsvPackage,
+    #[serde(default)]
+    pub ranges: Vec<OsvRange>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsvPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsvRange {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<OsvEvent>,
+}
+
+/// Each event sets exactly one of these.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OsvEvent {
+    pub introduced: Option<String>,
+    pub fixed: Option<String>,
+    pub last_affected: Option<String>,
+    pub limit: Option<String>,
+}
+
+// ─────────────────────────────────────────────────────────
+// Normalised advisories
+// ─────────────────────────────────────────────────────────
+
+/// One contiguous run of affected versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AffectedRange {
+    /// `None` means affected from the first release
+    pub introduced: Option<SemVer>,
+    pub fixed: Option<SemVer>,
+    pub last_affected: Option<SemVer>,
+}
+
+impl AffectedRange {
+    pub fn contains(&self, version: &SemVer) -> bool {
+        if self.introduced.as_ref().is_some_and(|i| version < i) {
+            return false;
+        }
+        match (&self.fixed, &self.last_affected) {
+            (Some(fixed), _) => version < fixed,
+            (None, Some(last)) => version <= last,
+            (None, None) => true,
+        }
+    }
+}
+
+/// What an advisory says about one package. Stored as JSON on the advisory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AffectedPackage {
+    pub name: String,
+    #[serde(default)]
+    pub ranges: Vec<AffectedRange>,
+    #[serde(default)]
+    pub versions: Vec<SemVer>,
+    /// RustSec-style patched requirements, used when there are no ranges
+    #[serde(default)]
+    pub patched: Vec<String>,
+}
+
+impl AffectedPackage {
+    /// A package from the older payload, which only lists patched versions.
+    pub fn from_patched(name: &str, patched: &[String]) -> Self {
+        AffectedPackage {
+            name: name.to_string(),
+            patched: patched.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    pub fn affects(&self, version: &SemVer) -> bool {
+        if self.ranges.is_empty() && self.versions.is_empty() {
+            return !self.patched_reqs().iter().any(|req| req.matches(version));
+        }
+        self.versions.contains(version) || self.ranges.iter().any(|r| r.contains(version))
+    }
+
+    fn patched_reqs(&self) -> Vec<VersionReq> {
+        self.patched
+            .iter()
+            .filter_map(|p| VersionReq::parse(p))
+            .collect()
+    }
+
+    /// Versions at which the package stops being affected: fix versions,
+    /// the patch after a last affected version, and the lower bounds of
+    /// patched requirements.
+    fn fix_candidates(&self) -> Vec<SemVer> {
+        let mut candidates = Vec::new();
+        for range in &self.ranges {
+            if let Some(fixed) = &range.fixed {
+                candidates.push(fixed.clone());
+            } else if let Some(last) = &range.last_affected {
+                candidates.push(SemVer::new(last.major, last.minor, last.patch + 1));
+            }
+        }
+        for req in self.patched_reqs() {
+            for c in &req.comparators {
+                if matches!(c.op, Op::GreaterEq | Op::Caret | Op::Tilde | Op::Exact) {
+                    candidates.push(SemVer {
+                        pre: c.pre.clone(),
+                        ..SemVer::new(c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0))
+                    });
+                }
+            }
+        }
+        candidates
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub description: Option<String>,
+    pub severity: String,
+    pub published: Option<DateTime<Utc>>,
+    pub modified: Option<DateTime<Utc>>,
+    pub withdrawn: Option<DateTime<Utc>>,
+    pub packages: Vec<AffectedPackage>,
+}
+
+impl Advisory {
+    /// Reduce an OSV record to crates.io packages and SemVer ranges.
+    /// Ranges with an event that is not valid SemVer are dropped, as are
+    /// GIT ranges, which name commits rather than releases.
+    pub fn from_osv(osv: OsvAdvisory) -> Self {
+        let severity = severity_label(&osv);
+        let packages = osv
+            .affected
+            .iter()
+            .filter(|a| a.package.ecosystem.eq_ignore_ascii_case(ECOSYSTEM))
+            .map(|a| AffectedPackage {
+                name: a.package.name.clone(),
+                ranges: a
+                    .ranges
+                    .iter()
+                    .filter(|r| r.kind == "SEMVER" || r.kind == "ECOSYSTEM")
+                    .flat_map(|r| ranges_from_events(&r.events))
+                    .collect(),
+                versions: a.versions.iter().filter_map(|v| SemVer::parse(v)).collect(),
+                patched: Vec::new(),
+            })
+            // Nothing left to match against; keeping it would flag every version
+            .filter(|p| !p.ranges.is_empty() || !p.versions.is_empty())
+            .collect();
+
+        Advisory {
+            id: osv.id,
+            aliases: osv.aliases,
+            description: osv.summary.or(osv.details),
+            severity,
+            published: osv.published,
+            modified: osv.modified,
+            withdrawn: osv.withdrawn,
+            packages,
+        }
+    }
+
+    #[cfg(test)]
+    fn package(&self, name: &str) -> Option<&AffectedPackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Fix versions across all packages, for the legacy `patched_versions`
+    /// column.
+    pub fn fixed_versions(&self) -> Vec<String> {
+        let mut fixed: Vec<SemVer> = self
+            .packages
+            .iter()
+            .flat_map(|p| p.ranges.iter().filter_map(|r| r.fixed.clone()))
+            .collect();
+        fixed.sort();
+        fixed.dedup();
+        fixed.iter().map(|v| v.to_string()).collect()
+    }
+}
+
+/// Parse an OSV version; `0` is the spec's "from the beginning".
+fn event_version(raw: &str) -> Option<SemVer> {
+    if raw == "0" {
+        Some(SemVer::new(0, 0, 0))
+    } else {
+        SemVer::parse(raw)
+    }
+}
+
+/// Turn a range's events into intervals. Events are sorted by version
+/// first; the spec does not guarantee their order.
+fn ranges_from_events(events: &[OsvEvent]) -> Vec<AffectedRange> {
+    enum Kind {
+        Introduced,
+        Fixed,
+        LastAffected,
+    }
+    let mut parsed = Vec::new();
+    for event in events {
+        let (kind, raw) = if let Some(v) = &event.introduced {
+            (Kind::Introduced, v)
+        } else if let Some(v) = &event.fixed {
+            (Kind::Fixed, v)
+        } else if let Some(v) = &event.last_affected {
+            (Kind::LastAffected, v)
+        } else {
+            continue;
+        };
+        // An unreadable bound would widen or cut short its interval
+        let Some(version) = event_version(raw) else {
+            return Vec::new();
+        };
+        parsed.push((version, kind));
+    }
+    parsed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut ranges = Vec::new();
+    let mut open: Option<Option<SemVer>> = None;
+    for (version, kind) in parsed {
+        match kind {
+            Kind::Introduced => {
+                if open.is_none() {
+                    let from_start = version == SemVer::new(0, 0, 0);
+                    open = Some((!from_start).then_some(version));
+                }
+            }
+            Kind::Fixed | Kind::LastAffected => {
+                let Some(introduced) = open.take() else {
+                    continue;
+                };
+                let fixed = matches!(kind, Kind::Fixed);
+                ranges.push(AffectedRange {
+                    introduced,
+                    fixed: fixed.then(|| version.clone()),
+                    last_affected: (!fixed).then_some(version),
+                });
+            }
+        }
+    }
+    if let Some(introduced) = open {
+        ranges.push(AffectedRange {
+            introduced,
+            fixed: None,
+            last_affected: None,
+        });
+    }
+    ranges
+}
+
+// ─────────────────────────────────────────────────────────
+// Severity
+// ─────────────────────────────────────────────────────────
+
+/// `critical`, `high`, `medium` or `low` from the advisory's own label or
+/// its CVSS v3 vector; `informational` for RustSec notices such as
+/// unmaintained crates; `unknown` otherwise.
+pub fn severity_label(osv: &OsvAdvisory) -> String {
+    let specific = osv.database_specific.as_ref();
+    if let Some(label) = specific
+        .and_then(|d| d.get("severity"))
+        .and_then(|s| s.as_str())
+    {
+        let label = label.to_lowercase();
+        return match label.as_str() {
+            "moderate" => "medium".to_string(),
+            _ => label,
+        };
+    }
+    let cvss = osv
+        .severity
+        .iter()
+        .filter(|s| s.kind == "CVSS_V3")
+        .find_map(|s| cvss_v3_base_score(&s.score));
+    if let Some(score) = cvss {
+        return score_label(score).to_string();
+    }
+    if specific
+        .and_then(|d| d.get("informational"))
+        .is_some_and(|i| !i.is_null())
+    {
+        return "informational".to_string();
+    }
+    "unknown".to_string()
+}
+
+pub fn score_label(score: f64) -> &'static str {
+    match score {
+        s if s >= 9.0 => "critical",
+        s if s >= 7.0 => "high",
+        s if s >= 4.0 => "medium",
+        _ => "low",
+    }
+}
+
+/// CVSS v3.x base score from a vector such as
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+pub fn cvss_v3_base_score(vector: &str) -> Option<f64> {
+    let mut parts = vector.split('/');
+    if !parts.next()?.starts_with("CVSS:3") {
+        return None;
+    }
+    let metrics: std::collections::HashMap<&str, &str> =
+        parts.filter_map(|p| p.split_once(':')).collect();
+    let metric = |name: &str| metrics.get(name).copied();
+
+    let changed = match metric("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let av = match metric("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match metric("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (metric("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match metric("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |name: &str| match metric(name)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let iss: f64 = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let score: f64 = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(score.min(10.0)))
+}
+
+/// The spec's Roundup: the smallest one-decimal number >= the input,
+/// computed in integers to avoid floating-point artefacts.
+fn round_up(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Matching
+// ─────────────────────────────────────────────────────────
+
+/// An advisory as it applies to one package.
+#[derive(Debug, Clone)]
+pub struct PackageAdvisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub severity: String,
+    pub package: AffectedPackage,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub severity: String,
+    /// Lowest version that fixes this advisory, preferring one no other
+    /// known advisory affects
+    pub recommended: Option<SemVer>,
+}
+
+/// Advisories affecting `version`. Records that are aliases of one another
+/// (a RustSec ID and its CVE, say) are reported once, under the first.
+pub fn evaluate(version: &SemVer, advisories: &[PackageAdvisory]) -> Vec<Finding> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut findings = Vec::new();
+
+    for advisory in advisories {
+        if !advisory.package.affects(version) {
+            continue;
+        }
+        let names = std::iter::once(&advisory.id).chain(&advisory.aliases);
+        if names.clone().any(|n| seen.contains(n.as_str())) {
+            continue;
+        }
+        seen.extend(names.map(String::as_str));
+
+        findings.push(Finding {
+            id: advisory.id.clone(),
+            aliases: advisory.aliases.clone(),
+            severity: advisory.severity.clone(),
+            recommended: minimal_safe_upgrade(version, &advisory.package, advisories),
+        });
+    }
+    findings
+}
+
+/// Smallest version above `current` that `fixing` does not affect. A
+/// version clear of every advisory for the package is preferred; failing
+/// that, the smallest that at least fixes this one.
+pub fn minimal_safe_upgrade(
+    current: &SemVer,
+    fixing: &AffectedPackage,
+    all: &[PackageAdvisory],
+) -> Option<SemVer> {
+    let mut candidates: Vec<SemVer> = all
+        .iter()
+        .flat_map(|a| a.package.fix_candidates())
+        .chain(fixing.fix_candidates())
+        .filter(|v| v > current && !fixing.affects(v))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    candidates
+        .iter()
+        .find(|v| all.iter().all(|a| !a.package.affects(v)))
+        .or(candidates.first())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> SemVer {
+        SemVer::parse(s).unwrap()
+    }
+
+    fn osv(json: serde_json::Value) -> Advisory {
+        Advisory::from_osv(serde_json::from_value(json).unwrap())
+    }
+
+    fn advisory(id: &str, package: AffectedPackage) -> PackageAdvisory {
+        PackageAdvisory {
+            id: id.to_string(),
+            aliases: Vec::new(),
+            severity: "high".to_string(),
+            package,
+        }
+    }
+
+    #[test]
+    fn osv_events_become_ranges() {
+        let adv = osv(serde_json::json!({
+            "id": "RUSTSEC-2024-0001",
+            "aliases": ["CVE-2024-1111"],
+            "affected": [{
+                "package": {"ecosystem": "crates.io", "name": "soroban-sdk"},
+                "ranges": [{"type": "SEMVER", "events": [
+                    {"fixed": "1.2.3"},
+                    {"introduced": "0"},
+                    {"introduced": "2.0.0"},
+                    {"fixed": "2.0.4"}
+                ]}]
+            }, {
+                "package": {"ecosystem": "npm", "name": "soroban-sdk"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}]}]
+            }]
+        }));
+        assert_eq!(adv.packages.len(), 1);
+        let pkg = adv.package("soroban-sdk").unwrap();
+        assert!(pkg.affects(&v("1.2.2")));
+        assert!(!pkg.affects(&v("1.2.3")));
+        assert!(!pkg.affects(&v("1.2.4")));
+        assert!(!pkg.affects(&v("1.9.0")));
+        assert!(pkg.affects(&v("2.0.0")));
+        assert!(pkg.affects(&v("2.0.4-rc.1")));
+        assert!(!pkg.affects(&v("2.0.4")));
+        assert_eq!(adv.fixed_versions(), vec!["1.2.3", "2.0.4"]);
+    }
+
+    #[test]
+    fn last_affected_and_open_ranges() {
+        let adv = osv(serde_json::json!({
+            "id": "GHSA-xxxx",
+            "affected": [{
+                "package": {"ecosystem": "crates.io", "name": "a"},
+                "ranges": [{"type": "ECOSYSTEM", "events": [
+                    {"introduced": "0.3.0"}, {"last_affected": "0.3.7"}
+                ]}, {"type": "SEMVER", "events": [{"introduced": "0.5.0"}]}],
+                "versions": ["0.1.9"]
+            }]
+        }));
+        let pkg = adv.package("a").unwrap();
+        assert!(pkg.affects(&v("0.1.9")));
+        assert!(!pkg.affects(&v("0.2.0")));
+        assert!(pkg.affects(&v("0.3.7")));
+        assert!(!pkg.affects(&v("0.3.8")));
+        assert!(pkg.affects(&v("7.0.0")));
+    }
+
+    #[test]
+    fn legacy_patched_versions_use_requirement_semantics() {
+        // "fixed in >= 1.2.3" must not flag 1.2.4
+        let pkg = AffectedPackage::from_patched("a", &[">= 1.2.3".into()]);
+        assert!(pkg.affects(&v("1.2.2")));
+        assert!(!pkg.affects(&v("1.2.4")));
+
+        // A bare version is a caret requirement, as in RustSec
+        let pkg = AffectedPackage::from_patched("a", &["0.9.5".into(), ">= 1.0.2".into()]);
+        assert!(!pkg.affects(&v("0.9.7")));
+        assert!(pkg.affects(&v("0.9.4")));
+        assert!(pkg.affects(&v("1.0.1")));
+        assert!(!pkg.affects(&v("1.3.0")));
+    }
+
+    #[test]
+    fn recommends_the_smallest_version_clear_of_every_advisory() {
+        let range = |from: &str, fixed: &str| AffectedRange {
+            introduced: Some(v(from)),
+            fixed: Some(v(fixed)),
+            last_affected: None,
+        };
+        let first = AffectedPackage {
+            name: "a".into(),
+            ranges: vec![range("1.0.0", "1.2.3")],
+            ..Default::default()
+        };
+        let second = AffectedPackage {
+            name: "a".into(),
+            ranges: vec![range("1.2.0", "1.2.5")],
+            ..Default::default()
+        };
+        let all = vec![advisory("A-1", first), advisory("A-2", second)];
+
+        let findings = evaluate(&v("1.2.1"), &all);
+        assert_eq!(findings.len(), 2);
+        // 1.2.3 fixes A-1 but is still hit by A-2
+        assert_eq!(findings[0].recommended, Some(v("1.2.5")));
+        assert_eq!(findings[1].recommended, Some(v("1.2.5")));
+
+        assert_eq!(evaluate(&v("1.1.0"), &all)[0].recommended, Some(v("1.2.5")));
+        assert!(evaluate(&v("1.2.5"), &all).is_empty());
+    }
+
+    #[test]
+    fn aliases_are_reported_once() {
+        let pkg = AffectedPackage::from_patched("a", &[">= 2.0.0".into()]);
+        let mut rustsec = advisory("RUSTSEC-2023-0042", pkg.clone());
+        rustsec.aliases = vec!["CVE-2023-9999".into()];
+        let cve = advisory("CVE-2023-9999", pkg);
+
+        let findings = evaluate(&v("1.0.0"), &[rustsec, cve]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id, "RUSTSEC-2023-0042");
+        assert_eq!(findings[0].recommended, Some(v("2.0.0")));
+    }
+
+    #[test]
+    fn cvss_scores_and_labels() {
+        let critical = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H";
+        assert_eq!(cvss_v3_base_score(critical), Some(9.8));
+        let changed = "CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N";
+        assert_eq!(cvss_v3_base_score(changed), Some(6.1));
+        assert_eq!(cvss_v3_base_score("CVSS:4.0/AV:N"), None);
+
+        let adv = osv(serde_json::json!({
+            "id": "RUSTSEC-2021-0001",
+            "severity": [{"type": "CVSS_V3", "score": critical}]
+        }));
+        assert_eq!(adv.severity, "critical");
+        let adv = osv(serde_json::json!({
+            "id": "RUSTSEC-2021-0002",
+            "database_specific": {"informational": "unmaintained"}
+        }));
+        assert_eq!(adv.severity, "informational");
+    }
+
+    #[test]
+    fn semver_requirements_follow_cargo() {
+        let req = |s: &str| VersionReq::parse(s).unwrap();
+        assert!(req(">= 1.2, < 2").matches(&v("1.9.9")));
+        assert!(!req(">= 1.2, < 2").matches(&v("2.0.0")));
+        assert!(req("<= 1.2").matches(&v("1.2.9")));
+        assert!(req("~1").matches(&v("1.9.0")));
+        assert!(!req("~1.2.3").matches(&v("1.3.0")));
+        assert!(req("^0.2.3").matches(&v("0.2.9")));
+        assert!(!req("^0.2.3").matches(&v("0.3.0")));
+        assert!(req("1.2.*").matches(&v("1.2.7")));
+        // Pre-releases only match requirements that name one
+        assert!(!req(">= 1.0.0").matches(&v("2.0.0-rc.1")));
+        assert!(req(">= 2.0.0-beta").matches(&v("2.0.0-rc.1")));
+        assert!(v("1.0.0-alpha.2") < v("1.0.0-alpha.10"));
+        assert!(v("1.0.0-rc.1") < v("1.0.0"));
+    }
+}
@@ -0,0 +1,315 @@
+// api/src/backup_engine.rs
+//
+// Contract state backups as content-addressed chunks. A backup captures the
+// contract's indexed ledger entries, sorts them by (durability, key) and
+// packs them into chunks of JSON lines. Chunk boundaries fall after entries
+// whose key hash matches a fixed bit pattern, so they depend only on the
+// entries themselves: a run of unchanged entries produces the same chunks,
+// and hence the same hashes, in every backup. Storing a backup then only
+// writes the chunks that changed since any earlier one. The manifest lists
+// every chunk in order, so each backup restores on its own without walking
+// a chain. Everything in here is synchronous.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::blob_store::content_hash;
+
+pub const MANIFEST_FORMAT: u32 = 1;
+/// A key hash with these low bits clear ends a chunk; averages 16 entries
+const BOUNDARY_MASK: u8 = 0x0f;
+/// Hard cap so one run of large entries cannot produce an unbounded chunk
+const MAX_CHUNK_BYTES: usize = 256 * 1024;
+
+/// One ledger entry as captured from the indexer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StateEntry {
+    /// `instance`, `persistent` or `temporary`
+    pub durability: String,
+    /// Base64 XDR `LedgerKey`
+    pub key_xdr: String,
+    /// Base64 XDR `LedgerEntry`
+    pub entry_xdr: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_display: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_until_ledger: Option<i32>,
+    pub last_modified_ledger: i32,
+}
+
+impl StateEntry {
+    /// Whether the entry is still live at `ledger`. Instance entries share
+    /// the contract's TTL, which the indexer records on them as well.
+    pub fn is_live_at(&self, ledger: i64) -> bool {
+        match self.live_until_ledger {
+            Some(until) => i64::from(until) >= ledger,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub hash: String,
+    pub bytes: Vec<u8>,
+    pub entries: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkRef {
+    pub hash: String,
+    pub size_bytes: u64,
+    pub entries: u32,
+}
+
+impl From<&Chunk> for ChunkRef {
+    fn from(chunk: &Chunk) -> Self {
+        Self {
+            hash: chunk.hash.clone(),
+            size_bytes: chunk.bytes.len() as u64,
+            entries: chunk.entries,
+        }
+    }
+}
+
+/// Everything needed to rebuild a backup from the blob store alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub contract_id: Uuid,
+    pub contract_address: String,
+    pub network: String,
+    pub wasm_hash: String,
+    pub ledger_sequence: Option<i64>,
+    pub ledger_timestamp: Option<i64>,
+    pub protocol_version: Option<i32>,
+    pub network_passphrase: Option<String>,
+    pub entry_count: u64,
+    pub logical_size_bytes: u64,
+    pub chunks: Vec<ChunkRef>,
+}
+
+impl Manifest {
+    /// Canonical encoding, hashed and stored beside the chunks
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("manifest serializes")
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Chunking
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn durability_rank(durability: &str) -> u8 {
+    match durability {
+        "instance" => 0,
+        "persistent" => 1,
+        _ => 2,
+    }
+}
+
+fn is_boundary(entry: &StateEntry) -> bool {
+    let digest = Sha256::digest(entry.key_xdr.as_bytes());
+    digest[0] & BOUNDARY_MASK == 0
+}
+
+/// Sort entries into their canonical order and drop those already expired
+/// at `ledger`, which the network has archived or deleted.
+pub fn canonical_entries(mut entries: Vec<StateEntry>, ledger: Option<i64>) -> Vec<StateEntry> {
+    if let Some(ledger) = ledger {
+        entries.retain(|e| e.is_live_at(ledger));
+    }
+    entries.sort_by(|a, b| {
+        durability_rank(&a.durability)
+            .cmp(&durability_rank(&b.durability))
+            .then_with(|| a.key_xdr.cmp(&b.key_xdr))
+    });
+    entries.dedup_by(|a, b| a.durability == b.durability && a.key_xdr == b.key_xdr);
+    entries
+}
+
+/// Pack canonically ordered entries into content-defined chunks.
+pub fn chunk_entries(entries: &[StateEntry]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut bytes = Vec::new();
+    let mut count = 0u32;
+
+    let mut flush = |bytes: &mut Vec<u8>, count: &mut u32| {
+        if *count > 0 {
+            let data = std::mem::take(bytes);
+            chunks.push(Chunk {
+                hash: content_hash(&data),
+                bytes: data,
+                entries: *count,
+            });
+            *count = 0;
+        }
+    };
+
+    for entry in entries {
+        let line = serde_json::to_vec(entry).expect("state entry serializes");
+        if count > 0 && bytes.len() + line.len() + 1 > MAX_CHUNK_BYTES {
+            flush(&mut bytes, &mut count);
+        }
+        bytes.extend_from_slice(&line);
+        bytes.push(b'\n');
+        count += 1;
+        if is_boundary(entry) {
+            flush(&mut bytes, &mut count);
+        }
+    }
+    flush(&mut bytes, &mut count);
+    chunks
+}
+
+/// Decode a chunk after checking it still matches its hash.
+pub fn decode_chunk(expected: &ChunkRef, bytes: &[u8]) -> Result<Vec<StateEntry>, String> {
+    let actual = content_hash(bytes);
+    if actual != expected.hash {
+        return Err(format!(
+            "chunk {} is corrupt (content hashes to {})",
+            expected.hash, actual
+        ));
+    }
+    let entries = bytes
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(serde_json::from_slice::<StateEntry>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("chunk {} is unreadable: {}", expected.hash, e))?;
+    if entries.len() as u32 != expected.entries {
+        return Err(format!(
+            "chunk {} holds {} entries, manifest lists {}",
+            expected.hash,
+            entries.len(),
+            expected.entries
+        ));
+    }
+    Ok(entries)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Verification
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Outcome of checking one stored chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkStatus {
+    Ok,
+    Missing,
+    Corrupt,
+}
+
+pub fn check_chunk(expected: &ChunkRef, stored: Option<&[u8]>) -> ChunkStatus {
+    match stored {
+        None => ChunkStatus::Missing,
+        Some(bytes) if content_hash(bytes) == expected.hash => ChunkStatus::Ok,
+        Some(_) => ChunkStatus::Corrupt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(durability: &str, key: usize, value: &str) -> StateEntry {
+        StateEntry {
+            durability: durability.into(),
+            key_xdr: format!("key-{:04}", key),
+            entry_xdr: value.into(),
+            key_display: None,
+            live_until_ledger: Some(1_000),
+            last_modified_ledger: 10,
+        }
+    }
+
+    fn state(n: usize) -> Vec<StateEntry> {
+        (0..n).map(|i| entry("persistent", i, "v1")).collect()
+    }
+
+    #[test]
+    fn canonical_order_drops_expired_entries() {
+        let mut expired = entry("temporary", 1, "gone");
+        expired.live_until_ledger = Some(50);
+        let entries = vec![
+            entry("persistent", 2, "b"),
+            expired,
+            entry("instance", 9, "i"),
+            entry("persistent", 1, "a"),
+        ];
+        let canonical = canonical_entries(entries, Some(100));
+        let keys: Vec<_> = canonical
+            .iter()
+            .map(|e| (e.durability.as_str(), e.key_xdr.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("instance", "key-0009"),
+                ("persistent", "key-0001"),
+                ("persistent", "key-0002"),
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_round_trip() {
+        let entries = canonical_entries(state(200), None);
+        let chunks = chunk_entries(&entries);
+        assert!(chunks.len() > 1);
+
+        let restored: Vec<StateEntry> = chunks
+            .iter()
+            .flat_map(|c| decode_chunk(&ChunkRef::from(c), &c.bytes).unwrap())
+            .collect();
+        assert_eq!(restored, entries);
+    }
+
+    #[test]
+    fn one_changed_entry_rewrites_one_chunk() {
+        let before = chunk_entries(&canonical_entries(state(500), None));
+        let mut changed = state(500);
+        changed[250].entry_xdr = "v2".into();
+        let after = chunk_entries(&canonical_entries(changed, None));
+
+        let new: Vec<_> = after
+            .iter()
+            .filter(|c| !before.iter().any(|b| b.hash == c.hash))
+            .collect();
+        assert_eq!(new.len(), 1);
+        assert_eq!(before.len(), after.len());
+    }
+
+    #[test]
+    fn inserted_entry_keeps_later_chunks() {
+        let before = chunk_entries(&canonical_entries(state(500), None));
+        let mut grown = state(500);
+        grown.push(entry("persistent", 10_000 + 1, "new"));
+        grown[0].key_xdr = "key-0000a".into();
+        let after = chunk_entries(&canonical_entries(grown, None));
+
+        let reused = after
+            .iter()
+            .filter(|c| before.iter().any(|b| b.hash == c.hash))
+            .count();
+        assert!(reused + 3 >= after.len());
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let chunks = chunk_entries(&state(20));
+        let chunk = &chunks[0];
+        let reference = ChunkRef::from(chunk);
+        assert_eq!(check_chunk(&reference, Some(&chunk.bytes)), ChunkStatus::Ok);
+        assert_eq!(check_chunk(&reference, None), ChunkStatus::Missing);
+
+        let mut tampered = chunk.bytes.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            check_chunk(&reference, Some(&tampered)),
+            ChunkStatus::Corrupt
+        );
+        assert!(decode_chunk(&reference, &tampered).is_err());
+    }
+}
@@ -1,68 +1,423 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     Json,
 };
-use chrono::{NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
 use shared::models::{
-    BackupRestoration, ContractBackup, CreateBackupRequest, RestoreBackupRequest,
+    BackupReplica, BackupReplicationStatus, BackupRestoration, BackupVerification, ContractBackup,
+    CreateBackupRequest, LedgerEntryRecord, LedgerSnapshotResponse, RegionReplicationHealth,
+    RestoreBackupRequest,
 };
+use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
+    backup_engine::{self, ChunkRef, ChunkStatus, Manifest, StateEntry, MANIFEST_FORMAT},
+    backup_replication::{self, CopyRole, PlacementDecision, ReplicaObservation},
+    blob_store::{self, content_hash, BlobStore, RegionStore},
     error::{ApiError, ApiResult},
+    residency_engine::{Placement, ResidencyContext},
+    residency_handlers,
     state::AppState,
 };
 
+/// A restored backup: the state as a ledger snapshot, in the same shape as
+/// `GET /regression/ledger-snapshot`, ready to load into a local sandbox.
+#[derive(Debug, Serialize)]
+pub struct RestoredBackup {
+    pub backup: ContractBackup,
+    pub restoration: BackupRestoration,
+    pub snapshot: LedgerSnapshotResponse,
+}
+
+#[derive(sqlx::FromRow)]
+struct ContractRow {
+    address: String,
+    network: String,
+    wasm_hash: String,
+    publisher_id: Uuid,
+    name: String,
+    description: Option<String>,
+    category: Option<String>,
+    tags: Vec<String>,
+}
+
+impl ContractRow {
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "network": self.network,
+            "category": self.category,
+            "tags": self.tags,
+        })
+    }
+}
+
+/// `requested_by` on residency records written by backup placement
+const REPLICATION_ACTOR: &str = "backup-replication";
+
+/// How often the replication task retries unfinished copies
+const REPLICATION_TICK_SECS: u64 = 5 * 60;
+
+fn db_err(e: sqlx::Error) -> ApiError {
+    ApiError::internal(format!("Database error: {}", e))
+}
+
+fn regions() -> ApiResult<Vec<RegionStore>> {
+    blob_store::regions_from_env().map_err(ApiError::internal)
+}
+
+fn region_store(regions: &[RegionStore], region: &str) -> Option<Arc<dyn BlobStore>> {
+    regions
+        .iter()
+        .find(|r| r.region.eq_ignore_ascii_case(region))
+        .map(|r| r.store.clone())
+}
+
+/// The store a backup was written to. Matched on the recorded store first,
+/// so backups taken before regions were configured still resolve.
+fn backup_store(regions: &[RegionStore], backup: &ContractBackup) -> ApiResult<Arc<dyn BlobStore>> {
+    regions
+        .iter()
+        .find(|r| backup.blob_store.as_deref() == Some(r.store.describe().as_str()))
+        .map(|r| r.store.clone())
+        .or_else(|| region_store(regions, &backup.primary_region))
+        .ok_or_else(|| {
+            ApiError::internal(format!(
+                "Backup store '{}' of backup {} is no longer configured",
+                backup
+                    .blob_store
+                    .as_deref()
+                    .unwrap_or(&backup.primary_region),
+                backup.id
+            ))
+        })
+}
+
+fn store_err(e: std::io::Error) -> ApiError {
+    ApiError::internal(format!("Backup store error: {}", e))
+}
+
+async fn load_contract(db: &PgPool, contract_id: Uuid) -> ApiResult<ContractRow> {
+    sqlx::query_as(
+        r#"SELECT contract_id AS address, network::text AS network, wasm_hash, publisher_id,
+            name, description, category, tags
+        FROM contracts WHERE id = $1"#,
+    )
+    .bind(contract_id)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)?
+    .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))
+}
+
+fn load_manifest(backup: &ContractBackup, raw: Option<serde_json::Value>) -> ApiResult<Manifest> {
+    let raw = raw.ok_or_else(|| {
+        ApiError::unprocessable(
+            "backup_has_no_state",
+            "This backup holds metadata only and has no ledger state to restore",
+        )
+    })?;
+    let manifest: Manifest = serde_json::from_value(raw)
+        .map_err(|e| ApiError::internal(format!("Unreadable backup manifest: {}", e)))?;
+    if backup.manifest_hash.as_deref() != Some(content_hash(&manifest.to_bytes()).as_str()) {
+        return Err(ApiError::internal(format!(
+            "Manifest of backup {} does not match its recorded hash",
+            backup.id
+        )));
+    }
+    Ok(manifest)
+}
+
+/// The backup a restore or verify request refers to.
+async fn find_backup(
+    db: &PgPool,
+    contract_id: Uuid,
+    req: &RestoreBackupRequest,
+) -> ApiResult<(ContractBackup, Option<serde_json::Value>)> {
+    let date = req
+        .backup_date
+        .as_deref()
+        .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|_| ApiError::bad_request("invalid_date", "Invalid date format"))?;
+
+    // Newest backup at or before whichever point the request names
+    let backup = sqlx::query_as::<_, ContractBackup>(
+        r#"SELECT * FROM contract_backups
+        WHERE contract_id = $1
+          AND ($2::uuid IS NULL OR id = $2)
+          AND ($3::bigint IS NULL OR ledger_sequence <= $3)
+          AND ($4::timestamptz IS NULL OR created_at <= $4)
+          AND ($5::date IS NULL OR backup_date = $5)
+        ORDER BY ledger_sequence DESC NULLS LAST, created_at DESC
+        LIMIT 1"#,
+    )
+    .bind(contract_id)
+    .bind(req.backup_id)
+    .bind(req.ledger_sequence)
+    .bind(req.at)
+    .bind(date)
+    .fetch_optional(db)
+    .await
+    .map_err(db_err)?
+    .ok_or_else(|| ApiError::not_found("backup", "Backup not found"))?;
+
+    let manifest: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT manifest FROM contract_backups WHERE id = $1")
+            .bind(backup.id)
+            .fetch_one(db)
+            .await
+            .map_err(db_err)?;
+
+    Ok((backup, manifest))
+}
+
 pub async fn create_backup(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Json(req): Json<CreateBackupRequest>,
 ) -> ApiResult<Json<ContractBackup>> {
-    let contract = sqlx::query!("SELECT * FROM contracts WHERE id = $1", contract_id)
-        .fetch_optional(&state.db)
+    let contract = load_contract(&state.db, contract_id).await?;
+
+    if !req.include_state {
+        let backup = sqlx::query_as::<_, ContractBackup>(
+            r#"
+            INSERT INTO contract_backups
+            (contract_id, backup_date, wasm_hash, metadata, storage_size_bytes, backup_kind)
+            VALUES ($1, $2, $3, $4, 0, 'metadata')
+            RETURNING *
+            "#,
+        )
+        .bind(contract_id)
+        .bind(Utc::now().date_naive())
+        .bind(&contract.wasm_hash)
+        .bind(contract.metadata())
+        .fetch_one(&state.db)
         .await
-        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))?;
-
-    let backup_date = Utc::now().date_naive();
-    
-    let metadata = serde_json::json!({
-        "name": contract.name,
-        "description": contract.description,
-        "network": contract.network,
-        "category": contract.category,
-        "tags": contract.tags,
+        .map_err(|e| ApiError::internal(format!("Failed to create backup: {}", e)))?;
+        return Ok(Json(backup));
+    }
+
+    let ledger: Option<(i32, i64, i32, String)> = sqlx::query_as(
+        r#"SELECT ledger_sequence, ledger_timestamp, protocol_version, network_passphrase
+        FROM indexer_ledger_state
+        WHERE network = $1::network_type"#,
+    )
+    .bind(&contract.network)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_err)?;
+
+    let entries: Vec<StateEntry> = sqlx::query_as(
+        r#"SELECT
+            durability::text as durability, key_xdr, entry_xdr, key_display,
+            live_until_ledger, last_modified_ledger
+        FROM contract_ledger_entries
+        WHERE contract_id = $1"#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_err)?;
+
+    if entries.is_empty() {
+        return Err(ApiError::unprocessable(
+            "ledger_state_not_indexed",
+            "No indexed ledger state for this contract; the indexer records entries once a transaction writes them",
+        ));
+    }
+
+    let ledger_sequence = ledger.as_ref().map(|l| i64::from(l.0));
+    let entries = backup_engine::canonical_entries(entries, ledger_sequence);
+    let chunks = backup_engine::chunk_entries(&entries);
+
+    let regions = regions()?;
+    let residency = residency_handlers::load_context(&state.db, &contract.address).await?;
+    let now = Utc::now();
+
+    let configured: Vec<String> = regions.iter().map(|r| r.region.clone()).collect();
+    let requested = req
+        .regions
+        .clone()
+        .unwrap_or_else(|| configured[1..].to_vec());
+    let placement = backup_replication::plan(&configured, &requested, |role, region| {
+        residency.permits(&backup_placement(role, region, now))
     });
+    record_placement(
+        &state.db,
+        &residency,
+        &contract.address,
+        &placement.decisions,
+        now,
+    )
+    .await?;
 
-    let state_snapshot = if req.include_state {
-        Some(serde_json::json!({"placeholder": "state data"}))
+    let primary = placement.primary.clone().ok_or_else(|| {
+        ApiError::unprocessable(
+            "residency_no_permitted_region",
+            "No configured backup region is permitted by the contract's residency policies",
+        )
+    })?;
+    let store = region_store(&regions, &primary)
+        .ok_or_else(|| ApiError::internal(format!("Region '{}' has no store", primary)))?;
+    let store_name = store.describe();
+
+    let parent: Option<Uuid> = if req.incremental {
+        sqlx::query_scalar(
+            r#"SELECT id FROM contract_backups
+            WHERE contract_id = $1 AND manifest IS NOT NULL
+            ORDER BY created_at DESC LIMIT 1"#,
+        )
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(db_err)?
     } else {
         None
     };
 
+    // Incremental backups trust the chunk index; full ones check the store
+    // itself and upload anything missing from it
+    let hashes: Vec<String> = chunks.iter().map(|c| c.hash.clone()).collect();
+    let mut known: HashSet<String> = if req.incremental {
+        sqlx::query_scalar::<_, String>(
+            "SELECT hash FROM backup_chunks WHERE hash = ANY($1) AND blob_store = $2",
+        )
+        .bind(&hashes)
+        .bind(&store_name)
+        .fetch_all(&state.db)
+        .await
+        .map_err(db_err)?
+        .into_iter()
+        .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let mut new_chunks = 0i32;
+    let mut new_bytes = 0i64;
+    for chunk in &chunks {
+        if known.contains(&chunk.hash) {
+            continue;
+        }
+        if req.incremental || !store.exists(&chunk.hash).await.map_err(store_err)? {
+            store
+                .put(&chunk.hash, &chunk.bytes)
+                .await
+                .map_err(store_err)?;
+            new_chunks += 1;
+            new_bytes += chunk.bytes.len() as i64;
+        }
+        sqlx::query(
+            r#"INSERT INTO backup_chunks (hash, size_bytes, entry_count, blob_store)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (hash, blob_store) DO NOTHING"#,
+        )
+        .bind(&chunk.hash)
+        .bind(chunk.bytes.len() as i64)
+        .bind(chunk.entries as i32)
+        .bind(&store_name)
+        .execute(&state.db)
+        .await
+        .map_err(db_err)?;
+        known.insert(chunk.hash.clone());
+    }
+
+    let manifest = Manifest {
+        format: MANIFEST_FORMAT,
+        contract_id,
+        contract_address: contract.address.clone(),
+        network: contract.network.clone(),
+        wasm_hash: contract.wasm_hash.clone(),
+        ledger_sequence,
+        ledger_timestamp: ledger.as_ref().map(|l| l.1),
+        protocol_version: ledger.as_ref().map(|l| l.2),
+        network_passphrase: ledger.map(|l| l.3),
+        entry_count: entries.len() as u64,
+        logical_size_bytes: chunks.iter().map(|c| c.bytes.len() as u64).sum(),
+        chunks: chunks.iter().map(ChunkRef::from).collect(),
+    };
+    let manifest_bytes = manifest.to_bytes();
+    let manifest_hash = content_hash(&manifest_bytes);
+    store
+        .put(&manifest_hash, &manifest_bytes)
+        .await
+        .map_err(store_err)?;
+
+    let kind = if parent.is_some() {
+        "incremental"
+    } else {
+        "full"
+    };
+
     let backup = sqlx::query_as::<_, ContractBackup>(
         r#"
-        INSERT INTO contract_backups 
-        (contract_id, backup_date, wasm_hash, metadata, state_snapshot, storage_size_bytes, primary_region, backup_regions)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-        ON CONFLICT (contract_id, backup_date) DO UPDATE 
-        SET wasm_hash = $3, metadata = $4, state_snapshot = $5
+        INSERT INTO contract_backups
+        (contract_id, backup_date, wasm_hash, metadata, storage_size_bytes, backup_kind,
+         parent_backup_id, ledger_sequence, manifest, manifest_hash, entry_count,
+         chunk_count, new_chunk_count, logical_size_bytes, blob_store, primary_region,
+         backup_regions)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
         RETURNING *
         "#,
     )
     .bind(contract_id)
-    .bind(backup_date)
+    .bind(Utc::now().date_naive())
     .bind(&contract.wasm_hash)
-    .bind(&metadata)
-    .bind(&state_snapshot)
-    .bind(1024i64) // Placeholder size
-    .bind("us-east-1")
-    .bind(vec!["us-west-2", "eu-west-1"])
+    .bind(contract.metadata())
+    .bind(new_bytes + manifest_bytes.len() as i64)
+    .bind(kind)
+    .bind(parent)
+    .bind(ledger_sequence)
+    .bind(serde_json::to_value(&manifest).map_err(|e| ApiError::internal(e.to_string()))?)
+    .bind(&manifest_hash)
+    .bind(entries.len() as i32)
+    .bind(chunks.len() as i32)
+    .bind(new_chunks)
+    .bind(manifest.logical_size_bytes as i64)
+    .bind(&store_name)
+    .bind(&primary)
+    .bind(&placement.replicas)
     .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(format!("Failed to create backup: {}", e)))?;
 
+    insert_replicas(&state.db, backup.id, &placement.decisions).await?;
+    let targets: Vec<RegionStore> = regions
+        .into_iter()
+        .filter(|r| {
+            placement
+                .replicas
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(&r.region))
+        })
+        .collect();
+    let mut blobs = hashes;
+    blobs.push(manifest_hash.clone());
+    tokio::spawn(replicate(
+        state.db.clone(),
+        backup.id,
+        store,
+        targets,
+        blobs,
+    ));
+
+    tracing::info!(
+        contract_id = %contract_id,
+        backup_id = %backup.id,
+        chunks = chunks.len(),
+        new_chunks,
+        "contract backup stored"
+    );
+
     Ok(Json(backup))
 }
 
@@ -71,105 +426,627 @@ pub async fn list_backups(
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<Vec<ContractBackup>>> {
     let backups = sqlx::query_as::<_, ContractBackup>(
-        "SELECT * FROM contract_backups WHERE contract_id = $1 ORDER BY backup_date DESC LIMIT 30",
+        "SELECT * FROM contract_backups WHERE contract_id = $1 ORDER BY created_at DESC LIMIT 30",
     )
     .bind(contract_id)
     .fetch_all(&state.db)
     .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+    .map_err(db_err)?;
 
     Ok(Json(backups))
 }
 
+/// Read every chunk of a backup back, checking each hash. A chunk that is
+/// missing or corrupt in one store is read from the next.
+async fn read_state(
+    stores: &[Arc<dyn BlobStore>],
+    manifest: &Manifest,
+) -> Result<Vec<StateEntry>, String> {
+    let mut entries = Vec::with_capacity(manifest.entry_count as usize);
+    for chunk in &manifest.chunks {
+        let mut last_err = format!("chunk {} is missing from every store", chunk.hash);
+        let mut decoded = None;
+        for store in stores {
+            let bytes = match store.get(&chunk.hash).await {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => continue,
+                Err(e) => {
+                    last_err = format!("backup store error: {}", e);
+                    continue;
+                }
+            };
+            match backup_engine::decode_chunk(chunk, &bytes) {
+                Ok(chunk_entries) => {
+                    decoded = Some(chunk_entries);
+                    break;
+                }
+                Err(e) => last_err = e,
+            }
+        }
+        entries.extend(decoded.ok_or(last_err)?);
+    }
+    Ok(entries)
+}
+
+/// The backup's own store followed by its completed replicas.
+async fn restore_sources(
+    db: &PgPool,
+    backup: &ContractBackup,
+) -> ApiResult<Vec<Arc<dyn BlobStore>>> {
+    let regions = regions()?;
+    let mut stores = vec![backup_store(&regions, backup)?];
+    let replicas: Vec<String> = sqlx::query_scalar(
+        "SELECT region FROM backup_replicas WHERE backup_id = $1 AND status = 'completed'",
+    )
+    .bind(backup.id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+    stores.extend(replicas.iter().filter_map(|r| region_store(&regions, r)));
+    Ok(stores)
+}
+
 pub async fn restore_backup(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Json(req): Json<RestoreBackupRequest>,
-) -> ApiResult<Json<BackupRestoration>> {
+) -> ApiResult<Json<RestoredBackup>> {
     let start = std::time::Instant::now();
+    let contract = load_contract(&state.db, contract_id).await?;
+    let (backup, raw_manifest) = find_backup(&state.db, contract_id, &req).await?;
+    let manifest = load_manifest(&backup, raw_manifest)?;
 
-    let backup_date = NaiveDate::parse_from_str(&req.backup_date, "%Y-%m-%d")
-        .map_err(|_| ApiError::bad_request("invalid_date", "Invalid date format"))?;
-
-    let backup = sqlx::query_as::<_, ContractBackup>(
-        "SELECT * FROM contract_backups WHERE contract_id = $1 AND backup_date = $2",
-    )
-    .bind(contract_id)
-    .bind(backup_date)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-    .ok_or_else(|| ApiError::not_found("backup", "Backup not found"))?;
-
-    // Simulate restoration
-    let duration_ms = start.elapsed().as_millis() as i32;
-
-    let contract = sqlx::query!("SELECT publisher_id FROM contracts WHERE id = $1", contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+    let stores = restore_sources(&state.db, &backup).await?;
+    let result = read_state(&stores, &manifest).await;
 
     let restoration = sqlx::query_as::<_, BackupRestoration>(
         r#"
-        INSERT INTO backup_restorations (backup_id, restored_by, restore_duration_ms, success)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO backup_restorations
+        (backup_id, restored_by, restore_duration_ms, success, error_message)
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING *
         "#,
     )
     .bind(backup.id)
     .bind(contract.publisher_id)
-    .bind(duration_ms)
-    .bind(true)
+    .bind(start.elapsed().as_millis() as i32)
+    .bind(result.is_ok())
+    .bind(result.as_ref().err())
     .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(format!("Failed to log restoration: {}", e)))?;
 
-    Ok(Json(restoration))
+    let entries = result.map_err(|e| {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "backup_corrupt",
+            format!("Backup {} cannot be restored: {}", backup.id, e),
+        )
+    })?;
+
+    let snapshot = LedgerSnapshotResponse {
+        contract_id,
+        contract_address: manifest.contract_address,
+        network: manifest.network,
+        ledger_sequence: manifest.ledger_sequence.and_then(|l| i32::try_from(l).ok()),
+        ledger_timestamp: manifest.ledger_timestamp,
+        protocol_version: manifest.protocol_version,
+        network_passphrase: manifest.network_passphrase,
+        entries: entries
+            .into_iter()
+            .map(|e| LedgerEntryRecord {
+                durability: e.durability,
+                key_xdr: e.key_xdr,
+                entry_xdr: e.entry_xdr,
+                key_display: e.key_display,
+                live_until_ledger: e.live_until_ledger,
+                last_modified_ledger: e.last_modified_ledger,
+            })
+            .collect(),
+    };
+
+    Ok(Json(RestoredBackup {
+        backup,
+        restoration,
+        snapshot,
+    }))
 }
 
+/// `:backup` is a backup ID, or a date for the newest backup taken that day.
 pub async fn verify_backup(
     State(state): State<AppState>,
-    Path((contract_id, backup_date)): Path<(Uuid, String)>,
-) -> ApiResult<StatusCode> {
-    let date = NaiveDate::parse_from_str(&backup_date, "%Y-%m-%d")
-        .map_err(|_| ApiError::bad_request("invalid_date", "Invalid date format"))?;
+    Path((contract_id, backup)): Path<(Uuid, String)>,
+) -> ApiResult<Json<BackupVerification>> {
+    let req = match Uuid::parse_str(&backup) {
+        Ok(id) => RestoreBackupRequest {
+            backup_id: Some(id),
+            ..Default::default()
+        },
+        Err(_) => RestoreBackupRequest {
+            backup_date: Some(backup),
+            ..Default::default()
+        },
+    };
+    let (backup, raw_manifest) = find_backup(&state.db, contract_id, &req).await?;
 
-    sqlx::query(
-        "UPDATE contract_backups SET verified = true WHERE contract_id = $1 AND backup_date = $2",
-    )
-    .bind(contract_id)
-    .bind(date)
-    .execute(&state.db)
-    .await
-    .map_err(|e| ApiError::internal(format!("Failed to verify backup: {}", e)))?;
+    let mut missing_chunks = Vec::new();
+    let mut corrupt_chunks = Vec::new();
+    let mut manifest_ok = true;
+    let mut chunks_checked = 0;
+
+    if let Some(raw) = raw_manifest {
+        let store = backup_store(&regions()?, &backup)?;
+        let manifest: Option<Manifest> = serde_json::from_value(raw).ok();
+        let stored = match &backup.manifest_hash {
+            Some(hash) => store.get(hash).await.map_err(store_err)?,
+            None => None,
+        };
+        manifest_ok = match (&manifest, &stored, &backup.manifest_hash) {
+            (Some(m), Some(bytes), Some(hash)) => {
+                content_hash(bytes) == *hash && content_hash(&m.to_bytes()) == *hash
+            }
+            _ => false,
+        };
+
+        for chunk in manifest.iter().flat_map(|m| m.chunks.iter()) {
+            let bytes = store.get(&chunk.hash).await.map_err(store_err)?;
+            chunks_checked += 1;
+            match backup_engine::check_chunk(chunk, bytes.as_deref()) {
+                ChunkStatus::Ok => {}
+                ChunkStatus::Missing => missing_chunks.push(chunk.hash.clone()),
+                ChunkStatus::Corrupt => corrupt_chunks.push(chunk.hash.clone()),
+            }
+        }
+    }
+
+    let verified = manifest_ok && missing_chunks.is_empty() && corrupt_chunks.is_empty();
+    let verified_at = Utc::now();
 
-    Ok(StatusCode::NO_CONTENT)
+    sqlx::query("UPDATE contract_backups SET verified = $2, verified_at = $3 WHERE id = $1")
+        .bind(backup.id)
+        .bind(verified)
+        .bind(verified_at)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to verify backup: {}", e)))?;
+
+    Ok(Json(BackupVerification {
+        backup_id: backup.id,
+        verified,
+        chunks_checked,
+        missing_chunks,
+        corrupt_chunks,
+        manifest_ok,
+        verified_at,
+    }))
 }
 
 pub async fn get_backup_stats(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let stats = sqlx::query!(
+    let (total, verified, stored, logical, latest): (
+        i64,
+        i64,
+        i64,
+        i64,
+        Option<chrono::DateTime<Utc>>,
+    ) = sqlx::query_as(
         r#"
-        SELECT 
-            COUNT(*) as total_backups,
-            COUNT(*) FILTER (WHERE verified = true) as verified_backups,
-            SUM(storage_size_bytes) as total_size_bytes,
-            MAX(backup_date) as latest_backup
-        FROM contract_backups 
+        SELECT
+            COUNT(*),
+            COUNT(*) FILTER (WHERE verified = true),
+            COALESCE(SUM(storage_size_bytes), 0)::BIGINT,
+            COALESCE(SUM(logical_size_bytes), 0)::BIGINT,
+            MAX(created_at)
+        FROM contract_backups
         WHERE contract_id = $1
         "#,
-        contract_id
     )
+    .bind(contract_id)
     .fetch_one(&state.db)
     .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+    .map_err(db_err)?;
+
+    // How much smaller the store is than keeping every backup whole
+    let dedup_ratio = if stored > 0 {
+        logical as f64 / stored as f64
+    } else {
+        0.0
+    };
 
     Ok(Json(serde_json::json!({
-        "total_backups": stats.total_backups.unwrap_or(0),
-        "verified_backups": stats.verified_backups.unwrap_or(0),
-        "total_size_bytes": stats.total_size_bytes.unwrap_or(0),
-        "latest_backup": stats.latest_backup,
+        "total_backups": total,
+        "verified_backups": verified,
+        "total_size_bytes": stored,
+        "logical_size_bytes": logical,
+        "dedup_ratio": dedup_ratio,
+        "latest_backup": latest,
     })))
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Replication
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Contract state may hold data of any classification, so backups are
+/// placed as unclassified data and every active policy applies.
+fn backup_placement(role: CopyRole, region: &str, at: DateTime<Utc>) -> Placement<'_> {
+    Placement {
+        region,
+        classification: None,
+        action: role.action(),
+        at,
+    }
+}
+
+/// Record each placement decision in the residency audit log, once per
+/// active policy. A rerouted copy logs both the refused and the chosen region.
+async fn record_placement(
+    db: &PgPool,
+    residency: &ResidencyContext,
+    contract_address: &str,
+    decisions: &[PlacementDecision],
+    at: DateTime<Utc>,
+) -> ApiResult<()> {
+    for decision in decisions {
+        let mut checked = vec![decision.requested.as_str()];
+        if decision.rerouted() {
+            checked.extend(decision.placed.as_deref());
+        }
+        for region in &checked {
+            for verdict in residency.evaluate(&backup_placement(decision.role, region, at)) {
+                residency_handlers::record_verdict(
+                    db,
+                    &verdict,
+                    contract_address,
+                    region,
+                    decision.role.action(),
+                    Some(REPLICATION_ACTOR),
+                    None,
+                    &format!("{}. {}", verdict.explanation, decision.reason),
+                )
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn insert_replicas(
+    db: &PgPool,
+    backup_id: Uuid,
+    decisions: &[PlacementDecision],
+) -> ApiResult<()> {
+    for decision in decisions.iter().filter(|d| d.role == CopyRole::Replica) {
+        let (region, status) = match &decision.placed {
+            Some(region) => (region, "pending"),
+            None => (&decision.requested, "refused"),
+        };
+        sqlx::query(
+            r#"INSERT INTO backup_replicas (backup_id, region, requested_region, status, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (backup_id, requested_region) DO NOTHING"#,
+        )
+        .bind(backup_id)
+        .bind(region)
+        .bind(&decision.requested)
+        .bind(status)
+        .bind(&decision.reason)
+        .execute(db)
+        .await
+        .map_err(db_err)?;
+    }
+    Ok(())
+}
+
+/// Copy blobs the target lacks, checking each against its hash on the way.
+async fn copy_blobs(
+    source: &dyn BlobStore,
+    target: &dyn BlobStore,
+    blobs: &[String],
+) -> Result<(i32, i64), String> {
+    let mut copied = 0i32;
+    let mut bytes_copied = 0i64;
+    for hash in blobs {
+        if target.exists(hash).await.map_err(|e| e.to_string())? {
+            continue;
+        }
+        let bytes = source
+            .get(hash)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("blob {} is missing from the source store", hash))?;
+        if content_hash(&bytes) != *hash {
+            return Err(format!("blob {} is corrupt in the source store", hash));
+        }
+        target.put(hash, &bytes).await.map_err(|e| e.to_string())?;
+        copied += 1;
+        bytes_copied += bytes.len() as i64;
+    }
+    Ok((copied, bytes_copied))
+}
+
+/// Copy a backup to each target region and record the outcome.
+async fn replicate(
+    db: PgPool,
+    backup_id: Uuid,
+    source: Arc<dyn BlobStore>,
+    targets: Vec<RegionStore>,
+    blobs: Vec<String>,
+) {
+    for target in targets {
+        let result = copy_blobs(source.as_ref(), target.store.as_ref(), &blobs).await;
+        let update = match &result {
+            Ok((chunks, bytes)) => sqlx::query(
+                r#"UPDATE backup_replicas
+                SET status = 'completed', chunks_copied = $3, bytes_copied = $4,
+                    error_message = NULL, replicated_at = NOW()
+                WHERE backup_id = $1 AND region = $2 AND status <> 'refused'"#,
+            )
+            .bind(backup_id)
+            .bind(&target.region)
+            .bind(chunks)
+            .bind(bytes),
+            Err(e) => sqlx::query(
+                r#"UPDATE backup_replicas SET status = 'failed', error_message = $3
+                WHERE backup_id = $1 AND region = $2 AND status <> 'refused'"#,
+            )
+            .bind(backup_id)
+            .bind(&target.region)
+            .bind(e),
+        };
+        if let Err(e) = update.execute(&db).await {
+            tracing::error!(backup_id = %backup_id, region = %target.region, error = %e, "failed to record replication");
+        }
+        match result {
+            Ok(_) => {
+                tracing::info!(backup_id = %backup_id, region = %target.region, "backup replicated")
+            }
+            Err(e) => {
+                tracing::warn!(backup_id = %backup_id, region = %target.region, error = %e, "backup replication failed")
+            }
+        }
+    }
+}
+
+/// Every blob a backup is made of: its chunks and the manifest itself.
+fn backup_blobs(backup: &ContractBackup, manifest: &Manifest) -> Vec<String> {
+    let mut blobs: Vec<String> = manifest.chunks.iter().map(|c| c.hash.clone()).collect();
+    blobs.extend(backup.manifest_hash.clone());
+    blobs
+}
+
+/// Reset a backup's pending and failed copies for another attempt and
+/// return the stores to copy to. Copies to regions that are no longer
+/// configured stay failed.
+async fn retry_targets(
+    db: &PgPool,
+    regions: &[RegionStore],
+    backup_id: Uuid,
+) -> ApiResult<Vec<RegionStore>> {
+    let retry: Vec<String> = sqlx::query_scalar(
+        r#"UPDATE backup_replicas SET status = 'pending', error_message = NULL
+        WHERE backup_id = $1 AND status IN ('pending', 'failed')
+        RETURNING region"#,
+    )
+    .bind(backup_id)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+
+    let mut targets = Vec::new();
+    for region in retry {
+        match region_store(regions, &region) {
+            Some(store) => targets.push(RegionStore { region, store }),
+            None => {
+                sqlx::query(
+                    r#"UPDATE backup_replicas
+                    SET status = 'failed', error_message = 'region is no longer configured'
+                    WHERE backup_id = $1 AND region = $2"#,
+                )
+                .bind(backup_id)
+                .bind(&region)
+                .execute(db)
+                .await
+                .map_err(db_err)?;
+            }
+        }
+    }
+    Ok(targets)
+}
+
+/// Retry copies that failed, or are still pending well past the lag
+/// threshold because the server restarted mid-copy, then log every region
+/// whose replication is degraded or failing.
+pub fn spawn_replication_task(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(REPLICATION_TICK_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(err) = retry_unfinished(&pool).await {
+                tracing::error!(error = ?err, "backup replication: retry failed");
+            }
+            match recent_observations(&pool).await {
+                Ok(observations) => {
+                    for region in backup_replication::region_health(&observations, Utc::now()) {
+                        if region.status != "healthy" {
+                            tracing::warn!(
+                                region = %region.region,
+                                status = %region.status,
+                                pending = region.pending,
+                                failed = region.failed,
+                                lag_seconds = region.current_lag_seconds,
+                                "backup replication: region is behind"
+                            );
+                        }
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(error = ?err, "backup replication: health check failed")
+                }
+            }
+        }
+    });
+}
+
+async fn retry_unfinished(db: &PgPool) -> ApiResult<()> {
+    let backups: Vec<ContractBackup> = sqlx::query_as(
+        r#"SELECT b.* FROM contract_backups b
+        WHERE EXISTS (
+            SELECT 1 FROM backup_replicas r
+            WHERE r.backup_id = b.id
+              AND (r.status = 'failed'
+                OR (r.status = 'pending' AND r.created_at < NOW() - make_interval(secs => $1)))
+        )
+        ORDER BY b.created_at"#,
+    )
+    .bind(backup_replication::LAG_THRESHOLD_SECS as f64)
+    .fetch_all(db)
+    .await
+    .map_err(db_err)?;
+    if backups.is_empty() {
+        return Ok(());
+    }
+
+    let regions = regions()?;
+    for backup in backups {
+        let raw: Option<serde_json::Value> =
+            sqlx::query_scalar("SELECT manifest FROM contract_backups WHERE id = $1")
+                .bind(backup.id)
+                .fetch_one(db)
+                .await
+                .map_err(db_err)?;
+        // One unreadable backup must not hold up the others
+        let (manifest, store) = match load_manifest(&backup, raw)
+            .and_then(|manifest| Ok((manifest, backup_store(&regions, &backup)?)))
+        {
+            Ok(found) => found,
+            Err(err) => {
+                tracing::warn!(backup_id = %backup.id, error = ?err, "backup replication: cannot retry backup");
+                continue;
+            }
+        };
+        let targets = retry_targets(db, &regions, backup.id).await?;
+        replicate(
+            db.clone(),
+            backup.id,
+            store,
+            targets,
+            backup_blobs(&backup, &manifest),
+        )
+        .await;
+    }
+    Ok(())
+}
+
+/// POST /api/contracts/:id/backups/:backup/replicate
+/// Retry pending and failed copies of a backup
+pub async fn replicate_backup(
+    State(state): State<AppState>,
+    Path((contract_id, backup_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<Vec<BackupReplica>>> {
+    let req = RestoreBackupRequest {
+        backup_id: Some(backup_id),
+        ..Default::default()
+    };
+    let (backup, raw_manifest) = find_backup(&state.db, contract_id, &req).await?;
+    let manifest = load_manifest(&backup, raw_manifest)?;
+    let regions = regions()?;
+    let source = backup_store(&regions, &backup)?;
+    let targets = retry_targets(&state.db, &regions, backup.id).await?;
+    tokio::spawn(replicate(
+        state.db.clone(),
+        backup.id,
+        source,
+        targets,
+        backup_blobs(&backup, &manifest),
+    ));
+
+    let replicas: Vec<BackupReplica> = sqlx::query_as(
+        "SELECT * FROM backup_replicas WHERE backup_id = $1 ORDER BY requested_region",
+    )
+    .bind(backup.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_err)?;
+
+    Ok(Json(replicas))
+}
+
+const OBSERVATIONS_SQL: &str = r#"SELECT r.region, r.status, b.created_at AS backup_created_at,
+        r.replicated_at
+    FROM backup_replicas r
+    JOIN contract_backups b ON b.id = r.backup_id"#;
+
+/// GET /api/contracts/:id/backups/replication
+/// Replication health per region for one contract, with its latest copies
+pub async fn get_replication_status(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<BackupReplicationStatus>> {
+    let observations: Vec<ReplicaObservation> =
+        sqlx::query_as(&format!("{} WHERE b.contract_id = $1", OBSERVATIONS_SQL))
+            .bind(contract_id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(db_err)?;
+
+    let replicas: Vec<BackupReplica> = sqlx::query_as(
+        r#"SELECT r.* FROM backup_replicas r
+        JOIN contract_backups b ON b.id = r.backup_id
+        WHERE b.contract_id = $1
+        ORDER BY r.created_at DESC
+        LIMIT 50"#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_err)?;
+
+    Ok(Json(BackupReplicationStatus {
+        regions: backup_replication::region_health(&observations, Utc::now()),
+        replicas,
+    }))
+}
+
+/// Replica outcomes of the last week, plus anything still pending.
+async fn recent_observations(db: &PgPool) -> ApiResult<Vec<ReplicaObservation>> {
+    sqlx::query_as(&format!(
+        "{} WHERE b.created_at > NOW() - INTERVAL '7 days' OR r.status = 'pending'",
+        OBSERVATIONS_SQL
+    ))
+    .fetch_all(db)
+    .await
+    .map_err(db_err)
+}
+
+/// GET /api/backups/replication/health
+/// Replication health of every region over the last week
+pub async fn get_replication_health(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<RegionReplicationHealth>>> {
+    let observations = recent_observations(&state.db).await?;
+    let mut health = backup_replication::region_health(&observations, Utc::now());
+    // Configured regions with nothing to report yet
+    for region in regions()?.into_iter().skip(1) {
+        if !health
+            .iter()
+            .any(|h| h.region.eq_ignore_ascii_case(&region.region))
+        {
+            health.push(RegionReplicationHealth {
+                region: region.region,
+                status: "healthy".into(),
+                completed: 0,
+                pending: 0,
+                failed: 0,
+                refused: 0,
+                last_replicated_at: None,
+                last_lag_seconds: None,
+                current_lag_seconds: 0,
+            });
+        }
+    }
+
+    Ok(Json(health))
+}
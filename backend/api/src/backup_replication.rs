@@ -0,0 +1,360 @@
+// api/src/backup_replication.rs
+//
+// Where backup copies may go, and how far behind each region is. A backup
+// is written to the primary region and copied to replica regions; every
+// region has to be permitted by the contract's residency policies
+// (see residency_engine). A region
+// a policy forbids is rerouted to the next configured region that is
+// permitted and not already holding a copy, or refused when none is left.
+// Health is derived from the replica records: lag is the time from a
+// backup being taken to its copy landing in the region. Everything in here
+// is synchronous.
+
+use chrono::{DateTime, Utc};
+use shared::models::RegionReplicationHealth;
+
+/// Pending copies older than this mark a region as degraded
+pub const LAG_THRESHOLD_SECS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyRole {
+    Primary,
+    Replica,
+}
+
+impl CopyRole {
+    /// The `action` recorded in residency audit logs
+    pub fn action(self) -> &'static str {
+        match self {
+            CopyRole::Primary => "backup_primary",
+            CopyRole::Replica => "backup_replica",
+        }
+    }
+}
+
+/// What happened to one requested copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacementDecision {
+    pub role: CopyRole,
+    pub requested: String,
+    /// Where the copy goes; `None` when it was refused
+    pub placed: Option<String>,
+    pub reason: String,
+}
+
+impl PlacementDecision {
+    pub fn rerouted(&self) -> bool {
+        self.placed
+            .as_deref()
+            .is_some_and(|p| !p.eq_ignore_ascii_case(&self.requested))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// `None` when no configured region is permitted
+    pub primary: Option<String>,
+    pub replicas: Vec<String>,
+    pub decisions: Vec<PlacementDecision>,
+}
+
+fn contains(regions: &[String], region: &str) -> bool {
+    regions.iter().any(|r| r.eq_ignore_ascii_case(region))
+}
+
+/// Place a backup's primary copy and its replicas.
+///
+/// `configured` lists the regions with a store, the default primary first.
+/// `requested` are the replica regions asked for; `permits` answers whether
+/// the residency policies allow a copy in that role in a region.
+pub fn plan(
+    configured: &[String],
+    requested: &[String],
+    permits: impl Fn(CopyRole, &str) -> bool,
+) -> Placement {
+    let mut decisions = Vec::new();
+    let mut used: Vec<String> = Vec::new();
+
+    // A rerouted copy takes the first permitted region nobody asked for, so
+    // it does not displace a later request
+    let spare = |role: CopyRole, used: &[String]| {
+        configured
+            .iter()
+            .find(|r| !contains(used, r) && !contains(requested, r) && permits(role, r))
+            .cloned()
+    };
+
+    let refused_all = |decisions: Vec<PlacementDecision>| Placement {
+        primary: None,
+        replicas: Vec::new(),
+        decisions,
+    };
+    let Some(default) = configured.first() else {
+        return refused_all(decisions);
+    };
+
+    let primary = if permits(CopyRole::Primary, default) {
+        decisions.push(PlacementDecision {
+            role: CopyRole::Primary,
+            requested: default.clone(),
+            placed: Some(default.clone()),
+            reason: format!("Region '{}' is permitted", default),
+        });
+        default.clone()
+    } else {
+        let target = spare(CopyRole::Primary, &used).or_else(|| {
+            configured
+                .iter()
+                .find(|r| permits(CopyRole::Primary, r))
+                .cloned()
+        });
+        decisions.push(PlacementDecision {
+            role: CopyRole::Primary,
+            requested: default.clone(),
+            placed: target.clone(),
+            reason: match &target {
+                Some(t) => format!(
+                    "Region '{}' is not permitted by residency policy; rerouted to '{}'",
+                    default, t
+                ),
+                None => format!(
+                    "Region '{}' is not permitted and no configured region is",
+                    default
+                ),
+            },
+        });
+        match target {
+            Some(t) => t,
+            None => return refused_all(decisions),
+        }
+    };
+    used.push(primary.clone());
+
+    for region in requested {
+        if contains(&used, region) {
+            continue;
+        }
+        if !contains(configured, region) {
+            decisions.push(PlacementDecision {
+                role: CopyRole::Replica,
+                requested: region.clone(),
+                placed: None,
+                reason: format!("Region '{}' has no backup store configured", region),
+            });
+            continue;
+        }
+        if permits(CopyRole::Replica, region) {
+            used.push(region.clone());
+            decisions.push(PlacementDecision {
+                role: CopyRole::Replica,
+                requested: region.clone(),
+                placed: Some(region.clone()),
+                reason: format!("Region '{}' is permitted", region),
+            });
+            continue;
+        }
+        let target = spare(CopyRole::Replica, &used);
+        decisions.push(PlacementDecision {
+            role: CopyRole::Replica,
+            requested: region.clone(),
+            placed: target.clone(),
+            reason: match &target {
+                Some(t) => format!(
+                    "Region '{}' is not permitted by residency policy; rerouted to '{}'",
+                    region, t
+                ),
+                None => format!(
+                    "Region '{}' is not permitted by residency policy and no permitted region is free",
+                    region
+                ),
+            },
+        });
+        if let Some(t) = target {
+            used.push(t);
+        }
+    }
+
+    Placement {
+        primary: Some(primary),
+        replicas: used.into_iter().skip(1).collect(),
+        decisions,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Health
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// One replica record, as needed for health.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReplicaObservation {
+    pub region: String,
+    /// `pending`, `completed`, `failed` or `refused`
+    pub status: String,
+    pub backup_created_at: DateTime<Utc>,
+    pub replicated_at: Option<DateTime<Utc>>,
+}
+
+/// Per-region replication health, worst regions first.
+pub fn region_health(
+    observations: &[ReplicaObservation],
+    now: DateTime<Utc>,
+) -> Vec<RegionReplicationHealth> {
+    let mut regions: Vec<String> = Vec::new();
+    for o in observations {
+        if !contains(&regions, &o.region) {
+            regions.push(o.region.clone());
+        }
+    }
+
+    let mut health: Vec<RegionReplicationHealth> = regions
+        .into_iter()
+        .map(|region| {
+            let rows: Vec<&ReplicaObservation> = observations
+                .iter()
+                .filter(|o| o.region.eq_ignore_ascii_case(&region))
+                .collect();
+            let count = |status: &str| rows.iter().filter(|o| o.status == status).count() as i64;
+
+            let latest_completed = rows
+                .iter()
+                .filter(|o| o.status == "completed")
+                .filter_map(|o| o.replicated_at.map(|at| (at, o.backup_created_at)))
+                .max_by_key(|(at, _)| *at);
+            let current_lag_seconds = rows
+                .iter()
+                .filter(|o| o.status == "pending")
+                .map(|o| (now - o.backup_created_at).num_seconds().max(0))
+                .max()
+                .unwrap_or(0);
+
+            // The newest finished copy decides whether the region is failing
+            let last_failed = rows
+                .iter()
+                .filter(|o| o.status == "completed" || o.status == "failed")
+                .max_by_key(|o| o.backup_created_at)
+                .is_some_and(|o| o.status == "failed");
+
+            let status = if last_failed {
+                "failing"
+            } else if current_lag_seconds > LAG_THRESHOLD_SECS {
+                "degraded"
+            } else {
+                "healthy"
+            };
+
+            RegionReplicationHealth {
+                region,
+                status: status.to_string(),
+                completed: count("completed"),
+                pending: count("pending"),
+                failed: count("failed"),
+                refused: count("refused"),
+                last_replicated_at: latest_completed.map(|(at, _)| at),
+                last_lag_seconds: latest_completed
+                    .map(|(at, created)| (at - created).num_seconds().max(0)),
+                current_lag_seconds,
+            }
+        })
+        .collect();
+
+    let rank = |status: &str| match status {
+        "failing" => 0,
+        "degraded" => 1,
+        _ => 2,
+    };
+    health.sort_by(|a, b| {
+        rank(&a.status)
+            .cmp(&rank(&b.status))
+            .then_with(|| a.region.cmp(&b.region))
+    });
+    health
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn regions(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn places_permitted_regions_as_requested() {
+        let configured = regions(&["us-east-1", "us-west-2", "eu-west-1"]);
+        let placement = plan(
+            &configured,
+            &regions(&["us-west-2", "eu-west-1"]),
+            |_, _| true,
+        );
+        assert_eq!(placement.primary.as_deref(), Some("us-east-1"));
+        assert_eq!(placement.replicas, regions(&["us-west-2", "eu-west-1"]));
+        assert!(placement.decisions.iter().all(|d| !d.rerouted()));
+    }
+
+    #[test]
+    fn reroutes_forbidden_regions() {
+        let configured = regions(&["us-east-1", "us-west-2", "eu-west-1", "eu-central-1"]);
+        let eu_only = |_, r: &str| r.starts_with("eu-");
+        let placement = plan(&configured, &regions(&["us-west-2"]), eu_only);
+
+        assert_eq!(placement.primary.as_deref(), Some("eu-west-1"));
+        assert_eq!(placement.replicas, regions(&["eu-central-1"]));
+        assert_eq!(placement.decisions.len(), 2);
+        assert!(placement.decisions.iter().all(|d| d.rerouted()));
+    }
+
+    #[test]
+    fn refuses_when_no_permitted_region_is_free() {
+        let configured = regions(&["us-east-1", "eu-west-1"]);
+        let placement = plan(
+            &configured,
+            &regions(&["us-east-1", "ap-south-1", "eu-west-1"]),
+            |_, r| r == "us-east-1",
+        );
+        assert_eq!(placement.primary.as_deref(), Some("us-east-1"));
+        assert!(placement.replicas.is_empty());
+        let refused: Vec<_> = placement
+            .decisions
+            .iter()
+            .filter(|d| d.placed.is_none())
+            .map(|d| d.requested.as_str())
+            .collect();
+        assert_eq!(refused, vec!["ap-south-1", "eu-west-1"]);
+
+        let nowhere = plan(&configured, &[], |_, _| false);
+        assert_eq!(nowhere.primary, None);
+    }
+
+    #[test]
+    fn reports_lag_and_failures_per_region() {
+        let now = Utc::now();
+        let obs = |region: &str, status: &str, age_min: i64, took_min: Option<i64>| {
+            let created = now - Duration::minutes(age_min);
+            ReplicaObservation {
+                region: region.into(),
+                status: status.into(),
+                backup_created_at: created,
+                replicated_at: took_min.map(|m| created + Duration::minutes(m)),
+            }
+        };
+        let observations = vec![
+            obs("us-west-2", "completed", 120, Some(2)),
+            obs("us-west-2", "completed", 60, Some(3)),
+            obs("eu-west-1", "completed", 120, Some(1)),
+            obs("eu-west-1", "pending", 30, None),
+            obs("ap-south-1", "completed", 120, Some(1)),
+            obs("ap-south-1", "failed", 60, None),
+        ];
+        let health = region_health(&observations, now);
+        let by = |r: &str| health.iter().find(|h| h.region == r).unwrap();
+
+        assert_eq!(health[0].region, "ap-south-1");
+        assert_eq!(by("ap-south-1").status, "failing");
+        assert_eq!(by("eu-west-1").status, "degraded");
+        assert_eq!(by("eu-west-1").current_lag_seconds, 30 * 60);
+        assert_eq!(by("us-west-2").status, "healthy");
+        assert_eq!(by("us-west-2").last_lag_seconds, Some(3 * 60));
+    }
+}
@@ -13,11 +13,23 @@ pub fn backup_routes() -> Router<AppState> {
             post(backup_handlers::restore_backup),
         )
         .route(
-            "/api/contracts/:id/backups/:date/verify",
+            "/api/contracts/:id/backups/:backup/verify",
             post(backup_handlers::verify_backup),
         )
         .route(
             "/api/contracts/:id/backups/stats",
             get(backup_handlers::get_backup_stats),
         )
+        .route(
+            "/api/contracts/:id/backups/replication",
+            get(backup_handlers::get_replication_status),
+        )
+        .route(
+            "/api/contracts/:id/backups/:backup/replicate",
+            post(backup_handlers::replicate_backup),
+        )
+        .route(
+            "/api/backups/replication/health",
+            get(backup_handlers::get_replication_health),
+        )
 }
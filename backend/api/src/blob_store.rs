@@ -0,0 +1,242 @@
+// api/src/blob_store.rs
+//
+// Content-addressed blob storage for contract backups. Blobs are immutable
+// and keyed by the lowercase hex SHA-256 of their bytes, so writing a blob
+// that already exists is a no-op and identical chunks from different
+// backups are stored once. The backend is chosen at runtime from
+// `BACKUP_STORE`; only the local filesystem is implemented so far, other
+// backends plug in behind the same trait. `BACKUP_REGIONS` configures one
+// store per region for cross-region replication.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+/// Environment variable selecting the backend (`local`)
+pub const BACKUP_STORE_ENV: &str = "BACKUP_STORE";
+/// Root directory of the `local` backend
+pub const BACKUP_STORE_PATH_ENV: &str = "BACKUP_STORE_PATH";
+const DEFAULT_LOCAL_PATH: &str = "./data/backups";
+/// Comma-separated `region=location` pairs, one store per region
+pub const BACKUP_REGIONS_ENV: &str = "BACKUP_REGIONS";
+/// Region backups are written to first; defaults to the first listed
+pub const BACKUP_PRIMARY_REGION_ENV: &str = "BACKUP_PRIMARY_REGION";
+const DEFAULT_REGION: &str = "local";
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Identifies the backend and location, recorded on each backup
+    fn describe(&self) -> String;
+
+    async fn exists(&self, hash: &str) -> io::Result<bool>;
+
+    /// Store `bytes` under `hash`. The caller computes the hash; existing
+    /// blobs are left untouched.
+    async fn put(&self, hash: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// The blob's bytes, or `None` if it is missing
+    async fn get(&self, hash: &str) -> io::Result<Option<Vec<u8>>>;
+}
+
+/// Lowercase hex SHA-256 of `bytes`, the key a blob is stored under.
+pub fn content_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn check_hash(hash: &str) -> io::Result<()> {
+    let valid = hash.len() == 64
+        && hash
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid blob hash '{}'", hash),
+        ))
+    }
+}
+
+/// Blobs as files under a root directory, fanned out by the first two
+/// bytes of the hash: `<root>/ab/cd/abcd…`.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, hash: &str) -> io::Result<PathBuf> {
+        check_hash(hash)?;
+        Ok(self.root.join(&hash[0..2]).join(&hash[2..4]).join(hash))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    fn describe(&self) -> String {
+        format!("local:{}", self.root.display())
+    }
+
+    async fn exists(&self, hash: &str) -> io::Result<bool> {
+        tokio::fs::try_exists(self.path_for(hash)?).await
+    }
+
+    async fn put(&self, hash: &str, bytes: &[u8]) -> io::Result<()> {
+        let path = self.path_for(hash)?;
+        if tokio::fs::try_exists(&path).await? {
+            return Ok(());
+        }
+        let dir = path.parent().unwrap_or(Path::new("."));
+        tokio::fs::create_dir_all(dir).await?;
+
+        // Write beside the final name and rename, so a crash never leaves a
+        // truncated blob under a valid hash
+        let tmp = dir.join(format!("{}.{}.tmp", hash, uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp, bytes).await?;
+        if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, hash: &str) -> io::Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path_for(hash)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn open(kind: &str, location: &str) -> Result<Arc<dyn BlobStore>, String> {
+    match kind {
+        "local" => Ok(Arc::new(LocalBlobStore::new(location))),
+        other => Err(format!(
+            "unsupported {} backend '{}'",
+            BACKUP_STORE_ENV, other
+        )),
+    }
+}
+
+/// The backend configured by `BACKUP_STORE` and its settings.
+pub fn from_env() -> Result<Arc<dyn BlobStore>, String> {
+    let kind = std::env::var(BACKUP_STORE_ENV).unwrap_or_else(|_| "local".into());
+    let root = std::env::var(BACKUP_STORE_PATH_ENV).unwrap_or_else(|_| DEFAULT_LOCAL_PATH.into());
+    open(&kind, &root)
+}
+
+/// A blob store holding backup copies in one region.
+#[derive(Clone)]
+pub struct RegionStore {
+    pub region: String,
+    pub store: Arc<dyn BlobStore>,
+}
+
+/// Parse `BACKUP_REGIONS`, e.g. `us-east-1=/srv/backups/use1,eu-west-1=/srv/backups/euw1`.
+pub fn parse_regions(raw: &str) -> Result<Vec<(String, String)>, String> {
+    let mut regions: Vec<(String, String)> = Vec::new();
+    for pair in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (region, location) = pair
+            .split_once('=')
+            .map(|(r, l)| (r.trim(), l.trim()))
+            .filter(|(r, l)| !r.is_empty() && !l.is_empty())
+            .ok_or_else(|| format!("invalid {} entry '{}'", BACKUP_REGIONS_ENV, pair))?;
+        if regions.iter().any(|(r, _)| r.eq_ignore_ascii_case(region)) {
+            return Err(format!("region '{}' is listed twice", region));
+        }
+        regions.push((region.to_string(), location.to_string()));
+    }
+    Ok(regions)
+}
+
+/// Every configured region with the primary first. Without
+/// `BACKUP_REGIONS` there is a single region backed by `from_env`.
+pub fn regions_from_env() -> Result<Vec<RegionStore>, String> {
+    let primary = std::env::var(BACKUP_PRIMARY_REGION_ENV).ok();
+    let Ok(raw) = std::env::var(BACKUP_REGIONS_ENV) else {
+        return Ok(vec![RegionStore {
+            region: primary.unwrap_or_else(|| DEFAULT_REGION.into()),
+            store: from_env()?,
+        }]);
+    };
+
+    let kind = std::env::var(BACKUP_STORE_ENV).unwrap_or_else(|_| "local".into());
+    let mut regions = parse_regions(&raw)?
+        .into_iter()
+        .map(|(region, location)| {
+            Ok(RegionStore {
+                region,
+                store: open(&kind, &location)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if regions.is_empty() {
+        return Err(format!("{} lists no regions", BACKUP_REGIONS_ENV));
+    }
+    if let Some(primary) = primary {
+        let at = regions
+            .iter()
+            .position(|r| r.region.eq_ignore_ascii_case(&primary))
+            .ok_or_else(|| {
+                format!(
+                    "primary region '{}' is not in {}",
+                    primary, BACKUP_REGIONS_ENV
+                )
+            })?;
+        let first = regions.remove(at);
+        regions.insert(0, first);
+    }
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_store_round_trips_and_dedupes() {
+        let root = std::env::temp_dir().join(format!("blob-store-{}", uuid::Uuid::new_v4()));
+        let store = LocalBlobStore::new(&root);
+        let bytes = b"ledger entries";
+        let hash = content_hash(bytes);
+
+        assert!(!store.exists(&hash).await.unwrap());
+        assert_eq!(store.get(&hash).await.unwrap(), None);
+
+        store.put(&hash, bytes).await.unwrap();
+        store.put(&hash, bytes).await.unwrap();
+        assert!(store.exists(&hash).await.unwrap());
+        assert_eq!(store.get(&hash).await.unwrap().as_deref(), Some(&bytes[..]));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn parses_region_list() {
+        let regions = parse_regions("us-east-1=/a, eu-west-1 = /b ,").unwrap();
+        assert_eq!(
+            regions,
+            vec![
+                ("us-east-1".to_string(), "/a".to_string()),
+                ("eu-west-1".to_string(), "/b".to_string()),
+            ]
+        );
+        assert!(parse_regions("us-east-1").is_err());
+        assert!(parse_regions("us-east-1=/a,US-EAST-1=/b").is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_that_are_not_hashes() {
+        let store = LocalBlobStore::new(std::env::temp_dir());
+        assert!(store.get("../../etc/passwd").await.is_err());
+        assert!(store.put("ABCD", b"x").await.is_err());
+    }
+}
@@ -0,0 +1,508 @@
+// api/src/canary_controller.rs
+//
+// Background canary controller. Every tick it observes each active canary
+// release for its current stage (recorded canary and stable metrics plus
+// the contract's event volume), judges the stage with canary_engine and
+// applies the outcome: advance to the next stage, promote the canary
+// deployment, or roll back to the stable one. Every transition is written
+// to canary_decisions together with the criteria that produced it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use shared::{
+    CanaryCriteria, CanaryCriterionResult, CanaryDecisionKind, CanaryRelease, CanaryVariant,
+    RolloutStage,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::canary_engine::{self, Evaluation, MetricSample, StageObservation, Verdict};
+use crate::metrics;
+
+const TICK_SECS: u64 = 60;
+/// Event volume during a stage is compared with this many days before the
+/// release started
+const EVENT_BASELINE_DAYS: i64 = 7;
+pub const CONTROLLER_ACTOR: &str = "canary-controller";
+
+pub fn spawn_canary_controller(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(TICK_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(err) = evaluate_active(&pool).await {
+                tracing::error!(error = ?err, "canary controller run failed");
+            }
+        }
+    });
+}
+
+async fn evaluate_active(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let canaries: Vec<CanaryRelease> =
+        sqlx::query_as("SELECT * FROM canary_releases WHERE status = 'active'")
+            .fetch_all(pool)
+            .await?;
+    metrics::CANARY_ACTIVE.set(canaries.len() as i64);
+
+    for canary in canaries {
+        if let Err(err) = step(pool, &canary).await {
+            tracing::error!(canary_id = %canary.id, error = ?err, "canary evaluation failed");
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Observation
+// ─────────────────────────────────────────────────────────
+
+pub async fn fetch_criteria(pool: &PgPool, canary_id: Uuid) -> Result<CanaryCriteria, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT error_rate_threshold::float8 AS error_rate_threshold, max_latency_increase_pct,
+                max_event_anomalies, min_stage_minutes, min_stage_requests, stage_percentages,
+                auto_advance
+           FROM canary_releases WHERE id = $1",
+    )
+    .bind(canary_id)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn stage_started_at(
+    pool: &PgPool,
+    canary_id: Uuid,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    sqlx::query_scalar("SELECT stage_started_at FROM canary_releases WHERE id = $1")
+        .bind(canary_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Events per completed hour in `[from, to)`, including empty hours.
+async fn hourly_event_counts(
+    pool: &PgPool,
+    address: &str,
+    network: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(e.id)
+           FROM generate_series(date_trunc('hour', $3::timestamptz),
+                                date_trunc('hour', $4::timestamptz) - INTERVAL '1 hour',
+                                INTERVAL '1 hour') AS h
+           LEFT JOIN contract_events e
+                  ON e.contract_id = $1 AND e.network::text = $2
+                 AND e.timestamp >= h AND e.timestamp < h + INTERVAL '1 hour'
+          GROUP BY h
+          ORDER BY h",
+    )
+    .bind(address)
+    .bind(network)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn observe(
+    pool: &PgPool,
+    canary: &CanaryRelease,
+    now: DateTime<Utc>,
+) -> Result<StageObservation, sqlx::Error> {
+    let since = stage_started_at(pool, canary.id).await?;
+
+    let rows: Vec<(CanaryVariant, i64, i64, Option<f64>)> = sqlx::query_as(
+        "SELECT variant, requests::int8, errors::int8, p95_response_time_ms::float8
+           FROM canary_metrics
+          WHERE canary_id = $1 AND timestamp >= $2",
+    )
+    .bind(canary.id)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+    let samples: Vec<MetricSample> = rows
+        .into_iter()
+        .map(|(variant, requests, errors, p95)| MetricSample {
+            variant,
+            requests,
+            errors,
+            p95_response_time_ms: p95,
+        })
+        .collect();
+
+    let (address, network): (String, String) =
+        sqlx::query_as("SELECT contract_id, network::text FROM contracts WHERE id = $1")
+            .bind(canary.contract_id)
+            .fetch_one(pool)
+            .await?;
+    let baseline_from = canary.started_at - chrono::Duration::days(EVENT_BASELINE_DAYS);
+    let baseline =
+        hourly_event_counts(pool, &address, &network, baseline_from, canary.started_at).await?;
+    let observed = hourly_event_counts(pool, &address, &network, since, now).await?;
+
+    Ok(StageObservation {
+        canary: canary_engine::summarize(&samples, CanaryVariant::Canary),
+        stable: canary_engine::summarize(&samples, CanaryVariant::Stable),
+        elapsed_minutes: (now - since).num_minutes(),
+        event_anomalies: canary_engine::event_anomalies(
+            &baseline,
+            &observed,
+            canary_engine::EVENT_ANOMALY_Z,
+        ),
+    })
+}
+
+// ─────────────────────────────────────────────────────────
+// Decisions
+// ─────────────────────────────────────────────────────────
+
+#[allow(clippy::too_many_arguments)]
+pub async fn record_decision(
+    conn: &mut PgConnection,
+    canary_id: Uuid,
+    stage: RolloutStage,
+    percentage: i32,
+    kind: CanaryDecisionKind,
+    reason: &str,
+    criteria: &[CanaryCriterionResult],
+    actor: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO canary_decisions (canary_id, stage, percentage, kind, reason, criteria, actor)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(canary_id)
+    .bind(stage)
+    .bind(percentage)
+    .bind(kind)
+    .bind(reason)
+    .bind(serde_json::to_value(criteria).unwrap_or_default())
+    .bind(actor)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Evaluate one active canary and apply the verdict. A hold is logged when
+/// the stage starts waiting on something new, not on every tick.
+pub async fn step(pool: &PgPool, canary: &CanaryRelease) -> Result<Evaluation, sqlx::Error> {
+    let criteria = fetch_criteria(pool, canary.id).await?;
+    let observation = observe(pool, canary, Utc::now()).await?;
+    let evaluation = canary_engine::evaluate(&criteria, &observation);
+
+    match &evaluation.verdict {
+        Verdict::Rollback(reason) => {
+            rollback(
+                pool,
+                canary,
+                CanaryDecisionKind::Rollback,
+                reason,
+                &evaluation.criteria,
+                CONTROLLER_ACTOR,
+            )
+            .await?;
+            tracing::warn!(canary_id = %canary.id, %reason, "canary rolled back");
+        }
+        Verdict::Advance if criteria.auto_advance => {
+            advance(
+                pool,
+                canary,
+                &criteria,
+                CanaryDecisionKind::Advance,
+                "all success criteria met",
+                &evaluation.criteria,
+                CONTROLLER_ACTOR,
+                None,
+            )
+            .await?;
+        }
+        Verdict::Advance => {
+            hold(
+                pool,
+                canary,
+                "all success criteria met; waiting for a manual advance",
+                &evaluation.criteria,
+            )
+            .await?;
+        }
+        Verdict::Hold(reason) => {
+            hold(pool, canary, reason, &evaluation.criteria).await?;
+        }
+    }
+    Ok(evaluation)
+}
+
+async fn hold(
+    pool: &PgPool,
+    canary: &CanaryRelease,
+    reason: &str,
+    criteria: &[CanaryCriterionResult],
+) -> Result<(), sqlx::Error> {
+    let last: Option<(CanaryDecisionKind, RolloutStage, serde_json::Value)> = sqlx::query_as(
+        "SELECT kind, stage, criteria FROM canary_decisions
+          WHERE canary_id = $1
+          ORDER BY created_at DESC, id DESC
+          LIMIT 1",
+    )
+    .bind(canary.id)
+    .fetch_optional(pool)
+    .await?;
+    // Observed values change every tick; only a different set of unmet
+    // criteria is worth a new entry
+    let unmet: Vec<&str> = criteria
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| c.name.as_str())
+        .collect();
+    let already_logged = last.is_some_and(|(kind, stage, logged)| {
+        let logged: Vec<CanaryCriterionResult> = serde_json::from_value(logged).unwrap_or_default();
+        kind == CanaryDecisionKind::Hold
+            && stage == canary.current_stage
+            && logged
+                .iter()
+                .filter(|c| !c.passed)
+                .map(|c| c.name.as_str())
+                .eq(unmet.iter().copied())
+    });
+    if already_logged {
+        return Ok(());
+    }
+    let mut conn = pool.acquire().await?;
+    record_decision(
+        &mut conn,
+        canary.id,
+        canary.current_stage,
+        canary.current_percentage,
+        CanaryDecisionKind::Hold,
+        reason,
+        criteria,
+        CONTROLLER_ACTOR,
+    )
+    .await
+}
+
+// ─────────────────────────────────────────────────────────
+// Transitions
+// ─────────────────────────────────────────────────────────
+
+/// Move to the next stage, or promote the canary deployment after the
+/// last stage. `percentage` overrides the next stage's configured share.
+#[allow(clippy::too_many_arguments)]
+pub async fn advance(
+    pool: &PgPool,
+    canary: &CanaryRelease,
+    criteria: &CanaryCriteria,
+    kind: CanaryDecisionKind,
+    reason: &str,
+    results: &[CanaryCriterionResult],
+    actor: &str,
+    percentage: Option<i32>,
+) -> Result<CanaryRelease, sqlx::Error> {
+    let next = canary_engine::next_stage(canary.current_stage);
+    let promote = next == RolloutStage::Complete;
+    let next_percentage = if promote {
+        100
+    } else {
+        percentage
+            .unwrap_or_else(|| canary_engine::stage_percentage(next, &criteria.stage_percentages))
+    };
+
+    let mut tx = pool.begin().await?;
+    let updated: CanaryRelease = sqlx::query_as(
+        "UPDATE canary_releases
+            SET current_stage = $2, current_percentage = $3, stage_started_at = NOW(),
+                status = CASE WHEN $4 THEN 'completed'::canary_status ELSE status END,
+                completed_at = CASE WHEN $4 THEN NOW() ELSE completed_at END
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(canary.id)
+    .bind(next)
+    .bind(next_percentage)
+    .bind(promote)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO canary_stage_history
+                (canary_id, from_stage, to_stage, from_percentage, to_percentage,
+                 transitioned_by, metrics_at_transition)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(canary.id)
+    .bind(canary.current_stage)
+    .bind(next)
+    .bind(canary.current_percentage)
+    .bind(next_percentage)
+    .bind(actor)
+    .bind(serde_json::to_value(results).unwrap_or_default())
+    .execute(&mut *tx)
+    .await?;
+
+    let (kind, reason) = if promote {
+        (
+            CanaryDecisionKind::Promote,
+            format!("{}; canary deployment promoted to all traffic", reason),
+        )
+    } else {
+        (
+            kind,
+            format!(
+                "{}; {} → {} at {}%",
+                reason, canary.current_stage, next, next_percentage
+            ),
+        )
+    };
+    record_decision(
+        &mut tx,
+        canary.id,
+        next,
+        next_percentage,
+        kind,
+        &reason,
+        results,
+        actor,
+    )
+    .await?;
+
+    if promote {
+        switch_deployments(
+            &mut tx,
+            canary,
+            canary.from_deployment_id,
+            Some(canary.to_deployment_id),
+            actor,
+            false,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    if promote {
+        metrics::CANARY_PROMOTIONS.inc();
+        tracing::info!(canary_id = %canary.id, "canary promoted");
+    }
+    Ok(updated)
+}
+
+/// Return all traffic to the stable deployment and mark the canary
+/// deployment failed.
+pub async fn rollback(
+    pool: &PgPool,
+    canary: &CanaryRelease,
+    kind: CanaryDecisionKind,
+    reason: &str,
+    results: &[CanaryCriterionResult],
+    actor: &str,
+) -> Result<CanaryRelease, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let updated: CanaryRelease = sqlx::query_as(
+        "UPDATE canary_releases
+            SET status = 'rolled_back', current_percentage = 0, completed_at = NOW()
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(canary.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO canary_stage_history
+                (canary_id, from_stage, to_stage, from_percentage, to_percentage,
+                 transitioned_by, metrics_at_transition)
+         VALUES ($1, $2, $2, $3, 0, $4, $5)",
+    )
+    .bind(canary.id)
+    .bind(canary.current_stage)
+    .bind(canary.current_percentage)
+    .bind(actor)
+    .bind(serde_json::to_value(results).unwrap_or_default())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE contract_deployments SET status = 'failed', error_message = $2 WHERE id = $1",
+    )
+    .bind(canary.to_deployment_id)
+    .bind(reason)
+    .execute(&mut *tx)
+    .await?;
+
+    let stable = match canary.from_deployment_id {
+        Some(id) => format!("stable deployment {}", id),
+        None => "the previous build".to_string(),
+    };
+    record_decision(
+        &mut tx,
+        canary.id,
+        canary.current_stage,
+        0,
+        kind,
+        &format!("{}; traffic returned to {}", reason, stable),
+        results,
+        actor,
+    )
+    .await?;
+
+    switch_deployments(
+        &mut tx,
+        canary,
+        Some(canary.to_deployment_id),
+        canary.from_deployment_id,
+        actor,
+        true,
+    )
+    .await?;
+
+    tx.commit().await?;
+    metrics::CANARY_ROLLBACKS.inc();
+    Ok(updated)
+}
+
+/// Activate `to` and deactivate `from`, recording a blue/green switch when
+/// both deployments exist.
+async fn switch_deployments(
+    conn: &mut PgConnection,
+    canary: &CanaryRelease,
+    from: Option<Uuid>,
+    to: Option<Uuid>,
+    actor: &str,
+    rollback: bool,
+) -> Result<(), sqlx::Error> {
+    if let (Some(from), false) = (from, rollback) {
+        sqlx::query("UPDATE contract_deployments SET status = 'inactive' WHERE id = $1")
+            .bind(from)
+            .execute(&mut *conn)
+            .await?;
+    }
+    if let Some(to) = to {
+        sqlx::query(
+            "UPDATE contract_deployments
+                SET status = 'active', activated_at = COALESCE(activated_at, NOW()),
+                    error_message = NULL
+              WHERE id = $1",
+        )
+        .bind(to)
+        .execute(&mut *conn)
+        .await?;
+    }
+    if let (Some(from), Some(to)) = (from, to) {
+        sqlx::query(
+            "INSERT INTO deployment_switches
+                    (contract_id, from_environment, to_environment, switched_by, rollback)
+             SELECT $1, f.environment, t.environment, $4, $5
+               FROM contract_deployments f, contract_deployments t
+              WHERE f.id = $2 AND t.id = $3 AND f.environment <> t.environment",
+        )
+        .bind(canary.contract_id)
+        .bind(from)
+        .bind(to)
+        .bind(actor)
+        .bind(rollback)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,382 @@
+// api/src/canary_engine.rs
+//
+// Decision rules for canary releases: summarising recorded metrics,
+// detecting event anomalies and judging a stage against the release's
+// success criteria. canary_controller.rs gathers the observations and
+// applies the resulting transitions; everything in here is synchronous.
+
+use shared::{CanaryCriteria, CanaryCriterionResult, CanaryVariant, RolloutStage};
+
+/// Event buckets further than this many standard deviations from the
+/// baseline mean count as anomalies
+pub const EVENT_ANOMALY_Z: f64 = 3.0;
+
+// ─────────────────────────────────────────────────────────
+// Stages
+// ─────────────────────────────────────────────────────────
+
+pub const DEFAULT_STAGE_PERCENTAGES: [i32; 4] = [1, 10, 50, 100];
+
+pub fn validate_stage_percentages(percentages: &[i32]) -> Result<(), String> {
+    if percentages.len() != 4 {
+        return Err("exactly four stage percentages are required".into());
+    }
+    if percentages.iter().any(|p| !(1..=100).contains(p)) {
+        return Err("stage percentages must be between 1 and 100".into());
+    }
+    if percentages.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("stage percentages must be strictly increasing".into());
+    }
+    Ok(())
+}
+
+pub fn next_stage(stage: RolloutStage) -> RolloutStage {
+    match stage {
+        RolloutStage::Stage1 => RolloutStage::Stage2,
+        RolloutStage::Stage2 => RolloutStage::Stage3,
+        RolloutStage::Stage3 => RolloutStage::Stage4,
+        RolloutStage::Stage4 | RolloutStage::Complete => RolloutStage::Complete,
+    }
+}
+
+/// Traffic share routed to the canary in a stage; a completed release
+/// serves all traffic.
+pub fn stage_percentage(stage: RolloutStage, percentages: &[i32]) -> i32 {
+    let index = match stage {
+        RolloutStage::Stage1 => 0,
+        RolloutStage::Stage2 => 1,
+        RolloutStage::Stage3 => 2,
+        RolloutStage::Stage4 => 3,
+        RolloutStage::Complete => return 100,
+    };
+    percentages
+        .get(index)
+        .copied()
+        .unwrap_or(DEFAULT_STAGE_PERCENTAGES[index])
+}
+
+// ─────────────────────────────────────────────────────────
+// Observations
+// ─────────────────────────────────────────────────────────
+
+/// One recorded metric row
+#[derive(Debug, Clone)]
+pub struct MetricSample {
+    pub variant: CanaryVariant,
+    pub requests: i64,
+    pub errors: i64,
+    pub p95_response_time_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariantSummary {
+    pub requests: i64,
+    pub errors: i64,
+    /// Percent; `None` without requests
+    pub error_rate: Option<f64>,
+    /// Request-weighted mean of the recorded p95 latencies
+    pub p95_latency_ms: Option<f64>,
+}
+
+pub fn summarize(samples: &[MetricSample], variant: CanaryVariant) -> VariantSummary {
+    let mut summary = VariantSummary::default();
+    let (mut weighted_latency, mut latency_weight) = (0.0, 0i64);
+    for s in samples.iter().filter(|s| s.variant == variant) {
+        summary.requests += s.requests;
+        summary.errors += s.errors;
+        if let Some(p95) = s.p95_response_time_ms {
+            weighted_latency += p95 * s.requests.max(1) as f64;
+            latency_weight += s.requests.max(1);
+        }
+    }
+    if summary.requests > 0 {
+        summary.error_rate = Some(summary.errors as f64 / summary.requests as f64 * 100.0);
+    }
+    if latency_weight > 0 {
+        summary.p95_latency_ms = Some(weighted_latency / latency_weight as f64);
+    }
+    summary
+}
+
+/// Canary p95 latency relative to stable, as a percentage increase.
+pub fn latency_increase_pct(canary: &VariantSummary, stable: &VariantSummary) -> Option<f64> {
+    match (canary.p95_latency_ms, stable.p95_latency_ms) {
+        (Some(c), Some(s)) if s > 0.0 => Some((c - s) / s * 100.0),
+        _ => None,
+    }
+}
+
+/// Number of observed event-count buckets (e.g. per hour) that deviate
+/// from the baseline by more than `z` standard deviations, in either
+/// direction. The deviation is floored at the Poisson noise of the mean so
+/// a perfectly flat baseline does not flag every small wobble.
+pub fn event_anomalies(baseline: &[i64], observed: &[i64], z: f64) -> i64 {
+    if baseline.len() < 2 {
+        return 0;
+    }
+    let n = baseline.len() as f64;
+    let mean = baseline.iter().sum::<i64>() as f64 / n;
+    let variance = baseline
+        .iter()
+        .map(|x| (*x as f64 - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    let deviation = variance.sqrt().max(mean.sqrt()).max(1.0);
+    observed
+        .iter()
+        .filter(|x| (**x as f64 - mean).abs() > z * deviation)
+        .count() as i64
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StageObservation {
+    pub canary: VariantSummary,
+    pub stable: VariantSummary,
+    pub elapsed_minutes: i64,
+    pub event_anomalies: i64,
+}
+
+// ─────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// The stage met every criterion
+    Advance,
+    /// Not enough traffic or time yet
+    Hold(String),
+    /// A criterion failed; return to the stable deployment
+    Rollback(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub verdict: Verdict,
+    pub criteria: Vec<CanaryCriterionResult>,
+}
+
+/// Judge the current stage. Error rate and latency are only judged once
+/// the stage has seen `min_stage_requests` canary requests, so a handful of
+/// early failures at 1% traffic cannot roll a release back on their own.
+/// Event anomalies roll back as soon as they exceed the limit.
+pub fn evaluate(criteria: &CanaryCriteria, obs: &StageObservation) -> Evaluation {
+    let latency = latency_increase_pct(&obs.canary, &obs.stable);
+    let results = vec![
+        CanaryCriterionResult {
+            name: "error_rate".into(),
+            observed: obs.canary.error_rate,
+            threshold: criteria.error_rate_threshold,
+            passed: !obs
+                .canary
+                .error_rate
+                .is_some_and(|r| r > criteria.error_rate_threshold),
+        },
+        CanaryCriterionResult {
+            name: "latency_increase_pct".into(),
+            observed: latency,
+            threshold: criteria.max_latency_increase_pct,
+            passed: !latency.is_some_and(|l| l > criteria.max_latency_increase_pct),
+        },
+        CanaryCriterionResult {
+            name: "event_anomalies".into(),
+            observed: Some(obs.event_anomalies as f64),
+            threshold: criteria.max_event_anomalies as f64,
+            passed: obs.event_anomalies <= criteria.max_event_anomalies as i64,
+        },
+        CanaryCriterionResult {
+            name: "stage_requests".into(),
+            observed: Some(obs.canary.requests as f64),
+            threshold: criteria.min_stage_requests as f64,
+            passed: obs.canary.requests >= criteria.min_stage_requests as i64,
+        },
+        CanaryCriterionResult {
+            name: "stage_minutes".into(),
+            observed: Some(obs.elapsed_minutes as f64),
+            threshold: criteria.min_stage_minutes as f64,
+            passed: obs.elapsed_minutes >= criteria.min_stage_minutes as i64,
+        },
+    ];
+
+    let enough_traffic = obs.canary.requests >= criteria.min_stage_requests as i64;
+    let mut failures = Vec::new();
+    if obs.event_anomalies > criteria.max_event_anomalies as i64 {
+        failures.push(format!(
+            "{} event anomal{} (limit {})",
+            obs.event_anomalies,
+            if obs.event_anomalies == 1 { "y" } else { "ies" },
+            criteria.max_event_anomalies
+        ));
+    }
+    if enough_traffic {
+        if let Some(rate) = obs
+            .canary
+            .error_rate
+            .filter(|r| *r > criteria.error_rate_threshold)
+        {
+            failures.push(format!(
+                "error rate {:.2}% exceeds {:.2}%",
+                rate, criteria.error_rate_threshold
+            ));
+        }
+        if let Some(increase) = latency.filter(|l| *l > criteria.max_latency_increase_pct) {
+            failures.push(format!(
+                "p95 latency {:.0}% above stable (limit {:.0}%)",
+                increase, criteria.max_latency_increase_pct
+            ));
+        }
+    }
+
+    let verdict = if !failures.is_empty() {
+        Verdict::Rollback(failures.join("; "))
+    } else if !enough_traffic {
+        Verdict::Hold(format!(
+            "{} of {} canary requests observed",
+            obs.canary.requests, criteria.min_stage_requests
+        ))
+    } else if obs.elapsed_minutes < criteria.min_stage_minutes as i64 {
+        Verdict::Hold(format!(
+            "stage has run {} of {} minutes",
+            obs.elapsed_minutes, criteria.min_stage_minutes
+        ))
+    } else {
+        Verdict::Advance
+    };
+
+    Evaluation {
+        verdict,
+        criteria: results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criteria() -> CanaryCriteria {
+        CanaryCriteria {
+            error_rate_threshold: 5.0,
+            max_latency_increase_pct: 20.0,
+            max_event_anomalies: 0,
+            min_stage_minutes: 30,
+            min_stage_requests: 100,
+            stage_percentages: DEFAULT_STAGE_PERCENTAGES.to_vec(),
+            auto_advance: true,
+        }
+    }
+
+    fn sample(variant: CanaryVariant, requests: i64, errors: i64, p95: f64) -> MetricSample {
+        MetricSample {
+            variant,
+            requests,
+            errors,
+            p95_response_time_ms: Some(p95),
+        }
+    }
+
+    fn observation(requests: i64, errors: i64, canary_p95: f64, minutes: i64) -> StageObservation {
+        let samples = [
+            sample(CanaryVariant::Canary, requests, errors, canary_p95),
+            sample(CanaryVariant::Stable, 10_000, 50, 100.0),
+        ];
+        StageObservation {
+            canary: summarize(&samples, CanaryVariant::Canary),
+            stable: summarize(&samples, CanaryVariant::Stable),
+            elapsed_minutes: minutes,
+            event_anomalies: 0,
+        }
+    }
+
+    #[test]
+    fn stages_progress_to_complete() {
+        assert_eq!(next_stage(RolloutStage::Stage1), RolloutStage::Stage2);
+        assert_eq!(next_stage(RolloutStage::Stage4), RolloutStage::Complete);
+        assert_eq!(next_stage(RolloutStage::Complete), RolloutStage::Complete);
+        assert_eq!(stage_percentage(RolloutStage::Stage2, &[5, 20, 60, 90]), 20);
+        assert_eq!(
+            stage_percentage(RolloutStage::Complete, &[5, 20, 60, 90]),
+            100
+        );
+        assert!(validate_stage_percentages(&[1, 10, 50, 100]).is_ok());
+        assert!(validate_stage_percentages(&[1, 10, 100]).is_err());
+        assert!(validate_stage_percentages(&[10, 10, 50, 100]).is_err());
+        assert!(validate_stage_percentages(&[0, 10, 50, 100]).is_err());
+    }
+
+    #[test]
+    fn summaries_weight_latency_by_requests() {
+        let samples = [
+            sample(CanaryVariant::Canary, 100, 2, 100.0),
+            sample(CanaryVariant::Canary, 300, 2, 200.0),
+            sample(CanaryVariant::Stable, 1000, 0, 80.0),
+        ];
+        let canary = summarize(&samples, CanaryVariant::Canary);
+        assert_eq!(canary.requests, 400);
+        assert_eq!(canary.error_rate, Some(1.0));
+        assert_eq!(canary.p95_latency_ms, Some(175.0));
+        let stable = summarize(&samples, CanaryVariant::Stable);
+        assert_eq!(stable.error_rate, Some(0.0));
+        assert_eq!(
+            latency_increase_pct(&canary, &stable).map(|l| l.round()),
+            Some(119.0)
+        );
+        assert_eq!(
+            summarize(&[], CanaryVariant::Canary),
+            VariantSummary::default()
+        );
+    }
+
+    #[test]
+    fn event_anomalies_flag_spikes_and_drops() {
+        let baseline = [100, 110, 90, 105, 95, 100];
+        assert_eq!(event_anomalies(&baseline, &[102, 98], EVENT_ANOMALY_Z), 0);
+        assert_eq!(
+            event_anomalies(&baseline, &[400, 101, 5], EVENT_ANOMALY_Z),
+            2
+        );
+        // Flat baselines fall back to Poisson noise
+        assert_eq!(event_anomalies(&[4, 4, 4], &[7, 12], EVENT_ANOMALY_Z), 1);
+        assert_eq!(event_anomalies(&[4], &[400], EVENT_ANOMALY_Z), 0);
+    }
+
+    #[test]
+    fn healthy_stages_advance_once_traffic_and_time_suffice() {
+        let c = criteria();
+        assert_eq!(
+            evaluate(&c, &observation(500, 5, 110.0, 45)).verdict,
+            Verdict::Advance
+        );
+        assert_eq!(
+            evaluate(&c, &observation(40, 0, 110.0, 45)).verdict,
+            Verdict::Hold("40 of 100 canary requests observed".into())
+        );
+        assert_eq!(
+            evaluate(&c, &observation(500, 5, 110.0, 10)).verdict,
+            Verdict::Hold("stage has run 10 of 30 minutes".into())
+        );
+    }
+
+    #[test]
+    fn regressions_roll_back_with_every_failure_listed() {
+        let c = criteria();
+        let eval = evaluate(&c, &observation(200, 20, 150.0, 5));
+        assert_eq!(
+            eval.verdict,
+            Verdict::Rollback(
+                "error rate 10.00% exceeds 5.00%; p95 latency 50% above stable (limit 20%)".into()
+            )
+        );
+        assert!(!eval.criteria[0].passed);
+        assert!(!eval.criteria[1].passed);
+        assert!(eval.criteria[2].passed);
+
+        // Too little traffic to judge error rate, but anomalies still count
+        let mut obs = observation(10, 5, 100.0, 5);
+        assert!(matches!(evaluate(&c, &obs).verdict, Verdict::Hold(_)));
+        obs.event_anomalies = 2;
+        assert_eq!(
+            evaluate(&c, &obs).verdict,
+            Verdict::Rollback("2 event anomalies (limit 0)".into())
+        );
+    }
+}
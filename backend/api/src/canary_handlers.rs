@@ -0,0 +1,552 @@
+// api/src/canary_handlers.rs
+//
+// Axum handlers for canary releases. Stage progression, promotion and
+// automatic rollback run in the background controller
+// (canary_controller.rs); these handlers start releases, ingest metrics
+// and expose operator overrides, each of which lands in the decision log.
+//
+// Routes (register in canary_routes.rs):
+//   POST   /api/canaries                   → create_canary
+//   GET    /api/canaries/:id               → get_canary
+//   POST   /api/canaries/:id/metrics       → record_canary_metric
+//   POST   /api/canaries/:id/evaluate      → evaluate_canary
+//   POST   /api/canaries/:id/advance       → advance_canary
+//   POST   /api/canaries/:id/rollback      → rollback_canary
+//   POST   /api/canaries/:id/pause         → pause_canary
+//   POST   /api/canaries/:id/resume        → resume_canary
+//   GET    /api/contracts/:id/canaries     → list_contract_canaries
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use shared::{
+    AdvanceCanaryRequest, CanaryActionRequest, CanaryDecision, CanaryDecisionKind, CanaryDetail,
+    CanaryEvaluation, CanaryMetric, CanaryRelease, CanaryStageTransition, CanaryStatus,
+    CanaryVariant, CreateCanaryRequest, DeploymentStatus, RecordCanaryMetricRequest,
+};
+
+use crate::{
+    canary_controller,
+    canary_engine::{self, Verdict, DEFAULT_STAGE_PERCENTAGES},
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+
+fn db_err(op: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = op, error = ?err, "canary database error");
+    ApiError::internal(format!("Database error while trying to {}", op))
+}
+
+fn parse_id(value: &str, code: &str, what: &str) -> ApiResult<Uuid> {
+    Uuid::parse_str(value)
+        .map_err(|_| ApiError::bad_request(code, format!("Invalid {}: {}", what, value)))
+}
+
+async fn fetch_canary(state: &AppState, canary_id: Uuid) -> ApiResult<CanaryRelease> {
+    sqlx::query_as("SELECT * FROM canary_releases WHERE id = $1")
+        .bind(canary_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("fetch canary", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "CanaryNotFound",
+                format!("No canary release found with ID: {}", canary_id),
+            )
+        })
+}
+
+fn require_status(canary: &CanaryRelease, allowed: &[CanaryStatus], action: &str) -> ApiResult<()> {
+    if allowed.contains(&canary.status) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        StatusCode::CONFLICT,
+        "InvalidCanaryState",
+        format!("Cannot {} a canary that is {:?}", action, canary.status).to_lowercase(),
+    ))
+}
+
+fn validate_criteria(req: &CreateCanaryRequest) -> ApiResult<()> {
+    if let Some(rate) = req.error_rate_threshold {
+        if !(0.0..=100.0).contains(&rate) {
+            return Err(ApiError::bad_request(
+                "InvalidCriteria",
+                "error_rate_threshold must be between 0 and 100",
+            ));
+        }
+    }
+    if req.max_latency_increase_pct.is_some_and(|l| l < 0.0) {
+        return Err(ApiError::bad_request(
+            "InvalidCriteria",
+            "max_latency_increase_pct must not be negative",
+        ));
+    }
+    let negative = [
+        req.max_event_anomalies,
+        req.min_stage_minutes,
+        req.min_stage_requests,
+    ]
+    .iter()
+    .any(|v| v.is_some_and(|v| v < 0));
+    if negative {
+        return Err(ApiError::bad_request(
+            "InvalidCriteria",
+            "max_event_anomalies, min_stage_minutes and min_stage_requests must not be negative",
+        ));
+    }
+    if let Some(percentages) = &req.stage_percentages {
+        canary_engine::validate_stage_percentages(percentages)
+            .map_err(|msg| ApiError::bad_request("InvalidStagePercentages", msg))?;
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries
+// ─────────────────────────────────────────────────────────
+
+pub async fn create_canary(
+    State(state): State<AppState>,
+    Json(req): Json<CreateCanaryRequest>,
+) -> ApiResult<(StatusCode, Json<CanaryRelease>)> {
+    let contract_id = parse_id(&req.contract_id, "InvalidContractId", "contract ID")?;
+    let to_deployment_id = parse_id(
+        &req.to_deployment_id,
+        "InvalidDeploymentId",
+        "deployment ID",
+    )?;
+    validate_criteria(&req)?;
+
+    let target: Option<(Uuid, DeploymentStatus)> =
+        sqlx::query_as("SELECT contract_id, status FROM contract_deployments WHERE id = $1")
+            .bind(to_deployment_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| db_err("fetch canary deployment", e))?;
+    match target {
+        Some((owner, _)) if owner != contract_id => {
+            return Err(ApiError::bad_request(
+                "DeploymentMismatch",
+                "to_deployment_id belongs to a different contract",
+            ))
+        }
+        Some((_, DeploymentStatus::Active)) => {
+            return Err(ApiError::bad_request(
+                "DeploymentAlreadyActive",
+                "to_deployment_id is already serving all traffic",
+            ))
+        }
+        Some(_) => {}
+        None => {
+            return Err(ApiError::not_found(
+                "DeploymentNotFound",
+                format!("No deployment found with ID: {}", to_deployment_id),
+            ))
+        }
+    }
+
+    let from_deployment_id: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM contract_deployments WHERE contract_id = $1 AND status = 'active'",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch stable deployment", e))?;
+
+    let percentages = req
+        .stage_percentages
+        .clone()
+        .unwrap_or_else(|| DEFAULT_STAGE_PERCENTAGES.to_vec());
+    let created_by = req.created_by.clone().unwrap_or_else(|| "api".to_string());
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+    let canary: CanaryRelease = sqlx::query_as(
+        "INSERT INTO canary_releases
+                (contract_id, from_deployment_id, to_deployment_id, status, current_stage,
+                 current_percentage, error_rate_threshold, max_latency_increase_pct,
+                 max_event_anomalies, min_stage_minutes, min_stage_requests,
+                 stage_percentages, auto_advance, created_by)
+         VALUES ($1, $2, $3, 'active', 'stage_1', $4, $5::numeric, $6, $7, $8, $9, $10, $11, $12)
+         RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(from_deployment_id)
+    .bind(to_deployment_id)
+    .bind(percentages[0])
+    .bind(req.error_rate_threshold.unwrap_or(5.0))
+    .bind(req.max_latency_increase_pct.unwrap_or(20.0))
+    .bind(req.max_event_anomalies.unwrap_or(0))
+    .bind(req.min_stage_minutes.unwrap_or(30))
+    .bind(req.min_stage_requests.unwrap_or(100))
+    .bind(&percentages)
+    .bind(req.auto_advance.unwrap_or(true))
+    .bind(&created_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+            StatusCode::CONFLICT,
+            "CanaryAlreadyLive",
+            "This contract already has a canary release in progress",
+        ),
+        _ => db_err("create canary", e),
+    })?;
+
+    sqlx::query("UPDATE contract_deployments SET status = 'testing' WHERE id = $1")
+        .bind(to_deployment_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("mark canary deployment", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit transaction", e))?;
+
+    Ok((StatusCode::CREATED, Json(canary)))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/canaries/:id
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<CanaryDetail>> {
+    let canary = fetch_canary(&state, id).await?;
+    let criteria = canary_controller::fetch_criteria(&state.db, id)
+        .await
+        .map_err(|e| db_err("fetch canary criteria", e))?;
+    let stage_started_at = canary_controller::stage_started_at(&state.db, id)
+        .await
+        .map_err(|e| db_err("fetch canary stage", e))?;
+
+    let transitions: Vec<CanaryStageTransition> = sqlx::query_as(
+        "SELECT * FROM canary_stage_history WHERE canary_id = $1 ORDER BY transitioned_at",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list canary transitions", e))?;
+
+    let decisions: Vec<CanaryDecision> = sqlx::query_as(
+        "SELECT * FROM canary_decisions WHERE canary_id = $1 ORDER BY created_at, id",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list canary decisions", e))?;
+
+    Ok(Json(CanaryDetail {
+        canary,
+        criteria,
+        stage_started_at,
+        transitions,
+        decisions,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/canaries
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_contract_canaries(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<Vec<CanaryRelease>>> {
+    let canaries: Vec<CanaryRelease> = sqlx::query_as(
+        "SELECT * FROM canary_releases WHERE contract_id = $1 ORDER BY started_at DESC",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list canaries", e))?;
+    Ok(Json(canaries))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries/:id/metrics
+// ─────────────────────────────────────────────────────────
+
+pub async fn record_canary_metric(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RecordCanaryMetricRequest>,
+) -> ApiResult<(StatusCode, Json<CanaryMetric>)> {
+    if req.requests < 0 || req.errors < 0 || req.errors > req.requests {
+        return Err(ApiError::bad_request(
+            "InvalidMetric",
+            "requests and errors must be non-negative and errors cannot exceed requests",
+        ));
+    }
+    let canary = fetch_canary(&state, id).await?;
+    require_status(
+        &canary,
+        &[CanaryStatus::Active, CanaryStatus::Paused],
+        "record metrics for",
+    )?;
+
+    let error_rate = if req.requests > 0 {
+        req.errors as f64 * 100.0 / req.requests as f64
+    } else {
+        0.0
+    };
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+    let metric: CanaryMetric = sqlx::query_as(
+        "INSERT INTO canary_metrics
+                (canary_id, requests, errors, error_rate, avg_response_time_ms,
+                 p95_response_time_ms, p99_response_time_ms, variant)
+         VALUES ($1, $2, $3, $4::numeric, $5::numeric, $6::numeric, $7::numeric, $8)
+         RETURNING id, canary_id, timestamp, requests, errors, error_rate,
+                   avg_response_time_ms, p95_response_time_ms, p99_response_time_ms",
+    )
+    .bind(id)
+    .bind(req.requests)
+    .bind(req.errors)
+    .bind(error_rate)
+    .bind(req.avg_response_time_ms)
+    .bind(req.p95_response_time_ms)
+    .bind(req.p99_response_time_ms)
+    .bind(req.variant)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("record canary metric", e))?;
+
+    if req.variant == CanaryVariant::Canary {
+        sqlx::query(
+            "UPDATE canary_releases
+                SET total_requests = COALESCE(total_requests, 0) + $2,
+                    error_count = COALESCE(error_count, 0) + $3,
+                    current_error_rate = ROUND(
+                        (COALESCE(error_count, 0) + $3)::numeric * 100
+                        / GREATEST(COALESCE(total_requests, 0) + $2, 1), 2)
+              WHERE id = $1",
+        )
+        .bind(id)
+        .bind(req.requests)
+        .bind(req.errors)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("update canary totals", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit transaction", e))?;
+
+    Ok((StatusCode::CREATED, Json(metric)))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries/:id/evaluate
+// ─────────────────────────────────────────────────────────
+
+/// Run the controller for this canary now instead of waiting for its next
+/// tick. The verdict is applied exactly as the controller would apply it.
+pub async fn evaluate_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<CanaryEvaluation>> {
+    let canary = fetch_canary(&state, id).await?;
+    require_status(&canary, &[CanaryStatus::Active], "evaluate")?;
+
+    let evaluation = canary_controller::step(&state.db, &canary)
+        .await
+        .map_err(|e| db_err("evaluate canary", e))?;
+    let (verdict, reason) = match evaluation.verdict {
+        Verdict::Advance => ("advance", None),
+        Verdict::Hold(reason) => ("hold", Some(reason)),
+        Verdict::Rollback(reason) => ("rollback", Some(reason)),
+    };
+
+    Ok(Json(CanaryEvaluation {
+        verdict: verdict.to_string(),
+        reason,
+        criteria: evaluation.criteria,
+        canary: fetch_canary(&state, id).await?,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries/:id/advance
+// ─────────────────────────────────────────────────────────
+
+/// Advance regardless of the success criteria. The criteria as they stood
+/// are still recorded with the decision.
+pub async fn advance_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<AdvanceCanaryRequest>,
+) -> ApiResult<Json<CanaryRelease>> {
+    let canary = fetch_canary(&state, id).await?;
+    require_status(&canary, &[CanaryStatus::Active], "advance")?;
+    if let Some(pct) = req.target_percentage {
+        if pct <= canary.current_percentage || pct > 100 {
+            return Err(ApiError::bad_request(
+                "InvalidTargetPercentage",
+                format!(
+                    "target_percentage must be above the current {}% and at most 100",
+                    canary.current_percentage
+                ),
+            ));
+        }
+    }
+
+    let criteria = canary_controller::fetch_criteria(&state.db, id)
+        .await
+        .map_err(|e| db_err("fetch canary criteria", e))?;
+    let observation = canary_controller::observe(&state.db, &canary, Utc::now())
+        .await
+        .map_err(|e| db_err("observe canary", e))?;
+    let evaluation = canary_engine::evaluate(&criteria, &observation);
+    let actor = req.advanced_by.unwrap_or_else(|| "api".to_string());
+
+    let updated = canary_controller::advance(
+        &state.db,
+        &canary,
+        &criteria,
+        CanaryDecisionKind::ManualAdvance,
+        &format!("advanced manually by {}", actor),
+        &evaluation.criteria,
+        &actor,
+        req.target_percentage,
+    )
+    .await
+    .map_err(|e| db_err("advance canary", e))?;
+    Ok(Json(updated))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries/:id/rollback
+// ─────────────────────────────────────────────────────────
+
+pub async fn rollback_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CanaryActionRequest>,
+) -> ApiResult<Json<CanaryRelease>> {
+    let canary = fetch_canary(&state, id).await?;
+    require_status(
+        &canary,
+        &[CanaryStatus::Active, CanaryStatus::Paused],
+        "roll back",
+    )?;
+    let actor = req.actor.unwrap_or_else(|| "api".to_string());
+    let reason = req
+        .reason
+        .unwrap_or_else(|| format!("rolled back manually by {}", actor));
+
+    let updated = canary_controller::rollback(
+        &state.db,
+        &canary,
+        CanaryDecisionKind::ManualRollback,
+        &reason,
+        &[],
+        &actor,
+    )
+    .await
+    .map_err(|e| db_err("roll back canary", e))?;
+    Ok(Json(updated))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/canaries/:id/pause
+// POST /api/canaries/:id/resume
+// ─────────────────────────────────────────────────────────
+
+pub async fn pause_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CanaryActionRequest>,
+) -> ApiResult<Json<CanaryRelease>> {
+    set_paused(state, id, req, true).await
+}
+
+/// Resuming restarts the stage clock so the stage is judged only on what
+/// happens after the pause.
+pub async fn resume_canary(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CanaryActionRequest>,
+) -> ApiResult<Json<CanaryRelease>> {
+    set_paused(state, id, req, false).await
+}
+
+async fn set_paused(
+    state: AppState,
+    id: Uuid,
+    req: CanaryActionRequest,
+    pause: bool,
+) -> ApiResult<Json<CanaryRelease>> {
+    let canary = fetch_canary(&state, id).await?;
+    let (from, to, kind, verb) = if pause {
+        (
+            CanaryStatus::Active,
+            CanaryStatus::Paused,
+            CanaryDecisionKind::Pause,
+            "paused",
+        )
+    } else {
+        (
+            CanaryStatus::Paused,
+            CanaryStatus::Active,
+            CanaryDecisionKind::Resume,
+            "resumed",
+        )
+    };
+    require_status(&canary, &[from], if pause { "pause" } else { "resume" })?;
+    let actor = req.actor.unwrap_or_else(|| "api".to_string());
+    let reason = match req.reason {
+        Some(reason) => format!("{} by {}: {}", verb, actor, reason),
+        None => format!("{} by {}", verb, actor),
+    };
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+    let updated: CanaryRelease = sqlx::query_as(
+        "UPDATE canary_releases
+            SET status = $2,
+                stage_started_at = CASE WHEN $3 THEN stage_started_at ELSE NOW() END
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(id)
+    .bind(to)
+    .bind(pause)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("update canary status", e))?;
+    canary_controller::record_decision(
+        &mut tx,
+        id,
+        canary.current_stage,
+        canary.current_percentage,
+        kind,
+        &reason,
+        &[],
+        &actor,
+    )
+    .await
+    .map_err(|e| db_err("record canary decision", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit transaction", e))?;
+
+    Ok(Json(updated))
+}
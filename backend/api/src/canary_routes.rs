@@ -0,0 +1,43 @@
+// canary_routes.rs
+// Route definitions for canary releases and their decision log
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{canary_handlers, state::AppState};
+
+pub fn canary_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/canaries", post(canary_handlers::create_canary))
+        .route("/api/canaries/:id", get(canary_handlers::get_canary))
+        .route(
+            "/api/canaries/:id/metrics",
+            post(canary_handlers::record_canary_metric),
+        )
+        .route(
+            "/api/canaries/:id/evaluate",
+            post(canary_handlers::evaluate_canary),
+        )
+        .route(
+            "/api/canaries/:id/advance",
+            post(canary_handlers::advance_canary),
+        )
+        .route(
+            "/api/canaries/:id/rollback",
+            post(canary_handlers::rollback_canary),
+        )
+        .route(
+            "/api/canaries/:id/pause",
+            post(canary_handlers::pause_canary),
+        )
+        .route(
+            "/api/canaries/:id/resume",
+            post(canary_handlers::resume_canary),
+        )
+        .route(
+            "/api/contracts/:id/canaries",
+            get(canary_handlers::list_contract_canaries),
+        )
+}
@@ -3,12 +3,14 @@
 // Pure capacity-planning computation: forecasting, alert generation,
 // recommendation generation, and cost estimation.
 // All functions are synchronous — handlers call these after fetching DB data.
+// History-driven models (Holt-Winters, changepoints) live in
+// capacity_forecast.rs and are attached to a bundle as `model_forecast`.
 
 use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
 use shared::{
-    AlertSeverity, CapacityAlert, CostEstimate, ForecastPoint, GrowthScenario,
+    CapacityAlert, CapacityAlertSeverity, CapacityCostEstimate, ForecastPoint, GrowthScenario,
     ImplementationEffort, RecommendationKind, ResourceForecast, ResourceKind,
     ResourceLimits, ResourceSnapshot, ScalingRecommendation, ScenarioBundle,
 };
@@ -99,7 +101,10 @@ pub fn build_scenario_bundle(
         &GrowthScenario::Custom { monthly_rate: r }, horizon_months, now,
     ));
 
-    ScenarioBundle { resource, current_value, limit, conservative, base, aggressive, custom }
+    ScenarioBundle {
+        resource, current_value, limit, conservative, base, aggressive, custom,
+        model_forecast: None,
+    }
 }
 
 /// Derive the current value for a resource from a slice of snapshots.
@@ -125,15 +130,45 @@ const WARN_PCT: f64  = 60.0;
 /// Emit Critical when breach is predicted within this many days (30-day window).
 const CRITICAL_DAYS: i64 = 30;
 
+/// Days until a breach, as used for alerting.
+///
+/// With enough history this is the day the **upper** bound of the model
+/// forecast crosses the limit, so a plausible breach is flagged before the
+/// point forecast gets there. Otherwise it falls back to the **base**
+/// scenario (conservative enough to avoid noise, aggressive enough to give
+/// 30-day lead time per the spec).
+pub fn breach_outlook(bundle: &ScenarioBundle) -> Option<i64> {
+    match &bundle.model_forecast {
+        Some(model) => model.earliest_breach_days,
+        None        => bundle.base.days_until_breach,
+    }
+}
+
+fn outlook_basis(bundle: &ScenarioBundle) -> String {
+    match &bundle.model_forecast {
+        Some(model) => {
+            let likely = match (model.days_until_breach, model.latest_breach_days) {
+                (Some(point), Some(latest)) => format!(", most likely ~{} days, by ~{} days", point, latest),
+                (Some(point), None)         => format!(", most likely ~{} days", point),
+                _                           => String::new(),
+            };
+            format!(
+                "the {} forecast ({:.0}% interval{})",
+                model.model, model.interval_level * 100.0, likely
+            )
+        }
+        None => "base growth".into(),
+    }
+}
+
 /// Evaluate one scenario bundle and produce zero or one alert.
-/// Uses the **base** scenario for alert timing (conservative enough to avoid
-/// noise, aggressive enough to give 30-day lead time per the spec).
+/// Breach timing comes from [`breach_outlook`].
 pub fn evaluate_alert(
     contract_id: Uuid,
     bundle: &ScenarioBundle,
     now: DateTime<Utc>,
 ) -> Option<CapacityAlert> {
-    let base = &bundle.base;
+    let outlook = breach_outlook(bundle);
     let pct  = if bundle.limit > 0.0 {
         (bundle.current_value / bundle.limit) * 100.0
     } else {
@@ -141,39 +176,44 @@ pub fn evaluate_alert(
     };
 
     let severity = if bundle.current_value > bundle.limit {
-        Some(AlertSeverity::Breached)
-    } else if let Some(days) = base.days_until_breach {
+        Some(CapacityAlertSeverity::Breached)
+    } else if let Some(days) = outlook {
         if days <= CRITICAL_DAYS {
-            Some(AlertSeverity::Critical)
+            Some(CapacityAlertSeverity::Critical)
         } else if pct >= WARN_PCT {
-            Some(AlertSeverity::Warning)
+            Some(CapacityAlertSeverity::Warning)
         } else {
             None
         }
     } else if pct >= WARN_PCT {
-        Some(AlertSeverity::Warning)
+        Some(CapacityAlertSeverity::Warning)
     } else {
         None
     };
 
     severity.map(|sev| {
         let message = match &sev {
-            AlertSeverity::Breached  => format!(
+            CapacityAlertSeverity::Breached  => format!(
                 "{} has EXCEEDED its limit ({:.0} / {:.0}, {:.1}% consumed).",
                 bundle.resource, bundle.current_value, bundle.limit, pct
             ),
-            AlertSeverity::Critical  => format!(
-                "{} will reach its limit in ~{} days under base growth ({:.1}% consumed).",
-                bundle.resource,
-                base.days_until_breach.unwrap_or(0),
-                pct,
-            ),
-            AlertSeverity::Warning   => format!(
-                "{} is at {:.1}% capacity. Breach predicted in ~{} days under base growth.",
+            CapacityAlertSeverity::Critical  => format!(
+                "{} may reach its limit in ~{} days under {} ({:.1}% consumed).",
                 bundle.resource,
+                outlook.unwrap_or(0),
+                outlook_basis(bundle),
                 pct,
-                base.days_until_breach.unwrap_or(i64::MAX),
             ),
+            CapacityAlertSeverity::Warning   => match outlook {
+                Some(days) => format!(
+                    "{} is at {:.1}% capacity. Breach predicted in ~{} days under {}.",
+                    bundle.resource, pct, days, outlook_basis(bundle),
+                ),
+                None => format!(
+                    "{} is at {:.1}% capacity. No breach predicted under {}.",
+                    bundle.resource, pct, outlook_basis(bundle),
+                ),
+            },
         };
 
         CapacityAlert {
@@ -184,8 +224,8 @@ pub fn evaluate_alert(
             current_value:       bundle.current_value,
             limit_value:         bundle.limit,
             pct_consumed:        pct,
-            breach_predicted_at: base.breach_at,
-            days_until_breach:   base.days_until_breach,
+            breach_predicted_at: outlook.map(|d| now + Duration::days(d)),
+            days_until_breach:   outlook,
             message,
             acknowledged:        false,
             created_at:          now,
@@ -208,7 +248,7 @@ pub fn generate_recommendations(
     let mut recs: Vec<ScalingRecommendation> = Vec::new();
 
     for bundle in bundles {
-        let base_days = bundle.base.days_until_breach;
+        let base_days = breach_outlook(bundle);
         let pct = if bundle.limit > 0.0 {
             (bundle.current_value / bundle.limit) * 100.0
         } else { 0.0 };
@@ -408,12 +448,12 @@ pub fn estimate_cost(
     projected_value: f64,
     horizon_months: u32,
     xlm_usd: f64,
-) -> CostEstimate {
+) -> CapacityCostEstimate {
     let cpu = cost_per_unit_xlm(&resource);
     let current_monthly  = current_value   * cpu;
     let projected_monthly = projected_value * cpu;
 
-    CostEstimate {
+    CapacityCostEstimate {
         resource,
         current_monthly_xlm:   current_monthly,
         projected_monthly_xlm: projected_monthly,
@@ -429,11 +469,11 @@ pub fn estimate_cost(
 // ─────────────────────────────────────────────────────────
 
 pub fn overall_status(alerts: &[CapacityAlert]) -> String {
-    if alerts.iter().any(|a| a.severity == AlertSeverity::Breached.to_string()) {
+    if alerts.iter().any(|a| a.severity == CapacityAlertSeverity::Breached.to_string()) {
         "breached".into()
-    } else if alerts.iter().any(|a| a.severity == AlertSeverity::Critical.to_string()) {
+    } else if alerts.iter().any(|a| a.severity == CapacityAlertSeverity::Critical.to_string()) {
         "critical".into()
-    } else if alerts.iter().any(|a| a.severity == AlertSeverity::Warning.to_string()) {
+    } else if alerts.iter().any(|a| a.severity == CapacityAlertSeverity::Warning.to_string()) {
         "warning".into()
     } else {
         "healthy".into()
@@ -442,7 +482,7 @@ pub fn overall_status(alerts: &[CapacityAlert]) -> String {
 
 pub fn nearest_breach_days(bundles: &[ScenarioBundle]) -> Option<i64> {
     bundles.iter()
-        .filter_map(|b| b.base.days_until_breach)
+        .filter_map(breach_outlook)
         .filter(|&d| d >= 0)
         .min()
 }
@@ -472,6 +512,7 @@ pub fn limit_for(resource: &ResourceKind, limits: &ResourceLimits) -> f64 {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use shared::{ForecastModelKind, ModelForecast};
 
     fn now() -> DateTime<Utc> { Utc::now() }
 
@@ -688,6 +729,56 @@ mod tests {
         assert_eq!(overall_status(&alerts), "breached");
     }
 
+    // ── History-driven outlook ───────────────────────────
+
+    fn model_forecast(earliest: Option<i64>, point: Option<i64>) -> ModelForecast {
+        ModelForecast {
+            model: ForecastModelKind::HoltWinters,
+            auto_selected: true,
+            history_days: 90,
+            interval_level: 0.9,
+            points: vec![],
+            days_until_breach: point,
+            earliest_breach_days: earliest,
+            latest_breach_days: None,
+            changepoints: vec![],
+            backtest: vec![],
+        }
+    }
+
+    #[test]
+    fn model_upper_bound_drives_alert_timing() {
+        let id = Uuid::new_v4();
+        // Base growth alone would only warn at 61%
+        let mut bundle = build_scenario_bundle(
+            id, ResourceKind::TransactionVolume,
+            30_500.0, 50_000.0, 12, None, now(),
+        );
+        bundle.model_forecast = Some(model_forecast(Some(12), Some(40)));
+        let alert = evaluate_alert(id, &bundle, now()).unwrap();
+        assert_eq!(alert.severity, "CRITICAL");
+        assert_eq!(alert.days_until_breach, Some(12));
+        assert!(alert.message.contains("holt_winters"), "{}", alert.message);
+        assert_eq!(nearest_breach_days(&[bundle]), Some(12));
+    }
+
+    #[test]
+    fn flat_model_forecast_overrides_base_growth() {
+        let id = Uuid::new_v4();
+        // Base growth breaches within a month, but history shows no growth
+        let mut bundle = build_scenario_bundle(
+            id, ResourceKind::StorageEntries,
+            85_000.0, 100_000.0, 12, None, now(),
+        );
+        assert_eq!(evaluate_alert(id, &bundle, now()).unwrap().severity, "CRITICAL");
+
+        bundle.model_forecast = Some(model_forecast(None, None));
+        let alert = evaluate_alert(id, &bundle, now()).unwrap();
+        assert_eq!(alert.severity, "WARNING");
+        assert!(alert.message.contains("No breach predicted"), "{}", alert.message);
+        assert_eq!(nearest_breach_days(&[bundle]), None);
+    }
+
     fn make_alert(severity: &str) -> CapacityAlert {
         CapacityAlert {
             id: Uuid::new_v4(), contract_id: Uuid::new_v4(),
@@ -0,0 +1,693 @@
+// api/src/capacity_forecast.rs
+//
+// History-driven forecasting for capacity planning. Where capacity_engine
+// projects fixed growth scenarios from the current value, this module fits
+// models to a resource's own snapshot history:
+//
+//   linear_trend — least-squares line over the whole history
+//   holt_winters — additive trend + weekly seasonality (ETS(A,A,A))
+//   changepoint  — piecewise-linear trend, extrapolating the last segment
+//
+// `auto` backtests every model on rolling origins and keeps the one with the
+// lowest mean absolute error. Every forecast carries a prediction interval;
+// the capacity engine alerts on the day its upper bound crosses the limit.
+// All functions are synchronous and free of I/O.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use shared::{
+    BacktestScore, ForecastModelKind, ModelForecast, ModelForecastPoint, ResourceKind,
+    ResourceSnapshot,
+};
+
+/// Weekly seasonality over daily values
+pub const SEASON: usize = 7;
+/// Holt-Winters needs two full seasons to initialise
+pub const MIN_HISTORY_DAYS: usize = 2 * SEASON;
+/// Older history is dropped; usage a year ago says little about next month
+pub const MAX_HISTORY_DAYS: usize = 365;
+pub const INTERVAL_LEVEL: f64 = 0.90;
+/// Two-sided standard normal quantile for INTERVAL_LEVEL
+const Z_INTERVAL: f64 = 1.644_853_626_951_472;
+
+const BACKTEST_HORIZON: usize = 14;
+const BACKTEST_FOLDS: usize = 3;
+const MIN_SEGMENT: usize = 7;
+const MAX_CHANGEPOINTS: usize = 5;
+
+/// Simplest first, so ties go to the simpler model.
+const CANDIDATES: [ForecastModelKind; 3] = [
+    ForecastModelKind::LinearTrend,
+    ForecastModelKind::Changepoint,
+    ForecastModelKind::HoltWinters,
+];
+
+// ─────────────────────────────────────────────────────────
+// Daily series
+// ─────────────────────────────────────────────────────────
+
+/// One value per UTC day: the last snapshot of the day, with gaps
+/// carried forward from the previous day.
+#[derive(Debug, Clone)]
+pub struct DailySeries {
+    pub start: NaiveDate,
+    pub values: Vec<f64>,
+}
+
+impl DailySeries {
+    pub fn last_date(&self) -> NaiveDate {
+        self.start + Duration::days(self.values.len() as i64 - 1)
+    }
+}
+
+pub fn daily_series(
+    snapshots: &[ResourceSnapshot],
+    resource: &ResourceKind,
+) -> Option<DailySeries> {
+    let mut points: Vec<(DateTime<Utc>, f64)> = snapshots
+        .iter()
+        .filter(|s| &s.resource == resource)
+        .map(|s| (s.recorded_at, s.value))
+        .collect();
+    points.sort_by_key(|(at, _)| *at);
+    let first = points.first()?.0.date_naive();
+    let last = points.last()?.0.date_naive();
+
+    let start = first.max(last - Duration::days(MAX_HISTORY_DAYS as i64 - 1));
+    let days = (last - start).num_days() as usize + 1;
+    let mut values: Vec<Option<f64>> = vec![None; days];
+    let mut carried = None;
+    for (at, value) in points {
+        let date = at.date_naive();
+        if date < start {
+            carried = Some(value);
+        } else {
+            values[(date - start).num_days() as usize] = Some(value);
+        }
+    }
+    let values = values
+        .into_iter()
+        .map(|v| {
+            carried = v.or(carried);
+            carried
+        })
+        .collect::<Option<Vec<f64>>>()?;
+    Some(DailySeries { start, values })
+}
+
+// ─────────────────────────────────────────────────────────
+// Linear fits
+// ─────────────────────────────────────────────────────────
+
+/// Prefix sums for O(1) least-squares fits over any index range. Values
+/// are centred first so large counters don't lose precision in Σy².
+struct PrefixSums {
+    offset: f64,
+    y: Vec<f64>,
+    xy: Vec<f64>,
+    yy: Vec<f64>,
+}
+
+impl PrefixSums {
+    fn new(values: &[f64]) -> Self {
+        let offset = values.iter().sum::<f64>() / values.len().max(1) as f64;
+        let mut sums = PrefixSums {
+            offset,
+            y: vec![0.0],
+            xy: vec![0.0],
+            yy: vec![0.0],
+        };
+        for (i, v) in values.iter().map(|v| v - offset).enumerate() {
+            sums.y.push(sums.y[i] + v);
+            sums.xy.push(sums.xy[i] + i as f64 * v);
+            sums.yy.push(sums.yy[i] + v * v);
+        }
+        sums
+    }
+
+    fn line(&self, a: usize, b: usize) -> Line {
+        let n = (b - a) as f64;
+        // Σx and Σx² over a..b in closed form
+        let sx = (a + b - 1) as f64 * n / 2.0;
+        let sq = |k: usize| {
+            let k = k as f64;
+            (k - 1.0) * k * (2.0 * k - 1.0) / 6.0
+        };
+        let sxx = sq(b) - sq(a);
+        let sy = self.y[b] - self.y[a];
+        let sxy = self.xy[b] - self.xy[a];
+        let syy = self.yy[b] - self.yy[a];
+
+        let mean_x = sx / n;
+        let sxx_c = sxx - sx * mean_x;
+        let slope = if sxx_c > 0.0 {
+            (sxy - sx * sy / n) / sxx_c
+        } else {
+            0.0
+        };
+        let intercept = sy / n - slope * mean_x;
+        let sse = (syy - intercept * sy - slope * sxy).max(0.0);
+        Line {
+            n: b - a,
+            intercept: intercept + self.offset,
+            slope,
+            mean_x,
+            sxx_c,
+            sse,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    n: usize,
+    intercept: f64,
+    slope: f64,
+    mean_x: f64,
+    sxx_c: f64,
+    sse: f64,
+}
+
+impl Line {
+    fn at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+
+    /// Standard deviation of a new observation at `x`.
+    fn prediction_sd(&self, x: f64) -> f64 {
+        if self.n <= 2 {
+            return 0.0;
+        }
+        let sigma2 = self.sse / (self.n - 2) as f64;
+        let leverage = if self.sxx_c > 0.0 {
+            (x - self.mean_x).powi(2) / self.sxx_c
+        } else {
+            0.0
+        };
+        (sigma2 * (1.0 + 1.0 / self.n as f64 + leverage)).sqrt()
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Robust per-point noise variance from the MAD of first differences,
+/// which ignores both the trend and the occasional level shift.
+fn noise_variance(values: &[f64]) -> f64 {
+    let mut diffs: Vec<f64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let centre = median(&mut diffs);
+    let mut deviations: Vec<f64> = diffs.iter().map(|d| (d - centre).abs()).collect();
+    let sigma = 1.4826 * median(&mut deviations) / std::f64::consts::SQRT_2;
+    let floor = 1e-12 * (1.0 + values.iter().map(|v| v * v).sum::<f64>() / values.len() as f64);
+    (sigma * sigma).max(floor)
+}
+
+/// Binary segmentation on a piecewise-linear model. A split is kept when it
+/// reduces the squared error by more than a BIC-style penalty of three
+/// parameters (slope, intercept, location) per changepoint.
+fn detect_changepoints(values: &[f64]) -> Vec<usize> {
+    let n = values.len();
+    if n < 2 * MIN_SEGMENT {
+        return vec![];
+    }
+    let sums = PrefixSums::new(values);
+    let penalty = 3.0 * noise_variance(values) * (n as f64).ln();
+
+    let mut changepoints: Vec<usize> = Vec::new();
+    let mut segments = vec![(0, n)];
+    while changepoints.len() < MAX_CHANGEPOINTS {
+        let mut best: Option<(f64, usize, usize)> = None;
+        for (i, &(a, b)) in segments.iter().enumerate() {
+            if b - a < 2 * MIN_SEGMENT {
+                continue;
+            }
+            let whole = sums.line(a, b).sse;
+            for k in a + MIN_SEGMENT..=b - MIN_SEGMENT {
+                let gain = whole - sums.line(a, k).sse - sums.line(k, b).sse;
+                if !best.is_some_and(|(g, _, _)| g >= gain) {
+                    best = Some((gain, i, k));
+                }
+            }
+        }
+        match best {
+            Some((gain, i, k)) if gain > penalty => {
+                let (a, b) = segments.remove(i);
+                segments.push((a, k));
+                segments.push((k, b));
+                changepoints.push(k);
+            }
+            _ => break,
+        }
+    }
+    changepoints.sort_unstable();
+    changepoints
+}
+
+// ─────────────────────────────────────────────────────────
+// Holt-Winters (additive trend and seasonality)
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct HoltWinters {
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+    level: f64,
+    trend: f64,
+    /// Indexed by day % SEASON
+    season: [f64; SEASON],
+    sigma2: f64,
+}
+
+impl HoltWinters {
+    /// Run the innovations-form recursions, returning the final state.
+    fn run(values: &[f64], alpha: f64, beta: f64, gamma: f64) -> Self {
+        let m = SEASON;
+        let first = values[..m].iter().sum::<f64>() / m as f64;
+        let second = values[m..2 * m].iter().sum::<f64>() / m as f64;
+        let trend = (second - first) / m as f64;
+        let mut season = [0.0; SEASON];
+        for (i, s) in season.iter_mut().enumerate() {
+            // Detrend the first season around its midpoint
+            *s = values[i] - (first + trend * (i as f64 - (m - 1) as f64 / 2.0));
+        }
+
+        let mut hw = HoltWinters {
+            alpha,
+            beta,
+            gamma,
+            level: first + trend * (m - 1) as f64 / 2.0,
+            trend,
+            season,
+            sigma2: 0.0,
+        };
+        let mut sse = 0.0;
+        for (t, &y) in values.iter().enumerate().skip(m) {
+            let s = &mut hw.season[t % m];
+            let error = y - (hw.level + hw.trend + *s);
+            sse += error * error;
+            hw.level += hw.trend + alpha * error;
+            hw.trend += beta * error;
+            *s += gamma * error;
+        }
+        hw.sigma2 = sse / (values.len() - m) as f64;
+        hw
+    }
+
+    /// Grid search for the smoothing parameters with the smallest
+    /// one-step-ahead error, within the usual admissible region.
+    fn fit(values: &[f64]) -> Self {
+        const ALPHAS: [f64; 7] = [0.05, 0.1, 0.2, 0.3, 0.5, 0.7, 0.9];
+        const BETAS: [f64; 5] = [0.001, 0.01, 0.05, 0.1, 0.2];
+        const GAMMAS: [f64; 5] = [0.001, 0.05, 0.1, 0.2, 0.3];
+        let mut best: Option<HoltWinters> = None;
+        for alpha in ALPHAS {
+            for beta in BETAS.into_iter().filter(|&b| b <= alpha) {
+                for gamma in GAMMAS.into_iter().filter(|&g| g <= 1.0 - alpha) {
+                    let hw = Self::run(values, alpha, beta, gamma);
+                    if !best.as_ref().is_some_and(|b| b.sigma2 <= hw.sigma2) {
+                        best = Some(hw);
+                    }
+                }
+            }
+        }
+        best.expect("parameter grid is non-empty")
+    }
+
+    /// Point forecast and standard deviation `h` days after the last value,
+    /// where `origin` is the number of values fitted.
+    fn forecast(&self, origin: usize, h: usize) -> (f64, f64) {
+        let hf = h as f64;
+        let mean = self.level + hf * self.trend + self.season[(origin - 1 + h) % SEASON];
+        let (a, b, g, m) = (self.alpha, self.beta, self.gamma, SEASON as f64);
+        let k = ((h - 1) / SEASON) as f64;
+        let growth = 1.0
+            + (hf - 1.0) * (a * a + a * b * hf + b * b * hf * (2.0 * hf - 1.0) / 6.0)
+            + g * k * (2.0 * a + g + b * m * (k + 1.0));
+        (mean, (self.sigma2 * growth).sqrt())
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Fitted models
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+enum Model {
+    Line(Line),
+    HoltWinters(HoltWinters),
+}
+
+#[derive(Debug, Clone)]
+pub struct FittedModel {
+    pub kind: ForecastModelKind,
+    /// Indexes into the fitted series where the trend changed
+    pub changepoints: Vec<usize>,
+    origin: usize,
+    model: Model,
+}
+
+impl FittedModel {
+    /// Point forecast and standard deviation `h ≥ 1` days past the history.
+    pub fn predict(&self, h: usize) -> (f64, f64) {
+        match &self.model {
+            Model::Line(line) => {
+                let x = (self.origin - 1 + h) as f64;
+                (line.at(x), line.prediction_sd(x))
+            }
+            Model::HoltWinters(hw) => hw.forecast(self.origin, h),
+        }
+    }
+}
+
+/// Fit a concrete model; `None` when the history is too short or `Auto`.
+pub fn fit(kind: ForecastModelKind, values: &[f64]) -> Option<FittedModel> {
+    let origin = values.len();
+    if origin < MIN_HISTORY_DAYS {
+        return None;
+    }
+    let (model, changepoints) = match kind {
+        ForecastModelKind::Auto => return None,
+        ForecastModelKind::LinearTrend => {
+            (Model::Line(PrefixSums::new(values).line(0, origin)), vec![])
+        }
+        ForecastModelKind::Changepoint => {
+            let changepoints = detect_changepoints(values);
+            let start = changepoints.last().copied().unwrap_or(0);
+            (
+                Model::Line(PrefixSums::new(values).line(start, origin)),
+                changepoints,
+            )
+        }
+        ForecastModelKind::HoltWinters => (Model::HoltWinters(HoltWinters::fit(values)), vec![]),
+    };
+    Some(FittedModel {
+        kind,
+        changepoints,
+        origin,
+        model,
+    })
+}
+
+/// Rolling-origin backtest: refit on all history before each origin and
+/// score the following BACKTEST_HORIZON days.
+pub fn backtest(kind: ForecastModelKind, values: &[f64]) -> Option<BacktestScore> {
+    let (mut folds, mut count, mut abs_sum, mut covered) = (0u32, 0usize, 0.0, 0usize);
+    let (mut pct_sum, mut pct_count) = (0.0, 0usize);
+    for fold in 1..=BACKTEST_FOLDS {
+        let Some(origin) = values.len().checked_sub(fold * BACKTEST_HORIZON) else {
+            break;
+        };
+        let Some(fitted) = fit(kind, &values[..origin]) else {
+            break;
+        };
+        folds += 1;
+        for h in 1..=BACKTEST_HORIZON {
+            let actual = values[origin + h - 1];
+            let (mean, sd) = fitted.predict(h);
+            let error = (actual - mean).abs();
+            abs_sum += error;
+            count += 1;
+            if error <= Z_INTERVAL * sd {
+                covered += 1;
+            }
+            if actual != 0.0 {
+                pct_sum += error / actual.abs();
+                pct_count += 1;
+            }
+        }
+    }
+    (folds > 0).then(|| BacktestScore {
+        model: kind,
+        folds,
+        mae: abs_sum / count as f64,
+        mape: (pct_count > 0).then(|| pct_sum / pct_count as f64 * 100.0),
+        interval_coverage: covered as f64 / count as f64,
+    })
+}
+
+/// Model with the lowest backtest MAE. With too little history to
+/// backtest, the linear trend is used.
+pub fn select_model(scores: &[BacktestScore]) -> ForecastModelKind {
+    scores
+        .iter()
+        .fold(None::<&BacktestScore>, |best, s| match best {
+            Some(b) if b.mae <= s.mae => Some(b),
+            _ => Some(s),
+        })
+        .map(|s| s.model)
+        .unwrap_or(ForecastModelKind::LinearTrend)
+}
+
+// ─────────────────────────────────────────────────────────
+// Forecast
+// ─────────────────────────────────────────────────────────
+
+/// Forecast a resource from its daily history over `horizon_days` from `now`.
+pub fn forecast_history(
+    series: &DailySeries,
+    limit: f64,
+    choice: ForecastModelKind,
+    horizon_days: u32,
+    now: DateTime<Utc>,
+) -> Option<ModelForecast> {
+    let values = &series.values;
+    if values.len() < MIN_HISTORY_DAYS {
+        return None;
+    }
+
+    let backtest: Vec<BacktestScore> = CANDIDATES
+        .iter()
+        .filter_map(|&kind| backtest(kind, values))
+        .collect();
+    let kind = match choice {
+        ForecastModelKind::Auto => select_model(&backtest),
+        explicit => explicit,
+    };
+    let fitted = fit(kind, values)?;
+
+    let last = series.last_date();
+    let day_start = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let lag = (now.date_naive() - last).num_days().max(0) as usize;
+    let at = |h: usize| day_start(last) + Duration::days(h as i64);
+    let days_from_now = |h: usize| (at(h) - now).num_days().max(0);
+
+    let (mut point_breach, mut earliest, mut latest) = (None, None, None);
+    let mut points = Vec::new();
+    for h in 1..=lag + horizon_days as usize {
+        let (mean, sd) = fitted.predict(h);
+        let value = mean.max(0.0);
+        let lower = (mean - Z_INTERVAL * sd).max(0.0);
+        let upper = (mean + Z_INTERVAL * sd).max(0.0);
+        if upper > limit && earliest.is_none() {
+            earliest = Some(days_from_now(h));
+        }
+        if value > limit && point_breach.is_none() {
+            point_breach = Some(days_from_now(h));
+        }
+        if lower > limit && latest.is_none() {
+            latest = Some(days_from_now(h));
+        }
+        if h > lag && (h - lag).is_multiple_of(30) {
+            points.push(ModelForecastPoint {
+                day: (h - lag) as u32,
+                at: at(h),
+                value,
+                lower,
+                upper,
+            });
+        }
+    }
+
+    Some(ModelForecast {
+        model: kind,
+        auto_selected: choice == ForecastModelKind::Auto,
+        history_days: values.len() as u32,
+        interval_level: INTERVAL_LEVEL,
+        points,
+        days_until_breach: point_breach,
+        earliest_breach_days: earliest,
+        latest_breach_days: latest,
+        changepoints: fitted
+            .changepoints
+            .iter()
+            .map(|&i| day_start(series.start) + Duration::days(i as i64))
+            .collect(),
+        backtest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Deterministic pseudo-noise in [-1, 1]
+    fn noise(i: usize) -> f64 {
+        let x = (i as f64 * 12.9898).sin() * 43_758.545_3;
+        2.0 * (x - x.floor()) - 1.0
+    }
+
+    fn weekly(days: usize) -> Vec<f64> {
+        const WEEK: [f64; 7] = [0.0, 40.0, 45.0, 42.0, 38.0, 10.0, -175.0];
+        (0..days)
+            .map(|i| 1_000.0 + 5.0 * i as f64 + WEEK[i % 7] + 3.0 * noise(i))
+            .collect()
+    }
+
+    fn series(values: Vec<f64>) -> DailySeries {
+        DailySeries {
+            start: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            values,
+        }
+    }
+
+    #[test]
+    fn daily_series_keeps_last_value_and_fills_gaps() {
+        let day = |d: i64, hour: u32, value: f64| ResourceSnapshot {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            resource: ResourceKind::StorageEntries,
+            value,
+            tag: None,
+            recorded_at: NaiveDate::from_ymd_opt(2026, 3, 1)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_utc()
+                + Duration::days(d),
+        };
+        let mut other = day(1, 0, 999.0);
+        other.resource = ResourceKind::UniqueUsers;
+        let snaps = vec![day(3, 9, 40.0), day(0, 23, 12.0), day(0, 1, 10.0), other];
+
+        let s = daily_series(&snaps, &ResourceKind::StorageEntries).unwrap();
+        assert_eq!(s.values, vec![12.0, 12.0, 12.0, 40.0]);
+        assert_eq!(s.last_date(), NaiveDate::from_ymd_opt(2026, 3, 4).unwrap());
+        assert!(daily_series(&snaps, &ResourceKind::FeePerOperation).is_none());
+    }
+
+    #[test]
+    fn linear_trend_recovers_an_exact_line() {
+        let values: Vec<f64> = (0..30).map(|i| 100.0 + 2.0 * i as f64).collect();
+        let fitted = fit(ForecastModelKind::LinearTrend, &values).unwrap();
+        let (mean, sd) = fitted.predict(10);
+        assert!((mean - (100.0 + 2.0 * 39.0)).abs() < 1e-6);
+        assert!(sd < 1e-6);
+    }
+
+    #[test]
+    fn changepoint_finds_a_launch_and_follows_the_new_slope() {
+        // Flat for 40 days, then growth of 20/day after a launch
+        let values: Vec<f64> = (0..70)
+            .map(|i| {
+                let base = if i < 40 {
+                    500.0
+                } else {
+                    500.0 + 20.0 * (i - 40) as f64
+                };
+                base + 2.0 * noise(i)
+            })
+            .collect();
+        let fitted = fit(ForecastModelKind::Changepoint, &values).unwrap();
+        assert!(
+            fitted.changepoints.iter().any(|&c| (38..=42).contains(&c)),
+            "{:?}",
+            fitted.changepoints
+        );
+        let (mean, _) = fitted.predict(30);
+        let expected = 500.0 + 20.0 * (69 + 30 - 40) as f64;
+        assert!(
+            (mean - expected).abs() / expected < 0.02,
+            "{mean} vs {expected}"
+        );
+
+        let linear = fit(ForecastModelKind::LinearTrend, &values).unwrap();
+        assert!(linear.predict(30).0 < mean * 0.9);
+    }
+
+    #[test]
+    fn no_changepoints_in_a_noisy_line() {
+        let values: Vec<f64> = (0..90).map(|i| 50.0 + i as f64 + 4.0 * noise(i)).collect();
+        assert!(detect_changepoints(&values).is_empty());
+    }
+
+    #[test]
+    fn auto_picks_holt_winters_for_weekly_cycles() {
+        let forecast = forecast_history(
+            &series(weekly(84)),
+            1e9,
+            ForecastModelKind::Auto,
+            60,
+            Utc::now(),
+        )
+        .unwrap();
+        assert_eq!(forecast.model, ForecastModelKind::HoltWinters);
+        assert!(forecast.auto_selected);
+        let score = |kind| {
+            forecast
+                .backtest
+                .iter()
+                .find(|s| s.model == kind)
+                .unwrap()
+                .mae
+        };
+        assert!(
+            score(ForecastModelKind::HoltWinters) < score(ForecastModelKind::LinearTrend) / 3.0
+        );
+    }
+
+    #[test]
+    fn holt_winters_intervals_cover_held_out_days() {
+        let values = weekly(84);
+        let score = backtest(ForecastModelKind::HoltWinters, &values).unwrap();
+        assert_eq!(score.folds, 3);
+        assert!(
+            score.interval_coverage >= 0.75,
+            "{}",
+            score.interval_coverage
+        );
+    }
+
+    #[test]
+    fn interval_brackets_the_breach_date() {
+        let values: Vec<f64> = (0..60)
+            .map(|i| 1_000.0 + 10.0 * i as f64 + 25.0 * noise(i))
+            .collect();
+        let s = series(values);
+        let now = s.last_date().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let f = forecast_history(&s, 3_000.0, ForecastModelKind::LinearTrend, 365, now).unwrap();
+
+        let (earliest, point, latest) = (
+            f.earliest_breach_days.unwrap(),
+            f.days_until_breach.unwrap(),
+            f.latest_breach_days.unwrap(),
+        );
+        assert!(earliest <= point && point <= latest);
+        // ~1590 today, +10/day → limit reached in about 141 days
+        assert!((130..=150).contains(&point), "{point}");
+        assert_eq!(f.points.len(), 12);
+        assert!(f
+            .points
+            .iter()
+            .all(|p| p.lower <= p.value && p.value <= p.upper));
+    }
+
+    #[test]
+    fn short_history_has_no_model_forecast() {
+        let s = series(weekly(MIN_HISTORY_DAYS - 1));
+        assert!(forecast_history(&s, 1e9, ForecastModelKind::Auto, 30, Utc::now()).is_none());
+        // Enough to fit but not to backtest: falls back to the linear trend
+        let s = series(weekly(MIN_HISTORY_DAYS + 2));
+        let f = forecast_history(&s, 1e9, ForecastModelKind::Auto, 30, Utc::now()).unwrap();
+        assert!(f.backtest.is_empty());
+        assert_eq!(f.model, ForecastModelKind::LinearTrend);
+    }
+}
@@ -9,6 +9,8 @@
 //   GET    /contracts/:id/capacity-alerts           → list_alerts
 //   PATCH  /contracts/:id/capacity-alerts/:aid/ack  → acknowledge_alert
 //   GET    /contracts/:id/capacity-recommendations  → list_recommendations
+//   GET    /contracts/:id/capacity-models           → list_model_settings
+//   PUT    /contracts/:id/capacity-models/:resource → set_model_setting
 
 use axum::{
     extract::{Path, Query, State},
@@ -18,12 +20,12 @@ use axum::{
 };
 use chrono::Utc;
 use uuid::Uuid;
-use sqlx::Row;
 
 // ── Types come from the shared crate (shared/src/capacity_models.rs) ─────────
 use shared::{
-    AcknowledgeAlertRequest, CapacityAlert, CapacityPlanParams, CapacityPlanResponse,
-    RecordSnapshotRequest, ResourceKind, ResourceLimits, ResourceSnapshot,
+    AcknowledgeAlertRequest, CapacityAlert, CapacityModelSetting, CapacityPlanParams,
+    CapacityPlanResponse, ForecastModelKind, RecordSnapshotRequest, ResourceKind,
+    ResourceLimits, ResourceSnapshot, SetCapacityModelRequest,
 };
 
 // ── Engine is api-internal: pure computation, no DB, lives in api/src/ ───────
@@ -33,6 +35,7 @@ use crate::{
         evaluate_alert, generate_recommendations, limit_for, nearest_breach_days,
         overall_status,
     },
+    capacity_forecast::{daily_series, forecast_history},
     state::AppState,
 };
 
@@ -152,6 +155,25 @@ pub async fn get_capacity_plan(
         Ok(s) => s,
     };
 
+    let settings = sqlx::query_as::<_, CapacityModelSetting>(
+        "SELECT * FROM capacity_model_settings WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await;
+
+    let settings = match settings {
+        Err(e) => {
+            tracing::error!("capacity_plan model settings error: {:?}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response();
+        }
+        Ok(s) => s,
+    };
+
     let limits = ResourceLimits::default();
     let now    = Utc::now();
 
@@ -167,10 +189,19 @@ pub async fn get_capacity_plan(
     let scenarios: Vec<_> = all_resources.iter().map(|resource| {
         let current = current_value_from_snapshots(&snaps, resource);
         let limit   = limit_for(resource, &limits);
-        build_scenario_bundle(
+        let mut bundle = build_scenario_bundle(
             contract_id, resource.clone(), current, limit,
             horizon, params.custom_rate, now,
-        )
+        );
+
+        // Fit the configured model (or the best backtested one) to history
+        let model = settings.iter()
+            .find(|s| &s.resource == resource)
+            .map(|s| s.model)
+            .unwrap_or_default();
+        bundle.model_forecast = daily_series(&snaps, resource)
+            .and_then(|series| forecast_history(&series, limit, model, horizon * 30, now));
+        bundle
     }).collect();
 
     let alerts: Vec<CapacityAlert> = scenarios.iter()
@@ -374,4 +405,89 @@ pub async fn list_recommendations(
 
     let recs = generate_recommendations(contract_id, &bundles, now);
     (StatusCode::OK, Json(recs)).into_response()
-}
\ No newline at end of file
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /contracts/:id/capacity-models
+// Forecasting model per resource ('auto' unless configured).
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_model_settings(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let rows = sqlx::query_as::<_, CapacityModelSetting>(
+        "SELECT * FROM capacity_model_settings WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await;
+
+    match rows {
+        Err(e) => {
+            tracing::error!("list_model_settings DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+        Ok(rows) => {
+            let settings: Vec<_> = ResourceKind::ALL.iter().map(|resource| {
+                let model = rows.iter()
+                    .find(|s| &s.resource == resource)
+                    .map(|s| s.model)
+                    .unwrap_or(ForecastModelKind::Auto);
+                serde_json::json!({ "resource": resource, "model": model })
+            }).collect();
+            (StatusCode::OK, Json(settings)).into_response()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// PUT /contracts/:id/capacity-models/:resource
+// Pin a forecasting model for one resource, or set it back to 'auto'.
+// ─────────────────────────────────────────────────────────
+
+pub async fn set_model_setting(
+    State(state): State<AppState>,
+    Path((contract_id, resource)): Path<(Uuid, ResourceKind)>,
+    Json(req): Json<SetCapacityModelRequest>,
+) -> impl IntoResponse {
+    let row = sqlx::query_as::<_, CapacityModelSetting>(
+        r#"
+        INSERT INTO capacity_model_settings (contract_id, resource, model, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (contract_id, resource) DO UPDATE SET
+            model      = EXCLUDED.model,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(contract_id)
+    .bind(&resource)
+    .bind(req.model)
+    .fetch_one(&state.db)
+    .await;
+
+    match row {
+        Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "NOT_FOUND",
+                "message": "Contract not found"
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("set_model_setting DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+        Ok(setting) => (StatusCode::OK, Json(setting)).into_response(),
+    }
+}
@@ -10,14 +10,14 @@
 //       .with_state(state);
 
 use axum::{
-    routing::{get, patch, post},
+    routing::{get, patch, post, put},
     Router,
 };
 
 use crate::{
     capacity_handlers::{
-        acknowledge_alert, get_capacity_plan, list_alerts,
-        list_recommendations, list_snapshots, record_snapshot,
+        acknowledge_alert, get_capacity_plan, list_alerts, list_model_settings,
+        list_recommendations, list_snapshots, record_snapshot, set_model_setting,
     },
     state::AppState,
 };
@@ -49,4 +49,13 @@ pub fn capacity_router() -> Router<AppState> {
             "/contracts/:id/capacity-recommendations",
             get(list_recommendations),
         )
-}
\ No newline at end of file
+        // Forecasting model per resource (auto = best backtest)
+        .route(
+            "/contracts/:id/capacity-models",
+            get(list_model_settings),
+        )
+        .route(
+            "/contracts/:id/capacity-models/:resource",
+            put(set_model_setting),
+        )
+}
@@ -0,0 +1,283 @@
+// api/src/cargo_lock.rs
+//
+// Cargo.lock parsing for dependency scans. A lockfile lists every crate in
+// the build with its exact version and the crates it depends on, so it
+// gives the full transitive graph without running Cargo. Workspace crates
+// (no `source`) are the roots; each crate is reported with the shortest
+// path from a root, e.g. `my-contract@0.1.0 > soroban-sdk@21.0.0 >
+// ed25519-dalek@2.0.0`. Handles lockfile versions 1 through 4. Everything
+// in here is synchronous.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// One crate in the resolved graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedCrate {
+    pub name: String,
+    pub version: String,
+    /// `registry+…`, `sparse+…` or `git+…`; `None` for workspace crates
+    pub source: Option<String>,
+    /// `name@version` from a workspace crate down to this one
+    pub path: Vec<String>,
+}
+
+impl LockedCrate {
+    /// Crates published to a registry, which advisories are written for
+    pub fn is_registry(&self) -> bool {
+        self.source
+            .as_deref()
+            .is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+    }
+
+    /// A dependency of a workspace crate, rather than of another dependency
+    pub fn is_direct(&self) -> bool {
+        self.path.len() == 2
+    }
+}
+
+fn label(p: &LockedPackage) -> String {
+    format!("{}@{}", p.name, p.version)
+}
+
+/// Parse a lockfile into its crates, each with its shortest path from a
+/// workspace crate. Crates unreachable from the workspace (left over from
+/// removed features) are still returned, with a path of just themselves.
+pub fn parse(raw: &str) -> Result<Vec<LockedCrate>, String> {
+    let lock: Lockfile = toml::from_str(raw).map_err(|e| format!("invalid Cargo.lock: {}", e))?;
+    let packages = lock.package;
+    if packages.is_empty() {
+        return Err("Cargo.lock lists no packages".into());
+    }
+
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, p) in packages.iter().enumerate() {
+        by_name.entry(p.name.as_str()).or_default().push(i);
+    }
+
+    // Entries are "name", "name version" or "name version (source)"; the
+    // shorter forms are only used when they are unambiguous
+    let resolve = |entry: &str| -> Option<usize> {
+        let mut parts = entry.splitn(3, ' ');
+        let name = parts.next()?;
+        let version = parts.next();
+        let source = parts
+            .next()
+            .map(|s| s.trim_start_matches('(').trim_end_matches(')'));
+        let candidates = by_name.get(name)?;
+        candidates.iter().copied().find(|&i| {
+            let p = &packages[i];
+            let version_ok = match version {
+                Some(v) => v == p.version,
+                None => true,
+            };
+            let source_ok = match source {
+                Some(s) => p.source.as_deref() == Some(s),
+                None => true,
+            };
+            version_ok && source_ok
+        })
+    };
+
+    let edges: Vec<Vec<usize>> = packages
+        .iter()
+        .map(|p| p.dependencies.iter().filter_map(|d| resolve(d)).collect())
+        .collect();
+
+    let mut roots: Vec<usize> = (0..packages.len())
+        .filter(|&i| packages[i].source.is_none())
+        .collect();
+    if roots.is_empty() {
+        // Lockfiles from a registry checkout have no workspace crate;
+        // start from whatever nothing else depends on
+        let mut depended = vec![false; packages.len()];
+        for targets in &edges {
+            for &t in targets {
+                depended[t] = true;
+            }
+        }
+        roots = (0..packages.len()).filter(|&i| !depended[i]).collect();
+    }
+
+    // Breadth-first, so each crate keeps its shortest path
+    let mut parent: Vec<Option<usize>> = vec![None; packages.len()];
+    let mut visited = vec![false; packages.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for &r in &roots {
+        visited[r] = true;
+        queue.push_back(r);
+    }
+    while let Some(node) = queue.pop_front() {
+        for &next in &edges[node] {
+            if !visited[next] {
+                visited[next] = true;
+                parent[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Ok(packages
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut path = vec![label(p)];
+            let mut at = i;
+            while let Some(up) = parent[at] {
+                path.push(label(&packages[up]));
+                at = up;
+            }
+            path.reverse();
+            LockedCrate {
+                name: p.name.clone(),
+                version: p.version.clone(),
+                source: p.source.clone(),
+                path,
+            }
+        })
+        .collect())
+}
+
+/// The top-most `Cargo.lock` in a `.zip` source archive.
+pub fn from_zip(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+        .map_err(|e| format!("invalid source archive: {}", e))?;
+    let best = (0..archive.len())
+        .filter_map(|i| {
+            let entry = archive.by_index(i).ok()?;
+            let name = entry.name().to_string();
+            let is_lock = name == "Cargo.lock" || name.ends_with("/Cargo.lock");
+            (entry.is_file() && is_lock).then(|| (name.matches('/').count(), i))
+        })
+        .min()
+        .ok_or("source archive contains no Cargo.lock")?;
+
+    let mut entry = archive
+        .by_index(best.1)
+        .map_err(|e| format!("invalid source archive: {}", e))?;
+    let mut raw = String::new();
+    entry
+        .read_to_string(&mut raw)
+        .map_err(|e| format!("unreadable Cargo.lock: {}", e))?;
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCK: &str = r#"
+version = 3
+
+[[package]]
+name = "token"
+version = "0.1.0"
+dependencies = [
+ "soroban-sdk",
+ "syn 2.0.48",
+]
+
+[[package]]
+name = "soroban-sdk"
+version = "21.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "ed25519-dalek",
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "ed25519-dalek"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.48"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    fn find<'a>(crates: &'a [LockedCrate], name: &str, version: &str) -> &'a LockedCrate {
+        crates
+            .iter()
+            .find(|c| c.name == name && c.version == version)
+            .unwrap()
+    }
+
+    #[test]
+    fn resolves_transitive_paths() {
+        let crates = parse(LOCK).unwrap();
+        assert_eq!(crates.len(), 5);
+
+        let root = find(&crates, "token", "0.1.0");
+        assert!(root.source.is_none());
+        assert_eq!(root.path, vec!["token@0.1.0"]);
+
+        let dalek = find(&crates, "ed25519-dalek", "2.0.0");
+        assert!(dalek.is_registry());
+        assert!(!dalek.is_direct());
+        assert_eq!(
+            dalek.path,
+            vec!["token@0.1.0", "soroban-sdk@21.0.0", "ed25519-dalek@2.0.0"]
+        );
+    }
+
+    #[test]
+    fn disambiguates_versions_of_one_crate() {
+        let crates = parse(LOCK).unwrap();
+        let syn2 = find(&crates, "syn", "2.0.48");
+        assert!(syn2.is_direct());
+        let syn1 = find(&crates, "syn", "1.0.109");
+        assert_eq!(syn1.path.len(), 3);
+    }
+
+    #[test]
+    fn v1_entries_with_sources_resolve() {
+        let lock = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "log 0.4.20 (registry+https://github.com/rust-lang/crates.io-index)",
+]
+
+[[package]]
+name = "log"
+version = "0.4.20"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let crates = parse(lock).unwrap();
+        assert_eq!(
+            find(&crates, "log", "0.4.20").path,
+            vec!["app@0.1.0", "log@0.4.20"]
+        );
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_lockfiles() {
+        assert!(parse("not toml [").is_err());
+        assert!(parse("version = 3").is_err());
+    }
+}
@@ -70,7 +70,7 @@ pub async fn get_contract_config(
     )
     .bind(contract_id)
     .bind(&query.environment)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?
     .ok_or_else(|| ApiError::not_found("ConfigNotFound", "Configuration not found"))?;
@@ -91,7 +91,7 @@ pub async fn create_contract_config(
     )
     .bind(contract_id)
     .bind(&payload.environment)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -115,7 +115,7 @@ pub async fn create_contract_config(
     .bind(&payload.config_data)
     .bind(encrypted_secrets)
     .bind(&payload.created_by)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -137,7 +137,7 @@ pub async fn get_config_history(
     )
     .bind(contract_id)
     .bind(&query.environment)
-    .fetch_all(&state.pool)
+    .fetch_all(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -162,7 +162,7 @@ pub async fn rollback_config(
     .bind(contract_id)
     .bind(&query.environment)
     .bind(payload.roll_back_to_version)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?
     .ok_or_else(|| ApiError::not_found("ConfigNotFound", "Target version not found for rollback"))?;
@@ -176,7 +176,7 @@ pub async fn rollback_config(
     )
     .bind(contract_id)
     .bind(&query.environment)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -195,7 +195,7 @@ pub async fn rollback_config(
     .bind(&target_config.config_data)
     .bind(&target_config.secrets_data) // Keep the already encrypted secrets
     .bind(&payload.created_by)
-    .fetch_one(&state.pool)
+    .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
@@ -6,7 +6,7 @@
 //   GET  /api/contracts/:id/history              – last 10 log entries (sidebar)
 //   GET  /api/contracts/:id/history/all          – paginated full history
 //   GET  /api/contracts/:id/history/export       – CSV download
-//   GET  /api/contracts/:id/versions/:v1/diff/:v2 – field-level diff
+//   GET  /api/contracts/:id/versions/:v1/diff/:v2 – field-level diff + upgrade safety
 //   POST /api/contracts/:id/rollback/:snapshot_id – admin rollback
 
 use axum::{
@@ -15,12 +15,13 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
     state::AppState,
+    upgrade_safety::{self, UpgradeSafetyReport},
 };
 use shared::{
     AuditActionType, AuditLogPage, ContractAuditLog, ContractSnapshot, FieldChange,
@@ -144,8 +145,6 @@ pub async fn export_history_csv(
     .await
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let mut csv =
-        String::from("id,contract_id,action_type,old_value,new_value,changed_by,timestamp\n");
     let mut csv = String::from("id,contract_id,action_type,old_value,new_value,changed_by,timestamp,previous_hash,hash,signature\n");
 
     for entry in &entries {
@@ -267,12 +266,21 @@ pub async fn verify_contract_history(
 
 // ─────────────────────────────────────────────────────────────────────────────
 // GET /api/contracts/:id/versions/:v1/diff/:v2
-// Computes a field-level diff between two snapshots.
+// Computes a field-level diff between two snapshots. When both snapshots name
+// a published version with an ABI on record, the storage layouts are compared
+// as well and an upgrade-safety verdict is attached.
 // ─────────────────────────────────────────────────────────────────────────────
+#[derive(Debug, Serialize)]
+pub struct VersionDiffResponse {
+    #[serde(flatten)]
+    pub diff: VersionDiff,
+    pub upgrade_safety: Option<UpgradeSafetyReport>,
+}
+
 pub async fn diff_versions(
     State(state): State<AppState>,
     Path((contract_id, v1, v2)): Path<(Uuid, i32, i32)>,
-) -> ApiResult<Json<VersionDiff>> {
+) -> ApiResult<Json<VersionDiffResponse>> {
     verify_contract_exists(&state, contract_id).await?;
 
     let snap_a: ContractSnapshot = sqlx::query_as(
@@ -316,7 +324,26 @@ pub async fn diff_versions(
         &snap_a.snapshot_data,
         &snap_b.snapshot_data,
     );
-    Ok(Json(diff))
+
+    let version_of = |snap: &ContractSnapshot| {
+        snap.snapshot_data
+            .get("version")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let upgrade_safety = match (version_of(&snap_a), version_of(&snap_b)) {
+        (Some(from), Some(to)) if from != to => {
+            upgrade_safety::analyze_versions(&state.db, contract_id, &from, &to, None)
+                .await
+                .map_err(|e| db_err("analyze upgrade safety", e))?
+        }
+        _ => None,
+    };
+
+    Ok(Json(VersionDiffResponse {
+        diff,
+        upgrade_safety,
+    }))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -391,19 +418,15 @@ pub async fn rollback_contract(
     .await
     .map_err(|e| db_err("apply rollback to contract", e))?;
 
-    // 5. Write audit log entry
-    let log_entry: ContractAuditLog = sqlx::query_as(
-        "INSERT INTO contract_audit_log
-               (contract_id, action_type, old_value, new_value, changed_by)
-         VALUES ($1, $2, $3, $4, $5)
-         RETURNING id, contract_id, action_type, old_value, new_value, changed_by, timestamp",
+    // 5. Write audit log entry, chained onto the existing log
+    let log_id = append_audit_entry(
+        &mut tx,
+        contract_id,
+        &AuditActionType::Rollback,
+        Some(&current_data),
+        Some(&snapshot.snapshot_data),
+        &req.changed_by,
     )
-    .bind(contract_id)
-    .bind(AuditActionType::Rollback)
-    .bind(&current_data)
-    .bind(&snapshot.snapshot_data)
-    .bind(&req.changed_by)
-    .fetch_one(&mut *tx)
     .await
     .map_err(|e| db_err("insert rollback audit log", e))?;
 
@@ -422,7 +445,7 @@ pub async fn rollback_contract(
     .bind(contract_id)
     .bind(next_ver)
     .bind(&snapshot.snapshot_data)
-    .bind(log_entry.id)
+    .bind(log_id)
     .execute(&mut *tx)
     .await
     .map_err(|e| db_err("insert post-rollback snapshot", e))?;
@@ -445,7 +468,7 @@ pub async fn rollback_contract(
         "contract_id": contract_id,
         "rolled_back_to_version": snapshot.version_number,
         "new_version": next_ver,
-        "audit_log_id": log_entry.id,
+        "audit_log_id": log_id,
     })))
 }
 
@@ -463,15 +486,59 @@ pub async fn log_contract_change(
     new_value: Option<serde_json::Value>,
     changed_by: &str,
 ) -> Result<Uuid, sqlx::Error> {
-    use sha2::{Sha256, Digest};
     let mut tx = db.begin().await?;
+    let log_id = append_audit_entry(
+        &mut tx,
+        contract_id,
+        &action_type,
+        old_value.as_ref(),
+        new_value.as_ref(),
+        changed_by,
+    )
+    .await?;
+
+    // If we have a new_value, persist a snapshot
+    if let Some(ref snap_data) = new_value {
+        let next_ver: i32 = sqlx::query_scalar("SELECT next_contract_version($1)")
+            .bind(contract_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO contract_snapshots
+                   (contract_id, version_number, snapshot_data, audit_log_id)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(contract_id)
+        .bind(next_ver)
+        .bind(snap_data)
+        .bind(log_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(log_id)
+}
+
+/// Append one entry to a contract's audit log inside `tx`, linking it to the
+/// previous entry's hash so `/history/verify` can check the chain.
+async fn append_audit_entry(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    contract_id: Uuid,
+    action_type: &AuditActionType,
+    old_value: Option<&serde_json::Value>,
+    new_value: Option<&serde_json::Value>,
+    changed_by: &str,
+) -> Result<Uuid, sqlx::Error> {
+    use sha2::{Digest, Sha256};
 
     // 1. Fetch the latest hash to use as previous_hash
     let prev_hash: Option<String> = sqlx::query_scalar(
         "SELECT hash FROM contract_audit_log WHERE contract_id = $1 ORDER BY timestamp DESC LIMIT 1"
     )
     .bind(contract_id)
-    .fetch_optional(&mut *tx)
+    .fetch_optional(&mut **tx)
     .await?;
 
     // 2. Compute new hash
@@ -482,7 +549,7 @@ pub async fn log_contract_change(
     hasher.update(contract_id.as_bytes());
     hasher.update(action_type.to_string().as_bytes());
     hasher.update(changed_by.as_bytes());
-    if let Some(nv) = &new_value {
+    if let Some(nv) = new_value {
         hasher.update(nv.to_string().as_bytes());
     }
     let new_hash = hex::encode(hasher.finalize());
@@ -496,38 +563,16 @@ pub async fn log_contract_change(
          RETURNING id",
     )
     .bind(contract_id)
-    .bind(&action_type)
-    .bind(&old_value)
-    .bind(&new_value)
+    .bind(action_type)
+    .bind(old_value)
+    .bind(new_value)
     .bind(changed_by)
     .bind(&prev_hash)
     .bind(&new_hash)
     .bind(&dummy_signature)
-    .fetch_one(&mut *tx)
+    .fetch_one(&mut **tx)
     .await?;
 
-
-    // If we have a new_value, persist a snapshot
-    if let Some(ref snap_data) = new_value {
-        let next_ver: i32 = sqlx::query_scalar("SELECT next_contract_version($1)")
-            .bind(contract_id)
-            .fetch_one(&mut *tx)
-            .await?;
-
-        sqlx::query(
-            "INSERT INTO contract_snapshots
-                   (contract_id, version_number, snapshot_data, audit_log_id)
-             VALUES ($1, $2, $3, $4)",
-        )
-        .bind(contract_id)
-        .bind(next_ver)
-        .bind(snap_data)
-        .bind(log_id)
-        .execute(&mut *tx)
-        .await?;
-    }
-
-    tx.commit().await?;
     Ok(log_id)
 }
 
@@ -1,96 +1,207 @@
 use axum::{
     extract::{Path, State},
+    http::StatusCode,
     Json,
 };
 use shared::models::{
     BatchCostEstimate, CostEstimate, CostEstimateRequest, CostForecast, CostOptimization,
+    MethodResourceProfile, RecordCostObservationRequest, SorobanResources,
 };
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
+    soroban_fees::{self, FeeConfig, StorageGrowth, STROOPS_PER_XLM},
     state::AppState,
 };
 
-// Stellar network constants (approximate)
-const STROOPS_PER_XLM: i64 = 10_000_000;
-const BASE_GAS_COST: i64 = 100_000; // stroops
-const STORAGE_COST_PER_KB: i64 = 50_000; // stroops
-const BANDWIDTH_COST_PER_KB: i64 = 10_000; // stroops
+/// Resources of a typical invocation, used for methods with no history
+const DEFAULT_RESOURCES: SorobanResources = SorobanResources {
+    instructions: 2_000_000,
+    read_entries: 4,
+    write_entries: 1,
+    read_bytes: 4_096,
+    write_bytes: 512,
+    events_bytes: 200,
+    transaction_size_bytes: 600,
+};
 
-pub async fn estimate_cost(
-    State(state): State<AppState>,
-    Path(contract_id): Path<Uuid>,
-    Json(req): Json<CostEstimateRequest>,
-) -> ApiResult<Json<CostEstimate>> {
-    let invocations = req.invocations.unwrap_or(1);
-    let storage_kb = req.storage_growth_kb.unwrap_or(0);
+fn db_err(operation: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = operation, error = ?err, "cost estimation database error");
+    ApiError::internal(format!("Database error while trying to {}", operation))
+}
+
+/// Fee settings for the contract's network: the indexer's latest snapshot,
+/// then the `SOROBAN_FEE_CONFIG` file, then built-in mainnet values.
+async fn fee_config(state: &AppState, contract_id: Uuid) -> ApiResult<(FeeConfig, String)> {
+    let network: String = sqlx::query_scalar("SELECT network::text FROM contracts WHERE id = $1")
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("load contract network", e))?
+        .ok_or_else(|| ApiError::not_found("ContractNotFound", "Contract not found"))?;
 
-    // Try to get historical data
-    let historical_gas = sqlx::query_scalar::<_, i64>(
-        "SELECT avg_gas_cost FROM cost_estimates WHERE contract_id = $1 AND method_name = $2",
+    let indexed = sqlx::query_as::<_, (serde_json::Value, i64)>(
+        r#"
+        SELECT settings, ledger_sequence FROM network_fee_configs
+        WHERE network::text = $1
+        ORDER BY ledger_sequence DESC
+        LIMIT 1
+        "#,
     )
-    .bind(contract_id)
-    .bind(&req.method_name)
+    .bind(&network)
     .fetch_optional(&state.db)
     .await
-    .unwrap_or(None);
+    .map_err(|e| db_err("load network fee config", e))?;
+
+    if let Some((settings, ledger)) = indexed {
+        match serde_json::from_value::<FeeConfig>(settings) {
+            Ok(cfg) => return Ok((cfg, format!("indexer (ledger {})", ledger))),
+            Err(err) => tracing::warn!(
+                network = %network,
+                error = %err,
+                "ignoring malformed indexed fee config"
+            ),
+        }
+    }
+    Ok(match soroban_fees::file_config(&network) {
+        Some(cfg) => (cfg, "file".to_string()),
+        None => (FeeConfig::default(), "default".to_string()),
+    })
+}
 
-    let gas_cost = historical_gas.unwrap_or(BASE_GAS_COST) * invocations;
-    let storage_cost = storage_kb * STORAGE_COST_PER_KB;
-    let bandwidth_cost = (storage_kb / 4) * BANDWIDTH_COST_PER_KB; // Estimate 4:1 ratio
+/// Per-invocation resources: from the request, else the method's recorded
+/// averages, else a typical invocation.
+async fn method_resources(
+    state: &AppState,
+    contract_id: Uuid,
+    req: &CostEstimateRequest,
+) -> ApiResult<(SorobanResources, &'static str)> {
+    if let Some(resources) = req.resources {
+        return Ok((resources, "request"));
+    }
+    let profile = load_profile(state, contract_id, &req.method_name).await?;
+    Ok(match profile {
+        Some(p) => (p.resources, "history"),
+        None => (DEFAULT_RESOURCES, "default"),
+    })
+}
 
-    let total_stroops = gas_cost + storage_cost + bandwidth_cost;
-    let total_xlm = total_stroops as f64 / STROOPS_PER_XLM as f64;
+async fn load_profile(
+    state: &AppState,
+    contract_id: Uuid,
+    method_name: &str,
+) -> ApiResult<Option<MethodResourceProfile>> {
+    type Row = (
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i32,
+        chrono::DateTime<chrono::Utc>,
+    );
+    let row = sqlx::query_as::<_, Row>(
+        r#"
+        SELECT avg_instructions, avg_read_entries, avg_write_entries,
+               avg_read_bytes, avg_write_bytes, avg_events_bytes, avg_tx_size_bytes,
+               resource_samples, last_updated
+        FROM cost_estimates
+        WHERE contract_id = $1 AND method_name = $2 AND resource_samples > 0
+        "#,
+    )
+    .bind(contract_id)
+    .bind(method_name)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("load method resource profile", e))?;
 
-    Ok(Json(CostEstimate {
-        method_name: req.method_name,
-        gas_cost,
-        storage_cost,
-        bandwidth_cost,
-        total_stroops,
-        total_xlm,
-        invocations,
+    Ok(row.map(|r| MethodResourceProfile {
+        method_name: method_name.to_string(),
+        resources: SorobanResources {
+            instructions: r.0.max(0) as u64,
+            read_entries: r.1.max(0) as u32,
+            write_entries: r.2.max(0) as u32,
+            read_bytes: r.3.max(0) as u32,
+            write_bytes: r.4.max(0) as u32,
+            events_bytes: r.5.max(0) as u32,
+            transaction_size_bytes: r.6.max(0) as u32,
+        },
+        samples: r.7,
+        last_updated: r.8,
     }))
 }
 
+fn validate(req: &CostEstimateRequest) -> ApiResult<()> {
+    if req.invocations.is_some_and(|n| n < 0) {
+        return Err(ApiError::bad_request(
+            "InvalidInvocations",
+            "invocations must be >= 0",
+        ));
+    }
+    if req.storage_growth_kb.is_some_and(|kb| kb < 0) {
+        return Err(ApiError::bad_request(
+            "InvalidStorageGrowth",
+            "storage_growth_kb must be >= 0",
+        ));
+    }
+    Ok(())
+}
+
+fn growth(req: &CostEstimateRequest, default_kb: i64) -> StorageGrowth {
+    StorageGrowth {
+        bytes: req.storage_growth_kb.unwrap_or(default_kb) * 1024,
+        durability: req.storage_durability.unwrap_or_default(),
+        rent_ledgers: req.rent_ledgers,
+    }
+}
+
+async fn estimate_one(
+    state: &AppState,
+    contract_id: Uuid,
+    req: CostEstimateRequest,
+    cfg: &FeeConfig,
+    cfg_source: &str,
+) -> ApiResult<CostEstimate> {
+    validate(&req)?;
+    let (resources, source) = method_resources(state, contract_id, &req).await?;
+    let mut estimate = soroban_fees::estimate(
+        req.method_name.clone(),
+        req.invocations.unwrap_or(1),
+        &resources,
+        &growth(&req, 0),
+        cfg,
+    );
+    estimate.resource_source = Some(source.to_string());
+    estimate.fee_config_source = Some(cfg_source.to_string());
+    Ok(estimate)
+}
+
+pub async fn estimate_cost(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<CostEstimateRequest>,
+) -> ApiResult<Json<CostEstimate>> {
+    let (cfg, cfg_source) = fee_config(&state, contract_id).await?;
+    let estimate = estimate_one(&state, contract_id, req, &cfg, &cfg_source).await?;
+    Ok(Json(estimate))
+}
+
 pub async fn batch_estimate(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Json(requests): Json<Vec<CostEstimateRequest>>,
 ) -> ApiResult<Json<BatchCostEstimate>> {
+    let (cfg, cfg_source) = fee_config(&state, contract_id).await?;
     let mut estimates = Vec::new();
     let mut total_stroops = 0i64;
 
     for req in requests {
-        let invocations = req.invocations.unwrap_or(1);
-        let storage_kb = req.storage_growth_kb.unwrap_or(0);
-
-        let historical_gas = sqlx::query_scalar::<_, i64>(
-            "SELECT avg_gas_cost FROM cost_estimates WHERE contract_id = $1 AND method_name = $2",
-        )
-        .bind(contract_id)
-        .bind(&req.method_name)
-        .fetch_optional(&state.db)
-        .await
-        .unwrap_or(None);
-
-        let gas_cost = historical_gas.unwrap_or(BASE_GAS_COST) * invocations;
-        let storage_cost = storage_kb * STORAGE_COST_PER_KB;
-        let bandwidth_cost = (storage_kb / 4) * BANDWIDTH_COST_PER_KB;
-
-        let estimate_total = gas_cost + storage_cost + bandwidth_cost;
-        total_stroops += estimate_total;
-
-        estimates.push(CostEstimate {
-            method_name: req.method_name,
-            gas_cost,
-            storage_cost,
-            bandwidth_cost,
-            total_stroops: estimate_total,
-            total_xlm: estimate_total as f64 / STROOPS_PER_XLM as f64,
-            invocations,
-        });
+        let estimate = estimate_one(&state, contract_id, req, &cfg, &cfg_source).await?;
+        total_stroops += estimate.total_stroops;
+        estimates.push(estimate);
     }
 
     Ok(Json(BatchCostEstimate {
@@ -127,7 +238,27 @@ pub async fn optimize_costs(
         optimized_cost = (optimized_cost as f64 * 0.92) as i64; // 8% savings
     }
 
-    let savings_percent = ((current_cost - optimized_cost) as f64 / current_cost as f64) * 100.0;
+    if let Some(fee) = estimate.per_invocation {
+        let resource_fee = (fee.non_refundable + fee.refundable).max(1) as f64;
+        // Read-entry fees dominate calls that touch many small entries
+        if fee.read_entries as f64 / resource_fee > 0.4 {
+            suggestions.push(
+                "Combine small ledger entries that are always read together into one entry"
+                    .to_string(),
+            );
+            optimized_cost = (optimized_cost as f64 * 0.90) as i64; // 10% savings
+        }
+        if fee.events as f64 / resource_fee > 0.2 {
+            suggestions.push("Emit smaller events; index details off-chain".to_string());
+            optimized_cost = (optimized_cost as f64 * 0.95) as i64; // 5% savings
+        }
+    }
+
+    let savings_percent = if current_cost > 0 {
+        ((current_cost - optimized_cost) as f64 / current_cost as f64) * 100.0
+    } else {
+        0.0
+    };
 
     Ok(Json(CostOptimization {
         current_cost,
@@ -142,29 +273,89 @@ pub async fn forecast_costs(
     Path(contract_id): Path<Uuid>,
     Json(req): Json<CostEstimateRequest>,
 ) -> ApiResult<Json<CostForecast>> {
+    validate(&req)?;
+    let (cfg, _) = fee_config(&state, contract_id).await?;
+    let (resources, _) = method_resources(&state, contract_id, &req).await?;
     let daily_invocations = req.invocations.unwrap_or(100);
-    let storage_kb = req.storage_growth_kb.unwrap_or(1);
+    let daily_growth = growth(&req, 1);
 
-    let historical_gas = sqlx::query_scalar::<_, i64>(
-        "SELECT avg_gas_cost FROM cost_estimates WHERE contract_id = $1 AND method_name = $2",
-    )
-    .bind(contract_id)
-    .bind(&req.method_name)
-    .fetch_optional(&state.db)
-    .await
-    .unwrap_or(None);
-
-    let gas_per_call = historical_gas.unwrap_or(BASE_GAS_COST);
-    let storage_cost = storage_kb * STORAGE_COST_PER_KB;
-    let bandwidth_cost = (storage_kb / 4) * BANDWIDTH_COST_PER_KB;
-
-    let daily_cost_stroops = (gas_per_call * daily_invocations) + storage_cost + bandwidth_cost;
-    let daily_cost_xlm = daily_cost_stroops as f64 / STROOPS_PER_XLM as f64;
+    // One day of invocations plus one day of storage growth
+    let daily = soroban_fees::estimate(
+        req.method_name.clone(),
+        daily_invocations,
+        &resources,
+        &daily_growth,
+        &cfg,
+    );
+    let daily_cost_xlm = daily.total_xlm;
 
     Ok(Json(CostForecast {
         daily_cost_xlm,
         monthly_cost_xlm: daily_cost_xlm * 30.0,
         yearly_cost_xlm: daily_cost_xlm * 365.0,
-        usage_pattern: format!("{} invocations/day, {} KB storage/day", daily_invocations, storage_kb),
+        usage_pattern: format!(
+            "{} invocations/day, {} KB storage/day",
+            daily_invocations,
+            daily_growth.bytes / 1024
+        ),
     }))
 }
+
+/// Fold the resources of a real invocation into the method's averages.
+pub async fn record_observation(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<RecordCostObservationRequest>,
+) -> ApiResult<(StatusCode, Json<MethodResourceProfile>)> {
+    if req.method_name.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidMethod",
+            "method_name must not be empty",
+        ));
+    }
+    let (cfg, _) = fee_config(&state, contract_id).await?;
+    let fee = soroban_fees::invocation_fee(&req.resources, &cfg);
+    let r = req.resources;
+
+    sqlx::query(
+        r#"
+        INSERT INTO cost_estimates (
+            contract_id, method_name, avg_gas_cost, avg_storage_bytes, sample_count,
+            avg_instructions, avg_read_entries, avg_write_entries, avg_read_bytes,
+            avg_write_bytes, avg_events_bytes, avg_tx_size_bytes, resource_samples, last_updated
+        ) VALUES ($1, $2, $3, $4, 1, $5, $6, $7, $8, $9, $10, $11, 1, NOW())
+        ON CONFLICT (contract_id, method_name) DO UPDATE SET
+            avg_gas_cost      = ROUND((cost_estimates.avg_gas_cost * cost_estimates.sample_count + EXCLUDED.avg_gas_cost)::numeric / (cost_estimates.sample_count + 1)),
+            avg_storage_bytes = ROUND((cost_estimates.avg_storage_bytes * cost_estimates.sample_count + EXCLUDED.avg_storage_bytes)::numeric / (cost_estimates.sample_count + 1)),
+            sample_count      = cost_estimates.sample_count + 1,
+            avg_instructions  = ROUND((COALESCE(cost_estimates.avg_instructions, 0)  * cost_estimates.resource_samples + EXCLUDED.avg_instructions)::numeric  / (cost_estimates.resource_samples + 1)),
+            avg_read_entries  = ROUND((COALESCE(cost_estimates.avg_read_entries, 0)  * cost_estimates.resource_samples + EXCLUDED.avg_read_entries)::numeric  / (cost_estimates.resource_samples + 1)),
+            avg_write_entries = ROUND((COALESCE(cost_estimates.avg_write_entries, 0) * cost_estimates.resource_samples + EXCLUDED.avg_write_entries)::numeric / (cost_estimates.resource_samples + 1)),
+            avg_read_bytes    = ROUND((COALESCE(cost_estimates.avg_read_bytes, 0)    * cost_estimates.resource_samples + EXCLUDED.avg_read_bytes)::numeric    / (cost_estimates.resource_samples + 1)),
+            avg_write_bytes   = ROUND((COALESCE(cost_estimates.avg_write_bytes, 0)   * cost_estimates.resource_samples + EXCLUDED.avg_write_bytes)::numeric   / (cost_estimates.resource_samples + 1)),
+            avg_events_bytes  = ROUND((COALESCE(cost_estimates.avg_events_bytes, 0)  * cost_estimates.resource_samples + EXCLUDED.avg_events_bytes)::numeric  / (cost_estimates.resource_samples + 1)),
+            avg_tx_size_bytes = ROUND((COALESCE(cost_estimates.avg_tx_size_bytes, 0) * cost_estimates.resource_samples + EXCLUDED.avg_tx_size_bytes)::numeric / (cost_estimates.resource_samples + 1)),
+            resource_samples  = cost_estimates.resource_samples + 1,
+            last_updated      = NOW()
+        "#,
+    )
+    .bind(contract_id)
+    .bind(&req.method_name)
+    .bind(fee.non_refundable + fee.refundable)
+    .bind(r.write_bytes as i64)
+    .bind(r.instructions as i64)
+    .bind(r.read_entries as i64)
+    .bind(r.write_entries as i64)
+    .bind(r.read_bytes as i64)
+    .bind(r.write_bytes as i64)
+    .bind(r.events_bytes as i64)
+    .bind(r.transaction_size_bytes as i64)
+    .execute(&state.db)
+    .await
+    .map_err(|e| db_err("record cost observation", e))?;
+
+    let profile = load_profile(&state, contract_id, &req.method_name)
+        .await?
+        .ok_or_else(|| ApiError::internal("Recorded observation was not found"))?;
+    Ok((StatusCode::CREATED, Json(profile)))
+}
@@ -20,4 +20,8 @@ pub fn cost_routes() -> Router<AppState> {
             "/api/contracts/:id/cost-estimate/forecast",
             post(cost_handlers::forecast_costs),
         )
+        .route(
+            "/api/contracts/:id/cost-estimate/observations",
+            post(cost_handlers::record_observation),
+        )
 }
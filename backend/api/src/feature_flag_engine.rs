@@ -0,0 +1,562 @@
+// api/src/feature_flag_engine.rs
+//
+// Targeting engine for feature flags. A flag is evaluated for a caller
+// context in a fixed order:
+//
+//   1. lifecycle  — inactive, sunset or expired flags are off
+//   2. prerequisites — every prerequisite flag must evaluate as required
+//   3. deny list, then allow list
+//   4. rules, in priority order; the first whose conditions all hold decides
+//   5. the flag's own rollout (full, or a percentage of callers)
+//
+// Percentages use consistent hashing: the caller address is hashed with the
+// flag ID into one of 10,000 buckets, so a caller always gets the same
+// answer and raising the percentage only ever adds callers.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use shared::{
+    FeatureFlag, FeatureFlagPrerequisite, FeatureFlagRule, FlagAttribute, FlagCondition,
+    FlagEvaluation, FlagEvaluationContext, FlagListType, FlagOperator, SemVer, VersionConstraint,
+};
+use uuid::Uuid;
+
+pub const BUCKETS: u32 = 10_000;
+
+/// Stable bucket in `0..BUCKETS` for a caller and flag.
+pub fn bucket(flag_id: Uuid, address: &str) -> u32 {
+    let digest = Sha256::digest(format!("{}:{}", flag_id, address).as_bytes());
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest[..8]);
+    (u64::from_be_bytes(prefix) % BUCKETS as u64) as u32
+}
+
+pub fn in_rollout(bucket: u32, percentage: i32) -> bool {
+    bucket < percentage.clamp(0, 100) as u32 * (BUCKETS / 100)
+}
+
+// ─────────────────────────────────────────────────────────
+// Conditions
+// ─────────────────────────────────────────────────────────
+
+pub fn validate_conditions(conditions: &[FlagCondition]) -> Result<(), String> {
+    for c in conditions {
+        if c.values.is_empty() || c.values.iter().any(|v| v.trim().is_empty()) {
+            return Err(format!(
+                "{:?} condition needs at least one value",
+                c.attribute
+            ));
+        }
+        match c.operator {
+            FlagOperator::In | FlagOperator::NotIn => {}
+            FlagOperator::SemverMatches | FlagOperator::SemverGte | FlagOperator::SemverLt => {
+                if c.attribute != FlagAttribute::ContractVersion {
+                    return Err("semver operators only apply to contract_version".into());
+                }
+                let valid = |v: &String| match c.operator {
+                    FlagOperator::SemverMatches => VersionConstraint::parse(v).is_some(),
+                    _ => SemVer::parse(v.trim()).is_some(),
+                };
+                if let Some(bad) = c.values.iter().find(|v| !valid(v)) {
+                    return Err(format!("'{}' is not a valid version", bad));
+                }
+                if c.operator != FlagOperator::SemverMatches && c.values.len() != 1 {
+                    return Err("semver_gte and semver_lt take exactly one version".into());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn attribute(ctx: &FlagEvaluationContext, attr: FlagAttribute) -> Option<&str> {
+    match attr {
+        FlagAttribute::Network => ctx.network.as_deref(),
+        FlagAttribute::ContractVersion => ctx.contract_version.as_deref(),
+        FlagAttribute::Publisher => ctx.publisher.as_deref(),
+    }
+}
+
+/// A condition on an attribute the context lacks only holds for `not_in`.
+pub fn condition_matches(condition: &FlagCondition, ctx: &FlagEvaluationContext) -> bool {
+    let Some(value) = attribute(ctx, condition.attribute).map(str::trim) else {
+        return condition.operator == FlagOperator::NotIn;
+    };
+    let listed = || {
+        condition
+            .values
+            .iter()
+            .any(|v| v.trim().eq_ignore_ascii_case(value))
+    };
+    let version = || SemVer::parse(value);
+    let bound = || {
+        condition
+            .values
+            .first()
+            .and_then(|v| SemVer::parse(v.trim()))
+    };
+    match condition.operator {
+        FlagOperator::In => listed(),
+        FlagOperator::NotIn => !listed(),
+        FlagOperator::SemverMatches => version().is_some_and(|v| {
+            condition
+                .values
+                .iter()
+                .filter_map(|c| VersionConstraint::parse(c))
+                .any(|c| c.matches(&v))
+        }),
+        FlagOperator::SemverGte => matches!((version(), bound()), (Some(v), Some(b)) if v >= b),
+        FlagOperator::SemverLt => matches!((version(), bound()), (Some(v), Some(b)) if v < b),
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────
+
+/// A flag with everything needed to evaluate it.
+#[derive(Debug, Clone)]
+pub struct FlagDefinition {
+    pub flag: FeatureFlag,
+    /// Sorted by priority
+    pub rules: Vec<FeatureFlagRule>,
+    pub allow: HashSet<String>,
+    pub deny: HashSet<String>,
+    pub prerequisites: Vec<FeatureFlagPrerequisite>,
+}
+
+/// Group rows loaded for one contract into flag definitions.
+pub fn assemble(
+    flags: Vec<FeatureFlag>,
+    mut rules: Vec<FeatureFlagRule>,
+    targets: Vec<(Uuid, String, FlagListType)>,
+    prerequisites: Vec<FeatureFlagPrerequisite>,
+) -> Vec<FlagDefinition> {
+    rules.sort_by_key(|r| r.priority);
+    flags
+        .into_iter()
+        .map(|flag| {
+            let id = flag.id;
+            let list = |kind: FlagListType| {
+                targets
+                    .iter()
+                    .filter(|(f, _, k)| *f == id && *k == kind)
+                    .map(|(_, address, _)| address.clone())
+                    .collect()
+            };
+            FlagDefinition {
+                rules: rules.iter().filter(|r| r.flag_id == id).cloned().collect(),
+                allow: list(FlagListType::Allow),
+                deny: list(FlagListType::Deny),
+                prerequisites: prerequisites
+                    .iter()
+                    .filter(|p| p.flag_id == id)
+                    .cloned()
+                    .collect(),
+                flag,
+            }
+        })
+        .collect()
+}
+
+/// Whether making `flag` depend on `prerequisite` would close a cycle.
+pub fn creates_cycle(edges: &[(Uuid, Uuid)], flag: Uuid, prerequisite: Uuid) -> bool {
+    let mut stack = vec![prerequisite];
+    let mut seen = HashSet::new();
+    while let Some(node) = stack.pop() {
+        if node == flag {
+            return true;
+        }
+        if seen.insert(node) {
+            stack.extend(edges.iter().filter(|(f, _)| *f == node).map(|(_, p)| *p));
+        }
+    }
+    false
+}
+
+struct Evaluator<'a> {
+    flags: HashMap<Uuid, &'a FlagDefinition>,
+    ctx: &'a FlagEvaluationContext,
+    now: DateTime<Utc>,
+    memo: HashMap<Uuid, FlagEvaluation>,
+    visiting: HashSet<Uuid>,
+}
+
+fn result(def: &FlagDefinition, enabled: bool, reason: impl Into<String>) -> FlagEvaluation {
+    FlagEvaluation {
+        flag: def.flag.name.clone(),
+        enabled,
+        reason: reason.into(),
+        rule_id: None,
+        bucket: None,
+    }
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&mut self, id: Uuid) -> FlagEvaluation {
+        if let Some(done) = self.memo.get(&id) {
+            return done.clone();
+        }
+        let def = self.flags[&id];
+        self.visiting.insert(id);
+        let evaluation = self.decide(def);
+        self.visiting.remove(&id);
+        self.memo.insert(id, evaluation.clone());
+        evaluation
+    }
+
+    fn decide(&mut self, def: &FlagDefinition) -> FlagEvaluation {
+        let flag = &def.flag;
+        if flag.state == "sunset" {
+            return result(def, false, "sunset");
+        }
+        if flag.sunset_at.is_some_and(|at| self.now >= at) {
+            return result(def, false, "flag_expired");
+        }
+        if flag.state != "active" {
+            return result(def, false, flag.state.clone());
+        }
+
+        for prereq in &def.prerequisites {
+            let id = prereq.prerequisite_flag_id;
+            if self.visiting.contains(&id) {
+                return result(def, false, "prerequisite_cycle");
+            }
+            let satisfied = self.flags.contains_key(&id)
+                && self.evaluate(id).enabled == prereq.required_enabled;
+            if !satisfied {
+                return result(
+                    def,
+                    false,
+                    format!("prerequisite_failed:{}", prereq.prerequisite_name),
+                );
+            }
+        }
+
+        let user = self.ctx.user.as_deref();
+        if let Some(user) = user {
+            if def.deny.contains(user) {
+                return result(def, false, "deny_list");
+            }
+            if def.allow.contains(user) {
+                return result(def, true, "allow_list");
+            }
+        }
+        let bucket = user.map(|u| bucket(flag.id, u));
+
+        for rule in &def.rules {
+            let Ok(conditions) =
+                serde_json::from_value::<Vec<FlagCondition>>(rule.conditions.clone())
+            else {
+                continue;
+            };
+            if !conditions.iter().all(|c| condition_matches(c, self.ctx)) {
+                continue;
+            }
+            // Matching callers outside the rule's percentage get the opposite
+            let (enabled, bucket) = if rule.rollout_percentage >= 100 {
+                (rule.serve_enabled, None)
+            } else {
+                let included = bucket.is_some_and(|b| in_rollout(b, rule.rollout_percentage));
+                (rule.serve_enabled == included, bucket)
+            };
+            return FlagEvaluation {
+                rule_id: Some(rule.id),
+                bucket,
+                ..result(def, enabled, "rule")
+            };
+        }
+
+        match flag.rollout_strategy.as_str() {
+            "full" => result(def, true, "active"),
+            "gradual" => match bucket {
+                Some(b) => FlagEvaluation {
+                    bucket: Some(b),
+                    ..result(
+                        def,
+                        in_rollout(b, flag.rollout_percentage),
+                        if in_rollout(b, flag.rollout_percentage) {
+                            "rollout"
+                        } else {
+                            "not_in_rollout"
+                        },
+                    )
+                },
+                None => result(def, false, "no_user"),
+            },
+            _ => result(def, false, "unknown_strategy"),
+        }
+    }
+}
+
+/// Evaluate every flag for one context, in the order given.
+pub fn evaluate_all(
+    defs: &[FlagDefinition],
+    ctx: &FlagEvaluationContext,
+    now: DateTime<Utc>,
+) -> Vec<FlagEvaluation> {
+    let mut evaluator = Evaluator {
+        flags: defs.iter().map(|d| (d.flag.id, d)).collect(),
+        ctx,
+        now,
+        memo: HashMap::new(),
+        visiting: HashSet::new(),
+    };
+    defs.iter().map(|d| evaluator.evaluate(d.flag.id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(name: &str, strategy: &str, pct: i32) -> FeatureFlag {
+        FeatureFlag {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            name: name.into(),
+            description: None,
+            state: "active".into(),
+            rollout_strategy: strategy.into(),
+            rollout_percentage: pct,
+            sunset_at: None,
+            created_by: None,
+            ab_enabled: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn def(flag: FeatureFlag) -> FlagDefinition {
+        FlagDefinition {
+            flag,
+            rules: vec![],
+            allow: HashSet::new(),
+            deny: HashSet::new(),
+            prerequisites: vec![],
+        }
+    }
+
+    fn rule(
+        flag_id: Uuid,
+        priority: i32,
+        conditions: Vec<FlagCondition>,
+        serve: bool,
+    ) -> FeatureFlagRule {
+        FeatureFlagRule {
+            id: Uuid::new_v4(),
+            flag_id,
+            priority,
+            description: None,
+            conditions: serde_json::to_value(conditions).unwrap(),
+            rollout_percentage: 100,
+            serve_enabled: serve,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn ctx(user: &str) -> FlagEvaluationContext {
+        FlagEvaluationContext {
+            user: Some(user.into()),
+            network: Some("testnet".into()),
+            contract_version: Some("1.4.2".into()),
+            publisher: Some("GPUB".into()),
+        }
+    }
+
+    #[test]
+    fn buckets_are_stable_uniform_and_monotonic() {
+        let id = Uuid::new_v4();
+        assert_eq!(bucket(id, "GABC"), bucket(id, "GABC"));
+
+        let users: Vec<String> = (0..10_000).map(|i| format!("G{:055}", i)).collect();
+        let at = |pct| {
+            users
+                .iter()
+                .filter(|u| in_rollout(bucket(id, u), pct))
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        let (quarter, half) = (at(25), at(50));
+        assert!((2300..2700).contains(&quarter.len()), "{}", quarter.len());
+        assert!(quarter.is_subset(&half));
+        assert_eq!(at(100).len(), users.len());
+        assert!(at(0).is_empty());
+    }
+
+    #[test]
+    fn conditions_cover_lists_and_versions() {
+        let c = ctx("GUSER");
+        let cond = |attribute, operator, values: &[&str]| FlagCondition {
+            attribute,
+            operator,
+            values: values.iter().map(|v| v.to_string()).collect(),
+        };
+        assert!(condition_matches(
+            &cond(
+                FlagAttribute::Network,
+                FlagOperator::In,
+                &["Mainnet", "TESTNET"]
+            ),
+            &c
+        ));
+        assert!(!condition_matches(
+            &cond(FlagAttribute::Publisher, FlagOperator::NotIn, &["GPUB"]),
+            &c
+        ));
+        assert!(condition_matches(
+            &cond(
+                FlagAttribute::ContractVersion,
+                FlagOperator::SemverMatches,
+                &["^1.2.0"]
+            ),
+            &c
+        ));
+        assert!(condition_matches(
+            &cond(
+                FlagAttribute::ContractVersion,
+                FlagOperator::SemverGte,
+                &["1.4.2"]
+            ),
+            &c
+        ));
+        assert!(!condition_matches(
+            &cond(
+                FlagAttribute::ContractVersion,
+                FlagOperator::SemverLt,
+                &["1.4.2"]
+            ),
+            &c
+        ));
+
+        let anonymous = FlagEvaluationContext::default();
+        assert!(condition_matches(
+            &cond(FlagAttribute::Network, FlagOperator::NotIn, &["mainnet"]),
+            &anonymous
+        ));
+        assert!(!condition_matches(
+            &cond(FlagAttribute::Network, FlagOperator::In, &["mainnet"]),
+            &anonymous
+        ));
+
+        assert!(validate_conditions(&[cond(
+            FlagAttribute::Network,
+            FlagOperator::SemverGte,
+            &["1.0.0"]
+        )])
+        .is_err());
+        assert!(validate_conditions(&[cond(
+            FlagAttribute::ContractVersion,
+            FlagOperator::SemverMatches,
+            &["^1.x"]
+        )])
+        .is_err());
+    }
+
+    #[test]
+    fn deny_beats_allow_and_allow_beats_rules() {
+        let mut d = def(flag("beta", "gradual", 0));
+        d.allow.insert("GALLOW".into());
+        d.allow.insert("GBOTH".into());
+        d.deny.insert("GBOTH".into());
+        let defs = vec![d];
+        let now = Utc::now();
+        assert_eq!(
+            evaluate_all(&defs, &ctx("GALLOW"), now)[0].reason,
+            "allow_list"
+        );
+        assert!(!evaluate_all(&defs, &ctx("GBOTH"), now)[0].enabled);
+        assert_eq!(
+            evaluate_all(&defs, &ctx("GOTHER"), now)[0].reason,
+            "not_in_rollout"
+        );
+        assert_eq!(
+            evaluate_all(&defs, &FlagEvaluationContext::default(), now)[0].reason,
+            "no_user"
+        );
+    }
+
+    #[test]
+    fn first_matching_rule_by_priority_decides() {
+        let f = flag("fees_v2", "full", 100);
+        let mainnet_off = rule(
+            f.id,
+            10,
+            vec![FlagCondition {
+                attribute: FlagAttribute::Network,
+                operator: FlagOperator::In,
+                values: vec!["mainnet".into()],
+            }],
+            false,
+        );
+        let old_versions_off = rule(
+            f.id,
+            20,
+            vec![FlagCondition {
+                attribute: FlagAttribute::ContractVersion,
+                operator: FlagOperator::SemverLt,
+                values: vec!["2.0.0".into()],
+            }],
+            false,
+        );
+        let defs = assemble(
+            vec![f],
+            vec![old_versions_off.clone(), mainnet_off],
+            vec![],
+            vec![],
+        );
+
+        let on_testnet = evaluate_all(&defs, &ctx("GUSER"), Utc::now());
+        assert!(!on_testnet[0].enabled);
+        assert_eq!(on_testnet[0].rule_id, Some(old_versions_off.id));
+
+        let upgraded = FlagEvaluationContext {
+            contract_version: Some("2.1.0".into()),
+            ..ctx("GUSER")
+        };
+        let r = &evaluate_all(&defs, &upgraded, Utc::now())[0];
+        assert!(r.enabled);
+        assert_eq!(r.reason, "active");
+    }
+
+    #[test]
+    fn prerequisites_gate_dependent_flags() {
+        let parent = flag("new_engine", "full", 100);
+        let mut child = def(flag("new_engine_ui", "full", 100));
+        child.prerequisites.push(FeatureFlagPrerequisite {
+            flag_id: child.flag.id,
+            prerequisite_flag_id: parent.id,
+            prerequisite_name: "new_engine".into(),
+            required_enabled: true,
+        });
+        let mut parent = def(parent);
+        let now = Utc::now();
+
+        let defs = vec![child.clone(), parent.clone()];
+        assert!(evaluate_all(&defs, &ctx("GUSER"), now)[0].enabled);
+
+        parent.flag.state = "inactive".into();
+        let defs = vec![child.clone(), parent.clone()];
+        assert_eq!(
+            evaluate_all(&defs, &ctx("GUSER"), now)[0].reason,
+            "prerequisite_failed:new_engine"
+        );
+
+        // A cycle that slipped past validation disables the flags instead of recursing
+        parent.flag.state = "active".into();
+        parent.prerequisites.push(FeatureFlagPrerequisite {
+            flag_id: parent.flag.id,
+            prerequisite_flag_id: child.flag.id,
+            prerequisite_name: "new_engine_ui".into(),
+            required_enabled: true,
+        });
+        let defs = vec![child.clone(), parent.clone()];
+        let results = evaluate_all(&defs, &ctx("GUSER"), now);
+        assert!(results.iter().all(|r| !r.enabled));
+
+        let edges = [(child.flag.id, parent.flag.id)];
+        assert!(creates_cycle(&edges, parent.flag.id, child.flag.id));
+        assert!(!creates_cycle(&edges, child.flag.id, Uuid::new_v4()));
+    }
+}
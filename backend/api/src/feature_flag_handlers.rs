@@ -17,6 +17,15 @@
 //   GET    /contracts/:id/feature-flags/:name/ab-test → get_ab_test
 //   POST   /contracts/:id/feature-flags/sweep         → sweep_expired
 //   GET    /contracts/:id/feature-flags/:name/check   → check_enabled
+//   POST   /contracts/:id/feature-flags/evaluate      → evaluate_flags
+//   GET    /contracts/:id/feature-flags/:name/targeting → get_targeting
+//   POST   /contracts/:id/feature-flags/:name/rules   → create_rule
+//   DELETE /contracts/:id/feature-flags/:name/rules/:rule_id → delete_rule
+//   PATCH  /contracts/:id/feature-flags/:name/targets → update_targets
+//   PUT    /contracts/:id/feature-flags/:name/prerequisites → set_prerequisite
+//   DELETE /contracts/:id/feature-flags/:name/prerequisites/:prerequisite → remove_prerequisite
+//
+// Evaluation itself (targeting rules, bucketing) lives in feature_flag_engine.rs.
 
 use axum::{
     extract::{Path, Query, State},
@@ -28,10 +37,16 @@ use chrono::Utc;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::state::AppState;
+use crate::{
+    feature_flag_engine::{self, FlagDefinition},
+    state::AppState,
+};
 use shared::{
-    AbTestConfig, CheckEnabledParams, ConfigureAbTestRequest, CreateFeatureFlagRequest,
-    FeatureFlag, FeatureFlagAnalytics, FeatureFlagListResponse, UpdateRolloutRequest,
+    AbTestConfig, BulkFlagEvaluationResponse, CheckEnabledParams, ConfigureAbTestRequest,
+    CreateFeatureFlagRequest, CreateFlagRuleRequest, FeatureFlag, FeatureFlagAnalytics,
+    FeatureFlagListResponse, FeatureFlagPrerequisite, FeatureFlagRule, FeatureFlagTargeting,
+    FlagEvaluationContext, FlagListType, SetFlagPrerequisiteRequest, UpdateFlagTargetsRequest,
+    UpdateRolloutRequest,
 };
 
 // ─────────────────────────────────────────────────────────
@@ -581,7 +596,7 @@ pub async fn sweep_expired(
 
 // ─────────────────────────────────────────────────────────
 // GET /contracts/:id/feature-flags/:name/check
-// Check if a flag is enabled for a given user address.
+// Check if a flag is enabled for a given caller context.
 // Used by clients before calling experimental contract functions.
 // ─────────────────────────────────────────────────────────
 
@@ -590,15 +605,9 @@ pub async fn check_enabled(
     Path((contract_id, name)): Path<(Uuid, String)>,
     Query(params): Query<CheckEnabledParams>,
 ) -> impl IntoResponse {
-    let flag = sqlx::query_as::<_, FeatureFlag>(
-        "SELECT * FROM feature_flags WHERE contract_id = $1 AND name = $2",
-    )
-    .bind(contract_id)
-    .bind(&name)
-    .fetch_optional(&state.db)
-    .await;
-
-    let flag = match flag {
+    // Prerequisites may live on any flag of the contract, so load them all
+    let (defs, ctx) = match load_evaluation_inputs(&state, contract_id, params).await {
+        Ok(inputs) => inputs,
         Err(e) => {
             tracing::error!("check_enabled DB error: {:?}", e);
             return (
@@ -607,116 +616,451 @@ pub async fn check_enabled(
             )
                 .into_response();
         }
-        // Unknown flags → disabled (backward compatible)
-        Ok(None) => {
+    };
+
+    // Unknown flags → disabled (backward compatible)
+    let Some(idx) = defs.iter().position(|d| d.flag.name == name) else {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "flag": name,
+                "enabled": false,
+                "reason": "flag_not_found"
+            })),
+        )
+            .into_response();
+    };
+
+    let now = Utc::now();
+    let evaluation = feature_flag_engine::evaluate_all(&defs, &ctx, now).swap_remove(idx);
+    let flag = &defs[idx].flag;
+
+    if evaluation.reason == "flag_expired" {
+        // Auto-sunset in DB (fire-and-forget)
+        let _ = sqlx::query(
+            "UPDATE feature_flags SET state = 'sunset', updated_at = NOW() WHERE id = $1 AND state != 'sunset'",
+        )
+        .bind(flag.id)
+        .execute(&state.db)
+        .await;
+    }
+
+    // Record this check in analytics (fire-and-forget)
+    record_checks(&state, &[(flag.id, evaluation.enabled)]).await;
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "flag":     name,
+            "enabled":  evaluation.enabled,
+            "strategy": flag.rollout_strategy,
+            "pct":      flag.rollout_percentage,
+            "reason":   evaluation.reason,
+            "rule_id":  evaluation.rule_id,
+            "bucket":   evaluation.bucket
+        })),
+    )
+        .into_response()
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /contracts/:id/feature-flags/evaluate
+// Evaluate every flag of a contract for one caller context.
+// Lets clients fetch all their flags in a single round trip.
+// ─────────────────────────────────────────────────────────
+
+pub async fn evaluate_flags(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(ctx): Json<FlagEvaluationContext>,
+) -> impl IntoResponse {
+    let (defs, ctx) = match load_evaluation_inputs(&state, contract_id, ctx).await {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            tracing::error!("evaluate_flags DB error: {:?}", e);
             return (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "flag": name,
-                    "enabled": false,
-                    "reason": "flag_not_found"
-                })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
             )
                 .into_response();
         }
-        Ok(Some(f)) => f,
     };
 
-    // Check auto-sunset
-    if let Some(sunset_at) = flag.sunset_at {
-        if Utc::now() >= sunset_at {
-            // Auto-sunset in DB (fire-and-forget)
-            let _ = sqlx::query(
-                "UPDATE feature_flags SET state = 'sunset', updated_at = NOW() WHERE id = $1 AND state != 'sunset'",
-            )
-            .bind(flag.id)
-            .execute(&state.db)
-            .await;
+    let now = Utc::now();
+    let evaluations = feature_flag_engine::evaluate_all(&defs, &ctx, now);
 
-            return (
-                StatusCode::OK,
-                Json(serde_json::json!({
-                    "flag": name,
-                    "enabled": false,
-                    "reason": "flag_expired"
-                })),
+    let checks: Vec<(Uuid, bool)> = defs
+        .iter()
+        .zip(&evaluations)
+        .map(|(d, e)| (d.flag.id, e.enabled))
+        .collect();
+    record_checks(&state, &checks).await;
+
+    (
+        StatusCode::OK,
+        Json(BulkFlagEvaluationResponse {
+            contract_id,
+            evaluations,
+            evaluated_at: now,
+        }),
+    )
+        .into_response()
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /contracts/:id/feature-flags/:name/targeting
+// Rules, allow/deny lists and prerequisites of a flag.
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_targeting(
+    State(state): State<AppState>,
+    Path((contract_id, name)): Path<(Uuid, String)>,
+) -> impl IntoResponse {
+    let flag = match fetch_flag(&state, contract_id, &name).await {
+        Ok(flag) => flag,
+        Err(resp) => return resp,
+    };
+    match load_targeting(&state, flag).await {
+        Ok(targeting) => (StatusCode::OK, Json(targeting)).into_response(),
+        Err(e) => {
+            tracing::error!("get_targeting DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
             )
-                .into_response();
+                .into_response()
         }
     }
+}
 
-    if flag.state == "inactive" || flag.state == "sunset" {
+// ─────────────────────────────────────────────────────────
+// POST /contracts/:id/feature-flags/:name/rules
+// Add a targeting rule. Without a priority it goes after the last rule.
+// ─────────────────────────────────────────────────────────
+
+pub async fn create_rule(
+    State(state): State<AppState>,
+    Path((contract_id, name)): Path<(Uuid, String)>,
+    Json(req): Json<CreateFlagRuleRequest>,
+) -> impl IntoResponse {
+    if req.conditions.is_empty() {
         return (
-            StatusCode::OK,
+            StatusCode::BAD_REQUEST,
             Json(serde_json::json!({
-                "flag": name,
-                "enabled": false,
-                "reason": flag.state
+                "error": "INVALID_RULE",
+                "message": "A rule needs at least one condition"
+            })),
+        )
+            .into_response();
+    }
+    if let Err(message) = feature_flag_engine::validate_conditions(&req.conditions) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "INVALID_RULE", "message": message })),
+        )
+            .into_response();
+    }
+    let pct = req.rollout_percentage.unwrap_or(100);
+    if pct > 100 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "INVALID_ROLLOUT",
+                "message": "rollout_percentage must be 0–100"
             })),
         )
             .into_response();
     }
 
-    // Apply rollout: deterministic hash of user address
-    let enabled = match flag.rollout_strategy.as_str() {
-        "full" => true,
-        "gradual" => {
-            let pct = flag.rollout_percentage as u64;
-            if let Some(ref user) = params.user {
-                // Simple deterministic hash: sum of bytes mod 100
-                let bucket: u64 = user
-                    .as_bytes()
-                    .iter()
-                    .fold(0u64, |acc, &b| acc.wrapping_add(b as u64))
-                    % 100;
-                bucket < pct
-            } else {
-                false // No user provided — treat as not in rollout
-            }
-        }
-        _ => false,
+    let flag = match fetch_flag(&state, contract_id, &name).await {
+        Ok(flag) => flag,
+        Err(resp) => return resp,
     };
 
-    // Record this check in analytics (fire-and-forget)
-    let _ = sqlx::query(
+    let row = sqlx::query_as::<_, FeatureFlagRule>(
         r#"
-        INSERT INTO feature_flag_analytics
-            (id, flag_id, total_checks, enabled_hits, disabled_hits,
-             first_check_at, last_check_at, unique_users_approx)
+        INSERT INTO feature_flag_rules
+            (id, flag_id, priority, description, conditions, rollout_percentage, serve_enabled)
         SELECT
-            gen_random_uuid(), ff.id,
-            1,
-            CASE WHEN $3 THEN 1 ELSE 0 END,
-            CASE WHEN $3 THEN 0 ELSE 1 END,
-            NOW(), NOW(),
-            1
-        FROM feature_flags ff
-        WHERE ff.contract_id = $1 AND ff.name = $2
-        ON CONFLICT (flag_id) DO UPDATE SET
-            total_checks       = feature_flag_analytics.total_checks + 1,
-            enabled_hits       = feature_flag_analytics.enabled_hits  + CASE WHEN $3 THEN 1 ELSE 0 END,
-            disabled_hits      = feature_flag_analytics.disabled_hits + CASE WHEN $3 THEN 0 ELSE 1 END,
-            last_check_at      = NOW(),
-            unique_users_approx = feature_flag_analytics.unique_users_approx + 1
+            gen_random_uuid(), $1,
+            COALESCE($2, (SELECT COALESCE(MAX(priority), 0) + 10 FROM feature_flag_rules WHERE flag_id = $1)),
+            $3, $4, $5, $6
+        RETURNING *
+        "#,
+    )
+    .bind(flag.id)
+    .bind(req.priority)
+    .bind(&req.description)
+    .bind(serde_json::to_value(&req.conditions).unwrap_or_default())
+    .bind(pct as i32)
+    .bind(req.serve_enabled.unwrap_or(true))
+    .fetch_one(&state.db)
+    .await;
+
+    match row {
+        Ok(rule) => (StatusCode::CREATED, Json(rule)).into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "PRIORITY_TAKEN",
+                "message": format!("Flag '{}' already has a rule at that priority", name)
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("create_rule DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// DELETE /contracts/:id/feature-flags/:name/rules/:rule_id
+// ─────────────────────────────────────────────────────────
+
+pub async fn delete_rule(
+    State(state): State<AppState>,
+    Path((contract_id, name, rule_id)): Path<(Uuid, String, Uuid)>,
+) -> impl IntoResponse {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM feature_flag_rules r
+        USING feature_flags f
+        WHERE r.id = $3 AND r.flag_id = f.id AND f.contract_id = $1 AND f.name = $2
         "#,
     )
     .bind(contract_id)
     .bind(&name)
-    .bind(enabled)
+    .bind(rule_id)
     .execute(&state.db)
     .await;
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "flag":     name,
-            "enabled":  enabled,
-            "strategy": flag.rollout_strategy,
-            "pct":      flag.rollout_percentage,
-            "reason":   if enabled { "active" } else { "not_in_rollout" }
-        })),
+    match result {
+        Ok(r) if r.rows_affected() == 0 => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "NOT_FOUND",
+                "message": format!("Rule {} not found on flag '{}'", rule_id, name)
+            })),
+        )
+            .into_response(),
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            tracing::error!("delete_rule DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// PATCH /contracts/:id/feature-flags/:name/targets
+// Add or remove addresses on the allow or deny list.
+// An address is on at most one list; adding it moves it.
+// ─────────────────────────────────────────────────────────
+
+pub async fn update_targets(
+    State(state): State<AppState>,
+    Path((contract_id, name)): Path<(Uuid, String)>,
+    Json(req): Json<UpdateFlagTargetsRequest>,
+) -> impl IntoResponse {
+    let invalid = req
+        .add
+        .iter()
+        .chain(&req.remove)
+        .find(|a| a.trim().is_empty() || a.len() > 56);
+    if let Some(address) = invalid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "INVALID_ADDRESS",
+                "message": format!("'{}' is not a valid address", address)
+            })),
+        )
+            .into_response();
+    }
+
+    let flag = match fetch_flag(&state, contract_id, &name).await {
+        Ok(flag) => flag,
+        Err(resp) => return resp,
+    };
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO feature_flag_targets (flag_id, address, list_type)
+            SELECT $1, UNNEST($2::varchar[]), $3
+            ON CONFLICT (flag_id, address) DO UPDATE SET
+                list_type  = EXCLUDED.list_type,
+                created_at = NOW()
+            "#,
+        )
+        .bind(flag.id)
+        .bind(&req.add)
+        .bind(req.list_type)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM feature_flag_targets WHERE flag_id = $1 AND list_type = $2 AND address = ANY($3)",
+        )
+        .bind(flag.id)
+        .bind(req.list_type)
+        .bind(&req.remove)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+    .await;
+
+    match result {
+        Ok(()) => get_targeting(State(state), Path((contract_id, name)))
+            .await
+            .into_response(),
+        Err(e) => {
+            tracing::error!("update_targets DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// PUT /contracts/:id/feature-flags/:name/prerequisites
+// Make this flag depend on another flag of the same contract.
+// ─────────────────────────────────────────────────────────
+
+pub async fn set_prerequisite(
+    State(state): State<AppState>,
+    Path((contract_id, name)): Path<(Uuid, String)>,
+    Json(req): Json<SetFlagPrerequisiteRequest>,
+) -> impl IntoResponse {
+    let flag = match fetch_flag(&state, contract_id, &name).await {
+        Ok(flag) => flag,
+        Err(resp) => return resp,
+    };
+    let prerequisite = match fetch_flag(&state, contract_id, &req.prerequisite).await {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let edges = sqlx::query_as::<_, (Uuid, Uuid)>(
+        r#"
+        SELECT p.flag_id, p.prerequisite_flag_id
+        FROM feature_flag_prerequisites p
+        JOIN feature_flags f ON f.id = p.flag_id
+        WHERE f.contract_id = $1
+        "#,
     )
-        .into_response()
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await;
+    let edges = match edges {
+        Ok(edges) => edges,
+        Err(e) => {
+            tracing::error!("set_prerequisite DB error: {:?}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response();
+        }
+    };
+    if feature_flag_engine::creates_cycle(&edges, flag.id, prerequisite.id) {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "PREREQUISITE_CYCLE",
+                "message": format!(
+                    "'{}' already depends on '{}'",
+                    prerequisite.name, flag.name
+                )
+            })),
+        )
+            .into_response();
+    }
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO feature_flag_prerequisites (flag_id, prerequisite_flag_id, required_enabled)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (flag_id, prerequisite_flag_id) DO UPDATE SET
+            required_enabled = EXCLUDED.required_enabled
+        "#,
+    )
+    .bind(flag.id)
+    .bind(prerequisite.id)
+    .bind(req.required_enabled.unwrap_or(true))
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(_) => get_targeting(State(state), Path((contract_id, name)))
+            .await
+            .into_response(),
+        Err(e) => {
+            tracing::error!("set_prerequisite DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// DELETE /contracts/:id/feature-flags/:name/prerequisites/:prerequisite
+// ─────────────────────────────────────────────────────────
+
+pub async fn remove_prerequisite(
+    State(state): State<AppState>,
+    Path((contract_id, name, prerequisite)): Path<(Uuid, String, String)>,
+) -> impl IntoResponse {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM feature_flag_prerequisites p
+        USING feature_flags f, feature_flags pf
+        WHERE p.flag_id = f.id AND p.prerequisite_flag_id = pf.id
+          AND f.contract_id = $1 AND f.name = $2
+          AND pf.contract_id = $1 AND pf.name = $3
+        "#,
+    )
+    .bind(contract_id)
+    .bind(&name)
+    .bind(&prerequisite)
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "NOT_FOUND",
+                "message": format!("Flag '{}' does not depend on '{}'", name, prerequisite)
+            })),
+        )
+            .into_response(),
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            tracing::error!("remove_prerequisite DB error: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response()
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────
@@ -769,4 +1113,197 @@ async fn transition_flag_state(
             .into_response(),
         Ok(Some(flag)) => (StatusCode::OK, Json(flag)).into_response(),
     }
-}
\ No newline at end of file
+}
+
+/// Fetch a flag by name, or the NOT_FOUND / DB_ERROR response to return.
+async fn fetch_flag(
+    state: &AppState,
+    contract_id: Uuid,
+    name: &str,
+) -> Result<FeatureFlag, axum::response::Response> {
+    let row = sqlx::query_as::<_, FeatureFlag>(
+        "SELECT * FROM feature_flags WHERE contract_id = $1 AND name = $2",
+    )
+    .bind(contract_id)
+    .bind(name)
+    .fetch_optional(&state.db)
+    .await;
+
+    match row {
+        Ok(Some(flag)) => Ok(flag),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "NOT_FOUND",
+                "message": format!("Flag '{}' not found", name)
+            })),
+        )
+            .into_response()),
+        Err(e) => {
+            tracing::error!("fetch_flag DB error: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "DB_ERROR" })),
+            )
+                .into_response())
+        }
+    }
+}
+
+async fn load_targeting(
+    state: &AppState,
+    flag: FeatureFlag,
+) -> Result<FeatureFlagTargeting, sqlx::Error> {
+    let rules = sqlx::query_as::<_, FeatureFlagRule>(
+        "SELECT * FROM feature_flag_rules WHERE flag_id = $1 ORDER BY priority",
+    )
+    .bind(flag.id)
+    .fetch_all(&state.db)
+    .await?;
+    let targets = sqlx::query_as::<_, (String, FlagListType)>(
+        "SELECT address, list_type FROM feature_flag_targets WHERE flag_id = $1 ORDER BY address",
+    )
+    .bind(flag.id)
+    .fetch_all(&state.db)
+    .await?;
+    let prerequisites = sqlx::query_as::<_, FeatureFlagPrerequisite>(
+        r#"
+        SELECT p.flag_id, p.prerequisite_flag_id, pf.name AS prerequisite_name, p.required_enabled
+        FROM feature_flag_prerequisites p
+        JOIN feature_flags pf ON pf.id = p.prerequisite_flag_id
+        WHERE p.flag_id = $1
+        ORDER BY pf.name
+        "#,
+    )
+    .bind(flag.id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let list = |kind: FlagListType| {
+        targets
+            .iter()
+            .filter(|(_, k)| *k == kind)
+            .map(|(address, _)| address.clone())
+            .collect()
+    };
+    Ok(FeatureFlagTargeting {
+        allow: list(FlagListType::Allow),
+        deny: list(FlagListType::Deny),
+        flag,
+        rules,
+        prerequisites,
+    })
+}
+
+/// Every flag definition of a contract, plus the caller context with
+/// missing attributes filled in from the contract itself.
+async fn load_evaluation_inputs(
+    state: &AppState,
+    contract_id: Uuid,
+    mut ctx: FlagEvaluationContext,
+) -> Result<(Vec<FlagDefinition>, FlagEvaluationContext), sqlx::Error> {
+    let flags = sqlx::query_as::<_, FeatureFlag>(
+        "SELECT * FROM feature_flags WHERE contract_id = $1 ORDER BY name",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await?;
+    let rules = sqlx::query_as::<_, FeatureFlagRule>(
+        r#"
+        SELECT r.* FROM feature_flag_rules r
+        JOIN feature_flags f ON f.id = r.flag_id
+        WHERE f.contract_id = $1
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await?;
+    let targets = sqlx::query_as::<_, (Uuid, String, FlagListType)>(
+        r#"
+        SELECT t.flag_id, t.address, t.list_type FROM feature_flag_targets t
+        JOIN feature_flags f ON f.id = t.flag_id
+        WHERE f.contract_id = $1
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await?;
+    let prerequisites = sqlx::query_as::<_, FeatureFlagPrerequisite>(
+        r#"
+        SELECT p.flag_id, p.prerequisite_flag_id, pf.name AS prerequisite_name, p.required_enabled
+        FROM feature_flag_prerequisites p
+        JOIN feature_flags pf ON pf.id = p.prerequisite_flag_id
+        WHERE pf.contract_id = $1
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    if ctx.network.is_none() || ctx.contract_version.is_none() || ctx.publisher.is_none() {
+        let defaults = sqlx::query_as::<_, (String, Option<String>, Option<String>)>(
+            r#"
+            SELECT c.network::text, p.stellar_address,
+                   (SELECT v.version FROM contract_versions v
+                    WHERE v.contract_id = c.id
+                    ORDER BY v.created_at DESC LIMIT 1)
+            FROM contracts c
+            LEFT JOIN publishers p ON p.id = c.publisher_id
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await?;
+        if let Some((network, publisher, version)) = defaults {
+            ctx.network.get_or_insert(network);
+            if ctx.publisher.is_none() {
+                ctx.publisher = publisher;
+            }
+            if ctx.contract_version.is_none() {
+                ctx.contract_version = version;
+            }
+        }
+    }
+
+    Ok((
+        feature_flag_engine::assemble(flags, rules, targets, prerequisites),
+        ctx,
+    ))
+}
+
+/// Count flag checks in analytics. Failures are logged, never surfaced.
+async fn record_checks(state: &AppState, checks: &[(Uuid, bool)]) {
+    if checks.is_empty() {
+        return;
+    }
+    let (ids, enabled): (Vec<Uuid>, Vec<bool>) = checks.iter().copied().unzip();
+    let result = sqlx::query(
+        r#"
+        INSERT INTO feature_flag_analytics
+            (id, flag_id, total_checks, enabled_hits, disabled_hits,
+             first_check_at, last_check_at, unique_users_approx)
+        SELECT
+            gen_random_uuid(), c.flag_id,
+            1,
+            CASE WHEN c.enabled THEN 1 ELSE 0 END,
+            CASE WHEN c.enabled THEN 0 ELSE 1 END,
+            NOW(), NOW(),
+            1
+        FROM UNNEST($1::uuid[], $2::bool[]) AS c(flag_id, enabled)
+        ON CONFLICT (flag_id) DO UPDATE SET
+            total_checks       = feature_flag_analytics.total_checks + 1,
+            enabled_hits       = feature_flag_analytics.enabled_hits  + EXCLUDED.enabled_hits,
+            disabled_hits      = feature_flag_analytics.disabled_hits + EXCLUDED.disabled_hits,
+            last_check_at      = NOW(),
+            unique_users_approx = feature_flag_analytics.unique_users_approx + 1
+        "#,
+    )
+    .bind(&ids)
+    .bind(&enabled)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("record_checks DB error: {:?}", e);
+    }
+}
@@ -9,15 +9,17 @@
 //       .with_state(state);
 
 use axum::{
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 
 use crate::{
     feature_flag_handlers::{
-        check_enabled, configure_ab_test, create_flag, disable_flag,
-        enable_flag, get_ab_test, get_analytics, get_flag, list_flags,
-        sunset_flag, sweep_expired, update_rollout,
+        check_enabled, configure_ab_test, create_flag, create_rule, delete_rule,
+        disable_flag, enable_flag, evaluate_flags, get_ab_test, get_analytics,
+        get_flag, get_targeting, list_flags, remove_prerequisite,
+        set_prerequisite, sunset_flag, sweep_expired, update_rollout,
+        update_targets,
     },
     state::AppState,
 };
@@ -34,6 +36,11 @@ pub fn feature_flag_router() -> Router<AppState> {
             "/contracts/:id/feature-flags/sweep",
             post(sweep_expired),
         )
+        // Batch: evaluate every flag for one caller context
+        .route(
+            "/contracts/:id/feature-flags/evaluate",
+            post(evaluate_flags),
+        )
         // Single flag: get
         .route(
             "/contracts/:id/feature-flags/:name",
@@ -72,4 +79,29 @@ pub fn feature_flag_router() -> Router<AppState> {
             "/contracts/:id/feature-flags/:name/check",
             get(check_enabled),
         )
-}
\ No newline at end of file
+        // Targeting: rules, allow/deny lists, prerequisites
+        .route(
+            "/contracts/:id/feature-flags/:name/targeting",
+            get(get_targeting),
+        )
+        .route(
+            "/contracts/:id/feature-flags/:name/rules",
+            post(create_rule),
+        )
+        .route(
+            "/contracts/:id/feature-flags/:name/rules/:rule_id",
+            delete(delete_rule),
+        )
+        .route(
+            "/contracts/:id/feature-flags/:name/targets",
+            patch(update_targets),
+        )
+        .route(
+            "/contracts/:id/feature-flags/:name/prerequisites",
+            put(set_prerequisite),
+        )
+        .route(
+            "/contracts/:id/feature-flags/:name/prerequisites/:prerequisite",
+            delete(remove_prerequisite),
+        )
+}
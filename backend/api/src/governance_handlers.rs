@@ -5,26 +5,186 @@ use axum::{
 };
 use chrono::{Duration, Utc};
 use shared::models::{
-    CastVoteRequest, CreateProposalRequest, GovernanceProposal, GovernanceVote, ProposalResults,
-    ProposalStatus, VoteDelegation,
+    CancelExecutionRequest, CastVoteRequest, CreateGovernanceProposalRequest,
+    ExecuteProposalRequest, GovernanceProposal, GovernanceProposalStatus, GovernanceVote,
+    ProposalExecution, ProposalExecutionStatus, ProposalResults, ProposalSource, ProposalTally,
+    VoteChoice, VoteDelegation,
 };
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
+    governance_tally::{self, TallyInput},
+    namespace, proposal_execution,
     state::AppState,
 };
 
+/// The statement a publisher's Stellar account signs to cast a vote. It
+/// names the proposal and the choice, so a signature cannot be replayed
+/// onto another proposal or flipped to a different choice.
+pub fn vote_statement(proposal_id: Uuid, voter: Uuid, choice: VoteChoice) -> String {
+    format!(
+        "soroban-registry governance vote\nproposal: {}\nvoter: {}\nchoice: {}",
+        proposal_id,
+        voter,
+        serde_json::to_value(choice)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    )
+}
+
+async fn contract_publisher(state: &AppState, contract_id: Uuid) -> ApiResult<Uuid> {
+    sqlx::query_scalar("SELECT publisher_id FROM contracts WHERE id = $1")
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))
+}
+
+async fn fetch_proposal(state: &AppState, proposal_id: Uuid) -> ApiResult<GovernanceProposal> {
+    sqlx::query_as::<_, GovernanceProposal>("SELECT * FROM governance_proposals WHERE id = $1")
+        .bind(proposal_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("proposal", "Proposal not found"))
+}
+
+/// Token balances as of `voting_starts_at`: raw balance per registered
+/// publisher, plus the balances of every holder for the eligible total.
+async fn balance_snapshot(
+    state: &AppState,
+    proposal: &GovernanceProposal,
+) -> ApiResult<(HashMap<Uuid, i128>, Vec<i128>)> {
+    let Some(token_contract_id) = proposal.token_contract_id else {
+        return Ok((HashMap::new(), Vec::new()));
+    };
+
+    let rows: Vec<(String, Option<Uuid>)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (h.holder) h.balance::TEXT, p.id
+        FROM token_balance_history h
+        LEFT JOIN publishers p ON p.stellar_address = h.holder
+        WHERE h.token_contract_id = $1 AND h.ledger_closed_at <= $2
+        ORDER BY h.holder, h.ledger_closed_at DESC, h.ledger_sequence DESC
+        "#,
+    )
+    .bind(token_contract_id)
+    .bind(proposal.voting_starts_at)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+
+    let mut by_publisher = HashMap::new();
+    let mut holders = Vec::with_capacity(rows.len());
+    for (balance, publisher) in rows {
+        let balance: i128 = balance
+            .parse()
+            .map_err(|_| ApiError::internal(format!("Invalid indexed balance: {}", balance)))?;
+        holders.push(balance);
+        if let Some(publisher) = publisher {
+            by_publisher.insert(publisher, balance);
+        }
+    }
+    Ok((by_publisher, holders))
+}
+
+/// Delegations active at `voting_starts_at`. A contract-specific delegation
+/// takes precedence over a registry-wide one.
+async fn delegation_snapshot(
+    state: &AppState,
+    proposal: &GovernanceProposal,
+) -> ApiResult<HashMap<Uuid, Uuid>> {
+    let rows: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (delegator) delegator, delegate
+        FROM vote_delegations
+        WHERE (contract_id = $1 OR contract_id IS NULL)
+          AND created_at <= $2
+          AND (revoked_at IS NULL OR revoked_at > $2)
+        ORDER BY delegator, (contract_id IS NULL), created_at DESC
+        "#,
+    )
+    .bind(proposal.contract_id)
+    .bind(proposal.voting_starts_at)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+
+    Ok(rows.into_iter().collect())
+}
+
+async fn compute_tally(state: &AppState, proposal: &GovernanceProposal) -> ApiResult<ProposalTally> {
+    let votes: Vec<(Uuid, VoteChoice)> =
+        sqlx::query_as("SELECT voter, vote_choice FROM governance_votes WHERE proposal_id = $1")
+            .bind(proposal.id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+
+    let (balances, holder_balances) = balance_snapshot(state, proposal).await?;
+    let delegations = delegation_snapshot(state, proposal).await?;
+
+    Ok(governance_tally::tally(&TallyInput {
+        model: proposal.governance_model,
+        votes: &votes,
+        balances: &balances,
+        holder_balances: &holder_balances,
+        delegations: &delegations,
+        quorum_required: proposal.quorum_required,
+        approval_threshold: proposal.approval_threshold,
+    }))
+}
+
 pub async fn create_proposal(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
-    Json(req): Json<CreateProposalRequest>,
+    Json(req): Json<CreateGovernanceProposalRequest>,
 ) -> ApiResult<Json<GovernanceProposal>> {
-    let contract = sqlx::query!("SELECT publisher_id FROM contracts WHERE id = $1", contract_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))?;
+    let publisher_id = contract_publisher(&state, contract_id).await?;
+
+    if req.governance_model.is_token_based() {
+        let token_contract_id = req.token_contract_id.ok_or_else(|| {
+            ApiError::bad_request(
+                "token_required",
+                "Token-weighted and quadratic proposals require token_contract_id",
+            )
+        })?;
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contracts WHERE id = $1)")
+                .bind(token_contract_id)
+                .fetch_one(&state.db)
+                .await
+                .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+        if !exists {
+            return Err(ApiError::not_found("token_contract", "Token contract not found"));
+        }
+    }
+
+    let quorum_required = req.quorum_required.unwrap_or(50);
+    let approval_threshold = req.approval_threshold.unwrap_or(50);
+    if quorum_required < 0
+        || (req.governance_model.is_token_based() && quorum_required > 100)
+        || !(0..=100).contains(&approval_threshold)
+    {
+        return Err(ApiError::bad_request(
+            "invalid_thresholds",
+            "quorum_required and approval_threshold must be percentages between 0 and 100",
+        ));
+    }
+    if req.execution_delay_hours.is_some_and(|h| h < 0) {
+        return Err(ApiError::bad_request(
+            "invalid_execution_delay",
+            "execution_delay_hours cannot be negative",
+        ));
+    }
+    if let Some(action) = &req.action {
+        proposal_execution::validate_action(action)
+            .map_err(|msg| ApiError::bad_request("invalid_action", msg))?;
+    }
 
     let now = Utc::now();
     let voting_starts_at = now;
@@ -33,19 +193,28 @@ pub async fn create_proposal(
     let proposal = sqlx::query_as::<_, GovernanceProposal>(
         r#"
         INSERT INTO governance_proposals 
-        (contract_id, title, description, governance_model, proposer, voting_starts_at, voting_ends_at, execution_delay_hours)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        (contract_id, title, description, governance_model, proposer, status, voting_starts_at, voting_ends_at,
+         execution_delay_hours, token_contract_id, quorum_required, approval_threshold, action)
+        VALUES ($1, $2, $3, $4, $5, 'active', $6, $7, $8, $9, $10, $11, $12)
         RETURNING *
         "#,
     )
     .bind(contract_id)
     .bind(&req.title)
     .bind(&req.description)
-    .bind(&req.governance_model)
-    .bind(contract.publisher_id)
+    .bind(req.governance_model)
+    .bind(publisher_id)
     .bind(voting_starts_at)
     .bind(voting_ends_at)
     .bind(req.execution_delay_hours)
+    .bind(req.token_contract_id.filter(|_| req.governance_model.is_token_based()))
+    .bind(quorum_required)
+    .bind(approval_threshold)
+    .bind(
+        req.action
+            .as_ref()
+            .map(|a| serde_json::to_value(a).unwrap_or_default()),
+    )
     .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(format!("Failed to create proposal: {}", e)))?;
@@ -89,20 +258,52 @@ pub async fn cast_vote(
     Path(proposal_id): Path<Uuid>,
     Json(req): Json<CastVoteRequest>,
 ) -> ApiResult<Json<GovernanceVote>> {
-    let proposal = sqlx::query_as::<_, GovernanceProposal>(
-        "SELECT * FROM governance_proposals WHERE id = $1",
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+
+    // The voter is whoever holds the publisher's Stellar account, not
+    // whatever id the body names
+    let voter_account: String =
+        sqlx::query_scalar("SELECT stellar_address FROM publishers WHERE id = $1")
+            .bind(req.voter)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("voter", "Voter is not a registered publisher"))?;
+    namespace::verify_account_signature(
+        &voter_account,
+        &vote_statement(proposal_id, req.voter, req.vote_choice),
+        &req.signature,
     )
-    .bind(proposal_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-    .ok_or_else(|| ApiError::not_found("proposal", "Proposal not found"))?;
+    .map_err(|e| {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "invalid_vote_signature",
+            format!("Vote signature {}", e),
+        )
+    })?;
 
-    // Get voter (use proposer as placeholder)
-    let voter_id = proposal.proposer;
+    let now = Utc::now();
+    if !matches!(
+        proposal.status,
+        GovernanceProposalStatus::Pending | GovernanceProposalStatus::Active
+    ) || now < proposal.voting_starts_at
+        || now >= proposal.voting_ends_at
+    {
+        return Err(ApiError::bad_request(
+            "voting_closed",
+            "Proposal is not open for voting",
+        ));
+    }
 
-    // Calculate voting power (simplified)
-    let voting_power = 1i64;
+    // Own power only; delegated power depends on who else votes and is
+    // resolved when the proposal is tallied.
+    let voting_power = if proposal.governance_model.is_token_based() {
+        let (balances, _) = balance_snapshot(&state, &proposal).await?;
+        let balance = balances.get(&req.voter).copied().unwrap_or_default();
+        governance_tally::weight(proposal.governance_model, balance)
+    } else {
+        1
+    };
 
     let vote = sqlx::query_as::<_, GovernanceVote>(
         r#"
@@ -114,9 +315,9 @@ pub async fn cast_vote(
         "#,
     )
     .bind(proposal_id)
-    .bind(voter_id)
-    .bind(&req.vote_choice)
-    .bind(voting_power)
+    .bind(req.voter)
+    .bind(req.vote_choice)
+    .bind(i64::try_from(voting_power).unwrap_or(i64::MAX))
     .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(format!("Failed to cast vote: {}", e)))?;
@@ -124,84 +325,181 @@ pub async fn cast_vote(
     Ok(Json(vote))
 }
 
+/// Live tally while voting is open; the frozen tally once the proposal is closed.
 pub async fn get_proposal_results(
     State(state): State<AppState>,
     Path(proposal_id): Path<Uuid>,
 ) -> ApiResult<Json<ProposalResults>> {
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+
+    let tally = match proposal
+        .final_tally
+        .clone()
+        .and_then(|t| serde_json::from_value::<ProposalTally>(t).ok())
+    {
+        Some(tally) => tally,
+        None => compute_tally(&state, &proposal).await?,
+    };
+
+    Ok(Json(ProposalResults { proposal, tally }))
+}
+
+/// Close voting once `voting_ends_at` has passed, enforcing quorum and
+/// approval threshold, and freeze the tally on the proposal.
+pub async fn close_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+) -> ApiResult<Json<ProposalResults>> {
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+
+    if !matches!(
+        proposal.status,
+        GovernanceProposalStatus::Pending | GovernanceProposalStatus::Active
+    ) {
+        return Err(ApiError::bad_request(
+            "already_closed",
+            "Proposal voting is already closed",
+        ));
+    }
+    if Utc::now() < proposal.voting_ends_at {
+        return Err(ApiError::bad_request(
+            "voting_open",
+            "Voting period has not ended yet",
+        ));
+    }
+
+    let tally = compute_tally(&state, &proposal).await?;
+    let status = if tally.approved {
+        GovernanceProposalStatus::Passed
+    } else {
+        GovernanceProposalStatus::Rejected
+    };
+
     let proposal = sqlx::query_as::<_, GovernanceProposal>(
-        "SELECT * FROM governance_proposals WHERE id = $1",
+        r#"
+        UPDATE governance_proposals
+        SET status = $1, closed_at = $2, final_tally = $3
+        WHERE id = $4 AND status IN ('pending', 'active')
+        RETURNING *
+        "#,
     )
+    .bind(status)
+    .bind(Utc::now())
+    .bind(serde_json::to_value(&tally).unwrap_or_default())
     .bind(proposal_id)
     .fetch_optional(&state.db)
     .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-    .ok_or_else(|| ApiError::not_found("proposal", "Proposal not found"))?;
+    .map_err(|e| ApiError::internal(format!("Failed to close proposal: {}", e)))?
+    .ok_or_else(|| ApiError::bad_request("already_closed", "Proposal voting is already closed"))?;
 
-    let votes = sqlx::query!(
-        r#"
-        SELECT 
-            SUM(CASE WHEN vote_choice = 'for' THEN voting_power ELSE 0 END) as votes_for,
-            SUM(CASE WHEN vote_choice = 'against' THEN voting_power ELSE 0 END) as votes_against,
-            SUM(CASE WHEN vote_choice = 'abstain' THEN voting_power ELSE 0 END) as votes_abstain,
-            SUM(voting_power) as total_votes
-        FROM governance_votes 
-        WHERE proposal_id = $1
-        "#,
-        proposal_id
+    if proposal.status == GovernanceProposalStatus::Passed {
+        queue_execution(&state, &proposal).await?;
+    }
+
+    Ok(Json(ProposalResults { proposal, tally }))
+}
+
+/// Put a passed proposal's action in the timelock queue. Proposals that
+/// passed before they were queued automatically are queued on first use.
+async fn queue_execution(
+    state: &AppState,
+    proposal: &GovernanceProposal,
+) -> ApiResult<ProposalExecution> {
+    let action = proposal_execution::parse_action(proposal.action.as_ref())
+        .map_err(|e| ApiError::internal(format!("Stored proposal action is invalid: {}", e)))?;
+    let approved_at = proposal.closed_at.unwrap_or(proposal.voting_ends_at);
+
+    proposal_execution::queue(
+        &state.db,
+        ProposalSource::Governance,
+        proposal.id,
+        Some(proposal.contract_id),
+        action.as_ref(),
+        proposal_execution::eta(approved_at, proposal.execution_delay_hours),
     )
-    .fetch_one(&state.db)
     .await
-    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
-
-    let votes_for = votes.votes_for.unwrap_or(0);
-    let votes_against = votes.votes_against.unwrap_or(0);
-    let votes_abstain = votes.votes_abstain.unwrap_or(0);
-    let total_votes = votes.total_votes.unwrap_or(0);
+    .map_err(|e| ApiError::internal(format!("Failed to queue proposal: {}", e)))
+}
 
-    let quorum_met = total_votes >= proposal.quorum_required as i64;
-    let approval_pct = if total_votes > 0 {
-        (votes_for * 100) / total_votes
-    } else {
-        0
-    };
-    let approved = quorum_met && approval_pct >= proposal.approval_threshold as i64;
-
-    Ok(Json(ProposalResults {
-        proposal,
-        votes_for,
-        votes_against,
-        votes_abstain,
-        total_votes,
-        quorum_met,
-        approved,
-    }))
+pub async fn get_execution(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+) -> ApiResult<Json<ProposalExecution>> {
+    proposal_execution::fetch_execution(&state.db, ProposalSource::Governance, proposal_id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("execution", "Proposal has not been queued"))
 }
 
+/// Apply a passed proposal's action once its timelock has expired.
 pub async fn execute_proposal(
     State(state): State<AppState>,
     Path(proposal_id): Path<Uuid>,
-) -> ApiResult<StatusCode> {
-    let results = get_proposal_results(State(state.clone()), Path(proposal_id))
-        .await?
-        .0;
+    body: Option<Json<ExecuteProposalRequest>>,
+) -> ApiResult<Json<ProposalExecution>> {
+    let proposal = fetch_proposal(&state, proposal_id).await?;
 
-    if !results.approved {
+    if proposal.status != GovernanceProposalStatus::Passed {
         return Err(ApiError::bad_request(
             "not_approved",
             "Proposal not approved",
         ));
     }
 
-    sqlx::query(
-        "UPDATE governance_proposals SET status = 'executed', executed_at = $1 WHERE id = $2",
+    queue_execution(&state, &proposal).await?;
+    let executed_by = body
+        .map(|Json(req)| req.executed_by)
+        .unwrap_or_else(|| "anonymous".to_string());
+    let execution = proposal_execution::execute(
+        &state,
+        ProposalSource::Governance,
+        proposal_id,
+        &executed_by,
     )
-    .bind(Utc::now())
-    .bind(proposal_id)
-    .execute(&state.db)
-    .await
-    .map_err(|e| ApiError::internal(format!("Failed to execute proposal: {}", e)))?;
+    .await?;
 
-    Ok(StatusCode::NO_CONTENT)
+    if execution.status == ProposalExecutionStatus::Executed {
+        sqlx::query(
+            "UPDATE governance_proposals SET status = 'executed', executed_at = $1 WHERE id = $2",
+        )
+        .bind(execution.executed_at)
+        .bind(proposal_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to execute proposal: {}", e)))?;
+    }
+
+    Ok(Json(execution))
+}
+
+/// Withdraw a passed proposal from the queue before its timelock expires.
+pub async fn cancel_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+    Json(req): Json<CancelExecutionRequest>,
+) -> ApiResult<Json<ProposalExecution>> {
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+
+    if proposal.status != GovernanceProposalStatus::Passed {
+        return Err(ApiError::bad_request(
+            "not_approved",
+            "Only passed proposals waiting for execution can be cancelled",
+        ));
+    }
+
+    queue_execution(&state, &proposal).await?;
+    let execution =
+        proposal_execution::cancel(&state.db, ProposalSource::Governance, proposal_id, &req)
+            .await?;
+
+    sqlx::query("UPDATE governance_proposals SET status = 'cancelled' WHERE id = $1")
+        .bind(proposal_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to cancel proposal: {}", e)))?;
+
+    Ok(Json(execution))
 }
 
 pub async fn delegate_vote(
@@ -209,11 +507,43 @@ pub async fn delegate_vote(
     Path(contract_id): Path<Uuid>,
     Json(delegate_id): Json<Uuid>,
 ) -> ApiResult<Json<VoteDelegation>> {
-    let contract = sqlx::query!("SELECT publisher_id FROM contracts WHERE id = $1", contract_id)
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))?;
+    let publisher_id = contract_publisher(&state, contract_id).await?;
+
+    if delegate_id == publisher_id {
+        return Err(ApiError::bad_request(
+            "self_delegation",
+            "Cannot delegate votes to yourself",
+        ));
+    }
+
+    // Reject delegations that would close a cycle; cycles that slip through
+    // (e.g. via registry-wide delegations) are excluded at tally time.
+    let active: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (delegator) delegator, delegate
+        FROM vote_delegations
+        WHERE active AND (contract_id = $1 OR contract_id IS NULL)
+        ORDER BY delegator, (contract_id IS NULL), created_at DESC
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+
+    let mut graph: HashMap<Uuid, Uuid> = active.into_iter().collect();
+    graph.insert(publisher_id, delegate_id);
+    let resolution = governance_tally::resolve_delegations(&graph, &Default::default());
+    if resolution
+        .cycles
+        .iter()
+        .any(|cycle| cycle.contains(&publisher_id))
+    {
+        return Err(ApiError::bad_request(
+            "delegation_cycle",
+            "Delegation would create a cycle",
+        ));
+    }
 
     let delegation = sqlx::query_as::<_, VoteDelegation>(
         r#"
@@ -222,7 +552,7 @@ pub async fn delegate_vote(
         RETURNING *
         "#,
     )
-    .bind(contract.publisher_id)
+    .bind(publisher_id)
     .bind(delegate_id)
     .bind(contract_id)
     .fetch_one(&state.db)
@@ -20,10 +20,22 @@ pub fn governance_routes() -> Router<AppState> {
             "/api/governance/proposals/:id/results",
             get(governance_handlers::get_proposal_results),
         )
+        .route(
+            "/api/governance/proposals/:id/close",
+            post(governance_handlers::close_proposal),
+        )
         .route(
             "/api/governance/proposals/:id/execute",
             post(governance_handlers::execute_proposal),
         )
+        .route(
+            "/api/governance/proposals/:id/cancel",
+            post(governance_handlers::cancel_proposal),
+        )
+        .route(
+            "/api/governance/proposals/:id/execution",
+            get(governance_handlers::get_execution),
+        )
         .route(
             "/api/contracts/:id/governance/delegate",
             post(governance_handlers::delegate_vote),
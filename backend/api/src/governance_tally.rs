@@ -0,0 +1,297 @@
+// api/src/governance_tally.rs
+//
+// Pure vote counting for governance proposals: per-model vote weighting,
+// transitive delegation resolution and quorum/approval evaluation.
+// Handlers load votes, balances and delegations, then call `tally`.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use uuid::Uuid;
+
+use shared::{GovernanceModel, ProposalTally, VoteChoice};
+
+// ─────────────────────────────────────────────────────────
+// Weighting
+// ─────────────────────────────────────────────────────────
+
+/// Integer square root (floor) via Newton's method.
+pub fn isqrt(n: i128) -> i128 {
+    if n <= 0 {
+        return 0;
+    }
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Voting power of one holder under `model`. One-per-voter models ignore the
+/// balance entirely.
+pub fn weight(model: GovernanceModel, balance: i128) -> i128 {
+    match model {
+        GovernanceModel::TokenWeighted => balance.max(0),
+        GovernanceModel::Quadratic => isqrt(balance),
+        GovernanceModel::Multisig | GovernanceModel::Timelock => 1,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Delegation
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DelegationResolution {
+    /// Non-voting delegator → voter their power is counted for
+    pub recipients: HashMap<Uuid, Uuid>,
+    /// Cycles in the delegation graph, each rotated to start at its
+    /// smallest member so that every cycle is reported once
+    pub cycles: Vec<Vec<Uuid>>,
+}
+
+/// Follow each non-voter's delegation chain until it reaches someone who
+/// voted. A direct vote always overrides the voter's own delegation. Chains
+/// that end at a non-voter, or run into a cycle, carry no power.
+pub fn resolve_delegations(
+    delegations: &HashMap<Uuid, Uuid>,
+    voters: &HashSet<Uuid>,
+) -> DelegationResolution {
+    let mut recipients = HashMap::new();
+    let mut cycles = BTreeSet::new();
+
+    for &delegator in delegations.keys() {
+        if voters.contains(&delegator) {
+            continue;
+        }
+        let mut path = vec![delegator];
+        let mut current = delegator;
+        while let Some(&next) = delegations.get(&current) {
+            if voters.contains(&next) {
+                recipients.insert(delegator, next);
+                break;
+            }
+            if let Some(start) = path.iter().position(|p| *p == next) {
+                let mut cycle = path[start..].to_vec();
+                let min = cycle
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, id)| **id)
+                    .map(|(i, _)| i)
+                    .unwrap_or(0);
+                cycle.rotate_left(min);
+                cycles.insert(cycle);
+                break;
+            }
+            path.push(next);
+            current = next;
+        }
+    }
+
+    DelegationResolution {
+        recipients,
+        cycles: cycles.into_iter().collect(),
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Tally
+// ─────────────────────────────────────────────────────────
+
+pub struct TallyInput<'a> {
+    pub model: GovernanceModel,
+    pub votes: &'a [(Uuid, VoteChoice)],
+    /// Raw token balance per publisher at `voting_starts_at`
+    pub balances: &'a HashMap<Uuid, i128>,
+    /// Raw balances of every holder at `voting_starts_at`, including
+    /// holders without a publisher account (token models only)
+    pub holder_balances: &'a [i128],
+    /// Delegator → delegate, as active at `voting_starts_at`
+    pub delegations: &'a HashMap<Uuid, Uuid>,
+    pub quorum_required: i32,
+    pub approval_threshold: i32,
+}
+
+fn percent(part: i128, whole: i128) -> f64 {
+    if whole <= 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+/// Count the votes on a proposal.
+///
+/// Each holder is weighted individually before delegated power is summed,
+/// so quadratic voting cannot be sidestepped by pooling balances through
+/// delegation. For token models `quorum_required` is a percentage of the
+/// eligible power; for one-per-voter models it is a minimum vote count.
+pub fn tally(input: &TallyInput) -> ProposalTally {
+    let own_power =
+        |id: &Uuid| weight(input.model, input.balances.get(id).copied().unwrap_or_default());
+
+    let voters: HashSet<Uuid> = input.votes.iter().map(|(v, _)| *v).collect();
+    let resolution = resolve_delegations(input.delegations, &voters);
+
+    let mut delegated: HashMap<Uuid, i128> = HashMap::new();
+    for (delegator, recipient) in &resolution.recipients {
+        *delegated.entry(*recipient).or_default() += own_power(delegator);
+    }
+
+    let mut result = ProposalTally {
+        delegated_voters: resolution.recipients.len(),
+        delegation_cycles: resolution.cycles,
+        ..Default::default()
+    };
+
+    for (voter, choice) in input.votes {
+        let power = own_power(voter) + delegated.get(voter).copied().unwrap_or_default();
+        match choice {
+            VoteChoice::For => result.votes_for += power,
+            VoteChoice::Against => result.votes_against += power,
+            VoteChoice::Abstain => result.votes_abstain += power,
+        }
+    }
+    result.total_votes = result.votes_for + result.votes_against + result.votes_abstain;
+    result.approval_pct = percent(result.votes_for, result.votes_for + result.votes_against);
+
+    result.quorum_met = if input.model.is_token_based() {
+        let eligible: i128 = input
+            .holder_balances
+            .iter()
+            .map(|b| weight(input.model, *b))
+            .sum();
+        let participation = percent(result.total_votes, eligible);
+        result.eligible_power = Some(eligible);
+        result.participation_pct = Some(participation);
+        eligible > 0 && participation >= input.quorum_required as f64
+    } else {
+        result.total_votes >= input.quorum_required as i128
+    };
+
+    result.approved = result.quorum_met
+        && result.votes_for > 0
+        && result.approval_pct >= input.approval_threshold as f64;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = (0..n).map(|_| Uuid::new_v4()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn isqrt_floors() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(1_000_000_000_000), 1_000_000);
+        assert_eq!(isqrt(-4), 0);
+    }
+
+    #[test]
+    fn delegation_is_transitive_and_direct_votes_override() {
+        let p = ids(4);
+        // p0 → p1 → p2 (voted); p3 → p2 but p3 also voted
+        let delegations = HashMap::from([(p[0], p[1]), (p[1], p[2]), (p[3], p[2])]);
+        let voters = HashSet::from([p[2], p[3]]);
+
+        let res = resolve_delegations(&delegations, &voters);
+        assert_eq!(res.recipients.len(), 2);
+        assert_eq!(res.recipients[&p[0]], p[2]);
+        assert_eq!(res.recipients[&p[1]], p[2]);
+        assert!(res.cycles.is_empty());
+    }
+
+    #[test]
+    fn delegation_cycles_are_reported_once_and_carry_no_power() {
+        let p = ids(4);
+        // p0 → p1 → p2 → p0, and p3 feeds into the cycle
+        let delegations = HashMap::from([(p[0], p[1]), (p[1], p[2]), (p[2], p[0]), (p[3], p[1])]);
+
+        let res = resolve_delegations(&delegations, &HashSet::new());
+        assert!(res.recipients.is_empty());
+        assert_eq!(res.cycles, vec![vec![p[0], p[1], p[2]]]);
+    }
+
+    #[test]
+    fn quadratic_weights_each_holder_before_delegation() {
+        let p = ids(3);
+        let balances = HashMap::from([(p[0], 100), (p[1], 100), (p[2], 400)]);
+        let delegations = HashMap::from([(p[1], p[0])]);
+        let votes = [(p[0], VoteChoice::For), (p[2], VoteChoice::Against)];
+
+        let result = tally(&TallyInput {
+            model: GovernanceModel::Quadratic,
+            votes: &votes,
+            balances: &balances,
+            holder_balances: &[100, 100, 400],
+            delegations: &delegations,
+            quorum_required: 50,
+            approval_threshold: 50,
+        });
+
+        // sqrt(100) + sqrt(100) vs sqrt(400): a tie, which meets 50%
+        assert_eq!(result.votes_for, 20);
+        assert_eq!(result.votes_against, 20);
+        assert_eq!(result.eligible_power, Some(40));
+        assert!(result.quorum_met);
+        assert!(result.approved);
+        assert_eq!(result.delegated_voters, 1);
+    }
+
+    #[test]
+    fn token_weighted_quorum_counts_holders_without_accounts() {
+        let p = ids(1);
+        let balances = HashMap::from([(p[0], 300)]);
+        let votes = [(p[0], VoteChoice::For)];
+
+        let result = tally(&TallyInput {
+            model: GovernanceModel::TokenWeighted,
+            votes: &votes,
+            balances: &balances,
+            // 700 tokens held by addresses that are not registered publishers
+            holder_balances: &[300, 700],
+            delegations: &HashMap::new(),
+            quorum_required: 40,
+            approval_threshold: 50,
+        });
+
+        assert_eq!(result.participation_pct, Some(30.0));
+        assert!(!result.quorum_met);
+        assert!(!result.approved);
+    }
+
+    #[test]
+    fn abstentions_count_for_quorum_but_not_approval() {
+        let p = ids(3);
+        let votes = [
+            (p[0], VoteChoice::For),
+            (p[1], VoteChoice::Against),
+            (p[2], VoteChoice::Abstain),
+        ];
+
+        let result = tally(&TallyInput {
+            model: GovernanceModel::Multisig,
+            votes: &votes,
+            balances: &HashMap::new(),
+            holder_balances: &[],
+            delegations: &HashMap::new(),
+            quorum_required: 3,
+            approval_threshold: 60,
+        });
+
+        assert_eq!(result.total_votes, 3);
+        assert!(result.quorum_met);
+        assert_eq!(result.approval_pct, 50.0);
+        assert!(!result.approved);
+        assert_eq!(result.eligible_power, None);
+    }
+}
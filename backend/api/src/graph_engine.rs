@@ -0,0 +1,494 @@
+// api/src/graph_engine.rs
+//
+// Analyses over the contract dependency graph: blast radius of a release,
+// dependency cycles, centrality ranking and the weighting behind pause
+// impact reports. Handlers load edges and contract metadata; everything in
+// here is synchronous.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use uuid::Uuid;
+
+use shared::{PauseSeverity, SemVer, VersionReq};
+
+/// PageRank damping factor
+pub const DAMPING: f64 = 0.85;
+const PAGERANK_ITERATIONS: usize = 100;
+const PAGERANK_TOLERANCE: f64 = 1e-10;
+
+/// Trust score at which a dependent counts as relied upon regardless of its
+/// declared maturity
+pub const TRUSTED_SCORE: f64 = 70.0;
+
+/// A resolved dependency: `dependent` declares `constraint` on `dependency`
+#[derive(Debug, Clone)]
+pub struct DependencyEdge {
+    pub dependent: Uuid,
+    pub dependency: Uuid,
+    pub constraint: String,
+}
+
+pub struct DependencyGraph {
+    nodes: Vec<Uuid>,
+    /// dependency → (dependent, constraint), one entry per declared edge
+    dependents: HashMap<Uuid, Vec<(Uuid, String)>>,
+    /// dependent → distinct dependencies
+    dependencies: HashMap<Uuid, Vec<Uuid>>,
+}
+
+impl DependencyGraph {
+    pub fn new(edges: &[DependencyEdge]) -> Self {
+        let mut nodes = HashSet::new();
+        let mut dependents: HashMap<Uuid, Vec<(Uuid, String)>> = HashMap::new();
+        let mut dependencies: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for edge in edges {
+            nodes.insert(edge.dependent);
+            nodes.insert(edge.dependency);
+            dependents
+                .entry(edge.dependency)
+                .or_default()
+                .push((edge.dependent, edge.constraint.clone()));
+            let deps = dependencies.entry(edge.dependent).or_default();
+            if !deps.contains(&edge.dependency) {
+                deps.push(edge.dependency);
+            }
+        }
+        let mut nodes: Vec<Uuid> = nodes.into_iter().collect();
+        nodes.sort();
+        Self {
+            nodes,
+            dependents,
+            dependencies,
+        }
+    }
+
+    /// Contracts with at least one edge, sorted by ID
+    pub fn nodes(&self) -> &[Uuid] {
+        &self.nodes
+    }
+
+    /// Dependencies of each node as positions in `nodes`
+    fn indexed_dependencies(&self) -> Vec<Vec<usize>> {
+        let position: HashMap<Uuid, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        self.nodes
+            .iter()
+            .map(|id| {
+                self.dependencies
+                    .get(id)
+                    .into_iter()
+                    .flatten()
+                    .map(|dep| position[dep])
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn direct_dependents(&self, contract: Uuid) -> usize {
+        self.dependents
+            .get(&contract)
+            .into_iter()
+            .flatten()
+            .map(|(dependent, _)| dependent)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Blast radius
+// ─────────────────────────────────────────────────────────
+
+/// A contract reached from the origin, at its shortest distance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reached {
+    pub contract: Uuid,
+    pub depth: i32,
+    pub via: Uuid,
+    pub constraint: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlastRadius {
+    /// Breadth-first order, so depths never decrease
+    pub reached: Vec<Reached>,
+    /// Direct dependents left out because their constraint excludes the
+    /// release and no other path reaches them
+    pub excluded_by_version: usize,
+}
+
+impl BlastRadius {
+    pub fn max_depth(&self) -> i32 {
+        self.reached.last().map_or(0, |r| r.depth)
+    }
+
+    /// The chain of dependents from `origin` to the most distant contract
+    pub fn critical_path(&self, origin: Uuid) -> Vec<Uuid> {
+        let Some(last) = self.reached.last() else {
+            return vec![origin];
+        };
+        let via: HashMap<Uuid, Uuid> = self.reached.iter().map(|r| (r.contract, r.via)).collect();
+        let mut path = vec![last.contract];
+        let mut current = last.contract;
+        while let Some(&parent) = via.get(&current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Unparseable constraints are assumed to admit the release; a blast radius
+/// should err towards including a contract.
+fn admits(constraint: &str, version: Option<&SemVer>) -> bool {
+    match (version, VersionReq::parse(constraint)) {
+        (Some(v), Some(req)) => req.matches(v),
+        _ => true,
+    }
+}
+
+impl DependencyGraph {
+    /// Every contract that depends on `origin`, directly or transitively.
+    ///
+    /// With a `version`, direct dependents only count when their constraint
+    /// admits that release. Deeper hops depend on a contract rather than on
+    /// the origin's release, so they are not filtered.
+    pub fn blast_radius(
+        &self,
+        origin: Uuid,
+        version: Option<&SemVer>,
+        max_depth: Option<usize>,
+    ) -> BlastRadius {
+        let mut depth: HashMap<Uuid, i32> = HashMap::from([(origin, 0)]);
+        let mut queue = VecDeque::from([origin]);
+        let mut excluded = HashSet::new();
+        let mut reached = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            let next_depth = depth[&current] + 1;
+            if max_depth.is_some_and(|max| next_depth as usize > max) {
+                continue;
+            }
+            for (dependent, constraint) in self.dependents.get(&current).into_iter().flatten() {
+                if depth.contains_key(dependent) {
+                    continue;
+                }
+                if current == origin && !admits(constraint, version) {
+                    excluded.insert(*dependent);
+                    continue;
+                }
+                depth.insert(*dependent, next_depth);
+                reached.push(Reached {
+                    contract: *dependent,
+                    depth: next_depth,
+                    via: current,
+                    constraint: constraint.clone(),
+                });
+                queue.push_back(*dependent);
+            }
+        }
+
+        let excluded_by_version = excluded.iter().filter(|c| !depth.contains_key(c)).count();
+        BlastRadius {
+            reached,
+            excluded_by_version,
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Cycles
+// ─────────────────────────────────────────────────────────
+
+impl DependencyGraph {
+    /// Groups of contracts that depend on each other in a loop: strongly
+    /// connected components with more than one member, or a contract that
+    /// depends on itself. Largest first; members sorted by ID.
+    pub fn cycles(&self) -> Vec<Vec<Uuid>> {
+        const UNVISITED: usize = usize::MAX;
+
+        let adjacency = self.indexed_dependencies();
+
+        // Iterative Tarjan, so deep chains cannot overflow the stack
+        let n = self.nodes.len();
+        let mut index = vec![UNVISITED; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((v, edge)) = calls.pop() {
+                if let Some(&w) = adjacency[v].get(edge) {
+                    calls.push((v, edge + 1));
+                    if index[w] == UNVISITED {
+                        index[w] = next_index;
+                        low[w] = next_index;
+                        next_index += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(self.nodes[w]);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    if component.len() > 1 || adjacency[v].contains(&v) {
+                        component.sort();
+                        components.push(component);
+                    }
+                }
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[v]);
+                }
+            }
+        }
+
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        components
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Centrality
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Centrality {
+    pub contract: Uuid,
+    pub pagerank: f64,
+    pub direct_dependents: usize,
+}
+
+impl DependencyGraph {
+    /// PageRank with rank flowing from each dependent to its dependencies,
+    /// so a contract ranks highly when important contracts build on it.
+    /// Contracts without dependencies spread their rank evenly. Most
+    /// central first.
+    pub fn centrality(&self) -> Vec<Centrality> {
+        let n = self.nodes.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let out = self.indexed_dependencies();
+
+        let base = (1.0 - DAMPING) / n as f64;
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..PAGERANK_ITERATIONS {
+            let dangling: f64 = out
+                .iter()
+                .zip(&rank)
+                .filter(|(deps, _)| deps.is_empty())
+                .map(|(_, r)| r)
+                .sum();
+            let mut next = vec![base + DAMPING * dangling / n as f64; n];
+            for (deps, r) in out.iter().zip(&rank) {
+                let share = DAMPING * r / deps.len().max(1) as f64;
+                for &dep in deps {
+                    next[dep] += share;
+                }
+            }
+            let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if delta < PAGERANK_TOLERANCE {
+                break;
+            }
+        }
+
+        let mut ranked: Vec<Centrality> = self
+            .nodes
+            .iter()
+            .zip(rank)
+            .map(|(id, pagerank)| Centrality {
+                contract: *id,
+                pagerank,
+                direct_dependents: self.direct_dependents(*id),
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.pagerank
+                .total_cmp(&a.pagerank)
+                .then(b.direct_dependents.cmp(&a.direct_dependents))
+                .then(a.contract.cmp(&b.contract))
+        });
+        ranked
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Pause impact
+// ─────────────────────────────────────────────────────────
+
+fn is_production(maturity: &str) -> bool {
+    matches!(maturity, "stable" | "mature")
+}
+
+fn maturity_weight(maturity: &str) -> f64 {
+    match maturity {
+        "mature" => 1.0,
+        "stable" => 0.8,
+        "beta" => 0.5,
+        "legacy" => 0.4,
+        _ => 0.25,
+    }
+}
+
+/// Severity of a dependent losing a paused dependency `depth` hops away.
+///
+/// Direct production dependents break outright. Direct beta or trusted
+/// dependents and transitive production ones are degraded. Everything else
+/// is low.
+pub fn pause_severity(maturity: &str, trust_score: f64, depth: i32) -> PauseSeverity {
+    let direct = depth <= 1;
+    if direct && is_production(maturity) {
+        PauseSeverity::High
+    } else if (direct && (maturity == "beta" || trust_score >= TRUSTED_SCORE))
+        || is_production(maturity)
+    {
+        PauseSeverity::Medium
+    } else {
+        PauseSeverity::Low
+    }
+}
+
+/// Contribution of one dependent to a pause impact score: its maturity,
+/// scaled between half and full by trust, divided by its distance.
+pub fn pause_weight(maturity: &str, trust_score: f64, depth: i32) -> f64 {
+    let trust = trust_score.clamp(0.0, 100.0) / 100.0;
+    maturity_weight(maturity) * (0.5 + trust / 2.0) / depth.max(1) as f64
+}
+
+// ─────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn edge(dependent: u128, dependency: u128, constraint: &str) -> DependencyEdge {
+        DependencyEdge {
+            dependent: id(dependent),
+            dependency: id(dependency),
+            constraint: constraint.into(),
+        }
+    }
+
+    #[test]
+    fn blast_radius_filters_direct_dependents_by_version() {
+        // 2 and 3 depend on 1; 4 depends on 2 and on 1 at an old release
+        let graph = DependencyGraph::new(&[
+            edge(2, 1, "^1.2"),
+            edge(3, 1, "^2.0"),
+            edge(4, 2, "*"),
+            edge(4, 1, "=0.9.0"),
+        ]);
+
+        let all = graph.blast_radius(id(1), None, None);
+        let ids: Vec<Uuid> = all.reached.iter().map(|r| r.contract).collect();
+        assert_eq!(ids, vec![id(2), id(3), id(4)]);
+        assert_eq!(all.max_depth(), 1);
+
+        let v = SemVer::parse("1.4.0").unwrap();
+        let radius = graph.blast_radius(id(1), Some(&v), None);
+        let reached: Vec<(Uuid, i32)> = radius
+            .reached
+            .iter()
+            .map(|r| (r.contract, r.depth))
+            .collect();
+        // 4 excludes 1.4.0 directly but still breaks through 2
+        assert_eq!(reached, vec![(id(2), 1), (id(4), 2)]);
+        assert_eq!(radius.excluded_by_version, 1);
+        assert_eq!(radius.critical_path(id(1)), vec![id(1), id(2), id(4)]);
+
+        let shallow = graph.blast_radius(id(1), Some(&v), Some(1));
+        assert_eq!(shallow.reached.len(), 1);
+        assert_eq!(shallow.excluded_by_version, 2);
+    }
+
+    #[test]
+    fn cycles_are_strongly_connected_groups() {
+        let graph = DependencyGraph::new(&[
+            edge(1, 2, "*"),
+            edge(2, 3, "*"),
+            edge(3, 1, "*"),
+            edge(4, 1, "*"),
+            edge(5, 5, "*"),
+            edge(6, 7, "*"),
+        ]);
+        assert_eq!(graph.cycles(), vec![vec![id(1), id(2), id(3)], vec![id(5)]]);
+
+        // The origin is never reported as its own dependent
+        let radius = graph.blast_radius(id(1), None, None);
+        let ids: HashSet<Uuid> = radius.reached.iter().map(|r| r.contract).collect();
+        assert_eq!(ids, HashSet::from([id(2), id(3), id(4)]));
+    }
+
+    #[test]
+    fn centrality_ranks_shared_foundations_first() {
+        // 10 is a token everyone uses; 11 is an oracle only 1 uses
+        let graph = DependencyGraph::new(&[
+            edge(1, 10, "*"),
+            edge(2, 10, "*"),
+            edge(3, 10, "*"),
+            edge(1, 11, "*"),
+            edge(11, 10, "*"),
+        ]);
+        let ranked = graph.centrality();
+        assert_eq!(ranked[0].contract, id(10));
+        assert_eq!(ranked[0].direct_dependents, 4);
+        assert_eq!(ranked[1].contract, id(11));
+
+        let total: f64 = ranked.iter().map(|c| c.pagerank).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(DependencyGraph::new(&[]).centrality().is_empty());
+    }
+
+    #[test]
+    fn pause_severity_weighs_maturity_trust_and_distance() {
+        assert_eq!(pause_severity("mature", 10.0, 1), PauseSeverity::High);
+        assert_eq!(pause_severity("stable", 10.0, 3), PauseSeverity::Medium);
+        assert_eq!(pause_severity("beta", 0.0, 1), PauseSeverity::Medium);
+        assert_eq!(pause_severity("alpha", 85.0, 1), PauseSeverity::Medium);
+        assert_eq!(pause_severity("alpha", 85.0, 2), PauseSeverity::Low);
+        assert_eq!(pause_severity("legacy", 0.0, 1), PauseSeverity::Low);
+
+        let near: f64 = pause_weight("mature", 100.0, 1);
+        let far: f64 = pause_weight("mature", 100.0, 2);
+        let untrusted: f64 = pause_weight("mature", 0.0, 1);
+        assert!((near - 1.0).abs() < 1e-9);
+        assert!((far - 0.5).abs() < 1e-9);
+        assert!((untrusted - 0.5).abs() < 1e-9);
+        assert!(pause_weight("alpha", 100.0, 1) < pause_weight("stable", 0.0, 1));
+    }
+}
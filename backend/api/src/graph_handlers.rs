@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    graph_engine::{self, BlastRadius, DependencyEdge, DependencyGraph},
+    state::AppState,
+};
+use shared::models::{
+    BlastRadiusParams, BlastRadiusResponse, CentralityEntry, CentralityParams, CentralityResponse,
+    DependencyCycle, DependencyCyclesResponse, GraphContract, ImpactedContract, PauseImpactEntry,
+    PauseImpactResponse, PauseSeverity,
+};
+use shared::SemVer;
+
+const MAX_DEPTH_LIMIT: usize = 50;
+const MAX_CENTRALITY_LIMIT: usize = 100;
+
+const CONTRACT_COLUMNS: &str = "id, contract_id, name, network::text AS network, \
+     maturity::text AS maturity, is_verified, trust_score FROM contracts";
+
+fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(context = ctx, error = %err, "database error");
+    ApiError::internal(format!("Database error during: {}", ctx))
+}
+
+/// Resolved dependency edges, optionally only those inside one network
+async fn load_graph(db: &PgPool, network: Option<&str>) -> Result<DependencyGraph, sqlx::Error> {
+    let rows: Vec<(Uuid, Uuid, String)> = sqlx::query_as(
+        "SELECT d.contract_id, d.dependency_contract_id, d.version_constraint
+         FROM contract_dependencies d
+         JOIN contracts a ON a.id = d.contract_id
+         JOIN contracts b ON b.id = d.dependency_contract_id
+         WHERE $1::text IS NULL OR (a.network::text = $1 AND b.network::text = $1)",
+    )
+    .bind(network)
+    .fetch_all(db)
+    .await?;
+    let edges: Vec<DependencyEdge> = rows
+        .into_iter()
+        .map(|(dependent, dependency, constraint)| DependencyEdge {
+            dependent,
+            dependency,
+            constraint,
+        })
+        .collect();
+    Ok(DependencyGraph::new(&edges))
+}
+
+async fn load_contracts(
+    db: &PgPool,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, GraphContract>, sqlx::Error> {
+    let rows: Vec<GraphContract> =
+        sqlx::query_as(&format!("SELECT {} WHERE id = ANY($1)", CONTRACT_COLUMNS))
+            .bind(ids)
+            .fetch_all(db)
+            .await?;
+    Ok(rows.into_iter().map(|c| (c.id, c)).collect())
+}
+
+/// The origin contract, the release asked about and everything it reaches
+struct Radius {
+    contract: GraphContract,
+    version: Option<String>,
+    radius: BlastRadius,
+    impacted: Vec<ImpactedContract>,
+}
+
+async fn compute_radius(
+    state: &AppState,
+    id: Uuid,
+    params: &BlastRadiusParams,
+) -> ApiResult<Radius> {
+    let version = params
+        .version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let semver = version
+        .map(|v| {
+            SemVer::parse(v).ok_or_else(|| {
+                ApiError::bad_request(
+                    "InvalidVersion",
+                    format!("'{}' is not a semantic version", v),
+                )
+            })
+        })
+        .transpose()?;
+    if let Some(max) = params.max_depth {
+        if !(1..=MAX_DEPTH_LIMIT).contains(&max) {
+            return Err(ApiError::bad_request(
+                "InvalidMaxDepth",
+                format!("max_depth must be between 1 and {}", MAX_DEPTH_LIMIT),
+            ));
+        }
+    }
+
+    let contract: GraphContract =
+        sqlx::query_as(&format!("SELECT {} WHERE id = $1", CONTRACT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| db_err("load contract", e))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    "ContractNotFound",
+                    format!("No contract found with ID: {}", id),
+                )
+            })?;
+
+    if let Some(v) = version {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM contract_versions WHERE contract_id = $1 AND version = $2)",
+        )
+        .bind(id)
+        .bind(v)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_err("check contract version", e))?;
+        if !exists {
+            return Err(ApiError::not_found(
+                "VersionNotFound",
+                format!("Contract {} has no version {}", id, v),
+            ));
+        }
+    }
+
+    let graph = load_graph(&state.db, None)
+        .await
+        .map_err(|e| db_err("load dependency graph", e))?;
+    let radius = graph.blast_radius(id, semver.as_ref(), params.max_depth);
+
+    let ids: Vec<Uuid> = radius.reached.iter().map(|r| r.contract).collect();
+    let mut contracts = load_contracts(&state.db, &ids)
+        .await
+        .map_err(|e| db_err("load impacted contracts", e))?;
+    let impacted = radius
+        .reached
+        .iter()
+        .filter_map(|r| {
+            Some(ImpactedContract {
+                contract: contracts.remove(&r.contract)?,
+                depth: r.depth,
+                via: r.via,
+                version_constraint: r.constraint.clone(),
+            })
+        })
+        .collect();
+
+    Ok(Radius {
+        contract,
+        version: version.map(str::to_string),
+        radius,
+        impacted,
+    })
+}
+
+/// GET /api/contracts/:id/blast-radius
+///
+/// Every contract that depends on this one, directly or transitively.
+/// `version` narrows direct dependents to those whose constraint admits
+/// that release.
+pub async fn get_blast_radius(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<BlastRadiusParams>,
+) -> ApiResult<Json<BlastRadiusResponse>> {
+    let r = compute_radius(&state, id, &params).await?;
+    Ok(Json(BlastRadiusResponse {
+        total: r.impacted.len(),
+        max_depth: r.radius.max_depth(),
+        excluded_by_version: r.radius.excluded_by_version,
+        critical_path: r.radius.critical_path(id),
+        contract: r.contract,
+        version: r.version,
+        impacted: r.impacted,
+    }))
+}
+
+/// GET /api/contracts/:id/pause-impact
+///
+/// The blast radius graded by each dependent's maturity, trust score and
+/// distance: what breaks, and how badly, if this contract is paused.
+pub async fn get_pause_impact(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<BlastRadiusParams>,
+) -> ApiResult<Json<PauseImpactResponse>> {
+    let r = compute_radius(&state, id, &params).await?;
+
+    let mut items: Vec<PauseImpactEntry> = r
+        .impacted
+        .into_iter()
+        .map(|impacted| {
+            let c = &impacted.contract;
+            PauseImpactEntry {
+                severity: graph_engine::pause_severity(&c.maturity, c.trust_score, impacted.depth),
+                weight: graph_engine::pause_weight(&c.maturity, c.trust_score, impacted.depth),
+                impacted,
+            }
+        })
+        .collect();
+    items.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.weight.total_cmp(&a.weight))
+    });
+
+    let count = |s: PauseSeverity| items.iter().filter(|i| i.severity == s).count();
+    Ok(Json(PauseImpactResponse {
+        total: items.len(),
+        high: count(PauseSeverity::High),
+        medium: count(PauseSeverity::Medium),
+        low: count(PauseSeverity::Low),
+        impact_score: items.iter().map(|i| i.weight).sum(),
+        critical_path: r.radius.critical_path(id),
+        contract: r.contract,
+        version: r.version,
+        items,
+    }))
+}
+
+/// GET /api/contracts/graph/cycles
+pub async fn get_dependency_cycles(
+    State(state): State<AppState>,
+) -> ApiResult<Json<DependencyCyclesResponse>> {
+    let graph = load_graph(&state.db, None)
+        .await
+        .map_err(|e| db_err("load dependency graph", e))?;
+    let cycles = graph.cycles();
+
+    let ids: Vec<Uuid> = cycles.iter().flatten().copied().collect();
+    let contracts = load_contracts(&state.db, &ids)
+        .await
+        .map_err(|e| db_err("load cycle contracts", e))?;
+    let cycles: Vec<DependencyCycle> = cycles
+        .into_iter()
+        .map(|members| DependencyCycle {
+            contracts: members
+                .iter()
+                .filter_map(|id| contracts.get(id).cloned())
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(DependencyCyclesResponse {
+        total: cycles.len(),
+        cycles,
+    }))
+}
+
+/// GET /api/contracts/graph/centrality
+///
+/// Systemically important contracts: PageRank over dependency edges, with
+/// direct and transitive dependent counts for the returned page.
+pub async fn get_centrality(
+    State(state): State<AppState>,
+    Query(params): Query<CentralityParams>,
+) -> ApiResult<Json<CentralityResponse>> {
+    let limit = params.limit.unwrap_or(20);
+    if !(1..=MAX_CENTRALITY_LIMIT).contains(&limit) {
+        return Err(ApiError::bad_request(
+            "InvalidLimit",
+            format!("limit must be between 1 and {}", MAX_CENTRALITY_LIMIT),
+        ));
+    }
+
+    let network = params.network.map(|n| n.to_string());
+    let graph = load_graph(&state.db, network.as_deref())
+        .await
+        .map_err(|e| db_err("load dependency graph", e))?;
+    let mut ranked = graph.centrality();
+    ranked.truncate(limit);
+
+    let ids: Vec<Uuid> = ranked.iter().map(|c| c.contract).collect();
+    let mut contracts = load_contracts(&state.db, &ids)
+        .await
+        .map_err(|e| db_err("load central contracts", e))?;
+    let items = ranked
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            Some(CentralityEntry {
+                contract: contracts.remove(&c.contract)?,
+                rank: i + 1,
+                pagerank: c.pagerank,
+                direct_dependents: c.direct_dependents,
+                transitive_dependents: graph.blast_radius(c.contract, None, None).reached.len(),
+            })
+        })
+        .collect();
+
+    Ok(Json(CentralityResponse {
+        total_contracts: graph.nodes().len(),
+        items,
+    }))
+}
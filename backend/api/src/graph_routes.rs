@@ -0,0 +1,23 @@
+use axum::{routing::get, Router};
+
+use crate::{graph_handlers, state::AppState};
+
+pub fn graph_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/contracts/graph/cycles",
+            get(graph_handlers::get_dependency_cycles),
+        )
+        .route(
+            "/api/contracts/graph/centrality",
+            get(graph_handlers::get_centrality),
+        )
+        .route(
+            "/api/contracts/:id/blast-radius",
+            get(graph_handlers::get_blast_radius),
+        )
+        .route(
+            "/api/contracts/:id/pause-impact",
+            get(graph_handlers::get_pause_impact),
+        )
+}
@@ -1,14 +1,22 @@
+pub mod migrations;
+
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde_json::{json, Value};
+use shared::models::{Contract, ContractSearchParams, ContractVersion, PaginatedResponse};
+use uuid::Uuid;
 
+use crate::error::{ApiError, ApiResult};
 use crate::state::AppState;
 
-pub type ApiResult<T> = Result<T, (StatusCode, String)>;
+pub fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = operation, error = ?err, "database operation failed");
+    ApiError::internal("An unexpected database error occurred")
+}
 
 pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
     let uptime = state.started_at.elapsed().as_secs();
@@ -74,223 +82,97 @@ pub async fn get_stats(State(state): State<AppState>) -> ApiResult<Json<serde_js
     })))
 }
 
-        "total_publishers": total_publishers,
-    })))
-}
-
-/// List and search contracts
+/// List contracts, optionally filtered by a name/description search
 pub async fn list_contracts(
     State(state): State<AppState>,
-    params: Result<Query<ContractSearchParams>, QueryRejection>,
-) -> axum::response::Response {
-    let Query(params) = match params {
-        Ok(q) => q,
-        Err(err) => return map_query_rejection(err).into_response(),
-    };
-    let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(20).clamp(1, 100);
-    let offset = (page - 1).max(0) * limit;
-
-    let contracts: Vec<Contract> = match sqlx::query_as(
-        "SELECT * FROM contracts ORDER BY created_at DESC LIMIT $1 OFFSET $2",
-    )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.db)
-    .await
-    {
-        Ok(rows) => rows,
-        Err(err) => return db_internal_error("list contracts", err).into_response(),
-    };
-    let contracts: Vec<Contract> =
-        match sqlx::query_as("SELECT * FROM contracts ORDER BY created_at DESC LIMIT $1 OFFSET $2")
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&state.db)
-            .await
-        {
-            Ok(rows) => rows,
-            Err(err) => return db_internal_error("list contracts", err).into_response(),
-        };
-    let total: i64 = match sqlx::query_scalar("SELECT COUNT(*) FROM contracts")
-        .fetch_one(&state.db)
-        .await
-    {
-        Ok(v) => v,
-        Err(err) => return db_internal_error("count contracts", err).into_response(),
-    };
-    (StatusCode::OK, Json(PaginatedResponse::new(contracts, total, page, limit))).into_response()
-    (
-        StatusCode::OK,
-        Json(PaginatedResponse::new(contracts, total, page, limit)),
-    )
-        .into_response()
-
+    Query(params): Query<ContractSearchParams>,
+) -> ApiResult<Json<PaginatedResponse<Contract>>> {
     let page = params.page.unwrap_or(1);
     let limit = params.limit.unwrap_or(20);
-
-    // bad input, bail early
-    if page < 1 || limit < 1 || limit > 100 {
-        return ApiError::bad_request(
+    if page < 1 || !(1..=100).contains(&limit) {
+        return Err(ApiError::bad_request(
             "InvalidPagination",
             "page must be >= 1 and limit must be between 1 and 100",
-        )
-        .into_response();
-    }
-
-    let offset = (page - 1) * limit;
-
-    // Build dynamic query based on filters
-    let mut query = String::from("SELECT * FROM contracts WHERE 1=1");
-    let mut count_query = String::from("SELECT COUNT(*) FROM contracts WHERE 1=1");
-
-    if let Some(ref q) = params.query {
-        let search_clause = format!(" AND (name ILIKE '%{}%' OR description ILIKE '%{}%')", q, q);
-        query.push_str(&search_clause);
-        count_query.push_str(&search_clause);
-    }
-
-    if let Some(verified) = params.verified_only {
-        if verified {
-            query.push_str(" AND is_verified = true");
-            count_query.push_str(" AND is_verified = true");
-        }
-    }
-
-    if let Some(ref category) = params.category {
-        let category_clause = format!(" AND category = '{}'", category);
-        query.push_str(&category_clause);
-        count_query.push_str(&category_clause);
-    }
-
-    query.push_str(&format!(
-        " ORDER BY created_at DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
-
-    let contracts: Vec<Contract> = match sqlx::query_as(&query).fetch_all(&state.db).await {
-        Ok(rows) => rows,
-        Err(err) => return db_internal_error("list contracts", err).into_response(),
-    };
-
-    let total: i64 = match sqlx::query_scalar(&count_query).fetch_one(&state.db).await {
-        Ok(n) => n,
-        Err(err) => return db_internal_error("count filtered contracts", err).into_response(),
-    };
-
-    let paginated = PaginatedResponse::new(contracts, total, page, limit);
-
-    // link headers for pagination
-    let total_pages = paginated.total_pages;
-    let mut links: Vec<String> = Vec::new();
-
-    if page > 1 {
-        links.push(format!(
-            "</api/contracts?page={}&limit={}>; rel=\"prev\"",
-            page - 1,
-            limit
-        ));
-    }
-    if page < total_pages {
-        links.push(format!(
-            "</api/contracts?page={}&limit={}>; rel=\"next\"",
-            page + 1,
-            limit
         ));
     }
 
-    let mut response = (StatusCode::OK, Json(paginated)).into_response();
+    let pattern = params.query.as_ref().map(|q| format!("%{}%", q));
+    let network = params.network.as_ref().map(|n| n.to_string());
+    let verified_only = params.verified_only.unwrap_or(false);
+    const FILTER: &str = "($1::text IS NULL OR name ILIKE $1 OR description ILIKE $1)
+          AND ($2::text IS NULL OR network::text = $2)
+          AND (NOT $3 OR is_verified)
+          AND ($4::text IS NULL OR category = $4)";
+
+    let contracts: Vec<Contract> = sqlx::query_as(&format!(
+        "SELECT * FROM contracts WHERE {} ORDER BY created_at DESC LIMIT $5 OFFSET $6",
+        FILTER
+    ))
+    .bind(&pattern)
+    .bind(&network)
+    .bind(verified_only)
+    .bind(&params.category)
+    .bind(limit)
+    .bind((page - 1) * limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list contracts", err))?;
+
+    let total: i64 =
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM contracts WHERE {}", FILTER))
+            .bind(&pattern)
+            .bind(&network)
+            .bind(verified_only)
+            .bind(&params.category)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("count contracts", err))?;
 
-    if !links.is_empty() {
-        if let Ok(value) = axum::http::HeaderValue::from_str(&links.join(", ")) {
-            response.headers_mut().insert("link", value);
-        }
-    }
+    Ok(Json(PaginatedResponse::new(contracts, total, page, limit)))
+}
 
-    response
-    Ok(Json(PaginatedResponse::new(
-        contracts, total, page, page_size,
-    )))
+pub async fn get_contract() -> impl IntoResponse {
+    Json(json!({"contract": null}))
 }
 
-pub async fn get_contract(
+pub async fn get_contract_abi() -> impl IntoResponse {
+    Json(json!({"abi": null}))
+}
+
+/// Get contract version history, newest first
+pub async fn get_contract_versions(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> ApiResult<Json<Contract>> {
-    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+) -> ApiResult<Json<Vec<ContractVersion>>> {
+    let contract_id = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
             "InvalidContractId",
             format!("Invalid contract ID format: {}", id),
         )
     })?;
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(contract_uuid)
-    Path(id): Path<Uuid>,
-) -> ApiResult<Json<Contract>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contracts WHERE id = $1)")
+        .bind(contract_id)
         .fetch_one(&state.db)
         .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract", err),
-        })?;
-    Ok(Json(contract))
-}
-
-pub async fn get_contract_abi(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let contract_uuid = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
-    let abi: Option<serde_json::Value> =
-        sqlx::query_scalar("SELECT abi FROM contracts WHERE id = $1")
-            .bind(contract_uuid)
-            .fetch_one(&state.db)
-            .await
-            .map_err(|_| StatusCode::NOT_FOUND)?;
-    abi.map(Json).ok_or(StatusCode::NOT_FOUND)
-}
-
-            _ => db_internal_error("get contract by id", err),
-        })?;
+        .map_err(|err| db_internal_error("look up contract", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", id),
+        ));
+    }
 
-    let active_deployment: Option<ContractDeployment> = sqlx::query_as(
-        "SELECT * FROM contract_deployments
-         WHERE contract_id = $1 AND status = 'active'",
+    let versions: Vec<ContractVersion> = sqlx::query_as(
+        "SELECT id, contract_id, version, wasm_hash, source_url, commit_hash, release_notes, created_at
+           FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
     )
-    .bind(contract.id)
-    .fetch_optional(&state.db)
+    .bind(contract_id)
+    .fetch_all(&state.db)
     .await
-    .map_err(|err| db_internal_error("get active deployment", err))?;
+    .map_err(|err| db_internal_error("list contract versions", err))?;
 
-    if let Some(deployment) = active_deployment {
-        let mut contract_with_deployment = contract.clone();
-        contract_with_deployment.wasm_hash = deployment.wasm_hash;
-        Ok(Json(contract_with_deployment))
-        (StatusCode::OK, Json(json!({"status": "ok", "version": "0.1.0", "timestamp": now, "uptime_secs": uptime})))
-    } else {
-        (StatusCode::SERVICE_UNAVAILABLE, Json(json!({"status": "degraded", "version": "0.1.0", "timestamp": now, "uptime_secs": uptime})))
-    }
-}
-
-pub async fn list_contracts() -> impl IntoResponse {
-    Json(json!({"contracts": []}))
-}
-
-pub async fn get_contract() -> impl IntoResponse {
-    Json(json!({"contract": null}))
-}
-
-pub async fn get_contract_abi() -> impl IntoResponse {
-    Json(json!({"abi": null}))
-}
-
-pub async fn get_contract_versions() -> impl IntoResponse {
-    Json(json!({"versions": []}))
+    Ok(Json(versions))
 }
 
 pub async fn get_contract_state() -> impl IntoResponse {
@@ -305,10 +187,6 @@ pub async fn get_contract_analytics() -> impl IntoResponse {
     Json(json!({"analytics": {}}))
 }
 
-pub async fn get_trust_score() -> impl IntoResponse {
-    Json(json!({"score": 0}))
-}
-
 pub async fn get_contract_dependencies() -> impl IntoResponse {
     Json(json!({"dependencies": []}))
 }
@@ -341,15 +219,107 @@ pub async fn deploy_green() -> impl IntoResponse {
     Json(json!({"deployment_id": ""}))
 }
 
-pub async fn get_stats(State(state): State<AppState>) -> ApiResult<Json<Value>> {
-    let total_contracts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM contracts")
-        .fetch_one(&state.db)
+pub async fn route_not_found() -> ApiError {
+    ApiError::not_found("RouteNotFound", "Route not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, Router};
+    use prometheus::Registry;
+    use tower::Service;
+
+    async fn get_json(app: &mut Router, uri: &str) -> (StatusCode, Value) {
+        let response = app
+            .call(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    /// The lookups `soroban-registry install` resolves the lockfile from:
+    /// search by name, then list that contract's versions. Needs a migrated
+    /// database in `DATABASE_URL` and is skipped without one.
+    #[tokio::test]
+    async fn contract_versions_for_lockfile_resolution() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            eprintln!("DATABASE_URL not set, skipping");
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.unwrap();
+
+        let suffix = Uuid::new_v4().simple().to_string();
+        let name = format!("lockfile-e2e-{}", &suffix[..12]);
+        let publisher_id: Uuid =
+            sqlx::query_scalar("INSERT INTO publishers (stellar_address) VALUES ($1) RETURNING id")
+                .bind(format!("G{}", &suffix[..20]))
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let contract_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO contracts (contract_id, wasm_hash, name, publisher_id, network)
+             VALUES ($1, 'hash-1.2.0', $2, $3, 'testnet') RETURNING id",
+        )
+        .bind(format!("C{}", &suffix[..20]))
+        .bind(&name)
+        .bind(publisher_id)
+        .fetch_one(&pool)
         .await
-        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string()))?;
+        .unwrap();
+        for version in ["1.0.0", "1.2.0"] {
+            sqlx::query(
+                "INSERT INTO contract_versions (contract_id, version, wasm_hash)
+                 VALUES ($1, $2, $3)",
+            )
+            .bind(contract_id)
+            .bind(version)
+            .bind(format!("hash-{}", version))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
 
-    Ok(Json(json!({"total_contracts": total_contracts, "verified_contracts": 0, "total_downloads": 0})))
-}
+        let mut app = crate::routes::contract_routes()
+            .with_state(AppState::new(pool.clone(), Registry::new()));
+        let search = get_json(&mut app, &format!("/api/contracts?query={}", name)).await;
+        let versions = get_json(
+            &mut app,
+            &format!("/api/contracts/{}/versions", contract_id),
+        )
+        .await;
+        let missing = get_json(
+            &mut app,
+            &format!("/api/contracts/{}/versions", Uuid::new_v4()),
+        )
+        .await;
+
+        sqlx::query("DELETE FROM publishers WHERE id = $1")
+            .bind(publisher_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(search.0, StatusCode::OK);
+        let found = search.1["contracts"].as_array().unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0]["id"], json!(contract_id));
+
+        assert_eq!(versions.0, StatusCode::OK);
+        let versions = versions.1.as_array().unwrap();
+        assert_eq!(versions.len(), 2);
+        for v in versions {
+            assert_eq!(v["contract_id"], json!(contract_id));
+            assert_eq!(
+                v["wasm_hash"],
+                json!(format!("hash-{}", v["version"].as_str().unwrap()))
+            );
+        }
 
-pub async fn route_not_found() -> impl IntoResponse {
-    (StatusCode::NOT_FOUND, Json(json!({"error": "Route not found"})))
+        assert_eq!(missing.0, StatusCode::NOT_FOUND);
+    }
 }
@@ -1,17 +1,97 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, State},
     http::StatusCode,
     Json,
 };
+use serde::Serialize;
 use shared::models::{
-    CreateMigrationRequest, Migration, MigrationStatus, PaginatedResponse,
-    UpdateMigrationStatusRequest,
+    AttachMigrationPlanRequest, CreateMigrationRequest, Migration, MigrationPlanStatus,
+    MigrationStatus, MigrationStep, MigrationStepKind, MigrationStepStatus, PaginatedResponse,
+    RecordMigrationStepRequest, UpdateMigrationStatusRequest,
 };
 use uuid::Uuid;
 
 use super::db_internal_error;
 use crate::error::ApiError;
 use crate::state::AppState;
+use crate::upgrade_safety::{self, UpgradeSafetyReport, UpgradeVerdict};
+
+/// Storage-compatibility plan for upgrading a contract to a new WASM
+#[derive(Debug, Serialize)]
+pub struct MigrationPlan {
+    pub contract_id: String,
+    pub wasm_hash: String,
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    /// `None` when either version has no ABI on record
+    pub upgrade_safety: Option<UpgradeSafetyReport>,
+    /// Whether a data migration has to accompany the WASM upgrade
+    pub requires_data_migration: bool,
+}
+
+/// Plan a migration: compare the storage layout of the deployed version
+/// with the version that published `wasm_hash`
+pub async fn plan_migration(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateMigrationRequest>,
+) -> Result<Json<MigrationPlan>, ApiError> {
+    let (id, current_hash): (Uuid, String) =
+        sqlx::query_as("SELECT id, wasm_hash FROM contracts WHERE contract_id = $1")
+            .bind(&payload.contract_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| db_internal_error("get contract", e))?
+            .ok_or(ApiError::not_found(
+                "ContractNotFound",
+                "Contract not found",
+            ))?;
+
+    let version_for = |hash: String| {
+        let db = state.db.clone();
+        async move {
+            sqlx::query_scalar::<_, String>(
+                "SELECT version FROM contract_versions
+                WHERE contract_id = $1 AND wasm_hash = $2
+                ORDER BY created_at DESC
+                LIMIT 1",
+            )
+            .bind(id)
+            .bind(hash)
+            .fetch_optional(&db)
+            .await
+            .map_err(|e| db_internal_error("get contract version", e))
+        }
+    };
+    let from_version = version_for(current_hash).await?;
+    let to_version = version_for(payload.wasm_hash.clone()).await?;
+
+    let upgrade_safety = match (&from_version, &to_version) {
+        (Some(from), Some(to)) => {
+            // The verified source describes the deployed code, i.e. the
+            // version whose data is already in storage.
+            let source = upgrade_safety::load_verified_source(&state.db, id)
+                .await
+                .map_err(|e| db_internal_error("get verified source", e))?;
+            upgrade_safety::analyze_versions(&state.db, id, from, to, source.as_deref())
+                .await
+                .map_err(|e| db_internal_error("analyze upgrade safety", e))?
+        }
+        _ => None,
+    };
+
+    let requires_data_migration = upgrade_safety
+        .as_ref()
+        .is_some_and(|r| r.verdict != UpgradeVerdict::Safe);
+
+    Ok(Json(MigrationPlan {
+        contract_id: payload.contract_id,
+        wasm_hash: payload.wasm_hash,
+        from_version,
+        to_version,
+        upgrade_safety,
+        requires_data_migration,
+    }))
+}
 
 /// Create a new migration
 pub async fn create_migration(
@@ -96,3 +176,253 @@ pub async fn get_migration(
 
     Ok(Json(migration))
 }
+
+const STEP_COLUMNS: &str = "id, migration_id, position, kind, function_name, args, expected, \
+     status, output, error, started_at, completed_at";
+
+/// Overall migration status implied by the outcomes of its plan steps
+fn status_from_steps(steps: &[MigrationStep]) -> MigrationStatus {
+    if steps.iter().any(|s| s.status == MigrationStepStatus::Failed) {
+        MigrationStatus::Failed
+    } else if !steps.is_empty() && steps.iter().all(|s| s.status.is_done()) {
+        MigrationStatus::Success
+    } else if steps.iter().any(|s| s.status != MigrationStepStatus::Pending) {
+        MigrationStatus::InProgress
+    } else {
+        MigrationStatus::Pending
+    }
+}
+
+fn resume_position(steps: &[MigrationStep]) -> Option<i32> {
+    steps
+        .iter()
+        .find(|s| !s.status.is_done())
+        .map(|s| s.position)
+}
+
+async fn fetch_migration(state: &AppState, id: Uuid) -> Result<Migration, ApiError> {
+    sqlx::query_as(
+        "SELECT id, contract_id, status, wasm_hash, log_output, created_at, updated_at
+        FROM migrations
+        WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_internal_error("get migration", e))?
+    .ok_or(ApiError::not_found(
+        "MigrationNotFound",
+        "Migration not found",
+    ))
+}
+
+async fn fetch_steps(state: &AppState, id: Uuid) -> Result<Vec<MigrationStep>, ApiError> {
+    sqlx::query_as(&format!(
+        "SELECT {STEP_COLUMNS} FROM migration_steps WHERE migration_id = $1 ORDER BY position"
+    ))
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_internal_error("get migration steps", e))
+}
+
+/// Attach an ordered plan to a migration that has not started yet,
+/// replacing any previously attached plan
+pub async fn attach_plan(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AttachMigrationPlanRequest>,
+) -> Result<Json<MigrationPlanStatus>, ApiError> {
+    if payload.steps.is_empty() {
+        return Err(ApiError::bad_request(
+            "EmptyPlan",
+            "A migration plan needs at least one step",
+        ));
+    }
+    for (position, step) in payload.steps.iter().enumerate() {
+        let needs_function = step.kind != MigrationStepKind::UpgradeWasm;
+        if needs_function && step.function_name.as_deref().unwrap_or("").is_empty() {
+            return Err(ApiError::bad_request(
+                "InvalidPlanStep",
+                format!("Step {position} must name the function to call"),
+            ));
+        }
+        if step.kind == MigrationStepKind::Verify && step.expected.is_none() {
+            return Err(ApiError::bad_request(
+                "InvalidPlanStep",
+                format!("Verify step {position} must define an expected value"),
+            ));
+        }
+    }
+
+    let migration = fetch_migration(&state, id).await?;
+    if migration.status != MigrationStatus::Pending {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "MigrationStarted",
+            "A plan can only be attached before the migration starts",
+        ));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_internal_error("begin transaction", e))?;
+
+    sqlx::query("DELETE FROM migration_steps WHERE migration_id = $1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_internal_error("clear migration steps", e))?;
+
+    let mut steps = Vec::with_capacity(payload.steps.len());
+    for (position, spec) in payload.steps.into_iter().enumerate() {
+        let step: MigrationStep = sqlx::query_as(&format!(
+            "INSERT INTO migration_steps (migration_id, position, kind, function_name, args, expected)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {STEP_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(position as i32)
+        .bind(spec.kind)
+        .bind(spec.function_name)
+        .bind(serde_json::to_value(&spec.args).unwrap_or_default())
+        .bind(spec.expected)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_internal_error("insert migration step", e))?;
+        steps.push(step);
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| db_internal_error("commit migration plan", e))?;
+
+    Ok(Json(MigrationPlanStatus {
+        resume_from: resume_position(&steps),
+        migration,
+        steps,
+    }))
+}
+
+/// Get a migration's plan and the step a runner should resume from
+pub async fn get_plan(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MigrationPlanStatus>, ApiError> {
+    let migration = fetch_migration(&state, id).await?;
+    let steps = fetch_steps(&state, id).await?;
+
+    Ok(Json(MigrationPlanStatus {
+        resume_from: resume_position(&steps),
+        migration,
+        steps,
+    }))
+}
+
+/// Record the outcome of one plan step. Steps complete strictly in order;
+/// completed steps are checkpoints and cannot be changed afterwards.
+pub async fn record_step(
+    State(state): State<AppState>,
+    Path((id, position)): Path<(Uuid, i32)>,
+    Json(payload): Json<RecordMigrationStepRequest>,
+) -> Result<Json<MigrationStep>, ApiError> {
+    let migration = fetch_migration(&state, id).await?;
+    let steps = fetch_steps(&state, id).await?;
+
+    let step = steps
+        .iter()
+        .find(|s| s.position == position)
+        .ok_or(ApiError::not_found(
+            "MigrationStepNotFound",
+            format!("Migration has no step {position}"),
+        ))?;
+
+    if step.status.is_done() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "StepAlreadyCompleted",
+            format!("Step {position} already completed"),
+        ));
+    }
+    if resume_position(&steps) != Some(position) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "StepOutOfOrder",
+            format!("Earlier steps must complete before step {position}"),
+        ));
+    }
+    if payload.status == MigrationStepStatus::Pending {
+        return Err(ApiError::bad_request(
+            "InvalidStepStatus",
+            "A step outcome cannot reset the step to pending",
+        ));
+    }
+    if matches!(
+        migration.status,
+        MigrationStatus::Success | MigrationStatus::RolledBack
+    ) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "MigrationFinished",
+            "Migration has already finished",
+        ));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_internal_error("begin transaction", e))?;
+
+    let running = payload.status == MigrationStepStatus::Running;
+    let updated: MigrationStep = sqlx::query_as(&format!(
+        "UPDATE migration_steps
+        SET status = $1,
+            output = $2,
+            error = $3,
+            started_at = CASE WHEN $4 THEN NOW() ELSE COALESCE(started_at, NOW()) END,
+            completed_at = CASE WHEN $4 THEN NULL ELSE NOW() END
+        WHERE id = $5
+        RETURNING {STEP_COLUMNS}"
+    ))
+    .bind(payload.status)
+    .bind(&payload.output)
+    .bind(&payload.error)
+    .bind(running)
+    .bind(step.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_internal_error("update migration step", e))?;
+
+    let steps: Vec<MigrationStep> = steps
+        .into_iter()
+        .map(|s| if s.id == updated.id { updated.clone() } else { s })
+        .collect();
+
+    let log_line = match (&updated.error, running) {
+        (_, true) => format!("step {position} ({:?}) started", updated.kind),
+        (Some(err), false) => format!("step {position} ({:?}) {:?}: {err}", updated.kind, updated.status),
+        (None, false) => format!("step {position} ({:?}) {:?}", updated.kind, updated.status),
+    };
+
+    sqlx::query(
+        "UPDATE migrations
+        SET status = $1,
+            log_output = COALESCE(log_output || E'\\n', '') || $2
+        WHERE id = $3",
+    )
+    .bind(status_from_steps(&steps))
+    .bind(log_line)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_internal_error("update migration status", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_internal_error("commit migration step", e))?;
+
+    Ok(Json(updated))
+}
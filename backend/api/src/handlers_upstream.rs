@@ -19,6 +19,8 @@ use uuid::Uuid;
 use crate::{
     analytics,
     error::{ApiError, ApiResult},
+    namespace::ScopedName,
+    organization_handlers,
     state::AppState,
 };
 
@@ -296,25 +298,48 @@ pub async fn publish_contract(
     .await
     .map_err(|err| db_internal_error("upsert publisher", err))?;
 
+    // `@org/name` publishes into an organization's namespace; unscoped
+    // names are screened against reserved and popular names
+    let scoped: ScopedName = req
+        .name
+        .parse()
+        .map_err(|e: String| ApiError::bad_request("InvalidName", e))?;
+    let organization =
+        organization_handlers::check_publish_name(&state.db, &scoped, &publisher).await?;
+
     // TODO: Fetch WASM hash from Stellar network
     let wasm_hash = "placeholder_hash".to_string();
 
     let contract: Contract = sqlx::query_as(
-        "INSERT INTO contracts (contract_id, wasm_hash, name, description, publisher_id, network, category, tags)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "INSERT INTO contracts (contract_id, wasm_hash, name, description, publisher_id, network, category, tags,
+                                namespace, organization_id, license, documentation)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
          RETURNING *",
     )
     .bind(&req.contract_id)
     .bind(&wasm_hash)
-    .bind(&req.name)
+    .bind(&scoped.name)
     .bind(&req.description)
     .bind(publisher.id)
     .bind(&req.network)
     .bind(&req.category)
     .bind(&req.tags)
+    .bind(&scoped.namespace)
+    .bind(organization.as_ref().map(|o| o.id))
+    .bind(&req.license)
+    .bind(&req.documentation)
     .fetch_one(&state.db)
     .await
-    .map_err(|err| db_internal_error("create contract", err))?;
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db) if db.constraint() == Some("idx_contracts_scoped_name") => {
+            ApiError::new(
+                StatusCode::CONFLICT,
+                "name_taken",
+                format!("{} is already published on this network", scoped),
+            )
+        }
+        _ => db_internal_error("create contract", err),
+    })?;
 
     // Fire-and-forget analytics event
     let pool = state.db.clone();
@@ -0,0 +1,322 @@
+// api/src/incident_engine.rs
+//
+// Incident lifecycle rules, dependency-graph impact analysis and
+// post-mortem rendering. Handlers persist incidents and their timelines;
+// everything in here is synchronous.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use shared::{IncidentDetail, IncidentImpact, IncidentSeverity, IncidentState};
+
+// ─────────────────────────────────────────────────────────
+// State machine
+// ─────────────────────────────────────────────────────────
+
+/// Critical incidents halt writes to the contract as soon as they are raised.
+pub fn breaker_on_trigger(severity: IncidentSeverity) -> bool {
+    severity == IncidentSeverity::Critical
+}
+
+/// Validate a state change and return whether the circuit breaker stays
+/// engaged afterwards.
+///
+/// Incidents may skip states going forward and may fall back to an earlier
+/// active state if containment turns out not to hold, but they never return
+/// to `detected` and `post_review` is final. Recovery releases the breaker;
+/// falling back afterwards does not re-engage it automatically.
+pub fn transition(
+    current: IncidentState,
+    next: IncidentState,
+    breaker_engaged: bool,
+) -> Result<bool, String> {
+    if current == next {
+        return Err(format!("incident is already in state {}", next));
+    }
+    if current == IncidentState::PostReview {
+        return Err("incident is closed for post-review".into());
+    }
+    if next == IncidentState::Detected {
+        return Err("incidents cannot return to Detected".into());
+    }
+    Ok(breaker_engaged && next.is_active())
+}
+
+// ─────────────────────────────────────────────────────────
+// Impact analysis
+// ─────────────────────────────────────────────────────────
+
+/// Every contract affected by an incident on `origin`, with its distance in
+/// dependency hops. `edges` are `(dependent, dependency)` pairs; each
+/// contract is reported once, at its shortest distance.
+pub fn affected_contracts(
+    origin: Uuid,
+    edges: &[(Uuid, Uuid)],
+) -> Vec<(Uuid, IncidentImpact, i32)> {
+    let mut dependents: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (dependent, dependency) in edges {
+        dependents.entry(*dependency).or_default().push(*dependent);
+    }
+
+    let mut depth: HashMap<Uuid, i32> = HashMap::from([(origin, 0)]);
+    let mut queue = VecDeque::from([origin]);
+    let mut affected = vec![(origin, IncidentImpact::Origin, 0)];
+
+    while let Some(current) = queue.pop_front() {
+        let next_depth = depth[&current] + 1;
+        for dependent in dependents.get(&current).into_iter().flatten() {
+            if depth.contains_key(dependent) {
+                continue;
+            }
+            depth.insert(*dependent, next_depth);
+            affected.push((*dependent, IncidentImpact::Dependent, next_depth));
+            queue.push_back(*dependent);
+        }
+    }
+
+    affected
+}
+
+// ─────────────────────────────────────────────────────────
+// Post-mortem
+// ─────────────────────────────────────────────────────────
+
+fn minutes_between(from: DateTime<Utc>, to: Option<DateTime<Utc>>) -> Option<i64> {
+    to.map(|t| (t - from).num_minutes())
+}
+
+fn format_minutes(minutes: Option<i64>) -> String {
+    match minutes {
+        Some(m) if m >= 60 => format!("{}h {:02}m", m / 60, m % 60),
+        Some(m) => format!("{}m", m),
+        None => "—".into(),
+    }
+}
+
+/// Markdown post-mortem following the structure required by
+/// docs/INCIDENT_RESPONSE.md. Sections without data are left as prompts
+/// for the review meeting rather than omitted.
+pub fn render_postmortem(detail: &IncidentDetail) -> String {
+    let incident = &detail.incident;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# Post-Mortem: {}", incident.title);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- **Incident ID:** {}", incident.id);
+    let _ = writeln!(out, "- **Severity:** {}", incident.severity);
+    let _ = writeln!(out, "- **State:** {}", incident.state);
+    let _ = writeln!(
+        out,
+        "- **Triggered:** {} by {}",
+        incident.triggered_at.to_rfc3339(),
+        incident.triggered_by
+    );
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Summary");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{}",
+        incident
+            .description
+            .as_deref()
+            .unwrap_or("_No description recorded._")
+    );
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Response Metrics");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Metric | Duration |");
+    let _ = writeln!(out, "|---|---|");
+    for (label, at) in [
+        ("Time to respond", incident.responding_at),
+        ("Time to contain", incident.contained_at),
+        ("Time to recover", incident.recovered_at),
+    ] {
+        let _ = writeln!(
+            out,
+            "| {} | {} |",
+            label,
+            format_minutes(minutes_between(incident.triggered_at, at))
+        );
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Timeline (UTC)");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Time | Author | Event |");
+    let _ = writeln!(out, "|---|---|---|");
+    for update in &detail.timeline {
+        let event = match (update.from_state, update.to_state) {
+            (Some(from), Some(to)) => format!("**{} → {}** — {}", from, to, update.message),
+            (None, Some(to)) => format!("**{}** — {}", to, update.message),
+            _ => update.message.clone(),
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} |",
+            update.created_at.format("%Y-%m-%d %H:%M"),
+            update.author,
+            event.replace('|', "\\|")
+        );
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Systems Impacted");
+    let _ = writeln!(out);
+    for affected in &detail.affected_contracts {
+        let relation = match affected.impact {
+            IncidentImpact::Origin => "origin".to_string(),
+            IncidentImpact::Dependent => format!("dependent, {} hop(s)", affected.depth),
+        };
+        let _ = writeln!(
+            out,
+            "- {} (`{}`) — {}",
+            affected.contract_name, affected.contract_id, relation
+        );
+    }
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Root Cause");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{}",
+        incident
+            .root_cause
+            .as_deref()
+            .unwrap_or("_To be completed during the post-mortem meeting._")
+    );
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Resolution");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{}",
+        incident
+            .resolution
+            .as_deref()
+            .unwrap_or("_To be completed during the post-mortem meeting._")
+    );
+    let _ = writeln!(out);
+
+    let _ = writeln!(out, "## Action Items");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| Action | Owner | Due |");
+    let _ = writeln!(out, "|---|---|---|");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use shared::{Incident, IncidentAffectedContract, IncidentUpdate};
+
+    #[test]
+    fn only_critical_incidents_engage_the_breaker() {
+        assert!(breaker_on_trigger(IncidentSeverity::Critical));
+        assert!(!breaker_on_trigger(IncidentSeverity::High));
+    }
+
+    #[test]
+    fn transitions_follow_the_lifecycle() {
+        use IncidentState::*;
+
+        assert_eq!(transition(Detected, Responding, true), Ok(true));
+        assert_eq!(transition(Responding, Contained, true), Ok(true));
+        assert_eq!(transition(Contained, Recovered, true), Ok(false));
+        // Skipping straight to post-review also releases the breaker
+        assert_eq!(transition(Contained, PostReview, true), Ok(false));
+        // Containment failed: back to responding without re-engaging
+        assert_eq!(transition(Recovered, Responding, false), Ok(false));
+
+        assert!(transition(Detected, Detected, false)
+            .unwrap_err()
+            .contains("already in state"));
+        assert!(transition(Responding, Detected, false).is_err());
+        assert!(transition(PostReview, Responding, false).is_err());
+    }
+
+    #[test]
+    fn affected_contracts_walks_dependents_breadth_first() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let (origin, a, b, c, unrelated) = (ids[0], ids[1], ids[2], ids[3], ids[4]);
+        // a and b use origin, c uses both a and b, origin uses c (cycle),
+        // origin uses unrelated (a dependency, not a dependent)
+        let edges = [
+            (a, origin),
+            (b, origin),
+            (c, a),
+            (c, b),
+            (origin, c),
+            (origin, unrelated),
+        ];
+
+        let affected = affected_contracts(origin, &edges);
+        let depth: HashMap<Uuid, i32> = affected.iter().map(|(id, _, d)| (*id, *d)).collect();
+
+        assert_eq!(affected.len(), 4);
+        assert_eq!(affected[0], (origin, IncidentImpact::Origin, 0));
+        assert_eq!(depth[&a], 1);
+        assert_eq!(depth[&b], 1);
+        assert_eq!(depth[&c], 2);
+        assert!(!depth.contains_key(&unrelated));
+    }
+
+    #[test]
+    fn postmortem_includes_metrics_timeline_and_impact() {
+        let triggered_at = Utc::now();
+        let incident = Incident {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            title: "Reentrancy in withdraw".into(),
+            description: None,
+            severity: IncidentSeverity::Critical,
+            state: IncidentState::PostReview,
+            breaker_engaged: false,
+            triggered_by: "oncall".into(),
+            triggered_at,
+            responding_at: Some(triggered_at + Duration::minutes(9)),
+            contained_at: Some(triggered_at + Duration::minutes(95)),
+            recovered_at: None,
+            post_review_at: Some(triggered_at + Duration::hours(5)),
+            root_cause: Some("Missing balance check".into()),
+            resolution: None,
+            updated_at: triggered_at,
+        };
+        let detail = IncidentDetail {
+            timeline: vec![IncidentUpdate {
+                id: Uuid::new_v4(),
+                incident_id: incident.id,
+                from_state: Some(IncidentState::Detected),
+                to_state: Some(IncidentState::Responding),
+                message: "Paging security | lead".into(),
+                author: "oncall".into(),
+                created_at: triggered_at,
+            }],
+            affected_contracts: vec![IncidentAffectedContract {
+                contract_id: incident.contract_id,
+                contract_name: "vault".into(),
+                impact: IncidentImpact::Origin,
+                depth: 0,
+            }],
+            incident,
+        };
+
+        let doc = render_postmortem(&detail);
+        assert!(doc.starts_with("# Post-Mortem: Reentrancy in withdraw"));
+        assert!(doc.contains("| Time to respond | 9m |"));
+        assert!(doc.contains("| Time to contain | 1h 35m |"));
+        assert!(doc.contains("| Time to recover | — |"));
+        assert!(doc.contains("**Detected → Responding** — Paging security \\| lead"));
+        assert!(doc.contains("- vault ("));
+        assert!(doc.contains("Missing balance check"));
+    }
+}
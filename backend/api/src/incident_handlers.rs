@@ -0,0 +1,514 @@
+// api/src/incident_handlers.rs
+//
+// Axum handlers for incident management.
+//
+// Routes (register in incident_routes.rs):
+//   POST   /api/incidents                     → trigger_incident
+//   GET    /api/incidents                     → list_incidents
+//   GET    /api/incidents/:id                 → get_incident
+//   POST   /api/incidents/:id/updates         → record_update
+//   POST   /api/incidents/:id/breaker         → set_breaker
+//   GET    /api/incidents/:id/postmortem      → export_postmortem
+//   GET    /api/contracts/:id/incidents       → list_contract_incidents
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use shared::{
+    Incident, IncidentAffectedContract, IncidentDetail, IncidentSeverity, IncidentState,
+    IncidentUpdate, RecordIncidentUpdateRequest, SetCircuitBreakerRequest, TriggerIncidentRequest,
+};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    incident_engine,
+    state::AppState,
+};
+
+fn db_err(op: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = op, error = ?err, "incident database error");
+    ApiError::internal(format!("Database error while trying to {}", op))
+}
+
+/// Accept either a registry UUID or an on-chain contract address.
+async fn resolve_contract(
+    state: &AppState,
+    contract_id: &str,
+    network: Option<&str>,
+) -> ApiResult<(Uuid, String)> {
+    let rows: Vec<(Uuid, String)> = match contract_id.parse::<Uuid>() {
+        Ok(id) => {
+            sqlx::query_as("SELECT id, name FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_all(&state.db)
+                .await
+        }
+        Err(_) => {
+            sqlx::query_as(
+                "SELECT id, name FROM contracts
+                  WHERE contract_id = $1 AND ($2::text IS NULL OR network::text = $2)",
+            )
+            .bind(contract_id)
+            .bind(network)
+            .fetch_all(&state.db)
+            .await
+        }
+    }
+    .map_err(|e| db_err("resolve contract", e))?;
+
+    match rows.len() {
+        0 => Err(ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", contract_id),
+        )),
+        1 => Ok(rows.into_iter().next().unwrap()),
+        _ => Err(ApiError::bad_request(
+            "AmbiguousContract",
+            format!(
+                "Contract {} is registered on several networks; specify network",
+                contract_id
+            ),
+        )),
+    }
+}
+
+async fn fetch_incident(state: &AppState, id: Uuid) -> ApiResult<Incident> {
+    sqlx::query_as("SELECT * FROM incidents WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("fetch incident", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "IncidentNotFound",
+                format!("No incident found with ID: {}", id),
+            )
+        })
+}
+
+async fn load_detail(state: &AppState, incident: Incident) -> ApiResult<IncidentDetail> {
+    let timeline: Vec<IncidentUpdate> = sqlx::query_as(
+        "SELECT * FROM incident_updates WHERE incident_id = $1 ORDER BY created_at, id",
+    )
+    .bind(incident.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch incident timeline", e))?;
+
+    let affected_contracts: Vec<IncidentAffectedContract> = sqlx::query_as(
+        "SELECT a.contract_id, c.name AS contract_name, a.impact, a.depth
+           FROM incident_affected_contracts a
+           JOIN contracts c ON c.id = a.contract_id
+          WHERE a.incident_id = $1
+          ORDER BY a.depth, c.name",
+    )
+    .bind(incident.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch affected contracts", e))?;
+
+    Ok(IncidentDetail {
+        incident,
+        timeline,
+        affected_contracts,
+    })
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/incidents
+// ─────────────────────────────────────────────────────────
+
+/// Open an incident against a contract. The contract's dependents are
+/// linked from the dependency graph, and critical incidents engage the
+/// circuit breaker straight away.
+pub async fn trigger_incident(
+    State(state): State<AppState>,
+    Json(req): Json<TriggerIncidentRequest>,
+) -> ApiResult<(StatusCode, Json<IncidentDetail>)> {
+    if req.triggered_by.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingTriggeredBy",
+            "triggered_by is required",
+        ));
+    }
+
+    let (contract_id, contract_name) =
+        resolve_contract(&state, &req.contract_id, req.network.as_deref()).await?;
+    let title = req
+        .title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| format!("{} incident on {}", req.severity, contract_name));
+    let breaker = incident_engine::breaker_on_trigger(req.severity);
+
+    let edges: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "SELECT contract_id, dependency_contract_id FROM contract_dependencies
+          WHERE dependency_contract_id IS NOT NULL",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("load dependency graph", e))?;
+    let affected = incident_engine::affected_contracts(contract_id, &edges);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin incident transaction", e))?;
+
+    let incident: Incident = sqlx::query_as(
+        "INSERT INTO incidents (contract_id, title, description, severity, breaker_engaged, triggered_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(&title)
+    .bind(&req.description)
+    .bind(req.severity)
+    .bind(breaker)
+    .bind(&req.triggered_by)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("create incident", e))?;
+
+    let mut message = format!("Incident triggered ({})", req.severity);
+    if breaker {
+        message.push_str("; circuit breaker engaged");
+    }
+    sqlx::query(
+        "INSERT INTO incident_updates (incident_id, to_state, message, author)
+         VALUES ($1, 'detected', $2, $3)",
+    )
+    .bind(incident.id)
+    .bind(&message)
+    .bind(&req.triggered_by)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_err("record incident timeline", e))?;
+
+    for (affected_id, impact, depth) in &affected {
+        sqlx::query(
+            "INSERT INTO incident_affected_contracts (incident_id, contract_id, impact, depth)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(incident.id)
+        .bind(affected_id)
+        .bind(impact)
+        .bind(depth)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("link affected contract", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit incident", e))?;
+
+    tracing::warn!(
+        incident_id = %incident.id,
+        contract_id = %contract_id,
+        severity    = %req.severity,
+        dependents  = affected.len() - 1,
+        breaker,
+        "incident triggered"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(load_detail(&state, incident).await?),
+    ))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/incidents
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ListIncidentsParams {
+    pub state: Option<IncidentState>,
+    pub severity: Option<IncidentSeverity>,
+    /// Only incidents that are still being worked on
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+pub async fn list_incidents(
+    State(state): State<AppState>,
+    Query(params): Query<ListIncidentsParams>,
+) -> ApiResult<Json<Vec<Incident>>> {
+    let incidents: Vec<Incident> = sqlx::query_as(
+        "SELECT * FROM incidents
+          WHERE ($1::incident_state IS NULL OR state = $1)
+            AND ($2::incident_severity IS NULL OR severity = $2)
+            AND (NOT $3 OR state IN ('detected', 'responding', 'contained'))
+          ORDER BY triggered_at DESC
+          LIMIT $4",
+    )
+    .bind(params.state)
+    .bind(params.severity)
+    .bind(params.active)
+    .bind(params.limit.clamp(1, 500))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list incidents", e))?;
+
+    Ok(Json(incidents))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/incidents
+// ─────────────────────────────────────────────────────────
+
+/// Incidents the contract is affected by, either as origin or dependent.
+pub async fn list_contract_incidents(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<Vec<Incident>>> {
+    let incidents: Vec<Incident> = sqlx::query_as(
+        "SELECT i.* FROM incidents i
+           JOIN incident_affected_contracts a ON a.incident_id = i.id
+          WHERE a.contract_id = $1
+          ORDER BY i.triggered_at DESC",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list contract incidents", e))?;
+
+    Ok(Json(incidents))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/incidents/:id
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_incident(
+    State(state): State<AppState>,
+    Path(incident_id): Path<Uuid>,
+) -> ApiResult<Json<IncidentDetail>> {
+    let incident = fetch_incident(&state, incident_id).await?;
+    Ok(Json(load_detail(&state, incident).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/incidents/:id/updates
+// ─────────────────────────────────────────────────────────
+
+/// Add a timeline entry, optionally moving the incident to a new state and
+/// recording the root cause or resolution.
+pub async fn record_update(
+    State(state): State<AppState>,
+    Path(incident_id): Path<Uuid>,
+    Json(req): Json<RecordIncidentUpdateRequest>,
+) -> ApiResult<Json<IncidentDetail>> {
+    if req.author.trim().is_empty() || req.message.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidUpdate",
+            "author and message are required",
+        ));
+    }
+
+    let incident = fetch_incident(&state, incident_id).await?;
+    let breaker = match req.state {
+        Some(next) => {
+            incident_engine::transition(incident.state, next, incident.breaker_engaged)
+                .map_err(|msg| ApiError::new(StatusCode::CONFLICT, "InvalidTransition", msg))?
+        }
+        None => incident.breaker_engaged,
+    };
+    let next_state = req.state.unwrap_or(incident.state);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin incident transaction", e))?;
+
+    // Each *_at column records the first time the state was entered
+    let updated: Incident = sqlx::query_as(
+        "UPDATE incidents
+            SET state           = $2,
+                breaker_engaged = $3,
+                root_cause      = COALESCE($4, root_cause),
+                resolution      = COALESCE($5, resolution),
+                responding_at   = CASE WHEN $2 = 'responding'  THEN COALESCE(responding_at, NOW())  ELSE responding_at END,
+                contained_at    = CASE WHEN $2 = 'contained'   THEN COALESCE(contained_at, NOW())   ELSE contained_at END,
+                recovered_at    = CASE WHEN $2 = 'recovered'   THEN COALESCE(recovered_at, NOW())   ELSE recovered_at END,
+                post_review_at  = CASE WHEN $2 = 'post_review' THEN COALESCE(post_review_at, NOW()) ELSE post_review_at END,
+                updated_at      = NOW()
+          WHERE id = $1 AND state = $6
+          RETURNING *",
+    )
+    .bind(incident_id)
+    .bind(next_state)
+    .bind(breaker)
+    .bind(&req.root_cause)
+    .bind(&req.resolution)
+    .bind(incident.state)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_err("update incident", e))?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "ConcurrentUpdate",
+            "Incident state changed while the update was being applied; retry",
+        )
+    })?;
+
+    let mut message = req.message.clone();
+    if incident.breaker_engaged && !breaker {
+        message.push_str(" (circuit breaker released)");
+    }
+    sqlx::query(
+        "INSERT INTO incident_updates (incident_id, from_state, to_state, message, author)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(incident_id)
+    .bind(req.state.map(|_| incident.state))
+    .bind(req.state)
+    .bind(&message)
+    .bind(&req.author)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_err("record incident timeline", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit incident update", e))?;
+
+    Ok(Json(load_detail(&state, updated).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/incidents/:id/breaker
+// ─────────────────────────────────────────────────────────
+
+/// Engage or release the circuit breaker by hand, e.g. to halt writes during
+/// a high-severity incident or to lift the halt once a fix is deployed.
+pub async fn set_breaker(
+    State(state): State<AppState>,
+    Path(incident_id): Path<Uuid>,
+    Json(req): Json<SetCircuitBreakerRequest>,
+) -> ApiResult<Json<IncidentDetail>> {
+    if req.author.trim().is_empty() {
+        return Err(ApiError::bad_request("MissingAuthor", "author is required"));
+    }
+
+    let incident = fetch_incident(&state, incident_id).await?;
+    if req.engaged && !incident.state.is_active() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "IncidentResolved",
+            format!(
+                "Cannot engage the circuit breaker for an incident in state {}",
+                incident.state
+            ),
+        ));
+    }
+    if req.engaged == incident.breaker_engaged {
+        return Ok(Json(load_detail(&state, incident).await?));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin incident transaction", e))?;
+
+    let updated: Incident = sqlx::query_as(
+        "UPDATE incidents SET breaker_engaged = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(incident_id)
+    .bind(req.engaged)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("update circuit breaker", e))?;
+
+    let mut message = if req.engaged {
+        "Circuit breaker engaged".to_string()
+    } else {
+        "Circuit breaker released".to_string()
+    };
+    if let Some(reason) = req.reason.as_deref().filter(|r| !r.trim().is_empty()) {
+        message = format!("{}: {}", message, reason);
+    }
+    sqlx::query("INSERT INTO incident_updates (incident_id, message, author) VALUES ($1, $2, $3)")
+        .bind(incident_id)
+        .bind(&message)
+        .bind(&req.author)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("record incident timeline", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit circuit breaker change", e))?;
+
+    tracing::warn!(
+        incident_id = %incident_id,
+        contract_id = %updated.contract_id,
+        engaged     = req.engaged,
+        "circuit breaker changed"
+    );
+
+    Ok(Json(load_detail(&state, updated).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/incidents/:id/postmortem
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct PostmortemParams {
+    /// `markdown` (default) or `json`
+    pub format: Option<String>,
+}
+
+pub async fn export_postmortem(
+    State(state): State<AppState>,
+    Path(incident_id): Path<Uuid>,
+    Query(params): Query<PostmortemParams>,
+) -> ApiResult<Response> {
+    let incident = fetch_incident(&state, incident_id).await?;
+    let detail = load_detail(&state, incident).await?;
+
+    match params.format.as_deref().unwrap_or("markdown") {
+        "json" => Ok(Json(detail).into_response()),
+        "markdown" | "md" => {
+            let filename = format!(
+                "postmortem-{}-{}.md",
+                detail.incident.triggered_at.format("%Y%m%d"),
+                detail.incident.id
+            );
+            Ok((
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, "text/markdown; charset=utf-8"),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        &format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                incident_engine::render_postmortem(&detail),
+            )
+                .into_response())
+        }
+        other => Err(ApiError::bad_request(
+            "InvalidFormat",
+            format!("Unsupported format '{}' (expected markdown|json)", other),
+        )),
+    }
+}
@@ -0,0 +1,36 @@
+// incident_routes.rs
+// Route definitions for incident management
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{incident_handlers, state::AppState};
+
+pub fn incident_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/incidents",
+            post(incident_handlers::trigger_incident).get(incident_handlers::list_incidents),
+        )
+        .route("/api/incidents/:id", get(incident_handlers::get_incident))
+        // Timeline entries and state transitions
+        .route(
+            "/api/incidents/:id/updates",
+            post(incident_handlers::record_update),
+        )
+        // Manual circuit breaker control
+        .route(
+            "/api/incidents/:id/breaker",
+            post(incident_handlers::set_breaker),
+        )
+        .route(
+            "/api/incidents/:id/postmortem",
+            get(incident_handlers::export_postmortem),
+        )
+        .route(
+            "/api/contracts/:id/incidents",
+            get(incident_handlers::list_contract_incidents),
+        )
+}
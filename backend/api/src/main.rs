@@ -1,23 +1,98 @@
+mod ab_test_engine;
+mod ab_test_handlers;
+mod ab_test_routes;
+mod advisory_engine;
 mod aggregation;
 mod analytics;
 //mod audit_handlers;
 //mod audit_routes;
-//mod benchmark_engine;
+mod backup_engine;
+mod backup_handlers;
+mod backup_replication;
+mod backup_routes;
+mod benchmark_engine;
 //mod benchmark_handlers;
 //mod benchmark_routes;
+mod blob_store;
 mod cache;
+mod canary_controller;
+mod canary_engine;
+mod canary_handlers;
+mod canary_routes;
+mod cargo_lock;
+mod capacity_engine;
+mod capacity_forecast;
+mod capacity_handlers;
+mod capacity_routes;
+mod config_handlers;
+mod config_routes;
+mod contract_history_handlers;
+mod contract_history_routes;
+mod cost_handlers;
+mod cost_routes;
 //mod cache_benchmark;
 //mod checklist;
 //mod detector;
 mod error;
+mod feature_flag_engine;
+mod feature_flag_handlers;
+mod feature_flag_routes;
+mod governance_handlers;
+mod governance_routes;
+mod governance_tally;
+mod graph_engine;
+mod graph_handlers;
+mod graph_routes;
 mod handlers;
-//mod multisig_handlers;
-//mod multisig_routes;
+mod incident_engine;
+mod incident_handlers;
+mod incident_routes;
+mod maintenance_middleware;
+mod metrics;
+mod multisig_handlers;
+mod multisig_routes;
 //mod models;
+mod namespace;
+mod organization_handlers;
+mod organization_routes;
+mod patch_engine;
+mod patch_handlers;
+mod patch_rollout;
+mod patch_routes;
+mod proposal_execution;
 mod rate_limit;
+mod regression_engine;
+mod regression_handlers;
+mod regression_routes;
+mod residency_engine;
+mod residency_handlers;
+mod residency_routes;
 mod routes;
+mod scan_handlers;
+mod scan_routes;
+mod scanner_service;
 //mod scoring;
+mod search_engine;
+mod search_handlers;
+mod search_index;
+mod search_routes;
+mod similarity_engine;
+mod similarity_handlers;
+mod similarity_routes;
+mod similarity_service;
+mod sla_engine;
+mod sla_handlers;
+mod sla_monitor;
+mod sla_routes;
+mod soroban_fees;
 mod state;
+mod trust;
+mod trust_handlers;
+mod trust_routes;
+mod trust_service;
+mod type_safety;
+mod upgrade_safety;
+mod validation;
 
 use anyhow::Result;
 use axum::http::{header, HeaderValue, Method};
@@ -64,6 +139,24 @@ async fn main() -> Result<()> {
     // Spawn the hourly analytics aggregation background task
     aggregation::spawn_aggregation_task(pool.clone());
 
+    // Spawn the SLA probe and window evaluation background task
+    sla_monitor::spawn_sla_monitor(pool.clone());
+
+    // Spawn the security patch rollout scheduler
+    patch_rollout::spawn_patch_rollout_scheduler(pool.clone());
+
+    // Spawn the canary release controller
+    canary_controller::spawn_canary_controller(pool.clone());
+
+    // Spawn the hourly search index refresh (trust scores and vocabulary)
+    search_index::spawn_search_index_task(pool.clone());
+
+    // Spawn the daily advisory sync; new advisories trigger a dependency rescan
+    scanner_service::spawn_advisory_sync(pool.clone());
+
+    // Spawn the backup replication task; retries unfinished copies and logs lagging regions
+    backup_handlers::spawn_replication_task(pool.clone());
+
     // Create prometheus registry for metrics
     let registry = Registry::new();
 
@@ -85,10 +178,34 @@ async fn main() -> Result<()> {
         .merge(routes::publisher_routes())
         .merge(routes::health_routes())
         .merge(routes::migration_routes())
-        //.merge(multisig_routes::multisig_routes())
+        .merge(sla_routes::sla_routes())
+        .merge(patch_routes::patch_routes())
+        .merge(canary_routes::canary_routes())
+        .merge(ab_test_routes::ab_test_routes())
+        .merge(feature_flag_routes::feature_flag_router())
+        .merge(capacity_routes::capacity_router())
+        .merge(organization_routes::organization_routes())
+        .merge(search_routes::search_routes())
+        .merge(similarity_routes::similarity_routes())
+        .merge(graph_routes::graph_routes())
+        .merge(trust_routes::trust_routes())
+        .merge(incident_routes::incident_routes())
+        .merge(regression_routes::regression_routes())
+        .merge(contract_history_routes::contract_history_routes())
+        .merge(config_routes::config_routes())
+        .merge(multisig_routes::multisig_routes())
+        .merge(governance_routes::governance_routes())
+        .merge(cost_routes::cost_routes())
+        .merge(scan_routes::scan_routes())
+        .merge(residency_routes::residency_routes())
+        .merge(backup_routes::backup_routes())
         //.merge(audit_routes::security_audit_routes())
         //.merge(benchmark_routes::benchmark_routes())
         .fallback(handlers::route_not_found)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            maintenance_middleware::maintenance_check,
+        ))
         .layer(middleware::from_fn(request_logger))
         .layer(middleware::from_fn_with_state(
             rate_limit_state,
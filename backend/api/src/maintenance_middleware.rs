@@ -24,6 +24,32 @@ pub async fn maintenance_check(
 
     // Extract contract_id from path if present
     if let Some(contract_id) = extract_contract_id(path) {
+        // An incident's circuit breaker halts writes to its contract and to
+        // every contract linked to it as affected, regardless of maintenance
+        let breaker = sqlx::query_as::<_, (uuid::Uuid, String)>(
+            "SELECT i.id, i.title FROM incidents i
+             LEFT JOIN incident_affected_contracts a
+                    ON a.incident_id = i.id AND a.contract_id = $1::uuid
+             WHERE i.breaker_engaged AND (i.contract_id = $1::uuid OR a.contract_id IS NOT NULL)
+             ORDER BY i.triggered_at DESC LIMIT 1"
+        )
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .unwrap_or(None);
+
+        if let Some((incident_id, title)) = breaker {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "error": "circuit_breaker",
+                    "message": format!("Contract is halted by incident: {}", title),
+                    "incident_id": incident_id
+                })),
+            )
+                .into_response();
+        }
+
         let is_maintenance = sqlx::query_scalar::<_, bool>(
             "SELECT is_maintenance FROM contracts WHERE id = $1::uuid",
         )
@@ -10,15 +10,17 @@ use axum::{
 use chrono::Utc;
 use serde::Deserialize;
 use shared::{
-    CreatePolicyRequest, CreateProposalRequest, DeployProposal, MultisigPolicy, ProposalSignature,
-    ProposalStatus, ProposalWithSignatures, SignProposalRequest,
+    CancelExecutionRequest, CreatePolicyRequest, CreateProposalRequest, DeployProposal,
+    ExecuteProposalRequest, MultisigPolicy, ProposalAction, ProposalExecution,
+    ProposalExecutionStatus, ProposalSignature, ProposalSource, ProposalStatus,
+    ProposalWithSignatures, SignProposalRequest,
 };
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
     handlers::db_internal_error,
-    resource_tracking::ResourceUsage,
+    proposal_execution,
     state::AppState,
 };
 
@@ -58,6 +60,43 @@ async fn expire_proposal(state: &AppState, id: Uuid) -> ApiResult<()> {
     Ok(())
 }
 
+/// Queue an approved proposal behind its timelock. Proposals for a contract
+/// already in the registry default to upgrading it to the proposed WASM;
+/// proposals for a contract that is not registered yet have nothing to apply.
+async fn queue_execution(
+    state: &AppState,
+    proposal: &DeployProposal,
+) -> ApiResult<ProposalExecution> {
+    let registered: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM contracts WHERE contract_id = $1 AND network = $2",
+    )
+    .bind(&proposal.contract_id)
+    .bind(&proposal.network)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("resolve proposal contract", err))?;
+
+    let action = proposal_execution::parse_action(proposal.action.as_ref())
+        .map_err(|err| ApiError::internal(format!("Stored proposal action is invalid: {}", err)))?
+        .or_else(|| {
+            registered.map(|_| ProposalAction::UpgradeWasm {
+                wasm_hash: proposal.wasm_hash.clone(),
+                version: None,
+            })
+        });
+
+    proposal_execution::queue(
+        &state.db,
+        ProposalSource::Multisig,
+        proposal.id,
+        registered,
+        action.as_ref(),
+        proposal_execution::eta(Utc::now(), Some(proposal.execution_delay_hours)),
+    )
+    .await
+    .map_err(|err| db_internal_error("queue proposal", err))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // POST /api/multisig/policies
 // ─────────────────────────────────────────────────────────────────────────────
@@ -151,6 +190,17 @@ pub async fn create_proposal(
             "proposer is required",
         ));
     }
+    let execution_delay_hours = req.execution_delay_hours.unwrap_or(0);
+    if execution_delay_hours < 0 {
+        return Err(ApiError::bad_request(
+            "InvalidExecutionDelay",
+            "execution_delay_hours cannot be negative",
+        ));
+    }
+    if let Some(action) = &req.action {
+        proposal_execution::validate_action(action)
+            .map_err(|msg| ApiError::bad_request("InvalidAction", msg))?;
+    }
 
     // Look up the policy to compute expires_at
     let policy: MultisigPolicy = sqlx::query_as("SELECT * FROM multisig_policies WHERE id = $1")
@@ -169,8 +219,9 @@ pub async fn create_proposal(
 
     let proposal: DeployProposal = sqlx::query_as(
         "INSERT INTO deploy_proposals
-            (contract_name, contract_id, wasm_hash, network, description, policy_id, expires_at, proposer)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            (contract_name, contract_id, wasm_hash, network, description, policy_id, expires_at, proposer,
+             action, execution_delay_hours)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
          RETURNING *",
     )
     .bind(&req.contract_name)
@@ -181,6 +232,12 @@ pub async fn create_proposal(
     .bind(req.policy_id)
     .bind(expires_at)
     .bind(&req.proposer)
+    .bind(
+        req.action
+            .as_ref()
+            .map(|a| serde_json::to_value(a).unwrap_or_default()),
+    )
+    .bind(execution_delay_hours)
     .fetch_one(&state.db)
     .await
     .map_err(|err| db_internal_error("create deploy proposal", err))?;
@@ -206,7 +263,8 @@ pub async fn create_proposal(
 /// - Signer is in the policy's signer list
 /// - Signer has not already signed
 ///
-/// If the threshold is met after this signature the proposal moves to `approved`.
+/// If the threshold is met after this signature the proposal moves to `approved`
+/// and its action is queued behind the proposal's execution delay.
 pub async fn sign_proposal(
     State(state): State<AppState>,
     Path(proposal_id): Path<Uuid>,
@@ -298,6 +356,7 @@ pub async fn sign_proposal(
         .await
         .map_err(|err| db_internal_error("approve proposal", err))?;
         proposal.status = ProposalStatus::Approved;
+        queue_execution(&state, &proposal).await?;
 
         tracing::info!(
             proposal_id = %proposal_id,
@@ -325,26 +384,17 @@ pub async fn sign_proposal(
 // POST /api/contracts/{id}/execute
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Execute an approved deployment proposal. The proposal must be in `approved`
-/// status and not expired. Once executed the status transitions to `executed`.
+/// Execute an approved deployment proposal once its timelock has expired.
+/// `expires_at` only bounds signature collection; an approved proposal stays
+/// executable for the queue's grace period. Once executed the status
+/// transitions to `executed`.
 pub async fn execute_proposal(
     State(state): State<AppState>,
     Path(proposal_id): Path<Uuid>,
+    body: Option<Json<ExecuteProposalRequest>>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let proposal = fetch_proposal(&state, proposal_id).await?;
 
-    // Check expiry even for approved proposals
-    if Utc::now() > proposal.expires_at {
-        if proposal.status != ProposalStatus::Executed {
-            expire_proposal(&state, proposal_id).await?;
-        }
-        return Err(ApiError::new(
-            StatusCode::GONE,
-            "ProposalExpired",
-            "This proposal has expired and cannot be executed",
-        ));
-    }
-
     if proposal.status != ProposalStatus::Approved {
         return Err(ApiError::bad_request(
             "ProposalNotApproved",
@@ -355,6 +405,24 @@ pub async fn execute_proposal(
         ));
     }
 
+    queue_execution(&state, &proposal).await?;
+    let executed_by = body
+        .map(|Json(req)| req.executed_by)
+        .unwrap_or_else(|| proposal.proposer.clone());
+    let execution =
+        proposal_execution::execute(&state, ProposalSource::Multisig, proposal_id, &executed_by)
+            .await?;
+
+    if execution.status != ProposalExecutionStatus::Executed {
+        return Err(ApiError::unprocessable(
+            "ExecutionFailed",
+            format!(
+                "Proposal action failed: {}",
+                execution.error.as_deref().unwrap_or("unknown error")
+            ),
+        ));
+    }
+
     // Mark as executed
     sqlx::query(
         "UPDATE deploy_proposals
@@ -372,20 +440,6 @@ pub async fn execute_proposal(
         wasm_hash    = %proposal.wasm_hash,
         "deployment proposal executed"
     );
-    let wasm_len = proposal.wasm_hash.len() as u64;
-    let cpu = 2_400_000 + wasm_len.saturating_mul(140);
-    let mem = 4_000_000 + wasm_len.saturating_mul(96);
-    let contract_id = proposal.contract_id.to_string();
-    let mut mgr = state.resource_mgr.write().unwrap();
-    let _ = mgr.record_usage(
-        &contract_id,
-        ResourceUsage {
-            cpu_instructions: cpu,
-            mem_bytes: mem,
-            storage_bytes: wasm_len,
-            timestamp: Utc::now(),
-        },
-    );
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -393,10 +447,75 @@ pub async fn execute_proposal(
         "contract_id": proposal.contract_id,
         "wasm_hash": proposal.wasm_hash,
         "executed_at": Utc::now().to_rfc3339(),
+        "execution": execution,
         "message": "Deployment proposal executed successfully"
     })))
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// POST /api/contracts/{id}/cancel
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Withdraw an approved proposal from the execution queue. Only possible
+/// while its timelock is still running; the proposal moves to `rejected`.
+pub async fn cancel_proposal(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+    payload: Result<Json<CancelExecutionRequest>, axum::extract::rejection::JsonRejection>,
+) -> ApiResult<Json<ProposalExecution>> {
+    let Json(req) = payload.map_err(map_json_rejection)?;
+
+    let proposal = fetch_proposal(&state, proposal_id).await?;
+    if proposal.status != ProposalStatus::Approved {
+        return Err(ApiError::bad_request(
+            "ProposalNotApproved",
+            format!(
+                "Only approved proposals can be cancelled. Current status: '{}'",
+                proposal.status
+            ),
+        ));
+    }
+
+    queue_execution(&state, &proposal).await?;
+    let execution =
+        proposal_execution::cancel(&state.db, ProposalSource::Multisig, proposal_id, &req).await?;
+
+    sqlx::query("UPDATE deploy_proposals SET status = 'rejected', updated_at = NOW() WHERE id = $1")
+        .bind(proposal_id)
+        .execute(&state.db)
+        .await
+        .map_err(|err| db_internal_error("cancel proposal", err))?;
+
+    tracing::info!(
+        proposal_id  = %proposal_id,
+        cancelled_by = %req.cancelled_by,
+        "deployment proposal cancelled"
+    );
+
+    Ok(Json(execution))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// GET /api/contracts/{id}/execution
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Return the queued execution for an approved proposal.
+pub async fn get_execution(
+    State(state): State<AppState>,
+    Path(proposal_id): Path<Uuid>,
+) -> ApiResult<Json<ProposalExecution>> {
+    proposal_execution::fetch_execution(&state.db, ProposalSource::Multisig, proposal_id)
+        .await
+        .map_err(|err| db_internal_error("fetch proposal execution", err))?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "ExecutionNotFound",
+                format!("Proposal {} has not been queued for execution", proposal_id),
+            )
+        })
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // GET /api/contracts/{id}/proposal
 // ─────────────────────────────────────────────────────────────────────────────
@@ -458,8 +577,8 @@ pub async fn list_proposals(
     let mut where_clauses: Vec<String> = Vec::new();
     let mut arg_idx = 1usize;
 
-    if let Some(ref s) = params.status {
-        where_clauses.push(format!("status = ${}", arg_idx));
+    if params.status.is_some() {
+        where_clauses.push(format!("status::text = ${}", arg_idx));
         arg_idx += 1;
     }
     if params.policy_id.is_some() {
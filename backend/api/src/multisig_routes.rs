@@ -36,6 +36,16 @@ pub fn multisig_routes() -> Router<AppState> {
             "/api/contracts/:id/execute",
             post(multisig_handlers::execute_proposal),
         )
+        // Cancel an approved proposal while its timelock is running
+        .route(
+            "/api/contracts/:id/cancel",
+            post(multisig_handlers::cancel_proposal),
+        )
+        // Timelock queue entry for an approved proposal
+        .route(
+            "/api/contracts/:id/execution",
+            get(multisig_handlers::get_execution),
+        )
         // Retrieve full proposal info with signatures and policy
         .route(
             "/api/contracts/:id/proposal",
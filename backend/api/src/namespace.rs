@@ -0,0 +1,533 @@
+// api/src/namespace.rs
+//
+// Publisher namespaces and organization verification. Contracts can be
+// published under an organization's scope (`@org/contract`); unscoped names
+// stay in a shared pool, where reserved names are protected and names that
+// look like a popular contract's are refused as likely typosquats. Names are
+// compared by a skeleton (lowercase, separators dropped, look-alike digits
+// folded) and the edit distance between skeletons.
+//
+// An organization is verified by its Stellar home domain: the domain's
+// `stellar.toml` lists the organization's account under `ACCOUNTS` and
+// carries, under `[SOROBAN_REGISTRY]`, a statement signed by that account.
+// Everything in here is synchronous.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+/// Organization slugs follow GitHub's limit
+pub const MAX_SLUG_LEN: usize = 39;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Names
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Normalise and check an organization slug: lowercase ASCII letters,
+/// digits and single hyphens, not at either end.
+pub fn validate_slug(slug: &str) -> Result<String, String> {
+    let slug = slug.trim().trim_start_matches('@').to_ascii_lowercase();
+    if slug.len() < 2 || slug.len() > MAX_SLUG_LEN {
+        return Err(format!(
+            "organization names must be 2 to {} characters",
+            MAX_SLUG_LEN
+        ));
+    }
+    if !slug
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err("organization names may only contain letters, digits and hyphens".into());
+    }
+    if slug.starts_with('-') || slug.ends_with('-') || slug.contains("--") {
+        return Err("organization names cannot start or end with a hyphen or repeat one".into());
+    }
+    Ok(slug)
+}
+
+/// A contract name, optionally scoped to an organization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopedName {
+    pub namespace: Option<String>,
+    pub name: String,
+}
+
+impl FromStr for ScopedName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some(scoped) = s.strip_prefix('@') else {
+            return Ok(Self {
+                namespace: None,
+                name: s.to_string(),
+            });
+        };
+        let (namespace, name) = scoped
+            .split_once('/')
+            .ok_or_else(|| format!("scoped name '{}' must look like @org/contract", s))?;
+        let name = name.trim();
+        if name.is_empty() || name.contains('/') {
+            return Err(format!("scoped name '{}' must look like @org/contract", s));
+        }
+        Ok(Self {
+            namespace: Some(validate_slug(namespace)?),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ScopedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.namespace {
+            Some(ns) => write!(f, "@{}/{}", ns, self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Similarity
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Fold a name to the form it is compared in: `USDC_Token`, `usdc-token`
+/// and `usdc-t0ken` all become `usdctoken`.
+pub fn skeleton(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '0' => 'o',
+            '1' => 'l',
+            '3' => 'e',
+            '5' => 's',
+            _ => c,
+        })
+        .collect();
+    folded.replace("rn", "m").replace("vv", "w")
+}
+
+/// Edit distance counting insertions, deletions, substitutions and
+/// transpositions of adjacent characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// How many edits still count as confusable; short names only clash on an
+/// identical skeleton
+fn max_distance(len: usize) -> usize {
+    match len {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameMatch {
+    pub name: String,
+    pub distance: usize,
+}
+
+/// The names in `known` that `candidate` could be mistaken for, closest
+/// first. Identical skeletons match at distance 0.
+pub fn similar_names<'a>(
+    candidate: &str,
+    known: impl IntoIterator<Item = &'a str>,
+) -> Vec<NameMatch> {
+    let target = skeleton(candidate);
+    if target.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<NameMatch> = known
+        .into_iter()
+        .filter_map(|name| {
+            let other = skeleton(name);
+            let limit = max_distance(target.chars().count().min(other.chars().count()));
+            let distance = edit_distance(&target, &other);
+            (distance <= limit).then(|| NameMatch {
+                name: name.to_string(),
+                distance,
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    matches.dedup_by(|a, b| a.name.eq_ignore_ascii_case(&b.name));
+    matches
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Roles
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OrgRole {
+    Publisher,
+    Admin,
+    Owner,
+}
+
+impl OrgRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Publisher => "publisher",
+            Self::Admin => "admin",
+            Self::Owner => "owner",
+        }
+    }
+
+    /// Add and remove members, and verify the organization
+    pub fn can_manage(self) -> bool {
+        self >= Self::Admin
+    }
+
+    /// Whether this role may grant or revoke `other`; only owners touch owners
+    pub fn can_assign(self, other: OrgRole) -> bool {
+        self.can_manage() && (self == Self::Owner || other < Self::Owner)
+    }
+}
+
+impl FromStr for OrgRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "publisher" => Ok(Self::Publisher),
+            "admin" => Ok(Self::Admin),
+            "owner" => Ok(Self::Owner),
+            other => Err(format!(
+                "unknown role '{}' (expected owner, admin or publisher)",
+                other
+            )),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Home domain verification
+// ─────────────────────────────────────────────────────────────────────────────
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// Strkey version byte of an ed25519 account id (`G…`)
+const ACCOUNT_VERSION_BYTE: u8 = 6 << 3;
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// The ed25519 public key behind a `G…` account id.
+pub fn decode_account_id(address: &str) -> Result<[u8; 32], String> {
+    let invalid = || format!("'{}' is not a valid Stellar account id", address);
+    let mut bits: u64 = 0;
+    let mut width = 0;
+    let mut bytes = Vec::with_capacity(35);
+    for c in address.trim().bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or_else(invalid)?;
+        bits = (bits << 5) | value as u64;
+        width += 5;
+        if width >= 8 {
+            width -= 8;
+            bytes.push((bits >> width) as u8);
+            bits &= (1 << width) - 1;
+        }
+    }
+    if bytes.len() != 35 || bytes[0] != ACCOUNT_VERSION_BYTE {
+        return Err(invalid());
+    }
+    let checksum = u16::from_le_bytes([bytes[33], bytes[34]]);
+    if crc16_xmodem(&bytes[..33]) != checksum {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes[1..33]);
+    Ok(key)
+}
+
+/// Where an organization's home domain publishes its `stellar.toml`
+pub fn stellar_toml_url(home_domain: &str) -> String {
+    format!("https://{}/.well-known/stellar.toml", home_domain)
+}
+
+/// Accept a bare host name only, so verification cannot be pointed at an
+/// arbitrary URL
+pub fn validate_home_domain(domain: &str) -> Result<String, String> {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    let valid = domain.len() <= 253
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && !domain
+            .split('.')
+            .all(|label| label.chars().all(|c| c.is_ascii_digit()));
+    if valid {
+        Ok(domain)
+    } else {
+        Err(format!("'{}' is not a valid home domain", domain))
+    }
+}
+
+/// The statement an organization's account signs to claim its namespace
+pub fn verification_statement(slug: &str, account: &str, nonce: &str) -> String {
+    format!(
+        "soroban-registry organization verification\norg: @{}\naccount: {}\nnonce: {}",
+        slug, account, nonce
+    )
+}
+
+/// Check a fetched `stellar.toml` against an organization:
+///
+/// ```toml
+/// ACCOUNTS = ["G…"]
+///
+/// [SOROBAN_REGISTRY]
+/// ORG = "@acme"
+/// SIGNATURE = "<base64 ed25519 signature of the statement>"
+/// ```
+pub fn verify_stellar_toml(
+    toml_text: &str,
+    slug: &str,
+    account: &str,
+    statement: &str,
+) -> Result<(), String> {
+    let doc: toml::Value =
+        toml::from_str(toml_text).map_err(|e| format!("stellar.toml does not parse: {}", e))?;
+
+    let listed = doc
+        .get("ACCOUNTS")
+        .and_then(toml::Value::as_array)
+        .is_some_and(|accounts| accounts.iter().any(|a| a.as_str() == Some(account)));
+    if !listed {
+        return Err(format!(
+            "stellar.toml does not list {} under ACCOUNTS",
+            account
+        ));
+    }
+
+    let section = doc
+        .get("SOROBAN_REGISTRY")
+        .ok_or("stellar.toml has no [SOROBAN_REGISTRY] section")?;
+    let org = section.get("ORG").and_then(toml::Value::as_str);
+    if org.map(|o| o.trim_start_matches('@').eq_ignore_ascii_case(slug)) != Some(true) {
+        return Err(format!("[SOROBAN_REGISTRY] ORG is not '@{}'", slug));
+    }
+    let signature = section
+        .get("SIGNATURE")
+        .and_then(toml::Value::as_str)
+        .ok_or("[SOROBAN_REGISTRY] has no SIGNATURE")?;
+
+    verify_account_signature(account, statement, signature).map_err(|e| format!("SIGNATURE {}", e))
+}
+
+/// Check a base64 ed25519 signature over `message` against the key behind
+/// a `G…` account id.
+pub fn verify_account_signature(
+    account: &str,
+    message: &str,
+    signature: &str,
+) -> Result<(), String> {
+    let signature: [u8; 64] = BASE64
+        .decode(signature.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("is not a base64 ed25519 signature")?;
+    let key = VerifyingKey::from_bytes(&decode_account_id(account)?)
+        .map_err(|_| format!("{} is not a valid ed25519 key", account))?;
+    key.verify(message.as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| format!("does not match the statement signed by {}", account))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn encode_account_id(key: &[u8; 32]) -> String {
+        let mut bytes = vec![ACCOUNT_VERSION_BYTE];
+        bytes.extend_from_slice(key);
+        bytes.extend_from_slice(&crc16_xmodem(&bytes).to_le_bytes());
+        let mut out = String::new();
+        let (mut bits, mut width) = (0u64, 0);
+        for b in bytes {
+            bits = (bits << 8) | u64::from(b);
+            width += 8;
+            while width >= 5 {
+                width -= 5;
+                out.push(BASE32_ALPHABET[((bits >> width) & 31) as usize] as char);
+            }
+        }
+        if width > 0 {
+            out.push(BASE32_ALPHABET[((bits << (5 - width)) & 31) as usize] as char);
+        }
+        out
+    }
+
+    #[test]
+    fn parses_scoped_names() {
+        let scoped: ScopedName = "@Acme/usdc-token".parse().unwrap();
+        assert_eq!(scoped.namespace.as_deref(), Some("acme"));
+        assert_eq!(scoped.to_string(), "@acme/usdc-token");
+
+        let plain: ScopedName = "usdc-token".parse().unwrap();
+        assert_eq!(plain.namespace, None);
+
+        assert!("@acme".parse::<ScopedName>().is_err());
+        assert!("@acme/".parse::<ScopedName>().is_err());
+        assert!("@-acme/token".parse::<ScopedName>().is_err());
+        assert!(validate_slug("a").is_err());
+        assert!(validate_slug("acme--labs").is_err());
+        assert_eq!(validate_slug("Acme-Labs").unwrap(), "acme-labs");
+    }
+
+    #[test]
+    fn flags_confusable_names() {
+        let popular = ["usdc-token", "soroswap-router", "blend", "aquarius"];
+
+        let hits = similar_names("usdc_t0ken", popular);
+        assert_eq!(hits[0].name, "usdc-token");
+        assert_eq!(hits[0].distance, 0);
+
+        assert_eq!(similar_names("soroswap-ruoter", popular)[0].distance, 1);
+        assert_eq!(similar_names("aquarlus", popular)[0].name, "aquarius");
+        // Short names only clash when identical
+        assert!(similar_names("blnd", popular).is_empty());
+        assert!(similar_names("my-lending-pool", popular).is_empty());
+        assert_eq!(edit_distance("abcd", "acbd"), 1);
+    }
+
+    #[test]
+    fn orders_roles() {
+        assert!(OrgRole::Owner.can_assign(OrgRole::Owner));
+        assert!(OrgRole::Admin.can_assign(OrgRole::Publisher));
+        assert!(!OrgRole::Admin.can_assign(OrgRole::Owner));
+        assert!(!OrgRole::Publisher.can_manage());
+        assert!("maintainer".parse::<OrgRole>().is_err());
+    }
+
+    #[test]
+    fn decodes_account_ids() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let account = encode_account_id(key.verifying_key().as_bytes());
+        assert!(account.starts_with('G'));
+        assert_eq!(account.len(), 56);
+        assert_eq!(
+            &decode_account_id(&account).unwrap(),
+            key.verifying_key().as_bytes()
+        );
+
+        let mut tampered = account.into_bytes();
+        tampered[10] = if tampered[10] == b'A' { b'B' } else { b'A' };
+        assert!(decode_account_id(&String::from_utf8(tampered).unwrap()).is_err());
+        assert!(decode_account_id("GABC").is_err());
+    }
+
+    #[test]
+    fn verifies_signed_stellar_toml() {
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let account = encode_account_id(key.verifying_key().as_bytes());
+        let statement = verification_statement("acme", &account, "n0nce");
+        let signature = BASE64.encode(key.sign(statement.as_bytes()).to_bytes());
+        let toml_text = |org: &str, sig: &str| {
+            format!(
+                "ACCOUNTS = [\"{}\"]\n\n[SOROBAN_REGISTRY]\nORG = \"{}\"\nSIGNATURE = \"{}\"\n",
+                account, org, sig
+            )
+        };
+
+        assert!(verify_stellar_toml(
+            &toml_text("@acme", &signature),
+            "acme",
+            &account,
+            &statement
+        )
+        .is_ok());
+        assert!(verify_stellar_toml(
+            &toml_text("@other", &signature),
+            "acme",
+            &account,
+            &statement
+        )
+        .is_err());
+
+        let stale = verification_statement("acme", &account, "old");
+        assert!(
+            verify_stellar_toml(&toml_text("@acme", &signature), "acme", &account, &stale).is_err()
+        );
+        assert!(verify_stellar_toml("ACCOUNTS = []", "acme", &account, &statement).is_err());
+    }
+
+    #[test]
+    fn verifies_account_signatures() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let account = encode_account_id(key.verifying_key().as_bytes());
+        let signature = BASE64.encode(key.sign(b"choice: for").to_bytes());
+
+        assert!(verify_account_signature(&account, "choice: for", &signature).is_ok());
+        assert!(verify_account_signature(&account, "choice: against", &signature).is_err());
+        assert!(verify_account_signature(&account, "choice: for", "not-base64").is_err());
+
+        let other = encode_account_id(
+            SigningKey::from_bytes(&[4u8; 32])
+                .verifying_key()
+                .as_bytes(),
+        );
+        assert!(verify_account_signature(&other, "choice: for", &signature).is_err());
+    }
+
+    #[test]
+    fn accepts_host_names_only() {
+        assert_eq!(
+            validate_home_domain("Acme.example.").unwrap(),
+            "acme.example"
+        );
+        assert!(validate_home_domain("https://acme.example").is_err());
+        assert!(validate_home_domain("acme.example/path").is_err());
+        assert!(validate_home_domain("127.0.0.1").is_err());
+        assert!(validate_home_domain("localhost").is_err());
+    }
+}
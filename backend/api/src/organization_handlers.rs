@@ -0,0 +1,866 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use shared::models::{
+    AddOrganizationMemberRequest, Contract, CreateOrganizationRequest, CreateReservedNameRequest,
+    NameCheck, NameCheckParams, Organization, OrganizationActorRequest, OrganizationDetail,
+    OrganizationMember, OrganizationVerificationChallenge, Publisher, ReservedName, SimilarName,
+    UpdateOrganizationRequest,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    namespace::{self, OrgRole, ScopedName},
+    state::AppState,
+};
+
+/// How many of the most popular contracts new names are screened against
+const POPULAR_NAME_LIMIT: i64 = 500;
+/// Largest stellar.toml accepted during verification
+const MAX_STELLAR_TOML_BYTES: usize = 100 * 1024;
+const STELLAR_TOML_TIMEOUT_SECS: u64 = 10;
+
+fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(context = ctx, error = %err, "database error");
+    ApiError::internal(format!("Database error during: {}", ctx))
+}
+
+fn forbidden(code: &str, message: impl Into<String>) -> ApiError {
+    ApiError::new(StatusCode::FORBIDDEN, code, message)
+}
+
+async fn fetch_org(db: &PgPool, slug: &str) -> ApiResult<Organization> {
+    sqlx::query_as("SELECT * FROM organizations WHERE slug = $1")
+        .bind(slug.trim_start_matches('@').to_ascii_lowercase())
+        .fetch_optional(db)
+        .await
+        .map_err(|e| db_err("fetch organization", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "OrganizationNotFound",
+                format!("No organization named @{}", slug.trim_start_matches('@')),
+            )
+        })
+}
+
+async fn upsert_publisher(db: &PgPool, address: &str) -> ApiResult<Publisher> {
+    sqlx::query_as(
+        "INSERT INTO publishers (stellar_address) VALUES ($1)
+         ON CONFLICT (stellar_address) DO UPDATE SET stellar_address = EXCLUDED.stellar_address
+         RETURNING *",
+    )
+    .bind(address)
+    .fetch_one(db)
+    .await
+    .map_err(|e| db_err("upsert publisher", e))
+}
+
+async fn find_publisher(db: &PgPool, address: &str) -> ApiResult<Option<Publisher>> {
+    sqlx::query_as("SELECT * FROM publishers WHERE stellar_address = $1")
+        .bind(address)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| db_err("fetch publisher", e))
+}
+
+async fn member_role(db: &PgPool, org_id: Uuid, publisher_id: Uuid) -> ApiResult<Option<OrgRole>> {
+    let role: Option<String> = sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND publisher_id = $2",
+    )
+    .bind(org_id)
+    .bind(publisher_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| db_err("fetch member role", e))?;
+    role.map(|r| r.parse().map_err(ApiError::internal))
+        .transpose()
+}
+
+/// The actor and their role, provided they may manage the organization
+async fn require_manager(
+    db: &PgPool,
+    org: &Organization,
+    actor_address: &str,
+) -> ApiResult<(Publisher, OrgRole)> {
+    let denied = || {
+        forbidden(
+            "not_an_org_admin",
+            format!(
+                "{} is not an owner or admin of @{}",
+                actor_address, org.slug
+            ),
+        )
+    };
+    let actor = find_publisher(db, actor_address)
+        .await?
+        .ok_or_else(denied)?;
+    match member_role(db, org.id, actor.id).await? {
+        Some(role) if role.can_manage() => Ok((actor, role)),
+        _ => Err(denied()),
+    }
+}
+
+async fn load_members(db: &PgPool, org_id: Uuid) -> ApiResult<Vec<OrganizationMember>> {
+    sqlx::query_as(
+        "SELECT m.publisher_id, p.stellar_address, p.username, m.role, m.added_at
+         FROM organization_members m
+         JOIN publishers p ON p.id = m.publisher_id
+         WHERE m.organization_id = $1
+         ORDER BY CASE m.role WHEN 'owner' THEN 0 WHEN 'admin' THEN 1 ELSE 2 END, m.added_at",
+    )
+    .bind(org_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| db_err("list organization members", e))
+}
+
+async fn owner_count(db: &PgPool, org_id: Uuid) -> ApiResult<i64> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM organization_members WHERE organization_id = $1 AND role = 'owner'",
+    )
+    .bind(org_id)
+    .fetch_one(db)
+    .await
+    .map_err(|e| db_err("count organization owners", e))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Name screening
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(sqlx::FromRow)]
+struct KnownContract {
+    name: String,
+    namespace: Option<String>,
+    publisher_id: Uuid,
+    organization_id: Option<Uuid>,
+}
+
+struct Rejection {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl From<Rejection> for ApiError {
+    fn from(r: Rejection) -> Self {
+        ApiError::new(r.status, r.code, r.message)
+    }
+}
+
+struct Screening {
+    organization: Option<Organization>,
+    similar: Vec<SimilarName>,
+    rejection: Option<Rejection>,
+}
+
+async fn memberships(db: &PgPool, publisher_id: Option<Uuid>) -> ApiResult<Vec<Uuid>> {
+    let Some(publisher_id) = publisher_id else {
+        return Ok(Vec::new());
+    };
+    sqlx::query_scalar("SELECT organization_id FROM organization_members WHERE publisher_id = $1")
+        .bind(publisher_id)
+        .fetch_all(db)
+        .await
+        .map_err(|e| db_err("list memberships", e))
+}
+
+/// Reserved names `name` is confusable with, unless reserved for one of
+/// the publisher's organizations
+async fn reserved_matches(
+    db: &PgPool,
+    name: &str,
+    member_of: &[Uuid],
+) -> ApiResult<Vec<SimilarName>> {
+    let reserved: Vec<ReservedName> = sqlx::query_as("SELECT * FROM reserved_names")
+        .fetch_all(db)
+        .await
+        .map_err(|e| db_err("list reserved names", e))?;
+    let foreign: Vec<&str> = reserved
+        .iter()
+        .filter(|r| !r.organization_id.is_some_and(|id| member_of.contains(&id)))
+        .map(|r| r.name.as_str())
+        .collect();
+    Ok(namespace::similar_names(name, foreign)
+        .into_iter()
+        .map(|m| SimilarName {
+            name: m.name,
+            distance: m.distance,
+            kind: "reserved".into(),
+        })
+        .collect())
+}
+
+async fn screen(
+    db: &PgPool,
+    scoped: &ScopedName,
+    publisher_id: Option<Uuid>,
+) -> ApiResult<Screening> {
+    let member_of = memberships(db, publisher_id).await?;
+
+    // Inside a namespace only membership matters; the scope itself tells
+    // the contract apart from look-alikes elsewhere
+    if let Some(slug) = &scoped.namespace {
+        let org = fetch_org(db, slug).await?;
+        let rejection = match publisher_id {
+            Some(_) if member_of.contains(&org.id) => None,
+            _ => Some(Rejection {
+                status: StatusCode::FORBIDDEN,
+                code: "not_an_org_member",
+                message: format!(
+                    "Only members of @{} can publish under its namespace",
+                    org.slug
+                ),
+            }),
+        };
+        return Ok(Screening {
+            organization: Some(org),
+            similar: Vec::new(),
+            rejection,
+        });
+    }
+
+    let reserved = reserved_matches(db, &scoped.name, &member_of).await?;
+    if !reserved.is_empty() {
+        return Ok(Screening {
+            organization: None,
+            rejection: Some(Rejection {
+                status: StatusCode::FORBIDDEN,
+                code: "reserved_name",
+                message: format!(
+                    "'{}' is too close to the reserved name '{}'",
+                    scoped.name, reserved[0].name
+                ),
+            }),
+            similar: reserved,
+        });
+    }
+
+    let popular: Vec<KnownContract> = sqlx::query_as(
+        "SELECT name, namespace, publisher_id, organization_id FROM contracts
+         WHERE is_verified OR popularity_score > 0
+         ORDER BY popularity_score DESC
+         LIMIT $1",
+    )
+    .bind(POPULAR_NAME_LIMIT)
+    .fetch_all(db)
+    .await
+    .map_err(|e| db_err("list popular contracts", e))?;
+
+    let others: Vec<&KnownContract> = popular
+        .iter()
+        .filter(|c| Some(c.publisher_id) != publisher_id)
+        .filter(|c| !c.organization_id.is_some_and(|id| member_of.contains(&id)))
+        .collect();
+    let similar: Vec<SimilarName> =
+        namespace::similar_names(&scoped.name, others.iter().map(|c| c.name.as_str()))
+            .into_iter()
+            .map(|m| {
+                let namespace = others
+                    .iter()
+                    .find(|c| c.name == m.name)
+                    .and_then(|c| c.namespace.clone());
+                SimilarName {
+                    name: ScopedName {
+                        namespace,
+                        name: m.name,
+                    }
+                    .to_string(),
+                    distance: m.distance,
+                    kind: "contract".into(),
+                }
+            })
+            .collect();
+
+    let rejection = (!similar.is_empty()).then(|| Rejection {
+        status: StatusCode::CONFLICT,
+        code: "name_too_similar",
+        message: format!(
+            "'{}' could be mistaken for {}; publish under your organization's namespace (@org/{}) instead",
+            scoped.name,
+            similar
+                .iter()
+                .map(|s| format!("'{}'", s.name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            scoped.name
+        ),
+    });
+    Ok(Screening {
+        organization: None,
+        similar,
+        rejection,
+    })
+}
+
+/// Check a name a publisher is about to publish under. Returns the
+/// organization for scoped names.
+pub async fn check_publish_name(
+    db: &PgPool,
+    scoped: &ScopedName,
+    publisher: &Publisher,
+) -> ApiResult<Option<Organization>> {
+    let screening = screen(db, scoped, Some(publisher.id)).await?;
+    if let Some(rejection) = screening.rejection {
+        tracing::warn!(
+            publisher = %publisher.stellar_address,
+            name = %scoped,
+            code = rejection.code,
+            "publish name rejected"
+        );
+        return Err(rejection.into());
+    }
+    Ok(screening.organization)
+}
+
+pub async fn check_name(
+    State(state): State<AppState>,
+    Query(params): Query<NameCheckParams>,
+) -> ApiResult<Json<NameCheck>> {
+    let scoped: ScopedName = params
+        .name
+        .parse()
+        .map_err(|e: String| ApiError::bad_request("InvalidName", e))?;
+    let publisher = match &params.publisher_address {
+        Some(address) => find_publisher(&state.db, address).await?,
+        None => None,
+    };
+    let screening = screen(&state.db, &scoped, publisher.map(|p| p.id)).await?;
+
+    Ok(Json(NameCheck {
+        name: scoped.to_string(),
+        namespace: scoped.namespace,
+        available: screening.rejection.is_none(),
+        reason: screening.rejection.map(|r| r.message),
+        similar: screening.similar,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Organizations
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Json(req): Json<CreateOrganizationRequest>,
+) -> ApiResult<(StatusCode, Json<OrganizationDetail>)> {
+    let slug = namespace::validate_slug(&req.slug)
+        .map_err(|e| ApiError::bad_request("InvalidOrganizationName", e))?;
+    if req.created_by.is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingCreatedBy",
+            "created_by is required",
+        ));
+    }
+    let home_domain = req
+        .home_domain
+        .as_deref()
+        .map(namespace::validate_home_domain)
+        .transpose()
+        .map_err(|e| ApiError::bad_request("InvalidHomeDomain", e))?;
+    if let Some(account) = &req.stellar_address {
+        namespace::decode_account_id(account)
+            .map_err(|e| ApiError::bad_request("InvalidStellarAddress", e))?;
+    }
+
+    let reserved = reserved_matches(&state.db, &slug, &[]).await?;
+    if let Some(r) = reserved.first() {
+        return Err(forbidden(
+            "reserved_name",
+            format!("@{} is too close to the reserved name '{}'", slug, r.name),
+        ));
+    }
+    let verified: Vec<String> = sqlx::query_scalar("SELECT slug FROM organizations WHERE verified")
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_err("list verified organizations", e))?;
+    if let Some(m) = namespace::similar_names(&slug, verified.iter().map(String::as_str)).first() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "name_too_similar",
+            format!(
+                "@{} could be mistaken for the verified organization @{}",
+                slug, m.name
+            ),
+        ));
+    }
+
+    let creator = upsert_publisher(&state.db, &req.created_by).await?;
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+    let org: Organization = sqlx::query_as(
+        "INSERT INTO organizations (slug, display_name, description, home_domain, stellar_address, created_by)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (slug) DO NOTHING
+         RETURNING *",
+    )
+    .bind(&slug)
+    .bind(&req.display_name)
+    .bind(&req.description)
+    .bind(&home_domain)
+    .bind(&req.stellar_address)
+    .bind(creator.id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_err("create organization", e))?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "organization_exists",
+            format!("@{} is already taken", slug),
+        )
+    })?;
+    sqlx::query(
+        "INSERT INTO organization_members (organization_id, publisher_id, role, added_by)
+         VALUES ($1, $2, 'owner', $2)",
+    )
+    .bind(org.id)
+    .bind(creator.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_err("add organization owner", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit organization", e))?;
+
+    tracing::info!(organization = %org.slug, owner = %creator.stellar_address, "organization created");
+
+    let members = load_members(&state.db, org.id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(OrganizationDetail {
+            organization: org,
+            members,
+            contract_count: 0,
+        }),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListOrganizationsParams {
+    pub verified: Option<bool>,
+}
+
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    Query(params): Query<ListOrganizationsParams>,
+) -> ApiResult<Json<Vec<Organization>>> {
+    let orgs: Vec<Organization> = sqlx::query_as(
+        "SELECT * FROM organizations
+         WHERE ($1::BOOLEAN IS NULL OR verified = $1)
+         ORDER BY verified DESC, slug",
+    )
+    .bind(params.verified)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list organizations", e))?;
+
+    Ok(Json(orgs))
+}
+
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> ApiResult<Json<OrganizationDetail>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    let members = load_members(&state.db, org.id).await?;
+    let contract_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM contracts WHERE organization_id = $1")
+            .bind(org.id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| db_err("count organization contracts", e))?;
+
+    Ok(Json(OrganizationDetail {
+        organization: org,
+        members,
+        contract_count,
+    }))
+}
+
+pub async fn update_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(req): Json<UpdateOrganizationRequest>,
+) -> ApiResult<Json<Organization>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    require_manager(&state.db, &org, &req.actor_address).await?;
+
+    let home_domain = req
+        .home_domain
+        .as_deref()
+        .map(namespace::validate_home_domain)
+        .transpose()
+        .map_err(|e| ApiError::bad_request("InvalidHomeDomain", e))?;
+    if let Some(account) = &req.stellar_address {
+        namespace::decode_account_id(account)
+            .map_err(|e| ApiError::bad_request("InvalidStellarAddress", e))?;
+    }
+    // A new domain or account has to prove itself again
+    let identity_changed = home_domain
+        .as_ref()
+        .is_some_and(|d| org.home_domain.as_ref() != Some(d))
+        || req
+            .stellar_address
+            .as_ref()
+            .is_some_and(|a| org.stellar_address.as_ref() != Some(a));
+
+    let updated: Organization = sqlx::query_as(
+        "UPDATE organizations
+         SET display_name       = COALESCE($2, display_name),
+             description        = COALESCE($3, description),
+             home_domain        = COALESCE($4, home_domain),
+             stellar_address    = COALESCE($5, stellar_address),
+             verified           = verified AND NOT $6,
+             verified_at        = CASE WHEN $6 THEN NULL ELSE verified_at END,
+             verification_nonce = CASE WHEN $6 THEN NULL ELSE verification_nonce END,
+             updated_at         = NOW()
+         WHERE id = $1
+         RETURNING *",
+    )
+    .bind(org.id)
+    .bind(&req.display_name)
+    .bind(&req.description)
+    .bind(&home_domain)
+    .bind(&req.stellar_address)
+    .bind(identity_changed)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("update organization", e))?;
+
+    if identity_changed && org.verified {
+        tracing::info!(organization = %org.slug, "organization identity changed; verification cleared");
+    }
+
+    Ok(Json(updated))
+}
+
+pub async fn get_organization_contracts(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> ApiResult<Json<Vec<Contract>>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    let contracts: Vec<Contract> =
+        sqlx::query_as("SELECT * FROM contracts WHERE organization_id = $1 ORDER BY name, network")
+            .bind(org.id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list organization contracts", e))?;
+
+    Ok(Json(contracts))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Members
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Add a member, or change an existing member's role
+pub async fn add_member(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(req): Json<AddOrganizationMemberRequest>,
+) -> ApiResult<Json<Vec<OrganizationMember>>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    let (actor, actor_role) = require_manager(&state.db, &org, &req.actor_address).await?;
+    let role: OrgRole = req
+        .role
+        .parse()
+        .map_err(|e: String| ApiError::bad_request("InvalidRole", e))?;
+
+    let member = upsert_publisher(&state.db, &req.publisher_address).await?;
+    let current = member_role(&state.db, org.id, member.id).await?;
+    if !actor_role.can_assign(role) || current.is_some_and(|c| !actor_role.can_assign(c)) {
+        return Err(forbidden(
+            "insufficient_role",
+            "Only owners can grant or change the owner role",
+        ));
+    }
+    if current == Some(OrgRole::Owner)
+        && role != OrgRole::Owner
+        && owner_count(&state.db, org.id).await? <= 1
+    {
+        return Err(ApiError::unprocessable(
+            "last_owner",
+            format!("@{} must keep at least one owner", org.slug),
+        ));
+    }
+
+    sqlx::query(
+        "INSERT INTO organization_members (organization_id, publisher_id, role, added_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (organization_id, publisher_id) DO UPDATE SET role = EXCLUDED.role",
+    )
+    .bind(org.id)
+    .bind(member.id)
+    .bind(role.as_str())
+    .bind(actor.id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| db_err("add organization member", e))?;
+
+    tracing::info!(
+        organization = %org.slug,
+        member = %member.stellar_address,
+        role = role.as_str(),
+        by = %actor.stellar_address,
+        "organization member set"
+    );
+
+    load_members(&state.db, org.id).await.map(Json)
+}
+
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Path((slug, address)): Path<(String, String)>,
+    Query(actor): Query<OrganizationActorRequest>,
+) -> ApiResult<Json<Vec<OrganizationMember>>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    let member = find_publisher(&state.db, &address).await?;
+    let role = match &member {
+        Some(m) => member_role(&state.db, org.id, m.id).await?,
+        None => None,
+    };
+    let (Some(member), Some(role)) = (member, role) else {
+        return Err(ApiError::not_found(
+            "MemberNotFound",
+            format!("{} is not a member of @{}", address, org.slug),
+        ));
+    };
+
+    // Members may always leave; removing someone else takes a manager
+    if actor.actor_address != address {
+        let (_, actor_role) = require_manager(&state.db, &org, &actor.actor_address).await?;
+        if !actor_role.can_assign(role) {
+            return Err(forbidden(
+                "insufficient_role",
+                "Only owners can remove an owner",
+            ));
+        }
+    }
+    if role == OrgRole::Owner && owner_count(&state.db, org.id).await? <= 1 {
+        return Err(ApiError::unprocessable(
+            "last_owner",
+            format!("@{} must keep at least one owner", org.slug),
+        ));
+    }
+
+    sqlx::query(
+        "DELETE FROM organization_members WHERE organization_id = $1 AND publisher_id = $2",
+    )
+    .bind(org.id)
+    .bind(member.id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| db_err("remove organization member", e))?;
+
+    tracing::info!(organization = %org.slug, member = %address, "organization member removed");
+
+    load_members(&state.db, org.id).await.map(Json)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Verification
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn verification_identity(org: &Organization) -> ApiResult<(&str, &str)> {
+    match (&org.home_domain, &org.stellar_address) {
+        (Some(domain), Some(account)) => Ok((domain, account)),
+        _ => Err(ApiError::unprocessable(
+            "verification_unconfigured",
+            "Set home_domain and stellar_address before verifying",
+        )),
+    }
+}
+
+/// Issue the statement the organization's account has to sign and publish
+pub async fn start_verification(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(req): Json<OrganizationActorRequest>,
+) -> ApiResult<Json<OrganizationVerificationChallenge>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    require_manager(&state.db, &org, &req.actor_address).await?;
+    let (domain, account) = verification_identity(&org)?;
+
+    let nonce: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    sqlx::query(
+        "UPDATE organizations SET verification_nonce = $2, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(org.id)
+    .bind(&nonce)
+    .execute(&state.db)
+    .await
+    .map_err(|e| db_err("store verification nonce", e))?;
+
+    let statement = namespace::verification_statement(&org.slug, account, &nonce);
+    Ok(Json(OrganizationVerificationChallenge {
+        organization: format!("@{}", org.slug),
+        stellar_toml_url: namespace::stellar_toml_url(domain),
+        account: account.to_string(),
+        stellar_toml_snippet: format!(
+            "ACCOUNTS = [\"{}\"]\n\n[SOROBAN_REGISTRY]\nORG = \"@{}\"\nSIGNATURE = \"<base64 ed25519 signature of statement>\"\n",
+            account, org.slug
+        ),
+        statement,
+    }))
+}
+
+async fn fetch_stellar_toml(url: &str) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(STELLAR_TOML_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("could not fetch {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("could not read {}: {}", url, e))?;
+    if body.len() > MAX_STELLAR_TOML_BYTES {
+        return Err(format!(
+            "{} is larger than {} bytes",
+            url, MAX_STELLAR_TOML_BYTES
+        ));
+    }
+    String::from_utf8(body.to_vec()).map_err(|_| format!("{} is not UTF-8", url))
+}
+
+/// Fetch the home domain's stellar.toml and check the signed statement
+pub async fn verify_organization(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(req): Json<OrganizationActorRequest>,
+) -> ApiResult<Json<Organization>> {
+    let org = fetch_org(&state.db, &slug).await?;
+    require_manager(&state.db, &org, &req.actor_address).await?;
+    let (domain, account) = verification_identity(&org)?;
+    let nonce = org.verification_nonce.as_deref().ok_or_else(|| {
+        ApiError::unprocessable(
+            "verification_not_started",
+            "Request a verification challenge first",
+        )
+    })?;
+
+    let url = namespace::stellar_toml_url(domain);
+    let statement = namespace::verification_statement(&org.slug, account, nonce);
+    let outcome = match fetch_stellar_toml(&url).await {
+        Ok(text) => namespace::verify_stellar_toml(&text, &org.slug, account, &statement),
+        Err(e) => Err(e),
+    };
+
+    let updated: Organization = sqlx::query_as(
+        "UPDATE organizations
+         SET verified           = $2,
+             verified_at        = CASE WHEN $2 THEN NOW() ELSE verified_at END,
+             verification_error = $3,
+             updated_at         = NOW()
+         WHERE id = $1
+         RETURNING *",
+    )
+    .bind(org.id)
+    .bind(outcome.is_ok())
+    .bind(outcome.as_ref().err())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("record organization verification", e))?;
+
+    match outcome {
+        Ok(()) => {
+            tracing::info!(organization = %org.slug, domain = %domain, "organization verified");
+            Ok(Json(updated))
+        }
+        Err(e) => {
+            tracing::warn!(organization = %org.slug, domain = %domain, error = %e, "organization verification failed");
+            Err(ApiError::unprocessable("verification_failed", e))
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Reserved names
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn list_reserved_names(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<ReservedName>>> {
+    let names: Vec<ReservedName> = sqlx::query_as("SELECT * FROM reserved_names ORDER BY name")
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_err("list reserved names", e))?;
+
+    Ok(Json(names))
+}
+
+pub async fn reserve_name(
+    State(state): State<AppState>,
+    Json(req): Json<CreateReservedNameRequest>,
+) -> ApiResult<(StatusCode, Json<ReservedName>)> {
+    let name = req.name.trim().to_lowercase();
+    if namespace::skeleton(&name).is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidName",
+            "name must contain letters or digits",
+        ));
+    }
+    let organization_id = match &req.organization {
+        Some(slug) => Some(fetch_org(&state.db, slug).await?.id),
+        None => None,
+    };
+
+    let reserved: ReservedName = sqlx::query_as(
+        "INSERT INTO reserved_names (name, organization_id, reason)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (name) DO UPDATE
+         SET organization_id = EXCLUDED.organization_id,
+             reason          = COALESCE(EXCLUDED.reason, reserved_names.reason)
+         RETURNING *",
+    )
+    .bind(&name)
+    .bind(organization_id)
+    .bind(&req.reason)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("reserve name", e))?;
+
+    tracing::info!(name = %name, organization = ?req.organization, "name reserved");
+
+    Ok((StatusCode::CREATED, Json(reserved)))
+}
+
+pub async fn release_name(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = sqlx::query("DELETE FROM reserved_names WHERE name = $1")
+        .bind(name.trim().to_lowercase())
+        .execute(&state.db)
+        .await
+        .map_err(|e| db_err("release reserved name", e))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found(
+            "ReservedNameNotFound",
+            format!("'{}' is not reserved", name),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,50 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::{organization_handlers, state::AppState};
+
+pub fn organization_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/organizations",
+            post(organization_handlers::create_organization)
+                .get(organization_handlers::list_organizations),
+        )
+        .route(
+            "/api/organizations/:slug",
+            get(organization_handlers::get_organization)
+                .put(organization_handlers::update_organization),
+        )
+        .route(
+            "/api/organizations/:slug/contracts",
+            get(organization_handlers::get_organization_contracts),
+        )
+        .route(
+            "/api/organizations/:slug/members",
+            post(organization_handlers::add_member),
+        )
+        .route(
+            "/api/organizations/:slug/members/:address",
+            delete(organization_handlers::remove_member),
+        )
+        .route(
+            "/api/organizations/:slug/verification/challenge",
+            post(organization_handlers::start_verification),
+        )
+        .route(
+            "/api/organizations/:slug/verification",
+            post(organization_handlers::verify_organization),
+        )
+        .route("/api/names/check", get(organization_handlers::check_name))
+        .route(
+            "/api/reserved-names",
+            get(organization_handlers::list_reserved_names)
+                .post(organization_handlers::reserve_name),
+        )
+        .route(
+            "/api/reserved-names/:name",
+            delete(organization_handlers::release_name),
+        )
+}
@@ -0,0 +1,612 @@
+// api/src/patch_engine.rs
+//
+// Rules for staged security-patch rollouts: wave planning, dependent
+// discovery, campaign state changes and the health gate evaluated between
+// waves. patch_rollout.rs loads and persists campaigns; everything in here
+// is synchronous.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use shared::{
+    PatchCampaign, PatchCampaignStatus, PatchRolloutProgress, PatchTargetImpact, PatchTargetStatus,
+    SecurityPatch,
+};
+
+use crate::incident_engine;
+
+// ─────────────────────────────────────────────────────────
+// Planning
+// ─────────────────────────────────────────────────────────
+
+pub const DEFAULT_WAVES: [i32; 4] = [5, 25, 50, 100];
+
+/// Wave percentages are cumulative: strictly increasing, ending at 100.
+pub fn validate_waves(waves: &[i32]) -> Result<(), String> {
+    if waves.is_empty() {
+        return Err("at least one wave is required".into());
+    }
+    if waves.len() > 10 {
+        return Err("at most 10 waves are allowed".into());
+    }
+    if waves.iter().any(|p| !(1..=100).contains(p)) {
+        return Err("wave percentages must be between 1 and 100".into());
+    }
+    if waves.windows(2).any(|w| w[0] >= w[1]) {
+        return Err("wave percentages must be strictly increasing".into());
+    }
+    if waves.last() != Some(&100) {
+        return Err("the last wave must reach 100%".into());
+    }
+    Ok(())
+}
+
+/// Without a campaign a patch's rollout percentage caps how many of the
+/// eligible contracts may take it, rounding up.
+pub fn rollout_quota(eligible: i64, rollout_percentage: i32) -> i64 {
+    (eligible * rollout_percentage as i64 + 99) / 100
+}
+
+/// A contract running the vulnerable build
+#[derive(Debug, Clone)]
+pub struct RolloutCandidate {
+    pub contract_id: Uuid,
+    pub network: String,
+    pub interactions: i64,
+}
+
+/// Rollout order: test networks before mainnet, then least-used first, so
+/// early waves carry the least risk.
+pub fn order_candidates(mut candidates: Vec<RolloutCandidate>) -> Vec<Uuid> {
+    candidates.sort_by(|a, b| {
+        (a.network == "mainnet")
+            .cmp(&(b.network == "mainnet"))
+            .then(a.interactions.cmp(&b.interactions))
+            .then(a.contract_id.cmp(&b.contract_id))
+    });
+    candidates.into_iter().map(|c| c.contract_id).collect()
+}
+
+/// Wave number (from 1) for each of `count` ordered targets. Wave `k`
+/// covers the first `ceil(count * waves[k] / 100)` targets, bumped so that
+/// every wave gets at least one new target while any remain; trailing
+/// waves may be empty for small campaigns.
+pub fn assign_waves(count: usize, waves: &[i32]) -> Vec<i32> {
+    let mut cutoffs = Vec::with_capacity(waves.len());
+    let mut previous = 0;
+    for p in waves {
+        let cutoff = (count * *p as usize)
+            .div_ceil(100)
+            .max(previous + 1)
+            .min(count);
+        cutoffs.push(cutoff);
+        previous = cutoff;
+    }
+    (0..count)
+        .map(|i| {
+            cutoffs
+                .iter()
+                .position(|cutoff| i < *cutoff)
+                .map_or(waves.len() as i32, |k| k as i32 + 1)
+        })
+        .collect()
+}
+
+/// Contracts that depend on any direct target, with their distance to the
+/// nearest one and which direct target that is. `edges` are
+/// `(dependent, dependency)` pairs.
+pub fn dependents_of(direct: &[Uuid], edges: &[(Uuid, Uuid)]) -> Vec<(Uuid, i32, Uuid)> {
+    let mut nearest: HashMap<Uuid, (i32, Uuid)> = HashMap::new();
+    for origin in direct {
+        for (contract_id, _, depth) in incident_engine::affected_contracts(*origin, edges) {
+            if depth == 0 || direct.contains(&contract_id) {
+                continue;
+            }
+            let entry = nearest.entry(contract_id).or_insert((depth, *origin));
+            if depth < entry.0 {
+                *entry = (depth, *origin);
+            }
+        }
+    }
+    let mut dependents: Vec<_> = nearest
+        .into_iter()
+        .map(|(id, (depth, via))| (id, depth, via))
+        .collect();
+    dependents.sort_by_key(|(id, depth, _)| (*depth, *id));
+    dependents
+}
+
+// ─────────────────────────────────────────────────────────
+// Campaign state
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampaignAction {
+    Start,
+    Advance,
+    Pause,
+    Resume,
+    Abort,
+}
+
+/// Whether an operator action is allowed in the campaign's current state.
+pub fn check_action(status: PatchCampaignStatus, action: CampaignAction) -> Result<(), String> {
+    use PatchCampaignStatus::*;
+    let allowed = match action {
+        CampaignAction::Start => status == Pending,
+        CampaignAction::Advance | CampaignAction::Pause => status == RollingOut,
+        CampaignAction::Resume => status == Paused,
+        CampaignAction::Abort => matches!(status, Pending | RollingOut | Paused),
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("cannot {:?} a campaign that is {}", action, status).to_lowercase())
+    }
+}
+
+/// Why a contract may not be upgraded yet under a live campaign, if it
+/// may not. Only scheduled direct targets of a running campaign qualify.
+pub fn apply_blocker(
+    campaign: &PatchCampaign,
+    target: Option<(PatchTargetImpact, PatchTargetStatus, Option<i32>)>,
+) -> Option<String> {
+    match campaign.status {
+        PatchCampaignStatus::Paused => {
+            return Some(format!(
+                "the patch campaign is paused: {}",
+                campaign
+                    .paused_reason
+                    .as_deref()
+                    .unwrap_or("no reason given")
+            ))
+        }
+        PatchCampaignStatus::Pending => {
+            return Some("the patch campaign has not started yet".into())
+        }
+        _ => {}
+    }
+    match target {
+        None | Some((PatchTargetImpact::Dependent, _, _)) => {
+            Some("the contract is not a direct target of this patch campaign".into())
+        }
+        Some((_, PatchTargetStatus::Scheduled, _)) => None,
+        Some((_, PatchTargetStatus::Pending, wave)) => Some(format!(
+            "the contract is scheduled in wave {}; the campaign is at wave {}",
+            wave.unwrap_or_default(),
+            campaign.current_wave
+        )),
+        Some((_, status, _)) => {
+            Some(format!("the contract's rollout status is {:?}", status).to_lowercase())
+        }
+    }
+}
+
+pub fn progress_of(statuses: &[PatchTargetStatus]) -> PatchRolloutProgress {
+    let mut progress = PatchRolloutProgress {
+        total: statuses.len() as i64,
+        ..Default::default()
+    };
+    for status in statuses {
+        match status {
+            PatchTargetStatus::Pending => progress.pending += 1,
+            PatchTargetStatus::Scheduled => progress.scheduled += 1,
+            PatchTargetStatus::Applied | PatchTargetStatus::Resolved => progress.applied += 1,
+            PatchTargetStatus::Failed => progress.failed += 1,
+            PatchTargetStatus::Skipped => progress.skipped += 1,
+        }
+    }
+    progress
+}
+
+// ─────────────────────────────────────────────────────────
+// Health gate
+// ─────────────────────────────────────────────────────────
+
+/// Performance of one upgraded contract before and after the patch
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TargetHealth {
+    pub contract_name: String,
+    pub baseline_error_rate: Option<f64>,
+    pub current_error_rate: Option<f64>,
+    pub baseline_latency_ms: Option<f64>,
+    pub current_latency_ms: Option<f64>,
+    /// Critical performance anomalies detected since the upgrade
+    pub anomalies: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GateDecision {
+    /// The wave is healthy and has soaked long enough
+    Pass,
+    /// Not enough evidence yet
+    Wait(String),
+    /// Regression or anomaly; the campaign pauses
+    Fail(String),
+}
+
+/// Health regressions for a single target, if any.
+pub fn target_regression(health: &TargetHealth, campaign: &PatchCampaign) -> Option<String> {
+    if health.anomalies > 0 {
+        return Some(format!(
+            "{} critical performance anomal{} on {} since the upgrade",
+            health.anomalies,
+            if health.anomalies == 1 { "y" } else { "ies" },
+            health.contract_name
+        ));
+    }
+    if let (Some(before), Some(after)) = (health.baseline_error_rate, health.current_error_rate) {
+        if after - before > campaign.max_error_rate_increase {
+            return Some(format!(
+                "error rate on {} rose from {:.2}% to {:.2}%",
+                health.contract_name, before, after
+            ));
+        }
+    }
+    if let (Some(before), Some(after)) = (health.baseline_latency_ms, health.current_latency_ms) {
+        if before > 0.0 && (after - before) / before * 100.0 > campaign.max_latency_increase_pct {
+            return Some(format!(
+                "latency on {} rose from {:.0}ms to {:.0}ms",
+                health.contract_name, before, after
+            ));
+        }
+    }
+    None
+}
+
+/// Decide whether the current wave may be followed by the next one.
+/// Regressions fail the gate immediately, even before the wave has soaked.
+pub fn evaluate_gate(
+    campaign: &PatchCampaign,
+    wave: &PatchRolloutProgress,
+    health: &[TargetHealth],
+    now: DateTime<Utc>,
+) -> GateDecision {
+    if let Some(reason) = health.iter().find_map(|h| target_regression(h, campaign)) {
+        return GateDecision::Fail(reason);
+    }
+    // Small campaigns can leave trailing waves without targets
+    if wave.total == 0 {
+        return GateDecision::Pass;
+    }
+
+    let eligible = wave.total - wave.skipped;
+    if eligible > 0 {
+        let applied_fraction = wave.applied as f64 / eligible as f64;
+        if applied_fraction < campaign.min_applied_fraction {
+            return GateDecision::Wait(format!(
+                "{} of {} contracts in wave {} upgraded; {:.0}% required",
+                wave.applied,
+                eligible,
+                campaign.current_wave,
+                campaign.min_applied_fraction * 100.0
+            ));
+        }
+    }
+
+    if let Some(started) = campaign.wave_started_at {
+        let soak_until = started + Duration::minutes(campaign.min_soak_minutes as i64);
+        if now < soak_until {
+            return GateDecision::Wait(format!(
+                "wave {} soaking until {}",
+                campaign.current_wave,
+                soak_until.format("%Y-%m-%d %H:%M UTC")
+            ));
+        }
+    }
+
+    GateDecision::Pass
+}
+
+// ─────────────────────────────────────────────────────────
+// Notifications
+// ─────────────────────────────────────────────────────────
+
+/// Subject and body asking a publisher to upgrade a scheduled contract.
+pub fn upgrade_notification(
+    patch: &SecurityPatch,
+    contract_name: &str,
+    wave: i32,
+) -> (String, String) {
+    (
+        format!(
+            "[{}] Security patch scheduled for {}",
+            patch.severity, contract_name
+        ),
+        format!(
+            "{} is running a build affected by a {} security issue (version {}). \
+             It is scheduled in wave {} of the patch rollout. Upgrade it to WASM {} \
+             and report the upgrade with `soroban-registry patch apply`.{}",
+            contract_name,
+            patch.severity.to_string().to_lowercase(),
+            patch.target_version,
+            wave,
+            patch.new_wasm_hash,
+            patch
+                .description
+                .as_deref()
+                .map(|d| format!("\n\n{}", d))
+                .unwrap_or_default()
+        ),
+    )
+}
+
+/// Subject and body telling a publisher that a dependency is vulnerable.
+pub fn dependent_notification(
+    patch: &SecurityPatch,
+    contract_name: &str,
+    via_name: &str,
+    depth: i32,
+) -> (String, String) {
+    (
+        format!(
+            "[{}] A dependency of {} is being patched",
+            patch.severity, contract_name
+        ),
+        format!(
+            "{} depends ({} hop(s)) on {}, which is affected by a {} security issue \
+             (version {}). No action is needed on {} itself; you will be notified \
+             when the upstream contract has been patched.",
+            contract_name,
+            depth,
+            via_name,
+            patch.severity.to_string().to_lowercase(),
+            patch.target_version,
+            contract_name
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::PatchSeverity;
+
+    fn campaign() -> PatchCampaign {
+        let now = Utc::now();
+        PatchCampaign {
+            id: Uuid::new_v4(),
+            patch_id: Uuid::new_v4(),
+            status: PatchCampaignStatus::RollingOut,
+            wave_percentages: DEFAULT_WAVES.to_vec(),
+            current_wave: 1,
+            wave_started_at: Some(now - Duration::minutes(90)),
+            max_error_rate_increase: 1.0,
+            max_latency_increase_pct: 25.0,
+            min_soak_minutes: 60,
+            min_applied_fraction: 0.8,
+            auto_advance: true,
+            paused_reason: None,
+            gate_reset_at: None,
+            created_by: "secops".into(),
+            created_at: now,
+            started_at: Some(now),
+            completed_at: None,
+            updated_at: now,
+        }
+    }
+
+    fn healthy(name: &str) -> TargetHealth {
+        TargetHealth {
+            contract_name: name.into(),
+            baseline_error_rate: Some(0.5),
+            current_error_rate: Some(0.7),
+            baseline_latency_ms: Some(100.0),
+            current_latency_ms: Some(110.0),
+            anomalies: 0,
+        }
+    }
+
+    fn wave(total: i64, applied: i64) -> PatchRolloutProgress {
+        PatchRolloutProgress {
+            total,
+            applied,
+            scheduled: total - applied,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn waves_must_be_cumulative_and_end_at_100() {
+        assert!(validate_waves(&DEFAULT_WAVES).is_ok());
+        assert!(validate_waves(&[100]).is_ok());
+        assert!(validate_waves(&[]).is_err());
+        assert!(validate_waves(&[50, 50, 100]).is_err());
+        assert!(validate_waves(&[10, 90]).is_err());
+        assert!(validate_waves(&[0, 100]).is_err());
+    }
+
+    #[test]
+    fn waves_round_up_so_early_waves_are_never_empty() {
+        assert_eq!(assign_waves(3, &DEFAULT_WAVES), vec![1, 2, 3]);
+        assert_eq!(
+            assign_waves(10, &[10, 50, 100]),
+            vec![1, 2, 2, 2, 2, 3, 3, 3, 3, 3]
+        );
+        assert!(assign_waves(0, &DEFAULT_WAVES).is_empty());
+        let big = assign_waves(200, &DEFAULT_WAVES);
+        assert_eq!(big.iter().filter(|w| **w == 1).count(), 10);
+        assert_eq!(big.iter().filter(|w| **w <= 2).count(), 50);
+    }
+
+    #[test]
+    fn rollout_quota_rounds_up() {
+        assert_eq!(rollout_quota(100, 0), 0);
+        assert_eq!(rollout_quota(10, 100), 10);
+        assert_eq!(rollout_quota(10, 50), 5);
+        assert_eq!(rollout_quota(3, 50), 2);
+        assert_eq!(rollout_quota(1, 1), 1);
+        assert_eq!(rollout_quota(0, 100), 0);
+    }
+
+    #[test]
+    fn test_networks_and_quiet_contracts_go_first() {
+        let c = |network: &str, interactions| RolloutCandidate {
+            contract_id: Uuid::new_v4(),
+            network: network.into(),
+            interactions,
+        };
+        let busy_mainnet = c("mainnet", 1_000);
+        let quiet_mainnet = c("mainnet", 3);
+        let testnet = c("testnet", 50_000);
+        let order = order_candidates(vec![
+            busy_mainnet.clone(),
+            testnet.clone(),
+            quiet_mainnet.clone(),
+        ]);
+        assert_eq!(
+            order,
+            vec![
+                testnet.contract_id,
+                quiet_mainnet.contract_id,
+                busy_mainnet.contract_id
+            ]
+        );
+    }
+
+    #[test]
+    fn dependents_use_nearest_direct_target() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let (a, b, x, y) = (ids[0], ids[1], ids[2], ids[3]);
+        // x uses a; y uses x and b; b uses a (both a and b are direct)
+        let edges = [(x, a), (y, x), (y, b), (b, a)];
+        let deps = dependents_of(&[a, b], &edges);
+        assert_eq!(deps.len(), 2);
+        assert!(deps.contains(&(x, 1, a)));
+        assert!(deps.contains(&(y, 1, b)));
+    }
+
+    #[test]
+    fn campaign_actions_follow_status() {
+        use PatchCampaignStatus::*;
+        assert!(check_action(Pending, CampaignAction::Start).is_ok());
+        assert!(check_action(RollingOut, CampaignAction::Start).is_err());
+        assert!(check_action(Paused, CampaignAction::Advance).is_err());
+        assert!(check_action(Paused, CampaignAction::Resume).is_ok());
+        assert_eq!(
+            check_action(Completed, CampaignAction::Abort).unwrap_err(),
+            "cannot abort a campaign that is completed"
+        );
+    }
+
+    #[test]
+    fn only_scheduled_direct_targets_may_apply() {
+        let mut c = campaign();
+        let scheduled = Some((
+            PatchTargetImpact::Direct,
+            PatchTargetStatus::Scheduled,
+            Some(1),
+        ));
+        assert_eq!(apply_blocker(&c, scheduled), None);
+        assert_eq!(
+            apply_blocker(
+                &c,
+                Some((
+                    PatchTargetImpact::Direct,
+                    PatchTargetStatus::Pending,
+                    Some(3)
+                ))
+            )
+            .unwrap(),
+            "the contract is scheduled in wave 3; the campaign is at wave 1"
+        );
+        assert!(apply_blocker(&c, None).is_some());
+        assert!(apply_blocker(
+            &c,
+            Some((
+                PatchTargetImpact::Dependent,
+                PatchTargetStatus::Pending,
+                None
+            ))
+        )
+        .is_some());
+
+        c.status = PatchCampaignStatus::Paused;
+        c.paused_reason = Some("latency regression".into());
+        assert_eq!(
+            apply_blocker(&c, scheduled).unwrap(),
+            "the patch campaign is paused: latency regression"
+        );
+    }
+
+    #[test]
+    fn empty_trailing_waves_pass_without_soaking() {
+        let mut c = campaign();
+        c.wave_started_at = Some(Utc::now());
+        assert_eq!(
+            evaluate_gate(&c, &wave(0, 0), &[healthy("a")], Utc::now()),
+            GateDecision::Pass
+        );
+    }
+
+    #[test]
+    fn gate_passes_healthy_soaked_waves() {
+        let c = campaign();
+        assert_eq!(
+            evaluate_gate(&c, &wave(5, 4), &[healthy("a"), healthy("b")], Utc::now()),
+            GateDecision::Pass
+        );
+    }
+
+    #[test]
+    fn gate_waits_for_upgrades_and_soak_time() {
+        let mut c = campaign();
+        assert!(matches!(
+            evaluate_gate(&c, &wave(5, 3), &[], Utc::now()),
+            GateDecision::Wait(reason) if reason.contains("3 of 5")
+        ));
+
+        c.wave_started_at = Some(Utc::now() - Duration::minutes(10));
+        assert!(matches!(
+            evaluate_gate(&c, &wave(5, 5), &[], Utc::now()),
+            GateDecision::Wait(reason) if reason.contains("soaking")
+        ));
+    }
+
+    #[test]
+    fn gate_fails_on_regressions_before_soak_completes() {
+        let mut c = campaign();
+        c.wave_started_at = Some(Utc::now());
+
+        let mut errors = healthy("vault");
+        errors.current_error_rate = Some(2.0);
+        assert!(matches!(
+            evaluate_gate(&c, &wave(5, 1), &[errors], Utc::now()),
+            GateDecision::Fail(reason) if reason.contains("error rate on vault")
+        ));
+
+        let mut slow = healthy("amm");
+        slow.current_latency_ms = Some(130.0);
+        assert!(matches!(
+            evaluate_gate(&c, &wave(5, 1), &[slow], Utc::now()),
+            GateDecision::Fail(reason) if reason.contains("latency on amm")
+        ));
+
+        let mut anomalous = healthy("oracle");
+        anomalous.anomalies = 2;
+        assert!(matches!(
+            evaluate_gate(&c, &wave(5, 1), &[anomalous], Utc::now()),
+            GateDecision::Fail(reason) if reason.contains("2 critical performance anomalies")
+        ));
+    }
+
+    #[test]
+    fn notifications_name_the_fix() {
+        let patch = SecurityPatch {
+            id: Uuid::new_v4(),
+            target_version: "1.2.0".into(),
+            severity: PatchSeverity::High,
+            new_wasm_hash: "ab".repeat(32),
+            rollout_percentage: 100,
+            description: None,
+            vulnerable_wasm_hash: None,
+            created_at: Utc::now(),
+        };
+        let (subject, body) = upgrade_notification(&patch, "vault", 2);
+        assert_eq!(subject, "[HIGH] Security patch scheduled for vault");
+        assert!(body.contains("wave 2"));
+        assert!(body.contains(&patch.new_wasm_hash));
+    }
+}
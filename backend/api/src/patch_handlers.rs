@@ -0,0 +1,868 @@
+// api/src/patch_handlers.rs
+//
+// Axum handlers for security patches and their staged rollout campaigns.
+// Wave progression and health gating run in the background scheduler
+// (patch_rollout.rs); these handlers create patches and campaigns, record
+// upgrades and expose operator controls.
+//
+// Routes (register in patch_routes.rs):
+//   POST   /api/patches                                → create_patch
+//   GET    /api/patches                                → list_patches
+//   GET    /api/patches/:id                            → get_patch
+//   GET    /api/patches/:id/audits                     → list_patch_audits
+//   POST   /api/patches/:id/apply                      → apply_patch
+//   POST   /api/patches/:id/campaigns                  → create_campaign
+//   GET    /api/patches/:id/campaigns                  → list_campaigns
+//   GET    /api/patch-campaigns/:id                    → get_campaign
+//   POST   /api/patch-campaigns/:id/start              → start_campaign
+//   POST   /api/patch-campaigns/:id/advance            → advance_campaign
+//   POST   /api/patch-campaigns/:id/pause              → pause_campaign
+//   POST   /api/patch-campaigns/:id/resume             → resume_campaign
+//   POST   /api/patch-campaigns/:id/abort              → abort_campaign
+//   POST   /api/patch-campaigns/:id/targets/:contract  → update_campaign_target
+//   GET    /api/publishers/:id/notifications           → list_publisher_notifications
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use shared::{
+    ApplyPatchRequest, CreatePatchCampaignRequest, CreateSecurityPatchRequest, PatchAudit,
+    PatchCampaign, PatchCampaignActionRequest, PatchCampaignDetail, PatchCampaignEvent,
+    PatchCampaignTarget, PatchTargetImpact, PatchTargetStatus, PatchWaveSummary,
+    PublisherNotification, SecurityPatch, UpdatePatchTargetRequest,
+};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    patch_engine::{self, CampaignAction, GateDecision},
+    patch_rollout,
+    state::AppState,
+};
+
+fn db_err(op: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = op, error = ?err, "patch database error");
+    ApiError::internal(format!("Database error while trying to {}", op))
+}
+
+fn is_wasm_hash(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+async fn fetch_patch(state: &AppState, patch_id: Uuid) -> ApiResult<SecurityPatch> {
+    patch_rollout::fetch_patch(&state.db, patch_id)
+        .await
+        .map_err(|e| db_err("fetch patch", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "PatchNotFound",
+                format!("No security patch found with ID: {}", patch_id),
+            )
+        })
+}
+
+async fn fetch_campaign(state: &AppState, campaign_id: Uuid) -> ApiResult<PatchCampaign> {
+    patch_rollout::fetch_campaign(&state.db, campaign_id)
+        .await
+        .map_err(|e| db_err("fetch patch campaign", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "CampaignNotFound",
+                format!("No patch campaign found with ID: {}", campaign_id),
+            )
+        })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub unread: bool,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/patches
+// ─────────────────────────────────────────────────────────
+
+pub async fn create_patch(
+    State(state): State<AppState>,
+    Json(req): Json<CreateSecurityPatchRequest>,
+) -> ApiResult<(StatusCode, Json<SecurityPatch>)> {
+    if req.target_version.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidPatch",
+            "target_version must not be empty",
+        ));
+    }
+    if !is_wasm_hash(&req.new_wasm_hash) {
+        return Err(ApiError::bad_request(
+            "InvalidPatch",
+            "new_wasm_hash must be a 64-character hex WASM hash",
+        ));
+    }
+    if req
+        .vulnerable_wasm_hash
+        .as_deref()
+        .is_some_and(|h| !is_wasm_hash(h))
+    {
+        return Err(ApiError::bad_request(
+            "InvalidPatch",
+            "vulnerable_wasm_hash must be a 64-character hex WASM hash",
+        ));
+    }
+    let rollout_percentage = req.rollout_percentage.unwrap_or(100);
+    if !(0..=100).contains(&rollout_percentage) {
+        return Err(ApiError::bad_request(
+            "InvalidPatch",
+            "rollout_percentage must be between 0 and 100",
+        ));
+    }
+    // Older clients pass the vulnerable WASM hash as the target version
+    let vulnerable_wasm_hash = req
+        .vulnerable_wasm_hash
+        .clone()
+        .or_else(|| is_wasm_hash(&req.target_version).then(|| req.target_version.clone()));
+
+    let patch: SecurityPatch = sqlx::query_as(
+        "INSERT INTO security_patches
+                (target_version, severity, new_wasm_hash, rollout_percentage, description,
+                 vulnerable_wasm_hash)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING *",
+    )
+    .bind(req.target_version.trim())
+    .bind(req.severity)
+    .bind(&req.new_wasm_hash)
+    .bind(rollout_percentage)
+    .bind(&req.description)
+    .bind(vulnerable_wasm_hash)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("create patch", e))?;
+
+    tracing::info!(patch_id = %patch.id, severity = %patch.severity, "security patch created");
+    Ok((StatusCode::CREATED, Json(patch)))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/patches
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_patches(
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> ApiResult<Json<Vec<SecurityPatch>>> {
+    let patches =
+        sqlx::query_as("SELECT * FROM security_patches ORDER BY created_at DESC LIMIT $1")
+            .bind(params.limit.clamp(1, 200))
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list patches", e))?;
+    Ok(Json(patches))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/patches/:id
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<Uuid>,
+) -> ApiResult<Json<SecurityPatch>> {
+    Ok(Json(fetch_patch(&state, patch_id).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/patches/:id/audits
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_patch_audits(
+    State(state): State<AppState>,
+    Path(patch_id): Path<Uuid>,
+) -> ApiResult<Json<serde_json::Value>> {
+    fetch_patch(&state, patch_id).await?;
+    let audits: Vec<PatchAudit> =
+        sqlx::query_as("SELECT * FROM patch_audits WHERE patch_id = $1 ORDER BY applied_at")
+            .bind(patch_id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list patch audits", e))?;
+
+    Ok(Json(serde_json::json!({
+        "total": audits.len(),
+        "items": audits,
+    })))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/patches/:id/apply
+// ─────────────────────────────────────────────────────────
+
+/// Record that a contract has been upgraded to the patched build. With a
+/// live campaign only contracts in a started wave may be upgraded; without
+/// one the patch's rollout percentage caps how many contracts may take it.
+pub async fn apply_patch(
+    State(state): State<AppState>,
+    Path(patch_id): Path<Uuid>,
+    Json(req): Json<ApplyPatchRequest>,
+) -> ApiResult<(StatusCode, Json<PatchAudit>)> {
+    let patch = fetch_patch(&state, patch_id).await?;
+    let campaign = patch_rollout::live_campaign(&state.db, patch_id)
+        .await
+        .map_err(|e| db_err("fetch patch campaign", e))?;
+
+    match &campaign {
+        Some(campaign) => {
+            let target: Option<(PatchTargetImpact, PatchTargetStatus, Option<i32>)> =
+                sqlx::query_as(
+                    "SELECT impact, status, wave FROM patch_campaign_targets
+                      WHERE campaign_id = $1 AND contract_id = $2",
+                )
+                .bind(campaign.id)
+                .bind(req.contract_id)
+                .fetch_optional(&state.db)
+                .await
+                .map_err(|e| db_err("fetch campaign target", e))?;
+            if let Some(reason) = patch_engine::apply_blocker(campaign, target) {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "RolloutBlocked",
+                    reason,
+                ));
+            }
+        }
+        None => {
+            let vulnerable = patch_rollout::vulnerable_contracts(&state.db, &patch)
+                .await
+                .map_err(|e| db_err("find vulnerable contracts", e))?;
+            let applied: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM patch_audits WHERE patch_id = $1")
+                    .bind(patch_id)
+                    .fetch_one(&state.db)
+                    .await
+                    .map_err(|e| db_err("count patch audits", e))?;
+            if !vulnerable.iter().any(|c| c.contract_id == req.contract_id) {
+                return Err(ApiError::unprocessable(
+                    "NotVulnerable",
+                    format!(
+                        "Contract {} is not running the build this patch fixes",
+                        req.contract_id
+                    ),
+                ));
+            }
+            // Already-patched contracts have left the vulnerable set
+            let eligible = vulnerable.len() as i64 + applied;
+            let quota = patch_engine::rollout_quota(eligible, patch.rollout_percentage);
+            if applied >= quota {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "RolloutQuotaExceeded",
+                    format!(
+                        "Rollout quota reached: {}/{} ({}% of {} eligible)",
+                        applied, quota, patch.rollout_percentage, eligible
+                    ),
+                ));
+            }
+        }
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin patch transaction", e))?;
+
+    let audit: PatchAudit = sqlx::query_as(
+        "INSERT INTO patch_audits (contract_id, patch_id)
+         VALUES ($1, $2)
+         ON CONFLICT (contract_id, patch_id) DO NOTHING
+         RETURNING *",
+    )
+    .bind(req.contract_id)
+    .bind(patch_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_err("record patch audit", e))?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            "AlreadyApplied",
+            format!("Patch already applied to contract {}", req.contract_id),
+        )
+    })?;
+
+    sqlx::query("UPDATE contracts SET wasm_hash = $2, updated_at = NOW() WHERE id = $1")
+        .bind(req.contract_id)
+        .bind(&patch.new_wasm_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("update contract WASM hash", e))?;
+
+    if let Some(campaign) = &campaign {
+        patch_rollout::record_applied(&mut tx, campaign.id, req.contract_id, audit.applied_at)
+            .await
+            .map_err(|e| db_err("update campaign target", e))?;
+    }
+
+    tx.commit().await.map_err(|e| db_err("commit patch", e))?;
+
+    tracing::info!(patch_id = %patch_id, contract_id = %req.contract_id, "security patch applied");
+    Ok((StatusCode::CREATED, Json(audit)))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/patches/:id/campaigns
+// ─────────────────────────────────────────────────────────
+
+pub async fn create_campaign(
+    State(state): State<AppState>,
+    Path(patch_id): Path<Uuid>,
+    Json(req): Json<CreatePatchCampaignRequest>,
+) -> ApiResult<(StatusCode, Json<PatchCampaignDetail>)> {
+    let patch = fetch_patch(&state, patch_id).await?;
+
+    let waves = req
+        .wave_percentages
+        .clone()
+        .unwrap_or_else(|| patch_engine::DEFAULT_WAVES.to_vec());
+    patch_engine::validate_waves(&waves)
+        .map_err(|msg| ApiError::bad_request("InvalidWaves", msg))?;
+    if req
+        .min_applied_fraction
+        .is_some_and(|f| f <= 0.0 || f > 1.0)
+    {
+        return Err(ApiError::bad_request(
+            "InvalidCampaign",
+            "min_applied_fraction must be in (0, 1]",
+        ));
+    }
+    if req.min_soak_minutes.is_some_and(|m| m < 0) {
+        return Err(ApiError::bad_request(
+            "InvalidCampaign",
+            "min_soak_minutes must not be negative",
+        ));
+    }
+    if req.created_by.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidCampaign",
+            "created_by must not be empty",
+        ));
+    }
+
+    let existing = patch_rollout::live_campaign(&state.db, patch_id)
+        .await
+        .map_err(|e| db_err("fetch patch campaign", e))?;
+    if let Some(existing) = existing {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "CampaignExists",
+            format!(
+                "Patch {} already has a {} campaign ({})",
+                patch_id, existing.status, existing.id
+            ),
+        ));
+    }
+
+    let candidates = patch_rollout::vulnerable_contracts(&state.db, &patch)
+        .await
+        .map_err(|e| db_err("find vulnerable contracts", e))?;
+    if candidates.is_empty() {
+        return Err(ApiError::unprocessable(
+            "NoVulnerableContracts",
+            "No registered contract is running the vulnerable build",
+        ));
+    }
+    let direct = patch_engine::order_candidates(candidates);
+    let assigned = patch_engine::assign_waves(direct.len(), &waves);
+    let dependents = if req.include_dependents.unwrap_or(true) {
+        let edges = patch_rollout::dependency_edges(&state.db)
+            .await
+            .map_err(|e| db_err("load dependency graph", e))?;
+        patch_engine::dependents_of(&direct, &edges)
+    } else {
+        Vec::new()
+    };
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin campaign transaction", e))?;
+
+    let campaign: PatchCampaign = sqlx::query_as(
+        "INSERT INTO patch_campaigns
+                (patch_id, wave_percentages, max_error_rate_increase, max_latency_increase_pct,
+                 min_soak_minutes, min_applied_fraction, auto_advance, created_by)
+         VALUES ($1, $2, COALESCE($3, 1.0), COALESCE($4, 25.0), COALESCE($5, 60),
+                 COALESCE($6, 0.8), COALESCE($7, TRUE), $8)
+         RETURNING *",
+    )
+    .bind(patch_id)
+    .bind(&waves)
+    .bind(req.max_error_rate_increase)
+    .bind(req.max_latency_increase_pct)
+    .bind(req.min_soak_minutes)
+    .bind(req.min_applied_fraction)
+    .bind(req.auto_advance)
+    .bind(req.created_by.trim())
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("create patch campaign", e))?;
+
+    for (contract_id, wave) in direct.iter().zip(&assigned) {
+        sqlx::query(
+            "INSERT INTO patch_campaign_targets (campaign_id, contract_id, impact, wave)
+             VALUES ($1, $2, 'direct', $3)",
+        )
+        .bind(campaign.id)
+        .bind(contract_id)
+        .bind(wave)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("add campaign target", e))?;
+    }
+
+    for (contract_id, depth, via) in &dependents {
+        sqlx::query(
+            "INSERT INTO patch_campaign_targets
+                    (campaign_id, contract_id, impact, depth, via_contract_id)
+             VALUES ($1, $2, 'dependent', $3, $4)",
+        )
+        .bind(campaign.id)
+        .bind(contract_id)
+        .bind(depth)
+        .bind(via)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("add campaign dependent", e))?;
+
+        let names: Vec<(Uuid, String)> =
+            sqlx::query_as("SELECT id, name FROM contracts WHERE id = ANY($1)")
+                .bind(vec![*contract_id, *via])
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| db_err("fetch contract names", e))?;
+        let name_of = |id: Uuid| {
+            names
+                .iter()
+                .find(|(n, _)| *n == id)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| id.to_string())
+        };
+        patch_rollout::notify_publisher(
+            &mut tx,
+            *contract_id,
+            "patch_dependency_vulnerable",
+            patch_engine::dependent_notification(
+                &patch,
+                &name_of(*contract_id),
+                &name_of(*via),
+                *depth,
+            ),
+            serde_json::json!({ "patch_id": patch.id, "campaign_id": campaign.id, "via": via }),
+        )
+        .await
+        .map_err(|e| db_err("notify publisher", e))?;
+    }
+
+    patch_rollout::log_event(
+        &mut tx,
+        campaign.id,
+        None,
+        "created",
+        &format!(
+            "Campaign created: {} vulnerable contract(s) in {} wave(s), {} dependent(s)",
+            direct.len(),
+            waves.len(),
+            dependents.len()
+        ),
+        Some(req.created_by.trim()),
+    )
+    .await
+    .map_err(|e| db_err("log campaign event", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit patch campaign", e))?;
+
+    tracing::info!(
+        campaign_id = %campaign.id,
+        patch_id    = %patch_id,
+        targets     = direct.len(),
+        dependents  = dependents.len(),
+        "patch campaign created"
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(load_detail(&state, campaign, patch).await?),
+    ))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/patches/:id/campaigns
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_campaigns(
+    State(state): State<AppState>,
+    Path(patch_id): Path<Uuid>,
+) -> ApiResult<Json<Vec<PatchCampaign>>> {
+    fetch_patch(&state, patch_id).await?;
+    let campaigns = sqlx::query_as(
+        "SELECT * FROM patch_campaigns WHERE patch_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(patch_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list patch campaigns", e))?;
+    Ok(Json(campaigns))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/patch-campaigns/:id
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_campaign(
+    State(state): State<AppState>,
+    Path(campaign_id): Path<Uuid>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    let campaign = fetch_campaign(&state, campaign_id).await?;
+    let patch = fetch_patch(&state, campaign.patch_id).await?;
+    Ok(Json(load_detail(&state, campaign, patch).await?))
+}
+
+async fn load_detail(
+    state: &AppState,
+    campaign: PatchCampaign,
+    patch: SecurityPatch,
+) -> ApiResult<PatchCampaignDetail> {
+    let targets: Vec<PatchCampaignTarget> = sqlx::query_as(
+        "SELECT t.campaign_id, t.contract_id, c.name AS contract_name, c.publisher_id,
+                t.impact, t.depth, t.via_contract_id, t.wave, t.status, t.scheduled_at,
+                t.applied_at, t.baseline_error_rate, t.baseline_latency_ms,
+                t.failure_reason, t.updated_at
+           FROM patch_campaign_targets t
+           JOIN contracts c ON c.id = t.contract_id
+          WHERE t.campaign_id = $1
+          ORDER BY t.impact, t.wave NULLS LAST, t.depth, c.name",
+    )
+    .bind(campaign.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch campaign targets", e))?;
+
+    let events: Vec<PatchCampaignEvent> = sqlx::query_as(
+        "SELECT * FROM patch_campaign_events WHERE campaign_id = $1 ORDER BY created_at, id",
+    )
+    .bind(campaign.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch campaign events", e))?;
+
+    let direct_statuses = |wave: Option<i32>| -> Vec<PatchTargetStatus> {
+        targets
+            .iter()
+            .filter(|t| t.impact == PatchTargetImpact::Direct)
+            .filter(|t| wave.is_none() || t.wave == wave)
+            .map(|t| t.status)
+            .collect()
+    };
+    let progress = patch_engine::progress_of(&direct_statuses(None));
+    let waves = campaign
+        .wave_percentages
+        .iter()
+        .enumerate()
+        .map(|(i, pct)| PatchWaveSummary {
+            wave: i as i32 + 1,
+            cumulative_percentage: *pct,
+            progress: patch_engine::progress_of(&direct_statuses(Some(i as i32 + 1))),
+        })
+        .collect();
+    let dependents: Vec<_> = targets
+        .iter()
+        .filter(|t| t.impact == PatchTargetImpact::Dependent)
+        .collect();
+
+    Ok(PatchCampaignDetail {
+        dependents_total: dependents.len() as i64,
+        dependents_resolved: dependents
+            .iter()
+            .filter(|t| t.status == PatchTargetStatus::Resolved)
+            .count() as i64,
+        campaign,
+        patch,
+        progress,
+        waves,
+        targets,
+        events,
+    })
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/patch-campaigns/:id/{start,advance,pause,resume,abort}
+// ─────────────────────────────────────────────────────────
+
+pub async fn start_campaign(
+    state: State<AppState>,
+    id: Path<Uuid>,
+    req: Json<PatchCampaignActionRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    campaign_action(state, id, req, CampaignAction::Start).await
+}
+
+pub async fn advance_campaign(
+    state: State<AppState>,
+    id: Path<Uuid>,
+    req: Json<PatchCampaignActionRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    campaign_action(state, id, req, CampaignAction::Advance).await
+}
+
+pub async fn pause_campaign(
+    state: State<AppState>,
+    id: Path<Uuid>,
+    req: Json<PatchCampaignActionRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    campaign_action(state, id, req, CampaignAction::Pause).await
+}
+
+pub async fn resume_campaign(
+    state: State<AppState>,
+    id: Path<Uuid>,
+    req: Json<PatchCampaignActionRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    campaign_action(state, id, req, CampaignAction::Resume).await
+}
+
+pub async fn abort_campaign(
+    state: State<AppState>,
+    id: Path<Uuid>,
+    req: Json<PatchCampaignActionRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    campaign_action(state, id, req, CampaignAction::Abort).await
+}
+
+async fn campaign_action(
+    State(state): State<AppState>,
+    Path(campaign_id): Path<Uuid>,
+    Json(req): Json<PatchCampaignActionRequest>,
+    action: CampaignAction,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    if req.actor.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidAction",
+            "actor must not be empty",
+        ));
+    }
+    let actor = Some(req.actor.trim());
+    let campaign = fetch_campaign(&state, campaign_id).await?;
+    let patch = fetch_patch(&state, campaign.patch_id).await?;
+    patch_engine::check_action(campaign.status, action)
+        .map_err(|msg| ApiError::new(StatusCode::CONFLICT, "InvalidCampaignAction", msg))?;
+
+    let updated = match action {
+        CampaignAction::Start => {
+            patch_rollout::start_next_wave(&state.db, &campaign, &patch, actor)
+                .await
+                .map_err(|e| db_err("start patch campaign", e))?
+        }
+        // Manual advance skips the soak and upgrade-fraction checks but
+        // never a failing health gate
+        CampaignAction::Advance => {
+            patch_rollout::reconcile(&state.db, &campaign, &patch)
+                .await
+                .map_err(|e| db_err("reconcile patch campaign", e))?;
+            let decision = patch_rollout::evaluate(&state.db, &campaign)
+                .await
+                .map_err(|e| db_err("evaluate health gate", e))?;
+            if let GateDecision::Fail(reason) = decision {
+                patch_rollout::pause(&state.db, &campaign, &reason, None)
+                    .await
+                    .map_err(|e| db_err("pause patch campaign", e))?;
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    "HealthGateFailed",
+                    format!("Campaign paused: {}", reason),
+                ));
+            }
+            patch_rollout::start_next_wave(&state.db, &campaign, &patch, actor)
+                .await
+                .map_err(|e| db_err("advance patch campaign", e))?
+        }
+        CampaignAction::Pause => {
+            let reason = req
+                .reason
+                .as_deref()
+                .filter(|r| !r.trim().is_empty())
+                .unwrap_or("Paused by operator");
+            patch_rollout::pause(&state.db, &campaign, reason, actor)
+                .await
+                .map_err(|e| db_err("pause patch campaign", e))?
+        }
+        CampaignAction::Resume | CampaignAction::Abort => {
+            let (status, kind, message) = if action == CampaignAction::Resume {
+                (
+                    "rolling_out",
+                    "resumed",
+                    "Campaign resumed; health measurement restarted",
+                )
+            } else {
+                ("aborted", "aborted", "Campaign aborted")
+            };
+            let mut tx = state
+                .db
+                .begin()
+                .await
+                .map_err(|e| db_err("begin campaign transaction", e))?;
+            let updated: PatchCampaign = sqlx::query_as(
+                "UPDATE patch_campaigns
+                    SET status = $2::patch_campaign_status,
+                        paused_reason = NULL,
+                        gate_reset_at = CASE WHEN $2 = 'rolling_out' THEN NOW() ELSE gate_reset_at END,
+                        wave_started_at = CASE WHEN $2 = 'rolling_out' THEN NOW() ELSE wave_started_at END,
+                        completed_at = CASE WHEN $2 = 'aborted' THEN NOW() ELSE completed_at END,
+                        updated_at = NOW()
+                  WHERE id = $1
+                  RETURNING *",
+            )
+            .bind(campaign.id)
+            .bind(status)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| db_err("update patch campaign", e))?;
+            let message = match req.reason.as_deref().filter(|r| !r.trim().is_empty()) {
+                Some(reason) => format!("{}: {}", message, reason.trim()),
+                None => message.to_string(),
+            };
+            patch_rollout::log_event(
+                &mut tx,
+                campaign.id,
+                Some(campaign.current_wave),
+                kind,
+                &message,
+                actor,
+            )
+            .await
+            .map_err(|e| db_err("log campaign event", e))?;
+            tx.commit()
+                .await
+                .map_err(|e| db_err("commit patch campaign", e))?;
+            updated
+        }
+    };
+
+    tracing::info!(campaign_id = %campaign_id, ?action, actor = %req.actor, "patch campaign action");
+    Ok(Json(load_detail(&state, updated, patch).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/patch-campaigns/:id/targets/:contract_id
+// ─────────────────────────────────────────────────────────
+
+/// Mark a direct target as failed or skipped. Neither counts towards the
+/// wave's health gate; skipped targets are also left out of the
+/// upgrade-fraction requirement.
+pub async fn update_campaign_target(
+    State(state): State<AppState>,
+    Path((campaign_id, contract_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdatePatchTargetRequest>,
+) -> ApiResult<Json<PatchCampaignDetail>> {
+    if !matches!(
+        req.status,
+        PatchTargetStatus::Failed | PatchTargetStatus::Skipped
+    ) {
+        return Err(ApiError::bad_request(
+            "InvalidTargetStatus",
+            "targets can only be marked failed or skipped",
+        ));
+    }
+    let campaign = fetch_campaign(&state, campaign_id).await?;
+    let patch = fetch_patch(&state, campaign.patch_id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin campaign transaction", e))?;
+
+    let wave: Option<Option<i32>> = sqlx::query_scalar(
+        "UPDATE patch_campaign_targets
+            SET status = $3, failure_reason = $4, updated_at = NOW()
+          WHERE campaign_id = $1 AND contract_id = $2 AND impact = 'direct'
+          RETURNING wave",
+    )
+    .bind(campaign_id)
+    .bind(contract_id)
+    .bind(req.status)
+    .bind(&req.reason)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_err("update campaign target", e))?;
+    let Some(wave) = wave else {
+        return Err(ApiError::not_found(
+            "TargetNotFound",
+            format!(
+                "Contract {} is not a direct target of campaign {}",
+                contract_id, campaign_id
+            ),
+        ));
+    };
+
+    patch_rollout::log_event(
+        &mut tx,
+        campaign_id,
+        wave,
+        "target_updated",
+        &format!(
+            "Contract {} marked {}{}",
+            contract_id,
+            format!("{:?}", req.status).to_lowercase(),
+            req.reason
+                .as_deref()
+                .map(|r| format!(": {}", r))
+                .unwrap_or_default()
+        ),
+        Some(req.actor.as_str()),
+    )
+    .await
+    .map_err(|e| db_err("log campaign event", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit campaign target", e))?;
+
+    Ok(Json(load_detail(&state, campaign, patch).await?))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/publishers/:id/notifications
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_publisher_notifications(
+    State(state): State<AppState>,
+    Path(publisher_id): Path<Uuid>,
+    Query(params): Query<ListParams>,
+) -> ApiResult<Json<Vec<PublisherNotification>>> {
+    let notifications = sqlx::query_as(
+        "SELECT * FROM publisher_notifications
+          WHERE publisher_id = $1 AND (NOT $2 OR read_at IS NULL)
+          ORDER BY created_at DESC
+          LIMIT $3",
+    )
+    .bind(publisher_id)
+    .bind(params.unread)
+    .bind(params.limit.clamp(1, 500))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list publisher notifications", e))?;
+    Ok(Json(notifications))
+}
@@ -0,0 +1,528 @@
+// api/src/patch_rollout.rs
+//
+// Loads and persists security-patch campaigns. The background scheduler
+// reconciles every rolling-out campaign against the registry (a target is
+// applied once the contract runs the patched WASM), evaluates the health
+// gate for the current wave and either pauses the campaign or starts the
+// next wave. Handlers reuse the same functions for operator actions.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use shared::{PatchCampaign, PatchRolloutProgress, PatchTargetStatus, SecurityPatch};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::patch_engine::{self, GateDecision, RolloutCandidate, TargetHealth};
+
+const TICK_SECS: u64 = 300;
+/// Performance before an upgrade is averaged over this many hours
+const BASELINE_HOURS: i64 = 24;
+
+pub fn spawn_patch_rollout_scheduler(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(TICK_SECS));
+        loop {
+            interval.tick().await;
+            if let Err(err) = tick_all(&pool).await {
+                tracing::error!(error = ?err, "patch rollout scheduler failed");
+            }
+        }
+    });
+}
+
+async fn tick_all(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let campaigns: Vec<PatchCampaign> =
+        sqlx::query_as("SELECT * FROM patch_campaigns WHERE status = 'rolling_out'")
+            .fetch_all(pool)
+            .await?;
+
+    for campaign in campaigns {
+        match tick_campaign(pool, &campaign, None).await {
+            Ok(GateDecision::Fail(reason)) => {
+                tracing::warn!(campaign_id = %campaign.id, %reason, "patch campaign paused")
+            }
+            Ok(_) => {}
+            Err(err) => {
+                tracing::error!(campaign_id = %campaign.id, error = ?err, "patch campaign tick failed")
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Loading
+// ─────────────────────────────────────────────────────────
+
+pub async fn fetch_patch(
+    pool: &PgPool,
+    patch_id: Uuid,
+) -> Result<Option<SecurityPatch>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM security_patches WHERE id = $1")
+        .bind(patch_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn fetch_campaign(
+    pool: &PgPool,
+    campaign_id: Uuid,
+) -> Result<Option<PatchCampaign>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM patch_campaigns WHERE id = $1")
+        .bind(campaign_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The patch's pending, rolling-out or paused campaign, if any
+pub async fn live_campaign(
+    pool: &PgPool,
+    patch_id: Uuid,
+) -> Result<Option<PatchCampaign>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT * FROM patch_campaigns
+          WHERE patch_id = $1 AND status IN ('pending', 'rolling_out', 'paused')",
+    )
+    .bind(patch_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Unpatched contracts running the vulnerable build: those whose WASM hash
+/// matches the patch's vulnerable hash, or whose current WASM is the one
+/// registered for the patch's target version.
+pub async fn vulnerable_contracts(
+    pool: &PgPool,
+    patch: &SecurityPatch,
+) -> Result<Vec<RolloutCandidate>, sqlx::Error> {
+    let rows: Vec<(Uuid, String, i64)> = sqlx::query_as(
+        "SELECT c.id, c.network::text,
+                (SELECT COUNT(*) FROM contract_interactions i WHERE i.contract_id = c.id)
+           FROM contracts c
+          WHERE c.wasm_hash <> $3
+            AND (c.wasm_hash = $1
+                 OR EXISTS (SELECT 1 FROM contract_versions v
+                             WHERE v.contract_id = c.id
+                               AND v.version = $2
+                               AND v.wasm_hash = c.wasm_hash))
+            AND NOT EXISTS (SELECT 1 FROM patch_audits a
+                             WHERE a.contract_id = c.id AND a.patch_id = $4)",
+    )
+    .bind(
+        patch
+            .vulnerable_wasm_hash
+            .as_deref()
+            .unwrap_or(&patch.target_version),
+    )
+    .bind(&patch.target_version)
+    .bind(&patch.new_wasm_hash)
+    .bind(patch.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(contract_id, network, interactions)| RolloutCandidate {
+            contract_id,
+            network,
+            interactions,
+        })
+        .collect())
+}
+
+pub async fn dependency_edges(pool: &PgPool) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT contract_id, dependency_contract_id FROM contract_dependencies
+          WHERE dependency_contract_id IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// ─────────────────────────────────────────────────────────
+// Events and notifications
+// ─────────────────────────────────────────────────────────
+
+pub async fn log_event(
+    conn: &mut PgConnection,
+    campaign_id: Uuid,
+    wave: Option<i32>,
+    kind: &str,
+    message: &str,
+    actor: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO patch_campaign_events (campaign_id, wave, kind, message, actor)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(campaign_id)
+    .bind(wave)
+    .bind(kind)
+    .bind(message)
+    .bind(actor)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Queue a notification for the publisher of `contract_id`.
+pub async fn notify_publisher(
+    conn: &mut PgConnection,
+    contract_id: Uuid,
+    kind: &str,
+    (subject, body): (String, String),
+    payload: serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO publisher_notifications (publisher_id, contract_id, kind, subject, body, payload)
+         SELECT publisher_id, id, $2, $3, $4, $5 FROM contracts WHERE id = $1",
+    )
+    .bind(contract_id)
+    .bind(kind)
+    .bind(subject)
+    .bind(body)
+    .bind(payload)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+async fn contract_name(conn: &mut PgConnection, contract_id: Uuid) -> Result<String, sqlx::Error> {
+    let name: Option<String> = sqlx::query_scalar("SELECT name FROM contracts WHERE id = $1")
+        .bind(contract_id)
+        .fetch_optional(conn)
+        .await?;
+    Ok(name.unwrap_or_else(|| contract_id.to_string()))
+}
+
+// ─────────────────────────────────────────────────────────
+// Waves
+// ─────────────────────────────────────────────────────────
+
+/// Move the campaign to its next wave: schedule that wave's targets and ask
+/// their publishers to upgrade. After the last wave the campaign completes.
+pub async fn start_next_wave(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+    patch: &SecurityPatch,
+    actor: Option<&str>,
+) -> Result<PatchCampaign, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let next = campaign.current_wave + 1;
+
+    if next as usize > campaign.wave_percentages.len() {
+        let completed: PatchCampaign = sqlx::query_as(
+            "UPDATE patch_campaigns
+                SET status = 'completed', completed_at = NOW(), updated_at = NOW()
+              WHERE id = $1
+              RETURNING *",
+        )
+        .bind(campaign.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let progress = campaign_progress(&mut tx, campaign.id, None).await?;
+        log_event(
+            &mut tx,
+            campaign.id,
+            Some(campaign.current_wave),
+            "completed",
+            &format!(
+                "Rollout complete: {} applied, {} failed, {} skipped of {} contracts",
+                progress.applied, progress.failed, progress.skipped, progress.total
+            ),
+            actor,
+        )
+        .await?;
+        tx.commit().await?;
+        return Ok(completed);
+    }
+
+    let scheduled: Vec<(Uuid, String)> = sqlx::query_as(
+        "UPDATE patch_campaign_targets t
+            SET status = 'scheduled', scheduled_at = NOW(), updated_at = NOW()
+           FROM contracts c
+          WHERE t.campaign_id = $1 AND t.wave = $2 AND t.status = 'pending'
+            AND c.id = t.contract_id
+          RETURNING t.contract_id, c.name",
+    )
+    .bind(campaign.id)
+    .bind(next)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (contract_id, name) in &scheduled {
+        notify_publisher(
+            &mut tx,
+            *contract_id,
+            "patch_scheduled",
+            patch_engine::upgrade_notification(patch, name, next),
+            serde_json::json!({
+                "patch_id": patch.id,
+                "campaign_id": campaign.id,
+                "wave": next,
+                "new_wasm_hash": patch.new_wasm_hash,
+            }),
+        )
+        .await?;
+    }
+
+    let updated: PatchCampaign = sqlx::query_as(
+        "UPDATE patch_campaigns
+            SET status = 'rolling_out', current_wave = $2, wave_started_at = NOW(),
+                started_at = COALESCE(started_at, NOW()), updated_at = NOW()
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(campaign.id)
+    .bind(next)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    log_event(
+        &mut tx,
+        campaign.id,
+        Some(next),
+        "wave_started",
+        &format!(
+            "Wave {} started: {} contract(s) scheduled ({}% cumulative)",
+            next,
+            scheduled.len(),
+            campaign.wave_percentages[next as usize - 1]
+        ),
+        actor,
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+async fn campaign_progress(
+    conn: &mut PgConnection,
+    campaign_id: Uuid,
+    wave: Option<i32>,
+) -> Result<PatchRolloutProgress, sqlx::Error> {
+    let statuses: Vec<PatchTargetStatus> = sqlx::query_scalar(
+        "SELECT status FROM patch_campaign_targets
+          WHERE campaign_id = $1 AND impact = 'direct' AND ($2::int IS NULL OR wave = $2)",
+    )
+    .bind(campaign_id)
+    .bind(wave)
+    .fetch_all(conn)
+    .await?;
+    Ok(patch_engine::progress_of(&statuses))
+}
+
+// ─────────────────────────────────────────────────────────
+// Reconciliation
+// ─────────────────────────────────────────────────────────
+
+/// Mark a target applied and capture its pre-upgrade performance baseline.
+pub async fn record_applied(
+    conn: &mut PgConnection,
+    campaign_id: Uuid,
+    contract_id: Uuid,
+    applied_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE patch_campaign_targets
+            SET status = 'applied', applied_at = $3, updated_at = NOW(),
+                baseline_error_rate = (
+                    SELECT AVG(value)::float8 FROM performance_metrics
+                     WHERE contract_id = $2 AND metric_type = 'error_rate'
+                       AND timestamp >= $3 - make_interval(hours => $4) AND timestamp < $3),
+                baseline_latency_ms = (
+                    SELECT AVG(value)::float8 FROM performance_metrics
+                     WHERE contract_id = $2 AND metric_type = 'execution_time'
+                       AND timestamp >= $3 - make_interval(hours => $4) AND timestamp < $3)
+          WHERE campaign_id = $1 AND contract_id = $2",
+    )
+    .bind(campaign_id)
+    .bind(contract_id)
+    .bind(applied_at)
+    .bind(BASELINE_HOURS as i32)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Pick up upgrades made outside `patch apply` (the contract now runs the
+/// patched WASM), then resolve dependents whose upstream target is patched.
+pub async fn reconcile(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+    patch: &SecurityPatch,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let upgraded: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT t.contract_id, c.updated_at
+           FROM patch_campaign_targets t
+           JOIN contracts c ON c.id = t.contract_id
+          WHERE t.campaign_id = $1 AND t.impact = 'direct'
+            AND t.status IN ('pending', 'scheduled')
+            AND c.wasm_hash = $2",
+    )
+    .bind(campaign.id)
+    .bind(&patch.new_wasm_hash)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (contract_id, applied_at) in &upgraded {
+        sqlx::query(
+            "INSERT INTO patch_audits (contract_id, patch_id, applied_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (contract_id, patch_id) DO NOTHING",
+        )
+        .bind(contract_id)
+        .bind(patch.id)
+        .bind(applied_at)
+        .execute(&mut *tx)
+        .await?;
+        record_applied(&mut tx, campaign.id, *contract_id, *applied_at).await?;
+    }
+
+    let resolved: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        "UPDATE patch_campaign_targets d
+            SET status = 'resolved', updated_at = NOW()
+           FROM patch_campaign_targets up
+          WHERE d.campaign_id = $1 AND d.impact = 'dependent' AND d.status = 'pending'
+            AND up.campaign_id = d.campaign_id AND up.contract_id = d.via_contract_id
+            AND up.status = 'applied'
+          RETURNING d.contract_id, d.via_contract_id",
+    )
+    .bind(campaign.id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (contract_id, via) in &resolved {
+        let name = contract_name(&mut tx, *contract_id).await?;
+        let via_name = contract_name(&mut tx, *via).await?;
+        notify_publisher(
+            &mut tx,
+            *contract_id,
+            "patch_dependency_resolved",
+            (
+                format!("[{}] Dependency of {} patched", patch.severity, name),
+                format!(
+                    "{} has been upgraded to the patched build (WASM {}). {} no longer \
+                     depends on a vulnerable contract through it.",
+                    via_name, patch.new_wasm_hash, name
+                ),
+            ),
+            serde_json::json!({ "patch_id": patch.id, "campaign_id": campaign.id, "via": via }),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Health gate
+// ─────────────────────────────────────────────────────────
+
+/// Before/after performance of every applied target in waves started so
+/// far. Measurement restarts at `gate_reset_at` after a resume.
+async fn applied_health(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+) -> Result<Vec<TargetHealth>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT c.name AS contract_name,
+                t.baseline_error_rate,
+                (SELECT AVG(m.value)::float8 FROM performance_metrics m
+                  WHERE m.contract_id = t.contract_id AND m.metric_type = 'error_rate'
+                    AND m.timestamp >= GREATEST(t.applied_at, $2)) AS current_error_rate,
+                t.baseline_latency_ms,
+                (SELECT AVG(m.value)::float8 FROM performance_metrics m
+                  WHERE m.contract_id = t.contract_id AND m.metric_type = 'execution_time'
+                    AND m.timestamp >= GREATEST(t.applied_at, $2)) AS current_latency_ms,
+                (SELECT COUNT(*) FROM performance_anomalies a
+                  WHERE a.contract_id = t.contract_id AND a.severity = 'critical'
+                    AND NOT a.resolved
+                    AND a.detected_at >= GREATEST(t.applied_at, $2)) AS anomalies
+           FROM patch_campaign_targets t
+           JOIN contracts c ON c.id = t.contract_id
+          WHERE t.campaign_id = $1 AND t.status = 'applied' AND t.wave <= $3",
+    )
+    .bind(campaign.id)
+    .bind(campaign.gate_reset_at)
+    .bind(campaign.current_wave)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn evaluate(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+) -> Result<GateDecision, sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    let wave = campaign_progress(&mut conn, campaign.id, Some(campaign.current_wave)).await?;
+    drop(conn);
+    let health = applied_health(pool, campaign).await?;
+    Ok(patch_engine::evaluate_gate(
+        campaign,
+        &wave,
+        &health,
+        Utc::now(),
+    ))
+}
+
+pub async fn pause(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+    reason: &str,
+    actor: Option<&str>,
+) -> Result<PatchCampaign, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let paused: PatchCampaign = sqlx::query_as(
+        "UPDATE patch_campaigns
+            SET status = 'paused', paused_reason = $2, updated_at = NOW()
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(campaign.id)
+    .bind(reason)
+    .fetch_one(&mut *tx)
+    .await?;
+    log_event(
+        &mut tx,
+        campaign.id,
+        Some(campaign.current_wave),
+        "paused",
+        reason,
+        actor,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(paused)
+}
+
+/// One scheduler step for a rolling-out campaign: reconcile, evaluate the
+/// gate, then pause on failure or advance when the gate passes and the
+/// campaign advances automatically. Returns the gate decision.
+pub async fn tick_campaign(
+    pool: &PgPool,
+    campaign: &PatchCampaign,
+    actor: Option<&str>,
+) -> Result<GateDecision, sqlx::Error> {
+    let Some(patch) = fetch_patch(pool, campaign.patch_id).await? else {
+        return Ok(GateDecision::Wait("patch no longer exists".into()));
+    };
+    reconcile(pool, campaign, &patch).await?;
+
+    let decision = evaluate(pool, campaign).await?;
+    match &decision {
+        GateDecision::Fail(reason) => {
+            pause(pool, campaign, reason, actor).await?;
+        }
+        GateDecision::Pass if campaign.auto_advance => {
+            start_next_wave(pool, campaign, &patch, actor).await?;
+        }
+        GateDecision::Pass | GateDecision::Wait(_) => {}
+    }
+    Ok(decision)
+}
@@ -0,0 +1,61 @@
+// patch_routes.rs
+// Route definitions for security patches and rollout campaigns
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{patch_handlers, state::AppState};
+
+pub fn patch_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/patches",
+            get(patch_handlers::list_patches).post(patch_handlers::create_patch),
+        )
+        .route("/api/patches/:id", get(patch_handlers::get_patch))
+        .route(
+            "/api/patches/:id/audits",
+            get(patch_handlers::list_patch_audits),
+        )
+        .route("/api/patches/:id/apply", post(patch_handlers::apply_patch))
+        // Campaigns
+        .route(
+            "/api/patches/:id/campaigns",
+            get(patch_handlers::list_campaigns).post(patch_handlers::create_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id",
+            get(patch_handlers::get_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/start",
+            post(patch_handlers::start_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/advance",
+            post(patch_handlers::advance_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/pause",
+            post(patch_handlers::pause_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/resume",
+            post(patch_handlers::resume_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/abort",
+            post(patch_handlers::abort_campaign),
+        )
+        .route(
+            "/api/patch-campaigns/:id/targets/:contract_id",
+            post(patch_handlers::update_campaign_target),
+        )
+        // Notifications
+        .route(
+            "/api/publishers/:id/notifications",
+            get(patch_handlers::list_publisher_notifications),
+        )
+}
@@ -0,0 +1,641 @@
+// api/src/proposal_execution.rs
+//
+// Timelocked execution of approved governance and multisig proposals.
+//
+// A proposal that passes is queued with an ETA `execution_delay_hours` after
+// approval. Before the ETA the queued action can be cancelled; from the ETA
+// until the grace period runs out anyone may execute it. The executor applies
+// the action through the existing handlers and records the outcome in the
+// contract audit log.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use shared::{
+    AuditActionType, CancelExecutionRequest, ConfigRollbackRequest, MaturityLevel,
+    ProposalAction, ProposalExecution, ProposalExecutionStatus, ProposalSource,
+};
+
+use crate::{
+    config_handlers::{self, ConfigQuery},
+    contract_history_handlers::log_contract_change,
+    error::{ApiError, ApiResult},
+    state::AppState,
+};
+
+/// How long a queued action stays executable after its ETA. Past this the
+/// approval is considered stale and the proposal has to be raised again.
+pub const GRACE_PERIOD_DAYS: i64 = 14;
+
+// ─────────────────────────────────────────────────────────
+// Timelock rules
+// ─────────────────────────────────────────────────────────
+
+/// Earliest execution time for a proposal approved at `approved_at`.
+pub fn eta(approved_at: DateTime<Utc>, delay_hours: Option<i32>) -> DateTime<Utc> {
+    approved_at + Duration::hours(delay_hours.unwrap_or(0).max(0) as i64)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExecutionBlocked {
+    /// The execution is in a state that does not allow the operation
+    Status(ProposalExecutionStatus),
+    TimelockActive {
+        eta: DateTime<Utc>,
+    },
+    Stale {
+        deadline: DateTime<Utc>,
+    },
+    CancellationWindowClosed {
+        eta: DateTime<Utc>,
+    },
+}
+
+impl From<ExecutionBlocked> for ApiError {
+    fn from(blocked: ExecutionBlocked) -> Self {
+        match blocked {
+            ExecutionBlocked::Status(status) => ApiError::new(
+                StatusCode::CONFLICT,
+                "invalid_execution_status",
+                format!(
+                    "Proposal execution is {}",
+                    serde_json::to_value(status).unwrap_or_default()
+                ),
+            ),
+            ExecutionBlocked::TimelockActive { eta } => ApiError::new(
+                StatusCode::CONFLICT,
+                "timelock_active",
+                format!("Proposal cannot be executed before {}", eta.to_rfc3339()),
+            ),
+            ExecutionBlocked::Stale { deadline } => ApiError::new(
+                StatusCode::GONE,
+                "execution_stale",
+                format!(
+                    "Execution window closed at {}; the proposal must be raised again",
+                    deadline.to_rfc3339()
+                ),
+            ),
+            ExecutionBlocked::CancellationWindowClosed { eta } => ApiError::new(
+                StatusCode::CONFLICT,
+                "cancellation_window_closed",
+                format!(
+                    "Proposal could only be cancelled before {}",
+                    eta.to_rfc3339()
+                ),
+            ),
+        }
+    }
+}
+
+/// Queued executions (and failed ones, for a retry) run between the ETA and
+/// the end of the grace period.
+pub fn check_executable(
+    execution: &ProposalExecution,
+    now: DateTime<Utc>,
+) -> Result<(), ExecutionBlocked> {
+    if !matches!(
+        execution.status,
+        ProposalExecutionStatus::Queued | ProposalExecutionStatus::Failed
+    ) {
+        return Err(ExecutionBlocked::Status(execution.status));
+    }
+    if now < execution.eta {
+        return Err(ExecutionBlocked::TimelockActive { eta: execution.eta });
+    }
+    let deadline = execution.eta + Duration::days(GRACE_PERIOD_DAYS);
+    if now > deadline {
+        return Err(ExecutionBlocked::Stale { deadline });
+    }
+    Ok(())
+}
+
+/// The timelock doubles as the cancellation window: once the ETA has
+/// passed the action may already be running, so it can no longer be pulled.
+pub fn check_cancellable(
+    execution: &ProposalExecution,
+    now: DateTime<Utc>,
+) -> Result<(), ExecutionBlocked> {
+    if execution.status != ProposalExecutionStatus::Queued {
+        return Err(ExecutionBlocked::Status(execution.status));
+    }
+    if now >= execution.eta {
+        return Err(ExecutionBlocked::CancellationWindowClosed { eta: execution.eta });
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Actions
+// ─────────────────────────────────────────────────────────
+
+/// Checks that can be made when the proposal is created, before anyone votes
+/// or signs on it.
+pub fn validate_action(action: &ProposalAction) -> Result<(), String> {
+    match action {
+        ProposalAction::UpgradeWasm { wasm_hash, version } => {
+            if wasm_hash.len() != 64 || !wasm_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err("wasm_hash must be a 64-character hex string".into());
+            }
+            if let Some(version) = version {
+                if shared::SemVer::parse(version).is_none() {
+                    return Err(format!("'{}' is not a valid semantic version", version));
+                }
+            }
+        }
+        ProposalAction::SetConfigVersion {
+            environment,
+            version,
+        } => {
+            if environment.trim().is_empty() {
+                return Err("environment is required".into());
+            }
+            if *version < 1 {
+                return Err("config version must be at least 1".into());
+            }
+        }
+        ProposalAction::ChangeMaturity { .. } | ProposalAction::TransferOwnership { .. } => {}
+    }
+    Ok(())
+}
+
+pub fn parse_action(
+    value: Option<&serde_json::Value>,
+) -> Result<Option<ProposalAction>, serde_json::Error> {
+    value
+        .filter(|v| !v.is_null())
+        .map(|v| serde_json::from_value(v.clone()))
+        .transpose()
+}
+
+async fn contract_row(db: &PgPool, contract_id: Uuid) -> ApiResult<serde_json::Value> {
+    sqlx::query_scalar("SELECT row_to_json(contracts.*) FROM contracts WHERE id = $1")
+        .bind(contract_id)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))
+}
+
+/// `maturity_level` label for an API maturity level. The database enum names
+/// the outer levels alpha and mature.
+fn maturity_label(level: &MaturityLevel) -> &'static str {
+    match level {
+        MaturityLevel::Experimental => "alpha",
+        MaturityLevel::Beta => "beta",
+        MaturityLevel::Stable => "stable",
+        MaturityLevel::Production => "mature",
+    }
+}
+
+/// Apply `action` to a registered contract. Configuration changes go through
+/// the config handler and maturity changes are written to `maturity_changes`,
+/// so both history tables are kept up to date.
+async fn apply_action(
+    state: &AppState,
+    contract_id: Uuid,
+    action: &ProposalAction,
+    actor: &str,
+) -> ApiResult<serde_json::Value> {
+    match action {
+        ProposalAction::UpgradeWasm { wasm_hash, version } => {
+            let mut tx = state
+                .db
+                .begin()
+                .await
+                .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+            if let Some(version) = version {
+                sqlx::query(
+                    "INSERT INTO contract_versions (contract_id, version, wasm_hash) VALUES ($1, $2, $3)",
+                )
+                .bind(contract_id)
+                .bind(version)
+                .bind(wasm_hash)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to register version: {}", e)))?;
+            }
+            sqlx::query("UPDATE contracts SET wasm_hash = $2, updated_at = NOW() WHERE id = $1")
+                .bind(contract_id)
+                .bind(wasm_hash)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to upgrade contract: {}", e)))?;
+            tx.commit()
+                .await
+                .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+            Ok(json!({ "wasm_hash": wasm_hash, "version": version }))
+        }
+        ProposalAction::SetConfigVersion {
+            environment,
+            version,
+        } => {
+            let (_, Json(config)) = config_handlers::rollback_config(
+                State(state.clone()),
+                Path(contract_id),
+                Query(ConfigQuery {
+                    environment: environment.clone(),
+                }),
+                Json(ConfigRollbackRequest {
+                    roll_back_to_version: *version,
+                    created_by: actor.to_string(),
+                }),
+            )
+            .await?;
+            Ok(serde_json::to_value(config).unwrap_or_default())
+        }
+        ProposalAction::ChangeMaturity { maturity, reason } => {
+            let mut tx = state
+                .db
+                .begin()
+                .await
+                .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+            // `old` is read from the pre-update snapshot
+            let (previous, publisher_id): (String, Uuid) = sqlx::query_as(
+                r#"
+                UPDATE contracts c SET maturity = $2::maturity_level, updated_at = NOW()
+                FROM contracts old
+                WHERE c.id = $1 AND old.id = c.id
+                RETURNING old.maturity::text, c.publisher_id
+                "#,
+            )
+            .bind(contract_id)
+            .bind(maturity_label(maturity))
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to update maturity: {}", e)))?
+            .ok_or_else(|| ApiError::not_found("contract", "Contract not found"))?;
+            sqlx::query(
+                "INSERT INTO maturity_changes (contract_id, from_level, to_level, reason, changed_by)
+                 VALUES ($1, $2::maturity_level, $3::maturity_level, $4, $5)",
+            )
+            .bind(contract_id)
+            .bind(&previous)
+            .bind(maturity_label(maturity))
+            .bind(reason)
+            .bind(publisher_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to log maturity change: {}", e)))?;
+            tx.commit()
+                .await
+                .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+            Ok(json!({ "maturity": maturity, "previous_maturity": previous }))
+        }
+        ProposalAction::TransferOwnership { new_publisher_id } => {
+            // `old` is read from the pre-update snapshot
+            let previous: Option<Uuid> = sqlx::query_scalar(
+                r#"
+                UPDATE contracts c SET publisher_id = $2, updated_at = NOW()
+                FROM contracts old
+                WHERE c.id = $1 AND old.id = c.id
+                  AND EXISTS (SELECT 1 FROM publishers WHERE id = $2)
+                RETURNING old.publisher_id
+                "#,
+            )
+            .bind(contract_id)
+            .bind(new_publisher_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to transfer ownership: {}", e)))?;
+            let previous = previous.ok_or_else(|| {
+                ApiError::not_found("publisher", "New owner is not a registered publisher")
+            })?;
+            Ok(json!({ "previous_publisher_id": previous, "new_publisher_id": new_publisher_id }))
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Queue
+// ─────────────────────────────────────────────────────────
+
+pub async fn fetch_execution(
+    db: &PgPool,
+    source: ProposalSource,
+    proposal_id: Uuid,
+) -> Result<Option<ProposalExecution>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM proposal_executions WHERE source = $1 AND proposal_id = $2")
+        .bind(source)
+        .bind(proposal_id)
+        .fetch_optional(db)
+        .await
+}
+
+async fn require_execution(
+    db: &PgPool,
+    source: ProposalSource,
+    proposal_id: Uuid,
+) -> ApiResult<ProposalExecution> {
+    fetch_execution(db, source, proposal_id)
+        .await
+        .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("execution", "Proposal has not been queued"))
+}
+
+/// Queue a passed proposal. Idempotent: a proposal already in the queue
+/// keeps its original ETA.
+pub async fn queue(
+    db: &PgPool,
+    source: ProposalSource,
+    proposal_id: Uuid,
+    contract_id: Option<Uuid>,
+    action: Option<&ProposalAction>,
+    eta: DateTime<Utc>,
+) -> Result<ProposalExecution, sqlx::Error> {
+    let inserted: Option<ProposalExecution> = sqlx::query_as(
+        r#"
+        INSERT INTO proposal_executions (source, proposal_id, contract_id, action, eta)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (source, proposal_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(source)
+    .bind(proposal_id)
+    .bind(contract_id)
+    .bind(action.map(|a| serde_json::to_value(a).unwrap_or_default()))
+    .bind(eta)
+    .fetch_optional(db)
+    .await?;
+
+    match inserted {
+        Some(execution) => {
+            tracing::info!(%source, %proposal_id, eta = %execution.eta, "proposal queued");
+            Ok(execution)
+        }
+        None => fetch_execution(db, source, proposal_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound),
+    }
+}
+
+pub async fn cancel(
+    db: &PgPool,
+    source: ProposalSource,
+    proposal_id: Uuid,
+    req: &CancelExecutionRequest,
+) -> ApiResult<ProposalExecution> {
+    if req.cancelled_by.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "missing_cancelled_by",
+            "cancelled_by is required",
+        ));
+    }
+    let execution = require_execution(db, source, proposal_id).await?;
+    check_cancellable(&execution, Utc::now())?;
+
+    // Re-check the window in the UPDATE so a cancel racing the ETA loses
+    sqlx::query_as(
+        r#"
+        UPDATE proposal_executions
+        SET status = 'cancelled', cancelled_at = NOW(), cancelled_by = $2, cancel_reason = $3
+        WHERE id = $1 AND status = 'queued' AND eta > NOW()
+        RETURNING *
+        "#,
+    )
+    .bind(execution.id)
+    .bind(&req.cancelled_by)
+    .bind(&req.reason)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to cancel execution: {}", e)))?
+    .ok_or_else(|| ExecutionBlocked::CancellationWindowClosed { eta: execution.eta }.into())
+}
+
+// ─────────────────────────────────────────────────────────
+// Executor
+// ─────────────────────────────────────────────────────────
+
+/// Run a queued action whose timelock has expired. Failures are recorded
+/// on the execution rather than returned, so callers always get the updated
+/// record back; a failed execution can be retried within the grace period.
+pub async fn execute(
+    state: &AppState,
+    source: ProposalSource,
+    proposal_id: Uuid,
+    executed_by: &str,
+) -> ApiResult<ProposalExecution> {
+    let execution = require_execution(&state.db, source, proposal_id).await?;
+    check_executable(&execution, Utc::now())?;
+
+    // Claim the execution so that concurrent callers cannot apply it twice
+    let claimed: Option<ProposalExecution> = sqlx::query_as(
+        "UPDATE proposal_executions SET status = 'executing'
+         WHERE id = $1 AND status IN ('queued', 'failed')
+         RETURNING *",
+    )
+    .bind(execution.id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Database error: {}", e)))?;
+    if claimed.is_none() {
+        return Err(ExecutionBlocked::Status(ProposalExecutionStatus::Executing).into());
+    }
+
+    let actor = format!("{}:{}", source, proposal_id);
+    let action = parse_action(execution.action.as_ref())
+        .map_err(|e| ApiError::internal(format!("Stored proposal action is invalid: {}", e)));
+
+    let mut audit_log_id = None;
+    let outcome = match (execution.contract_id, action) {
+        (_, Err(e)) => Err(e),
+        // Decision-only proposal: nothing to apply
+        (_, Ok(None)) => Ok(json!({ "applied": false })),
+        (None, Ok(Some(_))) => Err(ApiError::unprocessable(
+            "contract_not_registered",
+            "Proposal targets a contract that is not in the registry",
+        )),
+        (Some(contract_id), Ok(Some(action))) => {
+            let before = contract_row(&state.db, contract_id).await.ok();
+            let outcome = apply_action(state, contract_id, &action, &actor).await;
+
+            // Successful actions snapshot the contract as it is now; failed
+            // ones changed nothing, so only the attempt is recorded.
+            let logged = match &outcome {
+                Ok(_) => {
+                    let after = contract_row(&state.db, contract_id).await.ok();
+                    log_contract_change(
+                        &state.db,
+                        contract_id,
+                        AuditActionType::ProposalExecuted,
+                        before,
+                        after,
+                        &actor,
+                    )
+                    .await
+                }
+                Err(e) => {
+                    log_contract_change(
+                        &state.db,
+                        contract_id,
+                        AuditActionType::ProposalExecutionFailed,
+                        Some(json!({ "action": action, "error": e.message() })),
+                        None,
+                        &actor,
+                    )
+                    .await
+                }
+            };
+            match logged {
+                Ok(id) => audit_log_id = Some(id),
+                Err(e) => {
+                    tracing::warn!(%proposal_id, error = %e, "failed to audit proposal execution")
+                }
+            }
+            outcome
+        }
+    };
+
+    let (status, result, error) = match outcome {
+        Ok(result) => (ProposalExecutionStatus::Executed, Some(result), None),
+        Err(e) => (
+            ProposalExecutionStatus::Failed,
+            None,
+            Some(e.message().to_string()),
+        ),
+    };
+
+    let execution: ProposalExecution = sqlx::query_as(
+        r#"
+        UPDATE proposal_executions
+        SET status = $2, executed_at = NOW(), executed_by = $3, result = $4, error = $5,
+            audit_log_id = COALESCE($6, audit_log_id)
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(execution.id)
+    .bind(status)
+    .bind(executed_by)
+    .bind(&result)
+    .bind(&error)
+    .bind(audit_log_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(format!("Failed to record execution: {}", e)))?;
+
+    tracing::info!(%source, %proposal_id, status = ?execution.status, "proposal execution finished");
+    Ok(execution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::MaturityLevel;
+
+    fn queued(eta: DateTime<Utc>) -> ProposalExecution {
+        ProposalExecution {
+            id: Uuid::new_v4(),
+            source: ProposalSource::Governance,
+            proposal_id: Uuid::new_v4(),
+            contract_id: Some(Uuid::new_v4()),
+            action: None,
+            status: ProposalExecutionStatus::Queued,
+            queued_at: eta - Duration::hours(48),
+            eta,
+            cancelled_at: None,
+            cancelled_by: None,
+            cancel_reason: None,
+            executed_at: None,
+            executed_by: None,
+            result: None,
+            error: None,
+            audit_log_id: None,
+        }
+    }
+
+    #[test]
+    fn eta_ignores_negative_delays() {
+        let now = Utc::now();
+        assert_eq!(eta(now, Some(48)), now + Duration::hours(48));
+        assert_eq!(eta(now, Some(-5)), now);
+        assert_eq!(eta(now, None), now);
+    }
+
+    #[test]
+    fn timelock_splits_cancellation_and_execution() {
+        let now = Utc::now();
+        let execution = queued(now + Duration::hours(1));
+
+        assert_eq!(check_cancellable(&execution, now), Ok(()));
+        assert_eq!(
+            check_executable(&execution, now),
+            Err(ExecutionBlocked::TimelockActive { eta: execution.eta })
+        );
+
+        let later = now + Duration::hours(2);
+        assert_eq!(check_executable(&execution, later), Ok(()));
+        assert_eq!(
+            check_cancellable(&execution, later),
+            Err(ExecutionBlocked::CancellationWindowClosed { eta: execution.eta })
+        );
+    }
+
+    #[test]
+    fn executions_go_stale_after_grace_period() {
+        let now = Utc::now();
+        let execution = queued(now - Duration::days(GRACE_PERIOD_DAYS + 1));
+        assert!(matches!(
+            check_executable(&execution, now),
+            Err(ExecutionBlocked::Stale { .. })
+        ));
+    }
+
+    #[test]
+    fn failed_executions_can_be_retried_but_not_cancelled() {
+        let now = Utc::now();
+        let mut execution = queued(now - Duration::hours(1));
+        execution.status = ProposalExecutionStatus::Failed;
+        assert_eq!(check_executable(&execution, now), Ok(()));
+        assert_eq!(
+            check_cancellable(&execution, now - Duration::hours(2)),
+            Err(ExecutionBlocked::Status(ProposalExecutionStatus::Failed))
+        );
+
+        execution.status = ProposalExecutionStatus::Executed;
+        assert_eq!(
+            check_executable(&execution, now),
+            Err(ExecutionBlocked::Status(ProposalExecutionStatus::Executed))
+        );
+    }
+
+    #[test]
+    fn validates_actions_and_round_trips_payloads() {
+        let upgrade = ProposalAction::UpgradeWasm {
+            wasm_hash: "ab".repeat(32),
+            version: Some("1.2.0".into()),
+        };
+        assert_eq!(validate_action(&upgrade), Ok(()));
+        assert!(validate_action(&ProposalAction::UpgradeWasm {
+            wasm_hash: "xyz".into(),
+            version: None,
+        })
+        .is_err());
+        assert!(validate_action(&ProposalAction::SetConfigVersion {
+            environment: "mainnet".into(),
+            version: 0,
+        })
+        .is_err());
+
+        let value = json!({ "kind": "change_maturity", "maturity": "Stable", "reason": null });
+        assert_eq!(
+            parse_action(Some(&value)).unwrap(),
+            Some(ProposalAction::ChangeMaturity {
+                maturity: MaturityLevel::Stable,
+                reason: None,
+            })
+        );
+        assert_eq!(parse_action(Some(&serde_json::Value::Null)).unwrap(), None);
+        assert_eq!(
+            serde_json::to_value(&upgrade).unwrap()["kind"],
+            json!("upgrade_wasm")
+        );
+    }
+}
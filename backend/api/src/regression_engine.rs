@@ -6,10 +6,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
-use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::benchmark_engine::{BenchmarkRunner, BenchmarkStats, IterationResult};
+use crate::benchmark_engine::BenchmarkRunner;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "test_status", rename_all = "lowercase")]
@@ -21,7 +20,7 @@ pub enum TestStatus {
     Skipped,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "regression_severity", rename_all = "lowercase")]
 pub enum RegressionSeverity {
     None,
@@ -30,7 +29,7 @@ pub enum RegressionSeverity {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TestBaseline {
     pub id: Uuid,
     pub contract_id: Uuid,
@@ -45,7 +44,7 @@ pub struct TestBaseline {
     pub established_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TestRun {
     pub id: Uuid,
     pub contract_id: Uuid,
@@ -68,7 +67,7 @@ pub struct TestRun {
     pub triggered_by: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TestSuite {
     pub id: Uuid,
     pub contract_id: Uuid,
@@ -79,6 +78,43 @@ pub struct TestSuite {
     pub auto_run_on_deploy: bool,
 }
 
+impl RegressionSeverity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "minor" => Some(Self::Minor),
+            "major" => Some(Self::Major),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// One behavioural difference between the old and new WASM found by a snapshot replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFinding {
+    /// return_value | error | events | storage_writes | resources
+    pub kind: String,
+    pub severity: String,
+    pub message: String,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+/// Outcome of replaying one invocation against both WASM versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayInvocation {
+    pub step_name: String,
+    pub function_name: String,
+    pub old_output: serde_json::Value,
+    pub new_output: serde_json::Value,
+    pub old_cpu_instructions: Option<i64>,
+    pub new_cpu_instructions: Option<i64>,
+    pub new_memory_bytes: Option<i64>,
+    #[serde(default)]
+    pub findings: Vec<ReplayFinding>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RegressionThresholds {
     pub performance_degradation_minor: f64,    // e.g., 10%
@@ -424,6 +460,87 @@ impl RegressionEngine {
         )
     }
 
+    /// Record the result of replaying recorded ledger state against an old and
+    /// a new WASM version. Each invocation becomes a test run; any finding marks
+    /// the run as a regression, which raises an alert through the DB trigger.
+    pub async fn record_replay_run(
+        &self,
+        contract_id: Uuid,
+        old_version: &str,
+        new_version: &str,
+        suite_name: &str,
+        triggered_by: &str,
+        invocation: &ReplayInvocation,
+    ) -> Result<TestRun, sqlx::Error> {
+        let severity = invocation
+            .findings
+            .iter()
+            .filter_map(|f| RegressionSeverity::parse(&f.severity))
+            .max()
+            .unwrap_or(RegressionSeverity::None);
+        let regression_detected = !invocation.findings.is_empty();
+        let status = if regression_detected {
+            TestStatus::Failed
+        } else {
+            TestStatus::Passed
+        };
+        let output_matches = !invocation
+            .findings
+            .iter()
+            .any(|f| matches!(f.kind.as_str(), "return_value" | "error" | "storage_writes"));
+        let degradation = match (invocation.old_cpu_instructions, invocation.new_cpu_instructions) {
+            (Some(old), Some(new)) if old > 0 => {
+                Some(((new - old) as f64 / old as f64) * 100.0)
+            }
+            _ => None,
+        };
+        let output = serde_json::json!({
+            "step_name": invocation.step_name,
+            "old_version": old_version,
+            "old_output": invocation.old_output,
+            "new_output": invocation.new_output,
+            "findings": invocation.findings,
+        });
+        let now = Utc::now();
+
+        let test_run: TestRun = sqlx::query_as(
+            r#"INSERT INTO regression_test_runs (
+                contract_id, version, test_suite_name, function_name, status,
+                cpu_instructions, memory_bytes, output_data, output_hash,
+                output_matches_baseline, regression_detected, regression_severity,
+                performance_degradation_percent, started_at, completed_at,
+                duration_seconds, triggered_by, test_environment
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14, 0, $15, 'snapshot_replay')
+            RETURNING 
+                id, contract_id, version, baseline_id, test_suite_name,
+                function_name, status as "status: TestStatus", 
+                execution_time_ms, memory_bytes, output_data, output_hash,
+                output_matches_baseline, regression_detected,
+                regression_severity as "regression_severity: RegressionSeverity",
+                performance_degradation_percent, started_at, completed_at,
+                error_message, triggered_by"#,
+        )
+        .bind(contract_id)
+        .bind(new_version)
+        .bind(suite_name)
+        .bind(&invocation.function_name)
+        .bind(&status)
+        .bind(invocation.new_cpu_instructions)
+        .bind(invocation.new_memory_bytes)
+        .bind(&output)
+        .bind(Self::hash_output(&invocation.new_output))
+        .bind(output_matches)
+        .bind(regression_detected)
+        .bind(&severity)
+        .bind(degradation)
+        .bind(now)
+        .bind(triggered_by)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(test_run)
+    }
+
     /// Hash output for comparison
     fn hash_output(output: &serde_json::Value) -> String {
         let serialized = serde_json::to_string(output).unwrap_or_default();
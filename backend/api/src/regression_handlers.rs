@@ -8,14 +8,26 @@ use axum::{
 };
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use shared::{LedgerEntryRecord, LedgerSnapshotResponse};
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
-    regression_engine::{RegressionEngine, RegressionStatistics, TestBaseline, TestRun, TestSuite},
+    regression_engine::{
+        RegressionEngine, RegressionStatistics, ReplayInvocation, TestBaseline, TestRun, TestSuite,
+    },
     state::AppState,
 };
 
+fn internal_err(code: &str, err: impl std::fmt::Display) -> ApiError {
+    tracing::error!(code, error = %err, "regression request failed");
+    ApiError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        code,
+        "Regression testing request failed",
+    )
+}
+
 // ─────────────────────────────────────────────────────────
 // Request/Response types
 // ─────────────────────────────────────────────────────────
@@ -61,6 +73,15 @@ pub struct StatisticsQuery {
     pub days: Option<i64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RecordReplayRequest {
+    pub old_version: String,
+    pub new_version: String,
+    pub suite_name: String,
+    pub triggered_by: Option<String>,
+    pub invocations: Vec<ReplayInvocation>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TestRunSummary {
     pub total_runs: usize,
@@ -70,7 +91,7 @@ pub struct TestRunSummary {
     pub runs: Vec<TestRun>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, sqlx::FromRow)]
 pub struct RegressionAlert {
     pub id: Uuid,
     pub test_run_id: Uuid,
@@ -104,7 +125,7 @@ pub async fn establish_baseline(
         .bind(contract_uuid)
         .fetch_one(&state.db)
         .await
-        .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+        .map_err(|e| internal_err("DatabaseError", e))?;
 
     let engine = RegressionEngine::new(state.db.clone());
 
@@ -118,7 +139,7 @@ pub async fn establish_baseline(
             req.established_by,
         )
         .await
-        .map_err(|e| ApiError::internal_server_error("BaselineCreationFailed", e.to_string()))?;
+        .map_err(|e| internal_err("BaselineCreationFailed", e))?;
 
     Ok(Json(baseline))
 }
@@ -154,7 +175,7 @@ pub async fn run_regression_test(
             deployment_uuid,
         )
         .await
-        .map_err(|e| ApiError::internal_server_error("TestExecutionFailed", e.to_string()))?;
+        .map_err(|e| internal_err("TestExecutionFailed", e))?;
 
     Ok(Json(test_run))
 }
@@ -189,7 +210,7 @@ pub async fn run_test_suite(
             deployment_uuid,
         )
         .await
-        .map_err(|e| ApiError::internal_server_error("SuiteExecutionFailed", e.to_string()))?;
+        .map_err(|e| internal_err("SuiteExecutionFailed", e))?;
 
     let total_runs = runs.len();
     let passed = runs.iter().filter(|r| matches!(r.status, crate::regression_engine::TestStatus::Passed)).count();
@@ -231,7 +252,7 @@ pub async fn get_test_runs(
     .bind(contract_uuid)
     .fetch_all(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
 
     Ok(Json(runs))
 }
@@ -259,7 +280,7 @@ pub async fn get_baselines(
     .bind(contract_uuid)
     .fetch_all(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
 
     Ok(Json(baselines))
 }
@@ -287,7 +308,7 @@ pub async fn get_alerts(
     .bind(contract_uuid)
     .fetch_all(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
 
     Ok(Json(alerts))
 }
@@ -299,6 +320,9 @@ pub async fn acknowledge_alert(
     Path((contract_id, alert_id)): Path<(String, String)>,
     Json(body): Json<serde_json::Value>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    let contract_uuid = Uuid::parse_str(&contract_id).map_err(|_| {
+        ApiError::bad_request("InvalidContractId", "Invalid contract ID format")
+    })?;
     let alert_uuid = Uuid::parse_str(&alert_id).map_err(|_| {
         ApiError::bad_request("InvalidAlertId", "Invalid alert ID format")
     })?;
@@ -308,16 +332,21 @@ pub async fn acknowledge_alert(
         .and_then(|v| v.as_str())
         .unwrap_or("unknown");
 
-    sqlx::query(
+    let result = sqlx::query(
         "UPDATE regression_alerts 
          SET acknowledged = TRUE, acknowledged_at = NOW(), acknowledged_by = $1
-         WHERE id = $2",
+         WHERE id = $2 AND contract_id = $3",
     )
     .bind(acknowledged_by)
     .bind(alert_uuid)
+    .bind(contract_uuid)
     .execute(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("AlertNotFound", "Regression alert not found"));
+    }
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -332,6 +361,9 @@ pub async fn resolve_alert(
     Path((contract_id, alert_id)): Path<(String, String)>,
     Json(body): Json<serde_json::Value>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    let contract_uuid = Uuid::parse_str(&contract_id).map_err(|_| {
+        ApiError::bad_request("InvalidContractId", "Invalid contract ID format")
+    })?;
     let alert_uuid = Uuid::parse_str(&alert_id).map_err(|_| {
         ApiError::bad_request("InvalidAlertId", "Invalid alert ID format")
     })?;
@@ -341,16 +373,21 @@ pub async fn resolve_alert(
         .and_then(|v| v.as_str())
         .unwrap_or("");
 
-    sqlx::query(
+    let result = sqlx::query(
         "UPDATE regression_alerts 
          SET resolved = TRUE, resolved_at = NOW(), resolution_notes = $1
-         WHERE id = $2",
+         WHERE id = $2 AND contract_id = $3",
     )
     .bind(resolution_notes)
     .bind(alert_uuid)
+    .bind(contract_uuid)
     .execute(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("AlertNotFound", "Regression alert not found"));
+    }
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -378,7 +415,7 @@ pub async fn get_statistics(
     let stats = engine
         .get_statistics(contract_uuid, period_start, period_end)
         .await
-        .map_err(|e| ApiError::internal_server_error("StatisticsCalculationFailed", e.to_string()))?;
+        .map_err(|e| internal_err("StatisticsCalculationFailed", e))?;
 
     Ok(Json(stats))
 }
@@ -411,7 +448,7 @@ pub async fn create_test_suite(
     .bind(&req.created_by)
     .fetch_one(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("SuiteCreationFailed", e.to_string()))?;
+    .map_err(|e| internal_err("SuiteCreationFailed", e))?;
 
     Ok(Json(suite))
 }
@@ -436,7 +473,122 @@ pub async fn get_test_suites(
     .bind(contract_uuid)
     .fetch_all(&state.db)
     .await
-    .map_err(|e| ApiError::internal_server_error("DatabaseError", e.to_string()))?;
+    .map_err(|e| internal_err("DatabaseError", e))?;
 
     Ok(Json(suites))
 }
+
+/// GET /api/contracts/:id/regression/ledger-snapshot
+/// Indexed instance/persistent/temporary entries for recording a replay fixture
+pub async fn get_ledger_snapshot(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+) -> ApiResult<Json<LedgerSnapshotResponse>> {
+    let contract_uuid = Uuid::parse_str(&contract_id).map_err(|_| {
+        ApiError::bad_request("InvalidContractId", "Invalid contract ID format")
+    })?;
+
+    let contract: Option<(String, String)> = sqlx::query_as(
+        "SELECT contract_id, network::text FROM contracts WHERE id = $1",
+    )
+    .bind(contract_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| internal_err("DatabaseError", e))?;
+
+    let (contract_address, network) =
+        contract.ok_or_else(|| ApiError::not_found("ContractNotFound", "Contract not found"))?;
+
+    let ledger: Option<(i32, i64, i32, String)> = sqlx::query_as(
+        r#"SELECT ledger_sequence, ledger_timestamp, protocol_version, network_passphrase
+        FROM indexer_ledger_state
+        WHERE network = $1::network_type"#,
+    )
+    .bind(&network)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| internal_err("DatabaseError", e))?;
+
+    let entries: Vec<LedgerEntryRecord> = sqlx::query_as(
+        r#"SELECT 
+            durability::text as durability, key_xdr, entry_xdr, key_display,
+            live_until_ledger, last_modified_ledger
+        FROM contract_ledger_entries
+        WHERE contract_id = $1 AND durability IN ('instance', 'persistent')
+        ORDER BY durability, key_xdr"#,
+    )
+    .bind(contract_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| internal_err("DatabaseError", e))?;
+
+    if entries.is_empty() {
+        return Err(ApiError::not_found(
+            "LedgerStateNotIndexed",
+            "No indexed ledger state for this contract",
+        ));
+    }
+
+    Ok(Json(LedgerSnapshotResponse {
+        contract_id: contract_uuid,
+        contract_address,
+        network,
+        ledger_sequence: ledger.as_ref().map(|l| l.0),
+        ledger_timestamp: ledger.as_ref().map(|l| l.1),
+        protocol_version: ledger.as_ref().map(|l| l.2),
+        network_passphrase: ledger.map(|l| l.3),
+        entries,
+    }))
+}
+
+/// POST /api/contracts/:id/regression/replays
+/// Record behavioural differences found by replaying a ledger snapshot
+/// against two WASM versions
+pub async fn record_replay(
+    State(state): State<AppState>,
+    Path(contract_id): Path<String>,
+    Json(req): Json<RecordReplayRequest>,
+) -> ApiResult<Json<TestRunSummary>> {
+    let contract_uuid = Uuid::parse_str(&contract_id).map_err(|_| {
+        ApiError::bad_request("InvalidContractId", "Invalid contract ID format")
+    })?;
+
+    if req.invocations.is_empty() {
+        return Err(ApiError::bad_request(
+            "EmptyReplay",
+            "Replay report must contain at least one invocation",
+        ));
+    }
+
+    let engine = RegressionEngine::new(state.db.clone());
+    let triggered_by = req.triggered_by.as_deref().unwrap_or("replay");
+
+    let mut runs = Vec::with_capacity(req.invocations.len());
+    for invocation in &req.invocations {
+        let run = engine
+            .record_replay_run(
+                contract_uuid,
+                &req.old_version,
+                &req.new_version,
+                &req.suite_name,
+                triggered_by,
+                invocation,
+            )
+            .await
+            .map_err(|e| internal_err("ReplayRecordingFailed", e))?;
+        runs.push(run);
+    }
+
+    let total_runs = runs.len();
+    let passed = runs.iter().filter(|r| matches!(r.status, crate::regression_engine::TestStatus::Passed)).count();
+    let failed = total_runs - passed;
+    let regressions_detected = runs.iter().filter(|r| r.regression_detected).count();
+
+    Ok(Json(TestRunSummary {
+        total_runs,
+        passed,
+        failed,
+        regressions_detected,
+        runs,
+    }))
+}
@@ -32,6 +32,15 @@ pub fn regression_routes() -> Router<AppState> {
             "/api/contracts/:id/regression/runs",
             get(regression_handlers::get_test_runs),
         )
+        // Snapshot replay
+        .route(
+            "/api/contracts/:id/regression/ledger-snapshot",
+            get(regression_handlers::get_ledger_snapshot),
+        )
+        .route(
+            "/api/contracts/:id/regression/replays",
+            post(regression_handlers::record_replay),
+        )
         // Test suites
         .route(
             "/api/contracts/:id/regression/suites",
@@ -0,0 +1,746 @@
+// api/src/residency_engine.rs
+//
+// Residency policy evaluation. A policy decides whether data of a given
+// classification may be placed in a region for a given action. Checks run
+// in a fixed order, and the first one that decides is reported so callers
+// can explain the outcome:
+//
+//   1. classification scope  — policies limited to e.g. `pii` skip other data
+//   2. exceptions            — approved, time-boxed allowances
+//   3. deny list             — `denied_regions`
+//   4. rules                 — expressions such as `deny US when classification = pii`,
+//                              first match wins
+//   5. allow list            — `allowed_regions`
+//   6. default               — denied
+//
+// Region lists accept region names, jurisdiction groups (`EU`, `US`) that
+// expand to regions, and prefix wildcards (`eu-*`). Across policies a
+// region is allowed only if no applicable policy denies it. Everything in
+// here is synchronous.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use shared::models::{ResidencyException, ResidencyPolicy};
+use uuid::Uuid;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Classifications and jurisdictions
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Classification {
+    Public,
+    Financial,
+    Pii,
+}
+
+impl Classification {
+    pub const ALL: [Classification; 3] = [Self::Public, Self::Financial, Self::Pii];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Financial => "financial",
+            Self::Pii => "pii",
+        }
+    }
+}
+
+impl FromStr for Classification {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "unknown data classification '{}' (expected public, financial or pii)",
+                    s
+                )
+            })
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Jurisdiction groups by upper-case name, e.g. `EU` → `eu-west-1, …`.
+#[derive(Debug, Clone, Default)]
+pub struct Jurisdictions(HashMap<String, Vec<String>>);
+
+impl Jurisdictions {
+    pub fn new(groups: impl IntoIterator<Item = (String, Vec<String>)>) -> Self {
+        Self(
+            groups
+                .into_iter()
+                .map(|(name, regions)| (name.to_ascii_uppercase(), regions))
+                .collect(),
+        )
+    }
+
+    pub fn is_group(&self, name: &str) -> bool {
+        self.0.contains_key(&name.to_ascii_uppercase())
+    }
+
+    /// Whether a list item (region, group or `prefix*`) covers `region`
+    pub fn matches(&self, item: &str, region: &str) -> bool {
+        if let Some(members) = self.0.get(&item.to_ascii_uppercase()) {
+            return members.iter().any(|m| pattern_matches(m, region));
+        }
+        pattern_matches(item, region)
+    }
+
+    /// The first item of `list` that covers `region`
+    pub fn find<'a>(&self, list: &'a [String], region: &str) -> Option<&'a str> {
+        list.iter()
+            .map(String::as_str)
+            .find(|item| self.matches(item, region))
+    }
+}
+
+fn pattern_matches(pattern: &str, region: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let region = region.trim().to_ascii_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => region.starts_with(prefix),
+        None => pattern == region,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Rule expressions
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Classification(Vec<Classification>),
+    Action(Vec<String>),
+}
+
+/// `allow|deny <regions> [when <condition> [and <condition>]…]`
+///
+/// `<regions>` is `any`, one item or `[item, …]`; a condition is
+/// `classification = pii`, `classification in [pii, financial]`,
+/// `action = backup_replica` or `action in […]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub effect: Effect,
+    /// `None` matches every region
+    regions: Option<Vec<String>>,
+    conditions: Vec<Condition>,
+    pub source: String,
+}
+
+fn tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in s.chars() {
+        if c.is_whitespace() || matches!(c, '[' | ']' | ',' | '=') {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct Tokens {
+    tokens: Vec<String>,
+    at: usize,
+}
+
+impl Tokens {
+    fn next(&mut self) -> Option<String> {
+        let t = self.tokens.get(self.at).cloned();
+        self.at += 1;
+        t
+    }
+
+    fn expect(&mut self, what: &str) -> Result<String, String> {
+        self.next().ok_or_else(|| format!("expected {}", what))
+    }
+
+    /// One value or a bracketed list of values
+    fn values(&mut self) -> Result<Vec<String>, String> {
+        let first = self.expect("a value or '['")?;
+        if first != "[" {
+            return Ok(vec![first]);
+        }
+        let mut values = Vec::new();
+        loop {
+            let t = self.expect("']'")?;
+            match t.as_str() {
+                "]" => break,
+                "," => continue,
+                "[" | "=" => return Err(format!("unexpected '{}' in list", t)),
+                _ => values.push(t),
+            }
+        }
+        if values.is_empty() {
+            return Err("empty list".into());
+        }
+        Ok(values)
+    }
+}
+
+pub fn parse_rule(source: &str) -> Result<Rule, String> {
+    let err = |e: String| format!("invalid rule '{}': {}", source, e);
+    let mut tokens = Tokens {
+        tokens: tokenize(source),
+        at: 0,
+    };
+
+    let word = tokens.expect("allow or deny").map_err(err)?;
+    let effect = match word.to_lowercase().as_str() {
+        "allow" => Effect::Allow,
+        "deny" => Effect::Deny,
+        other => return Err(err(format!("expected allow or deny, found '{}'", other))),
+    };
+    let regions = tokens.values().map_err(err)?;
+    let regions = if regions.len() == 1 && regions[0].eq_ignore_ascii_case("any") {
+        None
+    } else {
+        Some(regions)
+    };
+
+    let mut conditions = Vec::new();
+    if let Some(t) = tokens.next() {
+        if !t.eq_ignore_ascii_case("when") {
+            return Err(err(format!("expected 'when', found '{}'", t)));
+        }
+        loop {
+            let field = tokens.expect("classification or action").map_err(err)?;
+            let op = tokens.expect("'=' or 'in'").map_err(err)?;
+            let values = match op.to_lowercase().as_str() {
+                "=" => vec![tokens.expect("a value").map_err(err)?],
+                "in" => tokens.values().map_err(err)?,
+                other => return Err(err(format!("expected '=' or 'in', found '{}'", other))),
+            };
+            conditions.push(match field.to_lowercase().as_str() {
+                "classification" => Condition::Classification(
+                    values
+                        .iter()
+                        .map(|v| v.parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(err)?,
+                ),
+                "action" => Condition::Action(values),
+                other => {
+                    return Err(err(format!(
+                        "unknown field '{}' (expected classification or action)",
+                        other
+                    )))
+                }
+            });
+            match tokens.next() {
+                None => break,
+                Some(t) if t.eq_ignore_ascii_case("and") => continue,
+                Some(t) => return Err(err(format!("expected 'and', found '{}'", t))),
+            }
+        }
+    }
+
+    Ok(Rule {
+        effect,
+        regions,
+        conditions,
+        source: source.trim().to_string(),
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A hypothetical or real placement of data.
+#[derive(Debug, Clone)]
+pub struct Placement<'a> {
+    pub region: &'a str,
+    /// `None` when the data is unclassified; every policy then applies
+    pub classification: Option<Classification>,
+    pub action: &'a str,
+    pub at: DateTime<Utc>,
+}
+
+impl Rule {
+    fn matches(&self, groups: &Jurisdictions, p: &Placement) -> bool {
+        let region_ok = match &self.regions {
+            None => true,
+            Some(list) => groups.find(list, p.region).is_some(),
+        };
+        region_ok
+            && self.conditions.iter().all(|c| match c {
+                // Unclassified data could be anything, so it matches any
+                // classification condition
+                Condition::Classification(list) => match p.classification {
+                    Some(class) => list.contains(&class),
+                    None => true,
+                },
+                Condition::Action(list) => list.iter().any(|a| a.eq_ignore_ascii_case(p.action)),
+            })
+    }
+}
+
+/// A policy with its rules parsed.
+#[derive(Debug, Clone)]
+pub struct CompiledPolicy {
+    pub policy: ResidencyPolicy,
+    classifications: Vec<Classification>,
+    rules: Vec<Rule>,
+}
+
+impl CompiledPolicy {
+    pub fn compile(policy: ResidencyPolicy) -> Result<Self, String> {
+        let classifications = policy
+            .classifications
+            .iter()
+            .map(|c| c.parse())
+            .collect::<Result<_, _>>()?;
+        let rules = policy
+            .rules
+            .iter()
+            .map(|r| parse_rule(r))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            policy,
+            classifications,
+            rules,
+        })
+    }
+}
+
+/// Which check decided a policy's verdict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecidedBy {
+    NotApplicable,
+    Exception { id: Uuid, approved_by: String },
+    DenyList { entry: String },
+    Rule { index: usize, source: String },
+    AllowList { entry: String },
+    Default,
+}
+
+impl DecidedBy {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotApplicable => "not_applicable",
+            Self::Exception { .. } => "exception",
+            Self::DenyList { .. } => "deny_list",
+            Self::Rule { .. } => "rule",
+            Self::AllowList { .. } => "allowed_regions",
+            Self::Default => "default",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    pub policy_id: Uuid,
+    pub applies: bool,
+    pub allowed: bool,
+    pub decided_by: DecidedBy,
+    pub explanation: String,
+}
+
+fn exception_covers(e: &ResidencyException, groups: &Jurisdictions, p: &Placement) -> bool {
+    let class_ok = match (&e.classification, p.classification) {
+        (None, _) => true,
+        (Some(c), Some(class)) => c.eq_ignore_ascii_case(class.as_str()),
+        // An exception for one class cannot cover data of unknown class
+        (Some(_), None) => false,
+    };
+    let action_ok = match &e.action {
+        None => true,
+        Some(a) => a.eq_ignore_ascii_case(p.action),
+    };
+    e.revoked_at.is_none()
+        && e.starts_at <= p.at
+        && p.at < e.expires_at
+        && groups.matches(&e.region, p.region)
+        && class_ok
+        && action_ok
+}
+
+pub fn evaluate(
+    compiled: &CompiledPolicy,
+    exceptions: &[ResidencyException],
+    groups: &Jurisdictions,
+    p: &Placement,
+) -> Verdict {
+    let policy = &compiled.policy;
+    let verdict =
+        |applies: bool, allowed: bool, decided_by: DecidedBy, explanation: String| Verdict {
+            policy_id: policy.id,
+            applies,
+            allowed,
+            decided_by,
+            explanation,
+        };
+
+    if let Some(class) = p.classification {
+        if !compiled.classifications.is_empty() && !compiled.classifications.contains(&class) {
+            return verdict(
+                false,
+                true,
+                DecidedBy::NotApplicable,
+                format!("Policy does not cover {} data", class),
+            );
+        }
+    }
+
+    if let Some(e) = exceptions
+        .iter()
+        .find(|e| e.policy_id == policy.id && exception_covers(e, groups, p))
+    {
+        return verdict(
+            true,
+            true,
+            DecidedBy::Exception {
+                id: e.id,
+                approved_by: e.approved_by.clone(),
+            },
+            format!(
+                "Region '{}' is allowed by an exception approved by {} until {}",
+                p.region,
+                e.approved_by,
+                e.expires_at.to_rfc3339()
+            ),
+        );
+    }
+
+    if let Some(entry) = groups.find(&policy.denied_regions, p.region) {
+        return verdict(
+            true,
+            false,
+            DecidedBy::DenyList {
+                entry: entry.to_string(),
+            },
+            format!("Region '{}' is on the deny list ('{}')", p.region, entry),
+        );
+    }
+
+    if let Some((index, rule)) = compiled
+        .rules
+        .iter()
+        .enumerate()
+        .find(|(_, r)| r.matches(groups, p))
+    {
+        let allowed = rule.effect == Effect::Allow;
+        return verdict(
+            true,
+            allowed,
+            DecidedBy::Rule {
+                index,
+                source: rule.source.clone(),
+            },
+            format!(
+                "Region '{}' is {} by rule {}: {}",
+                p.region,
+                if allowed { "allowed" } else { "denied" },
+                index + 1,
+                rule.source
+            ),
+        );
+    }
+
+    if let Some(entry) = groups.find(&policy.allowed_regions, p.region) {
+        return verdict(
+            true,
+            true,
+            DecidedBy::AllowList {
+                entry: entry.to_string(),
+            },
+            format!("Region '{}' is permitted by policy ('{}')", p.region, entry),
+        );
+    }
+
+    verdict(
+        true,
+        false,
+        DecidedBy::Default,
+        format!(
+            "Region '{}' is not in the allowed list: {:?}",
+            p.region, policy.allowed_regions
+        ),
+    )
+}
+
+/// Everything needed to evaluate a contract's active policies.
+#[derive(Debug, Clone, Default)]
+pub struct ResidencyContext {
+    pub policies: Vec<CompiledPolicy>,
+    pub exceptions: Vec<ResidencyException>,
+    pub groups: Jurisdictions,
+}
+
+impl ResidencyContext {
+    pub fn evaluate(&self, p: &Placement) -> Vec<Verdict> {
+        self.policies
+            .iter()
+            .map(|c| evaluate(c, &self.exceptions, &self.groups, p))
+            .collect()
+    }
+
+    /// Allowed unless an applicable policy denies it
+    pub fn permits(&self, p: &Placement) -> bool {
+        self.evaluate(p).iter().all(|v| v.allowed)
+    }
+}
+
+/// The verdict that settles a combined decision: the first denial, else the
+/// first applicable allowance. `None` when no policy applies.
+pub fn deciding(verdicts: &[Verdict]) -> Option<&Verdict> {
+    verdicts
+        .iter()
+        .find(|v| !v.allowed)
+        .or_else(|| verdicts.iter().find(|v| v.applies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn groups() -> Jurisdictions {
+        Jurisdictions::new([
+            (
+                "eu".to_string(),
+                vec!["eu-west-1".to_string(), "eu-central-1".to_string()],
+            ),
+            ("US".to_string(), vec!["us-*".to_string()]),
+        ])
+    }
+
+    fn policy(
+        allowed: &[&str],
+        denied: &[&str],
+        rules: &[&str],
+        classes: &[&str],
+    ) -> CompiledPolicy {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        CompiledPolicy::compile(ResidencyPolicy {
+            id: Uuid::new_v4(),
+            contract_id: "CABC".into(),
+            allowed_regions: strings(allowed),
+            description: None,
+            is_active: true,
+            created_by: "GADMIN".into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            classifications: strings(classes),
+            denied_regions: strings(denied),
+            rules: strings(rules),
+        })
+        .unwrap()
+    }
+
+    fn at<'a>(region: &'a str, class: Option<Classification>, action: &'a str) -> Placement<'a> {
+        Placement {
+            region,
+            classification: class,
+            action,
+            at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn parses_rule_expressions() {
+        let rule = parse_rule(
+            "deny [US, ap-*] when classification in [pii, financial] and action = export",
+        )
+        .unwrap();
+        assert_eq!(rule.effect, Effect::Deny);
+        assert_eq!(
+            rule.regions,
+            Some(vec!["US".to_string(), "ap-*".to_string()])
+        );
+        assert_eq!(rule.conditions.len(), 2);
+
+        assert_eq!(parse_rule("allow any").unwrap().regions, None);
+        assert!(parse_rule("permit EU").is_err());
+        assert!(parse_rule("deny EU when colour = red").is_err());
+        assert!(parse_rule("deny EU when classification = secret").is_err());
+        assert!(parse_rule("deny [EU").is_err());
+    }
+
+    #[test]
+    fn groups_and_wildcards_expand() {
+        let g = groups();
+        assert!(g.matches("EU", "eu-central-1"));
+        assert!(!g.matches("EU", "eu-west-2"));
+        assert!(g.matches("us", "us-west-2"));
+        assert!(g.matches("ap-*", "ap-south-1"));
+        assert!(!g.matches("eu-west-1", "eu-west-10"));
+    }
+
+    #[test]
+    fn explains_which_check_decided() {
+        let g = groups();
+        let p = policy(
+            &["EU", "US"],
+            &["us-east-2"],
+            &["deny US when classification = pii"],
+            &[],
+        );
+
+        let v = evaluate(
+            &p,
+            &[],
+            &g,
+            &at("eu-west-1", Some(Classification::Pii), "store"),
+        );
+        assert!(v.allowed);
+        assert_eq!(v.decided_by, DecidedBy::AllowList { entry: "EU".into() });
+
+        let v = evaluate(
+            &p,
+            &[],
+            &g,
+            &at("us-west-2", Some(Classification::Pii), "store"),
+        );
+        assert!(!v.allowed);
+        assert!(matches!(v.decided_by, DecidedBy::Rule { index: 0, .. }));
+
+        let v = evaluate(
+            &p,
+            &[],
+            &g,
+            &at("us-west-2", Some(Classification::Public), "store"),
+        );
+        assert!(v.allowed);
+
+        let v = evaluate(
+            &p,
+            &[],
+            &g,
+            &at("us-east-2", Some(Classification::Public), "store"),
+        );
+        assert_eq!(
+            v.decided_by,
+            DecidedBy::DenyList {
+                entry: "us-east-2".into()
+            }
+        );
+
+        let v = evaluate(&p, &[], &g, &at("sa-east-1", None, "store"));
+        assert_eq!(v.decided_by, DecidedBy::Default);
+    }
+
+    #[test]
+    fn classification_scope_and_unclassified_data() {
+        let g = groups();
+        let p = policy(&["EU"], &[], &[], &["pii"]);
+
+        let public = evaluate(
+            &p,
+            &[],
+            &g,
+            &at("us-east-1", Some(Classification::Public), "store"),
+        );
+        assert!(!public.applies);
+        assert!(public.allowed);
+
+        // Unclassified data is held to every policy
+        let unknown = evaluate(&p, &[], &g, &at("us-east-1", None, "store"));
+        assert!(unknown.applies);
+        assert!(!unknown.allowed);
+    }
+
+    #[test]
+    fn exceptions_are_time_boxed() {
+        let g = groups();
+        let p = policy(&["EU"], &["US"], &[], &[]);
+        let now = Utc::now();
+        let exception = ResidencyException {
+            id: Uuid::new_v4(),
+            policy_id: p.policy.id,
+            region: "us-east-1".into(),
+            classification: Some("financial".into()),
+            action: None,
+            reason: "audit".into(),
+            approved_by: "GCOMPLIANCE".into(),
+            starts_at: now - Duration::hours(1),
+            expires_at: now + Duration::hours(1),
+            revoked_at: None,
+            created_at: now,
+        };
+        let exceptions = vec![exception.clone()];
+
+        let v = evaluate(
+            &p,
+            &exceptions,
+            &g,
+            &at("us-east-1", Some(Classification::Financial), "store"),
+        );
+        assert!(v.allowed);
+        assert!(matches!(v.decided_by, DecidedBy::Exception { .. }));
+
+        let other_class = evaluate(
+            &p,
+            &exceptions,
+            &g,
+            &at("us-east-1", Some(Classification::Pii), "store"),
+        );
+        assert!(!other_class.allowed);
+
+        let mut later = at("us-east-1", Some(Classification::Financial), "store");
+        later.at = now + Duration::hours(2);
+        assert!(!evaluate(&p, &exceptions, &g, &later).allowed);
+
+        let revoked = vec![ResidencyException {
+            revoked_at: Some(now),
+            ..exception
+        }];
+        assert!(
+            !evaluate(
+                &p,
+                &revoked,
+                &g,
+                &at("us-east-1", Some(Classification::Financial), "store")
+            )
+            .allowed
+        );
+    }
+
+    #[test]
+    fn first_denial_decides_across_policies() {
+        let g = groups();
+        let ctx = ResidencyContext {
+            policies: vec![
+                policy(&["EU", "US"], &[], &[], &[]),
+                policy(&["EU"], &[], &[], &[]),
+            ],
+            exceptions: vec![],
+            groups: g,
+        };
+        let p = at("us-east-1", None, "store");
+        let verdicts = ctx.evaluate(&p);
+        assert!(!ctx.permits(&p));
+        assert_eq!(
+            deciding(&verdicts).unwrap().policy_id,
+            ctx.policies[1].policy.id
+        );
+        assert!(ctx.permits(&at("eu-west-1", None, "store")));
+    }
+}
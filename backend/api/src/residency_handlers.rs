@@ -3,19 +3,29 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use chrono::{Duration, Utc};
 use serde::Deserialize;
+use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
     error::{ApiError, ApiResult},
+    residency_engine::{
+        self, Classification, CompiledPolicy, Jurisdictions, Placement, ResidencyContext, Verdict,
+    },
     state::AppState,
 };
 use shared::models::{
-    CheckResidencyRequest, CreateResidencyPolicyRequest, ListResidencyLogsParams,
-    ResidencyAuditLog, ResidencyDecision, ResidencyPolicy, ResidencyViolation,
-    UpdateResidencyPolicyRequest,
+    CheckResidencyRequest, CreateResidencyExceptionRequest, CreateResidencyPolicyRequest,
+    ListResidencyLogsParams, ResidencyAuditLog, ResidencyDecision, ResidencyException,
+    ResidencyJurisdiction, ResidencyPolicy, ResidencyPolicyEvaluation, ResidencySimulation,
+    ResidencyViolation, SimulateResidencyRequest, UpdateResidencyPolicyRequest,
+    UpsertJurisdictionRequest,
 };
 
+/// Longest an exception may run before it has to be approved again
+const MAX_EXCEPTION_DAYS: i64 = 90;
+
 fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
     tracing::error!(context = ctx, error = %err, "database error");
     ApiError::internal(format!("Database error during: {}", ctx))
@@ -25,6 +35,127 @@ fn not_found(id: Uuid) -> ApiError {
     ApiError::not_found("PolicyNotFound", format!("No residency policy found with ID: {}", id))
 }
 
+fn parse_classification(value: Option<&str>) -> ApiResult<Option<Classification>> {
+    value
+        .map(|c| c.parse())
+        .transpose()
+        .map_err(|e: String| ApiError::bad_request("InvalidClassification", e))
+}
+
+/// Reject policies whose classifications or rules do not parse, or that
+/// could never allow anything
+fn validate_policy(policy: ResidencyPolicy) -> ApiResult<()> {
+    if policy.allowed_regions.is_empty() && policy.rules.is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingRegions",
+            "A policy needs allowed_regions or rules",
+        ));
+    }
+    CompiledPolicy::compile(policy)
+        .map(|_| ())
+        .map_err(|e| ApiError::bad_request("InvalidPolicy", e))
+}
+
+async fn load_jurisdictions(db: &PgPool) -> ApiResult<Jurisdictions> {
+    let rows: Vec<ResidencyJurisdiction> = sqlx::query_as("SELECT * FROM residency_jurisdictions")
+        .fetch_all(db)
+        .await
+        .map_err(|e| db_err("load jurisdictions", e))?;
+    Ok(Jurisdictions::new(rows.into_iter().map(|j| (j.name, j.regions))))
+}
+
+/// The active policies for a contract, with their unrevoked exceptions and
+/// the jurisdiction groups they may refer to
+pub async fn load_context(db: &PgPool, contract_id: &str) -> ApiResult<ResidencyContext> {
+    let policies: Vec<ResidencyPolicy> = sqlx::query_as(
+        "SELECT * FROM residency_policies WHERE contract_id = $1 AND is_active = TRUE ORDER BY created_at",
+    )
+    .bind(contract_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| db_err("load residency policies", e))?;
+
+    let ids: Vec<Uuid> = policies.iter().map(|p| p.id).collect();
+    let exceptions: Vec<ResidencyException> = sqlx::query_as(
+        "SELECT * FROM residency_exceptions WHERE policy_id = ANY($1) AND revoked_at IS NULL",
+    )
+    .bind(&ids)
+    .fetch_all(db)
+    .await
+    .map_err(|e| db_err("load residency exceptions", e))?;
+
+    let policies = policies
+        .into_iter()
+        .map(CompiledPolicy::compile)
+        .collect::<Result<_, _>>()
+        .map_err(|e| ApiError::internal(format!("Stored residency policy is invalid: {}", e)))?;
+
+    Ok(ResidencyContext {
+        policies,
+        exceptions,
+        groups: load_jurisdictions(db).await?,
+    })
+}
+
+/// Write a verdict to the audit log, and to the violations when it denies
+#[allow(clippy::too_many_arguments)]
+pub async fn record_verdict(
+    db: &PgPool,
+    verdict: &Verdict,
+    contract_id: &str,
+    region: &str,
+    action: &str,
+    requested_by: Option<&str>,
+    classification: Option<Classification>,
+    reason: &str,
+) -> ApiResult<()> {
+    let decision = if verdict.allowed { ResidencyDecision::Allowed } else { ResidencyDecision::Denied };
+
+    sqlx::query(
+        "INSERT INTO residency_audit_logs
+             (policy_id, contract_id, requested_region, decision, action, requested_by, reason,
+              classification, decided_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(verdict.policy_id)
+    .bind(contract_id)
+    .bind(region)
+    .bind(&decision)
+    .bind(action)
+    .bind(requested_by)
+    .bind(reason)
+    .bind(classification.map(Classification::as_str))
+    .bind(verdict.decided_by.kind())
+    .execute(db)
+    .await
+    .map_err(|e| db_err("insert residency audit log", e))?;
+
+    if !verdict.allowed {
+        sqlx::query(
+            "INSERT INTO residency_violations
+                 (policy_id, contract_id, attempted_region, action, attempted_by)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(verdict.policy_id)
+        .bind(contract_id)
+        .bind(region)
+        .bind(action)
+        .bind(requested_by)
+        .execute(db)
+        .await
+        .map_err(|e| db_err("insert residency violation", e))?;
+
+        tracing::warn!(
+            contract_id = %contract_id,
+            policy_id   = %verdict.policy_id,
+            region      = %region,
+            decided_by  = verdict.decided_by.kind(),
+            "residency violation detected and prevented"
+        );
+    }
+    Ok(())
+}
+
 async fn fetch_policy(state: &AppState, id: Uuid) -> ApiResult<ResidencyPolicy> {
     sqlx::query_as("SELECT * FROM residency_policies WHERE id = $1")
         .bind(id)
@@ -43,21 +174,35 @@ pub async fn create_policy(
     if req.contract_id.is_empty() {
         return Err(ApiError::bad_request("MissingContractId", "contract_id is required"));
     }
-    if req.allowed_regions.is_empty() {
-        return Err(ApiError::bad_request("MissingRegions", "allowed_regions must not be empty"));
-    }
     if req.created_by.is_empty() {
         return Err(ApiError::bad_request("MissingCreatedBy", "created_by is required"));
     }
+    validate_policy(ResidencyPolicy {
+        id: Uuid::nil(),
+        contract_id: req.contract_id.clone(),
+        allowed_regions: req.allowed_regions.clone(),
+        description: None,
+        is_active: true,
+        created_by: req.created_by.clone(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        classifications: req.classifications.clone(),
+        denied_regions: req.denied_regions.clone(),
+        rules: req.rules.clone(),
+    })?;
 
     let policy: ResidencyPolicy = sqlx::query_as(
-        "INSERT INTO residency_policies (contract_id, allowed_regions, description, created_by)
-         VALUES ($1, $2, $3, $4) RETURNING *",
+        "INSERT INTO residency_policies
+             (contract_id, allowed_regions, description, created_by, classifications, denied_regions, rules)
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
     )
     .bind(&req.contract_id)
     .bind(&req.allowed_regions)
     .bind(&req.description)
     .bind(&req.created_by)
+    .bind(&req.classifications)
+    .bind(&req.denied_regions)
+    .bind(&req.rules)
     .fetch_one(&state.db)
     .await
     .map_err(|e| db_err("create residency policy", e))?;
@@ -91,17 +236,23 @@ pub async fn update_policy(
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateResidencyPolicyRequest>,
 ) -> ApiResult<Json<ResidencyPolicy>> {
-    if let Some(ref regions) = req.allowed_regions {
-        if regions.is_empty() {
-            return Err(ApiError::bad_request("MissingRegions", "allowed_regions must not be empty"));
-        }
-    }
+    let current = fetch_policy(&state, id).await?;
+    validate_policy(ResidencyPolicy {
+        allowed_regions: req.allowed_regions.clone().unwrap_or(current.allowed_regions),
+        classifications: req.classifications.clone().unwrap_or(current.classifications),
+        denied_regions: req.denied_regions.clone().unwrap_or(current.denied_regions),
+        rules: req.rules.clone().unwrap_or(current.rules),
+        ..current
+    })?;
 
     let policy: ResidencyPolicy = sqlx::query_as(
         "UPDATE residency_policies
          SET allowed_regions = COALESCE($1, allowed_regions),
              description     = COALESCE($2, description),
              is_active       = COALESCE($3, is_active),
+             classifications = COALESCE($5, classifications),
+             denied_regions  = COALESCE($6, denied_regions),
+             rules           = COALESCE($7, rules),
              updated_at      = NOW()
          WHERE id = $4
          RETURNING *",
@@ -110,6 +261,9 @@ pub async fn update_policy(
     .bind(&req.description)
     .bind(req.is_active)
     .bind(id)
+    .bind(&req.classifications)
+    .bind(&req.denied_regions)
+    .bind(&req.rules)
     .fetch_one(&state.db)
     .await
     .map_err(|e| match e {
@@ -132,64 +286,279 @@ pub async fn check_residency(
         return Err(ApiError::bad_request("PolicyInactive", "The referenced residency policy is not active"));
     }
 
-    let is_allowed = policy.allowed_regions.iter().any(|r| r.eq_ignore_ascii_case(&req.requested_region));
-    let decision = if is_allowed { ResidencyDecision::Allowed } else { ResidencyDecision::Denied };
-    let reason = if is_allowed {
-        format!("Region '{}' is permitted by policy", req.requested_region)
-    } else {
-        format!(
-            "Region '{}' is not in the allowed list: {:?}",
-            req.requested_region, policy.allowed_regions
-        )
+    let classification = parse_classification(req.classification.as_deref())?;
+    let compiled = CompiledPolicy::compile(policy)
+        .map_err(|e| ApiError::internal(format!("Stored residency policy is invalid: {}", e)))?;
+    let exceptions: Vec<ResidencyException> = sqlx::query_as(
+        "SELECT * FROM residency_exceptions WHERE policy_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(compiled.policy.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("load residency exceptions", e))?;
+    let groups = load_jurisdictions(&state.db).await?;
+
+    let verdict = residency_engine::evaluate(
+        &compiled,
+        &exceptions,
+        &groups,
+        &Placement {
+            region: &req.requested_region,
+            classification,
+            action: &req.action,
+            at: Utc::now(),
+        },
+    );
+    let decision = if verdict.allowed { ResidencyDecision::Allowed } else { ResidencyDecision::Denied };
+
+    record_verdict(
+        &state.db,
+        &verdict,
+        &req.contract_id,
+        &req.requested_region,
+        &req.action,
+        req.requested_by.as_deref(),
+        classification,
+        &verdict.explanation,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "decision":         decision,
+        "reason":           verdict.explanation,
+        "decided_by":       verdict.decided_by.kind(),
+        "contract_id":      req.contract_id,
+        "policy_id":        compiled.policy.id,
+        "requested_region": req.requested_region,
+        "classification":   classification.map(Classification::as_str),
+        "allowed_regions":  compiled.policy.allowed_regions,
+    })))
+}
+
+fn evaluation(policy: &ResidencyPolicy, verdict: &Verdict) -> ResidencyPolicyEvaluation {
+    use residency_engine::DecidedBy;
+
+    let (matched, exception_id) = match &verdict.decided_by {
+        DecidedBy::Rule { source, .. } => (Some(source.clone()), None),
+        DecidedBy::DenyList { entry } | DecidedBy::AllowList { entry } => (Some(entry.clone()), None),
+        DecidedBy::Exception { id, .. } => (None, Some(*id)),
+        DecidedBy::NotApplicable | DecidedBy::Default => (None, None),
     };
+    ResidencyPolicyEvaluation {
+        policy_id: policy.id,
+        description: policy.description.clone(),
+        applies: verdict.applies,
+        decision: if verdict.allowed { ResidencyDecision::Allowed } else { ResidencyDecision::Denied },
+        decided_by: verdict.decided_by.kind().to_string(),
+        matched,
+        exception_id,
+        explanation: verdict.explanation.clone(),
+    }
+}
 
-    sqlx::query(
-        "INSERT INTO residency_audit_logs
-             (policy_id, contract_id, requested_region, decision, action, requested_by, reason)
-         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+/// Evaluate a hypothetical action against every active policy of the
+/// contract. Nothing is logged.
+pub async fn simulate_residency(
+    State(state): State<AppState>,
+    Json(req): Json<SimulateResidencyRequest>,
+) -> ApiResult<Json<ResidencySimulation>> {
+    if req.contract_id.is_empty() || req.region.is_empty() {
+        return Err(ApiError::bad_request("MissingField", "contract_id and region are required"));
+    }
+    let classification = parse_classification(req.classification.as_deref())?;
+    let at = req.at.unwrap_or_else(Utc::now);
+
+    let ctx = load_context(&state.db, &req.contract_id).await?;
+    let verdicts = ctx.evaluate(&Placement {
+        region: &req.region,
+        classification,
+        action: &req.action,
+        at,
+    });
+    let deciding = residency_engine::deciding(&verdicts);
+
+    let allowed = verdicts.iter().all(|v| v.allowed);
+    let explanation = match deciding {
+        Some(v) => v.explanation.clone(),
+        None => format!(
+            "No active policy for {} covers this data; region '{}' is allowed",
+            req.contract_id, req.region
+        ),
+    };
+
+    Ok(Json(ResidencySimulation {
+        evaluations: ctx
+            .policies
+            .iter()
+            .zip(&verdicts)
+            .map(|(c, v)| evaluation(&c.policy, v))
+            .collect(),
+        contract_id: req.contract_id,
+        region: req.region,
+        action: req.action,
+        classification: classification.map(|c| c.to_string()),
+        at,
+        decision: if allowed { ResidencyDecision::Allowed } else { ResidencyDecision::Denied },
+        deciding_policy_id: deciding.map(|v| v.policy_id),
+        explanation,
+    }))
+}
+
+pub async fn create_exception(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateResidencyExceptionRequest>,
+) -> ApiResult<(StatusCode, Json<ResidencyException>)> {
+    fetch_policy(&state, id).await?;
+
+    if req.region.is_empty() {
+        return Err(ApiError::bad_request("MissingRegion", "region is required"));
+    }
+    if req.approved_by.is_empty() {
+        return Err(ApiError::bad_request("MissingApprover", "approved_by is required"));
+    }
+    if req.reason.trim().is_empty() {
+        return Err(ApiError::bad_request("MissingReason", "reason is required"));
+    }
+    let classification = parse_classification(req.classification.as_deref())?;
+
+    let starts_at = req.starts_at.unwrap_or_else(Utc::now);
+    if req.expires_at <= starts_at || req.expires_at <= Utc::now() {
+        return Err(ApiError::bad_request(
+            "InvalidExpiry",
+            "expires_at must be in the future and after starts_at",
+        ));
+    }
+    if req.expires_at - starts_at > Duration::days(MAX_EXCEPTION_DAYS) {
+        return Err(ApiError::bad_request(
+            "ExceptionTooLong",
+            format!("Exceptions may run for at most {} days", MAX_EXCEPTION_DAYS),
+        ));
+    }
+
+    let exception: ResidencyException = sqlx::query_as(
+        "INSERT INTO residency_exceptions
+             (policy_id, region, classification, action, reason, approved_by, starts_at, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
     )
-    .bind(policy.id)
-    .bind(&req.contract_id)
-    .bind(&req.requested_region)
-    .bind(&decision)
+    .bind(id)
+    .bind(&req.region)
+    .bind(classification.map(Classification::as_str))
     .bind(&req.action)
-    .bind(&req.requested_by)
-    .bind(&reason)
-    .execute(&state.db)
+    .bind(&req.reason)
+    .bind(&req.approved_by)
+    .bind(starts_at)
+    .bind(req.expires_at)
+    .fetch_one(&state.db)
     .await
-    .map_err(|e| db_err("insert residency audit log", e))?;
+    .map_err(|e| db_err("create residency exception", e))?;
+
+    tracing::info!(
+        policy_id    = %id,
+        exception_id = %exception.id,
+        region       = %exception.region,
+        approved_by  = %exception.approved_by,
+        "residency exception granted"
+    );
 
-    if !is_allowed {
-        sqlx::query(
-            "INSERT INTO residency_violations
-                 (policy_id, contract_id, attempted_region, action, attempted_by)
-             VALUES ($1, $2, $3, $4, $5)",
-        )
-        .bind(policy.id)
-        .bind(&req.contract_id)
-        .bind(&req.requested_region)
-        .bind(&req.action)
-        .bind(&req.requested_by)
-        .execute(&state.db)
-        .await
-        .map_err(|e| db_err("insert residency violation", e))?;
+    Ok((StatusCode::CREATED, Json(exception)))
+}
 
-        tracing::warn!(
-            contract_id    = %req.contract_id,
-            policy_id      = %policy.id,
-            region         = %req.requested_region,
-            "residency violation detected and prevented"
-        );
+pub async fn list_exceptions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Vec<ResidencyException>>> {
+    fetch_policy(&state, id).await?;
+
+    let exceptions: Vec<ResidencyException> = sqlx::query_as(
+        "SELECT * FROM residency_exceptions WHERE policy_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list residency exceptions", e))?;
+
+    Ok(Json(exceptions))
+}
+
+pub async fn revoke_exception(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ResidencyException>> {
+    let exception: ResidencyException = sqlx::query_as(
+        "UPDATE residency_exceptions SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => ApiError::not_found(
+            "ExceptionNotFound",
+            format!("No residency exception found with ID: {}", id),
+        ),
+        _ => db_err("revoke residency exception", e),
+    })?;
+
+    tracing::info!(exception_id = %id, "residency exception revoked");
+
+    Ok(Json(exception))
+}
+
+pub async fn list_jurisdictions(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<ResidencyJurisdiction>>> {
+    let jurisdictions: Vec<ResidencyJurisdiction> =
+        sqlx::query_as("SELECT * FROM residency_jurisdictions ORDER BY name")
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| db_err("list jurisdictions", e))?;
+
+    Ok(Json(jurisdictions))
+}
+
+pub async fn upsert_jurisdiction(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<UpsertJurisdictionRequest>,
+) -> ApiResult<Json<ResidencyJurisdiction>> {
+    let name = name.to_ascii_uppercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ApiError::bad_request(
+            "InvalidJurisdiction",
+            "Jurisdiction names are letters, digits and underscores",
+        ));
+    }
+    if req.regions.is_empty() {
+        return Err(ApiError::bad_request("MissingRegions", "regions must not be empty"));
+    }
+    let groups = load_jurisdictions(&state.db).await?;
+    if let Some(nested) = req.regions.iter().find(|r| groups.is_group(r)) {
+        return Err(ApiError::bad_request(
+            "NestedJurisdiction",
+            format!("'{}' is a jurisdiction; list its regions instead", nested),
+        ));
     }
 
-    Ok(Json(serde_json::json!({
-        "decision":         decision,
-        "reason":           reason,
-        "contract_id":      req.contract_id,
-        "policy_id":        policy.id,
-        "requested_region": req.requested_region,
-        "allowed_regions":  policy.allowed_regions,
-    })))
+    let jurisdiction: ResidencyJurisdiction = sqlx::query_as(
+        "INSERT INTO residency_jurisdictions (name, regions, description)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (name) DO UPDATE
+         SET regions     = EXCLUDED.regions,
+             description = COALESCE(EXCLUDED.description, residency_jurisdictions.description),
+             updated_at  = NOW()
+         RETURNING *",
+    )
+    .bind(&name)
+    .bind(&req.regions)
+    .bind(&req.description)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("upsert jurisdiction", e))?;
+
+    tracing::info!(jurisdiction = %name, "residency jurisdiction updated");
+
+    Ok(Json(jurisdiction))
 }
 
 #[derive(Debug, Deserialize)]
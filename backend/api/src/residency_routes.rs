@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -15,7 +15,21 @@ pub fn residency_routes() -> Router<AppState> {
             "/api/residency/policies/:id",
             get(residency_handlers::get_policy).put(residency_handlers::update_policy),
         )
+        .route(
+            "/api/residency/policies/:id/exceptions",
+            post(residency_handlers::create_exception).get(residency_handlers::list_exceptions),
+        )
+        .route(
+            "/api/residency/exceptions/:id",
+            delete(residency_handlers::revoke_exception),
+        )
+        .route("/api/residency/jurisdictions", get(residency_handlers::list_jurisdictions))
+        .route(
+            "/api/residency/jurisdictions/:name",
+            put(residency_handlers::upsert_jurisdiction),
+        )
         .route("/api/residency/check", post(residency_handlers::check_residency))
+        .route("/api/residency/simulate", post(residency_handlers::simulate_residency))
         .route("/api/residency/logs", get(residency_handlers::get_audit_logs))
         .route("/api/residency/violations", get(residency_handlers::list_violations))
 }
@@ -3,9 +3,6 @@ use axum::{
     Router,
 };
 
-use crate::{handlers, metrics_handler, resource_handlers, state::AppState};
-use crate::{auth_handlers, handlers, metrics_handler, resource_handlers, state::AppState};
-use crate::{compatibility_handlers, handlers, metrics_handler, state::AppState};
 use crate::{handlers, state::AppState};
 
 pub fn observability_routes() -> Router<AppState> {
@@ -23,27 +20,9 @@ pub fn contract_routes() -> Router<AppState> {
         .route("/api/contracts/:id/versions", get(handlers::get_contract_versions))
         .route("/api/contracts/:id/state/:key", get(handlers::get_contract_state).post(handlers::update_contract_state))
         .route("/api/contracts/:id/analytics", get(handlers::get_contract_analytics))
-        .route("/api/contracts/:id/trust-score", get(handlers::get_trust_score))
         .route("/api/contracts/:id/dependencies", get(handlers::get_contract_dependencies))
         .route("/api/contracts/:id/dependents", get(handlers::get_contract_dependents))
         .route("/api/contracts/verify", post(handlers::verify_contract))
-        .route(
-            "/api/contracts/:id/state/:key",
-            get(handlers::get_contract_state).post(handlers::update_contract_state),
-        )
-        .route(
-            "/api/contracts/:id/performance",
-            get(handlers::get_contract_performance),
-        )
-        .route(
-            "/api/contracts/:id/compatibility",
-            get(compatibility_handlers::get_contract_compatibility)
-                .post(compatibility_handlers::add_contract_compatibility),
-        )
-        .route(
-            "/api/contracts/:id/compatibility/export",
-            get(compatibility_handlers::export_contract_compatibility),
-        )
         .route("/api/contracts/:id/deployments/status", get(handlers::get_deployment_status))
         .route("/api/deployments/green", post(handlers::deploy_green))
 }
@@ -60,4 +39,15 @@ pub fn health_routes() -> Router<AppState> {
 
 pub fn migration_routes() -> Router<AppState> {
     Router::new()
+        .route("/api/migrations", post(handlers::migrations::create_migration))
+        .route("/api/migrations", get(handlers::migrations::get_migrations))
+        .route("/api/migrations/plan", post(handlers::migrations::plan_migration))
+        .route("/api/migrations/:id", get(handlers::migrations::get_migration))
+        .route("/api/migrations/:id", put(handlers::migrations::update_migration))
+        .route("/api/migrations/:id/plan", get(handlers::migrations::get_plan))
+        .route("/api/migrations/:id/plan", put(handlers::migrations::attach_plan))
+        .route(
+            "/api/migrations/:id/steps/:position",
+            post(handlers::migrations::record_step),
+        )
 }
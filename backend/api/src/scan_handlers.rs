@@ -7,17 +7,14 @@ use axum::{
 use uuid::Uuid;
 
 use crate::state::AppState;
-use crate::scanner_service::{self, VulnerabilityPayload, ScanRequest};
+use crate::scanner_service::{self, AdvisoryInput, ScanRequest, ScannerError};
 
 pub async fn ingest_cves(
     State(state): State<AppState>,
-    Json(payload): Json<Vec<VulnerabilityPayload>>,
+    Json(payload): Json<Vec<AdvisoryInput>>,
 ) -> impl IntoResponse {
-    match scanner_service::sync_cves(&state.pool, payload).await {
-        Ok(count) => {
-            let msg = format!("Ingested {} CVEs successfully", count);
-            (StatusCode::OK, Json(msg)).into_response()
-        }
+    match scanner_service::sync_cves(&state.db, payload).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
         Err(e) => {
             let err = format!("Database error ingesting CVEs: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
@@ -25,13 +22,31 @@ pub async fn ingest_cves(
     }
 }
 
+pub async fn sync_local_advisories(State(state): State<AppState>) -> impl IntoResponse {
+    match scanner_service::sync_local(&state.db).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e @ ScannerError::Input(_)) => {
+            let err = format!("Failed to read advisories: {}", e);
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(err)).into_response()
+        }
+        Err(e) => {
+            let err = format!("Database error ingesting advisories: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
+        }
+    }
+}
+
 pub async fn scan_contract(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Json(payload): Json<ScanRequest>,
 ) -> impl IntoResponse {
-    match scanner_service::perform_scan(&state.pool, contract_id, payload).await {
+    match scanner_service::perform_scan(&state.db, contract_id, payload).await {
         Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e @ ScannerError::Input(_)) => {
+            let err = format!("Cannot scan contract: {}", e);
+            (StatusCode::BAD_REQUEST, Json(err)).into_response()
+        }
         Err(e) => {
             let err = format!("Failed to run contract scan: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
@@ -43,7 +58,7 @@ pub async fn get_scan_report(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
 ) -> impl IntoResponse {
-    match scanner_service::get_history(&state.pool, contract_id).await {
+    match scanner_service::get_history(&state.db, contract_id).await {
         Ok(report) => (StatusCode::OK, Json(report)).into_response(),
         Err(e) => {
             let err = format!("Failed to retrieve scan history: {}", e);
@@ -7,6 +7,7 @@ use crate::{scan_handlers, state::AppState};
 pub fn scan_routes() -> Router<AppState> {
     Router::new()
         .route("/api/vulnerabilities/sync", post(scan_handlers::ingest_cves))
+        .route("/api/vulnerabilities/sync/local", post(scan_handlers::sync_local_advisories))
         .route("/api/contracts/:id/scan", post(scan_handlers::scan_contract))
         .route("/api/contracts/:id/scan", get(scan_handlers::get_scan_report))
 }
@@ -1,32 +1,69 @@
-use chrono::{DateTime, Utc};
+use std::io::Read;
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
+use shared::{SemVer, SimilarContract};
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
+use crate::advisory_engine::{self, Advisory, AffectedPackage, OsvAdvisory, PackageAdvisory};
+use crate::cargo_lock;
+use crate::similarity_service;
+
+/// Directory or `.zip` archive of OSV advisories synced by `sync_local`
+pub const ADVISORY_SOURCE_ENV: &str = "ADVISORY_DB_PATH";
+/// How often the background task resyncs `ADVISORY_DB_PATH`
+const ADVISORY_SYNC_SECS: u64 = 24 * 60 * 60;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VulnerabilityPayload {
     pub cve_id: String,
     pub description: Option<String>,
     pub severity: String,
     pub package_name: String,
+    /// Requirements a fixed version satisfies, e.g. `>= 1.2.3` or `^0.9.5`
     pub patched_versions: Vec<String>,
 }
 
+/// An advisory posted to the sync endpoint: an OSV record, or the older
+/// flat payload.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum AdvisoryInput {
+    Osv(OsvAdvisory),
+    Legacy(VulnerabilityPayload),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DependencyDescriptor {
     pub package_name: String,
     pub version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// What to scan. A lockfile, from the request or an uploaded source
+/// archive, takes precedence over an explicit list. With neither, the
+/// contract's stored lockfile is used, then the one recorded with its
+/// verified build, then the dependencies from its last scan.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct ScanRequest {
+    #[serde(default)]
     pub dependencies: Vec<DependencyDescriptor>,
+    /// Contents of the contract's `Cargo.lock`
+    #[serde(default)]
+    pub cargo_lock: Option<String>,
+    /// Base64 `.zip` of the contract source; its top-most `Cargo.lock` is used
+    #[serde(default)]
+    pub source_archive: Option<String>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct ScanResultRow {
     pub cve_id: String,
+    pub aliases: Vec<String>,
     pub package_name: String,
+    /// `name@version` from the contract's crate down to the vulnerable one
+    pub dependency_path: Vec<String>,
     pub current_version: String,
     pub recommended_version: Option<String>,
     pub severity: String,
@@ -38,136 +75,604 @@ pub struct ScanReport {
     pub contract_id: Uuid,
     pub findings: Vec<ScanResultRow>,
     pub scanned_dependencies_count: usize,
+    /// Where the dependency graph came from: `request`, `archive`,
+    /// `stored`, `verification`, or `list` for an explicit list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency_source: Option<String>,
+    /// Dependencies whose version is not valid SemVer and was not matched
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unmatched_dependencies: Vec<DependencyDescriptor>,
+    /// Known-vulnerable contracts whose code this one carries: identical
+    /// builds, clones and forks
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub vulnerable_relatives: Vec<SimilarContract>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub ingested: usize,
+    pub withdrawn: usize,
+    /// Advisories with no crates.io package
+    pub skipped: usize,
+    /// Files in a local source that could not be parsed
+    pub invalid_files: Vec<String>,
+    /// Whether every scanned contract is being rescanned in the background
+    pub rescan_scheduled: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScannerError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// Unusable input: an unreadable advisory source, lockfile or archive
+    #[error("{0}")]
+    Input(String),
 }
 
-pub async fn sync_cves(pool: &PgPool, payloads: Vec<VulnerabilityPayload>) -> Result<usize, sqlx::Error> {
-    let mut count = 0;
+pub async fn sync_cves(pool: &PgPool, payloads: Vec<AdvisoryInput>) -> Result<SyncReport, sqlx::Error> {
+    let mut report = SyncReport::default();
     for payload in payloads {
-        sqlx::query!(
-            r#"
-            INSERT INTO cve_vulnerabilities (cve_id, description, severity, package_name, patched_versions, updated_at)
-            VALUES ($1, $2, $3, $4, $5, NOW())
-            ON CONFLICT (cve_id) DO UPDATE SET
-                description = EXCLUDED.description,
-                severity = EXCLUDED.severity,
-                package_name = EXCLUDED.package_name,
-                patched_versions = EXCLUDED.patched_versions,
-                updated_at = NOW()
-            "#,
-            payload.cve_id,
-            payload.description,
-            payload.severity,
-            payload.package_name,
-            &payload.patched_versions
+        match payload {
+            AdvisoryInput::Osv(osv) => {
+                let advisory = Advisory::from_osv(osv);
+                if advisory.packages.is_empty() {
+                    report.skipped += 1;
+                    continue;
+                }
+                if advisory.withdrawn.is_some() {
+                    report.withdrawn += 1;
+                }
+                store_advisory(pool, &advisory).await?;
+            }
+            AdvisoryInput::Legacy(payload) => store_legacy(pool, &payload).await?,
+        }
+        report.ingested += 1;
+    }
+    if report.ingested > 0 {
+        schedule_rescan(pool.clone());
+        report.rescan_scheduled = true;
+    }
+    Ok(report)
+}
+
+/// Sync every advisory in the directory or archive named by
+/// `ADVISORY_DB_PATH`.
+pub async fn sync_local(pool: &PgPool) -> Result<SyncReport, ScannerError> {
+    let path = std::env::var(ADVISORY_SOURCE_ENV)
+        .map_err(|_| ScannerError::Input(format!("{} is not set", ADVISORY_SOURCE_ENV)))?;
+    let (advisories, invalid_files) =
+        tokio::task::spawn_blocking(move || load_osv_source(Path::new(&path)))
+            .await
+            .map_err(|e| ScannerError::Input(e.to_string()))??;
+
+    let inputs = advisories.into_iter().map(AdvisoryInput::Osv).collect();
+    let mut report = sync_cves(pool, inputs).await?;
+    report.invalid_files = invalid_files;
+    Ok(report)
+}
+
+/// Resync `ADVISORY_DB_PATH` once a day. Each sync that ingests advisories
+/// schedules a rescan of every scanned contract. Disabled when the variable
+/// is unset; advisories can still be posted to the sync endpoint.
+pub fn spawn_advisory_sync(pool: PgPool) {
+    if std::env::var(ADVISORY_SOURCE_ENV).is_err() {
+        tracing::info!("{} is not set; periodic advisory sync disabled", ADVISORY_SOURCE_ENV);
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(ADVISORY_SYNC_SECS));
+        loop {
+            interval.tick().await;
+            match sync_local(&pool).await {
+                Ok(report) => tracing::info!(
+                    ingested = report.ingested,
+                    withdrawn = report.withdrawn,
+                    invalid_files = report.invalid_files.len(),
+                    "advisory sync finished"
+                ),
+                Err(e) => tracing::error!(error = %e, "advisory sync failed"),
+            }
+        }
+    });
+}
+
+/// Read OSV JSON files from a directory tree or a `.zip` archive (the
+/// format of OSV's bulk exports). Unparseable files are returned by name
+/// rather than failing the sync.
+pub fn load_osv_source(path: &Path) -> Result<(Vec<OsvAdvisory>, Vec<String>), ScannerError> {
+    let io_err = |e: std::io::Error| ScannerError::Input(format!("{}: {}", path.display(), e));
+    let mut advisories = Vec::new();
+    let mut invalid = Vec::new();
+    let mut push = |name: String, raw: &[u8]| match parse_osv_file(raw) {
+        Some(mut parsed) => advisories.append(&mut parsed),
+        None => invalid.push(name),
+    };
+
+    if path.is_dir() {
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(&dir).map_err(io_err)? {
+                let entry_path = entry.map_err(io_err)?.path();
+                if entry_path.is_dir() {
+                    pending.push(entry_path);
+                } else if entry_path.extension().is_some_and(|e| e == "json") {
+                    let raw = std::fs::read(&entry_path).map_err(io_err)?;
+                    push(entry_path.display().to_string(), &raw);
+                }
+            }
+        }
+    } else if path.extension().is_some_and(|e| e == "zip") {
+        let file = std::fs::File::open(path).map_err(io_err)?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| ScannerError::Input(e.to_string()))?;
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| ScannerError::Input(e.to_string()))?;
+            if !entry.is_file() || !entry.name().ends_with(".json") {
+                continue;
+            }
+            let mut raw = Vec::new();
+            entry.read_to_end(&mut raw).map_err(io_err)?;
+            push(entry.name().to_string(), &raw);
+        }
+    } else {
+        let raw = std::fs::read(path).map_err(io_err)?;
+        push(path.display().to_string(), &raw);
+    }
+    Ok((advisories, invalid))
+}
+
+/// A file holds one advisory or an array of them.
+fn parse_osv_file(raw: &[u8]) -> Option<Vec<OsvAdvisory>> {
+    serde_json::from_slice::<OsvAdvisory>(raw)
+        .map(|a| vec![a])
+        .or_else(|_| serde_json::from_slice::<Vec<OsvAdvisory>>(raw))
+        .ok()
+}
+
+async fn store_advisory(pool: &PgPool, advisory: &Advisory) -> Result<(), sqlx::Error> {
+    let affected = serde_json::to_value(&advisory.packages).unwrap_or_default();
+    sqlx::query(
+        r#"
+        INSERT INTO cve_vulnerabilities (
+            cve_id, description, severity, package_name, patched_versions,
+            aliases, affected, source, published_at, modified_at, withdrawn_at, updated_at
         )
-        .execute(pool)
-        .await?;
-        count += 1;
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 'osv', $8, $9, $10, NOW())
+        ON CONFLICT (cve_id) DO UPDATE SET
+            description = EXCLUDED.description,
+            severity = EXCLUDED.severity,
+            package_name = EXCLUDED.package_name,
+            patched_versions = EXCLUDED.patched_versions,
+            aliases = EXCLUDED.aliases,
+            affected = EXCLUDED.affected,
+            source = EXCLUDED.source,
+            published_at = EXCLUDED.published_at,
+            modified_at = EXCLUDED.modified_at,
+            withdrawn_at = EXCLUDED.withdrawn_at,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(&advisory.id)
+    .bind(&advisory.description)
+    .bind(&advisory.severity)
+    .bind(&advisory.packages[0].name)
+    .bind(advisory.fixed_versions())
+    .bind(&advisory.aliases)
+    .bind(affected)
+    .bind(advisory.published)
+    .bind(advisory.modified)
+    .bind(advisory.withdrawn)
+    .execute(pool)
+    .await?;
+
+    // A withdrawn advisory no longer applies to anything
+    if advisory.withdrawn.is_some() {
+        sqlx::query("DELETE FROM contract_scan_results WHERE cve_id = $1")
+            .bind(&advisory.id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn store_legacy(pool: &PgPool, payload: &VulnerabilityPayload) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO cve_vulnerabilities (cve_id, description, severity, package_name, patched_versions, updated_at)
+        VALUES ($1, $2, $3, $4, $5, NOW())
+        ON CONFLICT (cve_id) DO UPDATE SET
+            description = EXCLUDED.description,
+            severity = EXCLUDED.severity,
+            package_name = EXCLUDED.package_name,
+            patched_versions = EXCLUDED.patched_versions,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(&payload.cve_id)
+    .bind(&payload.description)
+    .bind(&payload.severity)
+    .bind(&payload.package_name)
+    .bind(&payload.patched_versions)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[derive(FromRow)]
+struct AdvisoryRow {
+    cve_id: String,
+    severity: String,
+    aliases: Vec<String>,
+    package_name: String,
+    patched_versions: Vec<String>,
+    affected: serde_json::Value,
+}
+
+impl AdvisoryRow {
+    /// The advisory as it applies to `package`. OSV records carry their
+    /// ranges; older records only list patched versions.
+    fn for_package(&self, package: &str) -> Option<PackageAdvisory> {
+        let packages: Vec<AffectedPackage> =
+            serde_json::from_value(self.affected.clone()).unwrap_or_default();
+        let affected = if packages.is_empty() {
+            (self.package_name == package)
+                .then(|| AffectedPackage::from_patched(package, &self.patched_versions))?
+        } else {
+            packages.into_iter().find(|p| p.name == package)?
+        };
+        Some(PackageAdvisory {
+            id: self.cve_id.clone(),
+            aliases: self.aliases.clone(),
+            severity: self.severity.clone(),
+            package: affected,
+        })
     }
-    Ok(count)
 }
 
-pub async fn perform_scan(pool: &PgPool, contract_id: Uuid, request: ScanRequest) -> Result<ScanReport, sqlx::Error> {
+/// Every live advisory for any of `packages`, fetched in one query.
+async fn advisories_for(pool: &PgPool, packages: &[String]) -> Result<Vec<AdvisoryRow>, sqlx::Error> {
+    // OSV records are listed before older ones so that, when both describe
+    // the same issue, the finding carries the OSV ID and ranges
+    sqlx::query_as(
+        r#"
+        SELECT cve_id, severity, aliases, package_name, patched_versions, affected
+        FROM cve_vulnerabilities
+        WHERE withdrawn_at IS NULL
+          AND (package_name = ANY($1)
+               OR EXISTS (SELECT 1 FROM jsonb_array_elements(affected) a WHERE a->>'name' = ANY($1)))
+        ORDER BY (source = 'osv') DESC, cve_id
+        "#,
+    )
+    .bind(packages)
+    .fetch_all(pool)
+    .await
+}
+
+/// A crate to check, with how the contract depends on it.
+struct ScannedDependency {
+    package_name: String,
+    version: String,
+    path: Vec<String>,
+    is_direct: bool,
+}
+
+impl ScannedDependency {
+    fn listed(dep: &DependencyDescriptor) -> Self {
+        ScannedDependency {
+            package_name: dep.package_name.clone(),
+            version: dep.version.clone(),
+            path: vec![format!("{}@{}", dep.package_name, dep.version)],
+            is_direct: true,
+        }
+    }
+}
+
+/// Registry crates from a lockfile. Workspace crates are the contract
+/// itself, and git crates are not covered by crates.io advisories.
+fn from_lockfile(raw: &str) -> Result<Vec<ScannedDependency>, ScannerError> {
+    let crates = cargo_lock::parse(raw).map_err(ScannerError::Input)?;
+    Ok(crates
+        .into_iter()
+        .filter(|c| c.is_registry())
+        .map(|c| ScannedDependency {
+            is_direct: c.is_direct(),
+            package_name: c.name,
+            version: c.version,
+            path: c.path,
+        })
+        .collect())
+}
+
+async fn save_lockfile(pool: &PgPool, contract_id: Uuid, content: &str, source: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO contract_lockfiles (contract_id, content, source, updated_at)
+        VALUES ($1, $2, $3, NOW())
+        ON CONFLICT (contract_id) DO UPDATE SET
+            content = EXCLUDED.content,
+            source = EXCLUDED.source,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(contract_id)
+    .bind(content)
+    .bind(source)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn resolve_dependencies(
+    pool: &PgPool,
+    contract_id: Uuid,
+    request: &ScanRequest,
+) -> Result<(Vec<ScannedDependency>, &'static str), ScannerError> {
+    let uploaded = match (&request.cargo_lock, &request.source_archive) {
+        (Some(lock), _) => Some((lock.clone(), "request")),
+        (None, Some(archive)) => {
+            let bytes = BASE64
+                .decode(archive.trim())
+                .map_err(|e| ScannerError::Input(format!("source_archive is not base64: {}", e)))?;
+            Some((cargo_lock::from_zip(&bytes).map_err(ScannerError::Input)?, "archive"))
+        }
+        (None, None) => None,
+    };
+    if let Some((lock, source)) = uploaded {
+        let deps = from_lockfile(&lock)?;
+        save_lockfile(pool, contract_id, &lock, source).await?;
+        return Ok((deps, source));
+    }
+    if !request.dependencies.is_empty() {
+        let deps = request.dependencies.iter().map(ScannedDependency::listed).collect();
+        return Ok((deps, "list"));
+    }
+
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT content FROM contract_lockfiles WHERE contract_id = $1")
+            .bind(contract_id)
+            .fetch_optional(pool)
+            .await?;
+    if let Some(lock) = stored {
+        return Ok((from_lockfile(&lock)?, "stored"));
+    }
+
+    // Reproducible builds record the lockfile they used
+    let verified: Option<String> = sqlx::query_scalar(
+        r#"
+        SELECT build_params->>'cargo_lock' FROM verifications
+        WHERE contract_id = $1 AND status = 'verified' AND build_params ? 'cargo_lock'
+        ORDER BY verified_at DESC NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(contract_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+    if let Some(lock) = verified {
+        let deps = from_lockfile(&lock)?;
+        save_lockfile(pool, contract_id, &lock, "verification").await?;
+        return Ok((deps, "verification"));
+    }
+
+    let previous: Vec<(String, String, Vec<String>, bool)> = sqlx::query_as(
+        "SELECT package_name, version, dependency_path, is_direct FROM contract_dependencies WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await?;
+    if previous.is_empty() {
+        return Err(ScannerError::Input(
+            "no dependencies to scan: send a Cargo.lock, a source archive or a dependency list".into(),
+        ));
+    }
+    let deps = previous
+        .into_iter()
+        .map(|(package_name, version, path, is_direct)| ScannedDependency {
+            path: if path.is_empty() { vec![format!("{}@{}", package_name, version)] } else { path },
+            package_name,
+            version,
+            is_direct,
+        })
+        .collect();
+    Ok((deps, "list"))
+}
+
+pub async fn perform_scan(pool: &PgPool, contract_id: Uuid, request: ScanRequest) -> Result<ScanReport, ScannerError> {
+    let (dependencies, dependency_source) = resolve_dependencies(pool, contract_id, &request).await?;
+
+    let mut names: Vec<String> = dependencies.iter().map(|d| d.package_name.clone()).collect();
+    names.sort();
+    names.dedup();
+    let advisories = advisories_for(pool, &names).await?;
+
     let mut findings = Vec::new();
+    let mut unmatched = Vec::new();
+    let mut tx = pool.begin().await?;
 
-    // Insert dependencies first
-    for dep in &request.dependencies {
-        sqlx::query!(
-            r#"
-            INSERT INTO contract_dependencies (contract_id, package_name, version)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (contract_id, package_name) DO UPDATE SET
-                version = EXCLUDED.version
-            "#,
-            contract_id,
-            dep.package_name,
-            dep.version
-        )
-        .execute(pool)
+    // The graph replaces whatever the last scan recorded
+    sqlx::query("DELETE FROM contract_dependencies WHERE contract_id = $1")
+        .bind(contract_id)
+        .execute(&mut *tx)
         .await?;
-        
-        // Match against vulnerabilities strictly to minimize false positives (<1%)
-        // This query matches if the vulnerability exists for the package and version is not in patched_versions
-        let cves = sqlx::query!(
+
+    for dep in &dependencies {
+        sqlx::query(
             r#"
-            SELECT cve_id, severity, patched_versions
-            FROM cve_vulnerabilities
-            WHERE package_name = $1
+            INSERT INTO contract_dependencies (contract_id, package_name, version, dependency_path, is_direct)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (contract_id, package_name, version) DO NOTHING
             "#,
-            dep.package_name
         )
-        .fetch_all(pool)
+        .bind(contract_id)
+        .bind(&dep.package_name)
+        .bind(&dep.version)
+        .bind(&dep.path)
+        .bind(dep.is_direct)
+        .execute(&mut *tx)
         .await?;
 
-        for cve in cves {
-            // Very simple semantic version match placeholder - if it's not patched, it's vulnerable.
-            // A rigid check to maintain <1% false positive. If no patch versions matching exactly, assume vulnerable
-            let is_patched = cve.patched_versions.contains(&dep.version);
-            if !is_patched {
-                let rec_version = cve.patched_versions.first().cloned();
-                
-                sqlx::query!(
-                    r#"
-                    INSERT INTO contract_scan_results (contract_id, cve_id, package_name, current_version, recommended_version)
-                    VALUES ($1, $2, $3, $4, $5)
-                    ON CONFLICT (contract_id, cve_id) DO UPDATE SET
-                        current_version = EXCLUDED.current_version,
-                        recommended_version = EXCLUDED.recommended_version
-                    "#,
-                    contract_id,
-                    cve.cve_id,
-                    dep.package_name,
-                    dep.version,
-                    rec_version
-                )
-                .execute(pool)
-                .await?;
-
-                findings.push(ScanResultRow {
-                    cve_id: cve.cve_id,
-                    severity: cve.severity,
-                    package_name: dep.package_name.clone(),
-                    current_version: dep.version.clone(),
-                    recommended_version: rec_version,
-                    is_false_positive: false,
-                });
-            }
+        // Without a parseable version there is nothing to compare ranges
+        // against; report it instead of guessing either way
+        let Some(version) = SemVer::parse(dep.version.trim()) else {
+            unmatched.push(DependencyDescriptor {
+                package_name: dep.package_name.clone(),
+                version: dep.version.clone(),
+            });
+            continue;
+        };
+        let applicable: Vec<PackageAdvisory> = advisories
+            .iter()
+            .filter_map(|row| row.for_package(&dep.package_name))
+            .collect();
+
+        for finding in advisory_engine::evaluate(&version, &applicable) {
+            let rec_version = finding.recommended.map(|v| v.to_string());
+
+            let is_false_positive: bool = sqlx::query_scalar(
+                r#"
+                INSERT INTO contract_scan_results (contract_id, cve_id, package_name, current_version, recommended_version, dependency_path)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (contract_id, cve_id, package_name, current_version) DO UPDATE SET
+                    recommended_version = EXCLUDED.recommended_version,
+                    dependency_path = EXCLUDED.dependency_path
+                RETURNING is_false_positive
+                "#,
+            )
+            .bind(contract_id)
+            .bind(&finding.id)
+            .bind(&dep.package_name)
+            .bind(&dep.version)
+            .bind(&rec_version)
+            .bind(&dep.path)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            findings.push(ScanResultRow {
+                cve_id: finding.id,
+                aliases: finding.aliases,
+                severity: finding.severity,
+                package_name: dep.package_name.clone(),
+                dependency_path: dep.path.clone(),
+                current_version: dep.version.clone(),
+                recommended_version: rec_version,
+                is_false_positive,
+            });
         }
     }
 
+    // Results from earlier scans that no longer apply, e.g. after an upgrade
+    let ids: Vec<&str> = findings.iter().map(|f| f.cve_id.as_str()).collect();
+    let packages: Vec<&str> = findings.iter().map(|f| f.package_name.as_str()).collect();
+    let versions: Vec<&str> = findings.iter().map(|f| f.current_version.as_str()).collect();
+    sqlx::query(
+        r#"
+        DELETE FROM contract_scan_results
+        WHERE contract_id = $1
+          AND (cve_id, package_name, current_version) NOT IN (
+              SELECT * FROM UNNEST($2::text[], $3::text[], $4::text[])
+          )
+        "#,
+    )
+    .bind(contract_id)
+    .bind(&ids)
+    .bind(&packages)
+    .bind(&versions)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
     Ok(ScanReport {
         contract_id,
         findings,
-        scanned_dependencies_count: request.dependencies.len(),
+        scanned_dependencies_count: dependencies.len(),
+        dependency_source: Some(dependency_source.to_string()),
+        unmatched_dependencies: unmatched,
+        vulnerable_relatives: similarity_service::vulnerable_relatives(pool, contract_id).await?,
     })
 }
 
+#[derive(Debug, Default, Serialize)]
+pub struct RescanSummary {
+    pub contracts: usize,
+    pub failed: usize,
+    pub findings: usize,
+}
+
+/// Rescan every contract that has been scanned before, using its stored
+/// lockfile or dependency list.
+pub async fn rescan_all(pool: &PgPool) -> Result<RescanSummary, sqlx::Error> {
+    let contracts: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT contract_id FROM contract_lockfiles
+        UNION
+        SELECT DISTINCT contract_id FROM contract_dependencies
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut summary = RescanSummary::default();
+    for contract_id in contracts {
+        match perform_scan(pool, contract_id, ScanRequest::default()).await {
+            Ok(report) => {
+                summary.contracts += 1;
+                summary.findings += report.findings.len();
+            }
+            Err(e) => {
+                summary.failed += 1;
+                tracing::warn!(contract_id = %contract_id, error = %e, "dependency rescan failed");
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Run `rescan_all` in the background after new advisories are synced.
+fn schedule_rescan(pool: PgPool) {
+    tokio::spawn(async move {
+        match rescan_all(&pool).await {
+            Ok(summary) => tracing::info!(
+                contracts = summary.contracts,
+                failed = summary.failed,
+                findings = summary.findings,
+                "rescanned dependencies after advisory sync"
+            ),
+            Err(e) => tracing::error!(error = %e, "dependency rescan after advisory sync failed"),
+        }
+    });
+}
+
 pub async fn get_history(pool: &PgPool, contract_id: Uuid) -> Result<ScanReport, sqlx::Error> {
-    let rows = sqlx::query_as!(
-        ScanResultRow,
+    let rows: Vec<ScanResultRow> = sqlx::query_as(
         r#"
-        SELECT s.cve_id, s.package_name, s.current_version, s.recommended_version, c.severity, s.is_false_positive
+        SELECT s.cve_id, c.aliases, s.package_name, s.dependency_path, s.current_version, s.recommended_version, c.severity, s.is_false_positive
         FROM contract_scan_results s
         JOIN cve_vulnerabilities c ON s.cve_id = c.cve_id
-        WHERE s.contract_id = $1
+        WHERE s.contract_id = $1 AND c.withdrawn_at IS NULL
         ORDER BY s.created_at DESC
         "#,
-        contract_id
     )
+    .bind(contract_id)
     .fetch_all(pool)
     .await?;
 
-    let dep_count = sqlx::query_scalar!(
-        r#"SELECT COUNT(*) FROM contract_dependencies WHERE contract_id = $1"#,
-        contract_id
-    )
-    .fetch_one(pool)
-    .await?
-    .unwrap_or(0);
+    let dep_count: i64 =
+        sqlx::query_scalar(r#"SELECT COUNT(*) FROM contract_dependencies WHERE contract_id = $1"#)
+            .bind(contract_id)
+            .fetch_one(pool)
+            .await?;
 
     Ok(ScanReport {
         contract_id,
         findings: rows,
         scanned_dependencies_count: dep_count as usize,
+        dependency_source: None,
+        unmatched_dependencies: Vec::new(),
+        vulnerable_relatives: similarity_service::vulnerable_relatives(pool, contract_id).await?,
     })
 }
@@ -0,0 +1,455 @@
+// api/src/search_engine.rs
+//
+// Query planning for ranked contract search. A raw query is split into
+// terms, each term is widened with its synonyms and the last one is matched
+// as a prefix, and the result becomes a `to_tsquery` expression built only
+// from alphanumeric words, so user input never reaches the tsquery parser.
+// Terms the index has never seen are corrected against the search
+// vocabulary within a small edit budget.
+//
+// The SQL produced here shares one parameter layout (see the SQL section),
+// so the hit, count and facet queries bind the same values in the same order.
+// Facet counts are disjunctive: each facet ignores its own filter, so picking
+// "testnet" still shows how many hits mainnet would have.
+//
+// Ranking: relevance (weighted text rank plus trigram name similarity) times
+// a bounded boost from popularity and trust:
+//
+//   relevance = 0.8 × ts_rank_cd(doc, q, 32) + 0.2 × similarity(name, q)
+//   boost     = 1 + 0.5 × pop / (pop + 50) + 0.5 × trust / 100
+//   score     = relevance × boost
+//
+// With no query every contract has relevance 1 and the boost alone orders
+// them. Everything in here is synchronous.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::namespace::edit_distance;
+
+/// Terms beyond this are ignored
+pub const MAX_TERMS: usize = 8;
+
+/// Longer words are not real terms and are dropped
+pub const MAX_TERM_LEN: usize = 48;
+
+pub const WEIGHT_TEXT: f64 = 0.8;
+pub const WEIGHT_NAME: f64 = 0.2;
+
+/// Largest share popularity can add to a score
+pub const POPULARITY_BOOST: f64 = 0.5;
+
+/// Popularity score at which half of the popularity boost is earned
+pub const POPULARITY_HALF: f64 = 50.0;
+
+/// Largest share trust can add to a score
+pub const TRUST_BOOST: f64 = 0.5;
+
+/// `ts_headline` options; `<mark>` is what clients look for
+pub const HIGHLIGHT_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MaxWords=24, MinWords=8, MaxFragments=2, FragmentDelimiter=\" … \"";
+
+/// Values of the `maturity_level` enum
+pub const MATURITY_LEVELS: [&str; 5] = ["alpha", "beta", "stable", "mature", "legacy"];
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Terms and synonyms
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Lowercased alphanumeric words of `raw`, in order, without duplicates.
+pub fn tokenize(raw: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    raw.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && w.chars().count() <= MAX_TERM_LEN)
+        .map(str::to_lowercase)
+        .filter(|w| seen.insert(w.clone()))
+        .take(MAX_TERMS)
+        .collect()
+}
+
+/// Synonym groups. Every member of a group stands in for every other, so
+/// `dex → [exchange, amm]` also widens "exchange" to "dex" and "amm".
+#[derive(Debug, Default)]
+pub struct Synonyms(HashMap<String, Vec<String>>);
+
+impl Synonyms {
+    pub fn new<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Vec<String>)>,
+    {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for (term, synonyms) in groups {
+            let mut members: Vec<String> = std::iter::once(term)
+                .chain(synonyms)
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect();
+            members.dedup();
+            for member in &members {
+                let alternatives = map.entry(member.clone()).or_default();
+                for other in members.iter().filter(|o| *o != member) {
+                    if !alternatives.contains(other) {
+                        alternatives.push(other.clone());
+                    }
+                }
+            }
+        }
+        Self(map)
+    }
+
+    pub fn alternatives(&self, term: &str) -> &[String] {
+        self.0.get(term).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// One term as a tsquery operand; multi-word synonyms become phrases.
+fn operand(phrase: &str, prefix: bool) -> Option<String> {
+    let words = tokenize(phrase);
+    match words.len() {
+        0 => None,
+        1 if prefix => Some(format!("{}:*", words[0])),
+        1 => Some(words[0].clone()),
+        _ => Some(format!("({})", words.join(" <-> "))),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Query plan
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    pub terms: Vec<String>,
+    /// Input for `to_tsquery('english', …)`; `None` when nothing searchable
+    /// is left
+    pub tsquery: Option<String>,
+    /// Lowercased raw query for trigram name matching
+    pub fuzzy: Option<String>,
+}
+
+/// Plan a query. The last term is matched as a prefix unless the query ends
+/// in whitespace, so results follow the user as they type.
+pub fn plan(raw: &str, synonyms: &Synonyms) -> QueryPlan {
+    let terms = tokenize(raw);
+    let trailing_space = raw.ends_with(char::is_whitespace);
+    let last = terms.len().saturating_sub(1);
+
+    let groups: Vec<String> = terms
+        .iter()
+        .enumerate()
+        .filter_map(|(i, term)| {
+            let prefix = i == last && !trailing_space;
+            let mut alts: Vec<String> = operand(term, prefix).into_iter().collect();
+            alts.extend(
+                synonyms
+                    .alternatives(term)
+                    .iter()
+                    .filter_map(|s| operand(s, false)),
+            );
+            match alts.len() {
+                0 => None,
+                1 => alts.pop(),
+                _ => Some(format!("({})", alts.join(" | "))),
+            }
+        })
+        .collect();
+
+    let fuzzy = raw.trim().to_lowercase();
+    QueryPlan {
+        terms,
+        tsquery: (!groups.is_empty()).then(|| groups.join(" & ")),
+        fuzzy: (!fuzzy.is_empty()).then_some(fuzzy),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Typo tolerance
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Edits allowed when correcting a term of `len` characters
+pub fn typo_budget(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+/// A vocabulary word close to a query term, with the number of contracts
+/// that use it
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub term: String,
+    pub word: String,
+    pub ndoc: i32,
+}
+
+/// Replace terms the vocabulary does not contain with their closest known
+/// word. Returns `None` when nothing changed. Ties go to the more common
+/// word.
+pub fn correct(terms: &[String], candidates: &[Candidate]) -> Option<Vec<String>> {
+    let mut changed = false;
+    let corrected = terms
+        .iter()
+        .map(|term| {
+            let near: Vec<&Candidate> = candidates.iter().filter(|c| &c.term == term).collect();
+            if near.iter().any(|c| &c.word == term) {
+                return term.clone();
+            }
+            let budget = typo_budget(term.chars().count());
+            let best = near
+                .into_iter()
+                .map(|c| (edit_distance(term, &c.word), c))
+                .filter(|(d, _)| *d <= budget)
+                .min_by(|(da, a), (db, b)| da.cmp(db).then(b.ndoc.cmp(&a.ndoc)));
+            match best {
+                Some((_, c)) => {
+                    changed = true;
+                    c.word.clone()
+                }
+                None => term.clone(),
+            }
+        })
+        .collect();
+    changed.then_some(corrected)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SQL
+// ─────────────────────────────────────────────────────────────────────────────
+
+// Positional parameters shared by every query from this module:
+//
+//   $1 tsquery text   $2 fuzzy text      $3 network     $4 category
+//   $5 maturity       $6 verified bool   $7 license     $8 tags text[]
+//
+// Each is NULL when unused. Hit queries add $9 limit and $10 offset.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facet {
+    Network,
+    Category,
+    Maturity,
+    Verified,
+    License,
+}
+
+impl Facet {
+    pub const ALL: [Facet; 5] = [
+        Facet::Network,
+        Facet::Category,
+        Facet::Maturity,
+        Facet::Verified,
+        Facet::License,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Facet::Network => "network",
+            Facet::Category => "category",
+            Facet::Maturity => "maturity",
+            Facet::Verified => "verified",
+            Facet::License => "license",
+        }
+    }
+
+    fn value_sql(self) -> &'static str {
+        match self {
+            Facet::Network => "c.network::text",
+            Facet::Category => "c.category",
+            Facet::Maturity => "c.maturity::text",
+            Facet::Verified => "c.is_verified::text",
+            Facet::License => "c.license",
+        }
+    }
+
+    fn filter_sql(self) -> &'static str {
+        match self {
+            Facet::Network => "($3::text IS NULL OR c.network::text = $3)",
+            Facet::Category => "($4::text IS NULL OR LOWER(c.category) = LOWER($4))",
+            Facet::Maturity => "($5::text IS NULL OR c.maturity::text = $5)",
+            Facet::Verified => "($6::bool IS NULL OR c.is_verified = $6)",
+            Facet::License => "($7::text IS NULL OR LOWER(c.license) = LOWER($7))",
+        }
+    }
+}
+
+const MATCH_SQL: &str = "(($1::text IS NULL AND $2::text IS NULL) \
+     OR c.search_document @@ to_tsquery('english', $1) \
+     OR LOWER(c.name) % $2)";
+
+const TAGS_SQL: &str = "($8::text[] IS NULL OR c.tags @> $8)";
+
+/// WHERE clause for matches under every filter except `skip`'s
+pub fn where_sql(skip: Option<Facet>) -> String {
+    let mut clauses = vec![MATCH_SQL, TAGS_SQL];
+    clauses.extend(
+        Facet::ALL
+            .iter()
+            .filter(|f| Some(**f) != skip)
+            .map(|f| f.filter_sql()),
+    );
+    clauses.join(" AND ")
+}
+
+/// Relevance × boost, as described at the top of this file
+pub fn score_sql() -> String {
+    format!(
+        "(CASE WHEN $1::text IS NULL AND $2::text IS NULL THEN 1.0 ELSE \
+           {WEIGHT_TEXT} * COALESCE(ts_rank_cd(c.search_document, to_tsquery('english', $1), 32), 0) \
+         + {WEIGHT_NAME} * COALESCE(similarity(LOWER(c.name), $2), 0) END) \
+         * (1 + {POPULARITY_BOOST} * GREATEST(c.popularity_score, 0) \
+                / (GREATEST(c.popularity_score, 0) + {POPULARITY_HALF}) \
+              + {TRUST_BOOST} * LEAST(GREATEST(c.trust_score, 0), 100) / 100.0)"
+    )
+}
+
+/// Ranked page of hits with highlights and the ABI functions that matched
+pub fn hits_sql() -> String {
+    format!(
+        "SELECT c.*, {score}::float8 AS score, \
+                c.popularity_score AS popularity, c.trust_score AS trust, \
+                CASE WHEN $1::text IS NULL THEN NULL ELSE \
+                  ts_headline('english', c.name, to_tsquery('english', $1), '{opts}') END AS name_highlight, \
+                CASE WHEN $1::text IS NULL OR c.description IS NULL THEN NULL ELSE \
+                  ts_headline('english', c.description, to_tsquery('english', $1), '{opts}') END \
+                  AS description_highlight, \
+                CASE WHEN $1::text IS NULL THEN ARRAY[]::text[] ELSE ARRAY( \
+                  SELECT f FROM unnest(c.abi_functions) AS f \
+                  WHERE to_tsvector('english', replace(f, '_', ' ')) @@ to_tsquery('english', $1)) \
+                END AS matched_functions \
+         FROM contracts c \
+         WHERE {filters} \
+         ORDER BY score DESC, c.created_at DESC \
+         LIMIT $9 OFFSET $10",
+        score = score_sql(),
+        opts = HIGHLIGHT_OPTIONS.replace('\'', "''"),
+        filters = where_sql(None),
+    )
+}
+
+pub fn count_sql() -> String {
+    format!("SELECT COUNT(*) FROM contracts c WHERE {}", where_sql(None))
+}
+
+/// `(facet, value, count)` rows, most common value first within each facet
+pub fn facets_sql() -> String {
+    let parts: Vec<String> = Facet::ALL
+        .iter()
+        .map(|f| {
+            format!(
+                "SELECT '{name}' AS facet, {value} AS value, COUNT(*) AS count \
+                 FROM contracts c WHERE {filters} AND {value} IS NOT NULL GROUP BY 2",
+                name = f.as_str(),
+                value = f.value_sql(),
+                filters = where_sql(Some(*f)),
+            )
+        })
+        .collect();
+    format!(
+        "SELECT facet, value, count FROM ({}) f ORDER BY facet, count DESC, value",
+        parts.join(" UNION ALL ")
+    )
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn synonyms() -> Synonyms {
+        Synonyms::new(vec![
+            (
+                "dex".to_string(),
+                vec!["exchange".to_string(), "amm".to_string()],
+            ),
+            ("oracle".to_string(), vec!["price feed".to_string()]),
+        ])
+    }
+
+    #[test]
+    fn tokenize_strips_operators_and_duplicates() {
+        assert_eq!(
+            tokenize("Token & (swap) | token:* !transfer_from"),
+            vec!["token", "swap", "transfer", "from"]
+        );
+        assert!(tokenize("&|!():*").is_empty());
+    }
+
+    #[test]
+    fn synonyms_work_in_both_directions() {
+        let s = synonyms();
+        assert_eq!(s.alternatives("dex"), ["exchange", "amm"]);
+        assert_eq!(s.alternatives("amm"), ["dex", "exchange"]);
+        assert!(s.alternatives("lending").is_empty());
+    }
+
+    #[test]
+    fn plan_expands_synonyms_and_prefixes_last_term() {
+        let p = plan("oracle dex", &synonyms());
+        assert_eq!(
+            p.tsquery.as_deref(),
+            Some("(oracle | (price <-> feed)) & (dex:* | exchange | amm)")
+        );
+        assert_eq!(p.fuzzy.as_deref(), Some("oracle dex"));
+
+        let typed = plan("dex ", &synonyms());
+        assert_eq!(typed.tsquery.as_deref(), Some("(dex | exchange | amm)"));
+
+        let empty = plan("  !! ", &synonyms());
+        assert_eq!(empty.tsquery, None);
+        assert_eq!(empty.fuzzy.as_deref(), Some("!!"));
+    }
+
+    #[test]
+    fn corrects_only_unknown_terms_within_budget() {
+        let cand = |term: &str, word: &str, ndoc| Candidate {
+            term: term.into(),
+            word: word.into(),
+            ndoc,
+        };
+        let terms = vec!["tokn".to_string(), "swap".to_string(), "lendng".to_string()];
+        let candidates = vec![
+            cand("tokn", "token", 40),
+            cand("tokn", "torn", 5),
+            cand("swap", "swap", 12),
+            cand("swap", "swam", 1),
+            cand("lendng", "landing", 3),
+        ];
+        // token and torn are both one edit from "tokn"; token is more common.
+        // "landing" is two edits away, over the budget for six letters
+        assert_eq!(
+            correct(&terms, &candidates),
+            Some(vec![
+                "token".to_string(),
+                "swap".to_string(),
+                "lendng".to_string()
+            ])
+        );
+        assert_eq!(correct(&["swap".to_string()], &candidates), None);
+        assert_eq!(typo_budget(3), 0);
+    }
+
+    #[test]
+    fn facets_ignore_their_own_filter() {
+        let sql = where_sql(Some(Facet::Network));
+        assert!(!sql.contains("c.network::text = $3"));
+        assert!(sql.contains("c.is_verified = $6"));
+        assert!(where_sql(None).contains("c.network::text = $3"));
+
+        let facets = facets_sql();
+        for f in Facet::ALL {
+            assert!(facets.contains(&format!("'{}' AS facet", f.as_str())));
+        }
+    }
+
+    #[test]
+    fn hit_query_escapes_highlight_options() {
+        let sql = hits_sql();
+        assert!(sql.contains("StartSel=<mark>"));
+        assert!(sql.contains("LIMIT $9 OFFSET $10"));
+        assert!(score_sql().contains(&POPULARITY_HALF.to_string()));
+    }
+}
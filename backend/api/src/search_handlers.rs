@@ -0,0 +1,274 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::PgPool;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    search_engine::{self, Candidate, Facet, QueryPlan, Synonyms},
+    state::AppState,
+    validation::{ValidatedJson, ValidatedQuery},
+};
+use shared::models::{
+    FacetCount, SearchFacet, SearchHit, SearchParams, SearchResponse, SearchSynonym,
+    UpsertSynonymRequest,
+};
+
+/// Vocabulary words fetched per query term when looking for corrections
+const CORRECTION_CANDIDATES: i64 = 10;
+
+fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(context = ctx, error = %err, "database error");
+    ApiError::internal(format!("Database error during: {}", ctx))
+}
+
+/// Filter values in the order `search_engine` binds them ($3..$8)
+struct Filters {
+    network: Option<String>,
+    category: Option<String>,
+    maturity: Option<String>,
+    verified: Option<bool>,
+    license: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+impl Filters {
+    /// Expects params already sanitized by `ValidatedQuery`
+    fn from_params(params: &SearchParams) -> Self {
+        Self {
+            network: params.network.as_ref().map(|n| n.to_string()),
+            category: params.category.clone(),
+            maturity: params.maturity.clone(),
+            verified: params.verified,
+            license: params.license.clone(),
+            tags: params
+                .tags
+                .as_deref()
+                .map(|tags| tags.split(',').map(String::from).collect()),
+        }
+    }
+}
+
+/// Bind the shared $1..$8 parameters onto any sqlx query builder
+macro_rules! bind_search {
+    ($query:expr, $plan:expr, $filters:expr) => {
+        $query
+            .bind(&$plan.tsquery)
+            .bind(&$plan.fuzzy)
+            .bind(&$filters.network)
+            .bind(&$filters.category)
+            .bind(&$filters.maturity)
+            .bind($filters.verified)
+            .bind(&$filters.license)
+            .bind(&$filters.tags)
+    };
+}
+
+async fn load_synonyms(db: &PgPool) -> ApiResult<Synonyms> {
+    let rows: Vec<(String, Vec<String>)> =
+        sqlx::query_as("SELECT term, synonyms FROM search_synonyms")
+            .fetch_all(db)
+            .await
+            .map_err(|e| db_err("load search synonyms", e))?;
+    Ok(Synonyms::new(rows))
+}
+
+async fn fetch_hits(
+    db: &PgPool,
+    plan: &QueryPlan,
+    filters: &Filters,
+    limit: i64,
+    offset: i64,
+) -> ApiResult<(Vec<SearchHit>, i64)> {
+    let hits_sql = search_engine::hits_sql();
+    let hits: Vec<SearchHit> = bind_search!(sqlx::query_as(&hits_sql), plan, filters)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(db)
+        .await
+        .map_err(|e| db_err("search contracts", e))?;
+
+    let count_sql = search_engine::count_sql();
+    let total: i64 = bind_search!(sqlx::query_scalar(&count_sql), plan, filters)
+        .fetch_one(db)
+        .await
+        .map_err(|e| db_err("count search hits", e))?;
+
+    Ok((hits, total))
+}
+
+async fn fetch_facets(
+    db: &PgPool,
+    plan: &QueryPlan,
+    filters: &Filters,
+) -> ApiResult<Vec<SearchFacet>> {
+    let facets_sql = search_engine::facets_sql();
+    let rows: Vec<(String, String, i64)> = bind_search!(sqlx::query_as(&facets_sql), plan, filters)
+        .fetch_all(db)
+        .await
+        .map_err(|e| db_err("count search facets", e))?;
+
+    Ok(Facet::ALL
+        .iter()
+        .map(|f| SearchFacet {
+            name: f.as_str().to_string(),
+            values: rows
+                .iter()
+                .filter(|(facet, _, _)| facet == f.as_str())
+                .map(|(_, value, count)| FacetCount {
+                    value: value.clone(),
+                    count: *count,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Closest vocabulary words for each term, found by trigram similarity
+async fn correction_candidates(db: &PgPool, terms: &[String]) -> ApiResult<Vec<Candidate>> {
+    let rows: Vec<(String, String, i32)> = sqlx::query_as(
+        "SELECT t.term, v.word, v.ndoc
+         FROM UNNEST($1::text[]) AS t(term)
+         JOIN LATERAL (
+             SELECT word, ndoc FROM search_vocabulary
+             WHERE word % t.term
+             ORDER BY similarity(word, t.term) DESC
+             LIMIT $2
+         ) v ON TRUE",
+    )
+    .bind(terms)
+    .bind(CORRECTION_CANDIDATES)
+    .fetch_all(db)
+    .await
+    .map_err(|e| db_err("load correction candidates", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(term, word, ndoc)| Candidate { term, word, ndoc })
+        .collect())
+}
+
+// ─────────────────────────────────────────────────────────
+// Search
+// ─────────────────────────────────────────────────────────
+
+/// GET /api/search
+///
+/// Ranked full-text search with facet counts. When nothing matches as typed,
+/// misspelled terms are corrected against the index vocabulary and the
+/// corrected query is reported back.
+pub async fn search(
+    State(state): State<AppState>,
+    ValidatedQuery(params): ValidatedQuery<SearchParams>,
+) -> ApiResult<Json<SearchResponse>> {
+    let page = params.page.unwrap_or(1);
+    let limit = params.limit.unwrap_or(20);
+    let offset = (page - 1) * limit;
+
+    let filters = Filters::from_params(&params);
+    let query = params.q.clone();
+
+    let synonyms = load_synonyms(&state.db).await?;
+    let mut plan = search_engine::plan(query.as_deref().unwrap_or_default(), &synonyms);
+    let (mut hits, mut total) = fetch_hits(&state.db, &plan, &filters, limit, offset).await?;
+
+    let mut corrected_query = None;
+    if total == 0 && !plan.terms.is_empty() {
+        let candidates = correction_candidates(&state.db, &plan.terms).await?;
+        if let Some(terms) = search_engine::correct(&plan.terms, &candidates) {
+            let corrected = terms.join(" ");
+            let retry = search_engine::plan(&corrected, &synonyms);
+            let (retry_hits, retry_total) =
+                fetch_hits(&state.db, &retry, &filters, limit, offset).await?;
+            if retry_total > 0 {
+                plan = retry;
+                hits = retry_hits;
+                total = retry_total;
+                corrected_query = Some(corrected);
+            }
+        }
+    }
+
+    let facets = fetch_facets(&state.db, &plan, &filters).await?;
+
+    Ok(Json(SearchResponse {
+        query,
+        corrected_query,
+        hits,
+        facets,
+        total,
+        page,
+        limit,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// Synonyms
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_synonyms(State(state): State<AppState>) -> ApiResult<Json<Vec<SearchSynonym>>> {
+    let rows: Vec<SearchSynonym> = sqlx::query_as("SELECT * FROM search_synonyms ORDER BY term")
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| db_err("list search synonyms", e))?;
+    Ok(Json(rows))
+}
+
+/// PUT /api/search/synonyms/:term — replaces the term's synonym group
+pub async fn upsert_synonyms(
+    State(state): State<AppState>,
+    Path(term): Path<String>,
+    ValidatedJson(req): ValidatedJson<UpsertSynonymRequest>,
+) -> ApiResult<Json<SearchSynonym>> {
+    let term = term.trim().to_lowercase();
+    if search_engine::tokenize(&term).is_empty() {
+        return Err(ApiError::bad_request(
+            "InvalidTerm",
+            "term must contain letters or digits",
+        ));
+    }
+    let synonyms: Vec<String> = req.synonyms.into_iter().filter(|s| *s != term).collect();
+    if synonyms.is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingSynonyms",
+            "at least one synonym other than the term itself is required",
+        ));
+    }
+
+    let row: SearchSynonym = sqlx::query_as(
+        "INSERT INTO search_synonyms (term, synonyms)
+         VALUES ($1, $2)
+         ON CONFLICT (term) DO UPDATE
+         SET synonyms = EXCLUDED.synonyms, updated_at = NOW()
+         RETURNING *",
+    )
+    .bind(&term)
+    .bind(&synonyms)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("upsert search synonyms", e))?;
+
+    tracing::info!(term = %term, synonyms = ?synonyms, "search synonyms updated");
+    Ok(Json(row))
+}
+
+pub async fn delete_synonyms(
+    State(state): State<AppState>,
+    Path(term): Path<String>,
+) -> ApiResult<StatusCode> {
+    let deleted = sqlx::query("DELETE FROM search_synonyms WHERE term = $1")
+        .bind(term.trim().to_lowercase())
+        .execute(&state.db)
+        .await
+        .map_err(|e| db_err("delete search synonyms", e))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found(
+            "SynonymNotFound",
+            format!("No synonyms defined for '{}'", term),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,150 @@
+// api/src/search_index.rs
+// Keeps the ranking signals search reads from `contracts` current: the
+// denormalised trust score and the vocabulary used for spelling corrections.
+// The trust refresh also records daily snapshots under every trust policy.
+
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::trust::{self, TrustInput};
+use crate::trust_service;
+
+/// Spawn a background task that refreshes trust scores and the search
+/// vocabulary every hour.
+pub fn spawn_search_index_task(pool: PgPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+
+        loop {
+            interval.tick().await;
+            tracing::info!("search index: starting hourly refresh");
+
+            if let Err(err) = refresh_trust_scores(&pool).await {
+                tracing::error!(error = ?err, "search index: trust score refresh failed");
+            }
+            if let Err(err) = refresh_vocabulary(&pool).await {
+                tracing::error!(error = ?err, "search index: vocabulary refresh failed");
+            }
+        }
+    });
+}
+
+#[derive(sqlx::FromRow)]
+struct TrustRow {
+    id: Uuid,
+    is_verified: bool,
+    created_at: chrono::DateTime<chrono::Utc>,
+    latest_audit_score: Option<f64>,
+    total_deployments: i64,
+    total_interactions: i64,
+    unresolved_critical_vulns: i64,
+}
+
+/// Collect trust inputs for one contract, or every contract when `contract`
+/// is `None`. Critical vulnerabilities are unresolved critical CVEs from
+/// dependency scans that were not marked as false positives.
+pub async fn load_trust_inputs(
+    pool: &PgPool,
+    contract: Option<Uuid>,
+) -> Result<Vec<(Uuid, TrustInput)>, sqlx::Error> {
+    let rows: Vec<TrustRow> = sqlx::query_as(
+        r#"
+        SELECT
+            c.id,
+            c.is_verified,
+            c.created_at,
+            (SELECT a.overall_score FROM security_audits a
+              WHERE a.contract_id = c.id
+              ORDER BY a.audit_date DESC LIMIT 1) AS latest_audit_score,
+            (SELECT COUNT(*) FROM contract_deployments d
+              WHERE d.contract_id = c.id) AS total_deployments,
+            (SELECT COUNT(*) FROM contract_interactions i
+              WHERE i.contract_id = c.id) AS total_interactions,
+            (SELECT COUNT(*) FROM contract_scan_results s
+               JOIN cve_vulnerabilities v ON v.cve_id = s.cve_id
+              WHERE s.contract_id = c.id
+                AND NOT s.is_false_positive
+                AND UPPER(v.severity) = 'CRITICAL') AS unresolved_critical_vulns
+        FROM contracts c
+        WHERE $1::uuid IS NULL OR c.id = $1
+        "#,
+    )
+    .bind(contract)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.id,
+                TrustInput {
+                    is_verified: r.is_verified,
+                    latest_audit_score: r.latest_audit_score,
+                    total_deployments: r.total_deployments,
+                    total_interactions: r.total_interactions,
+                    created_at: r.created_at,
+                    unresolved_critical_vulns: r.unresolved_critical_vulns,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Recompute `contracts.trust_score` for every contract under the default
+/// policy, and snapshot today's scores under the latest version of every
+/// policy.
+pub async fn refresh_trust_scores(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let inputs = load_trust_inputs(pool, None).await?;
+    let policies = trust_service::latest_policies(pool).await?;
+
+    let default = policies
+        .iter()
+        .find(|p| p.name == trust::DEFAULT_POLICY)
+        .map(|p| p.config.clone())
+        .unwrap_or_default();
+    let (ids, scores): (Vec<Uuid>, Vec<f64>) = inputs
+        .iter()
+        .map(|(id, input)| (*id, trust::score_with_policy(input, &default).score))
+        .unzip();
+
+    let result = sqlx::query(
+        "UPDATE contracts c SET trust_score = s.score, trust_score_updated_at = NOW()
+         FROM UNNEST($1::uuid[], $2::float8[]) AS s(id, score)
+         WHERE c.id = s.id",
+    )
+    .bind(&ids)
+    .bind(&scores)
+    .execute(pool)
+    .await?;
+
+    tracing::info!(
+        rows_updated = result.rows_affected(),
+        "search index: trust scores recalculated"
+    );
+
+    for policy in &policies {
+        let scores: Vec<_> = inputs
+            .iter()
+            .map(|(id, input)| (*id, trust::score_with_policy(input, &policy.config)))
+            .collect();
+        let recorded = trust_service::record_snapshots(pool, policy, &scores).await?;
+        tracing::info!(
+            policy = %policy.name,
+            version = policy.version,
+            snapshots = recorded,
+            "search index: trust score snapshots recorded"
+        );
+    }
+    Ok(())
+}
+
+/// Rebuild the word list spelling corrections are drawn from.
+pub async fn refresh_vocabulary(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY search_vocabulary")
+        .execute(pool)
+        .await?;
+    tracing::info!("search index: vocabulary refreshed");
+    Ok(())
+}
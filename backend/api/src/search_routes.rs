@@ -0,0 +1,16 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::{search_handlers, state::AppState};
+
+pub fn search_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/search", get(search_handlers::search))
+        .route("/api/search/synonyms", get(search_handlers::list_synonyms))
+        .route(
+            "/api/search/synonyms/:term",
+            put(search_handlers::upsert_synonyms).delete(search_handlers::delete_synonyms),
+        )
+}
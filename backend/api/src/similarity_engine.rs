@@ -0,0 +1,509 @@
+// api/src/similarity_engine.rs
+//
+// Contract similarity from three signals:
+//
+//   • code  — hashes of the function bodies in the WASM code section. Two
+//             builds of the same source share every body; a fork shares most
+//             of the original's. Bodies found in many contracts (SDK runtime
+//             helpers, tiny getters) say nothing about lineage and are
+//             dropped before comparing.
+//   • abi   — exported function names and full signatures from the ABI.
+//   • tags  — publisher-assigned tags.
+//
+// Each signal is a Jaccard index; the score is their weighted mean over the
+// signals both contracts have, so a contract with no uploaded WASM is still
+// compared on its interface and tags. Code overlap also classifies the
+// relation: identical WASM, clone (nearly all bodies shared) or fork (most
+// of one contract's bodies appear in the other).
+//
+// Everything in here is synchronous.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use shared::abi::ContractSpec;
+
+/// Function bodies shorter than this are too generic to identify code
+pub const MIN_FRAGMENT_BYTES: usize = 24;
+
+/// Hex characters kept from each body's SHA-256
+const FRAGMENT_HEX_LEN: usize = 16;
+
+pub const WEIGHT_CODE: f64 = 0.5;
+pub const WEIGHT_ABI: f64 = 0.35;
+pub const WEIGHT_TAGS: f64 = 0.15;
+
+/// Code Jaccard index from which two contracts are clones
+pub const CLONE_THRESHOLD: f64 = 0.9;
+
+/// Share of one contract's bodies found in the other that makes it a fork
+pub const FORK_CONTAINMENT: f64 = 0.6;
+
+/// Fewer distinctive bodies than this cannot establish a fork
+pub const MIN_FORK_FRAGMENTS: usize = 5;
+
+/// A body in more than this share of fingerprinted contracts is common code
+pub const COMMON_FRAGMENT_SHARE: f64 = 0.2;
+
+/// ...but only once it appears in at least this many contracts
+pub const COMMON_FRAGMENT_MIN_CONTRACTS: i64 = 3;
+
+// ─────────────────────────────────────────────────────────────────────────────
+// WASM code section
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmError {
+    NotWasm,
+    Truncated,
+    Malformed(&'static str),
+}
+
+impl fmt::Display for WasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasmError::NotWasm => write!(f, "not a WebAssembly module"),
+            WasmError::Truncated => write!(f, "module ends unexpectedly"),
+            WasmError::Malformed(what) => write!(f, "malformed module: {}", what),
+        }
+    }
+}
+
+const WASM_MAGIC: &[u8; 4] = b"\0asm";
+const CODE_SECTION: u8 = 10;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn byte(&mut self) -> Result<u8, WasmError> {
+        let b = *self.bytes.get(self.pos).ok_or(WasmError::Truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], WasmError> {
+        let end = self.pos.checked_add(len).ok_or(WasmError::Truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(WasmError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    /// Unsigned LEB128, at most five bytes for a u32
+    fn u32(&mut self) -> Result<u32, WasmError> {
+        let mut value: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let b = self.byte()?;
+            value |= u32::from(b & 0x7f)
+                .checked_shl(shift)
+                .ok_or(WasmError::Malformed("integer too large"))?;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(WasmError::Malformed("integer too large"))
+    }
+}
+
+/// What fingerprinting found in a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeFingerprint {
+    /// Sorted, distinct hashes of bodies of at least `MIN_FRAGMENT_BYTES`
+    pub fragments: Vec<String>,
+    /// Every function body in the code section
+    pub function_count: usize,
+}
+
+/// Hash every function body in the module's code section. Bodies are hashed
+/// as-is, including their local declarations, so they match across builds
+/// only when the compiled code is identical.
+pub fn fingerprint_wasm(wasm: &[u8]) -> Result<CodeFingerprint, WasmError> {
+    if wasm.len() < 8 || &wasm[..4] != WASM_MAGIC {
+        return Err(WasmError::NotWasm);
+    }
+    let mut reader = Reader {
+        bytes: wasm,
+        pos: 8,
+    };
+    let mut fragments = BTreeSet::new();
+    let mut function_count = 0;
+
+    while !reader.done() {
+        let id = reader.byte()?;
+        let size = reader.u32()? as usize;
+        let payload = reader.take(size)?;
+        if id != CODE_SECTION {
+            continue;
+        }
+
+        let mut code = Reader {
+            bytes: payload,
+            pos: 0,
+        };
+        let count = code.u32()?;
+        for _ in 0..count {
+            let body_size = code.u32()? as usize;
+            let body = code.take(body_size)?;
+            function_count += 1;
+            if body.len() >= MIN_FRAGMENT_BYTES {
+                let digest = hex::encode(Sha256::digest(body));
+                fragments.insert(digest[..FRAGMENT_HEX_LEN].to_string());
+            }
+        }
+        if !code.done() {
+            return Err(WasmError::Malformed("trailing bytes in code section"));
+        }
+    }
+
+    Ok(CodeFingerprint {
+        fragments: fragments.into_iter().collect(),
+        function_count,
+    })
+}
+
+/// Fragments appearing in so many contracts that they are shared library
+/// code rather than evidence of common origin. `counts` pairs each fragment
+/// with the number of fingerprinted contracts containing it.
+pub fn common_fragments(counts: &[(String, i64)], total_contracts: i64) -> HashSet<String> {
+    let limit = ((total_contracts as f64 * COMMON_FRAGMENT_SHARE).floor() as i64)
+        .max(COMMON_FRAGMENT_MIN_CONTRACTS);
+    counts
+        .iter()
+        .filter(|(_, n)| *n > limit)
+        .map(|(f, _)| f.clone())
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Profiles
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Exported function names and `name(in,…)->out` signatures from a stored
+/// ABI. ABIs that do not parse contribute nothing.
+pub fn abi_functions(abi: Option<&serde_json::Value>) -> (BTreeSet<String>, BTreeSet<String>) {
+    let specs: Vec<ContractSpec> = abi
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let mut names = BTreeSet::new();
+    let mut signatures = BTreeSet::new();
+    for spec in specs.iter().filter(|s| s.spec_type == "function") {
+        let inputs: Vec<&str> = spec
+            .inputs
+            .iter()
+            .map(|i| i.value.type_name.as_str())
+            .collect();
+        let outputs: Vec<&str> = spec.outputs.iter().map(|o| o.type_name.as_str()).collect();
+        names.insert(spec.name.clone());
+        signatures.insert(format!(
+            "{}({})->{}",
+            spec.name,
+            inputs.join(","),
+            outputs.join(",")
+        ));
+    }
+    (names, signatures)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub wasm_hash: Option<String>,
+    pub functions: BTreeSet<String>,
+    pub signatures: BTreeSet<String>,
+    /// `None` when no WASM has been fingerprinted
+    pub fragments: Option<BTreeSet<String>>,
+    pub tags: BTreeSet<String>,
+}
+
+impl Profile {
+    pub fn new(
+        wasm_hash: Option<&str>,
+        abi: Option<&serde_json::Value>,
+        fragments: Option<&[String]>,
+        tags: &[String],
+        common: &HashSet<String>,
+    ) -> Self {
+        let (functions, signatures) = abi_functions(abi);
+        Self {
+            // Placeholder hashes from unverified publishes identify nothing
+            wasm_hash: wasm_hash
+                .filter(|h| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()))
+                .map(str::to_lowercase),
+            functions,
+            signatures,
+            fragments: fragments
+                .map(|f| f.iter().filter(|h| !common.contains(*h)).cloned().collect()),
+            tags: tags.iter().map(|t| t.trim().to_lowercase()).collect(),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Comparison
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn jaccard<T: Ord>(a: &BTreeSet<T>, b: &BTreeSet<T>) -> Option<f64> {
+    let union = a.union(b).count();
+    (union > 0).then(|| a.intersection(b).count() as f64 / union as f64)
+}
+
+/// Share of `part` found in `whole`
+fn containment<T: Ord>(part: &BTreeSet<T>, whole: &BTreeSet<T>) -> f64 {
+    if part.is_empty() {
+        return 0.0;
+    }
+    part.intersection(whole).count() as f64 / part.len() as f64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// Same WASM hash
+    Identical,
+    /// Nearly every function body is shared
+    Clone,
+    /// Most of the other contract's bodies appear in this one
+    ForkOf,
+    /// Most of this contract's bodies appear in the other one
+    ForkedBy,
+    Similar,
+}
+
+impl Relation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Relation::Identical => "identical",
+            Relation::Clone => "clone",
+            Relation::ForkOf => "fork_of",
+            Relation::ForkedBy => "forked_by",
+            Relation::Similar => "similar",
+        }
+    }
+
+    /// Whether the compared contract carries the other one's code
+    pub fn inherits_code(self) -> bool {
+        matches!(
+            self,
+            Relation::Identical | Relation::Clone | Relation::ForkOf
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Similarity {
+    pub score: f64,
+    pub relation: Relation,
+    pub code: Option<f64>,
+    pub abi: Option<f64>,
+    pub tags: Option<f64>,
+    /// Function names both ABIs export
+    pub shared_functions: Vec<String>,
+    /// Distinctive function bodies both contain
+    pub shared_fragments: usize,
+}
+
+/// Compare `target` with `other`. `ForkOf` means `target` was derived from
+/// `other`.
+pub fn compare(target: &Profile, other: &Profile) -> Similarity {
+    let shared_functions: Vec<String> = target
+        .functions
+        .intersection(&other.functions)
+        .cloned()
+        .collect();
+
+    let abi = match (
+        jaccard(&target.functions, &other.functions),
+        jaccard(&target.signatures, &other.signatures),
+    ) {
+        (Some(names), Some(sigs)) => Some(0.4 * names + 0.6 * sigs),
+        _ => None,
+    };
+    let tags = jaccard(&target.tags, &other.tags);
+
+    let (code, shared_fragments, relation) = match (&target.fragments, &other.fragments) {
+        (Some(a), Some(b)) => {
+            let shared = a.intersection(b).count();
+            let code = jaccard(a, b);
+            let relation = if code.unwrap_or(0.0) >= CLONE_THRESHOLD {
+                Relation::Clone
+            } else if b.len() >= MIN_FORK_FRAGMENTS && containment(b, a) >= FORK_CONTAINMENT {
+                Relation::ForkOf
+            } else if a.len() >= MIN_FORK_FRAGMENTS && containment(a, b) >= FORK_CONTAINMENT {
+                Relation::ForkedBy
+            } else {
+                Relation::Similar
+            };
+            (code, shared, relation)
+        }
+        _ => (None, 0, Relation::Similar),
+    };
+
+    let identical = target.wasm_hash.is_some() && target.wasm_hash == other.wasm_hash;
+    let weighted = [(code, WEIGHT_CODE), (abi, WEIGHT_ABI), (tags, WEIGHT_TAGS)];
+    let weight: f64 = weighted
+        .iter()
+        .filter(|(s, _)| s.is_some())
+        .map(|(_, w)| w)
+        .sum();
+    let score = if identical {
+        1.0
+    } else if weight > 0.0 {
+        weighted
+            .iter()
+            .filter_map(|(s, w)| s.map(|s| s * w))
+            .sum::<f64>()
+            / weight
+    } else {
+        0.0
+    };
+
+    Similarity {
+        score,
+        relation: if identical {
+            Relation::Identical
+        } else {
+            relation
+        },
+        code,
+        abi,
+        tags,
+        shared_functions,
+        shared_fragments,
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn leb(mut n: usize, out: &mut Vec<u8>) {
+        loop {
+            let b = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                out.push(b);
+                return;
+            }
+            out.push(b | 0x80);
+        }
+    }
+
+    /// A module with a type section and a code section holding `bodies`
+    fn module(bodies: &[Vec<u8>]) -> Vec<u8> {
+        let mut code = Vec::new();
+        leb(bodies.len(), &mut code);
+        for body in bodies {
+            leb(body.len(), &mut code);
+            code.extend(body);
+        }
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend([1, 4, 1, 0x60, 0, 0]);
+        wasm.push(CODE_SECTION);
+        leb(code.len(), &mut wasm);
+        wasm.extend(code);
+        wasm
+    }
+
+    fn body(seed: u8, len: usize) -> Vec<u8> {
+        (0..len).map(|i| seed.wrapping_add(i as u8)).collect()
+    }
+
+    fn profile(fragments: &[&str], abi: serde_json::Value, tags: &[&str]) -> Profile {
+        let fragments: Vec<String> = fragments.iter().map(|s| s.to_string()).collect();
+        let tags: Vec<String> = tags.iter().map(|s| s.to_string()).collect();
+        Profile::new(None, Some(&abi), Some(&fragments), &tags, &HashSet::new())
+    }
+
+    fn abi(fns: &[(&str, &[&str])]) -> serde_json::Value {
+        json!(fns
+            .iter()
+            .map(|(name, inputs)| json!({
+                "type": "function",
+                "name": name,
+                "inputs": inputs.iter().map(|t| json!({"name": "x", "value": {"type": t}})).collect::<Vec<_>>(),
+                "outputs": [],
+            }))
+            .collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn fingerprints_code_section_bodies() {
+        let wasm = module(&[body(1, 40), body(2, 40), body(1, 40), body(9, 3)]);
+        let fp = fingerprint_wasm(&wasm).unwrap();
+        assert_eq!(fp.function_count, 4);
+        // Duplicates collapse and the 3-byte body is too small to count
+        assert_eq!(fp.fragments.len(), 2);
+        assert!(fp.fragments.iter().all(|f| f.len() == FRAGMENT_HEX_LEN));
+
+        assert_eq!(fingerprint_wasm(b"not wasm"), Err(WasmError::NotWasm));
+        assert_eq!(
+            fingerprint_wasm(&wasm[..wasm.len() - 5]),
+            Err(WasmError::Truncated)
+        );
+    }
+
+    #[test]
+    fn common_fragments_need_both_share_and_count() {
+        let counts = vec![("sdk".to_string(), 40), ("rare".to_string(), 3)];
+        let common = common_fragments(&counts, 100);
+        assert!(common.contains("sdk"));
+        assert!(!common.contains("rare"));
+        // In a small registry the minimum count applies
+        assert!(common_fragments(&[("x".to_string(), 3)], 5).is_empty());
+    }
+
+    #[test]
+    fn classifies_forks_by_containment() {
+        let original = ["a", "b", "c", "d", "e"];
+        let fork = ["a", "b", "c", "d", "x", "y", "z"];
+        let dex = abi(&[("swap", &["address", "i128"]), ("deposit", &["i128"])]);
+        let a = profile(&fork, dex.clone(), &["dex"]);
+        let b = profile(&original, dex, &["dex"]);
+
+        let s = compare(&a, &b);
+        assert_eq!(s.relation, Relation::ForkOf);
+        assert_eq!(s.shared_fragments, 4);
+        assert_eq!(compare(&b, &a).relation, Relation::ForkedBy);
+        assert_eq!(s.abi, Some(1.0));
+        assert_eq!(s.shared_functions, vec!["deposit", "swap"]);
+    }
+
+    #[test]
+    fn signature_changes_lower_abi_similarity() {
+        let a = profile(&[], abi(&[("swap", &["address", "i128"])]), &[]);
+        let b = profile(&[], abi(&[("swap", &["address", "u64"])]), &[]);
+        let s = compare(&a, &b);
+        // Same name, different signature: 0.4 × 1 + 0.6 × 0
+        assert!((s.abi.unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(s.relation, Relation::Similar);
+    }
+
+    #[test]
+    fn score_uses_only_signals_both_sides_have() {
+        let tags: Vec<String> = vec!["lending".into()];
+        let no_code = Profile::new(None, None, None, &tags, &HashSet::new());
+        let with_code = profile(&["a"], json!([]), &["lending"]);
+        let s = compare(&no_code, &with_code);
+        assert_eq!(s.code, None);
+        assert_eq!(s.abi, None);
+        assert_eq!(s.score, 1.0);
+
+        let hash = "ab".repeat(32);
+        let x = Profile::new(Some(&hash), None, None, &[], &HashSet::new());
+        assert_eq!(compare(&x, &x.clone()).relation, Relation::Identical);
+        let placeholder = Profile::new(Some("placeholder_hash"), None, None, &[], &HashSet::new());
+        assert_eq!(placeholder.wasm_hash, None);
+    }
+}
@@ -0,0 +1,147 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    Json,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    similarity_engine,
+    similarity_service::{self, SimilarQuery},
+    state::AppState,
+};
+use shared::models::{ContractFingerprint, SimilarContractsParams, SimilarContractsResponse};
+
+/// Well above the network's contract size limit
+const MAX_WASM_BYTES: usize = 256 * 1024;
+
+const DEFAULT_MIN_SCORE: f64 = 0.2;
+
+fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(context = ctx, error = %err, "database error");
+    ApiError::internal(format!("Database error during: {}", ctx))
+}
+
+fn contract_not_found(id: Uuid) -> ApiError {
+    ApiError::not_found(
+        "ContractNotFound",
+        format!("No contract found with ID: {}", id),
+    )
+}
+
+/// PUT /api/contracts/:id/fingerprint
+///
+/// Body: the contract's WASM. When the contract records a real WASM hash the
+/// upload must match it.
+pub async fn upload_fingerprint(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    wasm: Bytes,
+) -> ApiResult<Json<ContractFingerprint>> {
+    if wasm.len() > MAX_WASM_BYTES {
+        return Err(ApiError::bad_request(
+            "WasmTooLarge",
+            format!("WASM must be at most {} bytes", MAX_WASM_BYTES),
+        ));
+    }
+
+    let recorded: String = sqlx::query_scalar("SELECT wasm_hash FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("load contract wasm hash", e))?
+        .ok_or_else(|| contract_not_found(id))?;
+
+    let wasm_hash = hex::encode(Sha256::digest(&wasm));
+    let recorded_is_hash = recorded.len() == 64 && recorded.chars().all(|c| c.is_ascii_hexdigit());
+    if recorded_is_hash && !recorded.eq_ignore_ascii_case(&wasm_hash) {
+        return Err(ApiError::unprocessable(
+            "WasmHashMismatch",
+            format!(
+                "uploaded WASM hashes to {} but the contract records {}",
+                wasm_hash, recorded
+            ),
+        ));
+    }
+
+    let code = similarity_engine::fingerprint_wasm(&wasm)
+        .map_err(|e| ApiError::bad_request("InvalidWasm", e.to_string()))?;
+
+    let fingerprint: ContractFingerprint = sqlx::query_as(
+        "INSERT INTO contract_fingerprints
+             (contract_id, wasm_hash, function_hashes, function_count, wasm_size_bytes)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (contract_id) DO UPDATE
+         SET wasm_hash       = EXCLUDED.wasm_hash,
+             function_hashes = EXCLUDED.function_hashes,
+             function_count  = EXCLUDED.function_count,
+             wasm_size_bytes = EXCLUDED.wasm_size_bytes,
+             computed_at     = NOW()
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(&wasm_hash)
+    .bind(&code.fragments)
+    .bind(code.function_count as i32)
+    .bind(wasm.len() as i64)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_err("store contract fingerprint", e))?;
+
+    tracing::info!(
+        contract = %id,
+        functions = code.function_count,
+        fragments = code.fragments.len(),
+        "contract fingerprinted"
+    );
+
+    Ok(Json(fingerprint))
+}
+
+/// GET /api/contracts/:id/similar
+pub async fn get_similar_contracts(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<SimilarContractsParams>,
+) -> ApiResult<Json<SimilarContractsResponse>> {
+    let limit = params.limit.unwrap_or(10);
+    let min_score = params.min_score.unwrap_or(DEFAULT_MIN_SCORE);
+    if !(1..=50).contains(&limit) {
+        return Err(ApiError::bad_request(
+            "InvalidLimit",
+            "limit must be between 1 and 50",
+        ));
+    }
+    if !(0.0..=1.0).contains(&min_score) {
+        return Err(ApiError::bad_request(
+            "InvalidMinScore",
+            "min_score must be between 0 and 1",
+        ));
+    }
+
+    let fingerprinted = similarity_service::fingerprint_status(&state.db, id)
+        .await
+        .map_err(|e| db_err("load contract fingerprint", e))?
+        .ok_or_else(|| contract_not_found(id))?;
+
+    let query = SimilarQuery {
+        limit,
+        min_score,
+        network: params.network.map(|n| n.to_string()),
+        vulnerable_only: false,
+    };
+    let items = similarity_service::find_similar(&state.db, id, &query)
+        .await
+        .map_err(|e| db_err("find similar contracts", e))?
+        .into_iter()
+        .map(|(_, contract)| contract)
+        .collect();
+
+    Ok(Json(SimilarContractsResponse {
+        contract_id: id,
+        fingerprinted,
+        items,
+    }))
+}
@@ -0,0 +1,18 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::{similarity_handlers, state::AppState};
+
+pub fn similarity_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/contracts/:id/fingerprint",
+            put(similarity_handlers::upload_fingerprint),
+        )
+        .route(
+            "/api/contracts/:id/similar",
+            get(similarity_handlers::get_similar_contracts),
+        )
+}
@@ -0,0 +1,187 @@
+// api/src/similarity_service.rs
+// Loads contract profiles and ranks similar contracts with
+// `similarity_engine`. Shared by the similarity endpoint and the security
+// view, which flags forks of known-vulnerable contracts.
+
+use std::collections::HashSet;
+
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::similarity_engine::{self, Profile, Similarity};
+use shared::models::SimilarContract;
+
+/// Contracts compared per request, most popular first
+const CANDIDATE_POOL: i64 = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct SimilarQuery {
+    pub limit: usize,
+    pub min_score: f64,
+    pub network: Option<String>,
+    /// Only compare against contracts with open critical or high advisories
+    pub vulnerable_only: bool,
+}
+
+#[derive(FromRow)]
+struct ProfileRow {
+    id: Uuid,
+    contract_id: String,
+    name: String,
+    network: String,
+    is_verified: bool,
+    wasm_hash: String,
+    abi: Option<serde_json::Value>,
+    tags: Vec<String>,
+    function_hashes: Option<Vec<String>>,
+    advisories: Option<Vec<String>>,
+    severity: Option<String>,
+}
+
+impl ProfileRow {
+    fn profile(&self, common: &HashSet<String>) -> Profile {
+        Profile::new(
+            Some(&self.wasm_hash),
+            self.abi.as_ref(),
+            self.function_hashes.as_deref(),
+            &self.tags,
+            common,
+        )
+    }
+
+    fn into_similar(self, s: Similarity) -> SimilarContract {
+        SimilarContract {
+            id: self.id,
+            contract_id: self.contract_id,
+            name: self.name,
+            network: self.network,
+            is_verified: self.is_verified,
+            score: s.score,
+            relation: s.relation.as_str().to_string(),
+            code_similarity: s.code,
+            abi_similarity: s.abi,
+            tag_similarity: s.tags,
+            shared_functions: s.shared_functions,
+            shared_fragments: s.shared_fragments,
+            known_vulnerable: self.advisories.is_some(),
+            severity: self.severity,
+            advisories: self.advisories.unwrap_or_default(),
+        }
+    }
+}
+
+const PROFILE_COLUMNS: &str = "c.id, c.contract_id, c.name, c.network::text AS network, \
+     c.is_verified, c.wasm_hash, c.abi, COALESCE(c.tags, '{}') AS tags, \
+     f.function_hashes, v.advisories, v.severity \
+     FROM contracts c \
+     LEFT JOIN contract_fingerprints f ON f.contract_id = c.id \
+     LEFT JOIN vulnerable_contracts v ON v.contract_id = c.id";
+
+/// Function bodies shared by so many fingerprinted contracts that they are
+/// library code
+async fn common_fragments(db: &PgPool) -> Result<HashSet<String>, sqlx::Error> {
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM contract_fingerprints")
+        .fetch_one(db)
+        .await?;
+    let counts: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT h, COUNT(*) FROM contract_fingerprints, UNNEST(function_hashes) AS h
+         GROUP BY h HAVING COUNT(*) > $1",
+    )
+    .bind(similarity_engine::COMMON_FRAGMENT_MIN_CONTRACTS)
+    .fetch_all(db)
+    .await?;
+    Ok(similarity_engine::common_fragments(&counts, total))
+}
+
+/// Whether the contract exists and has a WASM fingerprint
+pub async fn fingerprint_status(
+    db: &PgPool,
+    contract_id: Uuid,
+) -> Result<Option<bool>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT f.contract_id IS NOT NULL FROM contracts c
+         LEFT JOIN contract_fingerprints f ON f.contract_id = c.id
+         WHERE c.id = $1",
+    )
+    .bind(contract_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// Contracts most similar to `contract_id`, best first. Candidates share at
+/// least one distinctive function body, ABI function or tag with it.
+pub async fn find_similar(
+    db: &PgPool,
+    contract_id: Uuid,
+    query: &SimilarQuery,
+) -> Result<Vec<(Similarity, SimilarContract)>, sqlx::Error> {
+    let Some(target) =
+        sqlx::query_as::<_, ProfileRow>(&format!("SELECT {} WHERE c.id = $1", PROFILE_COLUMNS))
+            .bind(contract_id)
+            .fetch_optional(db)
+            .await?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let common = common_fragments(db).await?;
+    let target_profile = target.profile(&common);
+    let fragments: Vec<String> = target_profile.fragments.iter().flatten().cloned().collect();
+    let functions: Vec<String> = target_profile.functions.iter().cloned().collect();
+    let tags: Vec<String> = target_profile.tags.iter().cloned().collect();
+
+    let candidates: Vec<ProfileRow> = sqlx::query_as(&format!(
+        "SELECT {} \
+         WHERE c.id <> $1 \
+           AND ($2::text IS NULL OR c.network::text = $2) \
+           AND ($3::bool = FALSE OR v.contract_id IS NOT NULL) \
+           AND (f.function_hashes && $4 OR c.abi_functions && $5 \
+                OR EXISTS (SELECT 1 FROM UNNEST(c.tags) AS t WHERE LOWER(t) = ANY($6)) \
+                OR LOWER(c.wasm_hash) = $7) \
+         ORDER BY c.popularity_score DESC \
+         LIMIT $8",
+        PROFILE_COLUMNS
+    ))
+    .bind(contract_id)
+    .bind(&query.network)
+    .bind(query.vulnerable_only)
+    .bind(&fragments)
+    .bind(&functions)
+    .bind(&tags)
+    .bind(&target_profile.wasm_hash)
+    .bind(CANDIDATE_POOL)
+    .fetch_all(db)
+    .await?;
+
+    let mut ranked: Vec<(Similarity, SimilarContract)> = candidates
+        .into_iter()
+        .map(|row| {
+            let s = similarity_engine::compare(&target_profile, &row.profile(&common));
+            (s.clone(), row.into_similar(s))
+        })
+        .filter(|(s, _)| s.score >= query.min_score && s.score > 0.0)
+        .collect();
+    ranked.sort_by(|(a, _), (b, _)| b.score.total_cmp(&a.score));
+    ranked.truncate(query.limit);
+    Ok(ranked)
+}
+
+/// Known-vulnerable contracts this one is identical to, a clone of, or
+/// derived from. Derivation needs WASM fingerprints on both sides.
+pub async fn vulnerable_relatives(
+    db: &PgPool,
+    contract_id: Uuid,
+) -> Result<Vec<SimilarContract>, sqlx::Error> {
+    let query = SimilarQuery {
+        limit: usize::MAX,
+        min_score: 0.0,
+        network: None,
+        vulnerable_only: true,
+    };
+    Ok(find_similar(db, contract_id, &query)
+        .await?
+        .into_iter()
+        .filter(|(s, _)| s.relation.inherits_code())
+        .map(|(_, contract)| contract)
+        .collect())
+}
@@ -0,0 +1,506 @@
+// api/src/sla_engine.rs
+//
+// SLA measurement and accounting rules. Windows are measured from probe
+// and invocation samples, checked against a contract's targets, and their
+// violations priced into the billing period. The SLA monitor loads samples
+// and persists results; everything in here is synchronous.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde_json::Value;
+
+use shared::{SetSlaTargetsRequest, SlaBillingPeriod, SlaTargets};
+
+/// Upper bound on windows evaluated per contract per monitor tick, so a
+/// contract that was offline for weeks catches up gradually.
+pub const MAX_WINDOWS_PER_RUN: usize = 48;
+
+// ─────────────────────────────────────────────────────────
+// Measurement
+// ─────────────────────────────────────────────────────────
+
+/// A single probe or invocation outcome
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub success: bool,
+    pub latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMeasurement {
+    /// Probe success percentage
+    pub uptime: Option<f64>,
+    /// p95 over every sample that reported a latency
+    pub p95_latency_ms: Option<f64>,
+    /// Failed invocation percentage
+    pub error_rate: Option<f64>,
+    pub probe_count: i32,
+    pub probe_successes: i32,
+    pub invocation_count: i32,
+}
+
+/// Share of samples with the given outcome, as a percentage
+fn outcome_percentage(samples: &[Sample], succeeded: bool) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let matching = samples.iter().filter(|s| s.success == succeeded).count();
+    Some(matching as f64 * 100.0 / samples.len() as f64)
+}
+
+/// Nearest-rank percentile; `p` in (0, 100].
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Uptime is what synthetic probes saw; error rate is what real callers
+/// saw. Latency pools both, since each probe is a real round trip too.
+pub fn measure_window(probes: &[Sample], invocations: &[Sample]) -> WindowMeasurement {
+    let latencies: Vec<f64> = probes
+        .iter()
+        .chain(invocations)
+        .filter_map(|s| s.latency_ms)
+        .collect();
+
+    WindowMeasurement {
+        uptime: outcome_percentage(probes, true),
+        p95_latency_ms: percentile(&latencies, 95.0),
+        error_rate: outcome_percentage(invocations, false),
+        probe_count: probes.len() as i32,
+        probe_successes: probes.iter().filter(|s| s.success).count() as i32,
+        invocation_count: invocations.len() as i32,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Targets
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlaMetric {
+    Uptime,
+    Latency,
+    ErrorRate,
+}
+
+impl SlaMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Uptime => "uptime",
+            Self::Latency => "latency",
+            Self::ErrorRate => "error_rate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    pub metric: SlaMetric,
+    pub actual: f64,
+    pub target: f64,
+}
+
+/// Metrics without samples in the window cannot breach.
+pub fn check_violations(measurement: &WindowMeasurement, targets: &SlaTargets) -> Vec<Breach> {
+    let mut breaches = Vec::new();
+    if let Some(uptime) = measurement.uptime.filter(|u| *u < targets.min_uptime) {
+        breaches.push(Breach {
+            metric: SlaMetric::Uptime,
+            actual: uptime,
+            target: targets.min_uptime,
+        });
+    }
+    if let Some(latency) = measurement
+        .p95_latency_ms
+        .filter(|l| *l > targets.max_latency_ms)
+    {
+        breaches.push(Breach {
+            metric: SlaMetric::Latency,
+            actual: latency,
+            target: targets.max_latency_ms,
+        });
+    }
+    if let Some(error_rate) = measurement
+        .error_rate
+        .filter(|e| *e > targets.max_error_rate)
+    {
+        breaches.push(Breach {
+            metric: SlaMetric::ErrorRate,
+            actual: error_rate,
+            target: targets.max_error_rate,
+        });
+    }
+    breaches
+}
+
+/// Apply a partial update on top of the stored (or default) targets.
+/// Empty probe strings clear the probe.
+pub fn apply_targets_update(targets: &mut SlaTargets, req: SetSlaTargetsRequest) {
+    fn non_empty(s: String) -> Option<String> {
+        let s = s.trim().to_string();
+        (!s.is_empty()).then_some(s)
+    }
+
+    if let Some(v) = req.min_uptime {
+        targets.min_uptime = v;
+    }
+    if let Some(v) = req.max_latency_ms {
+        targets.max_latency_ms = v;
+    }
+    if let Some(v) = req.max_error_rate {
+        targets.max_error_rate = v;
+    }
+    if let Some(v) = req.window_minutes {
+        targets.window_minutes = v;
+    }
+    if let Some(v) = req.penalty_per_violation {
+        targets.penalty_per_violation = v;
+    }
+    if let Some(v) = req.credit_rate {
+        targets.credit_rate = v;
+    }
+    if let Some(v) = req.probe_function {
+        targets.probe_function = non_empty(v);
+    }
+    if let Some(v) = req.probe_transaction {
+        targets.probe_transaction = non_empty(v);
+    }
+    if let Some(v) = req.probe_interval_seconds {
+        targets.probe_interval_seconds = v;
+    }
+    if let Some(v) = req.enabled {
+        targets.enabled = v;
+    }
+}
+
+pub fn validate_targets(targets: &SlaTargets) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&targets.min_uptime) {
+        return Err("min_uptime must be between 0 and 100".into());
+    }
+    if !(0.0..=100.0).contains(&targets.max_error_rate) {
+        return Err("max_error_rate must be between 0 and 100".into());
+    }
+    if targets.max_latency_ms <= 0.0 {
+        return Err("max_latency_ms must be positive".into());
+    }
+    // Windows must tile a day so none straddles a billing period boundary
+    if !(5..=1440).contains(&targets.window_minutes) || 1440 % targets.window_minutes != 0 {
+        return Err("window_minutes must divide a day evenly and be at least 5".into());
+    }
+    if targets.penalty_per_violation < 0.0 {
+        return Err("penalty_per_violation cannot be negative".into());
+    }
+    if !(0.0..=1.0).contains(&targets.credit_rate) {
+        return Err("credit_rate must be between 0 and 1".into());
+    }
+    if targets.probe_interval_seconds < 30 {
+        return Err("probe_interval_seconds must be at least 30".into());
+    }
+    if targets.probe_transaction.is_some() && targets.probe_function.is_none() {
+        return Err("probe_function is required when probe_transaction is set".into());
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Scheduling and billing
+// ─────────────────────────────────────────────────────────
+
+fn align_down(at: DateTime<Utc>, window: Duration) -> DateTime<Utc> {
+    let secs = window.num_seconds();
+    let ts = at.timestamp();
+    Utc.timestamp_opt(ts - ts.rem_euclid(secs), 0).unwrap()
+}
+
+/// Complete windows not evaluated yet, oldest first. Evaluation resumes at
+/// the first window boundary at or after `last_window_end` (the window size
+/// may have changed since), or at the window containing `since` for
+/// contracts that have never been evaluated.
+pub fn due_windows(
+    last_window_end: Option<DateTime<Utc>>,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    window_minutes: i32,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let window = Duration::minutes(window_minutes.max(1) as i64);
+    let mut start = match last_window_end {
+        Some(end) if align_down(end, window) == end => end,
+        Some(end) => align_down(end, window) + window,
+        None => align_down(since, window),
+    };
+    let mut windows = Vec::new();
+    while start + window <= now && windows.len() < MAX_WINDOWS_PER_RUN {
+        windows.push((start, start + window));
+        start += window;
+    }
+    windows
+}
+
+/// Calendar-month billing period containing `at`
+pub fn billing_period(at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = Utc
+        .with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+        .unwrap();
+    let (year, month) = if at.month() == 12 {
+        (at.year() + 1, 1)
+    } else {
+        (at.year(), at.month() + 1)
+    };
+    let end = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap();
+    (start, end)
+}
+
+/// Each breached metric in a window costs one penalty.
+pub fn window_penalty(breaches: &[Breach], targets: &SlaTargets) -> f64 {
+    breaches.len() as f64 * targets.penalty_per_violation
+}
+
+/// Credit issued when a period closes: contracts whose probe-weighted
+/// uptime over the whole period still met the target get `credit_rate` of
+/// that period's penalties back.
+pub fn period_credit(period: &SlaBillingPeriod, targets: &SlaTargets) -> f64 {
+    match period.uptime_percentage() {
+        Some(uptime) if uptime >= targets.min_uptime && period.penalty_accrued > 0.0 => {
+            period.penalty_accrued * targets.credit_rate
+        }
+        _ => 0.0,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Probes
+// ─────────────────────────────────────────────────────────
+
+/// Interpret a Soroban RPC `simulateTransaction` response. Transport-level
+/// JSON-RPC errors and host errors from the simulation both count as a
+/// failed probe.
+pub fn simulation_outcome(response: &Value) -> Result<(), String> {
+    if let Some(error) = response.get("error") {
+        return Err(error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()));
+    }
+    let result = response
+        .get("result")
+        .ok_or_else(|| "simulation returned no result".to_string())?;
+    if let Some(error) = result.get("error").and_then(Value::as_str) {
+        return Err(error.to_string());
+    }
+    let has_results = result
+        .get("results")
+        .and_then(Value::as_array)
+        .is_some_and(|r| !r.is_empty());
+    if !has_results {
+        return Err("simulation returned no invocation result".into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn targets() -> SlaTargets {
+        SlaTargets {
+            contract_id: Uuid::new_v4(),
+            min_uptime: 99.5,
+            max_latency_ms: 200.0,
+            max_error_rate: 1.0,
+            window_minutes: 60,
+            penalty_per_violation: 50.0,
+            credit_rate: 0.10,
+            probe_function: None,
+            probe_transaction: None,
+            probe_interval_seconds: 300,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn ok(latency: f64) -> Sample {
+        Sample {
+            success: true,
+            latency_ms: Some(latency),
+        }
+    }
+
+    fn failed() -> Sample {
+        Sample {
+            success: false,
+            latency_ms: None,
+        }
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&values, 95.0), Some(19.0));
+        assert_eq!(percentile(&values, 100.0), Some(20.0));
+        assert_eq!(percentile(&[7.0], 95.0), Some(7.0));
+        assert_eq!(percentile(&[], 95.0), None);
+    }
+
+    #[test]
+    fn uptime_comes_from_probes_and_errors_from_invocations() {
+        let probes = [ok(100.0), ok(120.0), ok(110.0), failed()];
+        let invocations = [ok(90.0), failed()];
+        let m = measure_window(&probes, &invocations);
+
+        assert_eq!(m.uptime, Some(75.0));
+        assert_eq!(m.error_rate, Some(50.0));
+        assert_eq!(m.p95_latency_ms, Some(120.0));
+        assert_eq!(
+            (m.probe_count, m.probe_successes, m.invocation_count),
+            (4, 3, 2)
+        );
+
+        let breaches = check_violations(&m, &targets());
+        let metrics: Vec<_> = breaches.iter().map(|b| b.metric).collect();
+        assert_eq!(metrics, vec![SlaMetric::Uptime, SlaMetric::ErrorRate]);
+        assert_eq!(window_penalty(&breaches, &targets()), 100.0);
+    }
+
+    #[test]
+    fn metrics_without_samples_do_not_breach() {
+        let m = measure_window(&[], &[failed(), failed()]);
+        assert_eq!(m.uptime, None);
+        assert_eq!(m.p95_latency_ms, None);
+        let breaches = check_violations(&m, &targets());
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].metric, SlaMetric::ErrorRate);
+    }
+
+    #[test]
+    fn due_windows_resume_and_only_cover_complete_windows() {
+        let since = Utc.with_ymd_and_hms(2026, 3, 1, 10, 20, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 13, 5, 0).unwrap();
+
+        let windows = due_windows(None, since, now, 60);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(
+            windows[0].0,
+            Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap()
+        );
+        assert_eq!(
+            windows[2].1,
+            Utc.with_ymd_and_hms(2026, 3, 1, 13, 0, 0).unwrap()
+        );
+
+        let resumed = due_windows(Some(windows[2].1), since, now, 60);
+        assert!(resumed.is_empty());
+
+        // Switching from 15 to 60 minute windows skips to the next hour
+        // rather than overlapping windows already evaluated
+        let last_end = Utc.with_ymd_and_hms(2026, 3, 1, 10, 15, 0).unwrap();
+        let switched = due_windows(Some(last_end), since, now, 60);
+        assert_eq!(
+            switched[0].0,
+            Utc.with_ymd_and_hms(2026, 3, 1, 11, 0, 0).unwrap()
+        );
+
+        let backlog = due_windows(None, since - Duration::days(30), now, 60);
+        assert_eq!(backlog.len(), MAX_WINDOWS_PER_RUN);
+    }
+
+    #[test]
+    fn billing_periods_are_calendar_months() {
+        let (start, end) = billing_period(Utc.with_ymd_and_hms(2026, 12, 15, 8, 0, 0).unwrap());
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn credits_require_period_uptime_at_target() {
+        let mut period = SlaBillingPeriod {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            period_start: Utc::now(),
+            period_end: Utc::now(),
+            windows_evaluated: 720,
+            windows_compliant: 718,
+            violation_count: 2,
+            probe_count: 1000,
+            probe_successes: 998,
+            penalty_accrued: 100.0,
+            credits_issued: 0.0,
+            closed_at: None,
+        };
+        assert!((period_credit(&period, &targets()) - 10.0).abs() < 1e-9);
+
+        period.probe_successes = 990;
+        assert_eq!(period_credit(&period, &targets()), 0.0);
+
+        period.probe_count = 0;
+        assert_eq!(period_credit(&period, &targets()), 0.0);
+    }
+
+    #[test]
+    fn targets_validation_and_updates() {
+        let mut t = targets();
+        apply_targets_update(
+            &mut t,
+            SetSlaTargetsRequest {
+                max_latency_ms: Some(500.0),
+                probe_transaction: Some("AAAA".into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(t.max_latency_ms, 500.0);
+        assert!(validate_targets(&t).unwrap_err().contains("probe_function"));
+
+        apply_targets_update(
+            &mut t,
+            SetSlaTargetsRequest {
+                probe_function: Some("balance".into()),
+                ..Default::default()
+            },
+        );
+        assert!(validate_targets(&t).is_ok());
+
+        t.window_minutes = 7;
+        assert!(validate_targets(&t).is_err());
+
+        apply_targets_update(
+            &mut t,
+            SetSlaTargetsRequest {
+                window_minutes: Some(15),
+                probe_transaction: Some("  ".into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(t.probe_transaction, None);
+        assert!(validate_targets(&t).is_ok());
+    }
+
+    #[test]
+    fn simulation_errors_fail_the_probe() {
+        let success = json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "latestLedger": 100, "results": [{"xdr": "AAAAAQ==", "auth": []}]
+        }});
+        assert!(simulation_outcome(&success).is_ok());
+
+        let host_error = json!({"result": {"error": "HostError: Error(Contract, #3)"}});
+        assert_eq!(
+            simulation_outcome(&host_error).unwrap_err(),
+            "HostError: Error(Contract, #3)"
+        );
+
+        let rpc_error = json!({"error": {"code": -32602, "message": "invalid transaction"}});
+        assert_eq!(
+            simulation_outcome(&rpc_error).unwrap_err(),
+            "invalid transaction"
+        );
+
+        assert!(simulation_outcome(&json!({"result": {"latestLedger": 1}})).is_err());
+    }
+}
@@ -0,0 +1,418 @@
+// api/src/sla_handlers.rs
+//
+// Axum handlers for SLA monitoring. Measurements are produced by the
+// background SLA monitor (sla_monitor.rs); these handlers configure targets,
+// accept indexed invocation results and read back status and history.
+//
+// Routes (register in sla_routes.rs):
+//   GET    /api/contracts/:id/sla               → get_sla_status
+//   PUT    /api/contracts/:id/sla/targets       → set_sla_targets
+//   GET    /api/contracts/:id/sla/windows       → list_sla_windows
+//   GET    /api/contracts/:id/sla/violations    → list_sla_violations
+//   GET    /api/contracts/:id/sla/periods       → list_sla_periods
+//   POST   /api/contracts/:id/sla/probe         → run_sla_probe
+//   POST   /api/contracts/:id/invocations       → record_invocations
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use shared::{
+    RecordInvocationsRequest, SetSlaTargetsRequest, SlaBillingPeriod, SlaProbeResult, SlaStatus,
+    SlaTargets, SlaViolation, SlaWindowMetrics,
+};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    sla_engine, sla_monitor,
+    state::AppState,
+};
+
+const MAX_INVOCATIONS_PER_REQUEST: usize = 1000;
+
+fn db_err(op: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(operation = op, error = ?err, "sla database error");
+    ApiError::internal(format!("Database error while trying to {}", op))
+}
+
+async fn ensure_contract(state: &AppState, contract_id: Uuid) -> ApiResult<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contracts WHERE id = $1)")
+        .bind(contract_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_err("look up contract", e))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", contract_id),
+        ));
+    }
+    Ok(())
+}
+
+async fn fetch_targets(state: &AppState, contract_id: Uuid) -> ApiResult<SlaTargets> {
+    sqlx::query_as("SELECT * FROM sla_targets WHERE contract_id = $1")
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| db_err("fetch SLA targets", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "SlaNotConfigured",
+                format!("No SLA targets configured for contract {}", contract_id),
+            )
+        })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlaHistoryParams {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    pub since: Option<DateTime<Utc>>,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+impl SlaHistoryParams {
+    fn limit(&self) -> i64 {
+        self.limit.clamp(1, 500)
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/sla
+// ─────────────────────────────────────────────────────────
+
+pub async fn get_sla_status(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<SlaStatus>> {
+    let targets = fetch_targets(&state, contract_id).await?;
+
+    let latest_window: Option<SlaWindowMetrics> = sqlx::query_as(
+        "SELECT id, contract_id, uptime_percentage, p95_latency_ms, error_rate,
+                window_start, window_end, probe_count, invocation_count, compliant, recorded_at
+           FROM sla_metrics
+          WHERE contract_id = $1 AND window_start IS NOT NULL
+          ORDER BY window_start DESC
+          LIMIT 1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch latest SLA window", e))?;
+
+    let current_period: Option<SlaBillingPeriod> = sqlx::query_as(
+        "SELECT * FROM sla_billing_periods
+          WHERE contract_id = $1
+          ORDER BY period_start DESC
+          LIMIT 1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch SLA billing period", e))?;
+
+    let recent_violations: Vec<SlaViolation> = sqlx::query_as(
+        "SELECT * FROM sla_violations
+          WHERE contract_id = $1
+          ORDER BY window_start DESC, metric
+          LIMIT 10",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch SLA violations", e))?;
+
+    let totals: Option<(f64, f64, Option<DateTime<Utc>>)> = sqlx::query_as(
+        "SELECT penalty_accrued, credits_issued, last_window_end
+           FROM sla_status WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| db_err("fetch SLA totals", e))?;
+    let (penalty_accrued, credits_issued, last_window_end) = totals.unwrap_or((0.0, 0.0, None));
+
+    Ok(Json(SlaStatus {
+        contract_id,
+        targets,
+        compliant: latest_window.as_ref().map(|w| w.compliant).unwrap_or(true),
+        latest_window,
+        current_period,
+        recent_violations,
+        penalty_accrued,
+        credits_issued,
+        last_window_end,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// PUT /api/contracts/:id/sla/targets
+// ─────────────────────────────────────────────────────────
+
+/// Create or update a contract's targets. Omitted fields keep their current
+/// value (or the default for a new configuration). Changes apply to windows
+/// evaluated from now on; past windows are not re-evaluated.
+pub async fn set_sla_targets(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<SetSlaTargetsRequest>,
+) -> ApiResult<Json<SlaTargets>> {
+    ensure_contract(&state, contract_id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+
+    sqlx::query("INSERT INTO sla_targets (contract_id) VALUES ($1) ON CONFLICT DO NOTHING")
+        .bind(contract_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("create SLA targets", e))?;
+
+    let mut targets: SlaTargets =
+        sqlx::query_as("SELECT * FROM sla_targets WHERE contract_id = $1 FOR UPDATE")
+            .bind(contract_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| db_err("fetch SLA targets", e))?;
+
+    sla_engine::apply_targets_update(&mut targets, req);
+    sla_engine::validate_targets(&targets)
+        .map_err(|msg| ApiError::bad_request("InvalidSlaTargets", msg))?;
+
+    let targets: SlaTargets = sqlx::query_as(
+        "UPDATE sla_targets SET
+            min_uptime = $2, max_latency_ms = $3, max_error_rate = $4, window_minutes = $5,
+            penalty_per_violation = $6, credit_rate = $7, probe_function = $8,
+            probe_transaction = $9, probe_interval_seconds = $10, enabled = $11
+          WHERE contract_id = $1
+          RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(targets.min_uptime)
+    .bind(targets.max_latency_ms)
+    .bind(targets.max_error_rate)
+    .bind(targets.window_minutes)
+    .bind(targets.penalty_per_violation)
+    .bind(targets.credit_rate)
+    .bind(&targets.probe_function)
+    .bind(&targets.probe_transaction)
+    .bind(targets.probe_interval_seconds)
+    .bind(targets.enabled)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("update SLA targets", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit SLA targets", e))?;
+
+    Ok(Json(targets))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/sla/windows
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_sla_windows(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<SlaHistoryParams>,
+) -> ApiResult<Json<Vec<SlaWindowMetrics>>> {
+    let windows: Vec<SlaWindowMetrics> = sqlx::query_as(
+        "SELECT id, contract_id, uptime_percentage, p95_latency_ms, error_rate,
+                window_start, window_end, probe_count, invocation_count, compliant, recorded_at
+           FROM sla_metrics
+          WHERE contract_id = $1 AND window_start IS NOT NULL
+            AND ($2::timestamptz IS NULL OR window_start >= $2)
+          ORDER BY window_start DESC
+          LIMIT $3",
+    )
+    .bind(contract_id)
+    .bind(params.since)
+    .bind(params.limit())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list SLA windows", e))?;
+
+    Ok(Json(windows))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/sla/violations
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_sla_violations(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<SlaHistoryParams>,
+) -> ApiResult<Json<Vec<SlaViolation>>> {
+    let violations: Vec<SlaViolation> = sqlx::query_as(
+        "SELECT * FROM sla_violations
+          WHERE contract_id = $1 AND ($2::timestamptz IS NULL OR window_start >= $2)
+          ORDER BY window_start DESC, metric
+          LIMIT $3",
+    )
+    .bind(contract_id)
+    .bind(params.since)
+    .bind(params.limit())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list SLA violations", e))?;
+
+    Ok(Json(violations))
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/sla/periods
+// ─────────────────────────────────────────────────────────
+
+pub async fn list_sla_periods(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<SlaHistoryParams>,
+) -> ApiResult<Json<Vec<SlaBillingPeriod>>> {
+    let periods: Vec<SlaBillingPeriod> = sqlx::query_as(
+        "SELECT * FROM sla_billing_periods
+          WHERE contract_id = $1 AND ($2::timestamptz IS NULL OR period_start >= $2)
+          ORDER BY period_start DESC
+          LIMIT $3",
+    )
+    .bind(contract_id)
+    .bind(params.since)
+    .bind(params.limit())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list SLA billing periods", e))?;
+
+    Ok(Json(periods))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/contracts/:id/sla/probe
+// ─────────────────────────────────────────────────────────
+
+/// Run a probe immediately, e.g. to check a new probe transaction. The
+/// result counts towards the current window like any scheduled probe.
+pub async fn run_sla_probe(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<SlaProbeResult>> {
+    let targets = fetch_targets(&state, contract_id).await?;
+    if targets.probe_transaction.is_none() {
+        return Err(ApiError::unprocessable(
+            "ProbeNotConfigured",
+            "Set probe_function and probe_transaction on the SLA targets first",
+        ));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| ApiError::internal(format!("Failed to build RPC client: {}", e)))?;
+
+    sla_monitor::probe_contract(&state.db, &client, &targets)
+        .await
+        .map_err(|e| db_err("record SLA probe", e))?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::unprocessable(
+                "RpcNotConfigured",
+                "No Soroban RPC endpoint is configured for this contract's network",
+            )
+        })
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/contracts/:id/invocations
+// ─────────────────────────────────────────────────────────
+
+/// Ingest indexed invocation results. Results already recorded for the same
+/// transaction are ignored, so the indexer can safely re-send a range.
+pub async fn record_invocations(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<RecordInvocationsRequest>,
+) -> ApiResult<(StatusCode, Json<serde_json::Value>)> {
+    if req.invocations.is_empty() {
+        return Err(ApiError::bad_request(
+            "EmptyBatch",
+            "invocations must not be empty",
+        ));
+    }
+    if req.invocations.len() > MAX_INVOCATIONS_PER_REQUEST {
+        return Err(ApiError::bad_request(
+            "BatchTooLarge",
+            format!(
+                "At most {} invocations per request",
+                MAX_INVOCATIONS_PER_REQUEST
+            ),
+        ));
+    }
+    if let Some(bad) = req
+        .invocations
+        .iter()
+        .find(|i| i.function_name.trim().is_empty() || i.latency_ms.is_some_and(|l| l < 0.0))
+    {
+        return Err(ApiError::bad_request(
+            "InvalidInvocation",
+            format!(
+                "Invalid invocation result for function '{}'",
+                bad.function_name
+            ),
+        ));
+    }
+    ensure_contract(&state, contract_id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin transaction", e))?;
+
+    let mut recorded = 0u64;
+    for invocation in &req.invocations {
+        recorded += sqlx::query(
+            "INSERT INTO contract_invocations
+                (contract_id, function_name, success, latency_ms, error,
+                 transaction_hash, ledger_sequence, invoked_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, NOW()))
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(contract_id)
+        .bind(&invocation.function_name)
+        .bind(invocation.success)
+        .bind(invocation.latency_ms)
+        .bind(&invocation.error)
+        .bind(&invocation.transaction_hash)
+        .bind(invocation.ledger_sequence)
+        .bind(invocation.invoked_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_err("record invocation", e))?
+        .rows_affected();
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit invocations", e))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "recorded": recorded,
+            "duplicates": req.invocations.len() as u64 - recorded,
+        })),
+    ))
+}
@@ -0,0 +1,408 @@
+// api/src/sla_monitor.rs
+//
+// Background SLA monitor. Every tick it probes contracts whose probe is due,
+// evaluates completed windows against their targets and closes finished
+// billing periods. Probes simulate the contract's configured read-only call
+// through Soroban RPC, so they never submit a transaction.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use shared::{SlaBillingPeriod, SlaProbeResult, SlaTargets};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::sla_engine::{self, Sample};
+
+const TICK_SECS: u64 = 60;
+const PROBE_TIMEOUT_SECS: u64 = 10;
+/// Windows are evaluated this long after they end so late-indexed
+/// invocations still land in the right window.
+const INGESTION_LAG_MINUTES: i64 = 5;
+
+pub fn spawn_sla_monitor(pool: PgPool) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+            .build()
+            .expect("failed to build SLA probe client");
+        let mut interval = tokio::time::interval(Duration::from_secs(TICK_SECS));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = run_due_probes(&pool, &client).await {
+                tracing::error!(error = ?err, "sla monitor: probe run failed");
+            }
+            let cutoff = Utc::now() - chrono::Duration::minutes(INGESTION_LAG_MINUTES);
+            if let Err(err) = evaluate_all(&pool, cutoff).await {
+                tracing::error!(error = ?err, "sla monitor: evaluation failed");
+            }
+            if let Err(err) = close_finished_periods(&pool, Utc::now()).await {
+                tracing::error!(error = ?err, "sla monitor: closing billing periods failed");
+            }
+        }
+    });
+}
+
+/// Soroban RPC endpoint for a network, from `SOROBAN_RPC_URL_<NETWORK>`.
+/// Public endpoints are used for test networks when unset; mainnet has no
+/// default because public mainnet RPC is rate limited.
+fn rpc_url(network: &str) -> Option<String> {
+    std::env::var(format!("SOROBAN_RPC_URL_{}", network.to_uppercase()))
+        .ok()
+        .or_else(|| match network {
+            "testnet" => Some("https://soroban-testnet.stellar.org".into()),
+            "futurenet" => Some("https://rpc-futurenet.stellar.org".into()),
+            _ => None,
+        })
+}
+
+// ─────────────────────────────────────────────────────────
+// Probes
+// ─────────────────────────────────────────────────────────
+
+async fn simulate(
+    client: &reqwest::Client,
+    url: &str,
+    transaction: &str,
+) -> (Result<(), String>, f64) {
+    let started = Instant::now();
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "simulateTransaction",
+            "params": { "transaction": transaction },
+        }))
+        .send()
+        .await;
+    let outcome = match response {
+        Ok(resp) => match resp.json::<serde_json::Value>().await {
+            Ok(body) => sla_engine::simulation_outcome(&body),
+            Err(err) => Err(format!("invalid RPC response: {}", err)),
+        },
+        Err(err) if err.is_timeout() => Err("probe timed out".into()),
+        Err(err) => Err(format!("RPC request failed: {}", err)),
+    };
+    (outcome, started.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Run one probe for a contract and store its result. Returns `None`
+/// without recording anything when the contract has no probe transaction
+/// or no RPC endpoint is configured for its network, so a registry
+/// misconfiguration never counts as contract downtime.
+pub async fn probe_contract(
+    pool: &PgPool,
+    client: &reqwest::Client,
+    targets: &SlaTargets,
+) -> Result<Option<SlaProbeResult>, sqlx::Error> {
+    let Some(transaction) = targets.probe_transaction.as_deref() else {
+        return Ok(None);
+    };
+    let network: String = sqlx::query_scalar("SELECT network::text FROM contracts WHERE id = $1")
+        .bind(targets.contract_id)
+        .fetch_one(pool)
+        .await?;
+    let Some(url) = rpc_url(&network) else {
+        tracing::warn!(
+            network,
+            "sla monitor: no Soroban RPC configured, skipping probe"
+        );
+        return Ok(None);
+    };
+
+    let (outcome, latency_ms) = simulate(client, &url, transaction).await;
+
+    sqlx::query_as(
+        "INSERT INTO sla_probe_results (contract_id, function_name, success, latency_ms, error)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING *",
+    )
+    .bind(targets.contract_id)
+    .bind(&targets.probe_function)
+    .bind(outcome.is_ok())
+    .bind(latency_ms)
+    .bind(outcome.err())
+    .fetch_one(pool)
+    .await
+    .map(Some)
+}
+
+async fn run_due_probes(pool: &PgPool, client: &reqwest::Client) -> Result<(), sqlx::Error> {
+    let due: Vec<SlaTargets> = sqlx::query_as(
+        "SELECT t.* FROM sla_targets t
+          WHERE t.enabled AND t.probe_transaction IS NOT NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM sla_probe_results p
+                 WHERE p.contract_id = t.contract_id
+                   AND p.probed_at > NOW() - make_interval(secs => t.probe_interval_seconds)
+            )",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for targets in &due {
+        let Some(result) = probe_contract(pool, client, targets).await? else {
+            continue;
+        };
+        if !result.success {
+            tracing::warn!(
+                contract_id = %targets.contract_id,
+                error = result.error.as_deref().unwrap_or_default(),
+                "sla monitor: probe failed"
+            );
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Evaluation
+// ─────────────────────────────────────────────────────────
+
+async fn load_samples(
+    pool: &PgPool,
+    table_query: &str,
+    contract_id: Uuid,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<Sample>, sqlx::Error> {
+    let rows: Vec<(bool, Option<f64>)> = sqlx::query_as(table_query)
+        .bind(contract_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(success, latency_ms)| Sample {
+            success,
+            latency_ms,
+        })
+        .collect())
+}
+
+/// Evaluate one window. Returns `false` if the window had already been
+/// evaluated (e.g. by a concurrent run).
+async fn evaluate_window(
+    pool: &PgPool,
+    targets: &SlaTargets,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let probes = load_samples(
+        pool,
+        // A failed probe's round trip says nothing about serving latency
+        "SELECT success, CASE WHEN success THEN latency_ms END FROM sla_probe_results
+          WHERE contract_id = $1 AND probed_at >= $2 AND probed_at < $3",
+        targets.contract_id,
+        start,
+        end,
+    )
+    .await?;
+    let invocations = load_samples(
+        pool,
+        "SELECT success, latency_ms FROM contract_invocations
+          WHERE contract_id = $1 AND invoked_at >= $2 AND invoked_at < $3",
+        targets.contract_id,
+        start,
+        end,
+    )
+    .await?;
+
+    let measurement = sla_engine::measure_window(&probes, &invocations);
+    let breaches = sla_engine::check_violations(&measurement, targets);
+    let penalty = sla_engine::window_penalty(&breaches, targets);
+    let compliant = breaches.is_empty();
+
+    let mut tx = pool.begin().await?;
+
+    let inserted: Option<Uuid> = sqlx::query_scalar(
+        "INSERT INTO sla_metrics
+            (contract_id, uptime_percentage, p95_latency_ms, error_rate,
+             window_start, window_end, probe_count, invocation_count, compliant)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (contract_id, window_start) DO NOTHING
+         RETURNING id",
+    )
+    .bind(targets.contract_id)
+    .bind(measurement.uptime)
+    .bind(measurement.p95_latency_ms)
+    .bind(measurement.error_rate)
+    .bind(start)
+    .bind(end)
+    .bind(measurement.probe_count)
+    .bind(measurement.invocation_count)
+    .bind(compliant)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if inserted.is_none() {
+        return Ok(false);
+    }
+
+    let (period_start, period_end) = sla_engine::billing_period(start);
+    let period_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO sla_billing_periods
+            (contract_id, period_start, period_end, windows_evaluated, windows_compliant,
+             violation_count, probe_count, probe_successes, penalty_accrued)
+         VALUES ($1, $2, $3, 1, $4, $5, $6, $7, $8)
+         ON CONFLICT (contract_id, period_start) DO UPDATE SET
+            windows_evaluated = sla_billing_periods.windows_evaluated + 1,
+            windows_compliant = sla_billing_periods.windows_compliant + EXCLUDED.windows_compliant,
+            violation_count   = sla_billing_periods.violation_count + EXCLUDED.violation_count,
+            probe_count       = sla_billing_periods.probe_count + EXCLUDED.probe_count,
+            probe_successes   = sla_billing_periods.probe_successes + EXCLUDED.probe_successes,
+            penalty_accrued   = sla_billing_periods.penalty_accrued + EXCLUDED.penalty_accrued
+         RETURNING id",
+    )
+    .bind(targets.contract_id)
+    .bind(period_start)
+    .bind(period_end)
+    .bind(compliant as i32)
+    .bind(breaches.len() as i32)
+    .bind(measurement.probe_count)
+    .bind(measurement.probe_successes)
+    .bind(penalty)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for breach in &breaches {
+        sqlx::query(
+            "INSERT INTO sla_violations
+                (contract_id, billing_period_id, metric, actual, target,
+                 window_start, window_end, penalty)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(targets.contract_id)
+        .bind(period_id)
+        .bind(breach.metric.as_str())
+        .bind(breach.actual)
+        .bind(breach.target)
+        .bind(start)
+        .bind(end)
+        .bind(targets.penalty_per_violation)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "INSERT INTO sla_status
+            (contract_id, total_records, violations_count, penalty_accrued, compliant, last_window_end)
+         VALUES ($1, 1, $2, $3, $4, $5)
+         ON CONFLICT (contract_id) DO UPDATE SET
+            total_records    = sla_status.total_records + 1,
+            violations_count = sla_status.violations_count + EXCLUDED.violations_count,
+            penalty_accrued  = sla_status.penalty_accrued + EXCLUDED.penalty_accrued,
+            compliant        = EXCLUDED.compliant,
+            last_window_end  = EXCLUDED.last_window_end,
+            updated_at       = NOW()",
+    )
+    .bind(targets.contract_id)
+    .bind(breaches.len() as i32)
+    .bind(penalty)
+    .bind(compliant)
+    .bind(end)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Evaluate every completed window for a contract that has not been
+/// evaluated yet. Returns the number of windows evaluated.
+pub async fn evaluate_contract(
+    pool: &PgPool,
+    targets: &SlaTargets,
+    now: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    let last_window_end: Option<DateTime<Utc>> =
+        sqlx::query_scalar("SELECT last_window_end FROM sla_status WHERE contract_id = $1")
+            .bind(targets.contract_id)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+    let mut evaluated = 0;
+    for (start, end) in sla_engine::due_windows(
+        last_window_end,
+        targets.created_at,
+        now,
+        targets.window_minutes,
+    ) {
+        if evaluate_window(pool, targets, start, end).await? {
+            evaluated += 1;
+        }
+    }
+    Ok(evaluated)
+}
+
+async fn evaluate_all(pool: &PgPool, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let all: Vec<SlaTargets> = sqlx::query_as("SELECT * FROM sla_targets WHERE enabled")
+        .fetch_all(pool)
+        .await?;
+
+    for targets in &all {
+        let evaluated = evaluate_contract(pool, targets, now).await?;
+        if evaluated > 0 {
+            tracing::debug!(contract_id = %targets.contract_id, evaluated, "sla monitor: windows evaluated");
+        }
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Billing
+// ─────────────────────────────────────────────────────────
+
+async fn close_finished_periods(pool: &PgPool, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+    let finished: Vec<SlaBillingPeriod> = sqlx::query_as(
+        "SELECT * FROM sla_billing_periods WHERE closed_at IS NULL AND period_end <= $1",
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    for period in &finished {
+        let targets: Option<SlaTargets> =
+            sqlx::query_as("SELECT * FROM sla_targets WHERE contract_id = $1")
+                .bind(period.contract_id)
+                .fetch_optional(pool)
+                .await?;
+        let credit = targets
+            .map(|t| sla_engine::period_credit(period, &t))
+            .unwrap_or(0.0);
+
+        let mut tx = pool.begin().await?;
+        let closed = sqlx::query(
+            "UPDATE sla_billing_periods SET credits_issued = $2, closed_at = NOW()
+              WHERE id = $1 AND closed_at IS NULL",
+        )
+        .bind(period.id)
+        .bind(credit)
+        .execute(&mut *tx)
+        .await?;
+        if closed.rows_affected() == 1 && credit > 0.0 {
+            sqlx::query(
+                "UPDATE sla_status SET credits_issued = credits_issued + $2, updated_at = NOW()
+                  WHERE contract_id = $1",
+            )
+            .bind(period.contract_id)
+            .bind(credit)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        tracing::info!(
+            contract_id = %period.contract_id,
+            period_start = %period.period_start,
+            penalty = period.penalty_accrued,
+            credit,
+            "sla monitor: billing period closed"
+        );
+    }
+    Ok(())
+}
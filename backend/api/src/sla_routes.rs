@@ -0,0 +1,40 @@
+// sla_routes.rs
+// Route definitions for SLA monitoring
+
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{sla_handlers, state::AppState};
+
+pub fn sla_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/contracts/:id/sla", get(sla_handlers::get_sla_status))
+        .route(
+            "/api/contracts/:id/sla/targets",
+            put(sla_handlers::set_sla_targets),
+        )
+        // History
+        .route(
+            "/api/contracts/:id/sla/windows",
+            get(sla_handlers::list_sla_windows),
+        )
+        .route(
+            "/api/contracts/:id/sla/violations",
+            get(sla_handlers::list_sla_violations),
+        )
+        .route(
+            "/api/contracts/:id/sla/periods",
+            get(sla_handlers::list_sla_periods),
+        )
+        // Measurement inputs
+        .route(
+            "/api/contracts/:id/sla/probe",
+            post(sla_handlers::run_sla_probe),
+        )
+        .route(
+            "/api/contracts/:id/invocations",
+            post(sla_handlers::record_invocations),
+        )
+}
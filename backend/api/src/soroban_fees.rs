@@ -0,0 +1,399 @@
+// api/src/soroban_fees.rs
+//
+// Soroban resource fee calculator. Mirrors the host's fee formula:
+//
+//   non-refundable = compute + ledger entry reads/writes + ledger bytes
+//                    read/written + historical data + transaction size
+//   refundable     = contract events + rent
+//
+// plus the classic inclusion fee per operation. Rates come from the
+// network's ConfigSetting ledger entries; handlers load them from the
+// indexer's `network_fee_configs` table or a JSON file, falling back to the
+// mainnet values below. All functions here are synchronous.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use shared::models::{CostEstimate, ResourceFeeBreakdown, SorobanResources, StorageDurability};
+
+pub const STROOPS_PER_XLM: i64 = 10_000_000;
+const INSTRUCTIONS_INCREMENT: i64 = 10_000;
+const DATA_SIZE_1KB_INCREMENT: i64 = 1024;
+/// Result size the host adds to the transaction size for the history fee
+const TX_BASE_RESULT_SIZE: i64 = 300;
+/// Size of the TTL entry rewritten when an entry's lifetime is extended
+const TTL_ENTRY_SIZE: i64 = 48;
+
+/// Environment variable naming a JSON file of `{ "<network>": FeeConfig }`
+pub const FEE_CONFIG_PATH_ENV: &str = "SOROBAN_FEE_CONFIG";
+
+/// Network fee settings. Missing fields take the mainnet defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeConfig {
+    /// Per 10,000 instructions
+    pub fee_per_instruction_increment: i64,
+    pub fee_per_read_entry: i64,
+    pub fee_per_write_entry: i64,
+    pub fee_per_read_1kb: i64,
+    pub fee_per_write_1kb: i64,
+    /// Rent rate per KB; the host derives it from the write fee
+    pub fee_per_rent_1kb: i64,
+    pub fee_per_historical_1kb: i64,
+    pub fee_per_contract_event_1kb: i64,
+    pub fee_per_transaction_size_1kb: i64,
+    pub persistent_rent_rate_denominator: i64,
+    pub temporary_rent_rate_denominator: i64,
+    pub min_persistent_ttl: u32,
+    pub min_temporary_ttl: u32,
+    /// Inclusion fee bid per operation
+    pub base_inclusion_fee: i64,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            fee_per_instruction_increment: 25,
+            fee_per_read_entry: 6_250,
+            fee_per_write_entry: 10_000,
+            fee_per_read_1kb: 1_786,
+            fee_per_write_1kb: 11_800,
+            fee_per_rent_1kb: 11_800,
+            fee_per_historical_1kb: 16_235,
+            fee_per_contract_event_1kb: 10_000,
+            fee_per_transaction_size_1kb: 1_624,
+            persistent_rent_rate_denominator: 2_103,
+            temporary_rent_rate_denominator: 4_206,
+            // ~120 days of 5-second ledgers
+            min_persistent_ttl: 2_073_600,
+            min_temporary_ttl: 16,
+            base_inclusion_fee: 100,
+        }
+    }
+}
+
+static FILE_CONFIGS: Lazy<HashMap<String, FeeConfig>> = Lazy::new(|| {
+    let Ok(path) = std::env::var(FEE_CONFIG_PATH_ENV) else {
+        return HashMap::new();
+    };
+    match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+    {
+        Ok(configs) => configs,
+        Err(err) => {
+            tracing::warn!(path = %path, error = %err, "ignoring unreadable Soroban fee config");
+            HashMap::new()
+        }
+    }
+});
+
+/// Fee settings for a network from the file named by `SOROBAN_FEE_CONFIG`.
+pub fn file_config(network: &str) -> Option<FeeConfig> {
+    FILE_CONFIGS.get(network).cloned()
+}
+
+fn ceil_div(numerator: i64, denominator: i64) -> i64 {
+    (numerator + denominator - 1) / denominator
+}
+
+fn per_kb(bytes: i64, fee_per_1kb: i64) -> i64 {
+    ceil_div(bytes * fee_per_1kb, DATA_SIZE_1KB_INCREMENT)
+}
+
+/// Resource fee for one invocation, excluding rent and the inclusion fee.
+pub fn invocation_fee(resources: &SorobanResources, cfg: &FeeConfig) -> ResourceFeeBreakdown {
+    let tx_size = resources.transaction_size_bytes as i64;
+    let compute = ceil_div(
+        resources.instructions as i64 * cfg.fee_per_instruction_increment,
+        INSTRUCTIONS_INCREMENT,
+    );
+    // Every written entry is also read
+    let read_entries =
+        cfg.fee_per_read_entry * (resources.read_entries as i64 + resources.write_entries as i64);
+    let write_entries = cfg.fee_per_write_entry * resources.write_entries as i64;
+    let read_bytes = per_kb(resources.read_bytes as i64, cfg.fee_per_read_1kb);
+    let write_bytes = per_kb(resources.write_bytes as i64, cfg.fee_per_write_1kb);
+    let historical = per_kb(tx_size + TX_BASE_RESULT_SIZE, cfg.fee_per_historical_1kb);
+    let bandwidth = per_kb(tx_size, cfg.fee_per_transaction_size_1kb);
+    let events = per_kb(
+        resources.events_bytes as i64,
+        cfg.fee_per_contract_event_1kb,
+    );
+
+    ResourceFeeBreakdown {
+        compute,
+        read_entries,
+        write_entries,
+        read_bytes,
+        write_bytes,
+        historical,
+        bandwidth,
+        events,
+        rent: 0,
+        non_refundable: compute
+            + read_entries
+            + write_entries
+            + read_bytes
+            + write_bytes
+            + historical
+            + bandwidth,
+        refundable: events,
+    }
+}
+
+/// Change to one ledger entry's size or lifetime within a transaction.
+#[derive(Debug, Clone, Copy)]
+pub struct RentChange {
+    pub persistent: bool,
+    pub old_size_bytes: u32,
+    pub new_size_bytes: u32,
+    /// For a new entry, the ledger before the current one
+    pub old_live_until: u32,
+    pub new_live_until: u32,
+}
+
+impl RentChange {
+    /// A newly created entry that lives for `ttl` ledgers.
+    pub fn new_entry(persistent: bool, size_bytes: u32, current_ledger: u32, ttl: u32) -> Self {
+        Self {
+            persistent,
+            old_size_bytes: 0,
+            new_size_bytes: size_bytes,
+            old_live_until: current_ledger.saturating_sub(1),
+            new_live_until: current_ledger.saturating_sub(1) + ttl,
+        }
+    }
+}
+
+fn rent_for(persistent: bool, size_bytes: i64, ledgers: i64, cfg: &FeeConfig) -> i64 {
+    let denominator = if persistent {
+        cfg.persistent_rent_rate_denominator
+    } else {
+        cfg.temporary_rent_rate_denominator
+    };
+    let numerator = size_bytes as i128 * cfg.fee_per_rent_1kb as i128 * ledgers as i128;
+    let denominator = DATA_SIZE_1KB_INCREMENT as i128 * denominator.max(1) as i128;
+    ((numerator + denominator - 1) / denominator) as i64
+}
+
+/// Rent fee for a set of entry changes, including rewriting the TTL
+/// entries of every entry whose lifetime is extended.
+pub fn rent_fee(changes: &[RentChange], current_ledger: u32, cfg: &FeeConfig) -> i64 {
+    let mut fee = 0;
+    let mut extended = 0i64;
+    for c in changes {
+        if c.new_live_until > c.old_live_until {
+            let ledgers = (c.new_live_until - c.old_live_until) as i64;
+            fee += rent_for(c.persistent, c.new_size_bytes as i64, ledgers, cfg);
+            extended += 1;
+        }
+        // Growing a live entry pays for the extra bytes over its remaining TTL
+        if c.new_size_bytes > c.old_size_bytes && c.old_live_until >= current_ledger {
+            let growth = (c.new_size_bytes - c.old_size_bytes) as i64;
+            let remaining = (c.old_live_until - current_ledger) as i64 + 1;
+            fee += rent_for(c.persistent, growth, remaining, cfg);
+        }
+    }
+    fee + extended * cfg.fee_per_write_entry
+        + per_kb(extended * TTL_ENTRY_SIZE, cfg.fee_per_write_1kb)
+}
+
+/// New contract data written over the estimate's period.
+#[derive(Debug, Clone, Copy)]
+pub struct StorageGrowth {
+    pub bytes: i64,
+    pub durability: StorageDurability,
+    /// Lifetime paid for up front; defaults to the network minimum
+    pub rent_ledgers: Option<u32>,
+}
+
+/// Assumed size of one new entry when splitting growth into entries.
+const GROWTH_ENTRY_BYTES: i64 = 1024;
+
+/// Fee for writing `growth` as new entries and paying their rent.
+pub fn storage_growth_fee(growth: &StorageGrowth, cfg: &FeeConfig) -> i64 {
+    if growth.bytes <= 0 {
+        return 0;
+    }
+    let persistent = growth.durability == StorageDurability::Persistent;
+    let ttl = growth.rent_ledgers.unwrap_or(if persistent {
+        cfg.min_persistent_ttl
+    } else {
+        cfg.min_temporary_ttl
+    });
+    let entries = ceil_div(growth.bytes, GROWTH_ENTRY_BYTES);
+    let changes: Vec<RentChange> = (0..entries)
+        .map(|i| {
+            let size = (growth.bytes - i * GROWTH_ENTRY_BYTES).min(GROWTH_ENTRY_BYTES) as u32;
+            RentChange::new_entry(persistent, size, 1, ttl)
+        })
+        .collect();
+    entries * (cfg.fee_per_write_entry + cfg.fee_per_read_entry)
+        + per_kb(growth.bytes, cfg.fee_per_write_1kb)
+        + rent_fee(&changes, 1, cfg)
+}
+
+/// Cost of `invocations` calls with the given per-call resources, plus
+/// any storage growth. Bucketed the way the API has always reported it:
+/// gas = compute, storage = ledger access and rent, bandwidth = transaction
+/// size, history and events.
+pub fn estimate(
+    method_name: String,
+    invocations: i64,
+    per_call: &SorobanResources,
+    growth: &StorageGrowth,
+    cfg: &FeeConfig,
+) -> CostEstimate {
+    let fee = invocation_fee(per_call, cfg);
+    let n = invocations.max(0);
+
+    let gas_cost = fee.compute * n;
+    let storage_cost =
+        (fee.read_entries + fee.write_entries + fee.read_bytes + fee.write_bytes + fee.rent) * n
+            + storage_growth_fee(growth, cfg);
+    let bandwidth_cost = (fee.historical + fee.bandwidth + fee.events) * n;
+    let inclusion_fee = cfg.base_inclusion_fee * n;
+    let total_stroops = gas_cost + storage_cost + bandwidth_cost + inclusion_fee;
+
+    CostEstimate {
+        method_name,
+        gas_cost,
+        storage_cost,
+        bandwidth_cost,
+        inclusion_fee,
+        total_stroops,
+        total_xlm: total_stroops as f64 / STROOPS_PER_XLM as f64,
+        invocations: n,
+        per_invocation: Some(fee),
+        resources: Some(*per_call),
+        resource_source: None,
+        fee_config_source: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap() -> SorobanResources {
+        SorobanResources {
+            instructions: 2_500_000,
+            read_entries: 3,
+            write_entries: 2,
+            read_bytes: 3_000,
+            write_bytes: 1_500,
+            events_bytes: 400,
+            transaction_size_bytes: 1_200,
+        }
+    }
+
+    #[test]
+    fn invocation_fee_follows_the_host_formula() {
+        let fee = invocation_fee(&swap(), &FeeConfig::default());
+        assert_eq!(fee.compute, 6_250); // 2.5M / 10k × 25
+        assert_eq!(fee.read_entries, 31_250); // (3 + 2) × 6,250
+        assert_eq!(fee.write_entries, 20_000);
+        assert_eq!(fee.read_bytes, 5_233); // ⌈3000 × 1786 / 1024⌉
+        assert_eq!(fee.write_bytes, 17_286); // ⌈1500 × 11800 / 1024⌉
+        assert_eq!(fee.historical, 23_782); // ⌈(1200 + 300) × 16235 / 1024⌉
+        assert_eq!(fee.bandwidth, 1_904);
+        assert_eq!(fee.events, 3_907);
+        assert_eq!(fee.non_refundable, 105_705);
+        assert_eq!(fee.refundable, 3_907);
+    }
+
+    #[test]
+    fn rent_scales_with_size_ttl_and_durability() {
+        let cfg = FeeConfig::default();
+        let persistent = rent_fee(
+            &[RentChange::new_entry(true, 1_000, 100, 10_000)],
+            100,
+            &cfg,
+        );
+        let temporary = rent_fee(
+            &[RentChange::new_entry(false, 1_000, 100, 10_000)],
+            100,
+            &cfg,
+        );
+        let longer = rent_fee(
+            &[RentChange::new_entry(true, 1_000, 100, 20_000)],
+            100,
+            &cfg,
+        );
+
+        // ⌈1000 × 11800 × 10000 / (1024 × 2103)⌉ + write entry + TTL entry bytes
+        let ttl_overhead = 10_000 + per_kb(48, 11_800);
+        assert_eq!(persistent, 54_796 + ttl_overhead);
+        assert!(temporary < persistent);
+        assert!(longer - ttl_overhead > 2 * (persistent - ttl_overhead) - 2);
+    }
+
+    #[test]
+    fn growing_a_live_entry_pays_for_remaining_ttl_only() {
+        let cfg = FeeConfig::default();
+        let grow = RentChange {
+            persistent: true,
+            old_size_bytes: 1_000,
+            new_size_bytes: 2_024,
+            old_live_until: 1_099,
+            new_live_until: 1_099,
+        };
+        // 1,024 extra bytes for the 100 ledgers left; no extension, no TTL write
+        assert_eq!(
+            rent_fee(&[grow], 1_000, &cfg),
+            rent_for(true, 1_024, 100, &cfg)
+        );
+        let expired = RentChange {
+            old_live_until: 900,
+            new_live_until: 900,
+            ..grow
+        };
+        assert_eq!(rent_fee(&[expired], 1_000, &cfg), 0);
+    }
+
+    #[test]
+    fn estimate_buckets_add_up_and_scale_with_invocations() {
+        let cfg = FeeConfig::default();
+        let none = StorageGrowth {
+            bytes: 0,
+            durability: StorageDurability::Persistent,
+            rent_ledgers: None,
+        };
+        let one = estimate("swap".into(), 1, &swap(), &none, &cfg);
+        assert_eq!(one.total_stroops, 105_705 + 3_907 + 100);
+        assert_eq!(
+            one.gas_cost + one.storage_cost + one.bandwidth_cost + one.inclusion_fee,
+            one.total_stroops
+        );
+
+        let ten = estimate("swap".into(), 10, &swap(), &none, &cfg);
+        assert_eq!(ten.total_stroops, 10 * one.total_stroops);
+
+        let growth = StorageGrowth {
+            bytes: 4 * 1024,
+            ..none
+        };
+        let grown = estimate("swap".into(), 10, &swap(), &growth, &cfg);
+        assert_eq!(
+            grown.storage_cost - ten.storage_cost,
+            storage_growth_fee(&growth, &cfg)
+        );
+        let temporary = StorageGrowth {
+            durability: StorageDurability::Temporary,
+            ..growth
+        };
+        assert!(storage_growth_fee(&temporary, &cfg) < storage_growth_fee(&growth, &cfg));
+    }
+
+    #[test]
+    fn partial_configs_fill_in_defaults() {
+        let cfg: FeeConfig = serde_json::from_str(r#"{"fee_per_write_1kb": 9000}"#).unwrap();
+        assert_eq!(cfg.fee_per_write_1kb, 9_000);
+        assert_eq!(
+            cfg.fee_per_read_entry,
+            FeeConfig::default().fee_per_read_entry
+        );
+    }
+}
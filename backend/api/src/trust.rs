@@ -2,7 +2,12 @@
 //
 // Contract Trust Scoring Engine
 //
-// ── Score breakdown (max 100 points) ────────────────────────────────────────
+// Scores are computed under a trust policy: versioned configuration holding
+// factor weights, caps, required minimums and a deploy gate threshold.
+// Policies are stored in `trust_policies`; the built-in `default` policy
+// reproduces the original fixed weights.
+//
+// ── Default policy (max 100 points) ─────────────────────────────────────────
 //
 //  Factor                  Weight   Description
 //  ──────────────────────  ──────   ────────────────────────────────────────
@@ -10,7 +15,11 @@
 //  Audit quality             35 pt  latest audit overall_score × 0.35
 //  Usage / adoption          20 pt  deployments + interactions, capped at 20
 //  Contract age              10 pt  days since created_at, capped at 10
-//  No critical vulns         10 pt  −10 per unresolved critical audit failure
+//  No critical vulns         10 pt  −5 per unresolved critical vulnerability
+//
+// Under other weights the earned points are scaled so the score stays 0–100.
+// Requirements are reported separately from the score: a contract can score
+// well and still fail "must be verified".
 //
 // ── Trust tiers ─────────────────────────────────────────────────────────────
 //
@@ -20,37 +29,16 @@
 //  75–89    Gold
 //  50–74    Silver
 //   0–49    Bronze
-//
-// All weights are defined as constants so they are easy to audit and adjust.
 
 use chrono::Utc;
 use serde::Serialize;
+use shared::TrustPolicyConfig;
 
-// ── Weight constants ──────────────────────────────────────────────────────────
-
-/// Maximum points awarded for on-chain verification
-pub const WEIGHT_VERIFIED: f64 = 25.0;
-
-/// Maximum points from audit quality (latest audit score × this fraction)
-pub const WEIGHT_AUDIT: f64 = 35.0;
-
-/// Maximum points from usage/adoption signals
-pub const WEIGHT_USAGE: f64 = 20.0;
-
-/// Maximum points from contract age
-pub const WEIGHT_AGE: f64 = 10.0;
-
-/// Maximum points from having no critical vulnerabilities
-pub const WEIGHT_NO_VULNS: f64 = 10.0;
-
-/// Number of deployments needed to earn full usage points
-const USAGE_DEPLOYMENT_CAP: f64 = 50.0;
+/// Policy used when a request names none; also drives `contracts.trust_score`
+pub const DEFAULT_POLICY: &str = "default";
 
-/// Number of interactions needed to contribute to usage points
-const USAGE_INTERACTION_CAP: f64 = 500.0;
-
-/// Days of age needed to earn full age points
-const AGE_DAYS_CAP: f64 = 180.0;
+/// Share of usage points from deployments; interactions earn the rest
+const USAGE_DEPLOYMENT_SHARE: f64 = 0.6;
 
 // ── Input data ────────────────────────────────────────────────────────────────
 
@@ -71,7 +59,7 @@ pub struct TrustInput {
     /// Contract creation timestamp (used to compute age)
     pub created_at: chrono::DateTime<Utc>,
 
-    /// Number of unresolved critical-severity audit check failures
+    /// Number of unresolved critical-severity vulnerabilities
     pub unresolved_critical_vulns: i64,
 }
 
@@ -84,12 +72,20 @@ pub struct TrustFactor {
     pub name: &'static str,
     /// Points earned for this factor
     pub points_earned: f64,
-    /// Maximum possible points for this factor
+    /// Maximum possible points for this factor under the policy
     pub points_max: f64,
     /// Plain-English explanation of why this score was given
     pub explanation: String,
 }
 
+/// Outcome of one policy requirement
+#[derive(Debug, Serialize)]
+pub struct RequirementCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub explanation: String,
+}
+
 /// Full trust score response
 #[derive(Debug, Serialize)]
 pub struct TrustScore {
@@ -101,10 +97,34 @@ pub struct TrustScore {
     pub badge_icon: &'static str,
     /// Individual factor breakdown
     pub factors: Vec<TrustFactor>,
+    /// Requirements the policy imposes, in declaration order
+    pub requirements: Vec<RequirementCheck>,
+    /// True when every requirement passed
+    pub meets_requirements: bool,
     /// Human-readable summary
     pub summary: String,
 }
 
+impl TrustScore {
+    /// Reasons this contract fails the policy's deploy gate; empty when it
+    /// passes.
+    pub fn gate_failures(&self, policy: &TrustPolicyConfig) -> Vec<String> {
+        let mut reasons: Vec<String> = self
+            .requirements
+            .iter()
+            .filter(|r| !r.passed)
+            .map(|r| r.explanation.clone())
+            .collect();
+        if self.score < policy.gate_threshold {
+            reasons.push(format!(
+                "Trust score {:.1} is below the threshold of {:.1}.",
+                self.score, policy.gate_threshold
+            ));
+        }
+        reasons
+    }
+}
+
 // ── Badge assignment ──────────────────────────────────────────────────────────
 
 /// Map a numeric score to a trust tier badge.
@@ -123,22 +143,77 @@ pub fn trust_badge(score: f64) -> (&'static str, &'static str) {
     }
 }
 
+// ── Policy validation ─────────────────────────────────────────────────────────
+
+/// Policy names are lowercase slugs so they read well in query strings.
+pub fn validate_policy_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err("policy name must be 1–64 lowercase letters, digits, '-' or '_'".into())
+    }
+}
+
+/// Reject configurations that cannot produce a meaningful 0–100 score.
+pub fn validate_policy(config: &TrustPolicyConfig) -> Result<(), String> {
+    let w = &config.weights;
+    let weights = [w.verification, w.audit, w.usage, w.age, w.vulnerabilities];
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err("weights must be finite and non-negative".into());
+    }
+    if weights.iter().sum::<f64>() <= 0.0 {
+        return Err("at least one weight must be positive".into());
+    }
+
+    let c = &config.caps;
+    if c.deployments <= 0 || c.interactions <= 0 || c.age_days <= 0 {
+        return Err("caps.deployments, caps.interactions and caps.age_days must be positive".into());
+    }
+    if !c.penalty_per_vulnerability.is_finite() || c.penalty_per_vulnerability < 0.0 {
+        return Err("caps.penalty_per_vulnerability must be non-negative".into());
+    }
+
+    let r = &config.requirements;
+    if r.min_audit_score.is_some_and(|s| !(0.0..=100.0).contains(&s)) {
+        return Err("requirements.min_audit_score must be between 0 and 100".into());
+    }
+    if r.max_critical_vulns.is_some_and(|n| n < 0) || r.min_age_days.is_some_and(|d| d < 0) {
+        return Err("requirements.max_critical_vulns and requirements.min_age_days must be non-negative".into());
+    }
+    if !(0.0..=100.0).contains(&config.gate_threshold) {
+        return Err("gate_threshold must be between 0 and 100".into());
+    }
+    Ok(())
+}
+
 // ── Scoring engine ────────────────────────────────────────────────────────────
 
-/// Compute the composite trust score from the collected input signals.
-///
-/// Returns a fully-populated [`TrustScore`] with per-factor breakdown.
+/// Compute the trust score under the built-in default policy.
 pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
+    score_with_policy(input, &TrustPolicyConfig::default())
+}
+
+/// Compute the composite trust score from the collected input signals under
+/// `policy`.
+///
+/// Returns a fully-populated [`TrustScore`] with per-factor breakdown and
+/// requirement checks.
+pub fn score_with_policy(input: &TrustInput, policy: &TrustPolicyConfig) -> TrustScore {
+    let weights = &policy.weights;
+    let caps = &policy.caps;
     let mut factors: Vec<TrustFactor> = Vec::with_capacity(5);
-    let mut total = 0.0f64;
+    let age_days = (Utc::now() - input.created_at).num_days().max(0);
 
     // ── Factor 1: Verification status ────────────────────────────────────────
-    let verification_points = if input.is_verified { WEIGHT_VERIFIED } else { 0.0 };
-    total += verification_points;
+    let verification_points = if input.is_verified { weights.verification } else { 0.0 };
     factors.push(TrustFactor {
         name: "Verification Status",
         points_earned: verification_points,
-        points_max: WEIGHT_VERIFIED,
+        points_max: weights.verification,
         explanation: if input.is_verified {
             "Contract source code has been verified on-chain.".into()
         } else {
@@ -148,80 +223,123 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
 
     // ── Factor 2: Audit quality ───────────────────────────────────────────────
     let audit_points = match input.latest_audit_score {
-        Some(s) => (s / 100.0) * WEIGHT_AUDIT,
+        Some(s) => (s.clamp(0.0, 100.0) / 100.0) * weights.audit,
         None    => 0.0,
     };
-    total += audit_points;
     factors.push(TrustFactor {
         name: "Audit Quality",
         points_earned: audit_points,
-        points_max: WEIGHT_AUDIT,
+        points_max: weights.audit,
         explanation: match input.latest_audit_score {
             Some(s) => format!(
                 "Latest security audit scored {:.1}/100. Audit score contributes up to {:.0} trust points.",
-                s, WEIGHT_AUDIT
+                s, weights.audit
+            ),
+            None => format!(
+                "No security audit found. Complete an audit to earn up to {:.0} points.",
+                weights.audit
             ),
-            None => "No security audit found. Complete an audit to earn up to 35 points.".into(),
         },
     });
 
     // ── Factor 3: Usage / adoption ────────────────────────────────────────────
-    // Blend deployments (weighted 60%) and interactions (weighted 40%), each capped
-    let deploy_ratio  = (input.total_deployments  as f64 / USAGE_DEPLOYMENT_CAP).min(1.0);
-    let interact_ratio = (input.total_interactions as f64 / USAGE_INTERACTION_CAP).min(1.0);
-    let usage_points  = (deploy_ratio * 0.6 + interact_ratio * 0.4) * WEIGHT_USAGE;
-    total += usage_points;
+    // Blend deployments and interactions, each capped
+    let deploy_ratio   = (input.total_deployments  as f64 / caps.deployments  as f64).min(1.0);
+    let interact_ratio = (input.total_interactions as f64 / caps.interactions as f64).min(1.0);
+    let usage_points   = (deploy_ratio * USAGE_DEPLOYMENT_SHARE
+        + interact_ratio * (1.0 - USAGE_DEPLOYMENT_SHARE))
+        * weights.usage;
     factors.push(TrustFactor {
         name: "Usage & Adoption",
         points_earned: usage_points,
-        points_max: WEIGHT_USAGE,
+        points_max: weights.usage,
         explanation: format!(
             "{} deployments and {} interactions recorded. Full marks at {} deployments / {} interactions.",
-            input.total_deployments,
-            input.total_interactions,
-            USAGE_DEPLOYMENT_CAP as i64,
-            USAGE_INTERACTION_CAP as i64,
+            input.total_deployments, input.total_interactions, caps.deployments, caps.interactions,
         ),
     });
 
     // ── Factor 4: Contract age ────────────────────────────────────────────────
-    let age_days = (Utc::now() - input.created_at).num_days().max(0) as f64;
-    let age_points = (age_days / AGE_DAYS_CAP).min(1.0) * WEIGHT_AGE;
-    total += age_points;
+    let age_points = (age_days as f64 / caps.age_days as f64).min(1.0) * weights.age;
     factors.push(TrustFactor {
         name: "Contract Age",
         points_earned: age_points,
-        points_max: WEIGHT_AGE,
+        points_max: weights.age,
         explanation: format!(
-            "Contract is {:.0} days old. Full age points awarded after {} days.",
-            age_days, AGE_DAYS_CAP as i64,
+            "Contract is {} days old. Full age points awarded after {} days.",
+            age_days, caps.age_days,
         ),
     });
 
     // ── Factor 5: No critical vulnerabilities ─────────────────────────────────
     // Each unresolved critical vuln deducts from this factor (floored at 0)
-    let vuln_penalty = (input.unresolved_critical_vulns as f64 * 5.0).min(WEIGHT_NO_VULNS);
-    let vuln_points  = (WEIGHT_NO_VULNS - vuln_penalty).max(0.0);
-    total += vuln_points;
+    let vuln_penalty = input.unresolved_critical_vulns as f64 * caps.penalty_per_vulnerability;
+    let vuln_points  = (weights.vulnerabilities - vuln_penalty).max(0.0);
     factors.push(TrustFactor {
         name: "Vulnerability Status",
         points_earned: vuln_points,
-        points_max: WEIGHT_NO_VULNS,
+        points_max: weights.vulnerabilities,
         explanation: if input.unresolved_critical_vulns == 0 {
             "No unresolved critical vulnerabilities detected.".into()
         } else {
             format!(
-                "{} unresolved critical vulnerability/vulnerabilities found. Each deducts 5 points.",
-                input.unresolved_critical_vulns
+                "{} unresolved critical vulnerability/vulnerabilities found. Each deducts {:.0} points.",
+                input.unresolved_critical_vulns, caps.penalty_per_vulnerability
             )
         },
     });
 
+    // ── Requirements ──────────────────────────────────────────────────────────
+    let required = &policy.requirements;
+    let mut requirements = Vec::new();
+    if required.verified {
+        requirements.push(RequirementCheck {
+            name: "Verified",
+            passed: input.is_verified,
+            explanation: if input.is_verified {
+                "Contract is verified.".into()
+            } else {
+                "Policy requires a verified contract.".into()
+            },
+        });
+    }
+    if let Some(min) = required.min_audit_score {
+        let passed = input.latest_audit_score.is_some_and(|s| s >= min);
+        requirements.push(RequirementCheck {
+            name: "Minimum Audit Score",
+            passed,
+            explanation: match input.latest_audit_score {
+                Some(s) => format!("Latest audit scored {:.1}; policy requires at least {:.1}.", s, min),
+                None    => format!("No audit found; policy requires an audit scoring at least {:.1}.", min),
+            },
+        });
+    }
+    if let Some(max) = required.max_critical_vulns {
+        requirements.push(RequirementCheck {
+            name: "Maximum Critical Vulnerabilities",
+            passed: input.unresolved_critical_vulns <= max,
+            explanation: format!(
+                "{} unresolved critical vulnerabilities; policy allows at most {}.",
+                input.unresolved_critical_vulns, max
+            ),
+        });
+    }
+    if let Some(min) = required.min_age_days {
+        requirements.push(RequirementCheck {
+            name: "Minimum Age",
+            passed: age_days >= min,
+            explanation: format!("Contract is {} days old; policy requires at least {}.", age_days, min),
+        });
+    }
+    let meets_requirements = requirements.iter().all(|r| r.passed);
+
     // ── Assemble result ───────────────────────────────────────────────────────
-    let score = total.clamp(0.0, 100.0);
+    let earned: f64   = factors.iter().map(|f| f.points_earned).sum();
+    let possible: f64 = factors.iter().map(|f| f.points_max).sum();
+    let score = if possible > 0.0 { (earned / possible * 100.0).clamp(0.0, 100.0) } else { 0.0 };
     let (badge, badge_icon) = trust_badge(score);
 
-    let summary = format!(
+    let mut summary = format!(
         "{} {} — Trust score {:.0}/100. {}",
         badge_icon,
         badge,
@@ -233,8 +351,11 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
             _          => "Low trust signals. Verification and auditing recommended.",
         }
     );
+    if !meets_requirements {
+        summary.push_str(" Does not meet the policy's requirements.");
+    }
 
-    TrustScore { score, badge, badge_icon, factors, summary }
+    TrustScore { score, badge, badge_icon, factors, requirements, meets_requirements, summary }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -242,6 +363,7 @@ pub fn compute_trust_score(input: &TrustInput) -> TrustScore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::{TrustRequirements, TrustWeights};
 
     fn base_input() -> TrustInput {
         TrustInput {
@@ -255,10 +377,11 @@ mod tests {
     }
 
     #[test]
-    fn zero_input_scores_zero_plus_age() {
+    fn zero_input_scores_only_vulnerability_points() {
         let score = compute_trust_score(&base_input());
-        // Only age can be > 0 when created_at is now — but it rounds to ~0
-        assert!(score.score < 5.0);
+        // A brand-new contract with no signals keeps the 10 no-vuln points
+        assert!((score.score - 10.0).abs() < 0.01);
+        assert!(score.meets_requirements);
     }
 
     #[test]
@@ -316,4 +439,68 @@ mod tests {
         let score = compute_trust_score(&base_input());
         assert_eq!(score.factors.len(), 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn custom_weights_are_normalised_to_100() {
+        // Verification only: a verified contract scores full marks
+        let policy = TrustPolicyConfig {
+            weights: TrustWeights { verification: 1.0, audit: 0.0, usage: 0.0, age: 0.0, vulnerabilities: 0.0 },
+            ..TrustPolicyConfig::default()
+        };
+        let input = TrustInput { is_verified: true, ..base_input() };
+        assert!((score_with_policy(&input, &policy).score - 100.0).abs() < 0.01);
+        assert_eq!(score_with_policy(&base_input(), &policy).score, 0.0);
+    }
+
+    #[test]
+    fn requirements_and_threshold_gate_independently_of_score() {
+        let policy = TrustPolicyConfig {
+            requirements: TrustRequirements {
+                verified: true,
+                min_audit_score: Some(80.0),
+                ..TrustRequirements::default()
+            },
+            gate_threshold: 60.0,
+            ..TrustPolicyConfig::default()
+        };
+        let input = TrustInput {
+            latest_audit_score: Some(100.0),
+            total_deployments: 50,
+            total_interactions: 500,
+            created_at: Utc::now() - chrono::Duration::days(365),
+            ..base_input()
+        };
+        let score = score_with_policy(&input, &policy);
+        assert!((score.score - 75.0).abs() < 0.01);
+        assert!(!score.meets_requirements);
+        assert_eq!(score.requirements.len(), 2);
+        assert_eq!(score.gate_failures(&policy), vec!["Policy requires a verified contract.".to_string()]);
+
+        let unaudited = score_with_policy(&base_input(), &policy);
+        assert_eq!(unaudited.gate_failures(&policy).len(), 3);
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected() {
+        assert!(validate_policy(&TrustPolicyConfig::default()).is_ok());
+
+        let zero = TrustPolicyConfig {
+            weights: TrustWeights { verification: 0.0, audit: 0.0, usage: 0.0, age: 0.0, vulnerabilities: 0.0 },
+            ..TrustPolicyConfig::default()
+        };
+        assert!(validate_policy(&zero).is_err());
+
+        let negative = TrustPolicyConfig {
+            weights: TrustWeights { audit: -1.0, ..TrustWeights::default() },
+            ..TrustPolicyConfig::default()
+        };
+        assert!(validate_policy(&negative).is_err());
+
+        let threshold = TrustPolicyConfig { gate_threshold: 101.0, ..TrustPolicyConfig::default() };
+        assert!(validate_policy(&threshold).is_err());
+
+        assert!(validate_policy_name("defi-strict").is_ok());
+        assert!(validate_policy_name("-strict").is_err());
+        assert!(validate_policy_name("Strict").is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    search_index,
+    state::AppState,
+    trust::{self, TrustScore},
+    trust_service,
+};
+use shared::models::{
+    CreateTrustPolicyRequest, TrustGateRequest, TrustGateResponse, TrustGateResult,
+    TrustHistoryParams, TrustPolicy, TrustPolicyParams, TrustScoreHistoryResponse,
+    TrustScoreSnapshot,
+};
+
+const MAX_GATE_CONTRACTS: usize = 100;
+const DEFAULT_HISTORY_DAYS: i64 = 90;
+const MAX_HISTORY_DAYS: i64 = 365;
+
+fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
+    tracing::error!(context = ctx, error = %err, "database error");
+    ApiError::internal(format!("Database error during: {}", ctx))
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustScoreResponse {
+    pub contract_id: Uuid,
+    pub policy: String,
+    pub policy_version: i32,
+    #[serde(flatten)]
+    pub trust: TrustScore,
+}
+
+async fn resolve_policy(
+    state: &AppState,
+    name: Option<&str>,
+    version: Option<i32>,
+) -> ApiResult<TrustPolicy> {
+    let name = name.unwrap_or(trust::DEFAULT_POLICY);
+    trust_service::find_policy(&state.db, name, version)
+        .await
+        .map_err(|e| db_err("load trust policy", e))?
+        .ok_or_else(|| {
+            let label = match version {
+                Some(v) => format!("{} v{}", name, v),
+                None => name.to_string(),
+            };
+            ApiError::not_found(
+                "TrustPolicyNotFound",
+                format!("No trust policy named {}", label),
+            )
+        })
+}
+
+/// Score one contract under `policy`; `None` when the contract is unknown
+async fn score_contract(
+    state: &AppState,
+    id: Uuid,
+    policy: &TrustPolicy,
+) -> ApiResult<Option<TrustScore>> {
+    Ok(search_index::load_trust_inputs(&state.db, Some(id))
+        .await
+        .map_err(|e| db_err("load trust inputs", e))?
+        .into_iter()
+        .next()
+        .map(|(_, input)| trust::score_with_policy(&input, &policy.config)))
+}
+
+// ─────────────────────────────────────────────────────────
+// Scores
+// ─────────────────────────────────────────────────────────
+
+/// GET /api/contracts/:id/trust-score?policy=&policy_version=
+pub async fn get_trust_score(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<TrustPolicyParams>,
+) -> ApiResult<Json<TrustScoreResponse>> {
+    let policy = resolve_policy(&state, params.policy.as_deref(), params.policy_version).await?;
+    let trust = score_contract(&state, id, &policy).await?.ok_or_else(|| {
+        ApiError::not_found(
+            "ContractNotFound",
+            format!("No contract found with ID: {}", id),
+        )
+    })?;
+
+    Ok(Json(TrustScoreResponse {
+        contract_id: id,
+        policy: policy.name,
+        policy_version: policy.version,
+        trust,
+    }))
+}
+
+/// GET /api/contracts/:id/trust-score/history?policy=&days=
+///
+/// Daily snapshots across every version of the policy, oldest first.
+pub async fn get_trust_score_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<TrustHistoryParams>,
+) -> ApiResult<Json<TrustScoreHistoryResponse>> {
+    let days = params.days.unwrap_or(DEFAULT_HISTORY_DAYS);
+    if !(1..=MAX_HISTORY_DAYS).contains(&days) {
+        return Err(ApiError::bad_request(
+            "InvalidDays",
+            format!("days must be between 1 and {}", MAX_HISTORY_DAYS),
+        ));
+    }
+    let policy = resolve_policy(&state, params.policy.as_deref(), None).await?;
+
+    let snapshots: Vec<TrustScoreSnapshot> = sqlx::query_as(
+        "SELECT s.snapshot_date, p.version AS policy_version, s.score, s.badge,
+                s.meets_requirements, s.factors, s.computed_at
+         FROM trust_score_snapshots s
+         JOIN trust_policies p ON p.id = s.policy_id
+         WHERE s.contract_id = $1 AND p.name = $2
+           AND s.snapshot_date > CURRENT_DATE - $3::int
+         ORDER BY s.snapshot_date, p.version",
+    )
+    .bind(id)
+    .bind(&policy.name)
+    .bind(days as i32)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("load trust score history", e))?;
+
+    Ok(Json(TrustScoreHistoryResponse {
+        contract_id: id,
+        policy: policy.name,
+        snapshots,
+    }))
+}
+
+// ─────────────────────────────────────────────────────────
+// Policies
+// ─────────────────────────────────────────────────────────
+
+/// GET /api/trust-policies
+///
+/// The latest version of every policy.
+pub async fn list_trust_policies(
+    State(state): State<AppState>,
+) -> ApiResult<Json<Vec<TrustPolicy>>> {
+    let policies = trust_service::latest_policies(&state.db)
+        .await
+        .map_err(|e| db_err("list trust policies", e))?;
+    Ok(Json(policies))
+}
+
+/// GET /api/trust-policies/:name
+///
+/// Every version of one policy, newest first.
+pub async fn get_trust_policy_versions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<Vec<TrustPolicy>>> {
+    let versions = trust_service::policy_versions(&state.db, &name)
+        .await
+        .map_err(|e| db_err("load trust policy versions", e))?;
+    if versions.is_empty() {
+        return Err(ApiError::not_found(
+            "TrustPolicyNotFound",
+            format!("No trust policy named {}", name),
+        ));
+    }
+    Ok(Json(versions))
+}
+
+/// POST /api/trust-policies
+///
+/// Publish a policy, or the next version of an existing one.
+pub async fn create_trust_policy(
+    State(state): State<AppState>,
+    Json(req): Json<CreateTrustPolicyRequest>,
+) -> ApiResult<(StatusCode, Json<TrustPolicy>)> {
+    trust::validate_policy_name(&req.name)
+        .map_err(|msg| ApiError::bad_request("InvalidPolicyName", msg))?;
+    trust::validate_policy(&req.config)
+        .map_err(|msg| ApiError::bad_request("InvalidTrustPolicy", msg))?;
+    if req.created_by.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "MissingCreatedBy",
+            "created_by is required",
+        ));
+    }
+
+    let policy = trust_service::create_policy(&state.db, &req)
+        .await
+        .map_err(|e| db_err("create trust policy", e))?;
+
+    tracing::info!(
+        policy = %policy.name,
+        version = policy.version,
+        created_by = %policy.created_by,
+        "trust policy published"
+    );
+
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+// ─────────────────────────────────────────────────────────
+// Deploy gate
+// ─────────────────────────────────────────────────────────
+
+/// POST /api/trust/gate
+///
+/// Check a deployment's dependencies against a policy. Every contract must
+/// be registered, meet the policy's requirements and score at least its
+/// gate threshold. Failures are reported per contract; the response is 200
+/// either way and `passed` carries the verdict.
+pub async fn check_trust_gate(
+    State(state): State<AppState>,
+    Json(req): Json<TrustGateRequest>,
+) -> ApiResult<Json<TrustGateResponse>> {
+    if req.contracts.is_empty() || req.contracts.len() > MAX_GATE_CONTRACTS {
+        return Err(ApiError::bad_request(
+            "InvalidContracts",
+            format!(
+                "contracts must list between 1 and {} contracts",
+                MAX_GATE_CONTRACTS
+            ),
+        ));
+    }
+    let policy = resolve_policy(&state, req.policy.as_deref(), req.policy_version).await?;
+
+    let mut results = Vec::with_capacity(req.contracts.len());
+    for contract in &req.contracts {
+        let rows: Vec<(Uuid, String)> = match contract.parse::<Uuid>() {
+            Ok(id) => {
+                sqlx::query_as("SELECT id, name FROM contracts WHERE id = $1")
+                    .bind(id)
+                    .fetch_all(&state.db)
+                    .await
+            }
+            Err(_) => {
+                sqlx::query_as(
+                    "SELECT id, name FROM contracts
+                      WHERE contract_id = $1 AND ($2::text IS NULL OR network::text = $2)",
+                )
+                .bind(contract)
+                .bind(&req.network)
+                .fetch_all(&state.db)
+                .await
+            }
+        }
+        .map_err(|e| db_err("resolve gated contract", e))?;
+
+        let failed = |reason: &str| TrustGateResult {
+            contract: contract.clone(),
+            id: None,
+            name: None,
+            score: None,
+            passed: false,
+            reasons: vec![reason.to_string()],
+        };
+        let (id, name) = match rows.len() {
+            0 => {
+                results.push(failed("Contract is not registered."));
+                continue;
+            }
+            1 => rows.into_iter().next().unwrap(),
+            _ => {
+                results.push(failed(
+                    "Contract is registered on several networks; specify network.",
+                ));
+                continue;
+            }
+        };
+
+        let Some(score) = score_contract(&state, id, &policy).await? else {
+            results.push(failed("Contract is not registered."));
+            continue;
+        };
+        let reasons = score.gate_failures(&policy.config);
+        results.push(TrustGateResult {
+            contract: contract.clone(),
+            id: Some(id),
+            name: Some(name),
+            score: Some(score.score),
+            passed: reasons.is_empty(),
+            reasons,
+        });
+    }
+
+    let passed = results.iter().all(|r| r.passed);
+    tracing::info!(
+        policy = %policy.name,
+        version = policy.version,
+        contracts = results.len(),
+        passed,
+        "trust gate checked"
+    );
+
+    Ok(Json(TrustGateResponse {
+        policy: policy.name,
+        policy_version: policy.version,
+        threshold: policy.config.gate_threshold,
+        passed,
+        results,
+    }))
+}
@@ -0,0 +1,27 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{state::AppState, trust_handlers};
+
+pub fn trust_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/contracts/:id/trust-score",
+            get(trust_handlers::get_trust_score),
+        )
+        .route(
+            "/api/contracts/:id/trust-score/history",
+            get(trust_handlers::get_trust_score_history),
+        )
+        .route(
+            "/api/trust-policies",
+            get(trust_handlers::list_trust_policies).post(trust_handlers::create_trust_policy),
+        )
+        .route(
+            "/api/trust-policies/:name",
+            get(trust_handlers::get_trust_policy_versions),
+        )
+        .route("/api/trust/gate", post(trust_handlers::check_trust_gate))
+}
@@ -0,0 +1,152 @@
+// api/src/trust_service.rs
+// Stores trust policies and daily score snapshots. Shared by the trust
+// endpoints and the hourly refresh in `search_index`.
+
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::trust::TrustScore;
+use shared::models::{CreateTrustPolicyRequest, TrustPolicy};
+
+#[derive(FromRow)]
+struct PolicyRow {
+    id: Uuid,
+    name: String,
+    version: i32,
+    description: Option<String>,
+    config: serde_json::Value,
+    created_by: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<PolicyRow> for TrustPolicy {
+    type Error = sqlx::Error;
+
+    fn try_from(row: PolicyRow) -> Result<Self, Self::Error> {
+        Ok(TrustPolicy {
+            id: row.id,
+            name: row.name,
+            version: row.version,
+            description: row.description,
+            config: serde_json::from_value(row.config)
+                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        })
+    }
+}
+
+const POLICY_COLUMNS: &str =
+    "id, name, version, description, config, created_by, created_at FROM trust_policies";
+
+fn into_policies(rows: Vec<PolicyRow>) -> Result<Vec<TrustPolicy>, sqlx::Error> {
+    rows.into_iter().map(TrustPolicy::try_from).collect()
+}
+
+/// `name` at `version`, or its latest version
+pub async fn find_policy(
+    db: &PgPool,
+    name: &str,
+    version: Option<i32>,
+) -> Result<Option<TrustPolicy>, sqlx::Error> {
+    sqlx::query_as::<_, PolicyRow>(&format!(
+        "SELECT {} WHERE name = $1 AND ($2::int IS NULL OR version = $2)
+         ORDER BY version DESC LIMIT 1",
+        POLICY_COLUMNS
+    ))
+    .bind(name)
+    .bind(version)
+    .fetch_optional(db)
+    .await?
+    .map(TrustPolicy::try_from)
+    .transpose()
+}
+
+/// The latest version of every policy, by name
+pub async fn latest_policies(db: &PgPool) -> Result<Vec<TrustPolicy>, sqlx::Error> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT DISTINCT ON (name) {} ORDER BY name, version DESC",
+        POLICY_COLUMNS
+    ))
+    .fetch_all(db)
+    .await?;
+    into_policies(rows)
+}
+
+/// Every version of `name`, newest first
+pub async fn policy_versions(db: &PgPool, name: &str) -> Result<Vec<TrustPolicy>, sqlx::Error> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {} WHERE name = $1 ORDER BY version DESC",
+        POLICY_COLUMNS
+    ))
+    .bind(name)
+    .fetch_all(db)
+    .await?;
+    into_policies(rows)
+}
+
+/// Publish the next version of `req.name`. The request must already be
+/// validated.
+pub async fn create_policy(
+    db: &PgPool,
+    req: &CreateTrustPolicyRequest,
+) -> Result<TrustPolicy, sqlx::Error> {
+    let config =
+        serde_json::to_value(&req.config).map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+    let row: PolicyRow = sqlx::query_as(
+        "INSERT INTO trust_policies (name, version, description, config, created_by)
+         SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4
+         FROM trust_policies WHERE name = $1
+         RETURNING id, name, version, description, config, created_by, created_at",
+    )
+    .bind(&req.name)
+    .bind(&req.description)
+    .bind(config)
+    .bind(&req.created_by)
+    .fetch_one(db)
+    .await?;
+    row.try_into()
+}
+
+/// Record today's scores under `policy`, replacing any taken earlier today.
+pub async fn record_snapshots(
+    db: &PgPool,
+    policy: &TrustPolicy,
+    scores: &[(Uuid, TrustScore)],
+) -> Result<u64, sqlx::Error> {
+    let mut ids = Vec::with_capacity(scores.len());
+    let mut values = Vec::with_capacity(scores.len());
+    let mut badges = Vec::with_capacity(scores.len());
+    let mut meets = Vec::with_capacity(scores.len());
+    let mut factors = Vec::with_capacity(scores.len());
+    for (id, score) in scores {
+        ids.push(*id);
+        values.push(score.score);
+        badges.push(score.badge);
+        meets.push(score.meets_requirements);
+        factors.push(serde_json::to_value(&score.factors).unwrap_or_default());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO trust_score_snapshots
+             (contract_id, policy_id, score, badge, meets_requirements, factors)
+         SELECT s.id, $1, s.score, s.badge, s.meets, s.factors
+         FROM UNNEST($2::uuid[], $3::float8[], $4::text[], $5::bool[], $6::jsonb[])
+              AS s(id, score, badge, meets, factors)
+         ON CONFLICT (contract_id, policy_id, snapshot_date) DO UPDATE
+         SET score              = EXCLUDED.score,
+             badge              = EXCLUDED.badge,
+             meets_requirements = EXCLUDED.meets_requirements,
+             factors            = EXCLUDED.factors,
+             computed_at        = NOW()",
+    )
+    .bind(policy.id)
+    .bind(&ids)
+    .bind(&values)
+    .bind(&badges)
+    .bind(&meets)
+    .bind(&factors)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,866 @@
+// api/src/upgrade_safety.rs
+//
+// Storage-layout compatibility analysis between two contract versions.
+//
+// `update_current_contract_wasm` keeps the contract's storage, so the new
+// code must be able to read every entry the old code wrote. This module
+// compares the `contracttype` definitions of two ABIs and classifies each
+// change by how it interacts with the Soroban value encoding:
+//
+//   * named structs    → ScMap keyed by field name (field order irrelevant)
+//   * tuple structs    → ScVec, positional
+//   * union enums      → ScVec [Symbol(variant), payload...]
+//   * integer enums    → ScU32 discriminant (variant names irrelevant)
+//
+// Analysis functions are synchronous — handlers load the ABIs (and
+// optionally verified source) with the helpers at the bottom of this file.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::type_safety::{parse_json_spec, ContractABI, EnumVariant, SorobanType, StructField};
+
+/// How an upgrade interacts with data already in storage. Ordered so that
+/// the report verdict is the maximum over all findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeVerdict {
+    /// Existing entries read back unchanged under the new code
+    Safe,
+    /// Existing entries become unreadable or orphaned; a data migration must
+    /// run as part of the upgrade
+    RequiresMigration,
+    /// Existing entries may be silently reinterpreted or lost
+    Unsafe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutChange {
+    TypeRemoved,
+    TypeKindChanged,
+    VariantRemoved,
+    VariantRenamed,
+    VariantPayloadChanged,
+    DiscriminantChanged,
+    FieldAdded,
+    FieldRemoved,
+    FieldRenamed,
+    FieldTypeChanged,
+}
+
+/// Where a type sits in the storage layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageRole {
+    /// Used as a storage key (e.g. `DataKey`)
+    Key,
+    /// Stored as a value, directly or nested inside another stored type
+    Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutFinding {
+    pub type_name: String,
+    pub role: StorageRole,
+    /// Variant or field the change applies to, if any
+    pub member: Option<String>,
+    pub change: LayoutChange,
+    pub severity: UpgradeVerdict,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeSafetyReport {
+    pub verdict: UpgradeVerdict,
+    pub key_types: Vec<String>,
+    pub stored_types: Vec<String>,
+    /// `true` when storage roles were derived from source rather than
+    /// inferred from type names in the spec
+    pub source_analyzed: bool,
+    pub findings: Vec<LayoutFinding>,
+}
+
+impl UpgradeSafetyReport {
+    pub fn is_safe(&self) -> bool {
+        self.verdict == UpgradeVerdict::Safe
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Storage role detection
+// ─────────────────────────────────────────────────────────
+
+const STORAGE_OPS: [&str; 6] = [".set(", ".get(", ".has(", ".remove(", ".update(", ".extend_ttl("];
+
+fn is_error_enum(abi: &ContractABI, name: &str) -> bool {
+    let prefix = format!("{}::", name);
+    abi.errors.iter().any(|e| e.name.starts_with(&prefix))
+}
+
+fn user_types(abi: &ContractABI) -> impl Iterator<Item = (&String, &SorobanType)> {
+    abi.types.iter().filter(move |(name, ty)| {
+        matches!(ty, SorobanType::Struct { .. } | SorobanType::Enum { .. })
+            && !is_error_enum(abi, name)
+    })
+}
+
+/// Spec-only heuristic: `DataKey`, `*Key` and `*StorageKey` enums are keys.
+fn looks_like_key_type(name: &str, ty: &SorobanType) -> bool {
+    matches!(ty, SorobanType::Enum { .. }) && (name == "DataKey" || name.ends_with("Key"))
+}
+
+/// Scan Rust source for storage calls and collect the type names used as
+/// keys (`.set(&DataKey::Admin, ..)`) and the other known types mentioned
+/// in the same statement (`let cfg: Config = env.storage()...get(..)`).
+fn roles_from_source(
+    source: &str,
+    known: &BTreeSet<String>,
+) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut keys = BTreeSet::new();
+    let mut values = BTreeSet::new();
+
+    for statement in source.split(';') {
+        if !statement.contains("storage()") {
+            continue;
+        }
+        let mut statement_keys = BTreeSet::new();
+        for op in STORAGE_OPS {
+            let mut rest = statement;
+            while let Some(pos) = rest.find(op) {
+                rest = &rest[pos + op.len()..];
+                let arg = rest.trim_start().trim_start_matches('&');
+                let ident: String = arg
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                if known.contains(&ident) && arg[ident.len()..].starts_with("::") {
+                    statement_keys.insert(ident);
+                }
+            }
+        }
+
+        for ident in statement
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|w| known.contains(*w))
+        {
+            if !statement_keys.contains(ident) {
+                values.insert(ident.to_string());
+            }
+        }
+        keys.extend(statement_keys);
+    }
+
+    (keys, values)
+}
+
+fn referenced_names(ty: &SorobanType, out: &mut Vec<String>) {
+    match ty {
+        SorobanType::Custom { name } => out.push(name.clone()),
+        SorobanType::Option { value_type } => referenced_names(value_type, out),
+        SorobanType::Vec { element_type } => referenced_names(element_type, out),
+        SorobanType::Map {
+            key_type,
+            value_type,
+        } => {
+            referenced_names(key_type, out);
+            referenced_names(value_type, out);
+        }
+        SorobanType::Result { ok_type, err_type } => {
+            referenced_names(ok_type, out);
+            referenced_names(err_type, out);
+        }
+        SorobanType::Tuple { elements } => elements.iter().for_each(|e| referenced_names(e, out)),
+        SorobanType::Struct { fields, .. } => {
+            fields.iter().for_each(|f| referenced_names(&f.field_type, out))
+        }
+        SorobanType::Enum { variants, .. } => variants
+            .iter()
+            .flat_map(|v| v.fields.iter().flatten())
+            .for_each(|f| referenced_names(&f.field_type, out)),
+        _ => {}
+    }
+}
+
+/// Extend `roots` with every user type reachable from them, since a type
+/// nested inside a stored struct or key payload is encoded in storage too.
+fn close_over(abi: &ContractABI, roots: BTreeSet<String>) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    let mut stack: Vec<String> = roots.into_iter().collect();
+    while let Some(name) = stack.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        if let Some(ty) = abi.types.get(&name) {
+            let mut refs = Vec::new();
+            referenced_names(ty, &mut refs);
+            stack.extend(refs.into_iter().filter(|r| abi.types.contains_key(r)));
+        }
+    }
+    seen
+}
+
+/// Decide which types of `abi` are storage keys and which are stored values.
+fn storage_roles(abi: &ContractABI, source: Option<&str>) -> BTreeMap<String, StorageRole> {
+    let known: BTreeSet<String> = user_types(abi).map(|(n, _)| n.clone()).collect();
+
+    let (keys, values) = match source {
+        Some(src) => {
+            let (keys, values) = roles_from_source(src, &known);
+            (keys, close_over(abi, values))
+        }
+        None => {
+            let keys: BTreeSet<String> = user_types(abi)
+                .filter(|(n, ty)| looks_like_key_type(n, ty))
+                .map(|(n, _)| n.clone())
+                .collect();
+            let values = known.difference(&keys).cloned().collect();
+            (keys, values)
+        }
+    };
+
+    let mut roles = BTreeMap::new();
+    // Types reachable from a key payload are part of the key encoding.
+    for name in close_over(abi, keys) {
+        roles.insert(name, StorageRole::Key);
+    }
+    for name in values {
+        roles.entry(name).or_insert(StorageRole::Value);
+    }
+    roles.retain(|name, _| known.contains(name));
+    roles
+}
+
+// ─────────────────────────────────────────────────────────
+// Layout comparison
+// ─────────────────────────────────────────────────────────
+
+struct Collector<'a> {
+    type_name: &'a str,
+    role: StorageRole,
+    findings: Vec<LayoutFinding>,
+}
+
+impl Collector<'_> {
+    fn push(
+        &mut self,
+        member: Option<&str>,
+        change: LayoutChange,
+        severity: UpgradeVerdict,
+        message: String,
+    ) {
+        self.findings.push(LayoutFinding {
+            type_name: self.type_name.to_string(),
+            role: self.role,
+            member: member.map(str::to_string),
+            change,
+            severity,
+            message,
+        });
+    }
+}
+
+fn is_tuple_struct(fields: &[StructField]) -> bool {
+    !fields.is_empty() && fields.iter().all(|f| f.name.parse::<u32>().is_ok())
+}
+
+fn is_integer_enum(variants: &[EnumVariant]) -> bool {
+    !variants.is_empty()
+        && variants
+            .iter()
+            .all(|v| v.value.is_some() && v.fields.iter().flatten().next().is_none())
+}
+
+fn field_types(fields: &Option<Vec<StructField>>) -> Vec<&SorobanType> {
+    fields
+        .iter()
+        .flatten()
+        .map(|f| &f.field_type)
+        .collect()
+}
+
+fn compare_struct(c: &mut Collector, old: &[StructField], new: &[StructField]) {
+    if is_tuple_struct(old) || is_tuple_struct(new) {
+        let old_types: Vec<_> = old.iter().map(|f| &f.field_type).collect();
+        let new_types: Vec<_> = new.iter().map(|f| &f.field_type).collect();
+        if old_types.len() != new_types.len() {
+            c.push(
+                None,
+                if new_types.len() > old_types.len() {
+                    LayoutChange::FieldAdded
+                } else {
+                    LayoutChange::FieldRemoved
+                },
+                UpgradeVerdict::RequiresMigration,
+                format!(
+                    "tuple struct arity changed from {} to {}; stored values no longer decode",
+                    old_types.len(),
+                    new_types.len()
+                ),
+            );
+        } else {
+            for (i, (a, b)) in old_types.iter().zip(&new_types).enumerate() {
+                if a != b {
+                    c.push(
+                        Some(&i.to_string()),
+                        LayoutChange::FieldTypeChanged,
+                        UpgradeVerdict::Unsafe,
+                        format!(
+                            "positional field {} changed from {} to {}",
+                            i,
+                            a.display_name(),
+                            b.display_name()
+                        ),
+                    );
+                }
+            }
+        }
+        return;
+    }
+
+    let old_by_name: HashMap<&str, &StructField> =
+        old.iter().map(|f| (f.name.as_str(), f)).collect();
+    let new_by_name: HashMap<&str, &StructField> =
+        new.iter().map(|f| (f.name.as_str(), f)).collect();
+
+    let removed: Vec<&StructField> = old
+        .iter()
+        .filter(|f| !new_by_name.contains_key(f.name.as_str()))
+        .collect();
+    let mut added: Vec<&StructField> = new
+        .iter()
+        .filter(|f| !old_by_name.contains_key(f.name.as_str()))
+        .collect();
+
+    for field in &removed {
+        // A removed field paired with an added field of the same type is
+        // most likely a rename; report it as one.
+        if let Some(pos) = added.iter().position(|a| a.field_type == field.field_type) {
+            let renamed = added.remove(pos);
+            c.push(
+                Some(&field.name),
+                LayoutChange::FieldRenamed,
+                UpgradeVerdict::RequiresMigration,
+                format!(
+                    "field `{}` renamed to `{}`; struct maps are keyed by field name",
+                    field.name, renamed.name
+                ),
+            );
+        } else {
+            c.push(
+                Some(&field.name),
+                LayoutChange::FieldRemoved,
+                UpgradeVerdict::RequiresMigration,
+                format!("field `{}` removed; stored values no longer decode", field.name),
+            );
+        }
+    }
+    for field in added {
+        c.push(
+            Some(&field.name),
+            LayoutChange::FieldAdded,
+            UpgradeVerdict::RequiresMigration,
+            format!(
+                "field `{}` added; existing values lack it and fail to decode",
+                field.name
+            ),
+        );
+    }
+
+    for field in old {
+        if let Some(after) = new_by_name.get(field.name.as_str()) {
+            if after.field_type != field.field_type {
+                c.push(
+                    Some(&field.name),
+                    LayoutChange::FieldTypeChanged,
+                    UpgradeVerdict::Unsafe,
+                    format!(
+                        "field `{}` changed from {} to {}",
+                        field.name,
+                        field.field_type.display_name(),
+                        after.field_type.display_name()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn compare_integer_enum(c: &mut Collector, old: &[EnumVariant], new: &[EnumVariant]) {
+    for variant in old {
+        let value = variant.value.unwrap_or_default();
+        match new.iter().find(|v| v.name == variant.name) {
+            Some(after) if after.value != variant.value => c.push(
+                Some(&variant.name),
+                LayoutChange::DiscriminantChanged,
+                UpgradeVerdict::Unsafe,
+                format!(
+                    "discriminant of `{}` changed from {} to {}; stored values are reinterpreted",
+                    variant.name,
+                    value,
+                    after.value.unwrap_or_default()
+                ),
+            ),
+            Some(_) => {}
+            None => match new.iter().find(|v| v.value == variant.value) {
+                // Integer enums are encoded by value, so a rename is harmless.
+                Some(_) => {}
+                None => c.push(
+                    Some(&variant.name),
+                    LayoutChange::VariantRemoved,
+                    UpgradeVerdict::RequiresMigration,
+                    format!(
+                        "variant `{}` ({}) removed; stored values holding it no longer decode",
+                        variant.name, value
+                    ),
+                ),
+            },
+        }
+    }
+}
+
+fn compare_union(c: &mut Collector, old: &[EnumVariant], new: &[EnumVariant]) {
+    let is_key = c.role == StorageRole::Key;
+
+    for (index, variant) in old.iter().enumerate() {
+        match new.iter().find(|v| v.name == variant.name) {
+            Some(after) => {
+                if field_types(&after.fields) != field_types(&variant.fields) {
+                    c.push(
+                        Some(&variant.name),
+                        LayoutChange::VariantPayloadChanged,
+                        UpgradeVerdict::Unsafe,
+                        if is_key {
+                            format!(
+                                "payload of key `{}` changed; existing entries can no longer be addressed",
+                                variant.name
+                            )
+                        } else {
+                            format!(
+                                "payload of `{}` changed; stored values are reinterpreted or fail to decode",
+                                variant.name
+                            )
+                        },
+                    );
+                }
+            }
+            None => {
+                let renamed = new.get(index).filter(|v| {
+                    !old.iter().any(|o| o.name == v.name)
+                        && field_types(&v.fields) == field_types(&variant.fields)
+                });
+                match renamed {
+                    // A renamed key silently hides existing entries: reads
+                    // fall back to defaults instead of failing.
+                    Some(after) => c.push(
+                        Some(&variant.name),
+                        LayoutChange::VariantRenamed,
+                        if is_key {
+                            UpgradeVerdict::Unsafe
+                        } else {
+                            UpgradeVerdict::RequiresMigration
+                        },
+                        format!(
+                            "variant `{}` renamed to `{}`; unions are encoded by variant name",
+                            variant.name, after.name
+                        ),
+                    ),
+                    None => c.push(
+                        Some(&variant.name),
+                        LayoutChange::VariantRemoved,
+                        UpgradeVerdict::RequiresMigration,
+                        if is_key {
+                            format!(
+                                "key `{}` removed; entries stored under it are orphaned",
+                                variant.name
+                            )
+                        } else {
+                            format!(
+                                "variant `{}` removed; stored values holding it no longer decode",
+                                variant.name
+                            )
+                        },
+                    ),
+                }
+            }
+        }
+    }
+}
+
+fn compare_type(c: &mut Collector, old: &SorobanType, new: &SorobanType) {
+    match (old, new) {
+        (SorobanType::Struct { fields: a, .. }, SorobanType::Struct { fields: b, .. }) => {
+            compare_struct(c, a, b)
+        }
+        (SorobanType::Enum { variants: a, .. }, SorobanType::Enum { variants: b, .. }) => {
+            if is_integer_enum(a) && is_integer_enum(b) {
+                compare_integer_enum(c, a, b)
+            } else if !is_integer_enum(a) && !is_integer_enum(b) {
+                compare_union(c, a, b)
+            } else {
+                c.push(
+                    None,
+                    LayoutChange::TypeKindChanged,
+                    UpgradeVerdict::Unsafe,
+                    "enum switched between integer and union encoding".into(),
+                )
+            }
+        }
+        _ => c.push(
+            None,
+            LayoutChange::TypeKindChanged,
+            UpgradeVerdict::Unsafe,
+            format!(
+                "type changed from {} to {}",
+                old.display_name(),
+                new.display_name()
+            ),
+        ),
+    }
+}
+
+/// Compare the storage layout of two contract versions.
+///
+/// Storage roles are derived from the old version (the data already in
+/// storage was written by it); `old_source`, when available, replaces the
+/// name-based heuristics with an analysis of actual storage calls.
+pub fn analyze_upgrade(
+    old: &ContractABI,
+    new: &ContractABI,
+    old_source: Option<&str>,
+) -> UpgradeSafetyReport {
+    let roles = storage_roles(old, old_source);
+    let mut findings = Vec::new();
+
+    for (name, role) in &roles {
+        let mut collector = Collector {
+            type_name: name,
+            role: *role,
+            findings: Vec::new(),
+        };
+        let before = &old.types[name];
+        match new.types.get(name) {
+            Some(after) => compare_type(&mut collector, before, after),
+            None => collector.push(
+                None,
+                LayoutChange::TypeRemoved,
+                UpgradeVerdict::RequiresMigration,
+                format!("type `{}` no longer exists in the new version", name),
+            ),
+        }
+        findings.extend(collector.findings);
+    }
+
+    let verdict = findings
+        .iter()
+        .map(|f| f.severity)
+        .max()
+        .unwrap_or(UpgradeVerdict::Safe);
+
+    UpgradeSafetyReport {
+        verdict,
+        key_types: roles
+            .iter()
+            .filter(|(_, r)| **r == StorageRole::Key)
+            .map(|(n, _)| n.clone())
+            .collect(),
+        stored_types: roles
+            .iter()
+            .filter(|(_, r)| **r == StorageRole::Value)
+            .map(|(n, _)| n.clone())
+            .collect(),
+        source_analyzed: old_source.is_some(),
+        findings,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Loading
+// ─────────────────────────────────────────────────────────
+
+/// Load the ABI recorded for `version` of a contract, if one was published.
+/// An ABI that fails to parse is treated as missing.
+pub async fn load_version_abi(
+    db: &PgPool,
+    contract_id: Uuid,
+    version: &str,
+) -> Result<Option<ContractABI>, sqlx::Error> {
+    let abi: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT abi FROM contract_abis WHERE contract_id = $1 AND version = $2")
+            .bind(contract_id)
+            .bind(version)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(abi.and_then(|json| match parse_json_spec(&json.to_string(), version) {
+        Ok(abi) => Some(abi),
+        Err(err) => {
+            tracing::warn!(%contract_id, version, error = %err, "unparseable contract ABI");
+            None
+        }
+    }))
+}
+
+/// Source of the contract's most recent successful verification.
+pub async fn load_verified_source(
+    db: &PgPool,
+    contract_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    let source: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT source_code FROM verifications
+          WHERE contract_id = $1 AND status = 'verified'
+          ORDER BY verified_at DESC NULLS LAST
+          LIMIT 1",
+    )
+    .bind(contract_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(source.flatten())
+}
+
+/// Analyze the upgrade between two published versions; `None` when either
+/// version has no ABI on record.
+pub async fn analyze_versions(
+    db: &PgPool,
+    contract_id: Uuid,
+    old_version: &str,
+    new_version: &str,
+    old_source: Option<&str>,
+) -> Result<Option<UpgradeSafetyReport>, sqlx::Error> {
+    let old = load_version_abi(db, contract_id, old_version).await?;
+    let new = load_version_abi(db, contract_id, new_version).await?;
+    Ok(match (old, new) {
+        (Some(old), Some(new)) => Some(analyze_upgrade(&old, &new, old_source)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, ty: SorobanType) -> StructField {
+        StructField {
+            name: name.into(),
+            field_type: ty,
+            doc: None,
+        }
+    }
+
+    fn unit(name: &str) -> EnumVariant {
+        EnumVariant {
+            name: name.into(),
+            value: None,
+            fields: None,
+            doc: None,
+        }
+    }
+
+    fn tuple(name: &str, fields: Vec<SorobanType>) -> EnumVariant {
+        EnumVariant {
+            name: name.into(),
+            value: None,
+            fields: Some(
+                fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, t)| field(&i.to_string(), t))
+                    .collect(),
+            ),
+            doc: None,
+        }
+    }
+
+    fn int(name: &str, value: u32) -> EnumVariant {
+        EnumVariant {
+            name: name.into(),
+            value: Some(value),
+            fields: None,
+            doc: None,
+        }
+    }
+
+    fn abi(types: Vec<SorobanType>) -> ContractABI {
+        let mut abi = ContractABI::new("token".into());
+        for ty in types {
+            let name = match &ty {
+                SorobanType::Struct { name, .. } | SorobanType::Enum { name, .. } => name.clone(),
+                _ => unreachable!(),
+            };
+            abi.types.insert(name, ty);
+        }
+        abi
+    }
+
+    fn data_key(variants: Vec<EnumVariant>) -> SorobanType {
+        SorobanType::Enum {
+            name: "DataKey".into(),
+            variants,
+        }
+    }
+
+    fn config(fields: Vec<StructField>) -> SorobanType {
+        SorobanType::Struct {
+            name: "Config".into(),
+            fields,
+        }
+    }
+
+    #[test]
+    fn identical_layouts_are_safe() {
+        let v1 = abi(vec![
+            data_key(vec![unit("Admin"), tuple("Balance", vec![SorobanType::Address])]),
+            config(vec![field("fee", SorobanType::U32)]),
+        ]);
+        let report = analyze_upgrade(&v1, &v1.clone(), None);
+        assert_eq!(report.verdict, UpgradeVerdict::Safe);
+        assert_eq!(report.key_types, vec!["DataKey"]);
+        assert_eq!(report.stored_types, vec!["Config"]);
+    }
+
+    #[test]
+    fn added_key_variant_and_reordered_fields_are_safe() {
+        let v1 = abi(vec![
+            data_key(vec![unit("Admin")]),
+            config(vec![
+                field("fee", SorobanType::U32),
+                field("admin", SorobanType::Address),
+            ]),
+        ]);
+        let v2 = abi(vec![
+            data_key(vec![unit("Admin"), unit("Paused")]),
+            config(vec![
+                field("admin", SorobanType::Address),
+                field("fee", SorobanType::U32),
+            ]),
+        ]);
+        assert!(analyze_upgrade(&v1, &v2, None).is_safe());
+    }
+
+    #[test]
+    fn renamed_key_variant_is_unsafe() {
+        let v1 = abi(vec![data_key(vec![
+            unit("Admin"),
+            tuple("Balance", vec![SorobanType::Address]),
+        ])]);
+        let v2 = abi(vec![data_key(vec![
+            unit("Admin"),
+            tuple("Bal", vec![SorobanType::Address]),
+        ])]);
+        let report = analyze_upgrade(&v1, &v2, None);
+        assert_eq!(report.verdict, UpgradeVerdict::Unsafe);
+        assert_eq!(report.findings[0].change, LayoutChange::VariantRenamed);
+    }
+
+    #[test]
+    fn changed_key_payload_is_unsafe() {
+        let v1 = abi(vec![data_key(vec![tuple("Allowance", vec![SorobanType::Address])])]);
+        let v2 = abi(vec![data_key(vec![tuple(
+            "Allowance",
+            vec![SorobanType::Address, SorobanType::Address],
+        )])]);
+        let report = analyze_upgrade(&v1, &v2, None);
+        assert_eq!(report.findings[0].change, LayoutChange::VariantPayloadChanged);
+        assert_eq!(report.verdict, UpgradeVerdict::Unsafe);
+    }
+
+    #[test]
+    fn struct_field_changes_require_migration_or_are_unsafe() {
+        let v1 = abi(vec![config(vec![
+            field("fee", SorobanType::U32),
+            field("owner", SorobanType::Address),
+        ])]);
+        let v2 = abi(vec![config(vec![
+            field("fee", SorobanType::I128),
+            field("admin", SorobanType::Address),
+            field("paused", SorobanType::Bool),
+        ])]);
+        let report = analyze_upgrade(&v1, &v2, None);
+        let changes: Vec<_> = report.findings.iter().map(|f| f.change).collect();
+        assert_eq!(
+            changes,
+            vec![
+                LayoutChange::FieldRenamed,
+                LayoutChange::FieldAdded,
+                LayoutChange::FieldTypeChanged
+            ]
+        );
+        assert_eq!(report.verdict, UpgradeVerdict::Unsafe);
+    }
+
+    #[test]
+    fn integer_enums_are_compared_by_discriminant() {
+        let status = |variants| SorobanType::Enum {
+            name: "Status".into(),
+            variants,
+        };
+        let v1 = abi(vec![status(vec![int("Active", 0), int("Paused", 1)])]);
+
+        let renamed = abi(vec![status(vec![int("Live", 0), int("Paused", 1)])]);
+        assert!(analyze_upgrade(&v1, &renamed, None).is_safe());
+
+        let renumbered = abi(vec![status(vec![int("Paused", 0), int("Active", 1)])]);
+        let report = analyze_upgrade(&v1, &renumbered, None);
+        assert_eq!(report.verdict, UpgradeVerdict::Unsafe);
+        assert!(report
+            .findings
+            .iter()
+            .all(|f| f.change == LayoutChange::DiscriminantChanged));
+    }
+
+    #[test]
+    fn removed_stored_type_requires_migration() {
+        let v1 = abi(vec![config(vec![field("fee", SorobanType::U32)])]);
+        let v2 = abi(vec![]);
+        let report = analyze_upgrade(&v1, &v2, None);
+        assert_eq!(report.verdict, UpgradeVerdict::RequiresMigration);
+        assert_eq!(report.findings[0].change, LayoutChange::TypeRemoved);
+    }
+
+    #[test]
+    fn source_analysis_limits_checks_to_stored_types() {
+        let args = SorobanType::Struct {
+            name: "TransferArgs".into(),
+            fields: vec![field("amount", SorobanType::I128)],
+        };
+        let slot = SorobanType::Enum {
+            name: "Slot".into(),
+            variants: vec![unit("Config")],
+        };
+        let v1 = abi(vec![
+            slot.clone(),
+            config(vec![field("fee", SorobanType::U32)]),
+            args,
+        ]);
+        let v2 = abi(vec![
+            slot,
+            config(vec![field("fee", SorobanType::U32)]),
+            SorobanType::Struct {
+                name: "TransferArgs".into(),
+                fields: vec![field("value", SorobanType::U128)],
+            },
+        ]);
+        let source = r#"
+            pub fn init(env: Env, cfg: Config) {
+                env.storage().instance().set(&Slot::Config, &cfg);
+            }
+            pub fn fee(env: Env) -> u32 {
+                let cfg: Config = env.storage().instance().get(&Slot::Config).unwrap();
+                cfg.fee
+            }
+        "#;
+
+        let report = analyze_upgrade(&v1, &v2, Some(source));
+        assert!(report.source_analyzed);
+        assert_eq!(report.key_types, vec!["Slot"]);
+        assert_eq!(report.stored_types, vec!["Config"]);
+        assert!(report.is_safe());
+
+        // Without source every non-key type is assumed to be stored.
+        assert!(!analyze_upgrade(&v1, &v2, None).is_safe());
+    }
+}
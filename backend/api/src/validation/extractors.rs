@@ -1,12 +1,13 @@
 //! Custom Axum extractors for validated input
 //!
 //! This module provides `ValidatedJson<T>` - a drop-in replacement for `Json<T>`
-//! that automatically sanitizes and validates incoming JSON payloads.
+//! that automatically sanitizes and validates incoming JSON payloads - and
+//! `ValidatedQuery<T>`, which does the same for query strings.
 
 use axum::{
     async_trait,
-    extract::{FromRequest, Request},
-    http::StatusCode,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::{request::Parts, StatusCode},
     Json,
 };
 use chrono::{SecondsFormat, Utc};
@@ -175,6 +176,37 @@ impl<T> std::ops::DerefMut for ValidatedJson<T> {
     }
 }
 
+/// Query string extractor that validates and sanitizes input
+///
+/// The `Query<T>` counterpart of `ValidatedJson<T>`.
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validatable + Send,
+    S: Send + Sync,
+{
+    type Rejection = ValidationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(mut data) =
+            Query::<T>::from_request_parts(parts, state)
+                .await
+                .map_err(|err| {
+                    ValidationError::single(
+                        "query",
+                        format!("Invalid query string: {}", err.body_text()),
+                    )
+                })?;
+
+        data.sanitize();
+        data.validate().map_err(ValidationError::new)?;
+
+        Ok(ValidatedQuery(data))
+    }
+}
+
 /// Builder for accumulating validation errors
 #[derive(Debug, Default)]
 pub struct ValidationBuilder {
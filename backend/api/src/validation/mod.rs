@@ -63,7 +63,9 @@ pub mod sanitizers;
 pub mod validators;
 
 // Re-export commonly used items
-pub use extractors::{FieldError, Validatable, ValidatedJson, ValidationBuilder, ValidationError};
+pub use extractors::{
+    FieldError, Validatable, ValidatedJson, ValidatedQuery, ValidationBuilder, ValidationError,
+};
 pub use sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description,
     sanitize_description_optional, sanitize_name, sanitize_tags, sanitize_url_optional, strip_html,
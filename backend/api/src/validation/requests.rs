@@ -4,10 +4,12 @@
 //! that need validation when received from clients.
 
 use shared::models::{
-    CreateMigrationRequest, DependencyDeclaration, PublishRequest, UpdateMigrationStatusRequest,
-    VerifyRequest,
+    CreateMigrationRequest, DependencyDeclaration, PublishRequest, SearchParams,
+    UpdateMigrationStatusRequest, UpsertSynonymRequest, VerifyRequest,
 };
 
+use crate::search_engine::MATURITY_LEVELS;
+
 use super::extractors::{FieldError, Validatable, ValidationBuilder};
 use super::sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description_optional, sanitize_name,
@@ -46,6 +48,18 @@ const MAX_DEPENDENCY_NAME_LENGTH: usize = 255;
 const MAX_VERSION_CONSTRAINT_LENGTH: usize = 100;
 /// Maximum number of dependencies
 const MAX_DEPENDENCIES_COUNT: usize = 50;
+/// Maximum length for an SPDX license expression
+const MAX_LICENSE_LENGTH: usize = 64;
+/// Maximum documentation size (64 KB)
+const MAX_DOCUMENTATION_BYTES: usize = 64 * 1024;
+/// Maximum length for a search query
+const MAX_SEARCH_QUERY_LENGTH: usize = 200;
+/// Maximum search results per page
+const MAX_SEARCH_LIMIT: i64 = 100;
+/// Maximum number of synonyms for one term
+const MAX_SYNONYMS_COUNT: usize = 50;
+/// Maximum length for each synonym
+const MAX_SYNONYM_LENGTH: usize = 100;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // PublishRequest validation
@@ -84,6 +98,15 @@ impl Validatable for PublishRequest {
             dep.name = trim(&dep.name);
             dep.version_constraint = trim(&dep.version_constraint);
         }
+
+        // Sanitize license and documentation
+        if let Some(ref mut license) = self.license {
+            *license = trim(license);
+            if license.is_empty() {
+                self.license = None;
+            }
+        }
+        sanitize_description_optional(&mut self.documentation);
     }
 
     fn validate(&self) -> Result<(), Vec<FieldError>> {
@@ -130,6 +153,32 @@ impl Validatable for PublishRequest {
             validate_tags(&self.tags, MAX_TAGS_COUNT, MAX_TAG_LENGTH)
         });
 
+        // license: optional SPDX expression
+        if let Some(ref license) = self.license {
+            builder.check("license", || {
+                validate_length(license, 1, MAX_LICENSE_LENGTH)?;
+                if license
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-.+() ".contains(c))
+                {
+                    Ok(())
+                } else {
+                    Err("must be an SPDX license identifier or expression".to_string())
+                }
+            });
+        }
+
+        // documentation: optional, max 64 KB
+        if let Some(ref docs) = self.documentation {
+            builder.check("documentation", || {
+                if docs.len() > MAX_DOCUMENTATION_BYTES {
+                    return Err(format!("must be at most {} bytes", MAX_DOCUMENTATION_BYTES));
+                }
+                Ok(())
+            });
+            builder.check("documentation", || validate_no_xss(docs));
+        }
+
         // dependencies: validate each
         builder.check("dependencies", || {
             if self.dependencies.len() > MAX_DEPENDENCIES_COUNT {
@@ -298,6 +347,122 @@ impl Validatable for DependencyDeclaration {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// SearchParams validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for SearchParams {
+    fn sanitize(&mut self) {
+        for field in [&mut self.q, &mut self.category, &mut self.license] {
+            if let Some(ref mut value) = field {
+                *value = trim(value);
+                if value.is_empty() {
+                    *field = None;
+                }
+            }
+        }
+
+        // Maturity is matched case-insensitively
+        if let Some(ref mut maturity) = self.maturity {
+            *maturity = trim(maturity).to_lowercase();
+        }
+
+        // Tags: drop blanks from the comma-separated list
+        if let Some(ref tags) = self.tags {
+            let tags: Vec<String> = tags
+                .split(',')
+                .map(trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+            self.tags = (!tags.is_empty()).then(|| tags.join(","));
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        if let Some(ref q) = self.q {
+            builder.check("q", || validate_length(q, 1, MAX_SEARCH_QUERY_LENGTH));
+        }
+
+        if let Some(ref category) = self.category {
+            builder.check("category", || {
+                validate_length(category, 1, MAX_CATEGORY_LENGTH)
+            });
+        }
+
+        if let Some(ref maturity) = self.maturity {
+            builder.check_condition(
+                !MATURITY_LEVELS.contains(&maturity.as_str()),
+                "maturity",
+                format!("must be one of: {}", MATURITY_LEVELS.join(", ")),
+            );
+        }
+
+        if let Some(ref license) = self.license {
+            builder.check("license", || {
+                validate_length(license, 1, MAX_LICENSE_LENGTH)
+            });
+        }
+
+        if let Some(ref tags) = self.tags {
+            builder.check("tags", || {
+                let tags: Vec<String> = tags.split(',').map(String::from).collect();
+                validate_tags(&tags, MAX_TAGS_COUNT, MAX_TAG_LENGTH)
+            });
+        }
+
+        if let Some(page) = self.page {
+            builder.check_condition(page < 1, "page", "must be at least 1");
+        }
+
+        if let Some(limit) = self.limit {
+            builder.check_condition(
+                !(1..=MAX_SEARCH_LIMIT).contains(&limit),
+                "limit",
+                format!("must be between 1 and {}", MAX_SEARCH_LIMIT),
+            );
+        }
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// UpsertSynonymRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for UpsertSynonymRequest {
+    fn sanitize(&mut self) {
+        // Synonyms are matched against lowercased query terms
+        self.synonyms = self
+            .synonyms
+            .iter()
+            .map(|s| trim(s).to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        builder.check("synonyms", || {
+            if self.synonyms.is_empty() {
+                return Err("at least one synonym is required".to_string());
+            }
+            if self.synonyms.len() > MAX_SYNONYMS_COUNT {
+                return Err(format!("maximum {} synonyms allowed", MAX_SYNONYMS_COUNT));
+            }
+            for synonym in &self.synonyms {
+                validate_length(synonym, 1, MAX_SYNONYM_LENGTH)?;
+            }
+            Ok(())
+        });
+
+        builder.build()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,6 +488,8 @@ mod tests {
             source_url: Some("https://github.com/user/repo".to_string()),
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            license: None,
+            documentation: None,
         };
 
         assert!(req.validate().is_ok());
@@ -340,6 +507,8 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            license: None,
+            documentation: None,
         };
 
         let result = req.validate();
@@ -360,6 +529,8 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            license: None,
+            documentation: None,
         };
 
         let result = req.validate();
@@ -381,6 +552,8 @@ mod tests {
             publisher_address: "  gdlzfc3syjydzt7k67vz75hpjvieuvnixf47zg2fb2rmqqvu2hhgcysc  "
                 .to_string(),
             dependencies: vec![],
+            license: None,
+            documentation: None,
         };
 
         req.sanitize();
@@ -461,6 +634,8 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            license: None,
+            documentation: None,
         };
 
         let result = req.validate();
@@ -468,4 +643,48 @@ mod tests {
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.field == "tags"));
     }
+
+    #[test]
+    fn test_search_params_sanitization() {
+        let mut params = SearchParams {
+            q: Some("   ".to_string()),
+            maturity: Some(" Stable ".to_string()),
+            tags: Some(" defi, ,token ".to_string()),
+            ..Default::default()
+        };
+
+        params.sanitize();
+
+        assert_eq!(params.q, None);
+        assert_eq!(params.maturity.as_deref(), Some("stable"));
+        assert_eq!(params.tags.as_deref(), Some("defi,token"));
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_search_params_invalid() {
+        let params = SearchParams {
+            maturity: Some("ancient".to_string()),
+            page: Some(0),
+            limit: Some(500),
+            ..Default::default()
+        };
+
+        let errors = params.validate().unwrap_err();
+        for field in ["maturity", "page", "limit"] {
+            assert!(errors.iter().any(|e| e.field == field));
+        }
+    }
+
+    #[test]
+    fn test_upsert_synonyms_requires_one() {
+        let mut req = UpsertSynonymRequest {
+            synonyms: vec!["  ".to_string()],
+        };
+
+        req.sanitize();
+
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "synonyms"));
+    }
 }
@@ -0,0 +1,258 @@
+// indexer/src/fees.rs
+// Turns the network's ConfigSetting entries into the fee settings the API's
+// cost estimator reads from `network_fee_configs`. Field names match the
+// API's `FeeConfig`; anything not set here (the inclusion fee bid) takes the
+// estimator's default.
+
+use anyhow::{bail, Result};
+use serde_json::json;
+use stellar_xdr::curr::{
+    ConfigSettingContractLedgerCostV0, ConfigSettingEntry, ConfigSettingId, LedgerEntryData,
+    LedgerKey, LedgerKeyConfigSetting, Limits, ReadXdr, WriteXdr,
+};
+
+/// The host never charges less than this per KB written
+const MINIMUM_WRITE_FEE_PER_1KB: i64 = 1_000;
+
+const FEE_SETTINGS: [ConfigSettingId; 7] = [
+    ConfigSettingId::ContractComputeV0,
+    ConfigSettingId::ContractLedgerCostV0,
+    ConfigSettingId::ContractHistoricalDataV0,
+    ConfigSettingId::ContractEventsV0,
+    ConfigSettingId::ContractBandwidthV0,
+    ConfigSettingId::StateArchival,
+    ConfigSettingId::BucketlistSizeWindow,
+];
+
+/// Base64 `LedgerKey`s of every ConfigSetting the fee settings come from
+pub fn setting_keys() -> Result<Vec<String>> {
+    FEE_SETTINGS
+        .iter()
+        .map(|id| {
+            Ok(LedgerKey::ConfigSetting(LedgerKeyConfigSetting {
+                config_setting_id: *id,
+            })
+            .to_xdr_base64(Limits::none())?)
+        })
+        .collect()
+}
+
+/// Fee settings JSON from base64 `LedgerEntryData` values. Fails unless every
+/// setting in `setting_keys` is present.
+pub fn fee_settings(entries: &[String]) -> Result<serde_json::Value> {
+    let mut compute = None;
+    let mut ledger_cost = None;
+    let mut historical = None;
+    let mut events = None;
+    let mut bandwidth = None;
+    let mut archival = None;
+    let mut bucket_list_window = None;
+
+    for raw in entries {
+        let LedgerEntryData::ConfigSetting(setting) =
+            LedgerEntryData::from_xdr_base64(raw, Limits::none())?
+        else {
+            continue;
+        };
+        match setting {
+            ConfigSettingEntry::ContractComputeV0(v) => compute = Some(v),
+            ConfigSettingEntry::ContractLedgerCostV0(v) => ledger_cost = Some(v),
+            ConfigSettingEntry::ContractHistoricalDataV0(v) => historical = Some(v),
+            ConfigSettingEntry::ContractEventsV0(v) => events = Some(v),
+            ConfigSettingEntry::ContractBandwidthV0(v) => bandwidth = Some(v),
+            ConfigSettingEntry::StateArchival(v) => archival = Some(v),
+            ConfigSettingEntry::BucketlistSizeWindow(v) => bucket_list_window = Some(v),
+            _ => {}
+        }
+    }
+
+    let (
+        Some(compute),
+        Some(ledger_cost),
+        Some(historical),
+        Some(events),
+        Some(bandwidth),
+        Some(archival),
+        Some(window),
+    ) = (
+        compute,
+        ledger_cost,
+        historical,
+        events,
+        bandwidth,
+        archival,
+        bucket_list_window,
+    )
+    else {
+        bail!("getLedgerEntries did not return every fee setting");
+    };
+
+    let bucket_list_size = match window.len() {
+        0 => 0,
+        n => (window.iter().map(|s| *s as u128).sum::<u128>() / n as u128) as i64,
+    };
+    let write_fee = write_fee_per_1kb(bucket_list_size, &ledger_cost);
+
+    Ok(json!({
+        "fee_per_instruction_increment": compute.fee_rate_per_instructions_increment,
+        "fee_per_read_entry": ledger_cost.fee_read_ledger_entry,
+        "fee_per_write_entry": ledger_cost.fee_write_ledger_entry,
+        "fee_per_read_1kb": ledger_cost.fee_read1_kb,
+        "fee_per_write_1kb": write_fee,
+        "fee_per_rent_1kb": write_fee,
+        "fee_per_historical_1kb": historical.fee_historical1_kb,
+        "fee_per_contract_event_1kb": events.fee_contract_events1_kb,
+        "fee_per_transaction_size_1kb": bandwidth.fee_tx_size1_kb,
+        "persistent_rent_rate_denominator": archival.persistent_rent_rate_denominator,
+        "temporary_rent_rate_denominator": archival.temp_rent_rate_denominator,
+        "min_persistent_ttl": archival.min_persistent_ttl,
+        "min_temporary_ttl": archival.min_temporary_ttl,
+    }))
+}
+
+/// The write fee scales with the bucket list size: linearly between the low
+/// and high rates up to the target size, and `growth_factor` times faster
+/// past it. Same arithmetic as the host.
+fn write_fee_per_1kb(bucket_list_size: i64, cost: &ConfigSettingContractLedgerCostV0) -> i64 {
+    let target = cost.bucket_list_target_size_bytes.max(1);
+    let multiplier = cost
+        .write_fee1_kb_bucket_list_high
+        .saturating_sub(cost.write_fee1_kb_bucket_list_low);
+
+    let fee = if bucket_list_size < cost.bucket_list_target_size_bytes {
+        div_ceil(multiplier.saturating_mul(bucket_list_size), target)
+            .saturating_add(cost.write_fee1_kb_bucket_list_low)
+    } else {
+        let past_target = bucket_list_size - cost.bucket_list_target_size_bytes;
+        let growth = multiplier
+            .saturating_mul(past_target)
+            .saturating_mul(cost.bucket_list_write_fee_growth_factor as i64);
+        cost.write_fee1_kb_bucket_list_high
+            .saturating_add(div_ceil(growth, target))
+    };
+    fee.max(MINIMUM_WRITE_FEE_PER_1KB)
+}
+
+fn div_ceil(a: i64, b: i64) -> i64 {
+    a / b + i64::from(a % b != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        ConfigSettingContractBandwidthV0, ConfigSettingContractComputeV0,
+        ConfigSettingContractEventsV0, ConfigSettingContractHistoricalDataV0,
+        StateArchivalSettings,
+    };
+
+    fn ledger_cost() -> ConfigSettingContractLedgerCostV0 {
+        ConfigSettingContractLedgerCostV0 {
+            ledger_max_read_ledger_entries: 200,
+            ledger_max_read_bytes: 500_000,
+            ledger_max_write_ledger_entries: 125,
+            ledger_max_write_bytes: 143_360,
+            tx_max_read_ledger_entries: 40,
+            tx_max_read_bytes: 200_000,
+            tx_max_write_ledger_entries: 25,
+            tx_max_write_bytes: 132_096,
+            fee_read_ledger_entry: 6_250,
+            fee_write_ledger_entry: 10_000,
+            fee_read1_kb: 1_786,
+            bucket_list_target_size_bytes: 1_000,
+            write_fee1_kb_bucket_list_low: 2_000,
+            write_fee1_kb_bucket_list_high: 12_000,
+            bucket_list_write_fee_growth_factor: 2,
+        }
+    }
+
+    fn encode(setting: ConfigSettingEntry) -> String {
+        LedgerEntryData::ConfigSetting(setting)
+            .to_xdr_base64(Limits::none())
+            .unwrap()
+    }
+
+    #[test]
+    fn write_fee_follows_bucket_list_size() {
+        let cost = ledger_cost();
+        assert_eq!(write_fee_per_1kb(0, &cost), 2_000);
+        assert_eq!(write_fee_per_1kb(500, &cost), 7_000);
+        assert_eq!(write_fee_per_1kb(1_000, &cost), 12_000);
+        // 10,000 * 100 * 2 / 1,000 past the target
+        assert_eq!(write_fee_per_1kb(1_100, &cost), 14_000);
+
+        let cheap = ConfigSettingContractLedgerCostV0 {
+            write_fee1_kb_bucket_list_low: 0,
+            write_fee1_kb_bucket_list_high: 0,
+            ..cost
+        };
+        assert_eq!(write_fee_per_1kb(0, &cheap), MINIMUM_WRITE_FEE_PER_1KB);
+    }
+
+    #[test]
+    fn builds_fee_settings_from_config_entries() {
+        let mut entries = vec![
+            encode(ConfigSettingEntry::ContractComputeV0(
+                ConfigSettingContractComputeV0 {
+                    ledger_max_instructions: 500_000_000,
+                    tx_max_instructions: 100_000_000,
+                    fee_rate_per_instructions_increment: 25,
+                    tx_memory_limit: 41_943_040,
+                },
+            )),
+            encode(ConfigSettingEntry::ContractLedgerCostV0(ledger_cost())),
+            encode(ConfigSettingEntry::ContractHistoricalDataV0(
+                ConfigSettingContractHistoricalDataV0 {
+                    fee_historical1_kb: 16_235,
+                },
+            )),
+            encode(ConfigSettingEntry::ContractEventsV0(
+                ConfigSettingContractEventsV0 {
+                    tx_max_contract_events_size_bytes: 8_198,
+                    fee_contract_events1_kb: 10_000,
+                },
+            )),
+            encode(ConfigSettingEntry::ContractBandwidthV0(
+                ConfigSettingContractBandwidthV0 {
+                    ledger_max_txs_size_bytes: 133_120,
+                    tx_max_size_bytes: 132_096,
+                    fee_tx_size1_kb: 1_624,
+                },
+            )),
+            encode(ConfigSettingEntry::StateArchival(StateArchivalSettings {
+                max_entry_ttl: 3_110_400,
+                min_temporary_ttl: 16,
+                min_persistent_ttl: 2_073_600,
+                persistent_rent_rate_denominator: 2_103,
+                temp_rent_rate_denominator: 4_206,
+                max_entries_to_archive: 1_000,
+                bucket_list_size_window_sample_size: 30,
+                bucket_list_window_sample_period: 64,
+                eviction_scan_size: 100_000,
+                starting_eviction_scan_level: 6,
+            })),
+        ];
+        assert!(fee_settings(&entries).is_err());
+
+        entries.push(encode(ConfigSettingEntry::BucketlistSizeWindow(
+            vec![400, 600].try_into().unwrap(),
+        )));
+        let settings = fee_settings(&entries).unwrap();
+        assert_eq!(settings["fee_per_instruction_increment"], 25);
+        assert_eq!(settings["fee_per_write_1kb"], 7_000);
+        assert_eq!(settings["fee_per_rent_1kb"], 7_000);
+        assert_eq!(settings["fee_per_transaction_size_1kb"], 1_624);
+        assert_eq!(settings["temporary_rent_rate_denominator"], 4_206);
+        assert_eq!(settings["min_persistent_ttl"], 2_073_600);
+    }
+
+    #[test]
+    fn setting_keys_round_trip() {
+        let keys = setting_keys().unwrap();
+        assert_eq!(keys.len(), FEE_SETTINGS.len());
+        assert!(matches!(
+            LedgerKey::from_xdr_base64(&keys[0], Limits::none()).unwrap(),
+            LedgerKey::ConfigSetting(_)
+        ));
+    }
+}
@@ -0,0 +1,709 @@
+// indexer/src/ledger.rs
+// Decodes `getTransactions` results into the changes the indexer stores.
+// Pure XDR handling; nothing here touches the database or the network.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use stellar_xdr::curr::{
+    ContractDataDurability, HostFunction, InnerTransactionResultResult, LedgerEntry,
+    LedgerEntryChange, LedgerEntryData, LedgerKey, LedgerKeyContractData, Limits, Operation,
+    OperationBody, OperationResult, OperationResultTr, ReadXdr, ScVal, SorobanTransactionData,
+    TransactionEnvelope, TransactionExt, TransactionMeta, TransactionResult,
+    TransactionResultResult, WriteXdr,
+};
+
+use crate::rpc::RpcTransaction;
+
+// ─────────────────────────────────────────────────────────
+// Types
+// ─────────────────────────────────────────────────────────
+
+/// Mirrors the `ledger_entry_durability` enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+impl Durability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Durability::Instance => "instance",
+            Durability::Persistent => "persistent",
+            Durability::Temporary => "temporary",
+        }
+    }
+}
+
+/// A contract storage entry as written by a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEntry {
+    pub contract: String,
+    pub durability: Durability,
+    /// Base64 `LedgerKey`
+    pub key_xdr: String,
+    /// Base64 `LedgerEntry`
+    pub entry_xdr: String,
+    pub key_display: String,
+    pub last_modified_ledger: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryChange {
+    Upsert(StoredEntry),
+    Remove {
+        contract: String,
+        key_xdr: String,
+        ledger: u32,
+    },
+    /// A TTL change, matched back to its storage key through the footprint
+    Ttl {
+        contract: String,
+        key_xdr: String,
+        live_until_ledger: u32,
+    },
+}
+
+/// A token holder's balance as written by a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    pub contract: String,
+    pub holder: String,
+    pub balance: i128,
+}
+
+/// The contract call a transaction made, and how it ended
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub contract: String,
+    pub function: String,
+    pub success: bool,
+    /// Result code of a failed call, e.g. `Trapped`
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedTransaction {
+    pub hash: String,
+    pub ledger: u32,
+    /// Ledger close time, unix seconds
+    pub closed_at: i64,
+    pub entries: Vec<EntryChange>,
+    pub balances: Vec<BalanceChange>,
+    pub invocation: Option<Invocation>,
+}
+
+// ─────────────────────────────────────────────────────────
+// Decoding
+// ─────────────────────────────────────────────────────────
+
+pub fn decode(tx: &RpcTransaction) -> Result<DecodedTransaction> {
+    let envelope = TransactionEnvelope::from_xdr_base64(&tx.envelope_xdr, Limits::none())
+        .context("invalid envelope XDR")?;
+    let meta = tx
+        .result_meta_xdr
+        .as_deref()
+        .map(|m| TransactionMeta::from_xdr_base64(m, Limits::none()))
+        .transpose()
+        .context("invalid result meta XDR")?;
+
+    let (entries, balances) = match &meta {
+        Some(meta) => (
+            entry_changes(&envelope, meta, tx.ledger)?,
+            balance_changes(meta),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let invocation = invoked_function(&envelope).map(|(contract, function)| {
+        let success = tx.status == "SUCCESS";
+        Invocation {
+            contract,
+            function,
+            success,
+            error: (!success).then(|| failure_code(tx)),
+        }
+    });
+
+    Ok(DecodedTransaction {
+        hash: tx.tx_hash.clone(),
+        ledger: tx.ledger,
+        closed_at: tx.created_at,
+        entries,
+        balances,
+        invocation,
+    })
+}
+
+fn operations(envelope: &TransactionEnvelope) -> &[Operation] {
+    match envelope {
+        TransactionEnvelope::TxV0(v0) => &v0.tx.operations,
+        TransactionEnvelope::Tx(v1) => &v1.tx.operations,
+        TransactionEnvelope::TxFeeBump(bump) => match &bump.tx.inner_tx {
+            stellar_xdr::curr::FeeBumpTransactionInnerTx::Tx(inner) => &inner.tx.operations,
+        },
+    }
+}
+
+/// Contract and function of the transaction's contract call. Soroban
+/// transactions carry exactly one host function operation.
+fn invoked_function(envelope: &TransactionEnvelope) -> Option<(String, String)> {
+    operations(envelope).iter().find_map(|op| match &op.body {
+        OperationBody::InvokeHostFunction(invoke) => match &invoke.host_function {
+            HostFunction::InvokeContract(call) => Some((
+                call.contract_address.to_string(),
+                call.function_name.to_utf8_string_lossy(),
+            )),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Why a transaction failed: the host function result code when the call
+/// itself failed, otherwise the transaction or operation result code
+fn failure_code(tx: &RpcTransaction) -> String {
+    let result = tx
+        .result_xdr
+        .as_deref()
+        .and_then(|r| TransactionResult::from_xdr_base64(r, Limits::none()).ok());
+    let ops = match result.as_ref().map(|r| &r.result) {
+        Some(TransactionResultResult::TxFailed(ops)) => ops,
+        Some(
+            TransactionResultResult::TxFeeBumpInnerFailed(pair)
+            | TransactionResultResult::TxFeeBumpInnerSuccess(pair),
+        ) => match &pair.result.result {
+            InnerTransactionResultResult::TxFailed(ops) => ops,
+            other => return other.name().to_string(),
+        },
+        Some(other) => return other.name().to_string(),
+        None => return tx.status.clone(),
+    };
+    ops.iter()
+        .find_map(|op| match op {
+            OperationResult::OpInner(OperationResultTr::InvokeHostFunction(r)) => {
+                Some(r.name().to_string())
+            }
+            OperationResult::OpInner(_) => None,
+            other => Some(other.name().to_string()),
+        })
+        .unwrap_or_else(|| tx.status.clone())
+}
+
+fn soroban_data(envelope: &TransactionEnvelope) -> Option<&SorobanTransactionData> {
+    let ext = match envelope {
+        TransactionEnvelope::TxV0(_) => return None,
+        TransactionEnvelope::Tx(v1) => &v1.tx.ext,
+        TransactionEnvelope::TxFeeBump(bump) => match &bump.tx.inner_tx {
+            stellar_xdr::curr::FeeBumpTransactionInnerTx::Tx(inner) => &inner.tx.ext,
+        },
+    };
+    match ext {
+        TransactionExt::V1(data) => Some(data),
+        TransactionExt::V0 => None,
+    }
+}
+
+/// Every ledger entry change in the meta, in application order
+fn meta_changes(meta: &TransactionMeta) -> Vec<&LedgerEntryChange> {
+    match meta {
+        TransactionMeta::V0(ops) => ops.iter().flat_map(|op| op.changes.0.iter()).collect(),
+        TransactionMeta::V1(v1) => v1
+            .tx_changes
+            .0
+            .iter()
+            .chain(v1.operations.iter().flat_map(|op| op.changes.0.iter()))
+            .collect(),
+        TransactionMeta::V2(v2) => v2
+            .tx_changes_before
+            .0
+            .iter()
+            .chain(v2.operations.iter().flat_map(|op| op.changes.0.iter()))
+            .chain(v2.tx_changes_after.0.iter())
+            .collect(),
+        TransactionMeta::V3(v3) => v3
+            .tx_changes_before
+            .0
+            .iter()
+            .chain(v3.operations.iter().flat_map(|op| op.changes.0.iter()))
+            .chain(v3.tx_changes_after.0.iter())
+            .collect(),
+    }
+}
+
+fn key_hash(key: &LedgerKey) -> Result<[u8; 32]> {
+    Ok(Sha256::digest(key.to_xdr(Limits::none())?).into())
+}
+
+fn entry_changes(
+    envelope: &TransactionEnvelope,
+    meta: &TransactionMeta,
+    ledger: u32,
+) -> Result<Vec<EntryChange>> {
+    // TTL entries only carry the hash of the key they extend; the key itself
+    // is in the transaction footprint.
+    let mut footprint = HashMap::new();
+    if let Some(data) = soroban_data(envelope) {
+        let fp = &data.resources.footprint;
+        for key in fp.read_only.iter().chain(fp.read_write.iter()) {
+            if let LedgerKey::ContractData(k) = key {
+                footprint.insert(key_hash(key)?, k);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    for change in meta_changes(meta) {
+        match change {
+            LedgerEntryChange::Created(entry) | LedgerEntryChange::Updated(entry) => {
+                match &entry.data {
+                    LedgerEntryData::ContractData(_) => {
+                        out.push(EntryChange::Upsert(stored_entry(entry)?));
+                    }
+                    LedgerEntryData::Ttl(ttl) => {
+                        if let Some(key) = footprint.get(&ttl.key_hash.0) {
+                            out.push(EntryChange::Ttl {
+                                contract: key.contract.to_string(),
+                                key_xdr: contract_key_xdr(key)?,
+                                live_until_ledger: ttl.live_until_ledger_seq,
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            LedgerEntryChange::Removed(LedgerKey::ContractData(key)) => {
+                out.push(EntryChange::Remove {
+                    contract: key.contract.to_string(),
+                    key_xdr: contract_key_xdr(key)?,
+                    ledger,
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Token balance writes, in application order. Both the SEP-41 reference
+/// token (`Balance(addr) => i128`) and the Stellar Asset Contract
+/// (`Balance(addr) => {amount, authorized, clawback}`) use this layout; a
+/// removed balance entry means the holder has nothing left.
+fn balance_changes(meta: &TransactionMeta) -> Vec<BalanceChange> {
+    meta_changes(meta)
+        .into_iter()
+        .filter_map(|change| {
+            let (contract, key, balance) = match change {
+                LedgerEntryChange::Created(entry) | LedgerEntryChange::Updated(entry) => {
+                    let LedgerEntryData::ContractData(data) = &entry.data else {
+                        return None;
+                    };
+                    (&data.contract, &data.key, balance_amount(&data.val)?)
+                }
+                LedgerEntryChange::Removed(LedgerKey::ContractData(key)) => {
+                    (&key.contract, &key.key, 0)
+                }
+                _ => return None,
+            };
+            Some(BalanceChange {
+                contract: contract.to_string(),
+                holder: balance_holder(key)?,
+                balance,
+            })
+        })
+        .collect()
+}
+
+fn balance_holder(key: &ScVal) -> Option<String> {
+    let ScVal::Vec(Some(items)) = key else {
+        return None;
+    };
+    match items.as_slice() {
+        [ScVal::Symbol(tag), ScVal::Address(holder)] if tag.as_slice() == b"Balance" => {
+            Some(holder.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn balance_amount(val: &ScVal) -> Option<i128> {
+    let amount = match val {
+        ScVal::Map(Some(fields)) => {
+            &fields
+                .iter()
+                .find(|e| matches!(&e.key, ScVal::Symbol(s) if s.as_slice() == b"amount"))?
+                .val
+        }
+        other => other,
+    };
+    match amount {
+        ScVal::I128(p) => Some(((p.hi as i128) << 64) | p.lo as i128),
+        _ => None,
+    }
+}
+
+fn contract_key_xdr(key: &LedgerKeyContractData) -> Result<String> {
+    Ok(LedgerKey::ContractData(key.clone()).to_xdr_base64(Limits::none())?)
+}
+
+fn stored_entry(entry: &LedgerEntry) -> Result<StoredEntry> {
+    let LedgerEntryData::ContractData(data) = &entry.data else {
+        anyhow::bail!("not a contract data entry");
+    };
+    let key = LedgerKeyContractData {
+        contract: data.contract.clone(),
+        key: data.key.clone(),
+        durability: data.durability,
+    };
+    let durability = match (&data.key, data.durability) {
+        (ScVal::LedgerKeyContractInstance, _) => Durability::Instance,
+        (_, ContractDataDurability::Persistent) => Durability::Persistent,
+        (_, ContractDataDurability::Temporary) => Durability::Temporary,
+    };
+
+    Ok(StoredEntry {
+        contract: data.contract.to_string(),
+        durability,
+        key_xdr: contract_key_xdr(&key)?,
+        entry_xdr: entry.to_xdr_base64(Limits::none())?,
+        key_display: render(&data.key),
+        last_modified_ledger: entry.last_modified_ledger_seq,
+    })
+}
+
+/// Short human-readable rendering of a storage key
+pub fn render(val: &ScVal) -> String {
+    let list = |items: &mut dyn Iterator<Item = String>| items.collect::<Vec<_>>().join(", ");
+    match val {
+        ScVal::LedgerKeyContractInstance => "instance".into(),
+        ScVal::Void => "()".into(),
+        ScVal::Bool(b) => b.to_string(),
+        ScVal::U32(n) => n.to_string(),
+        ScVal::I32(n) => n.to_string(),
+        ScVal::U64(n) => n.to_string(),
+        ScVal::I64(n) => n.to_string(),
+        ScVal::U128(p) => (((p.hi as u128) << 64) | p.lo as u128).to_string(),
+        ScVal::I128(p) => (((p.hi as i128) << 64) | p.lo as i128).to_string(),
+        ScVal::Symbol(s) => s.to_utf8_string_lossy(),
+        ScVal::String(s) => format!("{:?}", s.to_utf8_string_lossy()),
+        ScVal::Bytes(b) => format!("0x{}", hex_encode(b.as_slice())),
+        ScVal::Address(a) => a.to_string(),
+        ScVal::Vec(Some(items)) => match items.as_slice() {
+            // Enum-style keys, e.g. Balance(GABC…)
+            [ScVal::Symbol(tag), rest @ ..] if !rest.is_empty() => format!(
+                "{}({})",
+                tag.to_utf8_string_lossy(),
+                list(&mut rest.iter().map(render))
+            ),
+            items => format!("[{}]", list(&mut items.iter().map(render))),
+        },
+        ScVal::Map(Some(entries)) => format!(
+            "{{{}}}",
+            list(
+                &mut entries
+                    .iter()
+                    .map(|e| format!("{}: {}", render(&e.key), render(&e.val)))
+            )
+        ),
+        other => other.name().to_string(),
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        AccountId, ContractDataEntry, ExtensionPoint, Hash, Int128Parts, InvokeContractArgs,
+        InvokeHostFunctionOp, InvokeHostFunctionResult, LedgerEntryChanges, LedgerEntryExt,
+        LedgerFootprint, Memo, MuxedAccount, OperationMeta, Preconditions, PublicKey, ScAddress,
+        ScMapEntry, ScSymbol, SequenceNumber, SorobanResources, Transaction, TransactionMetaV3,
+        TransactionResultExt, TransactionV1Envelope, TtlEntry, Uint256,
+    };
+
+    pub const CONTRACT: [u8; 32] = [7; 32];
+
+    pub fn contract() -> ScAddress {
+        ScAddress::Contract(Hash(CONTRACT))
+    }
+
+    pub fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    pub fn data_entry(key: ScVal, val: ScVal, ledger: u32) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: ledger,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: contract(),
+                key,
+                durability: ContractDataDurability::Persistent,
+                val,
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    pub fn data_key(key: ScVal) -> LedgerKey {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract(),
+            key,
+            durability: ContractDataDurability::Persistent,
+        })
+    }
+
+    pub fn envelope(footprint: Vec<LedgerKey>) -> TransactionEnvelope {
+        TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: Transaction {
+                source_account: MuxedAccount::Ed25519(Uint256([1; 32])),
+                fee: 100,
+                seq_num: SequenceNumber(1),
+                cond: Preconditions::None,
+                memo: Memo::None,
+                operations: Vec::new().try_into().unwrap(),
+                ext: TransactionExt::V1(SorobanTransactionData {
+                    ext: ExtensionPoint::V0,
+                    resources: SorobanResources {
+                        footprint: LedgerFootprint {
+                            read_only: Vec::new().try_into().unwrap(),
+                            read_write: footprint.try_into().unwrap(),
+                        },
+                        instructions: 0,
+                        read_bytes: 0,
+                        write_bytes: 0,
+                    },
+                    resource_fee: 0,
+                }),
+            },
+            signatures: Vec::new().try_into().unwrap(),
+        })
+    }
+
+    pub fn meta(changes: Vec<LedgerEntryChange>) -> TransactionMeta {
+        TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LedgerEntryChanges(Vec::new().try_into().unwrap()),
+            operations: vec![OperationMeta {
+                changes: LedgerEntryChanges(changes.try_into().unwrap()),
+            }]
+            .try_into()
+            .unwrap(),
+            tx_changes_after: LedgerEntryChanges(Vec::new().try_into().unwrap()),
+            soroban_meta: None,
+        })
+    }
+
+    pub fn rpc_tx(
+        envelope: &TransactionEnvelope,
+        meta: &TransactionMeta,
+        ledger: u32,
+    ) -> RpcTransaction {
+        RpcTransaction {
+            status: "SUCCESS".into(),
+            ledger,
+            created_at: 1_700_000_000,
+            tx_hash: "ab".repeat(32),
+            envelope_xdr: envelope.to_xdr_base64(Limits::none()).unwrap(),
+            result_xdr: None,
+            result_meta_xdr: Some(meta.to_xdr_base64(Limits::none()).unwrap()),
+        }
+    }
+
+    #[test]
+    fn decodes_storage_writes_removals_and_ttls() {
+        let counter = symbol("Counter");
+        let old = symbol("Old");
+        let env = envelope(vec![data_key(counter.clone()), data_key(old.clone())]);
+        let meta = meta(vec![
+            LedgerEntryChange::State(data_entry(counter.clone(), ScVal::U32(1), 90)),
+            LedgerEntryChange::Updated(data_entry(counter.clone(), ScVal::U32(2), 100)),
+            LedgerEntryChange::Removed(data_key(old.clone())),
+            LedgerEntryChange::Updated(LedgerEntry {
+                last_modified_ledger_seq: 100,
+                data: LedgerEntryData::Ttl(TtlEntry {
+                    key_hash: Hash(key_hash(&data_key(counter.clone())).unwrap()),
+                    live_until_ledger_seq: 5000,
+                }),
+                ext: LedgerEntryExt::V0,
+            }),
+        ]);
+
+        let decoded = decode(&rpc_tx(&env, &meta, 100)).unwrap();
+        assert_eq!(decoded.entries.len(), 3);
+
+        let counter_xdr = data_key(counter).to_xdr_base64(Limits::none()).unwrap();
+        match &decoded.entries[0] {
+            EntryChange::Upsert(entry) => {
+                assert_eq!(entry.contract, contract().to_string());
+                assert_eq!(entry.durability, Durability::Persistent);
+                assert_eq!(entry.key_xdr, counter_xdr);
+                assert_eq!(entry.key_display, "Counter");
+                assert_eq!(entry.last_modified_ledger, 100);
+                let back = LedgerEntry::from_xdr_base64(&entry.entry_xdr, Limits::none()).unwrap();
+                assert_eq!(back.last_modified_ledger_seq, 100);
+            }
+            other => panic!("expected upsert, got {:?}", other),
+        }
+        assert!(matches!(
+            &decoded.entries[1],
+            EntryChange::Remove { ledger: 100, .. }
+        ));
+        assert_eq!(
+            decoded.entries[2],
+            EntryChange::Ttl {
+                contract: contract().to_string(),
+                key_xdr: counter_xdr,
+                live_until_ledger: 5000,
+            }
+        );
+    }
+
+    #[test]
+    fn instance_storage_is_its_own_durability() {
+        let env = envelope(Vec::new());
+        let meta = meta(vec![LedgerEntryChange::Created(data_entry(
+            ScVal::LedgerKeyContractInstance,
+            ScVal::Void,
+            10,
+        ))]);
+        let decoded = decode(&rpc_tx(&env, &meta, 10)).unwrap();
+        match &decoded.entries[0] {
+            EntryChange::Upsert(entry) => {
+                assert_eq!(entry.durability, Durability::Instance);
+                assert_eq!(entry.key_display, "instance");
+            }
+            other => panic!("expected upsert, got {:?}", other),
+        }
+    }
+
+    fn i128_val(n: i128) -> ScVal {
+        ScVal::I128(Int128Parts {
+            hi: (n >> 64) as i64,
+            lo: n as u64,
+        })
+    }
+
+    #[test]
+    fn decodes_token_balances() {
+        let holder =
+            ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([3; 32]))));
+        let balance_key = ScVal::Vec(Some(
+            vec![symbol("Balance"), ScVal::Address(holder.clone())]
+                .try_into()
+                .unwrap(),
+        ));
+        let sac_value = ScVal::Map(Some(
+            vec![
+                ScMapEntry {
+                    key: symbol("amount"),
+                    val: i128_val(7),
+                },
+                ScMapEntry {
+                    key: symbol("authorized"),
+                    val: ScVal::Bool(true),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        ));
+        let meta = meta(vec![
+            LedgerEntryChange::State(data_entry(balance_key.clone(), i128_val(1), 40)),
+            LedgerEntryChange::Updated(data_entry(
+                balance_key.clone(),
+                i128_val(u64::MAX as i128 + 5),
+                50,
+            )),
+            LedgerEntryChange::Updated(data_entry(balance_key.clone(), sac_value, 50)),
+            LedgerEntryChange::Updated(data_entry(symbol("Admin"), i128_val(9), 50)),
+            LedgerEntryChange::Removed(data_key(balance_key)),
+        ]);
+
+        let decoded = decode(&rpc_tx(&envelope(Vec::new()), &meta, 50)).unwrap();
+        assert_eq!(decoded.ledger, 50);
+        assert_eq!(decoded.closed_at, 1_700_000_000);
+        let balances: Vec<i128> = decoded.balances.iter().map(|b| b.balance).collect();
+        assert_eq!(balances, vec![u64::MAX as i128 + 5, 7, 0]);
+        assert!(decoded
+            .balances
+            .iter()
+            .all(|b| b.holder == holder.to_string() && b.contract == contract().to_string()));
+    }
+
+    #[test]
+    fn decodes_contract_invocations() {
+        let mut env = envelope(Vec::new());
+        let TransactionEnvelope::Tx(v1) = &mut env else {
+            unreachable!()
+        };
+        v1.tx.operations = vec![Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                    contract_address: contract(),
+                    function_name: ScSymbol("transfer".try_into().unwrap()),
+                    args: Vec::new().try_into().unwrap(),
+                }),
+                auth: Vec::new().try_into().unwrap(),
+            }),
+        }]
+        .try_into()
+        .unwrap();
+        let meta = meta(Vec::new());
+
+        let ok = decode(&rpc_tx(&env, &meta, 60)).unwrap();
+        assert_eq!(ok.hash, "ab".repeat(32));
+        assert_eq!(
+            ok.invocation,
+            Some(Invocation {
+                contract: contract().to_string(),
+                function: "transfer".into(),
+                success: true,
+                error: None,
+            })
+        );
+
+        let mut failed = rpc_tx(&env, &meta, 60);
+        failed.status = "FAILED".into();
+        failed.result_xdr = Some(
+            TransactionResult {
+                fee_charged: 100,
+                result: TransactionResultResult::TxFailed(
+                    vec![OperationResult::OpInner(
+                        OperationResultTr::InvokeHostFunction(InvokeHostFunctionResult::Trapped),
+                    )]
+                    .try_into()
+                    .unwrap(),
+                ),
+                ext: TransactionResultExt::V0,
+            }
+            .to_xdr_base64(Limits::none())
+            .unwrap(),
+        );
+        let invocation = decode(&failed).unwrap().invocation.unwrap();
+        assert!(!invocation.success);
+        assert_eq!(invocation.error.as_deref(), Some("Trapped"));
+
+        let plain = decode(&rpc_tx(&envelope(Vec::new()), &meta, 60)).unwrap();
+        assert_eq!(plain.invocation, None);
+    }
+
+    #[test]
+    fn renders_enum_style_keys() {
+        let key = ScVal::Vec(Some(
+            vec![symbol("Balance"), ScVal::Address(contract())]
+                .try_into()
+                .unwrap(),
+        ));
+        assert_eq!(render(&key), format!("Balance({})", contract()));
+        let list = ScVal::Vec(Some(
+            vec![ScVal::U32(1), ScVal::Bool(true)].try_into().unwrap(),
+        ));
+        assert_eq!(render(&list), "[1, true]");
+    }
+}
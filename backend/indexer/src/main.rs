@@ -1,11 +1,76 @@
 // Blockchain indexer for monitoring Stellar network
-// This will be implemented in future iterations
+//
+// Polls Soroban RPC for new transactions and records, for registered
+// contracts, the ledger state the registry reads elsewhere: contract storage
+// entries (replay fixtures and backups), token balance history (governance
+// tallying), contract invocations (SLA tracking) and the last processed
+// ledger. It also snapshots the network's fee settings for cost estimates.
 
-use anyhow::Result;
+mod fees;
+mod ledger;
+mod rpc;
+mod store;
+
+use anyhow::{bail, Context, Result};
+use dotenv::dotenv;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use crate::rpc::{PageStart, RpcClient, PAGE_LIMIT};
+use crate::store::LedgerState;
+
+const DEFAULT_RPC_URL: &str = "https://soroban-testnet.stellar.org";
+const DEFAULT_POLL_SECS: u64 = 5;
+/// Pages applied per poll, so one poll never holds a huge backlog in memory
+const MAX_PAGES_PER_POLL: usize = 50;
+/// Fee settings only change by validator vote, or slowly with the bucket
+/// list size
+const FEE_REFRESH_INTERVAL: Duration = Duration::from_secs(600);
+
+struct Config {
+    network: String,
+    rpc_url: String,
+    poll_interval: Duration,
+    /// Where to start when nothing has been indexed yet; defaults to the
+    /// latest ledger
+    start_ledger: Option<u32>,
+}
+
+impl Config {
+    fn from_env() -> Result<Self> {
+        let network = std::env::var("STELLAR_NETWORK").unwrap_or_else(|_| "testnet".into());
+        if !matches!(network.as_str(), "mainnet" | "testnet" | "futurenet") {
+            bail!(
+                "STELLAR_NETWORK must be mainnet, testnet or futurenet, got {}",
+                network
+            );
+        }
+        let poll_secs = match std::env::var("INDEXER_POLL_INTERVAL_SECS") {
+            Ok(v) => v
+                .parse()
+                .context("INDEXER_POLL_INTERVAL_SECS must be a number")?,
+            Err(_) => DEFAULT_POLL_SECS,
+        };
+        let start_ledger = std::env::var("INDEXER_START_LEDGER")
+            .ok()
+            .map(|v| v.parse().context("INDEXER_START_LEDGER must be a number"))
+            .transpose()?;
+
+        Ok(Self {
+            network,
+            rpc_url: std::env::var("STELLAR_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.into()),
+            poll_interval: Duration::from_secs(poll_secs.max(1)),
+            start_ledger,
+        })
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    dotenv().ok();
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
@@ -15,15 +80,135 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    tracing::info!("Indexer service starting...");
-    tracing::info!("This service will monitor Stellar network for contract deployments");
-    tracing::info!("Implementation coming soon!");
+    let config = Config::from_env()?;
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await?;
+
+    let rpc = RpcClient::new(config.rpc_url.clone());
+    let network_info = rpc.get_network().await?;
+    tracing::info!(
+        network = %config.network,
+        rpc = %config.rpc_url,
+        passphrase = %network_info.passphrase,
+        protocol = network_info.protocol_version,
+        "Indexer service starting..."
+    );
+
+    // Migrations are applied by the API; the indexer only writes
+    let mut interval = tokio::time::interval(config.poll_interval);
+    let mut fees_refreshed: Option<Instant> = None;
+    loop {
+        interval.tick().await;
+        if let Err(err) = poll(&pool, &rpc, &config, &network_info.passphrase).await {
+            tracing::error!(error = ?err, "indexer: poll failed");
+        }
+        if fees_refreshed.is_none_or(|at| at.elapsed() >= FEE_REFRESH_INTERVAL) {
+            match refresh_fees(&pool, &rpc, &config).await {
+                Ok(()) => fees_refreshed = Some(Instant::now()),
+                Err(err) => tracing::error!(error = ?err, "indexer: fee refresh failed"),
+            }
+        }
+    }
+}
+
+async fn refresh_fees(pool: &PgPool, rpc: &RpcClient, config: &Config) -> Result<()> {
+    let result = rpc.get_ledger_entries(&fees::setting_keys()?).await?;
+    let entries: Vec<String> = result.entries.into_iter().map(|e| e.xdr).collect();
+    let settings = fees::fee_settings(&entries)?;
+    if store::save_fee_config(pool, &config.network, result.latest_ledger, &settings).await? {
+        tracing::info!(
+            ledger = result.latest_ledger,
+            "indexer: recorded new fee settings"
+        );
+    }
+    Ok(())
+}
+
+/// Apply every transaction since the last processed ledger, one page per
+/// database transaction, recording progress only up to complete ledgers.
+async fn poll(pool: &PgPool, rpc: &RpcClient, config: &Config, passphrase: &str) -> Result<()> {
+    let latest = rpc.get_latest_ledger().await?;
+    let start = match store::last_ledger(pool, &config.network).await? {
+        Some(seq) => seq + 1,
+        None => config.start_ledger.unwrap_or(latest.sequence),
+    };
+    if start > latest.sequence {
+        return Ok(());
+    }
+
+    let health = rpc.get_health().await?;
+    let start = if start < health.oldest_ledger {
+        tracing::warn!(
+            from = start,
+            to = health.oldest_ledger,
+            "indexer: ledgers fell out of the RPC retention window; skipping ahead"
+        );
+        health.oldest_ledger
+    } else {
+        start
+    };
+
+    let contracts = store::registered_contracts(pool, &config.network).await?;
+    let mut page = rpc.get_transactions(PageStart::Ledger(start)).await?;
+    // Last ledger known to be complete, with its close time
+    let mut complete: Option<(u32, i64)> = None;
+    let mut current: Option<(u32, i64)> = None;
+    let mut applied = 0usize;
+
+    for pages in 1.. {
+        let mut tx = pool.begin().await?;
+        for raw in &page.transactions {
+            if let Some((ledger, closed_at)) = current {
+                if raw.ledger != ledger {
+                    complete = Some((ledger, closed_at));
+                }
+            }
+            current = Some((raw.ledger, raw.created_at));
+
+            match ledger::decode(raw) {
+                Ok(decoded) => {
+                    store::apply_transaction(&mut tx, &contracts, &decoded).await?;
+                    applied += 1;
+                }
+                Err(err) => {
+                    tracing::warn!(tx = %raw.tx_hash, error = ?err, "indexer: skipping undecodable transaction")
+                }
+            }
+        }
+
+        // A short page means we reached the RPC's latest ledger
+        let done = page.transactions.len() < PAGE_LIMIT;
+        if done {
+            complete = Some((page.latest_ledger, page.latest_ledger_close_timestamp));
+        }
+        if let Some((sequence, timestamp)) = complete {
+            let state = LedgerState {
+                sequence,
+                timestamp,
+                protocol_version: latest.protocol_version,
+                network_passphrase: passphrase,
+            };
+            store::save_ledger_state(&mut tx, &config.network, &state).await?;
+        }
+        tx.commit().await?;
 
-    // TODO: Implement indexer logic
-    // - Connect to Stellar RPC
-    // - Monitor for new contract deployments
-    // - Extract contract metadata
-    // - Store in database
+        if done || pages >= MAX_PAGES_PER_POLL {
+            break;
+        }
+        page = rpc
+            .get_transactions(PageStart::Cursor(page.cursor.clone()))
+            .await?;
+    }
 
+    if applied > 0 {
+        tracing::debug!(
+            transactions = applied,
+            ledger = complete.map(|c| c.0),
+            "indexer: applied transactions"
+        );
+    }
     Ok(())
 }
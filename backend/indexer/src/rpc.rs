@@ -0,0 +1,210 @@
+// indexer/src/rpc.rs
+// Minimal Soroban RPC client: just the JSON-RPC methods the indexer polls.
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::json;
+
+/// Transactions requested per `getTransactions` page (the RPC maximum is 200)
+pub const PAGE_LIMIT: usize = 200;
+
+pub struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInfo {
+    pub passphrase: String,
+    pub protocol_version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestLedger {
+    pub sequence: u32,
+    pub protocol_version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    pub oldest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsPage {
+    #[serde(default)]
+    pub transactions: Vec<RpcTransaction>,
+    pub latest_ledger: u32,
+    #[serde(deserialize_with = "int_or_string")]
+    pub latest_ledger_close_timestamp: i64,
+    pub cursor: String,
+}
+
+/// One transaction as returned by `getTransactions`, XDR fields base64 encoded
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    /// `SUCCESS` or `FAILED`
+    pub status: String,
+    pub ledger: u32,
+    /// Close time of `ledger`, unix seconds
+    #[serde(deserialize_with = "int_or_string")]
+    pub created_at: i64,
+    pub tx_hash: String,
+    pub envelope_xdr: String,
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+    #[serde(default)]
+    pub result_meta_xdr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerEntries {
+    #[serde(default)]
+    pub entries: Vec<RpcLedgerEntry>,
+    pub latest_ledger: u32,
+}
+
+/// One entry from `getLedgerEntries`; `xdr` is a base64 `LedgerEntryData`
+#[derive(Debug, Deserialize)]
+pub struct RpcLedgerEntry {
+    pub xdr: String,
+}
+
+/// Where a `getTransactions` page starts
+pub enum PageStart {
+    Ledger(u32),
+    Cursor(String),
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// Older RPC releases encode 64-bit integers as strings
+fn int_or_string<'de, D: Deserializer<'de>>(d: D) -> Result<i64, D::Error> {
+    match serde_json::Value::deserialize(d)? {
+        serde_json::Value::Number(n) => n
+            .as_i64()
+            .ok_or_else(|| serde::de::Error::custom("integer out of range")),
+        serde_json::Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        other => Err(serde::de::Error::custom(format!(
+            "expected an integer, got {}",
+            other
+        ))),
+    }
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let response: RpcResponse<T> = self
+            .http
+            .post(&self.url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .with_context(|| format!("{} request failed", method))?
+            .error_for_status()
+            .with_context(|| format!("{} request failed", method))?
+            .json()
+            .await
+            .with_context(|| format!("invalid {} response", method))?;
+
+        if let Some(err) = response.error {
+            bail!("{} failed ({}): {}", method, err.code, err.message);
+        }
+        response
+            .result
+            .with_context(|| format!("{} returned no result", method))
+    }
+
+    pub async fn get_network(&self) -> Result<NetworkInfo> {
+        self.call("getNetwork", json!({})).await
+    }
+
+    pub async fn get_latest_ledger(&self) -> Result<LatestLedger> {
+        self.call("getLatestLedger", json!({})).await
+    }
+
+    pub async fn get_health(&self) -> Result<Health> {
+        self.call("getHealth", json!({})).await
+    }
+
+    /// `keys` are base64 `LedgerKey`s
+    pub async fn get_ledger_entries(&self, keys: &[String]) -> Result<LedgerEntries> {
+        self.call("getLedgerEntries", json!({ "keys": keys })).await
+    }
+
+    pub async fn get_transactions(&self, start: PageStart) -> Result<TransactionsPage> {
+        let params = match start {
+            PageStart::Ledger(ledger) => json!({
+                "startLedger": ledger,
+                "pagination": { "limit": PAGE_LIMIT },
+            }),
+            PageStart::Cursor(cursor) => json!({
+                "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+            }),
+        };
+        self.call("getTransactions", params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions_page_accepts_both_integer_encodings() {
+        let page: TransactionsPage = serde_json::from_value(json!({
+            "transactions": [{
+                "status": "SUCCESS",
+                "applicationOrder": 1,
+                "feeBump": false,
+                "envelopeXdr": "AAAA",
+                "resultXdr": "AAAA",
+                "resultMetaXdr": "AAAA",
+                "ledger": 1234,
+                "createdAt": "1717166042",
+                "txHash": "ab12",
+            }],
+            "latestLedger": 1240,
+            "latestLedgerCloseTimestamp": 1717166070,
+            "oldestLedger": 1000,
+            "oldestLedgerCloseTimestamp": 1716000000,
+            "cursor": "5299989595623424",
+        }))
+        .unwrap();
+
+        assert_eq!(page.transactions[0].status, "SUCCESS");
+        assert_eq!(page.transactions[0].created_at, 1717166042);
+        assert_eq!(page.latest_ledger_close_timestamp, 1717166070);
+        assert_eq!(
+            page.transactions[0].result_meta_xdr.as_deref(),
+            Some("AAAA")
+        );
+    }
+}
@@ -0,0 +1,244 @@
+// indexer/src/store.rs
+// Database writes for indexed ledger data. Every write is idempotent so a
+// page can be re-applied after a crash or a partial poll.
+
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::ledger::{BalanceChange, DecodedTransaction, EntryChange, Invocation};
+
+/// Ledger the indexer has fully processed, with the network details
+/// replay fixtures need to rebuild a ledger snapshot
+pub struct LedgerState<'a> {
+    pub sequence: u32,
+    pub timestamp: i64,
+    pub protocol_version: u32,
+    pub network_passphrase: &'a str,
+}
+
+/// Registered contracts on `network`, keyed by on-chain contract ID
+pub async fn registered_contracts(
+    db: &PgPool,
+    network: &str,
+) -> Result<HashMap<String, Uuid>, sqlx::Error> {
+    let rows: Vec<(String, Uuid)> =
+        sqlx::query_as("SELECT contract_id, id FROM contracts WHERE network = $1::network_type")
+            .bind(network)
+            .fetch_all(db)
+            .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// The last ledger processed on `network`, if any
+pub async fn last_ledger(db: &PgPool, network: &str) -> Result<Option<u32>, sqlx::Error> {
+    let seq: Option<i32> = sqlx::query_scalar(
+        "SELECT ledger_sequence FROM indexer_ledger_state WHERE network = $1::network_type",
+    )
+    .bind(network)
+    .fetch_optional(db)
+    .await?;
+    Ok(seq.map(|s| s as u32))
+}
+
+pub async fn save_ledger_state(
+    tx: &mut Transaction<'_, Postgres>,
+    network: &str,
+    state: &LedgerState<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO indexer_ledger_state
+             (network, ledger_sequence, ledger_timestamp, protocol_version, network_passphrase)
+         VALUES ($1::network_type, $2, $3, $4, $5)
+         ON CONFLICT (network) DO UPDATE
+         SET ledger_sequence    = EXCLUDED.ledger_sequence,
+             ledger_timestamp   = EXCLUDED.ledger_timestamp,
+             protocol_version   = EXCLUDED.protocol_version,
+             network_passphrase = EXCLUDED.network_passphrase,
+             updated_at         = NOW()",
+    )
+    .bind(network)
+    .bind(state.sequence as i32)
+    .bind(state.timestamp)
+    .bind(state.protocol_version as i32)
+    .bind(state.network_passphrase)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Record the network's fee settings as of `ledger`, unless they match the
+/// latest recorded ones
+pub async fn save_fee_config(
+    db: &PgPool,
+    network: &str,
+    ledger: u32,
+    settings: &serde_json::Value,
+) -> Result<bool, sqlx::Error> {
+    let inserted = sqlx::query(
+        "INSERT INTO network_fee_configs (network, ledger_sequence, settings)
+         SELECT $1::network_type, $2, $3
+         WHERE NOT EXISTS (
+             SELECT 1 FROM (
+                 SELECT settings FROM network_fee_configs
+                 WHERE network = $1::network_type
+                 ORDER BY ledger_sequence DESC
+                 LIMIT 1
+             ) latest
+             WHERE latest.settings = $3
+         )
+         ON CONFLICT (network, ledger_sequence) DO NOTHING",
+    )
+    .bind(network)
+    .bind(ledger as i64)
+    .bind(settings)
+    .execute(db)
+    .await?
+    .rows_affected();
+    Ok(inserted > 0)
+}
+
+/// Store everything `decoded` changed for registered contracts. Older
+/// changes never overwrite newer ones, so re-applying a page is harmless.
+pub async fn apply_transaction(
+    tx: &mut Transaction<'_, Postgres>,
+    contracts: &HashMap<String, Uuid>,
+    decoded: &DecodedTransaction,
+) -> Result<(), sqlx::Error> {
+    for change in &decoded.entries {
+        match change {
+            EntryChange::Upsert(entry) => {
+                let Some(id) = contracts.get(&entry.contract) else {
+                    continue;
+                };
+                sqlx::query(
+                    "INSERT INTO contract_ledger_entries
+                         (contract_id, durability, key_xdr, entry_xdr, key_display,
+                          last_modified_ledger)
+                     VALUES ($1, $2::ledger_entry_durability, $3, $4, $5, $6)
+                     ON CONFLICT (contract_id, key_xdr) DO UPDATE
+                     SET entry_xdr            = EXCLUDED.entry_xdr,
+                         key_display          = EXCLUDED.key_display,
+                         last_modified_ledger = EXCLUDED.last_modified_ledger,
+                         updated_at           = NOW()
+                     WHERE contract_ledger_entries.last_modified_ledger
+                           <= EXCLUDED.last_modified_ledger",
+                )
+                .bind(id)
+                .bind(entry.durability.as_str())
+                .bind(&entry.key_xdr)
+                .bind(&entry.entry_xdr)
+                .bind(&entry.key_display)
+                .bind(entry.last_modified_ledger as i32)
+                .execute(&mut **tx)
+                .await?;
+            }
+            EntryChange::Remove {
+                contract,
+                key_xdr,
+                ledger,
+            } => {
+                let Some(id) = contracts.get(contract) else {
+                    continue;
+                };
+                sqlx::query(
+                    "DELETE FROM contract_ledger_entries
+                     WHERE contract_id = $1 AND key_xdr = $2 AND last_modified_ledger <= $3",
+                )
+                .bind(id)
+                .bind(key_xdr)
+                .bind(*ledger as i32)
+                .execute(&mut **tx)
+                .await?;
+            }
+            EntryChange::Ttl {
+                contract,
+                key_xdr,
+                live_until_ledger,
+            } => {
+                let Some(id) = contracts.get(contract) else {
+                    continue;
+                };
+                sqlx::query(
+                    "UPDATE contract_ledger_entries
+                     SET live_until_ledger = GREATEST(COALESCE(live_until_ledger, 0), $3),
+                         updated_at = NOW()
+                     WHERE contract_id = $1 AND key_xdr = $2",
+                )
+                .bind(id)
+                .bind(key_xdr)
+                .bind(*live_until_ledger as i32)
+                .execute(&mut **tx)
+                .await?;
+            }
+        }
+    }
+
+    if let Some(invocation) = &decoded.invocation {
+        if let Some(id) = contracts.get(&invocation.contract) {
+            record_invocation(tx, *id, invocation, decoded).await?;
+        }
+    }
+
+    for change in &decoded.balances {
+        if let Some(id) = contracts.get(&change.contract) {
+            record_balance(tx, *id, change, decoded).await?;
+        }
+    }
+    Ok(())
+}
+
+/// One row per holder per ledger; a later transaction in the same ledger
+/// replaces the earlier balance, so the row holds the balance at ledger close.
+async fn record_balance(
+    tx: &mut Transaction<'_, Postgres>,
+    token_contract_id: Uuid,
+    change: &BalanceChange,
+    decoded: &DecodedTransaction,
+) -> Result<(), sqlx::Error> {
+    if change.balance < 0 {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO token_balance_history
+             (token_contract_id, holder, balance, ledger_sequence, ledger_closed_at)
+         VALUES ($1, $2, $3::NUMERIC, $4, to_timestamp($5))
+         ON CONFLICT (token_contract_id, holder, ledger_sequence) DO UPDATE
+         SET balance = EXCLUDED.balance",
+    )
+    .bind(token_contract_id)
+    .bind(&change.holder)
+    .bind(change.balance.to_string())
+    .bind(decoded.ledger as i64)
+    .bind(decoded.closed_at as f64)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// On-chain calls carry no latency; SLA latency comes from probes and
+/// client-reported invocations
+async fn record_invocation(
+    tx: &mut Transaction<'_, Postgres>,
+    contract_id: Uuid,
+    invocation: &Invocation,
+    decoded: &DecodedTransaction,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO contract_invocations
+             (contract_id, function_name, success, error, transaction_hash,
+              ledger_sequence, invoked_at)
+         VALUES ($1, $2, $3, $4, $5, $6, to_timestamp($7))
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(contract_id)
+    .bind(&invocation.function)
+    .bind(invocation.success)
+    .bind(&invocation.error)
+    .bind(&decoded.hash)
+    .bind(decoded.ledger as i64)
+    .bind(decoded.closed_at as f64)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
@@ -25,6 +25,14 @@ pub struct Contract {
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
     pub is_maintenance: bool,
+    /// Organization slug for scoped names (`@namespace/name`)
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+    /// SPDX license identifier
+    #[serde(default)]
+    pub license: Option<String>,
 }
 
 /// Network where the contract is deployed
@@ -92,6 +100,12 @@ pub enum MaturityLevel {
     Production,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMaturityRequest {
+    pub maturity: MaturityLevel,
+    pub reason: Option<String>,
+}
+
 /// Publisher/developer information
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Publisher {
@@ -155,6 +169,12 @@ pub struct PublishRequest {
     // Dependencies (new field)
     #[serde(default)]
     pub dependencies: Vec<DependencyDeclaration>,
+    /// SPDX license identifier, e.g. `Apache-2.0`
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Long-form documentation (README); searchable
+    #[serde(default)]
+    pub documentation: Option<String>,
 }
 
 /// Dependency declaration in publish request
@@ -264,6 +284,7 @@ impl<T> PaginatedResponse<T> {
 #[sqlx(type_name = "migration_status", rename_all = "snake_case")]
 pub enum MigrationStatus {
     Pending,
+    InProgress,
     Success,
     Failed,
     RolledBack,
@@ -295,6 +316,98 @@ pub struct UpdateMigrationStatusRequest {
     pub log_output: Option<String>,
 }
 
+/// What a migration plan step does
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "migration_step_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStepKind {
+    /// Install the migration's WASM and call the contract's upgrade function
+    UpgradeWasm,
+    /// Call a contract function, typically the new version's `migrate`
+    Invoke,
+    /// Call a read-only function and compare the result with `expected`
+    Verify,
+}
+
+/// Outcome of a single migration plan step
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "migration_step_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStepStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+impl MigrationStepStatus {
+    /// Whether the step no longer needs to run when resuming
+    pub fn is_done(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Skipped)
+    }
+}
+
+/// Named argument of a migration step, kept in call order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MigrationStepArg {
+    pub name: String,
+    pub value: serde_json::Value,
+}
+
+/// One step of a migration plan as stored in `migration_steps`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MigrationStep {
+    pub id: Uuid,
+    pub migration_id: Uuid,
+    pub position: i32,
+    pub kind: MigrationStepKind,
+    pub function_name: Option<String>,
+    pub args: serde_json::Value,
+    pub expected: Option<serde_json::Value>,
+    pub status: MigrationStepStatus,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Step definition used when attaching a plan to a migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStepSpec {
+    pub kind: MigrationStepKind,
+    #[serde(default, alias = "function")]
+    pub function_name: Option<String>,
+    #[serde(default)]
+    pub args: Vec<MigrationStepArg>,
+    #[serde(default)]
+    pub expected: Option<serde_json::Value>,
+}
+
+/// Request to attach an ordered plan to a pending migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachMigrationPlanRequest {
+    pub steps: Vec<MigrationStepSpec>,
+}
+
+/// Request to record the outcome of one plan step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordMigrationStepRequest {
+    pub status: MigrationStepStatus,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+/// A migration together with its plan and the position to resume from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationPlanStatus {
+    pub migration: Migration,
+    pub steps: Vec<MigrationStep>,
+    /// First step that has not succeeded or been skipped; `None` once the
+    /// plan has completed
+    pub resume_from: Option<i32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
 #[sqlx(type_name = "deployment_environment", rename_all = "lowercase")]
 pub enum DeploymentEnvironment {
@@ -345,8 +458,9 @@ pub struct DeploymentSwitch {
     pub rollback: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
 #[sqlx(type_name = "canary_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum CanaryStatus {
     Pending,
     Active,
@@ -356,8 +470,9 @@ pub enum CanaryStatus {
     Failed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
 #[sqlx(type_name = "rollout_stage", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum RolloutStage {
     Stage1,
     Stage2,
@@ -366,6 +481,19 @@ pub enum RolloutStage {
     Complete,
 }
 
+impl std::fmt::Display for RolloutStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Stage1 => "stage 1",
+            Self::Stage2 => "stage 2",
+            Self::Stage3 => "stage 3",
+            Self::Stage4 => "stage 4",
+            Self::Complete => "complete",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CanaryRelease {
     pub id: Uuid,
@@ -408,16 +536,24 @@ pub struct CanaryUserAssignment {
     pub notified_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateCanaryRequest {
     pub contract_id: String,
     pub to_deployment_id: String,
     pub error_rate_threshold: Option<f64>,
     pub created_by: Option<String>,
+    pub max_latency_increase_pct: Option<f64>,
+    pub max_event_anomalies: Option<i32>,
+    pub min_stage_minutes: Option<i32>,
+    pub min_stage_requests: Option<i32>,
+    /// Traffic share for each of the four stages
+    pub stage_percentages: Option<Vec<i32>>,
+    pub auto_advance: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdvanceCanaryRequest {
+    #[serde(default)]
     pub canary_id: String,
     pub target_percentage: Option<i32>,
     pub advanced_by: Option<String>,
@@ -425,15 +561,119 @@ pub struct AdvanceCanaryRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordCanaryMetricRequest {
+    #[serde(default)]
     pub canary_id: String,
     pub requests: i32,
     pub errors: i32,
     pub avg_response_time_ms: Option<f64>,
     pub p95_response_time_ms: Option<f64>,
     pub p99_response_time_ms: Option<f64>,
+    /// Which side served the traffic (defaults to the canary)
+    #[serde(default)]
+    pub variant: CanaryVariant,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "canary_variant", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CanaryVariant {
+    #[default]
+    Canary,
+    Stable,
+}
+
+/// Success criteria a canary stage must meet before it advances
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CanaryCriteria {
+    /// Highest tolerated canary error rate, in percent
+    pub error_rate_threshold: f64,
+    /// Highest tolerated p95 latency increase over stable, in percent
+    pub max_latency_increase_pct: f64,
+    pub max_event_anomalies: i32,
+    pub min_stage_minutes: i32,
+    pub min_stage_requests: i32,
+    pub stage_percentages: Vec<i32>,
+    pub auto_advance: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "canary_decision_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CanaryDecisionKind {
+    Hold,
+    Advance,
+    Promote,
+    Rollback,
+    ManualAdvance,
+    ManualRollback,
+    Pause,
+    Resume,
+}
+
+/// Outcome of one success criterion at evaluation time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CanaryCriterionResult {
+    pub name: String,
+    pub observed: Option<f64>,
+    pub threshold: f64,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CanaryDecision {
+    pub id: Uuid,
+    pub canary_id: Uuid,
+    pub stage: RolloutStage,
+    pub percentage: i32,
+    pub kind: CanaryDecisionKind,
+    pub reason: String,
+    /// `Vec<CanaryCriterionResult>`
+    pub criteria: serde_json::Value,
+    pub actor: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CanaryStageTransition {
+    pub id: Uuid,
+    pub canary_id: Uuid,
+    pub from_stage: RolloutStage,
+    pub to_stage: RolloutStage,
+    pub from_percentage: i32,
+    pub to_percentage: i32,
+    pub transitioned_at: DateTime<Utc>,
+    pub transitioned_by: Option<String>,
+    pub metrics_at_transition: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryDetail {
+    #[serde(flatten)]
+    pub canary: CanaryRelease,
+    pub criteria: CanaryCriteria,
+    pub stage_started_at: DateTime<Utc>,
+    pub transitions: Vec<CanaryStageTransition>,
+    pub decisions: Vec<CanaryDecision>,
+}
+
+/// Result of evaluating a canary stage on demand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryEvaluation {
+    /// `advance`, `hold` or `rollback`
+    pub verdict: String,
+    pub reason: Option<String>,
+    pub criteria: Vec<CanaryCriterionResult>,
+    pub canary: CanaryRelease,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanaryActionRequest {
+    pub actor: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "ab_test_status", rename_all = "snake_case")]
 pub enum AbTestStatus {
     Draft,
@@ -443,7 +683,8 @@ pub enum AbTestStatus {
     Cancelled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "variant_type", rename_all = "snake_case")]
 pub enum VariantType {
     Control,
@@ -468,6 +709,20 @@ pub struct AbTest {
     pub ended_at: Option<DateTime<Utc>>,
     pub created_by: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub primary_metric_kind: AbMetricKind,
+    pub higher_is_better: bool,
+}
+
+/// How the primary metric is aggregated per variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "ab_metric_kind", rename_all = "snake_case")]
+pub enum AbMetricKind {
+    /// Share of users with a positive value
+    Conversion,
+    /// Mean of the recorded values
+    #[default]
+    Mean,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -529,19 +784,110 @@ pub struct CreateAbTestRequest {
     pub significance_threshold: Option<f64>,
     pub min_sample_size: Option<i32>,
     pub created_by: Option<String>,
+    #[serde(default)]
+    pub primary_metric_kind: AbMetricKind,
+    pub higher_is_better: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordAbTestMetricRequest {
+    #[serde(default)]
     pub test_id: String,
     pub user_address: Option<String>,
     pub metric_name: String,
     pub metric_value: f64,
     pub metadata: Option<serde_json::Value>,
+    /// Required when `user_address` has no assignment
+    pub variant_type: Option<VariantType>,
+}
+
+/// One interim analysis of a test's primary metric. Looks are recorded so
+/// the alpha already spent is known at the next analysis.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AbTestLook {
+    pub id: Uuid,
+    pub test_id: Uuid,
+    pub look_number: i32,
+    pub information_fraction: f64,
+    pub control_n: i64,
+    pub treatment_n: i64,
+    /// Cumulative alpha spent up to and including this look
+    pub alpha_spent: f64,
+    /// Alpha available to this look alone
+    pub nominal_alpha: f64,
+    pub boundary_z: f64,
+    pub z_statistic: Option<f64>,
+    pub crossed: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AbVariantStats {
+    pub variant_type: VariantType,
+    pub sample_size: i64,
+    /// Conversion rate (0-1) or mean value
+    pub mean: f64,
+    pub std_dev: f64,
+    pub ci_lower: f64,
+    pub ci_upper: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AbMetricComparison {
+    pub metric_name: String,
+    pub kind: AbMetricKind,
+    pub control: AbVariantStats,
+    pub treatment: AbVariantStats,
+    /// Treatment minus control
+    pub absolute_lift: f64,
+    pub relative_lift_pct: Option<f64>,
+    pub lift_ci_lower: f64,
+    pub lift_ci_upper: f64,
+    /// `two_proportion_z` or `welch_t`
+    pub test: String,
+    pub statistic: f64,
+    pub degrees_of_freedom: Option<f64>,
+    pub p_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SampleRatioCheck {
+    pub expected_control_share: f64,
+    pub control_n: i64,
+    pub treatment_n: i64,
+    pub chi_square: f64,
+    pub p_value: f64,
+    pub mismatch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbTestRecommendation {
+    ContinueTest,
+    ShipTreatment,
+    KeepControl,
+    NoSignificantDifference,
+    InvestigateSampleRatio,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbTestAnalysis {
+    pub test_id: Uuid,
+    pub status: AbTestStatus,
+    pub alpha: f64,
+    pub primary: Option<AbMetricComparison>,
+    /// Fixed-horizon comparisons of every other recorded metric
+    pub secondary: Vec<AbMetricComparison>,
+    pub sample_ratio: Option<SampleRatioCheck>,
+    pub sequential: Option<AbTestLook>,
+    pub recommendation: AbTestRecommendation,
+    pub rationale: String,
+    pub analyzed_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetVariantRequest {
+    #[serde(default)]
     pub test_id: String,
     pub user_address: String,
 }
@@ -831,6 +1177,8 @@ pub enum AuditActionType {
     PublisherChanged,
     VersionCreated,
     Rollback,
+    ProposalExecuted,
+    ProposalExecutionFailed,
 }
 
 impl std::fmt::Display for AuditActionType {
@@ -842,6 +1190,8 @@ impl std::fmt::Display for AuditActionType {
             Self::PublisherChanged => "publisher_changed",
             Self::VersionCreated => "version_created",
             Self::Rollback => "rollback",
+            Self::ProposalExecuted => "proposal_executed",
+            Self::ProposalExecutionFailed => "proposal_execution_failed",
         };
         write!(f, "{}", s)
     }
@@ -857,10 +1207,6 @@ pub struct ContractAuditLog {
     pub new_value: Option<serde_json::Value>,
     pub changed_by: String,
     pub timestamp: DateTime<Utc>,
-    pub old_value:   Option<serde_json::Value>,
-    pub new_value:   Option<serde_json::Value>,
-    pub changed_by:  String,
-    pub timestamp:   DateTime<Utc>,
     pub previous_hash: Option<String>,
     pub hash: Option<String>,
     pub signature: Option<String>,
@@ -907,29 +1253,86 @@ pub struct RollbackRequest {
 }
 
 // Multisig deployment types
+
+/// Lifecycle of a deployment proposal: pending → approved → executed, or
+/// expired / rejected
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "proposal_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    Pending,
+    Approved,
+    Executed,
+    Expired,
+    Rejected,
+}
+
+impl std::fmt::Display for ProposalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProposalStatus::Pending => write!(f, "pending"),
+            ProposalStatus::Approved => write!(f, "approved"),
+            ProposalStatus::Executed => write!(f, "executed"),
+            ProposalStatus::Expired => write!(f, "expired"),
+            ProposalStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct MultisigPolicy {
     pub id: Uuid,
     pub name: String,
+    /// Signatures required to approve a proposal (M-of-N)
     pub threshold: i32,
-    pub required_signatures: i32,
     pub signer_addresses: Vec<String>,
     pub expiry_seconds: i32,
     pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub threshold: i32,
+    pub signer_addresses: Vec<String>,
+    /// How long proposals under this policy collect signatures; defaults to a day
+    pub expiry_seconds: Option<i32>,
+    pub created_by: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct DeployProposal {
     pub id: Uuid,
     pub contract_name: String,
-    pub contract_id: Uuid,
+    /// On-chain contract address
+    pub contract_id: String,
     pub wasm_hash: String,
-    pub network: String,
+    pub network: Network,
     pub description: Option<String>,
     pub policy_id: Uuid,
-    pub status: String,
+    pub status: ProposalStatus,
     pub expires_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub proposer: String,
+    /// `ProposalAction` to apply once approved; defaults to upgrading the
+    /// registered contract to `wasm_hash`
+    pub action: Option<serde_json::Value>,
+    pub execution_delay_hours: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateProposalRequest {
+    pub contract_name: String,
+    pub contract_id: String,
+    pub wasm_hash: String,
+    pub network: Network,
+    pub description: Option<String>,
+    pub policy_id: Uuid,
     pub proposer: String,
+    pub action: Option<ProposalAction>,
+    /// Hours between approval and earliest execution
+    pub execution_delay_hours: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -937,27 +1340,27 @@ pub struct ProposalSignature {
     pub id: Uuid,
     pub proposal_id: Uuid,
     pub signer_address: String,
+    pub signature_data: Option<String>,
+    pub signed_at: DateTime<Utc>,
 }
 
-/// Paginated response for audit log
-// TODO: Implement missing types: DeployProposal, MultisigPolicy, ProposalSignature
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ProposalWithSignatures {
-//     pub proposal: DeployProposal,
-//     pub policy: MultisigPolicy,
-//     pub signatures: Vec<ProposalSignature>,
-//     /// How many more signatures are needed to reach the threshold
-//     pub signatures_needed: i32,
-// }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignProposalRequest {
+    pub signer_address: String,
+    /// Optional signature payload for off-chain verification
+    pub signature_data: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposalWithSignatures {
     pub proposal: DeployProposal,
     pub policy: MultisigPolicy,
     pub signatures: Vec<ProposalSignature>,
+    /// How many more signatures are needed to reach the threshold
     pub signatures_needed: i32,
 }
 
+/// Paginated response for audit log
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLogPage {
     pub items: Vec<ContractAuditLog>,
@@ -1057,6 +1460,12 @@ pub struct ResidencyPolicy {
     pub created_by: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Data classifications the policy covers (`public`, `financial`, `pii`); empty covers all
+    pub classifications: Vec<String>,
+    /// Regions, jurisdiction groups or `prefix*` patterns that are always denied
+    pub denied_regions: Vec<String>,
+    /// Rule expressions, checked in order, e.g. `deny US when classification = pii`
+    pub rules: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -1070,6 +1479,9 @@ pub struct ResidencyAuditLog {
     pub requested_by: Option<String>,
     pub reason: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub classification: Option<String>,
+    /// The check that decided: `exception`, `deny_list`, `rule`, `allowed_regions` or `default`
+    pub decided_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -1086,9 +1498,16 @@ pub struct ResidencyViolation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateResidencyPolicyRequest {
     pub contract_id: String,
+    #[serde(default)]
     pub allowed_regions: Vec<String>,
     pub description: Option<String>,
     pub created_by: String,
+    #[serde(default)]
+    pub classifications: Vec<String>,
+    #[serde(default)]
+    pub denied_regions: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1096,6 +1515,9 @@ pub struct UpdateResidencyPolicyRequest {
     pub allowed_regions: Option<Vec<String>>,
     pub description: Option<String>,
     pub is_active: Option<bool>,
+    pub classifications: Option<Vec<String>>,
+    pub denied_regions: Option<Vec<String>>,
+    pub rules: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1105,6 +1527,95 @@ pub struct CheckResidencyRequest {
     pub requested_region: String,
     pub action: String,
     pub requested_by: Option<String>,
+    /// `public`, `financial` or `pii`; unclassified data is held to every rule
+    pub classification: Option<String>,
+}
+
+/// A time-boxed allowance for a region a policy would otherwise deny
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResidencyException {
+    pub id: Uuid,
+    pub policy_id: Uuid,
+    /// Region, jurisdiction group or `prefix*` pattern
+    pub region: String,
+    /// Limits the exception to one classification
+    pub classification: Option<String>,
+    /// Limits the exception to one action
+    pub action: Option<String>,
+    pub reason: String,
+    pub approved_by: String,
+    pub starts_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateResidencyExceptionRequest {
+    pub region: String,
+    pub classification: Option<String>,
+    pub action: Option<String>,
+    pub reason: String,
+    pub approved_by: String,
+    /// Defaults to now
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ResidencyJurisdiction {
+    pub name: String,
+    pub regions: Vec<String>,
+    pub description: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertJurisdictionRequest {
+    pub regions: Vec<String>,
+    pub description: Option<String>,
+}
+
+/// A hypothetical action, evaluated against every active policy without
+/// being logged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulateResidencyRequest {
+    pub contract_id: String,
+    pub region: String,
+    pub action: String,
+    pub classification: Option<String>,
+    /// Evaluate as of this time, e.g. to see whether an exception will have expired; defaults to now
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// How one policy judged a simulated action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResidencyPolicyEvaluation {
+    pub policy_id: Uuid,
+    pub description: Option<String>,
+    /// Whether the policy covers the data's classification
+    pub applies: bool,
+    pub decision: ResidencyDecision,
+    /// `not_applicable`, `exception`, `deny_list`, `rule`, `allowed_regions` or `default`
+    pub decided_by: String,
+    /// The deciding rule expression or list entry
+    pub matched: Option<String>,
+    pub exception_id: Option<Uuid>,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResidencySimulation {
+    pub contract_id: String,
+    pub region: String,
+    pub action: String,
+    pub classification: Option<String>,
+    pub at: DateTime<Utc>,
+    pub decision: ResidencyDecision,
+    /// The policy whose verdict settled the decision; `None` when no policy applies
+    pub deciding_policy_id: Option<Uuid>,
+    pub explanation: String,
+    pub evaluations: Vec<ResidencyPolicyEvaluation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1343,3 +1854,2158 @@ pub struct TransparencyLogQueryParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// GOVERNANCE TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// How votes on a proposal are weighted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "governance_model", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceModel {
+    /// One unit of voting power per token held at `voting_starts_at`
+    TokenWeighted,
+    /// Square root of the token balance held at `voting_starts_at`
+    Quadratic,
+    /// One vote per signer
+    Multisig,
+    /// One vote per voter, executed after a delay
+    Timelock,
+}
+
+impl GovernanceModel {
+    /// Whether voting power comes from token balances
+    pub fn is_token_based(self) -> bool {
+        matches!(self, Self::TokenWeighted | Self::Quadratic)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "proposal_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum GovernanceProposalStatus {
+    Pending,
+    Active,
+    Passed,
+    Rejected,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
+#[sqlx(type_name = "vote_choice", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum VoteChoice {
+    For,
+    Against,
+    Abstain,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GovernanceProposal {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub governance_model: GovernanceModel,
+    pub proposer: Uuid,
+    pub status: GovernanceProposalStatus,
+    pub voting_starts_at: DateTime<Utc>,
+    pub voting_ends_at: DateTime<Utc>,
+    pub execution_delay_hours: Option<i32>,
+    /// Token models: percent of eligible voting power that must vote.
+    /// Other models: minimum number of votes.
+    pub quorum_required: i32,
+    /// Percent of decisive (for + against) power that must vote for
+    pub approval_threshold: i32,
+    pub created_at: DateTime<Utc>,
+    pub executed_at: Option<DateTime<Utc>>,
+    /// Token whose balances determine voting power (token models only)
+    pub token_contract_id: Option<Uuid>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Tally frozen when the proposal was closed
+    pub final_tally: Option<serde_json::Value>,
+    /// `ProposalAction` applied once the proposal passes and its timelock
+    /// expires; `None` for proposals that only record a decision
+    pub action: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GovernanceVote {
+    pub id: Uuid,
+    pub proposal_id: Uuid,
+    pub voter: Uuid,
+    pub vote_choice: VoteChoice,
+    /// The voter's own weighted power, excluding delegated power
+    pub voting_power: i64,
+    pub delegated_from: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VoteDelegation {
+    pub id: Uuid,
+    pub delegator: Uuid,
+    pub delegate: Uuid,
+    pub contract_id: Option<Uuid>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateGovernanceProposalRequest {
+    pub title: String,
+    pub description: String,
+    pub governance_model: GovernanceModel,
+    pub voting_duration_hours: i32,
+    pub execution_delay_hours: Option<i32>,
+    /// Required for token-weighted and quadratic proposals
+    pub token_contract_id: Option<Uuid>,
+    pub quorum_required: Option<i32>,
+    pub approval_threshold: Option<i32>,
+    pub action: Option<ProposalAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastVoteRequest {
+    /// Publisher casting the vote
+    pub voter: Uuid,
+    pub vote_choice: VoteChoice,
+    /// Base64 ed25519 signature of the vote statement by the voter's
+    /// Stellar account
+    pub signature: String,
+}
+
+/// Outcome of counting the votes on a proposal
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProposalTally {
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub votes_abstain: i128,
+    pub total_votes: i128,
+    /// Total weighted power that could have voted (token models only)
+    pub eligible_power: Option<i128>,
+    pub participation_pct: Option<f64>,
+    pub approval_pct: f64,
+    pub quorum_met: bool,
+    pub approved: bool,
+    /// Non-voting delegators whose power reached a voter through delegation
+    pub delegated_voters: usize,
+    /// Delegation cycles whose power was not counted
+    pub delegation_cycles: Vec<Vec<Uuid>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalResults {
+    pub proposal: GovernanceProposal,
+    #[serde(flatten)]
+    pub tally: ProposalTally,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PROPOSAL EXECUTION TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Change applied to a contract when an approved proposal is executed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProposalAction {
+    UpgradeWasm {
+        wasm_hash: String,
+        /// Registers a new contract version alongside the upgrade
+        version: Option<String>,
+    },
+    /// Re-publish an earlier configuration version as the current one
+    SetConfigVersion { environment: String, version: i32 },
+    ChangeMaturity {
+        maturity: MaturityLevel,
+        reason: Option<String>,
+    },
+    TransferOwnership { new_publisher_id: Uuid },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "proposal_source", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalSource {
+    Governance,
+    Multisig,
+}
+
+impl std::fmt::Display for ProposalSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Governance => "governance",
+            Self::Multisig => "multisig",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "proposal_execution_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalExecutionStatus {
+    Queued,
+    Executing,
+    Executed,
+    Failed,
+    Cancelled,
+}
+
+/// A passed proposal's action waiting in, or released from, the timelock queue
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProposalExecution {
+    pub id: Uuid,
+    pub source: ProposalSource,
+    pub proposal_id: Uuid,
+    pub contract_id: Option<Uuid>,
+    pub action: Option<serde_json::Value>,
+    pub status: ProposalExecutionStatus,
+    pub queued_at: DateTime<Utc>,
+    /// Earliest execution time; the proposal can be cancelled until then
+    pub eta: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub cancelled_by: Option<String>,
+    pub cancel_reason: Option<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub executed_by: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub audit_log_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelExecutionRequest {
+    pub cancelled_by: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecuteProposalRequest {
+    pub executed_by: String,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// INCIDENT TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[sqlx(type_name = "incident_severity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl std::str::FromStr for IncidentSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "critical" => Ok(Self::Critical),
+            "high" => Ok(Self::High),
+            "medium" => Ok(Self::Medium),
+            "low" => Ok(Self::Low),
+            _ => Err(format!(
+                "invalid severity: {} (expected critical|high|medium|low)",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for IncidentSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Critical => "CRITICAL",
+            Self::High => "HIGH",
+            Self::Medium => "MEDIUM",
+            Self::Low => "LOW",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "incident_state", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentState {
+    Detected,
+    Responding,
+    Contained,
+    Recovered,
+    PostReview,
+}
+
+impl IncidentState {
+    /// The incident is still being worked on
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Detected | Self::Responding | Self::Contained)
+    }
+}
+
+impl std::str::FromStr for IncidentState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "detected" => Ok(Self::Detected),
+            "responding" => Ok(Self::Responding),
+            "contained" => Ok(Self::Contained),
+            "recovered" => Ok(Self::Recovered),
+            "post_review" | "postreview" => Ok(Self::PostReview),
+            _ => Err(format!(
+                "invalid state: {} (expected detected|responding|contained|recovered|post_review)",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for IncidentState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Detected => "Detected",
+            Self::Responding => "Responding",
+            Self::Contained => "Contained",
+            Self::Recovered => "Recovered",
+            Self::PostReview => "PostReview",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "incident_impact", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentImpact {
+    /// The contract the incident was raised against
+    Origin,
+    /// A contract that depends on the origin, directly or transitively
+    Dependent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Incident {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub severity: IncidentSeverity,
+    pub state: IncidentState,
+    /// Writes to the contract are rejected while set
+    pub breaker_engaged: bool,
+    pub triggered_by: String,
+    pub triggered_at: DateTime<Utc>,
+    pub responding_at: Option<DateTime<Utc>>,
+    pub contained_at: Option<DateTime<Utc>>,
+    pub recovered_at: Option<DateTime<Utc>>,
+    pub post_review_at: Option<DateTime<Utc>>,
+    pub root_cause: Option<String>,
+    pub resolution: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One timeline entry: a state change, a note, or a breaker change
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IncidentUpdate {
+    pub id: Uuid,
+    pub incident_id: Uuid,
+    pub from_state: Option<IncidentState>,
+    pub to_state: Option<IncidentState>,
+    pub message: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IncidentAffectedContract {
+    pub contract_id: Uuid,
+    pub contract_name: String,
+    pub impact: IncidentImpact,
+    /// Dependency hops from the origin contract
+    pub depth: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentDetail {
+    #[serde(flatten)]
+    pub incident: Incident,
+    pub timeline: Vec<IncidentUpdate>,
+    pub affected_contracts: Vec<IncidentAffectedContract>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerIncidentRequest {
+    /// Registry contract UUID or on-chain contract address
+    pub contract_id: String,
+    /// Disambiguates an on-chain address registered on several networks
+    pub network: Option<String>,
+    pub severity: IncidentSeverity,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub triggered_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordIncidentUpdateRequest {
+    /// New state; omit to add a note without changing state
+    pub state: Option<IncidentState>,
+    pub message: String,
+    pub author: String,
+    pub root_cause: Option<String>,
+    pub resolution: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCircuitBreakerRequest {
+    pub engaged: bool,
+    pub author: String,
+    pub reason: Option<String>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// SLA MONITORING TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Per-contract SLA targets and probe configuration
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SlaTargets {
+    pub contract_id: Uuid,
+    /// Minimum probe success percentage per window
+    pub min_uptime: f64,
+    /// Maximum p95 latency per window
+    pub max_latency_ms: f64,
+    /// Maximum failed-invocation percentage per window
+    pub max_error_rate: f64,
+    pub window_minutes: i32,
+    pub penalty_per_violation: f64,
+    pub credit_rate: f64,
+    pub probe_function: Option<String>,
+    /// Base64 TransactionEnvelope simulated by each probe
+    pub probe_transaction: Option<String>,
+    pub probe_interval_seconds: i32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create or update SLA targets; omitted fields keep their current value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetSlaTargetsRequest {
+    pub min_uptime: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    pub max_error_rate: Option<f64>,
+    pub window_minutes: Option<i32>,
+    pub penalty_per_violation: Option<f64>,
+    pub credit_rate: Option<f64>,
+    pub probe_function: Option<String>,
+    pub probe_transaction: Option<String>,
+    pub probe_interval_seconds: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+/// One evaluated SLA window (a row of `sla_metrics`)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SlaWindowMetrics {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    /// `None` when the window had no samples for the metric
+    pub uptime_percentage: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+    pub error_rate: Option<f64>,
+    pub window_start: Option<DateTime<Utc>>,
+    pub window_end: Option<DateTime<Utc>>,
+    pub probe_count: i32,
+    pub invocation_count: i32,
+    pub compliant: bool,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SlaViolation {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub billing_period_id: Uuid,
+    /// `uptime`, `latency` or `error_rate`
+    pub metric: String,
+    pub actual: f64,
+    pub target: f64,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub penalty: f64,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SlaBillingPeriod {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub windows_evaluated: i32,
+    pub windows_compliant: i32,
+    pub violation_count: i32,
+    pub probe_count: i32,
+    pub probe_successes: i32,
+    pub penalty_accrued: f64,
+    pub credits_issued: f64,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl SlaBillingPeriod {
+    /// Probe-weighted uptime across the period, if any probes ran
+    pub fn uptime_percentage(&self) -> Option<f64> {
+        (self.probe_count > 0)
+            .then(|| self.probe_successes as f64 * 100.0 / self.probe_count as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SlaProbeResult {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub function_name: Option<String>,
+    pub success: bool,
+    pub latency_ms: f64,
+    pub error: Option<String>,
+    pub probed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaStatus {
+    pub contract_id: Uuid,
+    pub targets: SlaTargets,
+    /// Whether the most recent evaluated window met every target
+    pub compliant: bool,
+    pub latest_window: Option<SlaWindowMetrics>,
+    pub current_period: Option<SlaBillingPeriod>,
+    pub recent_violations: Vec<SlaViolation>,
+    /// Lifetime totals across all billing periods
+    pub penalty_accrued: f64,
+    pub credits_issued: f64,
+    pub last_window_end: Option<DateTime<Utc>>,
+}
+
+/// One indexed invocation result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvocationResult {
+    pub function_name: String,
+    pub success: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub ledger_sequence: Option<i64>,
+    pub invoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordInvocationsRequest {
+    pub invocations: Vec<InvocationResult>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// SECURITY PATCH CAMPAIGN TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "patch_severity", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PatchSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl std::fmt::Display for PatchSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Critical => "CRITICAL",
+            Self::High => "HIGH",
+            Self::Medium => "MEDIUM",
+            Self::Low => "LOW",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SecurityPatch {
+    pub id: Uuid,
+    pub target_version: String,
+    pub severity: PatchSeverity,
+    pub new_wasm_hash: String,
+    pub rollout_percentage: i32,
+    pub description: Option<String>,
+    /// WASM hash of the vulnerable build, when known
+    pub vulnerable_wasm_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSecurityPatchRequest {
+    pub target_version: String,
+    pub severity: PatchSeverity,
+    pub new_wasm_hash: String,
+    pub rollout_percentage: Option<i32>,
+    pub description: Option<String>,
+    pub vulnerable_wasm_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PatchAudit {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub patch_id: Uuid,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPatchRequest {
+    pub contract_id: Uuid,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "patch_campaign_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PatchCampaignStatus {
+    Pending,
+    RollingOut,
+    Paused,
+    Completed,
+    Aborted,
+}
+
+impl std::fmt::Display for PatchCampaignStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Pending => "pending",
+            Self::RollingOut => "rolling_out",
+            Self::Paused => "paused",
+            Self::Completed => "completed",
+            Self::Aborted => "aborted",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "patch_target_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PatchTargetStatus {
+    Pending,
+    Scheduled,
+    Applied,
+    Failed,
+    Skipped,
+    /// Dependent whose vulnerable upstream contracts have all been patched
+    Resolved,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "patch_target_impact", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PatchTargetImpact {
+    Direct,
+    Dependent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PatchCampaign {
+    pub id: Uuid,
+    pub patch_id: Uuid,
+    pub status: PatchCampaignStatus,
+    pub wave_percentages: Vec<i32>,
+    pub current_wave: i32,
+    pub wave_started_at: Option<DateTime<Utc>>,
+    pub max_error_rate_increase: f64,
+    pub max_latency_increase_pct: f64,
+    pub min_soak_minutes: i32,
+    pub min_applied_fraction: f64,
+    pub auto_advance: bool,
+    pub paused_reason: Option<String>,
+    pub gate_reset_at: Option<DateTime<Utc>>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PatchCampaignTarget {
+    pub campaign_id: Uuid,
+    pub contract_id: Uuid,
+    pub contract_name: String,
+    pub publisher_id: Uuid,
+    pub impact: PatchTargetImpact,
+    pub depth: i32,
+    pub via_contract_id: Option<Uuid>,
+    pub wave: Option<i32>,
+    pub status: PatchTargetStatus,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub applied_at: Option<DateTime<Utc>>,
+    pub baseline_error_rate: Option<f64>,
+    pub baseline_latency_ms: Option<f64>,
+    pub failure_reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PatchCampaignEvent {
+    pub id: Uuid,
+    pub campaign_id: Uuid,
+    pub wave: Option<i32>,
+    pub kind: String,
+    pub message: String,
+    pub actor: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Target counts for one wave (or the whole campaign)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PatchRolloutProgress {
+    pub total: i64,
+    pub pending: i64,
+    pub scheduled: i64,
+    pub applied: i64,
+    pub failed: i64,
+    pub skipped: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchWaveSummary {
+    pub wave: i32,
+    pub cumulative_percentage: i32,
+    pub progress: PatchRolloutProgress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchCampaignDetail {
+    #[serde(flatten)]
+    pub campaign: PatchCampaign,
+    pub patch: SecurityPatch,
+    /// Direct targets across all waves
+    pub progress: PatchRolloutProgress,
+    pub waves: Vec<PatchWaveSummary>,
+    pub dependents_total: i64,
+    pub dependents_resolved: i64,
+    pub targets: Vec<PatchCampaignTarget>,
+    pub events: Vec<PatchCampaignEvent>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreatePatchCampaignRequest {
+    pub wave_percentages: Option<Vec<i32>>,
+    pub max_error_rate_increase: Option<f64>,
+    pub max_latency_increase_pct: Option<f64>,
+    pub min_soak_minutes: Option<i32>,
+    pub min_applied_fraction: Option<f64>,
+    pub auto_advance: Option<bool>,
+    /// Track contracts that depend on vulnerable ones (default true)
+    pub include_dependents: Option<bool>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchCampaignActionRequest {
+    pub actor: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePatchTargetRequest {
+    /// `failed` or `skipped`
+    pub status: PatchTargetStatus,
+    pub reason: Option<String>,
+    pub actor: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PublisherNotification {
+    pub id: Uuid,
+    pub publisher_id: Uuid,
+    pub contract_id: Option<Uuid>,
+    pub kind: String,
+    pub subject: String,
+    pub body: String,
+    pub payload: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// FEATURE FLAG TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeatureFlag {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// `active`, `inactive` or `sunset`
+    pub state: String,
+    /// `full` or `gradual`
+    pub rollout_strategy: String,
+    pub rollout_percentage: i32,
+    pub sunset_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub ab_enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFeatureFlagRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub rollout_percentage: Option<u8>,
+    pub sunset_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRolloutRequest {
+    pub percentage: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagListResponse {
+    pub contract_id: Uuid,
+    pub flags: Vec<FeatureFlag>,
+    pub active_count: usize,
+    pub inactive_count: usize,
+    pub sunset_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeatureFlagAnalytics {
+    pub id: Uuid,
+    pub flag_id: Uuid,
+    pub total_checks: i64,
+    pub enabled_hits: i64,
+    pub disabled_hits: i64,
+    pub first_check_at: DateTime<Utc>,
+    pub last_check_at: DateTime<Utc>,
+    pub unique_users_approx: i64,
+    pub hit_rate_bps: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigureAbTestRequest {
+    pub variant_a_pct: u32,
+    pub variant_b_pct: u32,
+    pub variant_a_label: String,
+    pub variant_b_label: String,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AbTestConfig {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub flag_name: String,
+    pub variant_a_pct: i32,
+    pub variant_b_pct: i32,
+    pub variant_a_label: String,
+    pub variant_b_label: String,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Who is asking. Attributes that are left out default to the contract's
+/// own network, latest version and publisher.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlagEvaluationContext {
+    /// Caller address, used for allow/deny lists and percentage bucketing
+    pub user: Option<String>,
+    pub network: Option<String>,
+    pub contract_version: Option<String>,
+    /// Publisher stellar address
+    pub publisher: Option<String>,
+}
+
+pub type CheckEnabledParams = FlagEvaluationContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagAttribute {
+    Network,
+    ContractVersion,
+    Publisher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagOperator {
+    In,
+    NotIn,
+    /// Any of the values as a constraint: `1.2.3`, `^1.2.0`, `~1.4.0`
+    SemverMatches,
+    SemverGte,
+    SemverLt,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagCondition {
+    pub attribute: FlagAttribute,
+    pub operator: FlagOperator,
+    pub values: Vec<String>,
+}
+
+/// Targeting rule. Rules are tried in priority order and the first whose
+/// conditions all hold decides the flag.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeatureFlagRule {
+    pub id: Uuid,
+    pub flag_id: Uuid,
+    pub priority: i32,
+    pub description: Option<String>,
+    /// `Vec<FlagCondition>`
+    pub conditions: serde_json::Value,
+    /// Share of matching callers who get `serve_enabled`
+    pub rollout_percentage: i32,
+    pub serve_enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFlagRuleRequest {
+    pub priority: Option<i32>,
+    pub description: Option<String>,
+    pub conditions: Vec<FlagCondition>,
+    pub rollout_percentage: Option<u8>,
+    pub serve_enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "flag_list_type", rename_all = "lowercase")]
+pub enum FlagListType {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateFlagTargetsRequest {
+    pub list_type: FlagListType,
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeatureFlagPrerequisite {
+    pub flag_id: Uuid,
+    pub prerequisite_flag_id: Uuid,
+    pub prerequisite_name: String,
+    /// Whether the prerequisite must be on (or off) for this flag to apply
+    pub required_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetFlagPrerequisiteRequest {
+    /// Name of another flag on the same contract
+    pub prerequisite: String,
+    pub required_enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlagTargeting {
+    pub flag: FeatureFlag,
+    pub rules: Vec<FeatureFlagRule>,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub prerequisites: Vec<FeatureFlagPrerequisite>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagEvaluation {
+    pub flag: String,
+    pub enabled: bool,
+    /// What decided the result, e.g. `allow_list`, `rule`, `rollout`, `prerequisite_failed`
+    pub reason: String,
+    pub rule_id: Option<Uuid>,
+    /// The caller's bucket (0-9999) when a percentage was applied
+    pub bucket: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFlagEvaluationResponse {
+    pub contract_id: Uuid,
+    pub evaluations: Vec<FlagEvaluation>,
+    pub evaluated_at: DateTime<Utc>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// CAPACITY PLANNING TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "resource_kind", rename_all = "snake_case")]
+pub enum ResourceKind {
+    StorageEntries,
+    CpuInstructions,
+    UniqueUsers,
+    TransactionVolume,
+    WasmSizeBytes,
+    FeePerOperation,
+}
+
+impl ResourceKind {
+    pub const ALL: [ResourceKind; 6] = [
+        ResourceKind::StorageEntries,
+        ResourceKind::CpuInstructions,
+        ResourceKind::UniqueUsers,
+        ResourceKind::TransactionVolume,
+        ResourceKind::WasmSizeBytes,
+        ResourceKind::FeePerOperation,
+    ];
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ResourceKind::StorageEntries => "storage_entries",
+            ResourceKind::CpuInstructions => "cpu_instructions",
+            ResourceKind::UniqueUsers => "unique_users",
+            ResourceKind::TransactionVolume => "transaction_volume",
+            ResourceKind::WasmSizeBytes => "wasm_size_bytes",
+            ResourceKind::FeePerOperation => "fee_per_operation",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Hard Soroban limits used as capacity ceilings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    pub max_storage_entries: u64,
+    pub max_cpu_instructions: u64,
+    pub max_wasm_bytes: u64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_storage_entries: 100_000,
+            max_cpu_instructions: 100_000_000,
+            max_wasm_bytes: 65_536,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ResourceSnapshot {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub resource: ResourceKind,
+    pub value: f64,
+    pub tag: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordSnapshotRequest {
+    pub resource: ResourceKind,
+    pub value: f64,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "scenario")]
+pub enum GrowthScenario {
+    Conservative,
+    Base,
+    Aggressive,
+    Custom { monthly_rate: f64 },
+}
+
+impl GrowthScenario {
+    /// Monthly fractional growth rate
+    pub fn monthly_rate(&self) -> f64 {
+        match self {
+            GrowthScenario::Conservative => 0.10,
+            GrowthScenario::Base => 0.25,
+            GrowthScenario::Aggressive => 0.50,
+            GrowthScenario::Custom { monthly_rate } => *monthly_rate,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GrowthScenario::Conservative => "conservative",
+            GrowthScenario::Base => "base",
+            GrowthScenario::Aggressive => "aggressive",
+            GrowthScenario::Custom { .. } => "custom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastPoint {
+    pub month: u32,
+    pub at: DateTime<Utc>,
+    pub projected_value: f64,
+    pub pct_of_limit: f64,
+    pub exceeds_limit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceForecast {
+    pub contract_id: Uuid,
+    pub resource: ResourceKind,
+    pub scenario: String,
+    pub monthly_growth_rate: f64,
+    pub current_value: f64,
+    pub limit: f64,
+    pub horizon_months: u32,
+    pub points: Vec<ForecastPoint>,
+    pub breach_at_month: Option<u32>,
+    pub breach_at: Option<DateTime<Utc>>,
+    pub days_until_breach: Option<i64>,
+}
+
+/// Forecasting model applied to a resource's snapshot history.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "forecast_model", rename_all = "snake_case")]
+pub enum ForecastModelKind {
+    /// Pick whichever model backtests best
+    #[default]
+    Auto,
+    LinearTrend,
+    /// Additive Holt-Winters with weekly seasonality
+    HoltWinters,
+    /// Piecewise-linear trend continuing from the last detected changepoint
+    Changepoint,
+}
+
+impl std::fmt::Display for ForecastModelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForecastModelKind::Auto => write!(f, "auto"),
+            ForecastModelKind::LinearTrend => write!(f, "linear_trend"),
+            ForecastModelKind::HoltWinters => write!(f, "holt_winters"),
+            ForecastModelKind::Changepoint => write!(f, "changepoint"),
+        }
+    }
+}
+
+/// Rolling-origin backtest score for one model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestScore {
+    pub model: ForecastModelKind,
+    pub folds: u32,
+    pub mae: f64,
+    /// Mean absolute percentage error over non-zero actuals
+    pub mape: Option<f64>,
+    /// Share of held-out values inside the prediction interval
+    pub interval_coverage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelForecastPoint {
+    pub day: u32,
+    pub at: DateTime<Utc>,
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// History-driven forecast with a prediction interval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelForecast {
+    pub model: ForecastModelKind,
+    /// Whether the model was chosen by backtesting rather than configured
+    pub auto_selected: bool,
+    pub history_days: u32,
+    /// Two-sided coverage of `lower`..`upper`, e.g. 0.9
+    pub interval_level: f64,
+    /// Monthly points (every 30 days) up to the plan horizon
+    pub points: Vec<ModelForecastPoint>,
+    /// Days until the point forecast exceeds the limit
+    pub days_until_breach: Option<i64>,
+    /// Days until the upper bound exceeds the limit
+    pub earliest_breach_days: Option<i64>,
+    /// Days until the lower bound exceeds the limit
+    pub latest_breach_days: Option<i64>,
+    pub changepoints: Vec<DateTime<Utc>>,
+    pub backtest: Vec<BacktestScore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioBundle {
+    pub resource: ResourceKind,
+    pub current_value: f64,
+    pub limit: f64,
+    pub conservative: ResourceForecast,
+    pub base: ResourceForecast,
+    pub aggressive: ResourceForecast,
+    pub custom: Option<ResourceForecast>,
+    /// Present once the resource has enough snapshot history
+    pub model_forecast: Option<ModelForecast>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CapacityAlertSeverity {
+    Warning,
+    Critical,
+    Breached,
+}
+
+impl std::fmt::Display for CapacityAlertSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapacityAlertSeverity::Warning => write!(f, "WARNING"),
+            CapacityAlertSeverity::Critical => write!(f, "CRITICAL"),
+            CapacityAlertSeverity::Breached => write!(f, "BREACHED"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CapacityAlert {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub resource: ResourceKind,
+    pub severity: String,
+    pub current_value: f64,
+    pub limit_value: f64,
+    pub pct_consumed: f64,
+    pub breach_predicted_at: Option<DateTime<Utc>>,
+    pub days_until_breach: Option<i64>,
+    pub message: String,
+    pub acknowledged: bool,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcknowledgeAlertRequest {
+    pub acknowledged_by: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    StorageOptimization,
+    CodeOptimization,
+    ArchitectureChange,
+    InfrastructureScaling,
+    ConfigurationTuning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImplementationEffort {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalingRecommendation {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub resource: ResourceKind,
+    pub kind: RecommendationKind,
+    pub title: String,
+    pub description: String,
+    pub action: String,
+    pub effort: ImplementationEffort,
+    pub estimated_savings_pct: f64,
+    /// 1 = most urgent
+    pub priority: u8,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityCostEstimate {
+    pub resource: ResourceKind,
+    pub current_monthly_xlm: f64,
+    pub projected_monthly_xlm: f64,
+    pub projected_monthly_usd: f64,
+    pub cost_per_unit_xlm: f64,
+    pub units_at_horizon: f64,
+    pub horizon_months: u32,
+}
+
+fn default_horizon_months() -> u32 {
+    12
+}
+
+fn default_xlm_usd() -> f64 {
+    0.12
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityPlanParams {
+    #[serde(default = "default_horizon_months")]
+    pub horizon_months: u32,
+    #[serde(default = "default_xlm_usd")]
+    pub xlm_usd: f64,
+    pub custom_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityPlanResponse {
+    pub contract_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub scenarios: Vec<ScenarioBundle>,
+    pub alerts: Vec<CapacityAlert>,
+    pub recommendations: Vec<ScalingRecommendation>,
+    pub cost_estimates: Vec<CapacityCostEstimate>,
+    pub overall_status: String,
+    pub nearest_breach_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CapacityModelSetting {
+    pub contract_id: Uuid,
+    pub resource: ResourceKind,
+    pub model: ForecastModelKind,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCapacityModelRequest {
+    pub model: ForecastModelKind,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// COST ESTIMATION TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Resources one invocation consumes, as reported by `simulateTransaction`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SorobanResources {
+    pub instructions: u64,
+    pub read_entries: u32,
+    pub write_entries: u32,
+    pub read_bytes: u32,
+    pub write_bytes: u32,
+    pub events_bytes: u32,
+    pub transaction_size_bytes: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageDurability {
+    #[default]
+    Persistent,
+    Temporary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostEstimateRequest {
+    pub method_name: String,
+    pub invocations: Option<i64>,
+    /// New contract data over the estimate's period
+    pub storage_growth_kb: Option<i64>,
+    /// Per-invocation resources, e.g. from a simulation. Without them the
+    /// method's recorded averages are used.
+    #[serde(default)]
+    pub resources: Option<SorobanResources>,
+    #[serde(default)]
+    pub storage_durability: Option<StorageDurability>,
+    /// Lifetime to pay rent for on new storage; defaults to the network minimum
+    #[serde(default)]
+    pub rent_ledgers: Option<u32>,
+}
+
+/// Resource fee of one invocation, in stroops.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceFeeBreakdown {
+    pub compute: i64,
+    pub read_entries: i64,
+    pub write_entries: i64,
+    pub read_bytes: i64,
+    pub write_bytes: i64,
+    pub historical: i64,
+    pub bandwidth: i64,
+    pub events: i64,
+    pub rent: i64,
+    pub non_refundable: i64,
+    pub refundable: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostEstimate {
+    pub method_name: String,
+    /// CPU instructions
+    pub gas_cost: i64,
+    /// Ledger entry and byte access, plus rent
+    pub storage_cost: i64,
+    /// Transaction size, history and events
+    pub bandwidth_cost: i64,
+    #[serde(default)]
+    pub inclusion_fee: i64,
+    pub total_stroops: i64,
+    pub total_xlm: f64,
+    pub invocations: i64,
+    #[serde(default)]
+    pub per_invocation: Option<ResourceFeeBreakdown>,
+    #[serde(default)]
+    pub resources: Option<SorobanResources>,
+    /// `request`, `history` or `default`
+    #[serde(default)]
+    pub resource_source: Option<String>,
+    /// `indexer`, `file` or `default`
+    #[serde(default)]
+    pub fee_config_source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCostEstimate {
+    pub estimates: Vec<CostEstimate>,
+    pub total_stroops: i64,
+    pub total_xlm: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostOptimization {
+    pub current_cost: i64,
+    pub optimized_cost: i64,
+    pub savings_percent: f64,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostForecast {
+    pub daily_cost_xlm: f64,
+    pub monthly_cost_xlm: f64,
+    pub yearly_cost_xlm: f64,
+    pub usage_pattern: String,
+}
+
+/// Resources observed for one real invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordCostObservationRequest {
+    pub method_name: String,
+    pub resources: SorobanResources,
+}
+
+/// Running per-method resource averages used for estimates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodResourceProfile {
+    pub method_name: String,
+    pub resources: SorobanResources,
+    pub samples: i32,
+    pub last_updated: DateTime<Utc>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// LEDGER SNAPSHOT TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// One indexed ledger entry of a contract, as XDR
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LedgerEntryRecord {
+    pub durability: String,
+    pub key_xdr: String,
+    pub entry_xdr: String,
+    pub key_display: Option<String>,
+    pub live_until_ledger: Option<i32>,
+    pub last_modified_ledger: i32,
+}
+
+/// Indexed ledger state of a contract, enough to rebuild a local ledger
+/// snapshot. Served by the regression snapshot endpoint and by backup restores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerSnapshotResponse {
+    pub contract_id: Uuid,
+    pub contract_address: String,
+    pub network: String,
+    pub ledger_sequence: Option<i32>,
+    pub ledger_timestamp: Option<i64>,
+    pub protocol_version: Option<i32>,
+    pub network_passphrase: Option<String>,
+    pub entries: Vec<LedgerEntryRecord>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// BACKUP TYPES
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ContractBackup {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub backup_date: chrono::NaiveDate,
+    pub wasm_hash: String,
+    pub metadata: serde_json::Value,
+    /// `full`, `incremental` or `metadata`
+    pub backup_kind: String,
+    /// Previous backup of the contract; its chunks are not stored again
+    pub parent_backup_id: Option<Uuid>,
+    /// Ledger the captured state is as of
+    pub ledger_sequence: Option<i64>,
+    pub manifest_hash: Option<String>,
+    pub entry_count: i32,
+    pub chunk_count: i32,
+    /// Chunks this backup added to the store
+    pub new_chunk_count: i32,
+    /// Size of the captured state
+    pub logical_size_bytes: i64,
+    /// Bytes this backup added to the store
+    pub storage_size_bytes: i64,
+    pub blob_store: Option<String>,
+    pub verified: bool,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub primary_region: String,
+    pub backup_regions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBackupRequest {
+    /// Capture ledger state; without it only metadata and the WASM hash are kept
+    #[serde(default = "default_true")]
+    pub include_state: bool,
+    /// Trust the chunk index and upload only chunks it has not seen; `false`
+    /// checks the store for every chunk and re-uploads any that are missing
+    #[serde(default = "default_true")]
+    pub incremental: bool,
+    /// Regions to copy the backup to; defaults to every configured region
+    /// besides the primary. Residency policies may reroute or refuse them.
+    #[serde(default)]
+    pub regions: Option<Vec<String>>,
+}
+
+/// Selects the backup to restore. The newest backup at or before the given
+/// point is used; with nothing set, the latest backup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreBackupRequest {
+    #[serde(default)]
+    pub backup_id: Option<Uuid>,
+    /// `YYYY-MM-DD`
+    #[serde(default)]
+    pub backup_date: Option<String>,
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub ledger_sequence: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BackupRestoration {
+    pub id: Uuid,
+    pub backup_id: Uuid,
+    pub restored_by: Uuid,
+    pub restore_duration_ms: i32,
+    pub success: bool,
+    pub error_message: Option<String>,
+    pub restored_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub backup_id: Uuid,
+    pub verified: bool,
+    pub chunks_checked: usize,
+    pub missing_chunks: Vec<String>,
+    pub corrupt_chunks: Vec<String>,
+    pub manifest_ok: bool,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// A backup's copy in one region.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BackupReplica {
+    pub id: Uuid,
+    pub backup_id: Uuid,
+    /// Region holding the copy; the requested region when refused
+    pub region: String,
+    pub requested_region: String,
+    /// `pending`, `completed`, `failed` or `refused`
+    pub status: String,
+    pub chunks_copied: i32,
+    pub bytes_copied: i64,
+    pub reason: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub replicated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionReplicationHealth {
+    pub region: String,
+    /// `healthy`, `degraded` (copies pending too long) or `failing`
+    pub status: String,
+    pub completed: i64,
+    pub pending: i64,
+    pub failed: i64,
+    pub refused: i64,
+    pub last_replicated_at: Option<DateTime<Utc>>,
+    /// Time from backup to copy for the latest completed copy
+    pub last_lag_seconds: Option<i64>,
+    /// Age of the oldest copy still pending
+    pub current_lag_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupReplicationStatus {
+    pub regions: Vec<RegionReplicationHealth>,
+    pub replicas: Vec<BackupReplica>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PUBLISHER NAMESPACES & ORGANIZATIONS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub slug: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub home_domain: Option<String>,
+    /// Account whose signature in the home domain's stellar.toml verifies the organization
+    pub stellar_address: Option<String>,
+    pub verified: bool,
+    pub verified_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub verification_nonce: Option<String>,
+    pub verification_error: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrganizationMember {
+    pub publisher_id: Uuid,
+    pub stellar_address: String,
+    pub username: Option<String>,
+    /// `owner`, `admin` or `publisher`
+    pub role: String,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationDetail {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub members: Vec<OrganizationMember>,
+    pub contract_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrganizationRequest {
+    pub slug: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub home_domain: Option<String>,
+    pub stellar_address: Option<String>,
+    /// Becomes the first owner
+    pub created_by: String,
+}
+
+/// Changing `home_domain` or `stellar_address` clears verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateOrganizationRequest {
+    pub actor_address: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub home_domain: Option<String>,
+    pub stellar_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddOrganizationMemberRequest {
+    pub actor_address: String,
+    pub publisher_address: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationActorRequest {
+    pub actor_address: String,
+}
+
+/// What to publish in the home domain's stellar.toml to verify an organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationVerificationChallenge {
+    pub organization: String,
+    pub stellar_toml_url: String,
+    pub account: String,
+    /// Sign these exact bytes with the account's key
+    pub statement: String,
+    /// The section to add, with the signature left to fill in
+    pub stellar_toml_snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReservedName {
+    pub name: String,
+    /// The only organization allowed to publish under this name
+    pub organization_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReservedNameRequest {
+    pub name: String,
+    /// Organization slug the name is reserved for
+    pub organization: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarName {
+    /// Scoped when the existing contract is, e.g. `@acme/usdc-token`
+    pub name: String,
+    pub distance: usize,
+    /// `reserved`, `contract` or `organization`
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameCheckParams {
+    pub name: String,
+    pub publisher_address: Option<String>,
+}
+
+/// Whether a publisher may publish under a name, and why not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameCheck {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub available: bool,
+    pub reason: Option<String>,
+    pub similar: Vec<SimilarName>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// SEARCH
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Query parameters for `GET /api/search`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchParams {
+    #[serde(alias = "query")]
+    pub q: Option<String>,
+    pub network: Option<Network>,
+    pub category: Option<String>,
+    /// One of alpha, beta, stable, mature, legacy
+    pub maturity: Option<String>,
+    pub verified: Option<bool>,
+    pub license: Option<String>,
+    /// Comma-separated; a hit must carry every tag
+    pub tags: Option<String>,
+    pub page: Option<i64>,
+    #[serde(alias = "page_size")]
+    pub limit: Option<i64>,
+}
+
+/// One ranked result
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub contract: Contract,
+    pub score: f64,
+    pub popularity: f64,
+    pub trust: f64,
+    /// Name and description with matches wrapped in `<mark>` tags
+    pub name_highlight: Option<String>,
+    pub description_highlight: Option<String>,
+    /// ABI functions that matched the query
+    pub matched_functions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchFacet {
+    pub name: String,
+    pub values: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: Option<String>,
+    /// Set when nothing matched as typed and a spelling correction was used
+    pub corrected_query: Option<String>,
+    pub hits: Vec<SearchHit>,
+    pub facets: Vec<SearchFacet>,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SearchSynonym {
+    pub term: String,
+    pub synonyms: Vec<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpsertSynonymRequest {
+    pub synonyms: Vec<String>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// CONTRACT SIMILARITY
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Function-body hashes of a contract's WASM
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ContractFingerprint {
+    pub contract_id: Uuid,
+    pub wasm_hash: String,
+    pub function_hashes: Vec<String>,
+    pub function_count: i32,
+    pub wasm_size_bytes: i64,
+    pub computed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimilarContractsParams {
+    pub limit: Option<usize>,
+    /// 0.0–1.0; defaults to 0.2
+    pub min_score: Option<f64>,
+    pub network: Option<Network>,
+}
+
+/// A contract ranked by similarity to another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarContract {
+    pub id: Uuid,
+    pub contract_id: String,
+    pub name: String,
+    pub network: String,
+    pub is_verified: bool,
+    /// 0.0–1.0 weighted over the signals both contracts have
+    pub score: f64,
+    /// identical, clone, fork_of, forked_by or similar
+    pub relation: String,
+    pub code_similarity: Option<f64>,
+    pub abi_similarity: Option<f64>,
+    pub tag_similarity: Option<f64>,
+    pub shared_functions: Vec<String>,
+    pub shared_fragments: usize,
+    /// Has open critical or high advisories
+    pub known_vulnerable: bool,
+    pub severity: Option<String>,
+    pub advisories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarContractsResponse {
+    pub contract_id: Uuid,
+    /// Without a fingerprint only ABI and tags are compared
+    pub fingerprinted: bool,
+    pub items: Vec<SimilarContract>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// DEPENDENCY GRAPH ANALYTICS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A contract in a graph report, with the data used to weigh its importance
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GraphContract {
+    pub id: Uuid,
+    pub contract_id: String,
+    pub name: String,
+    pub network: String,
+    pub maturity: String,
+    pub is_verified: bool,
+    /// 0–100, refreshed by the search index task
+    pub trust_score: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlastRadiusParams {
+    /// Only count direct dependents whose version constraint admits this
+    /// release
+    pub version: Option<String>,
+    pub max_depth: Option<usize>,
+}
+
+/// A contract reached by walking dependents outwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactedContract {
+    #[serde(flatten)]
+    pub contract: GraphContract,
+    /// Hops from the origin; 1 for direct dependents
+    pub depth: i32,
+    /// The dependency through which this contract is reached
+    pub via: Uuid,
+    /// Requirement on `via`, as declared by this contract
+    pub version_constraint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlastRadiusResponse {
+    pub contract: GraphContract,
+    pub version: Option<String>,
+    pub total: usize,
+    pub max_depth: i32,
+    /// Direct dependents whose constraint excludes `version`
+    pub excluded_by_version: usize,
+    /// Origin first, ending at the most distant impacted contract
+    pub critical_path: Vec<Uuid>,
+    pub impacted: Vec<ImpactedContract>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyCycle {
+    pub contracts: Vec<GraphContract>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyCyclesResponse {
+    pub total: usize,
+    pub cycles: Vec<DependencyCycle>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CentralityParams {
+    pub limit: Option<usize>,
+    pub network: Option<Network>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CentralityEntry {
+    #[serde(flatten)]
+    pub contract: GraphContract,
+    pub rank: usize,
+    /// PageRank over dependency edges; sums to 1 across the graph
+    pub pagerank: f64,
+    pub direct_dependents: usize,
+    pub transitive_dependents: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CentralityResponse {
+    /// Contracts with at least one dependency edge
+    pub total_contracts: usize,
+    pub items: Vec<CentralityEntry>,
+}
+
+/// How badly pausing a dependency hurts a dependent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum PauseSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseImpactEntry {
+    #[serde(flatten)]
+    pub impacted: ImpactedContract,
+    pub severity: PauseSeverity,
+    /// Contribution to the report's impact score
+    pub weight: f64,
+}
+
+/// What stops working if a contract is paused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseImpactResponse {
+    pub contract: GraphContract,
+    pub version: Option<String>,
+    pub total: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    /// Sum of entry weights; comparable between contracts
+    pub impact_score: f64,
+    pub critical_path: Vec<Uuid>,
+    pub items: Vec<PauseImpactEntry>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TRUST POLICIES
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Maximum points per trust factor. Scores are normalised by the total, so
+/// weights need not add up to 100; a zero weight disables the factor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustWeights {
+    pub verification: f64,
+    pub audit: f64,
+    pub usage: f64,
+    pub age: f64,
+    pub vulnerabilities: f64,
+}
+
+impl Default for TrustWeights {
+    fn default() -> Self {
+        Self {
+            verification: 25.0,
+            audit: 35.0,
+            usage: 20.0,
+            age: 10.0,
+            vulnerabilities: 10.0,
+        }
+    }
+}
+
+/// Levels at which usage and age earn full points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustCaps {
+    pub deployments: i64,
+    pub interactions: i64,
+    pub age_days: i64,
+    /// Points deducted from the vulnerability factor per open critical finding
+    pub penalty_per_vulnerability: f64,
+}
+
+impl Default for TrustCaps {
+    fn default() -> Self {
+        Self {
+            deployments: 50,
+            interactions: 500,
+            age_days: 180,
+            penalty_per_vulnerability: 5.0,
+        }
+    }
+}
+
+/// Conditions a contract must meet under a policy, whatever its score
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustRequirements {
+    pub verified: bool,
+    pub min_audit_score: Option<f64>,
+    pub max_critical_vulns: Option<i64>,
+    pub min_age_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustPolicyConfig {
+    pub weights: TrustWeights,
+    pub caps: TrustCaps,
+    pub requirements: TrustRequirements,
+    /// Minimum score (0–100) for a dependency to pass the deploy gate
+    pub gate_threshold: f64,
+}
+
+impl Default for TrustPolicyConfig {
+    fn default() -> Self {
+        Self {
+            weights: TrustWeights::default(),
+            caps: TrustCaps::default(),
+            requirements: TrustRequirements::default(),
+            gate_threshold: 50.0,
+        }
+    }
+}
+
+/// One version of a named trust policy. Versions are immutable; publishing
+/// under an existing name adds the next version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustPolicy {
+    pub id: Uuid,
+    pub name: String,
+    pub version: i32,
+    pub description: Option<String>,
+    pub config: TrustPolicyConfig,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTrustPolicyRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub config: TrustPolicyConfig,
+    pub created_by: String,
+}
+
+/// Selects a policy; the latest version of `default` when omitted
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustPolicyParams {
+    pub policy: Option<String>,
+    pub policy_version: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustHistoryParams {
+    pub policy: Option<String>,
+    pub days: Option<i64>,
+}
+
+/// Daily trust score under one policy version
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TrustScoreSnapshot {
+    pub snapshot_date: chrono::NaiveDate,
+    pub policy_version: i32,
+    pub score: f64,
+    pub badge: String,
+    pub meets_requirements: bool,
+    pub factors: serde_json::Value,
+    pub computed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustScoreHistoryResponse {
+    pub contract_id: Uuid,
+    pub policy: String,
+    /// Oldest first
+    pub snapshots: Vec<TrustScoreSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustGateRequest {
+    pub policy: Option<String>,
+    pub policy_version: Option<i32>,
+    /// Registry UUIDs or on-chain contract IDs
+    pub contracts: Vec<String>,
+    /// Disambiguates on-chain IDs registered on several networks
+    pub network: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustGateResult {
+    /// As given in the request
+    pub contract: String,
+    pub id: Option<Uuid>,
+    pub name: Option<String>,
+    pub score: Option<f64>,
+    pub passed: bool,
+    /// Why the contract failed; empty when it passed
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustGateResponse {
+    pub policy: String,
+    pub policy_version: i32,
+    pub threshold: f64,
+    /// True only when every contract passed
+    pub passed: bool,
+    pub results: Vec<TrustGateResult>,
+}
@@ -1,18 +1,40 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 /// Semantic Versioning (SemVer) implementation
-/// Supports parsing MAJOR.MINOR.PATCH and constraints like ^1.0.0, ~2.3.0
+/// Supports parsing MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD], constraints like
+/// ^1.0.0 and ~2.3.0, and Cargo-style requirements like ">= 1.2, < 2".
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SemVer {
     pub major: u64,
     pub minor: u64,
     pub patch: u64,
+    /// Pre-release identifiers, e.g. `alpha.1`; empty for a release
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre: String,
 }
 
 impl SemVer {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        SemVer {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+        }
+    }
+
+    /// Parse `MAJOR.MINOR.PATCH`, with an optional `-pre` and `+build`.
+    /// Build metadata is discarded; it does not affect precedence.
     pub fn parse(s: &str) -> Option<Self> {
-        let parts: Vec<&str> = s.split('.').collect();
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) if valid_pre(pre) => (core, pre),
+            Some(_) => return None,
+            None => (s, ""),
+        };
+        let parts: Vec<&str> = core.split('.').collect();
         if parts.len() != 3 {
             return None;
         }
@@ -21,13 +43,60 @@ impl SemVer {
             major: parts[0].parse().ok()?,
             minor: parts[1].parse().ok()?,
             patch: parts[2].parse().ok()?,
+            pre: pre.to_string(),
         })
     }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+fn valid_pre(pre: &str) -> bool {
+    !pre.is_empty()
+        && pre
+            .split('.')
+            .all(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+}
+
+/// Pre-release precedence: a release sorts after any pre-release of the
+/// same version; identifiers compare numerically when both are numbers.
+fn cmp_pre(a: &str, b: &str) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => {}
+    }
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for SemVer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.is_prerelease() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
     }
 }
 
@@ -43,6 +112,7 @@ impl Ord for SemVer {
             .cmp(&other.major)
             .then(self.minor.cmp(&other.minor))
             .then(self.patch.cmp(&other.patch))
+            .then_with(|| cmp_pre(&self.pre, &other.pre))
     }
 }
 
@@ -93,3 +163,374 @@ impl VersionConstraint {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    Wildcard,
+}
+
+/// One comparator of a requirement. Versions may be partial (`>= 1.2`),
+/// in which case the missing parts are unconstrained, as in Cargo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comparator {
+    pub op: Op,
+    pub major: u64,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre: String,
+}
+
+impl Comparator {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| s.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Caret, s));
+        let rest = rest.trim().split('+').next()?;
+
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) if valid_pre(pre) => (core, pre.to_string()),
+            Some(_) => return None,
+            None => (rest, String::new()),
+        };
+        let mut parts = core.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let mut wildcard = false;
+        let mut part = |parts: &mut std::str::Split<'_, char>| -> Option<Option<u64>> {
+            match parts.next() {
+                None => Some(None),
+                Some("*") | Some("x") | Some("X") => {
+                    wildcard = true;
+                    Some(None)
+                }
+                Some(_) if wildcard => None,
+                Some(n) => n.parse().ok().map(Some),
+            }
+        };
+        let minor = part(&mut parts)?;
+        let patch = part(&mut parts)?;
+        if parts.next().is_some() || (!pre.is_empty() && patch.is_none()) {
+            return None;
+        }
+        let op = if wildcard {
+            match op {
+                Op::Caret | Op::Exact => Op::Wildcard,
+                _ => return None,
+            }
+        } else {
+            op
+        };
+        Some(Comparator {
+            op,
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+
+    pub fn matches(&self, version: &SemVer) -> bool {
+        match self.op {
+            Op::Exact | Op::Wildcard => self.matches_exact(version),
+            Op::Greater => self.matches_greater(version),
+            Op::GreaterEq => self.matches_exact(version) || self.matches_greater(version),
+            Op::Less => self.matches_less(version),
+            Op::LessEq => self.matches_exact(version) || self.matches_less(version),
+            Op::Tilde => self.matches_tilde(version),
+            Op::Caret => self.matches_caret(version),
+        }
+    }
+
+    fn matches_exact(&self, v: &SemVer) -> bool {
+        v.major == self.major
+            && part_matches(self.minor, v.minor)
+            && part_matches(self.patch, v.patch)
+            && v.pre == self.pre
+    }
+
+    fn matches_greater(&self, v: &SemVer) -> bool {
+        if v.major != self.major {
+            return v.major > self.major;
+        }
+        let Some(minor) = self.minor else {
+            return false;
+        };
+        if v.minor != minor {
+            return v.minor > minor;
+        }
+        let Some(patch) = self.patch else {
+            return false;
+        };
+        if v.patch != patch {
+            return v.patch > patch;
+        }
+        cmp_pre(&v.pre, &self.pre) == Ordering::Greater
+    }
+
+    fn matches_less(&self, v: &SemVer) -> bool {
+        if v.major != self.major {
+            return v.major < self.major;
+        }
+        let Some(minor) = self.minor else {
+            return false;
+        };
+        if v.minor != minor {
+            return v.minor < minor;
+        }
+        let Some(patch) = self.patch else {
+            return false;
+        };
+        if v.patch != patch {
+            return v.patch < patch;
+        }
+        cmp_pre(&v.pre, &self.pre) == Ordering::Less
+    }
+
+    // ~1.2.3 := >=1.2.3 <1.3.0, ~1.2 := >=1.2.0 <1.3.0, ~1 := >=1.0.0 <2.0.0
+    fn matches_tilde(&self, v: &SemVer) -> bool {
+        if v.major != self.major || !part_matches(self.minor, v.minor) {
+            return false;
+        }
+        match self.patch {
+            Some(patch) if v.patch != patch => v.patch > patch,
+            _ => cmp_pre(&v.pre, &self.pre) != Ordering::Less,
+        }
+    }
+
+    // ^1.2.3 := >=1.2.3 <2.0.0, ^0.2.3 := >=0.2.3 <0.3.0, ^0.0.3 := =0.0.3
+    fn matches_caret(&self, v: &SemVer) -> bool {
+        if v.major != self.major {
+            return false;
+        }
+        let Some(minor) = self.minor else {
+            return true;
+        };
+        let Some(patch) = self.patch else {
+            return if self.major > 0 {
+                v.minor >= minor
+            } else {
+                v.minor == minor
+            };
+        };
+        if self.major > 0 {
+            if v.minor != minor {
+                return v.minor > minor;
+            }
+            if v.patch != patch {
+                return v.patch > patch;
+            }
+        } else if minor > 0 {
+            if v.minor != minor {
+                return false;
+            }
+            if v.patch != patch {
+                return v.patch > patch;
+            }
+        } else if v.minor != minor || v.patch != patch {
+            return false;
+        }
+        cmp_pre(&v.pre, &self.pre) != Ordering::Less
+    }
+}
+
+/// A missing part of a partial version matches anything.
+fn part_matches(part: Option<u64>, value: u64) -> bool {
+    match part {
+        Some(part) => part == value,
+        None => true,
+    }
+}
+
+/// A Cargo-style version requirement: comma-separated comparators that must
+/// all match. `*` or an empty string matches every release. A bare version
+/// is a caret requirement, as in `Cargo.toml` and RustSec advisories.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionReq {
+    pub comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() || s == "*" {
+            return Some(VersionReq {
+                comparators: Vec::new(),
+            });
+        }
+        let comparators = s
+            .split(',')
+            .map(Comparator::parse)
+            .collect::<Option<Vec<_>>>()?;
+        Some(VersionReq { comparators })
+    }
+
+    /// Pre-releases only match when a comparator names a pre-release of the
+    /// same MAJOR.MINOR.PATCH, so `>= 1.0.0` does not pull in `2.0.0-rc.1`.
+    pub fn matches(&self, version: &SemVer) -> bool {
+        if !self.comparators.iter().all(|c| c.matches(version)) {
+            return false;
+        }
+        !version.is_prerelease()
+            || self.comparators.iter().any(|c| {
+                !c.pre.is_empty()
+                    && c.major == version.major
+                    && c.minor == Some(version.minor)
+                    && c.patch == Some(version.patch)
+            })
+    }
+}
+
+impl std::str::FromStr for VersionReq {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VersionReq::parse(s).ok_or_else(|| format!("invalid version requirement: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> SemVer {
+        SemVer::parse(s).unwrap()
+    }
+
+    fn req(s: &str) -> VersionReq {
+        VersionReq::parse(s).unwrap()
+    }
+
+    #[test]
+    fn prerelease_precedence_follows_the_spec() {
+        // The ordering example from semver.org §11
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(v("1.0.0+build.5"), v("1.0.0"));
+        assert!(v("1.0.0") < v("1.0.1-alpha"));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_versions() {
+        for bad in [
+            "1.0",
+            "1.0.0.0",
+            "1.x.0",
+            "1.0.0-",
+            "1.0.0-a..b",
+            "1.0.0-a_b",
+            "",
+        ] {
+            assert!(SemVer::parse(bad).is_none(), "{}", bad);
+        }
+        assert_eq!(v("2.3.4-rc.1").to_string(), "2.3.4-rc.1");
+    }
+
+    #[test]
+    fn caret_bounds() {
+        let r = req("^1.2.3");
+        assert!(r.matches(&v("1.2.3")));
+        assert!(r.matches(&v("1.9.0")));
+        assert!(!r.matches(&v("1.2.2")));
+        assert!(!r.matches(&v("2.0.0")));
+
+        let r = req("^0.2.3");
+        assert!(r.matches(&v("0.2.9")));
+        assert!(!r.matches(&v("0.3.0")));
+
+        let r = req("^0.0.3");
+        assert!(r.matches(&v("0.0.3")));
+        assert!(!r.matches(&v("0.0.4")));
+
+        // A bare version is a caret requirement
+        assert_eq!(req("1.2"), req("^1.2"));
+        assert!(req("1.2").matches(&v("1.5.0")));
+        assert!(!req("0.2").matches(&v("0.3.0")));
+    }
+
+    #[test]
+    fn tilde_bounds() {
+        let r = req("~1.2.3");
+        assert!(r.matches(&v("1.2.3")));
+        assert!(r.matches(&v("1.2.9")));
+        assert!(!r.matches(&v("1.3.0")));
+        assert!(!r.matches(&v("1.2.2")));
+
+        assert!(req("~1.2").matches(&v("1.2.0")));
+        assert!(!req("~1.2").matches(&v("1.3.0")));
+        assert!(req("~1").matches(&v("1.9.9")));
+        assert!(!req("~1").matches(&v("2.0.0")));
+    }
+
+    #[test]
+    fn prereleases_only_match_when_named() {
+        assert!(!req(">= 1.0.0").matches(&v("2.0.0-rc.1")));
+        assert!(req(">= 2.0.0-rc.1").matches(&v("2.0.0-rc.2")));
+        assert!(req(">= 2.0.0-rc.1").matches(&v("2.0.0")));
+        assert!(!req(">= 2.0.0-rc.1").matches(&v("2.0.0-beta")));
+        assert!(req("^1.2.3-alpha").matches(&v("1.2.3-beta")));
+        assert!(!req("^1.2.3-alpha").matches(&v("1.2.4-beta")));
+    }
+
+    #[test]
+    fn comparator_lists_and_wildcards() {
+        let r = req(">= 1.2, < 2");
+        assert!(r.matches(&v("1.2.0")));
+        assert!(r.matches(&v("1.9.9")));
+        assert!(!r.matches(&v("1.1.9")));
+        assert!(!r.matches(&v("2.0.0")));
+
+        assert!(req("*").matches(&v("0.0.1")));
+        assert!(req("").matches(&v("3.0.0")));
+        assert!(req("1.*").matches(&v("1.7.2")));
+        assert!(!req("1.*").matches(&v("2.0.0")));
+        assert_eq!(req("1.x").comparators[0].op, Op::Wildcard);
+    }
+
+    #[test]
+    fn comparator_parse_errors() {
+        for bad in [
+            ">=",
+            "abc",
+            "1.2.3.4",
+            "1.*.3",
+            ">1.*",
+            "~1.x",
+            "1.2-alpha",
+            "1.2.3-",
+            "1.2.3-a..b",
+            ">= 1.0, ",
+        ] {
+            assert!(VersionReq::parse(bad).is_none(), "{:?}", bad);
+        }
+        let err = "nope".parse::<VersionReq>().unwrap_err();
+        assert_eq!(err, "invalid version requirement: nope");
+    }
+}
@@ -1,129 +1,298 @@
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use shared::models::{
+    BackupReplicationStatus, BackupRestoration, BackupVerification, ContractBackup,
+    CreateBackupRequest, RestoreBackupRequest,
+};
 
-#[derive(Debug, Serialize)]
-struct CreateBackupRequest {
-    include_state: bool,
-}
+use crate::regression::{self, SnapshotResponse};
 
 #[derive(Debug, Deserialize)]
-struct ContractBackup {
-    id: String,
-    contract_id: String,
-    backup_date: String,
-    wasm_hash: String,
-    storage_size_bytes: i64,
-    verified: bool,
-    primary_region: String,
-    backup_regions: Vec<String>,
+struct RestoredBackup {
+    backup: ContractBackup,
+    restoration: BackupRestoration,
+    snapshot: SnapshotResponse,
 }
 
-#[derive(Debug, Serialize)]
-struct RestoreBackupRequest {
-    backup_date: String,
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response
+        .json()
+        .await
+        .context("Invalid response from registry")
 }
 
-#[derive(Debug, Deserialize)]
-struct BackupRestoration {
-    id: String,
-    restore_duration_ms: i32,
-    success: bool,
-    restored_at: String,
+fn ledger_label(ledger: Option<i64>) -> String {
+    ledger
+        .map(|l| format!("ledger {}", l))
+        .unwrap_or_else(|| "ledger unknown".into())
 }
 
-pub async fn create_backup(api_url: &str, contract_id: &str, include_state: bool) -> Result<()> {
+pub async fn create_backup(
+    api_url: &str,
+    contract_id: &str,
+    include_state: bool,
+    full: bool,
+    regions: Option<&str>,
+) -> Result<()> {
     let client = reqwest::Client::new();
-    let backup: ContractBackup = client
-        .post(format!("{}/api/contracts/{}/backups", api_url, contract_id))
-        .json(&CreateBackupRequest { include_state })
-        .send()
-        .await?
-        .json()
-        .await?;
+    let backup: ContractBackup = send(
+        client
+            .post(format!("{}/api/contracts/{}/backups", api_url, contract_id))
+            .json(&CreateBackupRequest {
+                include_state,
+                incremental: !full,
+                regions: regions.map(|r| {
+                    r.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                }),
+            }),
+        "create backup",
+    )
+    .await?;
 
     println!("✅ Backup created successfully");
-    println!("   Date: {}", backup.backup_date);
-    println!("   Size: {} bytes", backup.storage_size_bytes);
-    println!("   Regions: {}", backup.backup_regions.join(", "));
+    println!("   ID: {}", backup.id);
+    println!(
+        "   Kind: {} ({})",
+        backup.backup_kind,
+        ledger_label(backup.ledger_sequence)
+    );
+    println!(
+        "   Entries: {} in {} chunks ({} new)",
+        backup.entry_count, backup.chunk_count, backup.new_chunk_count
+    );
+    println!(
+        "   Size: {} bytes, {} bytes stored",
+        backup.logical_size_bytes, backup.storage_size_bytes
+    );
+    if let Some(store) = &backup.blob_store {
+        println!("   Store: {}", store);
+    }
+    println!("   Primary region: {}", backup.primary_region);
+    if !backup.backup_regions.is_empty() {
+        println!("   Replicating to: {}", backup.backup_regions.join(", "));
+    }
     Ok(())
 }
 
 pub async fn list_backups(api_url: &str, contract_id: &str) -> Result<()> {
     let client = reqwest::Client::new();
-    let backups: Vec<ContractBackup> = client
-        .get(format!("{}/api/contracts/{}/backups", api_url, contract_id))
-        .send()
-        .await?
-        .json()
-        .await?;
+    let backups: Vec<ContractBackup> = send(
+        client.get(format!("{}/api/contracts/{}/backups", api_url, contract_id)),
+        "list backups",
+    )
+    .await?;
 
-    println!("📦 Contract Backups (last 30 days)");
+    println!("📦 Contract Backups (latest 30)");
     println!("═══════════════════════════════════════════════════════");
     for backup in backups {
         let status = if backup.verified { "✓" } else { "○" };
         println!(
-            "{} {} - {} bytes - {}",
-            status, backup.backup_date, backup.storage_size_bytes, backup.primary_region
+            "{} {} {} {:<11} {:<16} {} entries, {} bytes stored",
+            status,
+            backup.id,
+            backup.created_at.format("%Y-%m-%d %H:%M"),
+            backup.backup_kind,
+            ledger_label(backup.ledger_sequence),
+            backup.entry_count,
+            backup.storage_size_bytes
         );
     }
     Ok(())
 }
 
-pub async fn restore_backup(api_url: &str, contract_id: &str, backup_date: &str) -> Result<()> {
+/// Restore the newest backup at or before the given point and write its
+/// state as a fixture that `regression replay` and the sandbox load.
+pub async fn restore_backup(
+    api_url: &str,
+    contract_id: &str,
+    backup_id: Option<&str>,
+    ledger: Option<i64>,
+    at: Option<&str>,
+    output: &str,
+) -> Result<()> {
     let client = reqwest::Client::new();
-    
-    println!("🔄 Restoring backup from {}...", backup_date);
-    
-    let restoration: BackupRestoration = client
-        .post(format!("{}/api/contracts/{}/backups/restore", api_url, contract_id))
-        .json(&RestoreBackupRequest {
-            backup_date: backup_date.to_string(),
-        })
-        .send()
-        .await?
-        .json()
-        .await?;
-
-    if restoration.success {
-        println!("✅ Restoration completed successfully");
-        println!("   Duration: {}ms", restoration.restore_duration_ms);
-        println!("   Restored at: {}", restoration.restored_at);
-    } else {
-        println!("❌ Restoration failed");
-    }
+
+    let request = RestoreBackupRequest {
+        backup_id: backup_id
+            .map(|id| id.parse().context("Invalid backup ID"))
+            .transpose()?,
+        backup_date: None,
+        at: at
+            .map(|t| {
+                DateTime::parse_from_rfc3339(t)
+                    .map(|t| t.with_timezone(&Utc))
+                    .context("Invalid --at timestamp, expected RFC 3339")
+            })
+            .transpose()?,
+        ledger_sequence: ledger,
+    };
+
+    println!("🔄 Restoring backup...");
+
+    let restored: RestoredBackup = send(
+        client
+            .post(format!(
+                "{}/api/contracts/{}/backups/restore",
+                api_url, contract_id
+            ))
+            .json(&request),
+        "restore backup",
+    )
+    .await?;
+
+    let fixture = regression::fixture_from_response(
+        contract_id,
+        restored.snapshot,
+        restored.backup.created_at,
+    )?;
+    std::fs::write(output, serde_json::to_string_pretty(&fixture)?)
+        .with_context(|| format!("Failed to write fixture {}", output))?;
+
+    println!("✅ Restoration completed successfully");
+    println!("   Backup: {}", restored.backup.id);
+    println!(
+        "   State: {} ({} entries)",
+        ledger_label(restored.backup.ledger_sequence),
+        fixture.snapshot.ledger_entries.len()
+    );
+    println!(
+        "   Duration: {}ms",
+        restored.restoration.restore_duration_ms
+    );
+    println!("   Fixture: {}", output);
     Ok(())
 }
 
-pub async fn verify_backup(api_url: &str, contract_id: &str, backup_date: &str) -> Result<()> {
+pub async fn verify_backup(api_url: &str, contract_id: &str, backup: &str) -> Result<()> {
     let client = reqwest::Client::new();
-    client
-        .post(format!(
+    let report: BackupVerification = send(
+        client.post(format!(
             "{}/api/contracts/{}/backups/{}/verify",
-            api_url, contract_id, backup_date
-        ))
-        .send()
-        .await?;
+            api_url, contract_id, backup
+        )),
+        "verify backup",
+    )
+    .await?;
 
-    println!("✅ Backup verified: {}", backup_date);
-    Ok(())
+    if report.verified {
+        println!(
+            "✅ Backup verified: {} ({} chunks)",
+            report.backup_id, report.chunks_checked
+        );
+        return Ok(());
+    }
+
+    println!("❌ Backup failed verification: {}", report.backup_id);
+    if !report.manifest_ok {
+        println!("   Manifest is missing or does not match its hash");
+    }
+    for hash in &report.missing_chunks {
+        println!("   Missing chunk: {}", hash);
+    }
+    for hash in &report.corrupt_chunks {
+        println!("   Corrupt chunk: {}", hash);
+    }
+    bail!(
+        "{} of {} chunks failed verification",
+        report.missing_chunks.len() + report.corrupt_chunks.len(),
+        report.chunks_checked
+    )
 }
 
 pub async fn backup_stats(api_url: &str, contract_id: &str) -> Result<()> {
     let client = reqwest::Client::new();
-    let stats: serde_json::Value = client
-        .get(format!("{}/api/contracts/{}/backups/stats", api_url, contract_id))
-        .send()
-        .await?
-        .json()
-        .await?;
+    let stats: serde_json::Value = send(
+        client.get(format!(
+            "{}/api/contracts/{}/backups/stats",
+            api_url, contract_id
+        )),
+        "fetch backup stats",
+    )
+    .await?;
 
     println!("📊 Backup Statistics");
     println!("═══════════════════════════════════════════════════════");
     println!("Total backups: {}", stats["total_backups"]);
     println!("Verified: {}", stats["verified_backups"]);
-    println!("Total size: {} bytes", stats["total_size_bytes"]);
+    println!("Stored size: {} bytes", stats["total_size_bytes"]);
+    println!("Logical size: {} bytes", stats["logical_size_bytes"]);
+    if let Some(ratio) = stats["dedup_ratio"].as_f64() {
+        println!("Deduplication: {:.1}x", ratio);
+    }
     if let Some(latest) = stats["latest_backup"].as_str() {
         println!("Latest backup: {}", latest);
     }
     Ok(())
 }
+
+pub async fn replication_status(api_url: &str, contract_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let status: BackupReplicationStatus = send(
+        client.get(format!(
+            "{}/api/contracts/{}/backups/replication",
+            api_url, contract_id
+        )),
+        "fetch replication status",
+    )
+    .await?;
+
+    println!("🌍 Backup Replication");
+    println!("═══════════════════════════════════════════════════════");
+    for region in &status.regions {
+        let lag = region
+            .last_lag_seconds
+            .map(|s| format!("{}s", s))
+            .unwrap_or_else(|| "-".into());
+        println!(
+            "{:<16} {:<9} {} done, {} pending, {} failed, {} refused, last lag {}",
+            region.region,
+            region.status,
+            region.completed,
+            region.pending,
+            region.failed,
+            region.refused,
+            lag
+        );
+        if region.current_lag_seconds > 0 {
+            println!(
+                "{:<16} oldest pending copy is {}s behind",
+                "", region.current_lag_seconds
+            );
+        }
+    }
+
+    let rerouted: Vec<_> = status
+        .replicas
+        .iter()
+        .filter(|r| r.status == "refused" || r.region != r.requested_region)
+        .collect();
+    if !rerouted.is_empty() {
+        println!();
+        println!("Residency decisions:");
+        for replica in rerouted {
+            println!(
+                "  {} {}: {}",
+                replica.backup_id,
+                replica.requested_region,
+                replica.reason.as_deref().unwrap_or(&replica.status)
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use anyhow::{bail, Context, Result};
+use colored::{ColoredString, Colorize};
+use serde::de::DeserializeOwned;
+use shared::models::{
+    AdvanceCanaryRequest, CanaryActionRequest, CanaryCriterionResult, CanaryDecisionKind,
+    CanaryDetail, CanaryEvaluation, CanaryMetric, CanaryRelease, CanaryStatus, CanaryVariant,
+    CreateCanaryRequest, RecordCanaryMetricRequest,
+};
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response
+        .json()
+        .await
+        .context("Invalid response from registry")
+}
+
+/// Parse `--stages 1,10,50,100` into the four stage percentages.
+pub fn parse_stages(value: &str) -> Result<Vec<i32>> {
+    let stages = value
+        .split(',')
+        .map(|s| {
+            s.trim()
+                .parse::<i32>()
+                .with_context(|| format!("Invalid stage percentage: {}", s))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if stages.len() != 4 {
+        bail!(
+            "Exactly four stage percentages are required, got {}",
+            stages.len()
+        );
+    }
+    if stages.windows(2).any(|w| w[0] >= w[1]) || stages[0] < 1 || stages[3] > 100 {
+        bail!("Stage percentages must increase strictly within 1-100");
+    }
+    Ok(stages)
+}
+
+pub fn parse_variant(value: &str) -> Result<CanaryVariant> {
+    match value.to_lowercase().as_str() {
+        "canary" => Ok(CanaryVariant::Canary),
+        "stable" => Ok(CanaryVariant::Stable),
+        _ => bail!("Invalid variant: {}. Must be canary or stable", value),
+    }
+}
+
+fn status_label(status: CanaryStatus) -> ColoredString {
+    match status {
+        CanaryStatus::Pending => "pending".normal(),
+        CanaryStatus::Active => "active".green(),
+        CanaryStatus::Paused => "paused".yellow(),
+        CanaryStatus::Completed => "completed".cyan(),
+        CanaryStatus::RolledBack => "rolled back".red().bold(),
+        CanaryStatus::Failed => "failed".red(),
+    }
+}
+
+fn decision_label(kind: CanaryDecisionKind) -> ColoredString {
+    match kind {
+        CanaryDecisionKind::Hold => "hold".normal(),
+        CanaryDecisionKind::Advance | CanaryDecisionKind::ManualAdvance => "advance".green(),
+        CanaryDecisionKind::Promote => "promote".cyan().bold(),
+        CanaryDecisionKind::Rollback | CanaryDecisionKind::ManualRollback => {
+            "rollback".red().bold()
+        }
+        CanaryDecisionKind::Pause | CanaryDecisionKind::Resume => "operator".yellow(),
+    }
+}
+
+/// One line per criterion, e.g. `error_rate  1.20 / 5.00  ✓`.
+pub fn criterion_line(c: &CanaryCriterionResult) -> String {
+    let observed = c
+        .observed
+        .map(|v| format!("{:.2}", v))
+        .unwrap_or_else(|| "n/a".to_string());
+    format!(
+        "{:<22} {:>10} / {:<10.2} {}",
+        c.name,
+        observed,
+        c.threshold,
+        if c.passed { "✓" } else { "✗" }
+    )
+}
+
+fn print_detail(detail: &CanaryDetail) {
+    let c = &detail.canary;
+    println!("\n{}", "Canary Release".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!("  {}: {}", "ID".bold(), c.id);
+    println!("  {}: {}", "Contract".bold(), c.contract_id);
+    println!("  {}: {}", "Status".bold(), status_label(c.status));
+    println!(
+        "  {}: {} at {}% (since {})",
+        "Stage".bold(),
+        c.current_stage,
+        c.current_percentage,
+        detail.stage_started_at.format("%Y-%m-%d %H:%M")
+    );
+    println!(
+        "  {}: {} requests, {} errors",
+        "Traffic".bold(),
+        c.total_requests,
+        c.error_count
+    );
+    let k = &detail.criteria;
+    println!(
+        "  {}: error rate ≤ {}%, latency +{}%, {} event anomalies, {} min / {} requests per stage{}",
+        "Criteria".bold(),
+        k.error_rate_threshold,
+        k.max_latency_increase_pct,
+        k.max_event_anomalies,
+        k.min_stage_minutes,
+        k.min_stage_requests,
+        if k.auto_advance { "" } else { ", manual advance" }
+    );
+
+    if !detail.decisions.is_empty() {
+        println!("\n  {}", "Decisions".bold());
+        for d in &detail.decisions {
+            println!(
+                "    {} {:<9} {:<8} {:>3}%  {} {}",
+                d.created_at
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .bright_black(),
+                decision_label(d.kind),
+                d.stage.to_string(),
+                d.percentage,
+                d.reason,
+                format!("({})", d.actor).bright_black()
+            );
+        }
+    }
+    println!();
+}
+
+pub async fn create(api_url: &str, request: CreateCanaryRequest) -> Result<()> {
+    let client = reqwest::Client::new();
+    let canary: CanaryRelease = send(
+        client
+            .post(format!("{}/api/canaries", api_url))
+            .json(&request),
+        "create canary release",
+    )
+    .await?;
+
+    println!(
+        "\n{} Canary {} started at {}% of traffic",
+        "✓".green(),
+        canary.id,
+        canary.current_percentage
+    );
+    println!(
+        "  Record metrics with `canary record-metric {} --requests N --errors N`\n",
+        canary.id
+    );
+    Ok(())
+}
+
+pub async fn status(api_url: &str, canary_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: CanaryDetail = send(
+        client.get(format!("{}/api/canaries/{}", api_url, canary_id)),
+        "fetch canary release",
+    )
+    .await?;
+    print_detail(&detail);
+    Ok(())
+}
+
+pub async fn list(api_url: &str, contract_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let canaries: Vec<CanaryRelease> = send(
+        client.get(format!(
+            "{}/api/contracts/{}/canaries",
+            api_url, contract_id
+        )),
+        "list canary releases",
+    )
+    .await?;
+
+    println!("\n{}", "Canary Releases".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if canaries.is_empty() {
+        println!("  No canary releases for this contract.\n");
+        return Ok(());
+    }
+    for c in &canaries {
+        println!(
+            "  {}  {:<12} {:<8} {:>3}%  {}",
+            c.id,
+            status_label(c.status),
+            c.current_stage.to_string(),
+            c.current_percentage,
+            c.started_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .bright_black()
+        );
+    }
+    println!();
+    Ok(())
+}
+
+pub async fn record_metric(
+    api_url: &str,
+    canary_id: &str,
+    request: RecordCanaryMetricRequest,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let metric: CanaryMetric = send(
+        client
+            .post(format!("{}/api/canaries/{}/metrics", api_url, canary_id))
+            .json(&request),
+        "record canary metric",
+    )
+    .await?;
+    println!(
+        "{} Recorded {} requests ({} errors) at {}",
+        "✓".green(),
+        metric.requests,
+        metric.errors,
+        metric.timestamp.format("%Y-%m-%d %H:%M:%S")
+    );
+    Ok(())
+}
+
+pub async fn evaluate(api_url: &str, canary_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let evaluation: CanaryEvaluation = send(
+        client.post(format!("{}/api/canaries/{}/evaluate", api_url, canary_id)),
+        "evaluate canary release",
+    )
+    .await?;
+
+    let verdict = match evaluation.verdict.as_str() {
+        "advance" => evaluation.verdict.green().bold(),
+        "rollback" => evaluation.verdict.red().bold(),
+        _ => evaluation.verdict.yellow(),
+    };
+    println!("\n  {}: {}", "Verdict".bold(), verdict);
+    if let Some(reason) = &evaluation.reason {
+        println!("  {}: {}", "Reason".bold(), reason);
+    }
+    println!();
+    for c in &evaluation.criteria {
+        println!("    {}", criterion_line(c));
+    }
+    println!(
+        "\n  Canary is now {} at {} ({}%)\n",
+        status_label(evaluation.canary.status),
+        evaluation.canary.current_stage,
+        evaluation.canary.current_percentage
+    );
+    Ok(())
+}
+
+pub async fn advance(
+    api_url: &str,
+    canary_id: &str,
+    target_percentage: Option<i32>,
+    actor: &str,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let canary: CanaryRelease = send(
+        client
+            .post(format!("{}/api/canaries/{}/advance", api_url, canary_id))
+            .json(&AdvanceCanaryRequest {
+                canary_id: canary_id.to_string(),
+                target_percentage,
+                advanced_by: Some(actor.to_string()),
+            }),
+        "advance canary release",
+    )
+    .await?;
+    println!(
+        "{} Canary is {} at {} ({}%)",
+        "✓".green(),
+        status_label(canary.status),
+        canary.current_stage,
+        canary.current_percentage
+    );
+    Ok(())
+}
+
+/// Run an operator action: rollback, pause or resume.
+pub async fn action(
+    api_url: &str,
+    canary_id: &str,
+    action: &str,
+    reason: Option<String>,
+    actor: &str,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let canary: CanaryRelease = send(
+        client
+            .post(format!("{}/api/canaries/{}/{}", api_url, canary_id, action))
+            .json(&CanaryActionRequest {
+                actor: Some(actor.to_string()),
+                reason,
+            }),
+        &format!("{} canary release", action),
+    )
+    .await?;
+    println!(
+        "{} Canary is now {} at {}%",
+        "✓".green(),
+        status_label(canary.status),
+        canary.current_percentage
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_must_be_four_increasing_percentages() {
+        assert_eq!(parse_stages("1, 10,50,100").unwrap(), vec![1, 10, 50, 100]);
+        assert!(parse_stages("1,10,100").is_err());
+        assert!(parse_stages("5,5,50,100").is_err());
+        assert!(parse_stages("0,10,50,100").is_err());
+    }
+
+    #[test]
+    fn criterion_line_marks_missing_observations() {
+        let line = criterion_line(&CanaryCriterionResult {
+            name: "latency_increase_pct".into(),
+            observed: None,
+            threshold: 20.0,
+            passed: true,
+        });
+        assert!(line.contains("n/a"));
+        assert!(line.ends_with('✓'));
+    }
+}
@@ -1,78 +1,149 @@
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
-use std::fmt;
+use colored::*;
+use serde_json::json;
 use std::fs;
-use std::path::PathBuf;
-use std::str::FromStr;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Network {
-    Mainnet,
-    Testnet,
-    Futurenet,
+
+pub use crate::config::Network;
+
 use std::path::Path;
 
 use crate::patch::{PatchManager, Severity};
 use crate::profiler;
-use crate::sla::SlaManager;
 use crate::test_framework;
 
+/// Filters and paging for `search`
+pub struct SearchOptions<'a> {
+    pub verified_only: bool,
+    pub category: Option<&'a str>,
+    pub maturity: Option<&'a str>,
+    pub license: Option<&'a str>,
+    /// Comma-separated; every tag must match
+    pub tags: Option<&'a str>,
+    pub limit: usize,
+}
+
+/// Render `<mark>`-delimited search highlights in bold yellow
+fn render_highlight(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<mark>") {
+        out.push_str(&rest[..start]);
+        let marked = &rest[start + "<mark>".len()..];
+        match marked.find("</mark>") {
+            Some(end) => {
+                out.push_str(&marked[..end].bold().yellow().to_string());
+                rest = &marked[end + "</mark>".len()..];
+            }
+            None => rest = marked,
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 pub async fn search(
     api_url: &str,
     query: &str,
     network: Network,
-    verified_only: bool,
-	 json: bool,
+    options: SearchOptions<'_>,
+    json: bool,
 ) -> Result<()> {
     let client = reqwest::Client::new();
-    let mut url = format!(
-        "{}/api/contracts?query={}&network={}",
-        api_url, query, network
-    );
-
-    if verified_only {
-        url.push_str("&verified_only=true");
+    let mut params: Vec<(&str, String)> = vec![
+        ("q", query.to_string()),
+        ("network", network.to_string()),
+        ("limit", options.limit.to_string()),
+    ];
+    if options.verified_only {
+        params.push(("verified", "true".to_string()));
+    }
+    for (key, value) in [
+        ("category", options.category),
+        ("maturity", options.maturity),
+        ("license", options.license),
+        ("tags", options.tags),
+    ] {
+        if let Some(value) = value {
+            params.push((key, value.to_string()));
+        }
     }
 
     let response = client
-        .get(&url)
+        .get(format!("{}/api/search", api_url))
+        .query(&params)
         .send()
         .await
         .context("Failed to search contracts")?;
 
+    if !response.status().is_success() {
+        let data: serde_json::Value = response.json().await.unwrap_or_default();
+        anyhow::bail!(
+            "Search failed: {}",
+            data["message"].as_str().unwrap_or("unknown error")
+        );
+    }
+
     let data: serde_json::Value = response.json().await?;
-    let items = data["items"].as_array().context("Invalid response")?;
+    let hits = data["hits"].as_array().context("Invalid response")?;
+    let empty = Vec::new();
+    let facets = data["facets"].as_array().unwrap_or(&empty);
 
-	 if json {
-        let contracts: Vec<serde_json::Value> = items
+    if json {
+        let contracts: Vec<serde_json::Value> = hits
             .iter()
             .map(|c| serde_json::json!({
                 "id":          c["contract_id"].as_str().unwrap_or(""),
                 "name":        c["name"].as_str().unwrap_or("Unknown"),
                 "is_verified": c["is_verified"].as_bool().unwrap_or(false),
                 "network":     c["network"].as_str().unwrap_or(""),
+                "score":       c["score"].as_f64().unwrap_or(0.0),
+                "matched_functions": c["matched_functions"],
             }))
             .collect();
-        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "contracts": contracts }))?);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "contracts": contracts,
+                "total": data["total"],
+                "corrected_query": data["corrected_query"],
+                "facets": facets,
+            }))?
+        );
         return Ok(());
     }
 
     println!("\n{}", "Search Results:".bold().cyan());
     println!("{}", "=".repeat(80).cyan());
 
-    if items.is_empty() {
+    if let Some(corrected) = data["corrected_query"].as_str() {
+        println!(
+            "No matches for {}; showing results for {}",
+            query.bold(),
+            corrected.bold().yellow()
+        );
+    }
+
+    if hits.is_empty() {
         println!("{}", "No contracts found.".yellow());
         return Ok(());
     }
 
-    for contract in items {
-        let name = contract["name"].as_str().unwrap_or("Unknown");
+    for contract in hits {
+        let name = contract["name_highlight"]
+            .as_str()
+            .or(contract["name"].as_str())
+            .unwrap_or("Unknown");
         let contract_id = contract["contract_id"].as_str().unwrap_or("");
         let is_verified = contract["is_verified"].as_bool().unwrap_or(false);
         let network = contract["network"].as_str().unwrap_or("");
+        let score = contract["score"].as_f64().unwrap_or(0.0);
 
-        println!("\n{} {}", "●".green(), name.bold());
+        println!(
+            "\n{} {} {}",
+            "●".green(),
+            render_highlight(name).bold(),
+            format!("({:.3})", score).bright_black()
+        );
         println!("  ID: {}", contract_id.bright_black());
         println!(
             "  Status: {} | Network: {}",
@@ -84,29 +155,60 @@ pub async fn search(
             network.bright_blue()
         );
 
-        if let Some(desc) = contract["description"].as_str() {
-            println!("  {}", desc.bright_black());
+        if let Some(desc) = contract["description_highlight"]
+            .as_str()
+            .or(contract["description"].as_str())
+        {
+            println!("  {}", render_highlight(desc));
+        }
+
+        let functions: Vec<&str> = contract["matched_functions"]
+            .as_array()
+            .map(|f| f.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        if !functions.is_empty() {
+            println!("  Functions: {}", functions.join(", ").yellow());
         }
     }
 
     println!("\n{}", "=".repeat(80).cyan());
-    println!("Found {} contract(s)\n", items.len());
-
-    Ok(())
-}
+    println!(
+        "Showing {} of {} contract(s)",
+        hits.len(),
+        data["total"].as_i64().unwrap_or(hits.len() as i64)
+    );
 
-impl fmt::Display for Network {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Network::Mainnet => write!(f, "mainnet"),
-            Network::Testnet => write!(f, "testnet"),
-            Network::Futurenet => write!(f, "futurenet"),
+    let facet_lines: Vec<String> = facets
+        .iter()
+        .filter_map(|facet| {
+            let values: Vec<String> = facet["values"]
+                .as_array()?
+                .iter()
+                .map(|v| {
+                    format!(
+                        "{} ({})",
+                        v["value"].as_str().unwrap_or("?"),
+                        v["count"].as_i64().unwrap_or(0)
+                    )
+                })
+                .collect();
+            (!values.is_empty()).then(|| {
+                let name = format!("{:<10}", facet["name"].as_str().unwrap_or("?"));
+                format!("  {} {}", name.bold(), values.join(" · "))
+            })
+        })
+        .collect();
+    if !facet_lines.is_empty() {
+        println!("\n{}", "Refine by:".bold());
+        for line in facet_lines {
+            println!("{}", line);
         }
     }
+    println!();
+
+    Ok(())
 }
 
-impl FromStr for Network {
-    type Err = anyhow::Error;
 fn resolve_smart_routing(current_network: Network) -> String {
     if current_network.to_string() == "auto" {
         "mainnet".to_string() 
@@ -115,6 +217,72 @@ fn resolve_smart_routing(current_network: Network) -> String {
     }
 }
 
+pub async fn info(api_url: &str, contract_id: &str, network: Network) -> Result<()> {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/api/contracts/{}?network={}",
+        api_url,
+        contract_id,
+        resolve_smart_routing(network)
+    );
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .context("Failed to fetch contract info")?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        anyhow::bail!("Contract not found: {}", contract_id);
+    }
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Failed to fetch contract: {}", error_text);
+    }
+
+    let contract: serde_json::Value = response.json().await?;
+
+    println!("\n{}", "Contract Information:".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!(
+        "\n{}: {}",
+        "Name".bold(),
+        contract["name"].as_str().unwrap_or("Unknown")
+    );
+    println!(
+        "{}: {}",
+        "ID".bold(),
+        contract["contract_id"].as_str().unwrap_or(contract_id).bright_black()
+    );
+    println!(
+        "{}: {}",
+        "Network".bold(),
+        contract["network"].as_str().unwrap_or("").bright_blue()
+    );
+    if let Some(description) = contract["description"].as_str() {
+        println!("{}: {}", "Description".bold(), description);
+    }
+    if let Some(category) = contract["category"].as_str() {
+        println!("{}: {}", "Category".bold(), category);
+    }
+    if let Some(tags) = contract["tags"].as_array() {
+        let tags: Vec<&str> = tags.iter().filter_map(|t| t.as_str()).collect();
+        if !tags.is_empty() {
+            println!("{}: {}", "Tags".bold(), tags.join(", "));
+        }
+    }
+    let verified = contract["is_verified"].as_bool().unwrap_or(false);
+    println!(
+        "{}: {}",
+        "Verified".bold(),
+        if verified { "yes".green() } else { "no".yellow() }
+    );
+    println!();
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn publish(
     api_url: &str,
     contract_id: &str,
@@ -353,17 +521,7 @@ pub async fn migrate(
                 shared::models::MigrationStatus::Success,
                 "Simulation: Migration executed successfully via soroban CLI (mocked).".to_string(),
             )
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "mainnet" => Ok(Network::Mainnet),
-            "testnet" => Ok(Network::Testnet),
-            "futurenet" => Ok(Network::Futurenet),
-            _ => anyhow::bail!(
-                "Invalid network: {}. Allowed values: mainnet, testnet, futurenet",
-                s
-            ),
         }
-    }
     };
 
     // 5. Update Status
@@ -390,20 +548,29 @@ pub async fn migrate(
             println!("{}", "Status: SUCCESS".green().bold());
         }
     }
+
+    Ok(())
 }
 
-impl FromStr for Network {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "mainnet" => Ok(Network::Mainnet),
-            "testnet" => Ok(Network::Testnet),
-            "futurenet" => Ok(Network::Futurenet),
-            _ => anyhow::bail!(
-                "Invalid network: {}. Allowed values: mainnet, testnet, futurenet",
-                s
-            ),
+pub async fn export(
+    api_url: &str,
+    contract_id: &str,
+    output: &str,
+    contract_dir: &str,
+) -> Result<()> {
+    println!("\n{}", "Exporting contract...".bold().cyan());
+
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/contracts/{}", api_url, contract_id);
+    let response = client.get(&url).send().await;
+
+    let (name, network) = match response {
+        Ok(resp) if resp.status().is_success() => {
+            let data: serde_json::Value = resp.json().await?;
+            (
+                data["name"].as_str().unwrap_or(contract_id).to_string(),
+                data["network"].as_str().unwrap_or("unknown").to_string(),
+            )
         }
         _ => (contract_id.to_string(), "unknown".to_string()),
     };
@@ -432,7 +599,7 @@ impl FromStr for Network {
 }
 
 pub async fn import(
-    api_url: &str,
+    _api_url: &str,
     archive: &str,
     network: Network,
     output_dir: &str,
@@ -483,6 +650,36 @@ pub async fn import(
     Ok(())
 }
 
+/// Write a Markdown reference of the functions a contract WASM exports,
+/// with parameter names taken from its embedded contract spec.
+pub fn doc(contract_path: &str, output: &str) -> Result<()> {
+    let wasm = fs::read(contract_path)
+        .with_context(|| format!("Failed to read WASM file at {}", contract_path))?;
+    let functions = crate::sandbox::exported_functions(&wasm)?;
+
+    let name = Path::new(contract_path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("contract");
+    let mut markdown = format!("# {}\n\n## Functions\n", name);
+    for function in &functions {
+        let params = crate::sandbox::function_params(&wasm, function)?.unwrap_or_default();
+        markdown.push_str(&format!("\n### `{}({})`\n", function, params.join(", ")));
+    }
+
+    fs::create_dir_all(output)
+        .with_context(|| format!("Failed to create output directory {}", output))?;
+    let out_path = Path::new(output).join(format!("{}.md", name));
+    fs::write(&out_path, markdown)
+        .with_context(|| format!("Failed to write {}", out_path.display()))?;
+
+    println!("{}", "✓ Documentation generated!".green().bold());
+    println!("  {}: {}", "Functions".bold(), functions.len());
+    println!("  {}: {}\n", "Output".bold(), out_path.display());
+
+    Ok(())
+}
+
 fn severity_colored(sev: &Severity) -> colored::ColoredString {
     match sev {
         Severity::Critical => "CRITICAL".red().bold(),
@@ -653,25 +850,53 @@ pub async fn patch_apply(api_url: &str, contract_id: &str, patch_id: &str) -> Re
     Ok(())
 }
 
-#[derive(Debug, Deserialize, Default)]
-struct ConfigFile {
-    network: Option<String>,
-}
 
-pub fn resolve_network(cli_flag: Option<String>) -> Result<Network> {
-    // 1. CLI Flag
-    if let Some(net_str) = cli_flag {
-        return net_str.parse::<Network>();
+pub async fn profile(
+    contract_path: &str,
+    method: Option<&str>,
+    output: Option<&str>,
+    flamegraph: Option<&str>,
+    compare: Option<&str>,
+    show_recommendations: bool,
+) -> Result<()> {
+    println!("\n{}", "Profiling contract...".bold().cyan());
+
+    let path = Path::new(contract_path);
+    anyhow::ensure!(path.exists(), "contract file not found: {}", contract_path);
+
+    let mut prof = profiler::Profiler::new();
+    profiler::simulate_execution(path, method, &mut prof)?;
+    let profile_data = prof.finish(contract_path.to_string(), method.map(str::to_string));
+
+    println!("\n{}", "Top functions by total time:".bold());
+    let mut ranked: Vec<_> = profile_data.functions.values().collect();
+    ranked.sort_by_key(|f| std::cmp::Reverse(f.total_time));
+    for f in ranked.iter().take(10) {
+        println!(
+            "  {:<30} {:>6} calls  {:.3}ms",
+            f.name,
+            f.call_count,
+            f.total_time.as_secs_f64() * 1000.0
+        );
+    }
+
+    if let Some(output_path) = output {
+        let json = serde_json::to_string_pretty(&profile_data)?;
+        fs::write(output_path, json)
+            .with_context(|| format!("Failed to write profile to {}", output_path))?;
+        println!("\n{} Profile written to {}", "✓".green(), output_path);
     }
 
-    // 2. Config File
-    if let Some(config_path) = config_file_path() {
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
+    if let Some(flame_path) = flamegraph {
+        profiler::generate_flame_graph(&profile_data, Path::new(flame_path))?;
+        println!("{} Flame graph written to {}", "✓".green(), flame_path);
+    }
 
-            let config: ConfigFile =
-                toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+    if let Some(baseline_path) = compare {
+        let content = fs::read_to_string(baseline_path)
+            .with_context(|| format!("Failed to read baseline profile {}", baseline_path))?;
+        let baseline: profiler::ProfileData =
+            serde_json::from_str(&content).context("Failed to parse baseline profile")?;
 
         let comparisons = profiler::compare_profiles(&baseline, &profile_data);
 
@@ -729,7 +954,7 @@ pub async fn deps_list(api_url: &str, contract_id: &str) -> Result<()> {
         return Ok(());
     }
 
-    fn print_tree(nodes: &[serde_json::Value], prefix: &str, is_last: bool) {
+    fn print_tree(nodes: &[serde_json::Value], prefix: &str, _is_last: bool) {
         for (i, node) in nodes.iter().enumerate() {
             let name = node["name"].as_str().unwrap_or("Unknown");
             let constraint = node["constraint_to_parent"].as_str().unwrap_or("*");
@@ -752,21 +977,15 @@ pub async fn deps_list(api_url: &str, contract_id: &str) -> Result<()> {
                      let new_prefix = format!("{}{}", prefix, if is_node_last { "    " } else { "│   " });
                      print_tree(children, &new_prefix, true);
                 }
-            if let Some(net_str) = config.network {
-                return net_str.parse::<Network>();
             }
         }
     }
 
-    // 3. Default
-    Ok(Network::Mainnet)
-}
+    print_tree(tree, "", true);
+    println!();
 
-fn config_file_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|mut p| {
-        p.push(".soroban-registry.toml");
-        p
-    })
+    Ok(())
+}
 
 pub async fn run_tests(
     test_file: &str,
@@ -837,6 +1056,10 @@ pub async fn run_tests(
                 step.assertions_passed,
                 step.assertions_passed + step.assertions_failed
             );
+            println!(
+                "     Resources: {} cpu instructions, {} bytes memory",
+                step.cpu_instructions, step.memory_bytes
+            );
         }
 
         if let Some(ref err) = step.error {
@@ -859,7 +1082,7 @@ pub async fn run_tests(
     }
 
     if let Some(junit_path) = junit_output {
-        test_framework::generate_junit_xml(&[result.clone()], Path::new(junit_path))?;
+        test_framework::generate_junit_xml(std::slice::from_ref(&result), Path::new(junit_path))?;
         println!("\n{} JUnit XML report exported to: {}", "✓".green(), junit_path);
     }
 
@@ -880,42 +1103,6 @@ pub async fn run_tests(
     Ok(())
 }
 
-pub fn incident_trigger(contract_id: &str, severity_str: &str) -> Result<()> {
-    use crate::incident::{IncidentManager, IncidentSeverity};
-
-    let severity = severity_str.parse::<IncidentSeverity>()?;
-    let mut mgr = IncidentManager::default();
-    let id = mgr.trigger(contract_id.to_string(), severity);
-
-    println!("\n{}", "Incident Triggered".bold().cyan());
-    println!("{}", "=".repeat(80).cyan());
-    println!("  {}: {}", "Incident ID".bold(), id);
-    println!("  {}: {}", "Contract".bold(), contract_id.bright_black());
-    println!(
-        "  {}: {}",
-        "Severity".bold(),
-        match severity {
-            IncidentSeverity::Critical => "CRITICAL".red().bold(),
-            IncidentSeverity::High => "HIGH".yellow().bold(),
-            IncidentSeverity::Medium => "MEDIUM".cyan(),
-            IncidentSeverity::Low => "LOW".normal(),
-        }
-    );
-    println!("  {}: Detected", "State".bold());
-
-    if mgr.is_halted(contract_id) {
-        println!(
-            "\n  {} {}",
-            "⚡ CIRCUIT BREAKER ENGAGED —".red().bold(),
-            format!("contract {} is now halted", contract_id).red()
-        );
-    }
-
-    println!(
-        "\n  {} To advance state:\n    soroban-registry incident update {} --state responding\n",
-        "→".bright_black(),
-        id
-    );
 pub async fn config_get(api_url: &str, contract_id: &str, environment: &str) -> Result<()> {
     let client = reqwest::Client::new();
     let url = format!("{}/api/contracts/{}/config?environment={}", api_url, contract_id, environment);
@@ -1049,33 +1236,11 @@ pub async fn config_rollback(
     Ok(())
 }
 
-pub fn incident_update(incident_id_str: &str, state_str: &str) -> Result<()> {
-    use crate::incident::IncidentState;
-    use uuid::Uuid;
-
-    let id = incident_id_str
-        .parse::<Uuid>()
-        .map_err(|_| anyhow::anyhow!("invalid incident ID: {}", incident_id_str))?;
-    let new_state = state_str.parse::<IncidentState>()?;
-
-    println!("\n{}", "Incident Updated".bold().cyan());
-    println!("{}", "=".repeat(80).cyan());
-    println!("  {}: {}", "Incident ID".bold(), id);
-    println!("  {}: {}", "New State".bold(), new_state.to_string().green().bold());
-
-    if matches!(new_state, IncidentState::Recovered | IncidentState::PostReview) {
-        println!(
-            "\n  {} {}",
-            "✓".green(),
-            "Circuit breaker cleared — registry interactions for this contract resumed.".green()
-        );
-    }
-
-    println!();
 pub async fn scan_deps(
     api_url: &str,
     contract_id: &str,
     dependencies: &str,
+    cargo_lock: Option<&str>,
     fail_on_high: bool,
 ) -> Result<()> {
     println!("\n{}", "Scanning Dependencies...".bold().cyan());
@@ -1098,8 +1263,17 @@ pub async fn scan_deps(
         }
     }
 
+    // A lockfile gives the full transitive graph; the list is ignored
+    let lock_contents = match cargo_lock {
+        Some(path) => Some(
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?,
+        ),
+        None => None,
+    };
+
     let payload = json!({
         "dependencies": deps_list,
+        "cargo_lock": lock_contents,
     });
 
     let response = client
@@ -1139,6 +1313,13 @@ pub async fn scan_deps(
 
         println!("  {} {}@{} - {}", severity_colored(&sev_enum), package, version, cve_id.bold());
         println!("    {} Recommended patch: {}", "↳".bright_black(), recommended.green());
+        let path: Vec<&str> = finding["dependency_path"]
+            .as_array()
+            .map(|p| p.iter().filter_map(|s| s.as_str()).collect())
+            .unwrap_or_default();
+        if path.len() > 1 {
+            println!("    {} Via: {}", "↳".bright_black(), path.join(" > ").bright_black());
+        }
     }
 
     println!("\n{}", "=".repeat(80).red());
@@ -1149,25 +1330,8 @@ pub async fn scan_deps(
     }
 
     Ok(())
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
-
-    #[test]
-    fn test_network_parsing() {
-        assert_eq!("mainnet".parse::<Network>().unwrap(), Network::Mainnet);
-        assert_eq!("testnet".parse::<Network>().unwrap(), Network::Testnet);
-        assert_eq!("futurenet".parse::<Network>().unwrap(), Network::Futurenet);
-        assert_eq!("Mainnet".parse::<Network>().unwrap(), Network::Mainnet); // Case insensitive
-        assert!("invalid".parse::<Network>().is_err());
-    }
-
-    // Note: Integration tests involving file system would require mocking or temporary files.
-    // Given the constraints and the environment, we focus on unit tests for parsing here.
-    // `resolve_network` with file interaction is harder to test in isolation without dependency injection or mocking `dirs` / `fs`.
 }
+
 /// Validate a contract function call for type safety
 pub async fn validate_call(
     api_url: &str,
@@ -1411,3 +1575,21 @@ pub async fn list_functions(api_url: &str, contract_id: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_parsing() {
+        assert_eq!("mainnet".parse::<Network>().unwrap(), Network::Mainnet);
+        assert_eq!("testnet".parse::<Network>().unwrap(), Network::Testnet);
+        assert_eq!("futurenet".parse::<Network>().unwrap(), Network::Futurenet);
+        assert_eq!("Mainnet".parse::<Network>().unwrap(), Network::Mainnet); // Case insensitive
+        assert!("invalid".parse::<Network>().is_err());
+    }
+
+    // Note: Integration tests involving file system would require mocking or temporary files.
+    // Given the constraints and the environment, we focus on unit tests for parsing here.
+    // `resolve_network` with file interaction is harder to test in isolation without dependency injection or mocking `dirs` / `fs`.
+}
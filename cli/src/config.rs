@@ -37,10 +37,6 @@ impl FromStr for Network {
             "mainnet" => Ok(Network::Mainnet),
             "testnet" => Ok(Network::Testnet),
             "futurenet" => Ok(Network::Futurenet),
-            _ => anyhow::bail!(
-                "Invalid network: {}. Allowed values: mainnet, testnet, futurenet",
-                s
-            ),
             "auto" => Ok(Network::Auto), // Issue #78: Allow "auto" string
             _ => anyhow::bail!("Invalid network: {}. Allowed values: mainnet, testnet, futurenet, auto", s),
         }
@@ -59,40 +55,6 @@ struct DefaultsSection {
     timeout: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
-pub struct RuntimeConfig {
-    pub network: Network,
-    pub api_base: String,
-    pub timeout: u64,
-}
-
-pub fn resolve_runtime_config(
-    cli_network: Option<String>,
-    cli_api_base: Option<String>,
-    cli_timeout: Option<u64>,
-) -> Result<RuntimeConfig> {
-    let config = load_defaults_section()?;
-
-    let network = match cli_network.or(config.network) {
-        Some(value) => value.parse::<Network>()?,
-        None => Network::Testnet,
-    };
-
-    let api_base = cli_api_base
-        .or(config.api_base)
-        .unwrap_or_else(|| DEFAULT_API_BASE.to_string());
-
-    let timeout = cli_timeout
-        .or(config.timeout)
-        .unwrap_or(DEFAULT_TIMEOUT_SECS);
-
-    Ok(RuntimeConfig {
-        network,
-        api_base,
-        timeout,
-    })
-}
-
 pub fn show_config() -> Result<()> {
     let path = config_file_path().context("Could not determine home directory")?;
     let defaults = load_defaults_section()?;
@@ -130,25 +92,59 @@ pub fn edit_config() -> Result<()> {
         anyhow::bail!("Editor exited with non-zero status");
     }
 
-    // 2. Config File
-    if let Some(config_path) = config_file_path() {
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
+    Ok(())
+}
 
-            let config: ConfigFile =
-                toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+/// Resolve the network: CLI flag, then `defaults.network` from the config
+/// file, then mainnet.
+pub fn resolve_network(cli_flag: Option<String>) -> Result<Network> {
+    // 1. CLI Flag
+    if let Some(net_str) = cli_flag {
+        return net_str.parse::<Network>();
+    }
 
-            if let Some(net_str) = config.network {
-                return net_str.parse::<Network>();
-            }
-        }
+    // 2. Config File
+    if let Some(net_str) = load_defaults_section()?.network {
+        return net_str.parse::<Network>();
     }
 
     // 3. Default
     Ok(Network::Mainnet)
 }
 
+fn load_defaults_section() -> Result<DefaultsSection> {
+    match config_file_path() {
+        Some(path) if path.exists() => {
+            Ok(load_config_file(&path)?.defaults.unwrap_or_default())
+        }
+        _ => Ok(DefaultsSection::default()),
+    }
+}
+
+fn load_config_file(path: &Path) -> Result<ConfigFile> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file at {:?}", path))?;
+    toml::from_str(&content).with_context(|| "Failed to parse config file")
+}
+
+fn ensure_config_file_exists(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    fs::write(
+        path,
+        format!(
+            "[defaults]\nnetwork = \"testnet\"\napi_base = \"{}\"\ntimeout = {}\n",
+            DEFAULT_API_BASE, DEFAULT_TIMEOUT_SECS
+        ),
+    )
+    .with_context(|| format!("Failed to write config file at {:?}", path))
+}
+
 fn config_file_path() -> Option<PathBuf> {
     dirs::home_dir().map(|mut p| {
         p.push(".soroban-registry");
@@ -193,8 +189,4 @@ timeout = 55
         assert_eq!(defaults.api_base.as_deref(), Some("http://localhost:9000"));
         assert_eq!(defaults.timeout, Some(55));
     }
-}
-        // Note: Integration tests involving file system would require mocking or temporary files.
-    // Given the constraints and the environment, we focus on unit tests for parsing here.
-    // `resolve_network` with file interaction is harder to test in isolation without dependency injection or mocking `dirs` / `fs`.
 }
@@ -1,46 +1,59 @@
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use shared::models::{
+    CostEstimate, CostEstimateRequest, CostForecast, CostOptimization, ResourceFeeBreakdown,
+    SorobanResources,
+};
 
-#[derive(Debug, Serialize)]
-struct CostEstimateRequest {
-    method_name: String,
-    invocations: Option<i64>,
-    storage_growth_kb: Option<i64>,
-}
-
-#[derive(Debug, Deserialize)]
-struct CostEstimate {
-    method_name: String,
-    gas_cost: i64,
-    storage_cost: i64,
-    bandwidth_cost: i64,
-    total_stroops: i64,
-    total_xlm: f64,
-    invocations: i64,
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response
+        .json()
+        .await
+        .context("Invalid response from registry")
 }
 
-#[derive(Debug, Deserialize)]
-struct CostOptimization {
-    current_cost: i64,
-    optimized_cost: i64,
-    savings_percent: f64,
-    suggestions: Vec<String>,
+/// Read per-invocation resources from a JSON file, e.g. the `resources`
+/// reported by a transaction simulation.
+fn load_resources(path: &str) -> Result<SorobanResources> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    serde_json::from_str(&raw).with_context(|| format!("Invalid resources JSON in {}", path))
 }
 
-#[derive(Debug, Deserialize)]
-struct CostForecast {
-    daily_cost_xlm: f64,
-    monthly_cost_xlm: f64,
-    yearly_cost_xlm: f64,
-    usage_pattern: String,
+fn print_breakdown(fee: &ResourceFeeBreakdown) {
+    println!("Per-Invocation Resource Fee:");
+    println!("  Compute:        {:>12} stroops", fee.compute);
+    println!("  Entry Reads:    {:>12} stroops", fee.read_entries);
+    println!("  Entry Writes:   {:>12} stroops", fee.write_entries);
+    println!("  Bytes Read:     {:>12} stroops", fee.read_bytes);
+    println!("  Bytes Written:  {:>12} stroops", fee.write_bytes);
+    println!("  History:        {:>12} stroops", fee.historical);
+    println!("  Tx Size:        {:>12} stroops", fee.bandwidth);
+    println!("  Events:         {:>12} stroops", fee.events);
+    println!("  ─────────────────────────────────────");
+    println!("  Non-refundable: {:>12} stroops", fee.non_refundable);
+    println!("  Refundable:     {:>12} stroops", fee.refundable);
+    println!();
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn estimate_costs(
     api_url: &str,
     contract_id: &str,
     method: &str,
     invocations: Option<i64>,
     storage_kb: Option<i64>,
+    resources_path: Option<&str>,
     optimize: bool,
     forecast: bool,
 ) -> Result<()> {
@@ -50,16 +63,22 @@ pub async fn estimate_costs(
         method_name: method.to_string(),
         invocations,
         storage_growth_kb: storage_kb,
+        resources: resources_path.map(load_resources).transpose()?,
+        storage_durability: None,
+        rent_ledgers: None,
     };
 
     // Get base estimate
-    let estimate: CostEstimate = client
-        .post(format!("{}/api/contracts/{}/cost-estimate", api_url, contract_id))
-        .json(&request)
-        .send()
-        .await?
-        .json()
-        .await?;
+    let estimate: CostEstimate = send(
+        client
+            .post(format!(
+                "{}/api/contracts/{}/cost-estimate",
+                api_url, contract_id
+            ))
+            .json(&request),
+        "estimate costs",
+    )
+    .await?;
 
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║           CONTRACT COST ESTIMATION                   ║");
@@ -67,11 +86,20 @@ pub async fn estimate_costs(
     println!();
     println!("Method: {}", estimate.method_name);
     println!("Invocations: {}", estimate.invocations);
+    println!(
+        "Resources: {}   Fee schedule: {}",
+        estimate.resource_source.as_deref().unwrap_or("unknown"),
+        estimate.fee_config_source.as_deref().unwrap_or("unknown")
+    );
     println!();
+    if let Some(fee) = &estimate.per_invocation {
+        print_breakdown(fee);
+    }
     println!("Cost Breakdown:");
     println!("  Gas Cost:       {:>12} stroops", estimate.gas_cost);
     println!("  Storage Cost:   {:>12} stroops", estimate.storage_cost);
     println!("  Bandwidth Cost: {:>12} stroops", estimate.bandwidth_cost);
+    println!("  Inclusion Fee:  {:>12} stroops", estimate.inclusion_fee);
     println!("  ─────────────────────────────────────");
     println!("  Total:          {:>12} stroops", estimate.total_stroops);
     println!("  Total:          {:>12.6} XLM", estimate.total_xlm);
@@ -79,13 +107,16 @@ pub async fn estimate_costs(
 
     // Show optimization suggestions
     if optimize {
-        let optimization: CostOptimization = client
-            .post(format!("{}/api/contracts/{}/cost-estimate/optimize", api_url, contract_id))
-            .json(&estimate)
-            .send()
-            .await?
-            .json()
-            .await?;
+        let optimization: CostOptimization = send(
+            client
+                .post(format!(
+                    "{}/api/contracts/{}/cost-estimate/optimize",
+                    api_url, contract_id
+                ))
+                .json(&estimate),
+            "fetch optimization suggestions",
+        )
+        .await?;
 
         println!("╔═══════════════════════════════════════════════════════╗");
         println!("║           OPTIMIZATION SUGGESTIONS                   ║");
@@ -104,13 +135,16 @@ pub async fn estimate_costs(
 
     // Show forecast
     if forecast {
-        let forecast_data: CostForecast = client
-            .post(format!("{}/api/contracts/{}/cost-estimate/forecast", api_url, contract_id))
-            .json(&request)
-            .send()
-            .await?
-            .json()
-            .await?;
+        let forecast_data: CostForecast = send(
+            client
+                .post(format!(
+                    "{}/api/contracts/{}/cost-estimate/forecast",
+                    api_url, contract_id
+                ))
+                .json(&request),
+            "forecast costs",
+        )
+        .await?;
 
         println!("╔═══════════════════════════════════════════════════════╗");
         println!("║           COST FORECAST                              ║");
@@ -27,7 +27,7 @@ pub async fn run(contract_path: &str, _tests: &str, threshold: f64, output: &str
 
     let mut cmd = Command::new("cargo");
     cmd.current_dir(path);
-    cmd.args(&[
+    cmd.args([
         "tarpaulin",
         "--out", "Html",
         "--out", "Json",
@@ -86,13 +86,13 @@ pub async fn run(contract_path: &str, _tests: &str, threshold: f64, output: &str
 
 fn ensure_tarpaulin_installed() -> Result<()> {
     let check = Command::new("cargo")
-        .args(&["tarpaulin", "--version"])
+        .args(["tarpaulin", "--version"])
         .output();
         
     if check.is_err() || !check.unwrap().status.success() {
         println!("{}", "cargo-tarpaulin not found. Installing... (this may take a while)".yellow().bold());
         let install = Command::new("cargo")
-            .args(&["install", "cargo-tarpaulin"])
+            .args(["install", "cargo-tarpaulin"])
             .status()
             .context("Failed to run cargo install cargo-tarpaulin")?;
             
@@ -122,7 +122,7 @@ fn parse_and_print_report(report: &Value) {
         for file in files {
             if let Some(traces) = file.get("traces").and_then(|t| t.as_array()) {
                 for trace in traces {
-                    if let Some(line) = trace.get("line").and_then(|l| l.as_u64()) {
+                    if let Some(_line) = trace.get("line").and_then(|l| l.as_u64()) {
                         coverable_lines += 1;
                         if let Some(stats) = trace.get("stats").and_then(|s| s.as_object()) {
                             if stats.values().any(|v| v.as_u64().unwrap_or(0) > 0) {
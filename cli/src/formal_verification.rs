@@ -2,7 +2,6 @@ use anyhow::{Context, Result};
 use colored::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
 
 #[derive(Debug, Deserialize)]
 pub struct PropertiesConfig {
@@ -32,6 +31,7 @@ pub struct VerificationReport {
 pub struct PropertyResult {
     pub id: String,
     pub description: String,
+    pub severity: String,
     pub status: String, // "Proved", "Violated", "Unknown"
     pub counterexample: Option<String>,
 }
@@ -91,6 +91,7 @@ pub async fn run(
         report.results.push(PropertyResult {
             id: prop.id.clone(),
             description: prop.description.clone(),
+            severity: prop.severity.clone(),
             status,
             counterexample,
         });
@@ -131,7 +132,7 @@ pub async fn run(
                         "property_id": res.id,
                         "description": res.description,
                         "invariant": "N/A",
-                        "severity": "Medium"
+                        "severity": res.severity
                     },
                     "result": {
                         "id": uuid::Uuid::new_v4().to_string(),
@@ -277,7 +277,7 @@ impl Fuzzer {
                 {
                     let case_num = cases_run.fetch_add(1, Ordering::Relaxed);
                     
-                    if case_num % 1000 == 0 && case_num > 0 {
+                    if case_num.is_multiple_of(1000) && case_num > 0 {
                         print!(
                             "\r  {} Test cases run: {} | Crashes: {}    ",
                             "→".bright_black(),
@@ -671,14 +671,14 @@ impl Clone for Fuzzer {
 
 fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
-    let (num, unit) = if s.ends_with('s') {
-        (&s[..s.len() - 1], "s")
-    } else if s.ends_with('m') && !s.ends_with("ms") {
-        (&s[..s.len() - 1], "m")
-    } else if s.ends_with('h') {
-        (&s[..s.len() - 1], "h")
-    } else if s.ends_with("ms") {
-        (&s[..s.len() - 2], "ms")
+    let (num, unit) = if let Some(num) = s.strip_suffix("ms") {
+        (num, "ms")
+    } else if let Some(num) = s.strip_suffix('s') {
+        (num, "s")
+    } else if let Some(num) = s.strip_suffix('m') {
+        (num, "m")
+    } else if let Some(num) = s.strip_suffix('h') {
+        (num, "h")
     } else {
         (s, "s")
     };
@@ -787,10 +787,7 @@ mod tests {
     fn test_generate_value() {
         let mut rng = StdRng::from_entropy();
         
-        let val = match Fuzzer::generate_value_static(&ArgType::Bool, &mut rng) {
-            FuzzValue::Bool(_) => true,
-            _ => false,
-        };
-        assert!(val);
+        let val = Fuzzer::generate_value_static(&ArgType::Bool, &mut rng);
+        assert!(matches!(val, FuzzValue::Bool(_)));
     }
 }
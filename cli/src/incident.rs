@@ -1,200 +1,387 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt;
-use std::str::FromStr;
-
-use anyhow::{bail, Result};
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum IncidentSeverity {
-    Critical,
-    High,
-    Medium,
-    Low,
+// cli/src/incident.rs
+// Incident response commands. Incidents, their timelines and the circuit
+// breaker are kept by the registry API, so every responder sees the same
+// state and the breaker keeps blocking writes after the command exits.
+
+use anyhow::{bail, Context, Result};
+use colored::{ColoredString, Colorize};
+use serde::de::DeserializeOwned;
+use shared::models::{
+    Incident, IncidentDetail, IncidentImpact, IncidentSeverity, IncidentState,
+    RecordIncidentUpdateRequest, SetCircuitBreakerRequest, TriggerIncidentRequest,
+};
+use std::fs;
+
+/// Author recorded on timeline entries when `--author` is not given
+pub fn default_author() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "cli".to_string())
 }
 
-impl FromStr for IncidentSeverity {
-    type Err = anyhow::Error;
+fn severity_label(severity: IncidentSeverity) -> ColoredString {
+    match severity {
+        IncidentSeverity::Critical => "CRITICAL".red().bold(),
+        IncidentSeverity::High => "HIGH".yellow().bold(),
+        IncidentSeverity::Medium => "MEDIUM".cyan(),
+        IncidentSeverity::Low => "LOW".normal(),
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().as_str() {
-            "critical" => Ok(Self::Critical),
-            "high" => Ok(Self::High),
-            "medium" => Ok(Self::Medium),
-            "low" => Ok(Self::Low),
-            _ => bail!("invalid severity: {} (expected critical|high|medium|low)", s),
-        }
+/// Timeline message for an update: a state change without a message gets a
+/// generated one, a bare note needs text.
+pub fn update_message(state: Option<IncidentState>, message: Option<&str>) -> Result<String> {
+    match (state, message.map(str::trim).filter(|m| !m.is_empty())) {
+        (_, Some(message)) => Ok(message.to_string()),
+        (Some(state), None) => Ok(format!("Moved to {}", state)),
+        (None, None) => bail!("Provide --state, --message, or both"),
     }
 }
 
-impl fmt::Display for IncidentSeverity {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Critical => write!(f, "CRITICAL"),
-            Self::High => write!(f, "HIGH"),
-            Self::Medium => write!(f, "MEDIUM"),
-            Self::Low => write!(f, "LOW"),
-        }
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
     }
+    response.json().await.context("Invalid response from registry")
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum IncidentState {
-    Detected,
-    Responding,
-    Contained,
-    Recovered,
-    PostReview,
+fn print_summary(incident: &Incident) {
+    println!("  {}: {}", "Incident ID".bold(), incident.id);
+    println!("  {}: {}", "Title".bold(), incident.title);
+    println!(
+        "  {}: {}",
+        "Contract".bold(),
+        incident.contract_id.to_string().bright_black()
+    );
+    println!(
+        "  {}: {}",
+        "Severity".bold(),
+        severity_label(incident.severity)
+    );
+    println!(
+        "  {}: {}",
+        "State".bold(),
+        incident.state.to_string().green().bold()
+    );
+    if incident.breaker_engaged {
+        println!(
+            "  {}: {}",
+            "Circuit breaker".bold(),
+            "ENGAGED — writes to this contract are blocked".red().bold()
+        );
+    }
 }
 
-impl FromStr for IncidentState {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        match s.to_lowercase().replace('-', "_").as_str() {
-            "detected" => Ok(Self::Detected),
-            "responding" => Ok(Self::Responding),
-            "contained" => Ok(Self::Contained),
-            "recovered" => Ok(Self::Recovered),
-            "post_review" | "postreview" => Ok(Self::PostReview),
-            _ => bail!(
-                "invalid state: {} (expected detected|responding|contained|recovered|post_review)",
-                s
-            ),
+fn print_detail(detail: &IncidentDetail) {
+    print_summary(&detail.incident);
+
+    let dependents: Vec<_> = detail
+        .affected_contracts
+        .iter()
+        .filter(|a| a.impact == IncidentImpact::Dependent)
+        .collect();
+    if !dependents.is_empty() {
+        println!(
+            "\n  {} ({})",
+            "Affected dependents".bold(),
+            dependents.len()
+        );
+        for affected in dependents {
+            println!(
+                "    {} {} {}",
+                "•".yellow(),
+                affected.contract_name,
+                format!("({} hop(s), {})", affected.depth, affected.contract_id).bright_black()
+            );
         }
     }
-}
 
-impl fmt::Display for IncidentState {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Detected => write!(f, "Detected"),
-            Self::Responding => write!(f, "Responding"),
-            Self::Contained => write!(f, "Contained"),
-            Self::Recovered => write!(f, "Recovered"),
-            Self::PostReview => write!(f, "PostReview"),
+    if !detail.timeline.is_empty() {
+        println!("\n  {}", "Timeline".bold());
+        for entry in &detail.timeline {
+            println!(
+                "    {} {} {}",
+                entry
+                    .created_at
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .bright_black(),
+                format!("[{}]", entry.author).cyan(),
+                entry.message
+            );
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Incident {
-    pub id: Uuid,
-    pub contract_id: String,
-    pub severity: IncidentSeverity,
-    pub state: IncidentState,
-    pub triggered_at: DateTime<Utc>,
-}
+pub async fn trigger(
+    api_url: &str,
+    contract_id: &str,
+    network: Option<&str>,
+    severity: &str,
+    title: Option<&str>,
+    description: Option<&str>,
+    author: &str,
+) -> Result<()> {
+    let severity = severity
+        .parse::<IncidentSeverity>()
+        .map_err(anyhow::Error::msg)?;
 
-#[derive(Default)]
-pub struct IncidentManager {
-    incidents: HashMap<Uuid, Incident>,
-    halted: HashSet<String>,
-}
+    let client = reqwest::Client::new();
+    let detail: IncidentDetail = send(
+        client
+            .post(format!("{}/api/incidents", api_url))
+            .json(&TriggerIncidentRequest {
+                contract_id: contract_id.to_string(),
+                network: network.map(str::to_string),
+                severity,
+                title: title.map(str::to_string),
+                description: description.map(str::to_string),
+                triggered_by: author.to_string(),
+            }),
+        "trigger incident",
+    )
+    .await?;
 
-impl IncidentManager {
-    pub fn trigger(&mut self, contract_id: String, severity: IncidentSeverity) -> Uuid {
-        let id = Uuid::new_v4();
-        let incident = Incident {
-            id,
-            contract_id: contract_id.clone(),
-            severity,
-            state: IncidentState::Detected,
-            triggered_at: Utc::now(),
-        };
-        self.incidents.insert(id, incident);
-        if severity == IncidentSeverity::Critical {
-            self.halted.insert(contract_id);
-        }
-        id
+    println!("\n{}", "Incident Triggered".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    print_detail(&detail);
+
+    if detail.incident.breaker_engaged {
+        println!(
+            "\n  {} {}",
+            "⚡ CIRCUIT BREAKER ENGAGED —".red().bold(),
+            format!("contract {} is now halted", contract_id).red()
+        );
     }
 
-    pub fn update_state(&mut self, id: Uuid, new_state: IncidentState) -> Result<()> {
-        let incident = self
-            .incidents
-            .get_mut(&id)
-            .ok_or_else(|| anyhow::anyhow!("incident not found: {}", id))?;
+    println!(
+        "\n  {} To advance state:\n    soroban-registry incident update {} --state responding\n",
+        "→".bright_black(),
+        detail.incident.id
+    );
 
-        if incident.state == new_state {
-            bail!("incident {} is already in state {}", id, new_state);
-        }
+    Ok(())
+}
 
-        incident.state = new_state;
+#[allow(clippy::too_many_arguments)]
+pub async fn update(
+    api_url: &str,
+    incident_id: &str,
+    state: Option<&str>,
+    message: Option<&str>,
+    root_cause: Option<&str>,
+    resolution: Option<&str>,
+    author: &str,
+) -> Result<()> {
+    let state = state
+        .map(|s| s.parse::<IncidentState>().map_err(anyhow::Error::msg))
+        .transpose()?;
+    let message = update_message(state, message)?;
 
-        if matches!(new_state, IncidentState::Recovered | IncidentState::PostReview) {
-            self.halted.remove(&incident.contract_id);
-        }
+    let client = reqwest::Client::new();
+    let detail: IncidentDetail = send(
+        client
+            .post(format!("{}/api/incidents/{}/updates", api_url, incident_id))
+            .json(&RecordIncidentUpdateRequest {
+                state,
+                message,
+                author: author.to_string(),
+                root_cause: root_cause.map(str::to_string),
+                resolution: resolution.map(str::to_string),
+            }),
+        "update incident",
+    )
+    .await?;
 
-        Ok(())
-    }
+    println!("\n{}", "Incident Updated".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    print_summary(&detail.incident);
 
-    pub fn is_halted(&self, contract_id: &str) -> bool {
-        self.halted.contains(contract_id)
+    if !detail.incident.breaker_engaged
+        && matches!(
+            state,
+            Some(IncidentState::Recovered | IncidentState::PostReview)
+        )
+    {
+        println!(
+            "\n  {} {}",
+            "✓".green(),
+            "Circuit breaker cleared — registry interactions for this contract resumed.".green()
+        );
     }
 
-    pub fn get(&self, id: Uuid) -> Option<&Incident> {
-        self.incidents.get(&id)
+    println!();
+    Ok(())
+}
+
+pub async fn show(api_url: &str, incident_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: IncidentDetail = send(
+        client.get(format!("{}/api/incidents/{}", api_url, incident_id)),
+        "fetch incident",
+    )
+    .await?;
+
+    println!("\n{}", "Incident".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    print_detail(&detail);
+    println!();
+    Ok(())
+}
+
+pub async fn list(api_url: &str, active: bool) -> Result<()> {
+    let client = reqwest::Client::new();
+    let incidents: Vec<Incident> = send(
+        client
+            .get(format!("{}/api/incidents", api_url))
+            .query(&[("active", active)]),
+        "list incidents",
+    )
+    .await?;
+
+    println!("\n{}", "Incidents".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if incidents.is_empty() {
+        println!("  No incidents found.\n");
+        return Ok(());
+    }
+    for incident in &incidents {
+        println!(
+            "  {} {:<8} {:<11} {}{}",
+            incident
+                .triggered_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .bright_black(),
+            severity_label(incident.severity),
+            incident.state.to_string(),
+            incident.title,
+            if incident.breaker_engaged {
+                " ⚡".red().to_string()
+            } else {
+                String::new()
+            }
+        );
+        println!("    {}", incident.id.to_string().bright_black());
     }
+    println!();
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub async fn breaker(
+    api_url: &str,
+    incident_id: &str,
+    engage: bool,
+    reason: Option<&str>,
+    author: &str,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: IncidentDetail = send(
+        client
+            .post(format!("{}/api/incidents/{}/breaker", api_url, incident_id))
+            .json(&SetCircuitBreakerRequest {
+                engaged: engage,
+                author: author.to_string(),
+                reason: reason.map(str::to_string),
+            }),
+        "change circuit breaker",
+    )
+    .await?;
 
-    #[test]
-    fn trigger_non_critical_no_halt() {
-        let mut mgr = IncidentManager::default();
-        let id = mgr.trigger("C1".into(), IncidentSeverity::High);
-        assert!(!mgr.is_halted("C1"));
-        assert_eq!(mgr.get(id).unwrap().state, IncidentState::Detected);
+    if detail.incident.breaker_engaged {
+        println!(
+            "\n{} {}\n",
+            "⚡ CIRCUIT BREAKER ENGAGED —".red().bold(),
+            format!("contract {} is now halted", detail.incident.contract_id).red()
+        );
+    } else {
+        println!(
+            "\n{} {}\n",
+            "✓".green(),
+            format!(
+                "Circuit breaker released for contract {}",
+                detail.incident.contract_id
+            )
+            .green()
+        );
     }
+    Ok(())
+}
 
-    #[test]
-    fn trigger_critical_auto_halts() {
-        let mut mgr = IncidentManager::default();
-        mgr.trigger("C2".into(), IncidentSeverity::Critical);
-        assert!(mgr.is_halted("C2"));
+pub async fn postmortem(
+    api_url: &str,
+    incident_id: &str,
+    format: &str,
+    output: Option<&str>,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("{}/api/incidents/{}/postmortem", api_url, incident_id))
+        .query(&[("format", format)])
+        .send()
+        .await
+        .context("Failed to export post-mortem")?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to export post-mortem: {}",
+            response.text().await.unwrap_or_default()
+        );
     }
+    let body = response.text().await?;
 
-    #[test]
-    fn update_state_transitions() {
-        let mut mgr = IncidentManager::default();
-        let id = mgr.trigger("C3".into(), IncidentSeverity::High);
-        mgr.update_state(id, IncidentState::Responding).unwrap();
-        mgr.update_state(id, IncidentState::Contained).unwrap();
-        assert_eq!(mgr.get(id).unwrap().state, IncidentState::Contained);
+    match output {
+        Some(path) => {
+            fs::write(path, &body).with_context(|| format!("Failed to write {}", path))?;
+            println!("{} Post-mortem written to {}", "✓".green(), path);
+        }
+        None => println!("{}", body),
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn recovery_clears_breaker() {
-        let mut mgr = IncidentManager::default();
-        let id = mgr.trigger("C4".into(), IncidentSeverity::Critical);
-        assert!(mgr.is_halted("C4"));
-        mgr.update_state(id, IncidentState::Responding).unwrap();
-        mgr.update_state(id, IncidentState::Contained).unwrap();
-        mgr.update_state(id, IncidentState::Recovered).unwrap();
-        assert!(!mgr.is_halted("C4"));
+    fn state_change_without_message_gets_generated_text() {
+        assert_eq!(
+            update_message(Some(IncidentState::Contained), None).unwrap(),
+            "Moved to Contained"
+        );
+        assert_eq!(
+            update_message(Some(IncidentState::Contained), Some("Paused deposits")).unwrap(),
+            "Paused deposits"
+        );
     }
 
     #[test]
-    fn post_review_clears_breaker() {
-        let mut mgr = IncidentManager::default();
-        let id = mgr.trigger("C5".into(), IncidentSeverity::Critical);
-        mgr.update_state(id, IncidentState::Responding).unwrap();
-        mgr.update_state(id, IncidentState::Contained).unwrap();
-        mgr.update_state(id, IncidentState::PostReview).unwrap();
-        assert!(!mgr.is_halted("C5"));
+    fn note_requires_text() {
+        assert!(update_message(None, Some("  ")).is_err());
+        assert_eq!(
+            update_message(None, Some("Root cause found")).unwrap(),
+            "Root cause found"
+        );
     }
 
     #[test]
-    fn invalid_state_transition_same_state() {
-        let mut mgr = IncidentManager::default();
-        let id = mgr.trigger("C6".into(), IncidentSeverity::Medium);
-        let err = mgr.update_state(id, IncidentState::Detected).unwrap_err();
-        assert!(err.to_string().contains("already in state"));
+    fn states_parse_with_cli_spellings() {
+        assert_eq!(
+            "post-review".parse::<IncidentState>(),
+            Ok(IncidentState::PostReview)
+        );
+        assert!("resolved".parse::<IncidentState>().is_err());
+        assert_eq!(
+            "Critical".parse::<IncidentSeverity>(),
+            Ok(IncidentSeverity::Critical)
+        );
     }
 }
@@ -0,0 +1,597 @@
+// cli/src/lockfile.rs
+// Dependency lockfile (`soroban-registry.lock`) for registry contract dependencies.
+//
+// The project manifest (`soroban-registry.toml`) declares version constraints;
+// the lockfile pins the exact versions, WASM hashes and per-network contract IDs
+// that a project was resolved against, in the same spirit as `Cargo.lock`.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use shared::{DependencyDeclaration, SemVer, VersionConstraint};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST_FILE: &str = "soroban-registry.toml";
+pub const LOCKFILE_FILE: &str = "soroban-registry.lock";
+const LOCKFILE_VERSION: u32 = 1;
+const LOCKFILE_HEADER: &str =
+    "# This file is generated by soroban-registry. It is not intended for manual editing.\n";
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Manifest
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// `soroban-registry.toml` — the project's declared registry dependencies.
+///
+/// ```toml
+/// [dependencies]
+/// token = "^1.2.0"
+/// oracle = "~0.3.1"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectManifest {
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+
+impl ProjectManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Invalid manifest {}", path.display()))
+    }
+
+    pub fn declarations(&self) -> Vec<DependencyDeclaration> {
+        self.dependencies
+            .iter()
+            .map(|(name, constraint)| DependencyDeclaration {
+                name: name.clone(),
+                version_constraint: constraint.clone(),
+            })
+            .collect()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Lockfile
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub constraint: String,
+    pub wasm_hash: String,
+    /// On-chain contract ID keyed by network name (mainnet/testnet/futurenet).
+    #[serde(default)]
+    pub contract_ids: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            packages: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let lock: Lockfile = toml::from_str(raw).context("Invalid lockfile")?;
+        if lock.version != LOCKFILE_VERSION {
+            bail!(
+                "Unsupported lockfile version {} (expected {})",
+                lock.version,
+                LOCKFILE_VERSION
+            );
+        }
+        Ok(lock)
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lockfile {}", path.display()))?;
+        Self::parse(&raw).map(Some)
+    }
+
+    pub fn render(&self) -> Result<String> {
+        let mut sorted = self.clone();
+        sorted.packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(format!("{}{}", LOCKFILE_HEADER, toml::to_string(&sorted)?))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.render()?)
+            .with_context(|| format!("Failed to write lockfile {}", path.display()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+}
+
+/// A single published version of a dependency as reported by the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailableVersion {
+    pub version: SemVer,
+    pub wasm_hash: String,
+    /// Deployments that published this version, keyed by network name.
+    pub contract_ids: BTreeMap<String, String>,
+}
+
+/// Pick the highest available version that satisfies `constraint`.
+pub fn resolve_version<'a>(
+    constraint: &VersionConstraint,
+    available: &'a [AvailableVersion],
+) -> Option<&'a AvailableVersion> {
+    available
+        .iter()
+        .filter(|v| constraint.matches(&v.version))
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// Returns the locked entry if it can be reused unchanged for `decl`, i.e. the
+/// constraint is identical and the pinned version still satisfies it.
+pub fn reusable_lock<'a>(
+    existing: Option<&'a Lockfile>,
+    decl: &DependencyDeclaration,
+) -> Option<&'a LockedPackage> {
+    let locked = existing?.get(&decl.name)?;
+    if locked.constraint != decl.version_constraint {
+        return None;
+    }
+    let constraint = VersionConstraint::parse(&decl.version_constraint)?;
+    let version = SemVer::parse(&locked.version)?;
+    constraint.matches(&version).then_some(locked)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockChange {
+    Added { name: String, version: String },
+    Removed { name: String, version: String },
+    Updated { name: String, from: String, to: String },
+    HashChanged { name: String, version: String },
+}
+
+impl std::fmt::Display for LockChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockChange::Added { name, version } => write!(f, "add {} v{}", name, version),
+            LockChange::Removed { name, version } => write!(f, "remove {} v{}", name, version),
+            LockChange::Updated { name, from, to } => {
+                write!(f, "update {} v{} -> v{}", name, from, to)
+            }
+            LockChange::HashChanged { name, version } => {
+                write!(f, "wasm hash of {} v{} changed", name, version)
+            }
+        }
+    }
+}
+
+/// Compute the changes needed to go from `old` to `new`.
+pub fn diff(old: &Lockfile, new: &Lockfile) -> Vec<LockChange> {
+    let mut changes = Vec::new();
+
+    for pkg in &new.packages {
+        match old.get(&pkg.name) {
+            None => changes.push(LockChange::Added {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+            }),
+            Some(prev) if prev.version != pkg.version => changes.push(LockChange::Updated {
+                name: pkg.name.clone(),
+                from: prev.version.clone(),
+                to: pkg.version.clone(),
+            }),
+            Some(prev) if prev.wasm_hash != pkg.wasm_hash => {
+                changes.push(LockChange::HashChanged {
+                    name: pkg.name.clone(),
+                    version: pkg.version.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for pkg in &old.packages {
+        if new.get(&pkg.name).is_none() {
+            changes.push(LockChange::Removed {
+                name: pkg.name.clone(),
+                version: pkg.version.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Registry lookups
+// ─────────────────────────────────────────────────────────────────────────────
+
+struct RegistryPackage {
+    versions: Vec<AvailableVersion>,
+}
+
+fn json_items(data: &serde_json::Value) -> Vec<serde_json::Value> {
+    data.as_array()
+        .or_else(|| data["contracts"].as_array())
+        .or_else(|| data["items"].as_array())
+        .or_else(|| data["versions"].as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+async fn fetch_package(
+    client: &reqwest::Client,
+    api_url: &str,
+    name: &str,
+) -> Result<RegistryPackage> {
+    let response = client
+        .get(format!("{}/api/contracts", api_url))
+        .query(&[("query", name)])
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        bail!("Failed to look up '{}': {}", name, response.status());
+    }
+
+    let data: serde_json::Value = response.json().await?;
+    let matches: Vec<serde_json::Value> = json_items(&data)
+        .into_iter()
+        .filter(|c| c["name"].as_str() == Some(name))
+        .collect();
+
+    if matches.is_empty() {
+        bail!("Dependency '{}' not found in the registry", name);
+    }
+
+    // Registry ID -> (network, on-chain contract ID)
+    let deployments: BTreeMap<&str, (String, &str)> = matches
+        .iter()
+        .filter_map(|c| {
+            let network = c["network"].as_str().unwrap_or("unknown").to_string();
+            Some((c["id"].as_str()?, (network, c["contract_id"].as_str()?)))
+        })
+        .collect();
+    let mut versions: Vec<AvailableVersion> = Vec::new();
+
+    for contract in &matches {
+        let id = contract["id"].as_str().context("Invalid contract response")?;
+        let resp = client
+            .get(format!("{}/api/contracts/{}/versions", api_url, id))
+            .send()
+            .await
+            .context("Failed to fetch contract versions")?;
+
+        if !resp.status().is_success() {
+            bail!("Failed to fetch versions of '{}': {}", name, resp.status());
+        }
+
+        let data: serde_json::Value = resp.json().await?;
+        for v in json_items(&data) {
+            let Some(version) = v["version"].as_str().and_then(SemVer::parse) else {
+                log::debug!("Skipping unparseable version of {}: {}", name, v["version"]);
+                continue;
+            };
+            // The version record names the deployment that published it
+            let owner = v["contract_id"].as_str().unwrap_or(id);
+            let index = match versions.iter().position(|a| a.version == version) {
+                Some(index) => index,
+                None => {
+                    let wasm_hash = v["wasm_hash"].as_str().unwrap_or_default().to_string();
+                    versions.push(AvailableVersion {
+                        version,
+                        wasm_hash,
+                        contract_ids: BTreeMap::new(),
+                    });
+                    versions.len() - 1
+                }
+            };
+            if let Some((network, cid)) = deployments.get(owner) {
+                versions[index]
+                    .contract_ids
+                    .insert(network.clone(), cid.to_string());
+            }
+        }
+    }
+
+    Ok(RegistryPackage { versions })
+}
+
+async fn resolve(
+    api_url: &str,
+    manifest: &ProjectManifest,
+    existing: Option<&Lockfile>,
+) -> Result<Lockfile> {
+    let client = reqwest::Client::new();
+    let mut lock = Lockfile::default();
+
+    for decl in manifest.declarations() {
+        if let Some(locked) = reusable_lock(existing, &decl) {
+            log::debug!("Reusing locked {} v{}", locked.name, locked.version);
+            lock.packages.push(locked.clone());
+            continue;
+        }
+
+        let constraint = VersionConstraint::parse(&decl.version_constraint).with_context(|| {
+            format!(
+                "Invalid version constraint '{}' for {}",
+                decl.version_constraint, decl.name
+            )
+        })?;
+
+        let package = fetch_package(&client, api_url, &decl.name).await?;
+        let chosen = resolve_version(&constraint, &package.versions).with_context(|| {
+            format!(
+                "No published version of '{}' satisfies {}",
+                decl.name, decl.version_constraint
+            )
+        })?;
+
+        lock.packages.push(LockedPackage {
+            name: decl.name.clone(),
+            version: chosen.version.to_string(),
+            constraint: decl.version_constraint.clone(),
+            wasm_hash: chosen.wasm_hash.clone(),
+            contract_ids: chosen.contract_ids.clone(),
+        });
+    }
+
+    Ok(lock)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn install(api_url: &str, project_dir: &str, locked: bool) -> Result<()> {
+    let dir = Path::new(project_dir);
+    let manifest = ProjectManifest::load(&dir.join(MANIFEST_FILE))?;
+    let lock_path = dir.join(LOCKFILE_FILE);
+    let existing = Lockfile::load(&lock_path)?;
+
+    if locked && existing.is_none() {
+        bail!("--locked was passed but {} does not exist", LOCKFILE_FILE);
+    }
+
+    println!("\n{}", "Resolving dependencies...".bold().cyan());
+
+    let resolved = resolve(api_url, &manifest, existing.as_ref()).await?;
+    let previous = existing.unwrap_or_default();
+    let changes = diff(&previous, &resolved);
+
+    if locked && !changes.is_empty() {
+        let summary: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        bail!(
+            "{} needs to be updated but --locked was passed:\n  {}",
+            LOCKFILE_FILE,
+            summary.join("\n  ")
+        );
+    }
+
+    for change in &changes {
+        println!("  {} {}", "→".bright_black(), change);
+    }
+
+    for pkg in &resolved.packages {
+        println!(
+            "{} {} v{} {}",
+            "✓".green(),
+            pkg.name.bold(),
+            pkg.version,
+            pkg.wasm_hash.bright_black()
+        );
+    }
+
+    if !changes.is_empty() || !lock_path.exists() {
+        resolved.save(&lock_path)?;
+        println!("\n{} {}", "Wrote".green().bold(), lock_path.display());
+    } else {
+        println!("\n{}", "Lockfile is up to date.".green());
+    }
+
+    Ok(())
+}
+
+pub async fn outdated(api_url: &str, project_dir: &str) -> Result<()> {
+    let dir = Path::new(project_dir);
+    let manifest = ProjectManifest::load(&dir.join(MANIFEST_FILE))?;
+    let lock = Lockfile::load(&dir.join(LOCKFILE_FILE))?.with_context(|| {
+        format!(
+            "{} not found — run `soroban-registry install` first",
+            LOCKFILE_FILE
+        )
+    })?;
+    let client = reqwest::Client::new();
+
+    println!("\n{}", "Outdated Dependencies:".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!(
+        "{:<24} {:<12} {:<12} {:<12}",
+        "Name".bold(),
+        "Locked".bold(),
+        "Compatible".bold(),
+        "Latest".bold()
+    );
+
+    let mut outdated = 0;
+    for decl in manifest.declarations() {
+        let Some(locked) = lock.get(&decl.name) else {
+            println!("{:<24} {}", decl.name, "not locked".yellow());
+            outdated += 1;
+            continue;
+        };
+
+        let package = fetch_package(&client, api_url, &decl.name).await?;
+        let latest = package.versions.iter().map(|v| &v.version).max();
+        let compatible = VersionConstraint::parse(&decl.version_constraint)
+            .and_then(|c| resolve_version(&c, &package.versions))
+            .map(|v| &v.version);
+
+        let current = SemVer::parse(&locked.version);
+        let is_behind = |v: Option<&SemVer>| match (v, current.as_ref()) {
+            (Some(v), Some(cur)) => v > cur,
+            _ => false,
+        };
+
+        if !is_behind(compatible) && !is_behind(latest) {
+            continue;
+        }
+        outdated += 1;
+
+        let fmt_version = |v: Option<&SemVer>| v.map(|v| v.to_string()).unwrap_or("-".into());
+        println!(
+            "{:<24} {:<12} {:<12} {:<12}",
+            decl.name,
+            locked.version,
+            fmt_version(compatible).green(),
+            fmt_version(latest).yellow()
+        );
+    }
+
+    println!("{}", "=".repeat(80).cyan());
+    if outdated == 0 {
+        println!("{}", "All dependencies are up to date.".green());
+    } else {
+        println!("{} dependency(ies) can be upgraded\n", outdated);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn avail(v: &str) -> AvailableVersion {
+        AvailableVersion {
+            version: SemVer::parse(v).unwrap(),
+            wasm_hash: format!("hash-{}", v),
+            contract_ids: BTreeMap::from([("testnet".into(), format!("C-{}", v))]),
+        }
+    }
+
+    fn locked(name: &str, version: &str, constraint: &str) -> LockedPackage {
+        LockedPackage {
+            name: name.into(),
+            version: version.into(),
+            constraint: constraint.into(),
+            wasm_hash: format!("hash-{}", version),
+            contract_ids: BTreeMap::from([("testnet".into(), "CABC".into())]),
+        }
+    }
+
+    #[test]
+    fn resolve_picks_highest_matching() {
+        let versions = vec![avail("1.0.0"), avail("1.4.2"), avail("1.3.0"), avail("2.0.0")];
+        let c = VersionConstraint::parse("^1.2.0").unwrap();
+        let chosen = resolve_version(&c, &versions).unwrap();
+        assert_eq!(chosen.version.to_string(), "1.4.2");
+        assert_eq!(chosen.contract_ids["testnet"], "C-1.4.2");
+
+        let c = VersionConstraint::parse("~1.3.0").unwrap();
+        assert_eq!(resolve_version(&c, &versions).unwrap().version.to_string(), "1.3.0");
+
+        let c = VersionConstraint::parse("^3.0.0").unwrap();
+        assert!(resolve_version(&c, &versions).is_none());
+    }
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let lock = Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: vec![locked("token", "1.2.3", "^1.2.0"), locked("amm", "0.3.1", "~0.3.0")],
+        };
+        let rendered = lock.render().unwrap();
+        assert!(rendered.starts_with("# This file is generated"));
+        let parsed = Lockfile::parse(&rendered).unwrap();
+        assert_eq!(parsed.packages[0].name, "amm");
+        assert_eq!(parsed.get("token"), lock.get("token"));
+    }
+
+    #[test]
+    fn lockfile_rejects_unknown_version() {
+        assert!(Lockfile::parse("version = 99\n").is_err());
+    }
+
+    #[test]
+    fn reuse_requires_same_constraint_and_match() {
+        let lock = Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: vec![locked("token", "1.2.3", "^1.2.0")],
+        };
+        let same = DependencyDeclaration {
+            name: "token".into(),
+            version_constraint: "^1.2.0".into(),
+        };
+        let changed = DependencyDeclaration {
+            name: "token".into(),
+            version_constraint: "^2.0.0".into(),
+        };
+        assert!(reusable_lock(Some(&lock), &same).is_some());
+        assert!(reusable_lock(Some(&lock), &changed).is_none());
+        assert!(reusable_lock(None, &same).is_none());
+    }
+
+    #[test]
+    fn diff_reports_all_change_kinds() {
+        let old = Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: vec![
+                locked("token", "1.2.3", "^1.2.0"),
+                locked("oracle", "0.1.0", "^0.1.0"),
+                locked("amm", "0.3.1", "~0.3.0"),
+            ],
+        };
+        let mut rehashed = locked("amm", "0.3.1", "~0.3.0");
+        rehashed.wasm_hash = "different".into();
+        let new = Lockfile {
+            version: LOCKFILE_VERSION,
+            packages: vec![
+                locked("token", "1.3.0", "^1.2.0"),
+                rehashed,
+                locked("vault", "2.0.0", "^2.0.0"),
+            ],
+        };
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 4);
+        assert!(changes.contains(&LockChange::Updated {
+            name: "token".into(),
+            from: "1.2.3".into(),
+            to: "1.3.0".into()
+        }));
+        assert!(changes.contains(&LockChange::Removed {
+            name: "oracle".into(),
+            version: "0.1.0".into()
+        }));
+        assert!(changes.iter().any(|c| matches!(c, LockChange::HashChanged { .. })));
+        assert!(changes.iter().any(|c| matches!(c, LockChange::Added { .. })));
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn manifest_parses_dependencies() {
+        let manifest: ProjectManifest =
+            toml::from_str("[dependencies]\ntoken = \"^1.2.0\"\n").unwrap();
+        let decls = manifest.declarations();
+        assert_eq!(decls.len(), 1);
+        assert_eq!(decls[0].version_constraint, "^1.2.0");
+    }
+}
@@ -1,21 +1,28 @@
 mod commands;
 mod backup;
+mod canary;
 mod config;
 mod events;
 mod export;
 mod fuzz;
 mod import;
 mod incident;
+mod lockfile;
+mod migration;
 mod manifest;
 mod multisig;
 mod package_signing;
 mod patch;
 mod profiler;
+mod regression;
+mod sandbox;
 mod sla;
+mod template;
 mod test_framework;
 mod wizard;
 mod formal_verification;
 mod coverage;
+mod costs;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
@@ -49,11 +56,26 @@ pub struct Cli {
 pub enum Commands {
     /// Search for contracts in the registry
     Search {
-        /// Search query
+        /// Search query; matches names, descriptions, tags, ABI functions and docs
         query: String,
         /// Only show verified contracts
         #[arg(long)]
         verified_only: bool,
+        /// Filter by category
+        #[arg(long)]
+        category: Option<String>,
+        /// Filter by maturity (alpha, beta, stable, mature, legacy)
+        #[arg(long)]
+        maturity: Option<String>,
+        /// Filter by SPDX license identifier
+        #[arg(long)]
+        license: Option<String>,
+        /// Comma-separated tags that must all be present
+        #[arg(long)]
+        tags: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
 		  /// Output results as machine-readable JSON
 		  #[arg(long)]
 		  json: bool,
@@ -71,7 +93,7 @@ pub enum Commands {
         #[arg(long)]
         contract_id: String,
 
-        /// Human-readable contract name
+        /// Contract name; `@org/name` publishes into an organization's namespace
         #[arg(long)]
         name: String,
 
@@ -179,6 +201,12 @@ pub enum Commands {
         action: PatchCommands,
     },
 
+    /// Canary releases with automatic promotion and rollback
+    Canary {
+        #[command(subcommand)]
+        action: CanaryCommands,
+    },
+
     /// Incident response management
     Incident {
         #[command(subcommand)]
@@ -196,13 +224,13 @@ pub enum Commands {
         #[arg(long)]
         contract_path: String,
         #[arg(long)]
-        duration: u64,
+        duration: String,
         #[arg(long)]
-        timeout: u64,
+        timeout: String,
         #[arg(long)]
-        threads: u32,
+        threads: usize,
         #[arg(long)]
-        max_cases: u32,
+        max_cases: u64,
         #[arg(long)]
         output: String,
         #[arg(long)]
@@ -267,6 +295,40 @@ pub enum Commands {
         #[command(subcommand)]
         action: ConfigSubcommands,
     },
+
+    /// Browse and clone contract templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateCommands,
+    },
+
+    /// Show or edit CLI defaults (~/.soroban-registry/config.toml)
+    Defaults {
+        #[command(subcommand)]
+        action: DefaultsCommands,
+    },
+
+    /// Query events emitted by a contract
+    Events {
+        /// Contract ID
+        contract_id: String,
+        /// Only events with this topic
+        #[arg(long)]
+        topic: Option<String>,
+        /// Only events whose data matches this pattern
+        #[arg(long)]
+        filter: Option<String>,
+        #[arg(long, default_value = "50")]
+        limit: i64,
+        #[arg(long, default_value = "0")]
+        offset: i64,
+        /// Write the events to this CSV file
+        #[arg(long)]
+        export: Option<String>,
+        /// Show aggregate statistics instead of events
+        #[arg(long)]
+        stats: bool,
+    },
     
     /// Run formal verification analysis against a deployed or local contract
     VerifyFormal {
@@ -291,10 +353,43 @@ pub enum Commands {
         contract_id: String,
         #[arg(long, default_value = ",")]
         dependencies: String,
+        /// Path to Cargo.lock; scans the full dependency graph
+        #[arg(long)]
+        cargo_lock: Option<String>,
         #[arg(long, default_value_t = false)]
         fail_on_high: bool,
     },
 
+    /// Estimate Soroban fees for calling a contract method
+    Costs {
+        /// Contract UUID
+        contract_id: String,
+
+        /// Method to estimate
+        #[arg(long)]
+        method: String,
+
+        /// Number of invocations (per day with --forecast)
+        #[arg(long)]
+        invocations: Option<i64>,
+
+        /// New contract storage in KB
+        #[arg(long)]
+        storage_kb: Option<i64>,
+
+        /// JSON file of per-invocation resources, e.g. from a simulation
+        #[arg(long)]
+        resources: Option<String>,
+
+        /// Show optimization suggestions
+        #[arg(long)]
+        optimize: bool,
+
+        /// Show daily, monthly and yearly projections
+        #[arg(long)]
+        forecast: bool,
+    },
+
     /// Measure and report code coverage for contract tests
     Coverage {
         /// Path to contract directory
@@ -358,9 +453,45 @@ pub enum Commands {
         #[command(subcommand)]
         action: KeysCommands,
     },
+
+    /// Data migration plans: attach, dry-run, execute and inspect
+    Migration {
+        #[command(subcommand)]
+        action: MigrationCommands,
+    },
+
+    /// Snapshot-based regression testing against recorded ledger state
+    Regression {
+        #[command(subcommand)]
+        action: RegressionCommands,
+    },
+
+    /// Back up contract state and restore it into a local sandbox fixture
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommands,
+    },
+
+    /// Resolve registry dependencies and write soroban-registry.lock
+    Install {
+        /// Project directory containing soroban-registry.toml
+        #[arg(long, default_value = ".")]
+        project_dir: String,
+
+        /// Fail if the lockfile is missing or resolution would change it
+        #[arg(long)]
+        locked: bool,
+    },
+
+    /// Show locked dependencies that have newer versions available
+    Outdated {
+        /// Project directory containing soroban-registry.toml
+        #[arg(long, default_value = ".")]
+        project_dir: String,
+    },
 }
 
-#[derive(Subcommand)]
+#[derive(Debug, Subcommand)]
 pub enum ConfigSubcommands {
     Get {
         #[arg(long)]
@@ -436,25 +567,101 @@ pub enum ConfigSubcommands {
     },
 }
 
+/// Sub-commands for the `template` group
+#[derive(Debug, Subcommand)]
+pub enum TemplateCommands {
+    /// List available templates
+    List {
+        #[arg(long)]
+        category: Option<String>,
+    },
+    /// Render a template into a new contract directory
+    Clone {
+        /// Template slug, e.g. `token`
+        template: String,
+        /// Name of the new contract
+        name: String,
+        /// Directory to create the contract in
+        #[arg(long, default_value = ".")]
+        output_dir: String,
+    },
+}
+
+/// Sub-commands for the `defaults` group
+#[derive(Debug, Subcommand)]
+pub enum DefaultsCommands {
+    /// Print the resolved defaults
+    Show,
+    /// Open the config file in $EDITOR, creating it if needed
+    Edit,
+}
+
 /// Sub-commands for the `sla` group
 #[derive(Debug, Subcommand)]
 pub enum SlaCommands {
-    /// Record hourly SLA metrics for a contract
-    Record {
-        /// Contract identifier
-        id: String,
-        /// Uptime percentage (0-100)
-        uptime: f64,
-        /// Average latency in milliseconds
-        latency: f64,
-        /// Error rate percentage (0-100)
-        error_rate: f64,
-    },
-    /// Show real-time SLA compliance dashboard
+    /// Show SLA compliance, the current billing period and recent violations
     Status {
-        /// Contract identifier
+        /// Contract UUID
+        id: String,
+    },
+    /// Create or update SLA targets and the synthetic probe
+    Targets {
+        /// Contract UUID
         id: String,
+        /// Minimum probe success percentage per window
+        #[arg(long)]
+        min_uptime: Option<f64>,
+        /// Maximum p95 latency in milliseconds
+        #[arg(long)]
+        max_latency: Option<f64>,
+        /// Maximum failed-invocation percentage per window
+        #[arg(long)]
+        max_error_rate: Option<f64>,
+        /// Evaluation window in minutes (must divide a day evenly)
+        #[arg(long)]
+        window_minutes: Option<i32>,
+        /// Penalty per violated metric per window
+        #[arg(long)]
+        penalty: Option<f64>,
+        /// Share of a period's penalties credited back when the period meets its uptime target (0-1)
+        #[arg(long)]
+        credit_rate: Option<f64>,
+        /// Read-only function the probe calls
+        #[arg(long)]
+        probe_function: Option<String>,
+        /// Base64 transaction envelope invoking the probe function (pass "" to remove the probe)
+        #[arg(long)]
+        probe_transaction: Option<String>,
+        /// Seconds between probes
+        #[arg(long)]
+        probe_interval: Option<i32>,
+        /// Pause monitoring for this contract
+        #[arg(long, conflicts_with = "enable")]
+        disable: bool,
+        /// Resume monitoring for this contract
+        #[arg(long)]
+        enable: bool,
     },
+    /// Show evaluated SLA windows
+    History {
+        id: String,
+        #[arg(long, default_value = "24")]
+        limit: usize,
+    },
+    /// Show SLA violation history
+    Violations {
+        id: String,
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+    /// Show billing periods with penalties and credits
+    Periods {
+        id: String,
+        #[arg(long, default_value = "12")]
+        limit: usize,
+    },
+    /// Run the synthetic probe once now
+    Probe { id: String },
     /// Show the trust score and breakdown for a contract
     TrustScore {
         /// Contract UUID to score
@@ -462,6 +669,143 @@ pub enum SlaCommands {
     },
 }
 
+/// Sub-commands for the `migration` group
+#[derive(Debug, Subcommand)]
+pub enum MigrationCommands {
+    /// Attach an ordered plan (YAML or JSON) to a pending migration
+    Attach {
+        /// Migration ID (UUID)
+        migration_id: String,
+        /// Plan file listing the steps
+        #[arg(long)]
+        plan: String,
+    },
+    /// Run a plan against a recorded ledger snapshot without sending transactions
+    DryRun {
+        /// Plan file listing the steps
+        #[arg(long)]
+        plan: String,
+        /// Fixture produced by `regression record`
+        #[arg(long)]
+        fixture: String,
+        /// Currently deployed WASM
+        #[arg(long)]
+        old_wasm: String,
+        /// WASM the plan upgrades to
+        #[arg(long)]
+        new_wasm: String,
+    },
+    /// Execute a migration's plan on the network, resuming after the last completed step
+    Run {
+        /// Migration ID (UUID)
+        migration_id: String,
+        /// WASM the plan upgrades to (must match the migration's hash)
+        #[arg(long)]
+        wasm: String,
+        /// Identity or secret key that signs the transactions
+        #[arg(long)]
+        source: String,
+    },
+    /// Show a migration's plan and per-step outcomes
+    Status {
+        /// Migration ID (UUID)
+        migration_id: String,
+    },
+}
+
+/// Sub-commands for the `regression` group
+#[derive(Debug, Subcommand)]
+pub enum RegressionCommands {
+    /// Record a contract's indexed ledger state into a fixture file
+    Record {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+        /// Output fixture path
+        #[arg(long, default_value = "ledger-fixture.json")]
+        output: String,
+    },
+    /// Replay a scenario against old and new WASM on top of a recorded fixture
+    Replay {
+        /// Fixture produced by `regression record`
+        #[arg(long)]
+        fixture: String,
+        /// Scenario file (YAML or JSON) listing the invocations to replay
+        #[arg(long)]
+        suite: String,
+        /// Currently deployed WASM
+        #[arg(long)]
+        old_wasm: String,
+        /// Candidate WASM
+        #[arg(long)]
+        new_wasm: String,
+        #[arg(long, default_value = "old")]
+        old_version: String,
+        #[arg(long, default_value = "new")]
+        new_version: String,
+        /// Record findings in the registry as regression test runs
+        #[arg(long)]
+        post: bool,
+    },
+}
+
+/// Sub-commands for the `backup` group
+#[derive(Debug, Subcommand)]
+pub enum BackupCommands {
+    /// Capture the contract's indexed ledger state
+    Create {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+        /// Keep metadata and the WASM hash only
+        #[arg(long)]
+        metadata_only: bool,
+        /// Check the store for every chunk instead of trusting the chunk index
+        #[arg(long)]
+        full: bool,
+        /// Comma-separated replica regions (default: every configured region)
+        #[arg(long)]
+        regions: Option<String>,
+    },
+    /// List the contract's latest backups
+    List {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+    },
+    /// Restore a backup into a fixture for `regression replay`
+    Restore {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+        /// Backup ID; defaults to the newest backup at or before --ledger/--at
+        #[arg(long)]
+        backup_id: Option<String>,
+        /// Restore the state as of this ledger
+        #[arg(long)]
+        ledger: Option<i64>,
+        /// Restore the state as of this time (RFC 3339)
+        #[arg(long)]
+        at: Option<String>,
+        /// Output fixture path
+        #[arg(long, default_value = "ledger-fixture.json")]
+        output: String,
+    },
+    /// Re-hash every stored chunk of a backup
+    Verify {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+        /// Backup ID, or a date (YYYY-MM-DD) for that day's newest backup
+        backup: String,
+    },
+    /// Show backup counts and storage use
+    Stats {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+    },
+    /// Show replication lag and health per region
+    Replication {
+        /// Contract registry ID (UUID)
+        contract_id: String,
+    },
+}
+
 /// Sub-commands for the `multisig` group
 #[derive(Debug, Subcommand)]
 pub enum MultisigCommands {
@@ -524,21 +868,157 @@ pub enum MultisigCommands {
 /// Sub-commands for the `incident` group
 #[derive(Debug, Subcommand)]
 pub enum IncidentCommands {
-    /// Trigger a new incident for a contract
+    /// Trigger a new incident for a contract (critical incidents engage the circuit breaker)
     Trigger {
-        /// On-chain contract ID
+        /// Registry UUID or on-chain contract ID
         contract_id: String,
         /// Incident severity (critical|high|medium|low)
         #[arg(long)]
         severity: String,
+        /// Short summary shown in incident lists
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        /// Responder recorded on the timeline (defaults to $USER)
+        #[arg(long)]
+        author: Option<String>,
     },
-    /// Update the state of an existing incident
+    /// Move an incident to a new state and/or add a timeline note
     Update {
         /// Incident UUID returned by trigger
         incident_id: String,
-        /// New state (detected|responding|contained|recovered|post_review)
+        /// New state (responding|contained|recovered|post_review)
+        #[arg(long)]
+        state: Option<String>,
+        /// Timeline note
+        #[arg(long)]
+        message: Option<String>,
+        #[arg(long)]
+        root_cause: Option<String>,
+        #[arg(long)]
+        resolution: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Show an incident with its timeline and affected contracts
+    Show { incident_id: String },
+    /// List incidents
+    List {
+        /// Only incidents that have not recovered yet
+        #[arg(long)]
+        active: bool,
+    },
+    /// Manually engage or release an incident's circuit breaker
+    Breaker {
+        incident_id: String,
+        /// Release the breaker instead of engaging it
+        #[arg(long)]
+        release: bool,
+        #[arg(long)]
+        reason: Option<String>,
         #[arg(long)]
-        state: String,
+        author: Option<String>,
+    },
+    /// Export a post-mortem document
+    Postmortem {
+        incident_id: String,
+        /// Output format (markdown|json)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        /// Write to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
+/// Sub-commands for the `canary` group
+#[derive(Debug, Subcommand)]
+pub enum CanaryCommands {
+    /// Start a canary release of a deployment
+    Create {
+        /// Contract UUID
+        #[arg(long)]
+        contract_id: String,
+        /// Deployment UUID to roll out
+        #[arg(long)]
+        deployment_id: String,
+        /// Traffic share for each of the four stages
+        #[arg(long, default_value = "1,10,50,100")]
+        stages: String,
+        /// Highest tolerated canary error rate, in percent
+        #[arg(long)]
+        max_error_rate: Option<f64>,
+        /// Largest tolerated p95 latency increase over stable, in percent
+        #[arg(long)]
+        max_latency_increase: Option<f64>,
+        /// Hours with abnormal event volume before rolling back
+        #[arg(long)]
+        max_event_anomalies: Option<i32>,
+        /// Minimum minutes per stage
+        #[arg(long)]
+        stage_minutes: Option<i32>,
+        /// Minimum canary requests per stage
+        #[arg(long)]
+        stage_requests: Option<i32>,
+        /// Require `canary advance` between stages
+        #[arg(long)]
+        manual: bool,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Show stage, criteria and the decision log
+    Status { canary_id: String },
+    /// List canary releases of a contract
+    List { contract_id: String },
+    /// Record a metrics sample for the canary or stable deployment
+    RecordMetric {
+        canary_id: String,
+        #[arg(long)]
+        requests: i32,
+        #[arg(long)]
+        errors: i32,
+        /// p95 latency in milliseconds
+        #[arg(long)]
+        p95_ms: Option<f64>,
+        /// canary or stable
+        #[arg(long, default_value = "canary")]
+        variant: String,
+    },
+    /// Evaluate the current stage now and apply the verdict
+    Evaluate { canary_id: String },
+    /// Advance to the next stage regardless of the criteria
+    Advance {
+        canary_id: String,
+        /// Traffic share to move to instead of the next stage's
+        #[arg(long)]
+        percentage: Option<i32>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Return all traffic to the stable deployment
+    Rollback {
+        canary_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Stop automatic evaluation
+    Pause {
+        canary_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Restart the current stage and resume automatic evaluation
+    Resume {
+        canary_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
     },
 }
 
@@ -573,10 +1053,111 @@ pub enum PatchCommands {
         #[command(subcommand)]
         command: DepsCommands,
     },
+    /// Roll a patch out to all vulnerable contracts in health-gated waves
+    Campaign {
+        #[command(subcommand)]
+        action: PatchCampaignCommands,
+    },
+    /// Show notifications sent to a publisher about patches
+    Notifications {
+        /// Publisher UUID
+        publisher_id: String,
+        /// Only notifications that have not been read
+        #[arg(long)]
+        unread: bool,
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PatchCampaignCommands {
+    /// Plan a campaign covering every contract running the vulnerable build
+    Create {
+        #[arg(long)]
+        patch_id: String,
+        /// Cumulative share of contracts after each wave
+        #[arg(long, default_value = "5,25,50,100")]
+        waves: String,
+        /// Largest tolerated error-rate increase, in percentage points
+        #[arg(long)]
+        max_error_rate_increase: Option<f64>,
+        /// Largest tolerated latency increase, in percent
+        #[arg(long)]
+        max_latency_increase: Option<f64>,
+        /// Minimum minutes a wave runs before the next one starts
+        #[arg(long)]
+        soak_minutes: Option<i32>,
+        /// Fraction of a wave that must be upgraded before advancing (0-1]
+        #[arg(long)]
+        min_applied: Option<f64>,
+        /// Require `patch campaign advance` between waves
+        #[arg(long)]
+        manual: bool,
+        /// Do not track contracts that depend on vulnerable ones
+        #[arg(long)]
+        no_dependents: bool,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Show waves, outstanding contracts and recent decisions
+    Status { campaign_id: String },
+    /// List campaigns for a patch
+    List {
+        #[arg(long)]
+        patch_id: String,
+    },
+    /// Start the first wave
+    Start {
+        campaign_id: String,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Start the next wave without waiting for the soak period
+    Advance {
+        campaign_id: String,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Stop scheduling new waves
+    Pause {
+        campaign_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Resume a paused campaign; health measurement restarts from now
+    Resume {
+        campaign_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Abandon the campaign
+    Abort {
+        campaign_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
+    /// Mark a contract as failed or skipped so it no longer holds up its wave
+    Target {
+        campaign_id: String,
+        contract_id: String,
+        /// failed|skipped
+        #[arg(long)]
+        status: String,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        author: Option<String>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
-enum DepsCommands {
 pub enum DepsCommands {
     /// List dependencies for a contract
     List {
@@ -644,17 +1225,28 @@ async fn main() -> Result<()> {
     log::debug!("Network: {:?}", network);
 
     match cli.command {
-         Commands::Search { query, verified_only, json } => {
+         Commands::Search {
+            query, verified_only, category, maturity, license, tags, limit, json,
+        } => {
             log::debug!("Command: search | query={:?} verified_only={}", query, verified_only);
-            ccommands::search(&cli.api_url, &query, network, verified_only, json).await?;
+            let options = commands::SearchOptions {
+                verified_only,
+                category: category.as_deref(),
+                maturity: maturity.as_deref(),
+                license: license.as_deref(),
+                tags: tags.as_deref(),
+                limit,
+            };
+            commands::search(&cli.api_url, &query, network, options, json).await?;
         }
         Commands::Info { contract_id } => {
             log::debug!("Command: info | contract_id={}", contract_id);
             commands::info(&cli.api_url, &contract_id, network).await?;
         }
         Commands::Publish {
-            contract_id, name, description, category, tags, publisher,
+            contract_id, name, description, network, category, tags, publisher,
         } => {
+            let network = network.parse::<config::Network>()?;
             let tags_vec = tags
                 .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
                 .unwrap_or_default();
@@ -699,14 +1291,107 @@ async fn main() -> Result<()> {
             log::debug!("Command: history | search={:?} limit={}", search, limit);
             wizard::show_history(search.as_deref(), limit)?;
         }
+        Commands::Canary { action } => match action {
+            CanaryCommands::Create {
+                contract_id, deployment_id, stages, max_error_rate, max_latency_increase,
+                max_event_anomalies, stage_minutes, stage_requests, manual, author,
+            } => {
+                log::debug!("Command: canary create | contract_id={} deployment_id={}", contract_id, deployment_id);
+                let request = shared::models::CreateCanaryRequest {
+                    contract_id,
+                    to_deployment_id: deployment_id,
+                    error_rate_threshold: max_error_rate,
+                    created_by: Some(author.unwrap_or_else(incident::default_author)),
+                    max_latency_increase_pct: max_latency_increase,
+                    max_event_anomalies,
+                    min_stage_minutes: stage_minutes,
+                    min_stage_requests: stage_requests,
+                    stage_percentages: Some(canary::parse_stages(&stages)?),
+                    auto_advance: Some(!manual),
+                };
+                canary::create(&cli.api_url, request).await?;
+            }
+            CanaryCommands::Status { canary_id } => {
+                log::debug!("Command: canary status | canary_id={}", canary_id);
+                canary::status(&cli.api_url, &canary_id).await?;
+            }
+            CanaryCommands::List { contract_id } => {
+                log::debug!("Command: canary list | contract_id={}", contract_id);
+                canary::list(&cli.api_url, &contract_id).await?;
+            }
+            CanaryCommands::RecordMetric { canary_id, requests, errors, p95_ms, variant } => {
+                log::debug!("Command: canary record-metric | canary_id={} variant={}", canary_id, variant);
+                let request = shared::models::RecordCanaryMetricRequest {
+                    canary_id: canary_id.clone(),
+                    requests,
+                    errors,
+                    avg_response_time_ms: None,
+                    p95_response_time_ms: p95_ms,
+                    p99_response_time_ms: None,
+                    variant: canary::parse_variant(&variant)?,
+                };
+                canary::record_metric(&cli.api_url, &canary_id, request).await?;
+            }
+            CanaryCommands::Evaluate { canary_id } => {
+                log::debug!("Command: canary evaluate | canary_id={}", canary_id);
+                canary::evaluate(&cli.api_url, &canary_id).await?;
+            }
+            CanaryCommands::Advance { canary_id, percentage, author } => {
+                log::debug!("Command: canary advance | canary_id={}", canary_id);
+                let actor = author.unwrap_or_else(incident::default_author);
+                canary::advance(&cli.api_url, &canary_id, percentage, &actor).await?;
+            }
+            CanaryCommands::Rollback { canary_id, reason, author } => {
+                log::debug!("Command: canary rollback | canary_id={}", canary_id);
+                let actor = author.unwrap_or_else(incident::default_author);
+                canary::action(&cli.api_url, &canary_id, "rollback", reason, &actor).await?;
+            }
+            CanaryCommands::Pause { canary_id, reason, author } => {
+                log::debug!("Command: canary pause | canary_id={}", canary_id);
+                let actor = author.unwrap_or_else(incident::default_author);
+                canary::action(&cli.api_url, &canary_id, "pause", reason, &actor).await?;
+            }
+            CanaryCommands::Resume { canary_id, reason, author } => {
+                log::debug!("Command: canary resume | canary_id={}", canary_id);
+                let actor = author.unwrap_or_else(incident::default_author);
+                canary::action(&cli.api_url, &canary_id, "resume", reason, &actor).await?;
+            }
+        },
         Commands::Incident { action } => match action {
-            IncidentCommands::Trigger { contract_id, severity } => {
+            IncidentCommands::Trigger { contract_id, severity, title, description, author } => {
                 log::debug!("Command: incident trigger | contract_id={} severity={}", contract_id, severity);
-                commands::incident_trigger(&contract_id, &severity)?;
+                let author = author.unwrap_or_else(incident::default_author);
+                // Auto routing has no registry network to disambiguate by
+                let network = (!matches!(network, config::Network::Auto)).then(|| network.to_string());
+                incident::trigger(
+                    &cli.api_url, &contract_id, network.as_deref(), &severity,
+                    title.as_deref(), description.as_deref(), &author,
+                ).await?;
+            }
+            IncidentCommands::Update { incident_id, state, message, root_cause, resolution, author } => {
+                log::debug!("Command: incident update | incident_id={} state={:?}", incident_id, state);
+                let author = author.unwrap_or_else(incident::default_author);
+                incident::update(
+                    &cli.api_url, &incident_id, state.as_deref(), message.as_deref(),
+                    root_cause.as_deref(), resolution.as_deref(), &author,
+                ).await?;
+            }
+            IncidentCommands::Show { incident_id } => {
+                log::debug!("Command: incident show | incident_id={}", incident_id);
+                incident::show(&cli.api_url, &incident_id).await?;
+            }
+            IncidentCommands::List { active } => {
+                log::debug!("Command: incident list | active={}", active);
+                incident::list(&cli.api_url, active).await?;
+            }
+            IncidentCommands::Breaker { incident_id, release, reason, author } => {
+                log::debug!("Command: incident breaker | incident_id={} release={}", incident_id, release);
+                let author = author.unwrap_or_else(incident::default_author);
+                incident::breaker(&cli.api_url, &incident_id, !release, reason.as_deref(), &author).await?;
             }
-            IncidentCommands::Update { incident_id, state } => {
-                log::debug!("Command: incident update | incident_id={} state={}", incident_id, state);
-                commands::incident_update(&incident_id, &state)?;
+            IncidentCommands::Postmortem { incident_id, format, output } => {
+                log::debug!("Command: incident postmortem | incident_id={} format={}", incident_id, format);
+                incident::postmortem(&cli.api_url, &incident_id, &format, output.as_deref()).await?;
             }
         },
         Commands::Patch { action } => match action {
@@ -723,12 +1408,72 @@ async fn main() -> Result<()> {
                 log::debug!("Command: patch apply | contract_id={} patch_id={}", contract_id, patch_id);
                 commands::patch_apply(&cli.api_url, &contract_id, &patch_id).await?;
             }
+            PatchCommands::Campaign { action } => match action {
+                PatchCampaignCommands::Create {
+                    patch_id, waves, max_error_rate_increase, max_latency_increase,
+                    soak_minutes, min_applied, manual, no_dependents, author,
+                } => {
+                    log::debug!("Command: patch campaign create | patch_id={} waves={}", patch_id, waves);
+                    let request = shared::models::CreatePatchCampaignRequest {
+                        wave_percentages: Some(patch::parse_waves(&waves)?),
+                        max_error_rate_increase,
+                        max_latency_increase_pct: max_latency_increase,
+                        min_soak_minutes: soak_minutes,
+                        min_applied_fraction: min_applied,
+                        auto_advance: Some(!manual),
+                        include_dependents: Some(!no_dependents),
+                        created_by: author.unwrap_or_else(incident::default_author),
+                    };
+                    patch::campaign_create(&cli.api_url, &patch_id, request).await?;
+                }
+                PatchCampaignCommands::Status { campaign_id } => {
+                    log::debug!("Command: patch campaign status | campaign_id={}", campaign_id);
+                    patch::campaign_status(&cli.api_url, &campaign_id).await?;
+                }
+                PatchCampaignCommands::List { patch_id } => {
+                    log::debug!("Command: patch campaign list | patch_id={}", patch_id);
+                    patch::campaign_list(&cli.api_url, &patch_id).await?;
+                }
+                PatchCampaignCommands::Start { campaign_id, author } => {
+                    log::debug!("Command: patch campaign start | campaign_id={}", campaign_id);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_action(&cli.api_url, &campaign_id, "start", None, &author).await?;
+                }
+                PatchCampaignCommands::Advance { campaign_id, author } => {
+                    log::debug!("Command: patch campaign advance | campaign_id={}", campaign_id);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_action(&cli.api_url, &campaign_id, "advance", None, &author).await?;
+                }
+                PatchCampaignCommands::Pause { campaign_id, reason, author } => {
+                    log::debug!("Command: patch campaign pause | campaign_id={}", campaign_id);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_action(&cli.api_url, &campaign_id, "pause", reason, &author).await?;
+                }
+                PatchCampaignCommands::Resume { campaign_id, reason, author } => {
+                    log::debug!("Command: patch campaign resume | campaign_id={}", campaign_id);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_action(&cli.api_url, &campaign_id, "resume", reason, &author).await?;
+                }
+                PatchCampaignCommands::Abort { campaign_id, reason, author } => {
+                    log::debug!("Command: patch campaign abort | campaign_id={}", campaign_id);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_action(&cli.api_url, &campaign_id, "abort", reason, &author).await?;
+                }
+                PatchCampaignCommands::Target { campaign_id, contract_id, status, reason, author } => {
+                    log::debug!("Command: patch campaign target | campaign_id={} contract_id={} status={}", campaign_id, contract_id, status);
+                    let author = author.unwrap_or_else(incident::default_author);
+                    patch::campaign_target(&cli.api_url, &campaign_id, &contract_id, &status, reason, &author).await?;
+                }
+            },
+            PatchCommands::Notifications { publisher_id, unread, limit } => {
+                log::debug!("Command: patch notifications | publisher_id={} unread={}", publisher_id, unread);
+                patch::notifications(&cli.api_url, &publisher_id, unread, limit).await?;
+            }
             PatchCommands::Deps { command } => match command {
                 DepsCommands::List { contract_id } => {
                     commands::deps_list(&cli.api_url, &contract_id).await?;
                 }
             },
-            }
         },
         // ── Multi-sig commands (issue #47) ───────────────────────────────────
         Commands::Multisig { action } => match action {
@@ -831,20 +1576,48 @@ async fn main() -> Result<()> {
             .await?;
         }
         Commands::Sla { action } => match action {
-            SlaCommands::Record { id, uptime, latency, error_rate } => {
-                log::debug!("Command: sla record | id={} uptime={} latency={} error_rate={}", id, uptime, latency, error_rate);
-                commands::sla_record(&id, uptime, latency, error_rate)?;
-            }
             SlaCommands::Status { id } => {
                 log::debug!("Command: sla status | id={}", id);
-                commands::sla_status(&id)?;
+                sla::status(&cli.api_url, &id).await?;
+            }
+            SlaCommands::Targets {
+                id, min_uptime, max_latency, max_error_rate, window_minutes, penalty,
+                credit_rate, probe_function, probe_transaction, probe_interval, disable, enable,
+            } => {
+                log::debug!("Command: sla targets | id={}", id);
+                let request = shared::models::SetSlaTargetsRequest {
+                    min_uptime,
+                    max_latency_ms: max_latency,
+                    max_error_rate,
+                    window_minutes,
+                    penalty_per_violation: penalty,
+                    credit_rate,
+                    probe_function,
+                    probe_transaction,
+                    probe_interval_seconds: probe_interval,
+                    enabled: if disable { Some(false) } else if enable { Some(true) } else { None },
+                };
+                sla::set_targets(&cli.api_url, &id, request).await?;
+            }
+            SlaCommands::History { id, limit } => {
+                log::debug!("Command: sla history | id={} limit={}", id, limit);
+                sla::history(&cli.api_url, &id, limit).await?;
+            }
+            SlaCommands::Violations { id, limit } => {
+                log::debug!("Command: sla violations | id={} limit={}", id, limit);
+                sla::violations(&cli.api_url, &id, limit).await?;
+            }
+            SlaCommands::Periods { id, limit } => {
+                log::debug!("Command: sla periods | id={} limit={}", id, limit);
+                sla::periods(&cli.api_url, &id, limit).await?;
+            }
+            SlaCommands::Probe { id } => {
+                log::debug!("Command: sla probe | id={}", id);
+                sla::probe(&cli.api_url, &id).await?;
             }
             SlaCommands::TrustScore { contract_id } => {
                 log::debug!("Command: trust-score | contract_id={}", contract_id);
-                // NOTE: Make sure commands::trust_score isn't expecting `network` since SlaCommands doesn't define it
-                // I removed `network` from the function call here because it wasn't available in scope. 
-                // Adjust if you need to fetch global network config instead.
-                commands::trust_score(&cli.api_url, &contract_id).await?;
+                commands::trust_score(&cli.api_url, &contract_id, network).await?;
             }
         },
         Commands::Config { action } => match action {
@@ -860,12 +1633,65 @@ async fn main() -> Result<()> {
             ConfigSubcommands::Rollback { contract_id, environment, version, created_by } => {
                 commands::config_rollback(&cli.api_url, &contract_id, &environment, version, &created_by).await?;
             }
+            ConfigSubcommands::ValidateCall { contract_id, method_name, params, strict } => {
+                commands::validate_call(&cli.api_url, &contract_id, &method_name, &params, strict).await?;
+            }
+            ConfigSubcommands::GenerateBindings { contract_id, language, output } => {
+                commands::generate_bindings(&cli.api_url, &contract_id, &language, output.as_deref()).await?;
+            }
+            ConfigSubcommands::ListFunctions { contract_id } => {
+                commands::list_functions(&cli.api_url, &contract_id).await?;
+            }
+            ConfigSubcommands::TrustScore { contract_id } => {
+                commands::trust_score(&cli.api_url, &contract_id, network).await?;
+            }
+        },
+        Commands::Template { action } => match action {
+            TemplateCommands::List { category } => {
+                template::list(&cli.api_url, category.as_deref()).await?;
+            }
+            TemplateCommands::Clone { template, name, output_dir } => {
+                log::debug!("Command: template clone | template={} name={}", template, name);
+                template::clone(&cli.api_url, &template, &name, &output_dir).await?;
+            }
+        },
+        Commands::Defaults { action } => match action {
+            DefaultsCommands::Show => config::show_config()?,
+            DefaultsCommands::Edit => config::edit_config()?,
         },
+        Commands::Events { contract_id, topic, filter, limit, offset, export, stats } => {
+            log::debug!("Command: events | contract_id={}", contract_id);
+            events::query_events(
+                &cli.api_url,
+                &contract_id,
+                topic.as_deref(),
+                filter.as_deref(),
+                limit,
+                offset,
+                export.as_deref(),
+                stats,
+            )
+            .await?;
+        }
         Commands::VerifyFormal { contract_path, properties, output, post } => {
             formal_verification::run(&cli.api_url, &contract_path, &properties, &output, post).await?;
         },
-        Commands::ScanDeps { contract_id, dependencies, fail_on_high } => {
-            commands::scan_deps(&cli.api_url, &contract_id, &dependencies, fail_on_high).await?;
+        Commands::ScanDeps { contract_id, dependencies, cargo_lock, fail_on_high } => {
+            commands::scan_deps(&cli.api_url, &contract_id, &dependencies, cargo_lock.as_deref(), fail_on_high).await?;
+        }
+        Commands::Costs { contract_id, method, invocations, storage_kb, resources, optimize, forecast } => {
+            log::debug!("Command: costs | contract_id={} method={}", contract_id, method);
+            costs::estimate_costs(
+                &cli.api_url,
+                &contract_id,
+                &method,
+                invocations,
+                storage_kb,
+                resources.as_deref(),
+                optimize,
+                forecast,
+            )
+            .await?;
         }
         Commands::Coverage { contract_path, tests, threshold, output } => {
             coverage::run(&contract_path, &tests, threshold, &output).await?;
@@ -921,10 +1747,171 @@ async fn main() -> Result<()> {
                     &cli.api_url,
                     contract_id.as_deref(),
                     entry_type.as_deref(),
-                    *limit,
+                    limit,
                 ).await?;
             }
         }
+        Commands::Migration { action } => match action {
+            MigrationCommands::Attach { migration_id, plan } => {
+                log::debug!(
+                    "Command: migration attach | migration_id={} plan={}",
+                    migration_id,
+                    plan
+                );
+                migration::attach(&cli.api_url, &migration_id, &plan).await?;
+            }
+            MigrationCommands::DryRun {
+                plan,
+                fixture,
+                old_wasm,
+                new_wasm,
+            } => {
+                log::debug!(
+                    "Command: migration dry-run | plan={} fixture={}",
+                    plan,
+                    fixture
+                );
+                migration::dry_run(&plan, &fixture, &old_wasm, &new_wasm)?;
+            }
+            MigrationCommands::Run {
+                migration_id,
+                wasm,
+                source,
+            } => {
+                log::debug!(
+                    "Command: migration run | migration_id={} wasm={}",
+                    migration_id,
+                    wasm
+                );
+                migration::run(
+                    &cli.api_url,
+                    &migration_id,
+                    &wasm,
+                    &source,
+                    &network.to_string(),
+                )
+                .await?;
+            }
+            MigrationCommands::Status { migration_id } => {
+                log::debug!("Command: migration status | migration_id={}", migration_id);
+                migration::status(&cli.api_url, &migration_id).await?;
+            }
+        },
+        Commands::Regression { action } => match action {
+            RegressionCommands::Record {
+                contract_id,
+                output,
+            } => {
+                log::debug!(
+                    "Command: regression record | contract_id={} output={}",
+                    contract_id,
+                    output
+                );
+                regression::record(&cli.api_url, &contract_id, &output).await?;
+            }
+            RegressionCommands::Replay {
+                fixture,
+                suite,
+                old_wasm,
+                new_wasm,
+                old_version,
+                new_version,
+                post,
+            } => {
+                log::debug!(
+                    "Command: regression replay | fixture={} suite={}",
+                    fixture,
+                    suite
+                );
+                regression::replay(
+                    &cli.api_url,
+                    &fixture,
+                    &suite,
+                    &old_wasm,
+                    &new_wasm,
+                    &old_version,
+                    &new_version,
+                    post,
+                )
+                .await?;
+            }
+        },
+        Commands::Backup { action } => match action {
+            BackupCommands::Create {
+                contract_id,
+                metadata_only,
+                full,
+                regions,
+            } => {
+                log::debug!(
+                    "Command: backup create | contract_id={} metadata_only={} full={}",
+                    contract_id,
+                    metadata_only,
+                    full
+                );
+                backup::create_backup(
+                    &cli.api_url,
+                    &contract_id,
+                    !metadata_only,
+                    full,
+                    regions.as_deref(),
+                )
+                .await?;
+            }
+            BackupCommands::List { contract_id } => {
+                log::debug!("Command: backup list | contract_id={}", contract_id);
+                backup::list_backups(&cli.api_url, &contract_id).await?;
+            }
+            BackupCommands::Restore {
+                contract_id,
+                backup_id,
+                ledger,
+                at,
+                output,
+            } => {
+                log::debug!(
+                    "Command: backup restore | contract_id={} output={}",
+                    contract_id,
+                    output
+                );
+                backup::restore_backup(
+                    &cli.api_url,
+                    &contract_id,
+                    backup_id.as_deref(),
+                    ledger,
+                    at.as_deref(),
+                    &output,
+                )
+                .await?;
+            }
+            BackupCommands::Verify {
+                contract_id,
+                backup,
+            } => {
+                log::debug!(
+                    "Command: backup verify | contract_id={} backup={}",
+                    contract_id,
+                    backup
+                );
+                backup::verify_backup(&cli.api_url, &contract_id, &backup).await?;
+            }
+            BackupCommands::Stats { contract_id } => {
+                log::debug!("Command: backup stats | contract_id={}", contract_id);
+                backup::backup_stats(&cli.api_url, &contract_id).await?;
+            }
+            BackupCommands::Replication { contract_id } => {
+                log::debug!("Command: backup replication | contract_id={}", contract_id);
+                backup::replication_status(&cli.api_url, &contract_id).await?;
+            }
+        },
+        Commands::Install { project_dir, locked } => {
+            log::debug!("Command: install | project_dir={} locked={}", project_dir, locked);
+            lockfile::install(&cli.api_url, &project_dir, locked).await?;
+        }
+        Commands::Outdated { project_dir } => {
+            log::debug!("Command: outdated | project_dir={}", project_dir);
+            lockfile::outdated(&cli.api_url, &project_dir).await?;
+        }
     }
 
     Ok(())
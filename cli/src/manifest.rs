@@ -57,14 +57,4 @@ impl ExportManifest {
             signature: None,
         }
     }
-
-    pub fn with_signature(mut self, sig_info: PackageSignatureInfo) -> Self {
-        self.signature = Some(sig_info);
-        self.audit_trail.push(AuditEntry {
-            action: "package_signed".into(),
-            timestamp: Utc::now(),
-            actor: "soroban-registry-cli".into(),
-        });
-        self
-    }
 }
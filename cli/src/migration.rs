@@ -0,0 +1,688 @@
+// cli/src/migration.rs
+// Data migration plans: attach ordered steps to a migration record, dry-run
+// them against a recorded ledger snapshot, and execute them on the network
+// with per-step checkpoints so an interrupted run resumes where it stopped.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use shared::models::{
+    AttachMigrationPlanRequest, MigrationPlanStatus, MigrationStepArg, MigrationStepKind,
+    MigrationStepSpec, MigrationStepStatus,
+};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::regression::ReplayFixture;
+use crate::sandbox::{self, Sandbox};
+use crate::test_framework::{self, TestValue, TypedValue};
+
+const TARGET_ALIAS: &str = "target";
+/// Function called by `upgrade_wasm` steps that do not name one
+const DEFAULT_UPGRADE_FN: &str = "upgrade";
+/// Key the uploaded WASM hash is reported under in step output
+const WASM_HASH_ARG: &str = "new_wasm_hash";
+
+#[derive(Debug, Deserialize)]
+struct PlanFile {
+    steps: Vec<MigrationStepSpec>,
+}
+
+pub fn load_plan(path: &Path) -> Result<Vec<MigrationStepSpec>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read plan file: {}", path.display()))?;
+
+    let plan: PlanFile = match path.extension().and_then(|s| s.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse YAML plan: {}", path.display()))?,
+        _ => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON plan: {}", path.display()))?,
+    };
+
+    if plan.steps.is_empty() {
+        bail!("Plan {} has no steps", path.display());
+    }
+    Ok(plan.steps)
+}
+
+fn describe(spec: &MigrationStepSpec) -> String {
+    match spec.kind {
+        MigrationStepKind::UpgradeWasm => format!(
+            "upgrade WASM via {}()",
+            spec.function_name.as_deref().unwrap_or(DEFAULT_UPGRADE_FN)
+        ),
+        MigrationStepKind::Invoke => format!(
+            "invoke {}({})",
+            spec.function_name.as_deref().unwrap_or("?"),
+            spec.args
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        MigrationStepKind::Verify => format!(
+            "verify {}() == {}",
+            spec.function_name.as_deref().unwrap_or("?"),
+            spec.expected.clone().unwrap_or_default()
+        ),
+    }
+}
+
+/// Whether a step's return value satisfies its `expected` value, using the
+/// same loose comparison as test scenario assertions (numbers vs numeric
+/// strings, explicit type annotations ignored).
+pub fn matches_expected(actual: &serde_json::Value, expected: &serde_json::Value) -> bool {
+    let actual: Option<TestValue> = serde_json::from_value(actual.clone()).ok();
+    let expected: Option<TestValue> = serde_json::from_value(expected.clone()).ok();
+    match (actual, expected) {
+        (Some(a), Some(e)) => test_framework::values_equal(&a, &e),
+        _ => false,
+    }
+}
+
+/// Render a JSON argument the way `soroban contract invoke` expects it:
+/// strings verbatim, everything else as JSON.
+pub fn cli_arg(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Step execution
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Where plan steps run: an in-process sandbox or the real network.
+trait StepExecutor {
+    /// Upload the new WASM and call `function` with its hash.
+    fn upgrade(&mut self, function: &str) -> Result<serde_json::Value>;
+    fn invoke(
+        &mut self,
+        function: &str,
+        args: &[MigrationStepArg],
+        read_only: bool,
+    ) -> Result<serde_json::Value>;
+}
+
+/// Run one step; `Err` carries the message recorded as the step's error.
+fn run_step(
+    executor: &mut dyn StepExecutor,
+    spec: &MigrationStepSpec,
+) -> std::result::Result<serde_json::Value, String> {
+    let function = spec.function_name.as_deref();
+    match spec.kind {
+        MigrationStepKind::UpgradeWasm => executor
+            .upgrade(function.unwrap_or(DEFAULT_UPGRADE_FN))
+            .map_err(|e| format!("{:#}", e)),
+        MigrationStepKind::Invoke => executor
+            .invoke(function.unwrap_or_default(), &spec.args, false)
+            .map_err(|e| format!("{:#}", e)),
+        MigrationStepKind::Verify => {
+            let actual = executor
+                .invoke(function.unwrap_or_default(), &spec.args, true)
+                .map_err(|e| format!("{:#}", e))?;
+            let expected = spec.expected.clone().unwrap_or_default();
+            if matches_expected(&actual, &expected) {
+                Ok(actual)
+            } else {
+                Err(format!(
+                    "invariant violated: expected {}, got {}",
+                    expected, actual
+                ))
+            }
+        }
+    }
+}
+
+struct SandboxExecutor {
+    sandbox: Sandbox,
+    new_wasm: Vec<u8>,
+}
+
+impl StepExecutor for SandboxExecutor {
+    fn upgrade(&mut self, function: &str) -> Result<serde_json::Value> {
+        let hash = self.sandbox.upload_wasm(&self.new_wasm);
+        let arg = TestValue::Typed(TypedValue {
+            ty: "bytes".into(),
+            value: Box::new(TestValue::String(hash.clone())),
+        });
+        let invocation = self.sandbox.invoke(TARGET_ALIAS, function, &[arg])?;
+        invocation.result.map_err(|e| anyhow::anyhow!(e))?;
+        Ok(serde_json::json!({ WASM_HASH_ARG: hash }))
+    }
+
+    fn invoke(
+        &mut self,
+        function: &str,
+        args: &[MigrationStepArg],
+        _read_only: bool,
+    ) -> Result<serde_json::Value> {
+        let args = args
+            .iter()
+            .map(|a| {
+                serde_json::from_value::<TestValue>(a.value.clone())
+                    .with_context(|| format!("Invalid value for argument '{}'", a.name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let invocation = self.sandbox.invoke(TARGET_ALIAS, function, &args)?;
+        let value = invocation.result.map_err(|e| anyhow::anyhow!(e))?;
+        Ok(serde_json::to_value(value)?)
+    }
+}
+
+struct NetworkExecutor {
+    contract_id: String,
+    wasm_path: String,
+    source: String,
+    network: String,
+}
+
+/// The parameter `function` takes the new WASM hash in, per the contract
+/// spec of `wasm`. Upgrade functions that take more than the hash need an
+/// explicit `invoke` step instead.
+fn upgrade_hash_param(wasm: &[u8], function: &str) -> Result<String> {
+    let params = sandbox::function_params(wasm, function)?
+        .with_context(|| format!("The deployed contract has no '{}' function", function))?;
+    match params.as_slice() {
+        [param] => Ok(param.clone()),
+        _ => bail!(
+            "'{}' takes ({}); an upgrade_wasm step needs a function taking only the WASM hash",
+            function,
+            params.join(", ")
+        ),
+    }
+}
+
+impl NetworkExecutor {
+    /// `contract <sub> --source .. --network ..`; contract arguments go after `--`.
+    fn command(&self, sub: &str) -> Vec<String> {
+        [
+            "contract",
+            sub,
+            "--source",
+            self.source.as_str(),
+            "--network",
+            self.network.as_str(),
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    fn soroban(&self, args: &[String]) -> Result<String> {
+        let output = Command::new("soroban")
+            .args(args)
+            .output()
+            .context("Failed to run the soroban CLI; is it installed?")?;
+        if !output.status.success() {
+            bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// WASM of the contract as currently deployed
+    fn fetch_deployed(&self) -> Result<Vec<u8>> {
+        let out = tempfile::NamedTempFile::new()?;
+        let args = vec![
+            "contract".to_string(),
+            "fetch".into(),
+            "--id".into(),
+            self.contract_id.clone(),
+            "--network".into(),
+            self.network.clone(),
+            "--out-file".into(),
+            out.path().display().to_string(),
+        ];
+        self.soroban(&args)?;
+        fs::read(out.path()).context("Failed to read the fetched contract WASM")
+    }
+
+    fn invoke_args(&self, function: &str, read_only: bool) -> Vec<String> {
+        let mut args = self.command("invoke");
+        args.push("--id".into());
+        args.push(self.contract_id.clone());
+        if read_only {
+            args.push("--send=no".into());
+        }
+        args.push("--".into());
+        args.push(function.into());
+        args
+    }
+}
+
+impl StepExecutor for NetworkExecutor {
+    fn upgrade(&mut self, function: &str) -> Result<serde_json::Value> {
+        // The upgrade function belongs to the code being replaced
+        let param = upgrade_hash_param(&self.fetch_deployed()?, function)?;
+
+        let mut upload = self.command("upload");
+        upload.push("--wasm".into());
+        upload.push(self.wasm_path.clone());
+        let hash = self.soroban(&upload)?;
+
+        let mut args = self.invoke_args(function, false);
+        args.push(format!("--{}", param));
+        args.push(hash.clone());
+        self.soroban(&args)?;
+        Ok(serde_json::json!({ WASM_HASH_ARG: hash }))
+    }
+
+    fn invoke(
+        &mut self,
+        function: &str,
+        args: &[MigrationStepArg],
+        read_only: bool,
+    ) -> Result<serde_json::Value> {
+        let mut cmd = self.invoke_args(function, read_only);
+        for arg in args {
+            cmd.push(format!("--{}", arg.name));
+            cmd.push(cli_arg(&arg.value));
+        }
+        let stdout = self.soroban(&cmd)?;
+        Ok(serde_json::from_str(&stdout).unwrap_or(serde_json::Value::String(stdout)))
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Attach a plan to a migration record
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn attach(api_url: &str, migration_id: &str, plan_path: &str) -> Result<()> {
+    let steps = load_plan(Path::new(plan_path))?;
+    let client = reqwest::Client::new();
+
+    println!("\n{}", "Attaching migration plan...".bold().cyan());
+
+    let response = client
+        .put(format!("{}/api/migrations/{}/plan", api_url, migration_id))
+        .json(&AttachMigrationPlanRequest { steps })
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        let err = response.text().await?;
+        bail!("API error: {}", err);
+    }
+
+    let plan: MigrationPlanStatus = response.json().await?;
+    println!("{}", "✓ Plan attached!".green().bold());
+    print_plan(&plan);
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Dry-run against a recorded ledger snapshot
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub fn dry_run(plan_path: &str, fixture_path: &str, old_wasm: &str, new_wasm: &str) -> Result<()> {
+    let steps = load_plan(Path::new(plan_path))?;
+    let raw = fs::read_to_string(fixture_path)
+        .with_context(|| format!("Failed to read fixture {}", fixture_path))?;
+    let fixture: ReplayFixture =
+        serde_json::from_str(&raw).with_context(|| format!("Invalid fixture {}", fixture_path))?;
+    let old = fs::read(old_wasm).with_context(|| format!("Failed to read {}", old_wasm))?;
+    let new = fs::read(new_wasm).with_context(|| format!("Failed to read {}", new_wasm))?;
+
+    // Fixtures hold contract data only, so the deployed code is attached
+    // explicitly before the plan upgrades it.
+    let mut sandbox = Sandbox::from_snapshot(fixture.snapshot);
+    sandbox.attach(TARGET_ALIAS, &fixture.contract_address, &old)?;
+    let mut executor = SandboxExecutor {
+        sandbox,
+        new_wasm: new,
+    };
+
+    println!("\n{}", "[DRY RUN] Migration plan".bold().yellow());
+    println!("{}", "=".repeat(80).cyan());
+    println!(
+        "  {} {} on {} (snapshot, no transactions sent)\n",
+        "Contract".bold(),
+        fixture.contract_address,
+        fixture.network
+    );
+
+    for (position, spec) in steps.iter().enumerate() {
+        match run_step(&mut executor, spec) {
+            Ok(output) => println!(
+                "{} [{}] {} → {}",
+                "✓".green(),
+                position,
+                describe(spec),
+                output
+            ),
+            Err(err) => {
+                println!("{} [{}] {}", "✗".red(), position, describe(spec));
+                println!("    {}", err.red());
+                println!("{}", "=".repeat(80).cyan());
+                bail!("Dry run failed at step {}", position);
+            }
+        }
+    }
+
+    println!("{}", "=".repeat(80).cyan());
+    println!(
+        "{}\n",
+        "Dry run completed: all steps succeeded.".green().bold()
+    );
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Execute on the network, resuming from the last checkpoint
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn fetch_plan(
+    client: &reqwest::Client,
+    api_url: &str,
+    migration_id: &str,
+) -> Result<MigrationPlanStatus> {
+    let response = client
+        .get(format!("{}/api/migrations/{}/plan", api_url, migration_id))
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        let err = response.text().await?;
+        bail!("API error: {}", err);
+    }
+    Ok(response.json().await?)
+}
+
+async fn record_step(
+    client: &reqwest::Client,
+    api_url: &str,
+    migration_id: &str,
+    position: i32,
+    status: MigrationStepStatus,
+    output: Option<serde_json::Value>,
+    error: Option<String>,
+) -> Result<()> {
+    let response = client
+        .post(format!(
+            "{}/api/migrations/{}/steps/{}",
+            api_url, migration_id, position
+        ))
+        .json(&serde_json::json!({
+            "status": status,
+            "output": output,
+            "error": error,
+        }))
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        let err = response.text().await?;
+        bail!("API error: {}", err);
+    }
+    Ok(())
+}
+
+pub async fn run(
+    api_url: &str,
+    migration_id: &str,
+    wasm_path: &str,
+    source: &str,
+    network: &str,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let plan = fetch_plan(&client, api_url, migration_id).await?;
+
+    if plan.steps.is_empty() {
+        bail!("Migration {} has no plan attached", migration_id);
+    }
+    let Some(resume_from) = plan.resume_from else {
+        println!("{}", "Migration plan already completed.".green().bold());
+        return Ok(());
+    };
+
+    let wasm = fs::read(wasm_path).with_context(|| format!("Failed to read {}", wasm_path))?;
+    let wasm_hash = hex::encode(Sha256::digest(&wasm));
+    if wasm_hash != plan.migration.wasm_hash {
+        bail!(
+            "WASM hash {} does not match the migration's {}",
+            wasm_hash,
+            plan.migration.wasm_hash
+        );
+    }
+
+    let mut executor = NetworkExecutor {
+        contract_id: plan.migration.contract_id.clone(),
+        wasm_path: wasm_path.to_string(),
+        source: source.to_string(),
+        network: network.to_string(),
+    };
+
+    println!("\n{}", "Executing migration plan...".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if resume_from > 0 {
+        println!(
+            "{}",
+            format!(
+                "Resuming from step {} (earlier steps completed)",
+                resume_from
+            )
+            .yellow()
+        );
+    }
+
+    for step in plan.steps.iter().filter(|s| !s.status.is_done()) {
+        let spec = MigrationStepSpec {
+            kind: step.kind,
+            function_name: step.function_name.clone(),
+            args: serde_json::from_value(step.args.clone()).unwrap_or_default(),
+            expected: step.expected.clone(),
+        };
+
+        record_step(
+            &client,
+            api_url,
+            migration_id,
+            step.position,
+            MigrationStepStatus::Running,
+            None,
+            None,
+        )
+        .await?;
+
+        match run_step(&mut executor, &spec) {
+            Ok(output) => {
+                println!("{} [{}] {}", "✓".green(), step.position, describe(&spec));
+                record_step(
+                    &client,
+                    api_url,
+                    migration_id,
+                    step.position,
+                    MigrationStepStatus::Succeeded,
+                    Some(output),
+                    None,
+                )
+                .await?;
+            }
+            Err(err) => {
+                println!("{} [{}] {}", "✗".red(), step.position, describe(&spec));
+                println!("    {}", err.red());
+                record_step(
+                    &client,
+                    api_url,
+                    migration_id,
+                    step.position,
+                    MigrationStepStatus::Failed,
+                    None,
+                    Some(err),
+                )
+                .await?;
+                bail!(
+                    "Migration stopped at step {}; fix the cause and re-run to resume",
+                    step.position
+                );
+            }
+        }
+    }
+
+    println!("{}", "=".repeat(80).cyan());
+    println!("{}\n", "Migration completed successfully.".green().bold());
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Status
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn print_plan(plan: &MigrationPlanStatus) {
+    println!("  {}: {}", "Migration".bold(), plan.migration.id);
+    println!("  {}: {}", "Contract".bold(), plan.migration.contract_id);
+    println!("  {}: {:?}", "Status".bold(), plan.migration.status);
+    println!();
+
+    for step in &plan.steps {
+        let spec = MigrationStepSpec {
+            kind: step.kind,
+            function_name: step.function_name.clone(),
+            args: serde_json::from_value(step.args.clone()).unwrap_or_default(),
+            expected: step.expected.clone(),
+        };
+        let marker = match step.status {
+            MigrationStepStatus::Succeeded => "✓".green(),
+            MigrationStepStatus::Skipped => "-".bright_black(),
+            MigrationStepStatus::Failed => "✗".red(),
+            MigrationStepStatus::Running => "…".yellow(),
+            MigrationStepStatus::Pending => "○".normal(),
+        };
+        println!("  {} [{}] {}", marker, step.position, describe(&spec));
+        if let Some(ref err) = step.error {
+            println!("      {}", err.red());
+        }
+    }
+
+    if let Some(position) = plan.resume_from {
+        println!("\n  {} step {}", "Resumes from".bold(), position);
+    }
+    println!();
+}
+
+pub async fn status(api_url: &str, migration_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let plan = fetch_plan(&client, api_url, migration_id).await?;
+
+    println!("\n{}", "Migration plan".bold().cyan());
+    print_plan(&plan);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct Recorder {
+        calls: Vec<String>,
+        result: serde_json::Value,
+    }
+
+    impl StepExecutor for Recorder {
+        fn upgrade(&mut self, function: &str) -> Result<serde_json::Value> {
+            self.calls.push(format!("upgrade:{}", function));
+            Ok(json!({ WASM_HASH_ARG: "ab" }))
+        }
+
+        fn invoke(
+            &mut self,
+            function: &str,
+            _args: &[MigrationStepArg],
+            read_only: bool,
+        ) -> Result<serde_json::Value> {
+            self.calls
+                .push(format!("invoke:{}:{}", function, read_only));
+            Ok(self.result.clone())
+        }
+    }
+
+    fn spec(kind: MigrationStepKind, function: Option<&str>) -> MigrationStepSpec {
+        MigrationStepSpec {
+            kind,
+            function_name: function.map(str::to_string),
+            args: Vec::new(),
+            expected: None,
+        }
+    }
+
+    #[test]
+    fn plan_file_accepts_function_alias_and_named_args() {
+        let plan: PlanFile = serde_yaml::from_str(
+            r#"
+steps:
+  - kind: upgrade_wasm
+  - kind: invoke
+    function: migrate
+    args:
+      - name: from_version
+        value: 1
+  - kind: verify
+    function: version
+    expected: 2
+"#,
+        )
+        .unwrap();
+        assert_eq!(plan.steps.len(), 3);
+        assert_eq!(plan.steps[0].kind, MigrationStepKind::UpgradeWasm);
+        assert_eq!(plan.steps[1].function_name.as_deref(), Some("migrate"));
+        assert_eq!(plan.steps[1].args[0].name, "from_version");
+        assert_eq!(plan.steps[2].expected, Some(json!(2)));
+    }
+
+    #[test]
+    fn expected_values_compare_loosely() {
+        assert!(matches_expected(&json!("2"), &json!(2)));
+        assert!(matches_expected(
+            &json!(7),
+            &json!({ "$type": "u32", "$value": 7 })
+        ));
+        assert!(!matches_expected(&json!(3), &json!(2)));
+    }
+
+    #[test]
+    fn verify_step_fails_on_mismatch() {
+        let mut exec = Recorder {
+            calls: Vec::new(),
+            result: json!(1),
+        };
+        let mut verify = spec(MigrationStepKind::Verify, Some("version"));
+        verify.expected = Some(json!(2));
+
+        let err = run_step(&mut exec, &verify).unwrap_err();
+        assert!(err.contains("invariant violated"));
+        assert_eq!(exec.calls, vec!["invoke:version:true"]);
+    }
+
+    #[test]
+    fn upgrade_step_defaults_to_upgrade_function() {
+        let mut exec = Recorder {
+            calls: Vec::new(),
+            result: json!(null),
+        };
+        run_step(&mut exec, &spec(MigrationStepKind::UpgradeWasm, None)).unwrap();
+        run_step(&mut exec, &spec(MigrationStepKind::Invoke, Some("migrate"))).unwrap();
+        assert_eq!(exec.calls, vec!["upgrade:upgrade", "invoke:migrate:false"]);
+    }
+
+    #[test]
+    fn upgrade_hash_param_comes_from_contract_spec() {
+        let wasm = include_bytes!("../tests/fixtures/constructor.wasm");
+        assert_eq!(upgrade_hash_param(wasm, "get_data").unwrap(), "key");
+        assert!(upgrade_hash_param(wasm, "__constructor").is_err());
+        assert!(upgrade_hash_param(wasm, "upgrade").is_err());
+    }
+
+    #[test]
+    fn cli_args_render_strings_verbatim() {
+        assert_eq!(cli_arg(&json!("GABC")), "GABC");
+        assert_eq!(cli_arg(&json!(5)), "5");
+        assert_eq!(cli_arg(&json!({"a": 1})), r#"{"a":1}"#);
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::{Context, Result, bail};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use colored::Colorize;
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ed25519_dalek::{Signer, SigningKey};
 use rand::rngs::OsRng;
 use serde_json::json;
 use sha2::{Digest, Sha256};
@@ -45,7 +45,7 @@ pub async fn sign_package(
     let url = format!("{}/api/signatures", api_url);
 
     let expires_dt = expires_at
-        .map(|s| chrono::DateTime::parse_from_rfc3339(s))
+        .map(chrono::DateTime::parse_from_rfc3339)
         .transpose()
         .context("Invalid expires_at format, use RFC3339 (e.g., 2025-12-31T23:59:59Z)")?
         .map(|dt| dt.with_timezone(&Utc));
@@ -483,7 +483,7 @@ fn decode_private_key(key: &str) -> Result<SigningKey> {
         .try_into()
         .map_err(|_| anyhow::anyhow!("Private key must be 32 bytes"))?;
 
-    SigningKey::from_bytes(&bytes).context("Invalid private key")
+    Ok(SigningKey::from_bytes(&bytes))
 }
 
 fn create_signing_message(hash: &str, contract_id: &str, version: &str) -> Vec<u8> {
@@ -495,12 +495,12 @@ fn derive_stellar_address(public_key_bytes: &[u8; 32]) -> String {
     use ripemd::Ripemd160;
 
     let sha256_hash = Sha256::digest(public_key_bytes);
-    let ripemd_hash = Ripemd160::digest(&sha256_hash);
+    let ripemd_hash = Ripemd160::digest(sha256_hash);
 
     let mut versioned = vec![0x00];
     versioned.extend_from_slice(&ripemd_hash);
 
-    let checksum = Sha256::digest(&Sha256::digest(&versioned));
+    let checksum = Sha256::digest(Sha256::digest(&versioned));
     versioned.extend_from_slice(&checksum[..4]);
 
     bs58::encode(&versioned).into_string()
@@ -1,9 +1,16 @@
 use std::fmt;
 use std::str::FromStr;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
+use colored::{ColoredString, Colorize};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use shared::models::{
+    CreatePatchCampaignRequest, PatchCampaign, PatchCampaignActionRequest, PatchCampaignDetail,
+    PatchCampaignStatus, PatchRolloutProgress, PatchTargetImpact, PatchTargetStatus,
+    PublisherNotification, UpdatePatchTargetRequest,
+};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -62,14 +69,6 @@ pub struct PatchAudit {
 pub struct PatchManager;
 
 impl PatchManager {
-    pub fn check_rollout(applied: usize, total: usize, rollout_pct: u8) -> bool {
-        if total == 0 {
-            return false;
-        }
-        let max_allowed = (total as f64 * rollout_pct as f64 / 100.0).ceil() as usize;
-        applied < max_allowed
-    }
-
     pub async fn create(
         api_url: &str,
         version: &str,
@@ -132,70 +131,378 @@ impl PatchManager {
         Ok((patch, contracts))
     }
 
-    pub async fn apply(
-        api_url: &str,
-        contract_id: &str,
-        patch_id: &str,
-    ) -> Result<PatchAudit> {
+    /// Record an upgrade. The registry enforces the rollout: a live
+    /// campaign only admits contracts in a started wave, otherwise the
+    /// patch's rollout percentage caps how many contracts may take it.
+    pub async fn apply(api_url: &str, contract_id: &str, patch_id: &str) -> Result<PatchAudit> {
         let client = reqwest::Client::new();
+        let payload = serde_json::json!({ "contract_id": contract_id });
 
-        let patch_resp = client
-            .get(format!("{}/api/patches/{}", api_url, patch_id))
+        let resp = client
+            .post(format!("{}/api/patches/{}/apply", api_url, patch_id))
+            .json(&payload)
             .send()
             .await?;
 
-        if !patch_resp.status().is_success() {
-            bail!("patch not found: {}", patch_id);
+        if !resp.status().is_success() {
+            bail!("failed to apply patch: {}", resp.text().await?);
         }
 
-        let patch: SecurityPatch = patch_resp.json().await?;
+        Ok(resp.json().await?)
+    }
+}
 
-        let audits_resp = client
-            .get(format!("{}/api/patches/{}/audits", api_url, patch_id))
-            .send()
-            .await?;
+// ─────────────────────────────────────────────────────────
+// Rollout campaigns
+// ─────────────────────────────────────────────────────────
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response.json().await.context("Invalid response from registry")
+}
 
-        let audits_data: serde_json::Value = audits_resp.json().await?;
-        let applied = audits_data["total"].as_u64().unwrap_or(0) as usize;
+/// Parse cumulative wave percentages such as `5,25,50,100`.
+pub fn parse_waves(value: &str) -> Result<Vec<i32>> {
+    value
+        .split(',')
+        .map(|p| {
+            p.trim()
+                .trim_end_matches('%')
+                .parse::<i32>()
+                .with_context(|| format!("invalid wave percentage: {}", p.trim()))
+        })
+        .collect()
+}
 
-        let contracts_resp = client
-            .get(format!(
-                "{}/api/contracts?wasm_hash={}",
-                api_url, patch.target_version
-            ))
-            .send()
-            .await?;
+fn campaign_status_label(status: PatchCampaignStatus) -> ColoredString {
+    let label = status.to_string().replace('_', " ").to_uppercase();
+    match status {
+        PatchCampaignStatus::Pending => label.normal(),
+        PatchCampaignStatus::RollingOut => label.cyan().bold(),
+        PatchCampaignStatus::Paused => label.yellow().bold(),
+        PatchCampaignStatus::Completed => label.green().bold(),
+        PatchCampaignStatus::Aborted => label.red(),
+    }
+}
 
-        let contracts_data: serde_json::Value = contracts_resp.json().await?;
-        let total = contracts_data["total"].as_u64().unwrap_or(0) as usize;
+fn target_status_label(status: PatchTargetStatus) -> ColoredString {
+    let label = format!("{:?}", status).to_lowercase();
+    match status {
+        PatchTargetStatus::Pending => label.bright_black(),
+        PatchTargetStatus::Scheduled => label.cyan(),
+        PatchTargetStatus::Applied | PatchTargetStatus::Resolved => label.green(),
+        PatchTargetStatus::Failed => label.red(),
+        PatchTargetStatus::Skipped => label.yellow(),
+    }
+}
 
-        if !Self::check_rollout(applied, total, patch.rollout_percentage) {
-            bail!(
-                "rollout quota exceeded: {}/{} ({}% of {} eligible)",
-                applied,
-                (total as f64 * patch.rollout_percentage as f64 / 100.0).ceil() as usize,
-                patch.rollout_percentage,
-                total
+/// `applied/eligible` bar for a wave or the whole campaign
+pub fn progress_bar(progress: &PatchRolloutProgress, width: usize) -> String {
+    let eligible = (progress.total - progress.skipped).max(0);
+    let filled = if eligible == 0 {
+        0
+    } else {
+        (progress.applied as usize * width / eligible as usize).min(width)
+    };
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+fn print_detail(detail: &PatchCampaignDetail) {
+    let c = &detail.campaign;
+    println!("\n{}", "Patch Campaign".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!("  {}: {}", "Campaign".bold(), c.id);
+    println!(
+        "  {}: {} [{}] → {}",
+        "Patch".bold(),
+        detail.patch.target_version,
+        detail.patch.severity,
+        detail.patch.new_wasm_hash.bright_black()
+    );
+    println!("  {}: {}", "Status".bold(), campaign_status_label(c.status));
+    if let Some(reason) = &c.paused_reason {
+        println!("  {}: {}", "Paused".bold(), reason.yellow());
+    }
+    println!(
+        "  {}: error rate +{}pp, latency +{}%, soak {} min, {:.0}% upgraded, {}",
+        "Gate".bold(),
+        c.max_error_rate_increase,
+        c.max_latency_increase_pct,
+        c.min_soak_minutes,
+        c.min_applied_fraction * 100.0,
+        if c.auto_advance {
+            "auto-advance"
+        } else {
+            "manual advance"
+        }
+    );
+
+    println!("\n  {}", "Waves".bold());
+    for w in &detail.waves {
+        let marker = if w.wave == c.current_wave {
+            "▶".cyan()
+        } else {
+            " ".normal()
+        };
+        println!(
+            "  {} {:>2}. {:>3}%  [{}] {}/{} applied, {} failed, {} skipped",
+            marker,
+            w.wave,
+            w.cumulative_percentage,
+            progress_bar(&w.progress, 20),
+            w.progress.applied,
+            w.progress.total,
+            w.progress.failed,
+            w.progress.skipped
+        );
+    }
+    println!(
+        "\n  {}: {}/{} contracts patched, {}/{} dependents resolved",
+        "Progress".bold(),
+        detail.progress.applied,
+        detail.progress.total,
+        detail.dependents_resolved,
+        detail.dependents_total
+    );
+
+    let outstanding: Vec<_> = detail
+        .targets
+        .iter()
+        .filter(|t| t.impact == PatchTargetImpact::Direct)
+        .filter(|t| {
+            matches!(
+                t.status,
+                PatchTargetStatus::Scheduled | PatchTargetStatus::Failed
+            )
+        })
+        .collect();
+    if !outstanding.is_empty() {
+        println!("\n  {}", "Awaiting upgrade".bold());
+        for t in outstanding {
+            println!(
+                "    {:<30} wave {:<2} {}{}",
+                t.contract_name,
+                t.wave.unwrap_or_default(),
+                target_status_label(t.status),
+                t.failure_reason
+                    .as_deref()
+                    .map(|r| format!(" ({})", r))
+                    .unwrap_or_default()
+                    .bright_black()
             );
         }
+    }
 
-        let payload = serde_json::json!({
-            "contract_id": contract_id,
-            "patch_id": patch_id,
-        });
+    if !detail.events.is_empty() {
+        println!("\n  {}", "Recent events".bold());
+        let skip = detail.events.len().saturating_sub(8);
+        for e in detail.events.iter().skip(skip) {
+            println!(
+                "    {} {:<15} {}",
+                e.created_at
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+                    .bright_black(),
+                e.kind,
+                e.message
+            );
+        }
+    }
+    println!();
+}
 
-        let resp = client
-            .post(format!("{}/api/patches/{}/apply", api_url, patch_id))
-            .json(&payload)
-            .send()
-            .await?;
+pub async fn campaign_create(
+    api_url: &str,
+    patch_id: &str,
+    request: CreatePatchCampaignRequest,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: PatchCampaignDetail = send(
+        client
+            .post(format!("{}/api/patches/{}/campaigns", api_url, patch_id))
+            .json(&request),
+        "create patch campaign",
+    )
+    .await?;
+
+    println!("\n{} Campaign created", "✓".green());
+    print_detail(&detail);
+    println!(
+        "  Start the first wave with `patch campaign start {}`\n",
+        detail.campaign.id
+    );
+    Ok(())
+}
 
-        if !resp.status().is_success() {
-            bail!("failed to apply patch: {}", resp.text().await?);
-        }
+pub async fn campaign_status(api_url: &str, campaign_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: PatchCampaignDetail = send(
+        client.get(format!("{}/api/patch-campaigns/{}", api_url, campaign_id)),
+        "fetch patch campaign",
+    )
+    .await?;
+    print_detail(&detail);
+    Ok(())
+}
 
-        Ok(resp.json().await?)
+pub async fn campaign_list(api_url: &str, patch_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let campaigns: Vec<PatchCampaign> = send(
+        client.get(format!("{}/api/patches/{}/campaigns", api_url, patch_id)),
+        "list patch campaigns",
+    )
+    .await?;
+
+    println!("\n{}", "Patch Campaigns".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if campaigns.is_empty() {
+        println!("  No campaigns for this patch.\n");
+        return Ok(());
+    }
+    for c in &campaigns {
+        println!(
+            "  {}  {:<12} wave {}/{}  {}",
+            c.id,
+            campaign_status_label(c.status),
+            c.current_wave,
+            c.wave_percentages.len(),
+            c.created_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .bright_black()
+        );
     }
+    println!();
+    Ok(())
+}
+
+/// Run an operator action: start, advance, pause, resume or abort.
+pub async fn campaign_action(
+    api_url: &str,
+    campaign_id: &str,
+    action: &str,
+    reason: Option<String>,
+    actor: &str,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let detail: PatchCampaignDetail = send(
+        client
+            .post(format!(
+                "{}/api/patch-campaigns/{}/{}",
+                api_url, campaign_id, action
+            ))
+            .json(&PatchCampaignActionRequest {
+                actor: actor.to_string(),
+                reason,
+            }),
+        &format!("{} patch campaign", action),
+    )
+    .await?;
+
+    println!(
+        "\n{} Campaign is now {} (wave {}/{})",
+        "✓".green(),
+        campaign_status_label(detail.campaign.status),
+        detail.campaign.current_wave,
+        detail.campaign.wave_percentages.len()
+    );
+    print_detail(&detail);
+    Ok(())
+}
+
+pub async fn campaign_target(
+    api_url: &str,
+    campaign_id: &str,
+    contract_id: &str,
+    status: &str,
+    reason: Option<String>,
+    actor: &str,
+) -> Result<()> {
+    let status = match status.to_lowercase().as_str() {
+        "failed" => PatchTargetStatus::Failed,
+        "skipped" => PatchTargetStatus::Skipped,
+        other => bail!("invalid target status: {} (expected failed|skipped)", other),
+    };
+    let client = reqwest::Client::new();
+    let detail: PatchCampaignDetail = send(
+        client
+            .post(format!(
+                "{}/api/patch-campaigns/{}/targets/{}",
+                api_url, campaign_id, contract_id
+            ))
+            .json(&UpdatePatchTargetRequest {
+                status,
+                reason,
+                actor: actor.to_string(),
+            }),
+        "update campaign target",
+    )
+    .await?;
+
+    println!(
+        "\n{} Contract {} marked {}",
+        "✓".green(),
+        contract_id,
+        target_status_label(status)
+    );
+    print_detail(&detail);
+    Ok(())
+}
+
+pub async fn notifications(
+    api_url: &str,
+    publisher_id: &str,
+    unread: bool,
+    limit: usize,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let notifications: Vec<PublisherNotification> = send(
+        client
+            .get(format!(
+                "{}/api/publishers/{}/notifications",
+                api_url, publisher_id
+            ))
+            .query(&[("unread", unread.to_string()), ("limit", limit.to_string())]),
+        "fetch publisher notifications",
+    )
+    .await?;
+
+    println!("\n{}", "Publisher Notifications".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if notifications.is_empty() {
+        println!("  No notifications.\n");
+        return Ok(());
+    }
+    for n in &notifications {
+        let subject = if n.read_at.is_none() {
+            n.subject.bold()
+        } else {
+            n.subject.normal()
+        };
+        println!(
+            "  {} {}",
+            n.created_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .bright_black(),
+            subject
+        );
+        for line in n.body.lines() {
+            println!("    {}", line);
+        }
+        println!();
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -224,39 +531,21 @@ mod tests {
     }
 
     #[test]
-    fn rollout_zero_percent() {
-        assert!(!PatchManager::check_rollout(0, 100, 0));
-    }
-
-    #[test]
-    fn rollout_full() {
-        assert!(PatchManager::check_rollout(0, 10, 100));
-        assert!(PatchManager::check_rollout(9, 10, 100));
-        assert!(!PatchManager::check_rollout(10, 10, 100));
-    }
-
-    #[test]
-    fn rollout_fifty_percent() {
-        assert!(PatchManager::check_rollout(0, 10, 50));
-        assert!(PatchManager::check_rollout(4, 10, 50));
-        assert!(!PatchManager::check_rollout(5, 10, 50));
-    }
-
-    #[test]
-    fn rollout_rounds_up() {
-        assert!(PatchManager::check_rollout(0, 3, 50));
-        assert!(PatchManager::check_rollout(1, 3, 50));
-        assert!(!PatchManager::check_rollout(2, 3, 50));
-    }
-
-    #[test]
-    fn rollout_empty_total() {
-        assert!(!PatchManager::check_rollout(0, 0, 100));
+    fn waves_parse_from_comma_separated_percentages() {
+        assert_eq!(parse_waves("5,25,50,100").unwrap(), vec![5, 25, 50, 100]);
+        assert_eq!(parse_waves("10%, 100%").unwrap(), vec![10, 100]);
+        assert!(parse_waves("10,half").is_err());
     }
 
     #[test]
-    fn rollout_one_contract() {
-        assert!(PatchManager::check_rollout(0, 1, 1));
-        assert!(!PatchManager::check_rollout(1, 1, 1));
+    fn progress_bar_ignores_skipped_targets() {
+        let progress = PatchRolloutProgress {
+            total: 5,
+            applied: 2,
+            skipped: 1,
+            ..Default::default()
+        };
+        assert_eq!(progress_bar(&progress, 4), "██░░");
+        assert_eq!(progress_bar(&PatchRolloutProgress::default(), 3), "░░░");
     }
 }
@@ -91,7 +91,7 @@ impl Profiler {
         if let Some(parent) = self.call_stack.get(self.call_stack.len().saturating_sub(2)) {
             self.call_graph
                 .entry(parent.0.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(name.to_string());
         }
     }
@@ -104,7 +104,7 @@ impl Profiler {
         self.call_stack.pop();
         self.function_stats
             .entry(name.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(duration);
     }
 
@@ -122,12 +122,9 @@ impl Profiler {
             .map(|(name, durations)| {
                 let total: Duration = durations.iter().sum();
                 let count = durations.len() as u64;
-                let avg = if count > 0 {
-                    Duration::from_nanos(total.as_nanos() as u64 / count)
-                    total / count as u32
-                } else {
-                    Duration::ZERO
-                };
+                let avg = (total.as_nanos() as u64)
+                    .checked_div(count)
+                    .map_or(Duration::ZERO, Duration::from_nanos);
                 let min = durations.iter().min().copied().unwrap_or(Duration::ZERO);
                 let max = durations.iter().max().copied().unwrap_or(Duration::ZERO);
                 let children = self.call_graph.get(&name).cloned().unwrap_or_default();
@@ -166,7 +163,7 @@ pub fn parse_contract_functions(contract_path: &Path) -> Result<Vec<String>> {
     let mut functions = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
 
-    for (i, line) in lines.iter().enumerate() {
+    for line in lines.iter() {
         if line.trim().starts_with("pub fn ") || line.trim().starts_with("fn ") {
             if let Some(name_start) = line.find("fn ") {
                 let after_fn = &line[name_start + 3..];
@@ -246,9 +243,9 @@ pub fn generate_flame_graph(profile: &ProfileData, output_path: &Path) -> Result
     let width = 1200.0;
 
     let mut sorted_functions: Vec<_> = profile.functions.values().collect();
-    sorted_functions.sort_by(|a, b| b.total_time.cmp(&a.total_time));
+    sorted_functions.sort_by_key(|f| std::cmp::Reverse(f.total_time));
 
-    for (idx, func) in sorted_functions.iter().take(30).enumerate() {
+    for func in sorted_functions.iter().take(30) {
         let time_ratio = func.total_time.as_nanos() as f64 / max_time;
         let bar_width = width * time_ratio.min(1.0);
         
@@ -351,7 +348,7 @@ pub fn compare_profiles(profile1: &ProfileData, profile2: &ProfileData) -> Vec<C
         }
     }
 
-    results.sort_by(|a, b| b.time_diff_ns.abs().cmp(&a.time_diff_ns.abs()));
+    results.sort_by_key(|r| std::cmp::Reverse(r.time_diff_ns.abs()));
     results
 }
 
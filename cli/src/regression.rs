@@ -0,0 +1,498 @@
+// cli/src/regression.rs
+// Snapshot-based regression testing: record a contract's indexed ledger state
+// into a fixture file, then replay a suite of invocations against an old and a
+// new WASM and report behavioural differences.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use soroban_ledger_snapshot::LedgerSnapshot;
+use soroban_sdk::xdr::{LedgerEntry, LedgerKey, Limits, ReadXdr};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::sandbox::{EmittedEvent, Sandbox};
+use crate::test_framework::{self, TestValue};
+
+const TARGET_ALIAS: &str = "target";
+/// CPU increase (percent) above which a replay reports a resource regression
+const CPU_REGRESSION_PERCENT: f64 = 25.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFixture {
+    /// Registry UUID of the contract
+    pub registry_id: String,
+    pub contract_address: String,
+    pub network: String,
+    pub recorded_at: DateTime<Utc>,
+    pub ledger_sequence: Option<u32>,
+    pub snapshot: LedgerSnapshot,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotEntry {
+    key_xdr: String,
+    entry_xdr: String,
+    live_until_ledger: Option<i64>,
+}
+
+/// Indexed ledger state as served by the registry, for a live contract or a
+/// restored backup
+#[derive(Debug, Deserialize)]
+pub(crate) struct SnapshotResponse {
+    contract_address: String,
+    network: String,
+    ledger_sequence: Option<i64>,
+    ledger_timestamp: Option<i64>,
+    network_passphrase: Option<String>,
+    entries: Vec<SnapshotEntry>,
+}
+
+/// Observable behaviour of one step against one WASM version.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepOutcome {
+    pub result: std::result::Result<TestValue, String>,
+    pub events: Vec<EmittedEvent>,
+    pub storage_writes: BTreeMap<String, Option<TestValue>>,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub kind: String,
+    pub severity: String,
+    pub message: String,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+impl Finding {
+    fn new<T: Serialize>(kind: &str, severity: &str, message: String, old: &T, new: &T) -> Self {
+        Self {
+            kind: kind.into(),
+            severity: severity.into(),
+            message,
+            old: serde_json::to_value(old).ok(),
+            new: serde_json::to_value(new).ok(),
+        }
+    }
+}
+
+/// Keys whose value differs between `before` and `after`; `None` marks a removal.
+pub fn storage_writes(
+    before: &BTreeMap<String, TestValue>,
+    after: &BTreeMap<String, TestValue>,
+) -> BTreeMap<String, Option<TestValue>> {
+    let mut writes = BTreeMap::new();
+    for (key, value) in after {
+        if before.get(key) != Some(value) {
+            writes.insert(key.clone(), Some(value.clone()));
+        }
+    }
+    for key in before.keys() {
+        if !after.contains_key(key) {
+            writes.insert(key.clone(), None);
+        }
+    }
+    writes
+}
+
+/// Compare the behaviour of one step under the old and the new WASM.
+pub fn compare_outcomes(old: &StepOutcome, new: &StepOutcome) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    match (&old.result, &new.result) {
+        (Ok(a), Ok(b)) if a != b => findings.push(Finding::new(
+            "return_value",
+            "major",
+            "return value changed".into(),
+            a,
+            b,
+        )),
+        (Ok(a), Err(e)) => findings.push(Finding::new(
+            "error",
+            "critical",
+            format!("call now fails: {}", e),
+            &Some(a),
+            &None::<&TestValue>,
+        )),
+        (Err(e), Ok(b)) => findings.push(Finding::new(
+            "error",
+            "major",
+            format!("call that used to fail now succeeds (was: {})", e),
+            &None::<&TestValue>,
+            &Some(b),
+        )),
+        (Err(a), Err(b)) if a != b => findings.push(Finding::new(
+            "error",
+            "minor",
+            "call fails with a different error".into(),
+            a,
+            b,
+        )),
+        _ => {}
+    }
+
+    if old.events != new.events {
+        findings.push(Finding::new(
+            "events",
+            "major",
+            format!(
+                "emitted events differ ({} before, {} after)",
+                old.events.len(),
+                new.events.len()
+            ),
+            &old.events,
+            &new.events,
+        ));
+    }
+
+    if old.storage_writes != new.storage_writes {
+        let changed: Vec<&String> = old
+            .storage_writes
+            .keys()
+            .chain(new.storage_writes.keys())
+            .filter(|k| old.storage_writes.get(*k) != new.storage_writes.get(*k))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        findings.push(Finding::new(
+            "storage_writes",
+            "critical",
+            format!(
+                "storage writes differ for {} key(s): {}",
+                changed.len(),
+                changed
+                    .iter()
+                    .map(|k| k.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            &old.storage_writes,
+            &new.storage_writes,
+        ));
+    }
+
+    if old.cpu_instructions > 0 {
+        let delta = (new.cpu_instructions as f64 - old.cpu_instructions as f64)
+            / old.cpu_instructions as f64
+            * 100.0;
+        if delta > CPU_REGRESSION_PERCENT {
+            findings.push(Finding::new(
+                "resources",
+                "minor",
+                format!("cpu instructions increased by {:.1}%", delta),
+                &old.cpu_instructions,
+                &new.cpu_instructions,
+            ));
+        }
+    }
+
+    findings
+}
+
+fn network_id(passphrase: &str) -> [u8; 32] {
+    Sha256::digest(passphrase.as_bytes()).into()
+}
+
+fn build_snapshot(resp: &SnapshotResponse) -> Result<LedgerSnapshot> {
+    let mut snapshot = LedgerSnapshot::default();
+    if let Some(seq) = resp.ledger_sequence {
+        snapshot.sequence_number = u32::try_from(seq).context("invalid ledger sequence")?;
+    }
+    if let Some(ts) = resp.ledger_timestamp {
+        snapshot.timestamp = u64::try_from(ts).context("invalid ledger timestamp")?;
+    }
+    if let Some(ref passphrase) = resp.network_passphrase {
+        snapshot.network_id = network_id(passphrase);
+    }
+
+    for entry in &resp.entries {
+        let key = LedgerKey::from_xdr_base64(&entry.key_xdr, Limits::none())
+            .context("invalid ledger key XDR")?;
+        let value = LedgerEntry::from_xdr_base64(&entry.entry_xdr, Limits::none())
+            .context("invalid ledger entry XDR")?;
+        let live_until = entry.live_until_ledger.map(|l| l as u32);
+        snapshot
+            .ledger_entries
+            .push((Box::new(key), (Box::new(value), live_until)));
+    }
+
+    Ok(snapshot)
+}
+
+pub(crate) fn fixture_from_response(
+    registry_id: &str,
+    resp: SnapshotResponse,
+    recorded_at: DateTime<Utc>,
+) -> Result<ReplayFixture> {
+    let snapshot = build_snapshot(&resp)?;
+    Ok(ReplayFixture {
+        registry_id: registry_id.to_string(),
+        contract_address: resp.contract_address,
+        network: resp.network,
+        recorded_at,
+        ledger_sequence: resp.ledger_sequence.map(|s| s as u32),
+        snapshot,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Record
+// ─────────────────────────────────────────────────────────────────────────────
+
+pub async fn record(api_url: &str, contract_id: &str, output: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/api/contracts/{}/regression/ledger-snapshot",
+        api_url, contract_id
+    );
+
+    println!("\n{}", "Recording ledger snapshot...".bold().cyan());
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .context("Failed to reach registry API")?;
+
+    if !response.status().is_success() {
+        let err = response.text().await?;
+        bail!("API error: {}", err);
+    }
+
+    let resp: SnapshotResponse = response.json().await?;
+    let fixture = fixture_from_response(contract_id, resp, Utc::now())?;
+    let entry_count = fixture.snapshot.ledger_entries.len();
+
+    fs::write(output, serde_json::to_string_pretty(&fixture)?)
+        .with_context(|| format!("Failed to write fixture {}", output))?;
+
+    println!("{}", "✓ Snapshot recorded!".green().bold());
+    println!("  {}: {}", "Contract".bold(), fixture.contract_address);
+    println!("  {}: {}", "Network".bold(), fixture.network);
+    println!(
+        "  {}: {}",
+        "Ledger".bold(),
+        fixture
+            .ledger_sequence
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "unknown".into())
+    );
+    println!("  {}: {}", "Entries".bold(), entry_count);
+    println!("  {}: {}\n", "Fixture".bold(), output);
+
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Replay
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn run_suite(
+    fixture: &ReplayFixture,
+    wasm: &[u8],
+    scenario: &test_framework::TestScenario,
+) -> Result<Vec<StepOutcome>> {
+    let mut sandbox = Sandbox::from_snapshot(fixture.snapshot.clone());
+    sandbox.attach(TARGET_ALIAS, &fixture.contract_address, wasm)?;
+
+    let mut outcomes = Vec::with_capacity(scenario.steps.len());
+    for step in &scenario.steps {
+        let before = sandbox.contract_storage(TARGET_ALIAS)?;
+        let invocation = sandbox.invoke(
+            TARGET_ALIAS,
+            &step.method,
+            step.args.as_deref().unwrap_or_default(),
+        )?;
+        let after = sandbox.contract_storage(TARGET_ALIAS)?;
+
+        outcomes.push(StepOutcome {
+            result: invocation.result,
+            events: invocation.events,
+            storage_writes: storage_writes(&before, &after),
+            cpu_instructions: invocation.cpu_instructions,
+            memory_bytes: invocation.memory_bytes,
+        });
+    }
+    Ok(outcomes)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn replay(
+    api_url: &str,
+    fixture_path: &str,
+    suite_path: &str,
+    old_wasm: &str,
+    new_wasm: &str,
+    old_version: &str,
+    new_version: &str,
+    post: bool,
+) -> Result<()> {
+    let raw = fs::read_to_string(fixture_path)
+        .with_context(|| format!("Failed to read fixture {}", fixture_path))?;
+    let fixture: ReplayFixture =
+        serde_json::from_str(&raw).with_context(|| format!("Invalid fixture {}", fixture_path))?;
+    let scenario = test_framework::load_test_scenario(Path::new(suite_path))?;
+    let old = fs::read(old_wasm).with_context(|| format!("Failed to read {}", old_wasm))?;
+    let new = fs::read(new_wasm).with_context(|| format!("Failed to read {}", new_wasm))?;
+
+    println!("\n{}", "Replaying recorded state...".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!(
+        "  {} {} on {} (ledger {})",
+        "Contract".bold(),
+        fixture.contract_address,
+        fixture.network,
+        fixture
+            .ledger_sequence
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "?".into())
+    );
+    println!(
+        "  {} {} → {} ({} step(s))\n",
+        "Versions".bold(),
+        old_version,
+        new_version,
+        scenario.steps.len()
+    );
+
+    let old_outcomes = run_suite(&fixture, &old, &scenario)?;
+    let new_outcomes = run_suite(&fixture, &new, &scenario)?;
+
+    let mut invocations = Vec::new();
+    let mut total_findings = 0;
+    for ((step, before), after) in scenario.steps.iter().zip(&old_outcomes).zip(&new_outcomes) {
+        let findings = compare_outcomes(before, after);
+        total_findings += findings.len();
+
+        if findings.is_empty() {
+            println!("{} {}", "✓".green(), step.name.bold());
+        } else {
+            println!("{} {}", "✗".red(), step.name.bold());
+            for f in &findings {
+                println!(
+                    "    [{}] {}: {}",
+                    f.severity.to_uppercase().red(),
+                    f.kind,
+                    f.message
+                );
+            }
+        }
+
+        invocations.push(serde_json::json!({
+            "step_name": step.name,
+            "function_name": step.method,
+            "old_output": before,
+            "new_output": after,
+            "old_cpu_instructions": before.cpu_instructions,
+            "new_cpu_instructions": after.cpu_instructions,
+            "new_memory_bytes": after.memory_bytes,
+            "findings": findings,
+        }));
+    }
+
+    println!("{}", "=".repeat(80).cyan());
+
+    if post {
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!(
+                "{}/api/contracts/{}/regression/replays",
+                api_url, fixture.registry_id
+            ))
+            .json(&serde_json::json!({
+                "old_version": old_version,
+                "new_version": new_version,
+                "suite_name": scenario.name,
+                "invocations": invocations,
+            }))
+            .send()
+            .await
+            .context("Failed to reach registry API")?;
+
+        if !response.status().is_success() {
+            let err = response.text().await?;
+            bail!("API error: {}", err);
+        }
+        println!("{} Findings recorded in the registry", "✓".green());
+    }
+
+    if total_findings > 0 {
+        bail!(
+            "{} behavioural difference(s) between {} and {}",
+            total_findings,
+            old_version,
+            new_version
+        );
+    }
+
+    println!("{}\n", "No behavioural differences found.".green().bold());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(result: std::result::Result<TestValue, String>) -> StepOutcome {
+        StepOutcome {
+            result,
+            events: Vec::new(),
+            storage_writes: BTreeMap::new(),
+            cpu_instructions: 1_000,
+            memory_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn storage_writes_detects_updates_and_removals() {
+        let before = BTreeMap::from([
+            ("persistent:\"a\"".to_string(), TestValue::Number(1)),
+            ("persistent:\"b\"".to_string(), TestValue::Number(2)),
+        ]);
+        let after = BTreeMap::from([
+            ("persistent:\"a\"".to_string(), TestValue::Number(1)),
+            ("persistent:\"c\"".to_string(), TestValue::Number(3)),
+        ]);
+        let writes = storage_writes(&before, &after);
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes["persistent:\"b\""], None);
+        assert_eq!(writes["persistent:\"c\""], Some(TestValue::Number(3)));
+    }
+
+    #[test]
+    fn identical_outcomes_have_no_findings() {
+        let a = outcome(Ok(TestValue::Number(5)));
+        assert!(compare_outcomes(&a, &a.clone()).is_empty());
+    }
+
+    #[test]
+    fn new_failure_is_critical() {
+        let old = outcome(Ok(TestValue::Number(5)));
+        let new = outcome(Err("Error(Contract, #3)".into()));
+        let findings = compare_outcomes(&old, &new);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, "error");
+        assert_eq!(findings[0].severity, "critical");
+    }
+
+    #[test]
+    fn storage_and_cpu_differences_are_reported() {
+        let old = outcome(Ok(TestValue::Null));
+        let mut new = outcome(Ok(TestValue::Null));
+        new.storage_writes
+            .insert("persistent:\"Balance\"".into(), Some(TestValue::Number(7)));
+        new.cpu_instructions = 2_000;
+
+        let kinds: Vec<String> = compare_outcomes(&old, &new)
+            .into_iter()
+            .map(|f| f.kind)
+            .collect();
+        assert_eq!(kinds, vec!["storage_writes", "resources"]);
+    }
+}
@@ -0,0 +1,771 @@
+// cli/src/sandbox.rs
+// Embedded Soroban host used by `soroban-registry test` to execute scenarios
+// against real contract WASM instead of simulated results.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use soroban_ledger_snapshot::LedgerSnapshot;
+use soroban_sdk::testutils::{AuthorizedFunction, AuthorizedInvocation, Events as _, Ledger as _};
+use soroban_sdk::xdr::{
+    ContractDataDurability, LedgerEntryData, LedgerKey, Limited, Limits, ReadXdr, ScSpecEntry,
+    ScVal,
+};
+use soroban_sdk::{
+    Address, Bytes, Env, IntoVal, Map, String as SorobanString, Symbol, TryFromVal, Val,
+    Vec as SorobanVec,
+};
+use std::collections::{BTreeMap, HashMap};
+use stellar_strkey::Strkey;
+
+use crate::test_framework::{TestValue, TypedValue};
+
+const WASM_MAGIC: &[u8; 8] = b"\0asm\x01\0\0\0";
+const EXPORT_SECTION_ID: u8 = 7;
+const EXPORT_KIND_FUNC: u8 = 0;
+const CUSTOM_SECTION_ID: u8 = 0;
+const SPEC_SECTION: &str = "contractspecv0";
+const CONSTRUCTOR: &str = "__constructor";
+
+/// An event emitted by a contract during an invocation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmittedEvent {
+    pub contract: String,
+    pub topics: Vec<TestValue>,
+    pub data: TestValue,
+}
+
+/// One `require_auth` recorded during an invocation (sub-invocations flattened).
+#[derive(Debug, Clone)]
+pub struct AuthRecord {
+    pub address: String,
+    pub function: String,
+}
+
+/// Everything observable about a single contract invocation.
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    pub result: std::result::Result<TestValue, String>,
+    pub events: Vec<EmittedEvent>,
+    pub auths: Vec<AuthRecord>,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Durability {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+impl std::str::FromStr for Durability {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "instance" => Ok(Self::Instance),
+            "persistent" => Ok(Self::Persistent),
+            "temporary" => Ok(Self::Temporary),
+            _ => bail!(
+                "invalid storage durability: {} (expected instance|persistent|temporary)",
+                s
+            ),
+        }
+    }
+}
+
+pub struct Sandbox {
+    env: Env,
+    contracts: HashMap<String, Address>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sandbox {
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        Self {
+            env,
+            contracts: HashMap::new(),
+        }
+    }
+
+    /// Start from recorded ledger state, e.g. a replay fixture.
+    pub fn from_snapshot(snapshot: LedgerSnapshot) -> Self {
+        let env = Env::from_ledger_snapshot(snapshot);
+        env.mock_all_auths();
+        Self {
+            env,
+            contracts: HashMap::new(),
+        }
+    }
+
+    /// Bind `alias` to a contract already present in the ledger and swap its
+    /// code for `wasm`, keeping its storage — exactly what an upgrade does.
+    pub fn attach(&mut self, alias: &str, contract_address: &str, wasm: &[u8]) -> Result<()> {
+        if !matches!(
+            Strkey::from_string(contract_address),
+            Ok(Strkey::Contract(_))
+        ) {
+            bail!("'{}' is not a valid contract strkey", contract_address);
+        }
+        let address = Address::from_str(&self.env, contract_address);
+        let hash = self
+            .env
+            .deployer()
+            .upload_contract_wasm(Bytes::from_slice(&self.env, wasm));
+        self.env.as_contract(&address, || {
+            self.env.deployer().update_current_contract_wasm(hash)
+        });
+        self.contracts.insert(alias.to_string(), address);
+        Ok(())
+    }
+
+    /// Upload `wasm` without deploying it and return its hex-encoded hash,
+    /// e.g. to pass to a contract's own upgrade function.
+    pub fn upload_wasm(&self, wasm: &[u8]) -> String {
+        let hash = self
+            .env
+            .deployer()
+            .upload_contract_wasm(Bytes::from_slice(&self.env, wasm));
+        hex::encode(hash.to_array())
+    }
+
+    /// Register `wasm` under `alias`, running its constructor with `args` if it has one.
+    ///
+    /// `args` must match the constructor's parameters; the host panics on a
+    /// mismatch, so it is checked against the contract spec first.
+    pub fn deploy(&mut self, alias: &str, wasm: &[u8], args: &[TestValue]) -> Result<String> {
+        let params = constructor_params(wasm)?;
+        if args.len() != params.len() {
+            if params.is_empty() {
+                bail!(
+                    "{} has no constructor but {} argument(s) were given",
+                    alias,
+                    args.len()
+                );
+            }
+            bail!(
+                "{} has a constructor taking ({}) but {} argument(s) were given",
+                alias,
+                params.join(", "),
+                args.len()
+            );
+        }
+
+        let mut ctor_args = SorobanVec::<Val>::new(&self.env);
+        for arg in args {
+            ctor_args.push_back(to_val(&self.env, arg)?);
+        }
+        let address = self.env.register(wasm, ctor_args);
+        let id = address_string(&self.env, &address)?;
+        self.contracts.insert(alias.to_string(), address);
+        Ok(id)
+    }
+
+    pub fn is_deployed(&self, alias: &str) -> bool {
+        self.contracts.contains_key(alias)
+    }
+
+    fn address(&self, alias: &str) -> Result<&Address> {
+        self.contracts
+            .get(alias)
+            .with_context(|| format!("Contract not deployed: {}", alias))
+    }
+
+    pub fn invoke(&self, alias: &str, method: &str, args: &[TestValue]) -> Result<Invocation> {
+        let address = self.address(alias)?;
+        let mut call_args = SorobanVec::<Val>::new(&self.env);
+        for arg in args {
+            call_args.push_back(to_val(&self.env, arg)?);
+        }
+        if !is_symbol(method) {
+            bail!("'{}' is not a valid contract function name", method);
+        }
+        let func = Symbol::new(&self.env, method);
+
+        let mut budget = self.env.cost_estimate().budget();
+        budget.reset_default();
+
+        let outcome = self
+            .env
+            .try_invoke_contract::<Val, soroban_sdk::Error>(address, &func, call_args);
+
+        let cpu_instructions = budget.cpu_instruction_cost();
+        let memory_bytes = budget.memory_bytes_cost();
+
+        let result = match outcome {
+            Ok(Ok(val)) => to_test_value(&self.env, &val).map_err(|e| e.to_string()),
+            Ok(Err(e)) => Err(format!("return value conversion failed: {:?}", e)),
+            Err(Ok(e)) => Err(format!("{:?}", e)),
+            Err(Err(e)) => Err(format!("{:?}", e)),
+        };
+
+        Ok(Invocation {
+            result,
+            events: self.events()?,
+            auths: self.auths()?,
+            cpu_instructions,
+            memory_bytes,
+        })
+    }
+
+    fn events(&self) -> Result<Vec<EmittedEvent>> {
+        let mut out = Vec::new();
+        for (contract, topics, data) in self.env.events().all().iter() {
+            let mut rendered = Vec::new();
+            for topic in topics.iter() {
+                rendered.push(to_test_value(&self.env, &topic)?);
+            }
+            out.push(EmittedEvent {
+                contract: address_string(&self.env, &contract)?,
+                topics: rendered,
+                data: to_test_value(&self.env, &data)?,
+            });
+        }
+        Ok(out)
+    }
+
+    fn auths(&self) -> Result<Vec<AuthRecord>> {
+        fn walk(
+            env: &Env,
+            signer: &str,
+            invocation: &AuthorizedInvocation,
+            out: &mut Vec<AuthRecord>,
+        ) -> Result<()> {
+            if let AuthorizedFunction::Contract((_, func, _)) = &invocation.function {
+                out.push(AuthRecord {
+                    address: signer.to_string(),
+                    function: value_string(&to_test_value(env, &func.to_val())?),
+                });
+            }
+            for sub in &invocation.sub_invocations {
+                walk(env, signer, sub, out)?;
+            }
+            Ok(())
+        }
+
+        let mut out = Vec::new();
+        for (address, invocation) in self.env.auths() {
+            let signer = address_string(&self.env, &address)?;
+            walk(&self.env, &signer, &invocation, &mut out)?;
+        }
+        Ok(out)
+    }
+
+    /// Read a storage entry of a deployed contract; `None` when the key is absent.
+    pub fn storage_get(
+        &self,
+        alias: &str,
+        key: &TestValue,
+        durability: Durability,
+    ) -> Result<Option<TestValue>> {
+        let address = self.address(alias)?;
+        let key = to_val(&self.env, key)?;
+        let value: Option<Val> = self.env.as_contract(address, || {
+            let storage = self.env.storage();
+            match durability {
+                Durability::Instance => storage.instance().get::<Val, Val>(&key),
+                Durability::Persistent => storage.persistent().get::<Val, Val>(&key),
+                Durability::Temporary => storage.temporary().get::<Val, Val>(&key),
+            }
+        });
+        value.map(|v| to_test_value(&self.env, &v)).transpose()
+    }
+
+    /// All storage of a deployed contract, keyed by `durability:key`. Instance
+    /// storage is flattened per key so that the executable hash is not compared.
+    pub fn contract_storage(&self, alias: &str) -> Result<BTreeMap<String, TestValue>> {
+        let owner = address_string(&self.env, self.address(alias)?)?;
+        let mut out = BTreeMap::new();
+
+        for (key, (entry, _)) in self.env.to_ledger_snapshot().ledger_entries {
+            let (LedgerKey::ContractData(key), LedgerEntryData::ContractData(data)) =
+                (key.as_ref(), &entry.data)
+            else {
+                continue;
+            };
+            if key.contract.to_string() != owner {
+                continue;
+            }
+
+            match (&key.key, &data.val) {
+                (ScVal::LedgerKeyContractInstance, ScVal::ContractInstance(instance)) => {
+                    for item in instance.storage.iter().flat_map(|m| m.0.iter()) {
+                        out.insert(
+                            format!("instance:{}", render_key(&item.key)),
+                            from_scval(&item.val),
+                        );
+                    }
+                }
+                (k, v) => {
+                    let durability = match key.durability {
+                        ContractDataDurability::Persistent => "persistent",
+                        ContractDataDurability::Temporary => "temporary",
+                    };
+                    out.insert(format!("{}:{}", durability, render_key(k)), from_scval(v));
+                }
+            }
+        }
+
+        Ok(out)
+    }
+
+    pub fn set_timestamp(&self, timestamp: u64) {
+        self.env.ledger().set_timestamp(timestamp);
+    }
+
+    pub fn set_sequence(&self, sequence: u32) {
+        self.env.ledger().set_sequence_number(sequence);
+    }
+}
+
+fn address_string(env: &Env, address: &Address) -> Result<String> {
+    Ok(value_string(&to_test_value(env, &address.to_val())?))
+}
+
+fn render_key(key: &ScVal) -> String {
+    serde_json::to_string(&from_scval(key)).unwrap_or_else(|_| format!("{:?}", key))
+}
+
+fn value_string(value: &TestValue) -> String {
+    match value {
+        TestValue::String(s) => s.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Value conversion
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn looks_like_strkey(s: &str) -> bool {
+    s.len() == 56 && (s.starts_with('G') || s.starts_with('C'))
+}
+
+/// Parse an account (`G…`) or contract (`C…`) strkey, checksum included.
+fn parse_address(env: &Env, s: &str) -> Result<Address> {
+    match Strkey::from_string(s) {
+        Ok(Strkey::PublicKeyEd25519(_)) | Ok(Strkey::Contract(_)) => Ok(Address::from_str(env, s)),
+        Ok(_) => bail!("'{}' is not an account or contract strkey", s),
+        Err(e) => bail!("'{}' is not a valid strkey: {}", s, e),
+    }
+}
+
+fn is_symbol(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 32 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_int<T: std::str::FromStr>(value: &TestValue, ty: &str) -> Result<T> {
+    let raw = match value {
+        TestValue::Number(n) => n.to_string(),
+        TestValue::String(s) => s.clone(),
+        other => bail!("expected a number for {} but got {:?}", ty, other),
+    };
+    raw.parse::<T>()
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid {}", raw, ty))
+}
+
+fn as_str<'a>(value: &'a TestValue, ty: &str) -> Result<&'a str> {
+    match value {
+        TestValue::String(s) => Ok(s),
+        other => bail!("expected a string for {} but got {:?}", ty, other),
+    }
+}
+
+/// Convert a scenario value into a host `Val`.
+///
+/// Untyped values map to the most common Soroban types: numbers become `i128`,
+/// strkeys become `Address`, other strings become `String`, arrays become `Vec`
+/// and objects become `Map<Symbol, _>`. Use `{ $type, $value }` to pick another type.
+/// A string shaped like a strkey with a bad checksum is an error rather than a
+/// `String`; type it as `string` if that is really meant.
+pub fn to_val(env: &Env, value: &TestValue) -> Result<Val> {
+    Ok(match value {
+        TestValue::Typed(typed) => return typed_to_val(env, typed),
+        TestValue::Null => ().into_val(env),
+        TestValue::Boolean(b) => b.into_val(env),
+        TestValue::Number(n) => (*n as i128).into_val(env),
+        TestValue::String(s) if looks_like_strkey(s) => parse_address(env, s)?.into_val(env),
+        TestValue::String(s) => SorobanString::from_str(env, s).into_val(env),
+        TestValue::Array(items) => {
+            let mut vec = SorobanVec::<Val>::new(env);
+            for item in items {
+                vec.push_back(to_val(env, item)?);
+            }
+            vec.into_val(env)
+        }
+        TestValue::Object(fields) => {
+            let mut map = Map::<Val, Val>::new(env);
+            for (k, v) in fields {
+                if !is_symbol(k) {
+                    bail!("map key '{}' is not a valid symbol", k);
+                }
+                map.set(Symbol::new(env, k).to_val(), to_val(env, v)?);
+            }
+            map.into_val(env)
+        }
+    })
+}
+
+fn typed_to_val(env: &Env, typed: &TypedValue) -> Result<Val> {
+    let value = typed.value.as_ref();
+    Ok(match typed.ty.as_str() {
+        "void" => ().into_val(env),
+        "bool" => match value {
+            TestValue::Boolean(b) => b.into_val(env),
+            other => bail!("expected a boolean but got {:?}", other),
+        },
+        "u32" => parse_int::<u32>(value, "u32")?.into_val(env),
+        "i32" => parse_int::<i32>(value, "i32")?.into_val(env),
+        "u64" => parse_int::<u64>(value, "u64")?.into_val(env),
+        "i64" => parse_int::<i64>(value, "i64")?.into_val(env),
+        "u128" => parse_int::<u128>(value, "u128")?.into_val(env),
+        "i128" => parse_int::<i128>(value, "i128")?.into_val(env),
+        "symbol" => {
+            let s = as_str(value, "symbol")?;
+            if !is_symbol(s) {
+                bail!("'{}' is not a valid symbol", s);
+            }
+            Symbol::new(env, s).to_val()
+        }
+        "string" => SorobanString::from_str(env, as_str(value, "string")?).into_val(env),
+        "address" => parse_address(env, as_str(value, "address")?)?.into_val(env),
+        "bytes" => {
+            let raw = hex::decode(as_str(value, "bytes")?).context("bytes must be hex encoded")?;
+            Bytes::from_slice(env, &raw).into_val(env)
+        }
+        "vec" | "map" => to_val(env, value)?,
+        other => bail!("unsupported argument type: {}", other),
+    })
+}
+
+/// Convert a host `Val` into a scenario value for assertions and reports.
+pub fn to_test_value(env: &Env, val: &Val) -> Result<TestValue> {
+    let sc = ScVal::try_from_val(env, val)
+        .map_err(|e| anyhow::anyhow!("failed to convert host value: {:?}", e))?;
+    Ok(from_scval(&sc))
+}
+
+fn int_value<T: TryInto<i64> + ToString + Copy>(n: T) -> TestValue {
+    match n.try_into() {
+        Ok(v) => TestValue::Number(v),
+        Err(_) => TestValue::String(n.to_string()),
+    }
+}
+
+pub fn from_scval(sc: &ScVal) -> TestValue {
+    match sc {
+        ScVal::Void => TestValue::Null,
+        ScVal::Bool(b) => TestValue::Boolean(*b),
+        ScVal::U32(n) => TestValue::Number(*n as i64),
+        ScVal::I32(n) => TestValue::Number(*n as i64),
+        ScVal::U64(n) => int_value(*n),
+        ScVal::I64(n) => TestValue::Number(*n),
+        ScVal::Timepoint(t) => int_value(t.0),
+        ScVal::Duration(d) => int_value(d.0),
+        ScVal::U128(parts) => int_value(((parts.hi as u128) << 64) | parts.lo as u128),
+        ScVal::I128(parts) => int_value(((parts.hi as i128) << 64) | parts.lo as i128),
+        ScVal::Symbol(s) => TestValue::String(s.0.to_utf8_string_lossy()),
+        ScVal::String(s) => TestValue::String(s.0.to_utf8_string_lossy()),
+        ScVal::Bytes(b) => TestValue::String(hex::encode(b.0.as_slice())),
+        ScVal::Address(a) => TestValue::String(a.to_string()),
+        ScVal::Vec(Some(items)) => TestValue::Array(items.0.iter().map(from_scval).collect()),
+        ScVal::Vec(None) => TestValue::Array(Vec::new()),
+        ScVal::Map(Some(entries)) => TestValue::Object(
+            entries
+                .0
+                .iter()
+                .map(|e| (value_string(&from_scval(&e.key)), from_scval(&e.val)))
+                .collect(),
+        ),
+        ScVal::Map(None) => TestValue::Object(HashMap::new()),
+        other => TestValue::String(format!("{:?}", other)),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// WASM inspection
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn read_leb_u32(bytes: &[u8], pos: &mut usize) -> Result<u32> {
+    let mut result: u32 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes.get(*pos).context("unexpected end of WASM module")?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+        if shift >= 35 {
+            bail!("malformed LEB128 integer in WASM module");
+        }
+    }
+}
+
+/// The `(id, contents)` of every section of a WASM module.
+fn sections(wasm: &[u8]) -> Result<Vec<(u8, &[u8])>> {
+    if wasm.len() < WASM_MAGIC.len() || &wasm[..WASM_MAGIC.len()] != WASM_MAGIC {
+        bail!("not a WASM module");
+    }
+
+    let mut out = Vec::new();
+    let mut pos = WASM_MAGIC.len();
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_leb_u32(wasm, &mut pos)? as usize;
+        let end = pos
+            .checked_add(size)
+            .filter(|end| *end <= wasm.len())
+            .context("WASM section exceeds module size")?;
+        out.push((id, &wasm[pos..end]));
+        pos = end;
+    }
+    Ok(out)
+}
+
+/// Names of the functions exported by a WASM module (the contract's entry points).
+pub fn exported_functions(wasm: &[u8]) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for (id, body) in sections(wasm)? {
+        if id != EXPORT_SECTION_ID {
+            continue;
+        }
+        let mut p = 0;
+        let count = read_leb_u32(body, &mut p)?;
+        for _ in 0..count {
+            let len = read_leb_u32(body, &mut p)? as usize;
+            let name = body.get(p..p + len).context("truncated export name")?;
+            p += len;
+            let kind = *body.get(p).context("truncated export entry")?;
+            p += 1;
+            read_leb_u32(body, &mut p)?;
+            if kind == EXPORT_KIND_FUNC {
+                names.push(String::from_utf8_lossy(name).into_owned());
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// Parameter names of `function` according to the contract spec embedded in
+/// the module; `None` when the spec does not declare it.
+pub fn function_params(wasm: &[u8], function: &str) -> Result<Option<Vec<String>>> {
+    for (id, body) in sections(wasm)? {
+        if id != CUSTOM_SECTION_ID {
+            continue;
+        }
+        let mut p = 0;
+        let len = read_leb_u32(body, &mut p)? as usize;
+        if body.get(p..p + len) != Some(SPEC_SECTION.as_bytes()) {
+            continue;
+        }
+
+        let mut reader = Limited::new(&body[p + len..], Limits::none());
+        for entry in ScSpecEntry::read_xdr_iter(&mut reader) {
+            if let ScSpecEntry::FunctionV0(f) = entry.context("malformed contract spec")? {
+                if f.name.0.as_slice() == function.as_bytes() {
+                    return Ok(Some(
+                        f.inputs
+                            .iter()
+                            .map(|i| i.name.to_utf8_string_lossy())
+                            .collect(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Parameter names of the contract's constructor; empty when it has none.
+pub fn constructor_params(wasm: &[u8]) -> Result<Vec<String>> {
+    Ok(function_params(wasm, CONSTRUCTOR)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::xdr::{Int128Parts, ScMap, ScMapEntry, ScSymbol, ScVec};
+
+    fn module_with_exports(exports: &[(&str, u8)]) -> Vec<u8> {
+        let mut body = vec![exports.len() as u8];
+        for (name, kind) in exports {
+            body.push(name.len() as u8);
+            body.extend_from_slice(name.as_bytes());
+            body.push(*kind);
+            body.push(0);
+        }
+        let mut wasm = WASM_MAGIC.to_vec();
+        // An empty custom section before the export section
+        wasm.extend_from_slice(&[0, 2, 1, b'x']);
+        wasm.push(EXPORT_SECTION_ID);
+        wasm.push(body.len() as u8);
+        wasm.extend(body);
+        wasm
+    }
+
+    #[test]
+    fn exported_functions_reads_export_section() {
+        let wasm = module_with_exports(&[("transfer", 0), ("memory", 2), ("balance", 0)]);
+        assert_eq!(
+            exported_functions(&wasm).unwrap(),
+            vec!["transfer", "balance"]
+        );
+    }
+
+    #[test]
+    fn exported_functions_rejects_non_wasm() {
+        assert!(exported_functions(b"dummy\n").is_err());
+        let mut truncated = module_with_exports(&[("transfer", 0)]);
+        truncated.truncate(truncated.len() - 3);
+        assert!(exported_functions(&truncated).is_err());
+    }
+
+    #[test]
+    fn scval_rendering() {
+        assert_eq!(from_scval(&ScVal::U32(7)), TestValue::Number(7));
+        let big = i128::MAX;
+        let parts = Int128Parts {
+            hi: (big >> 64) as i64,
+            lo: big as u64,
+        };
+        assert_eq!(
+            from_scval(&ScVal::I128(parts)),
+            TestValue::String(big.to_string())
+        );
+
+        let symbol = ScVal::Symbol(ScSymbol("balance".try_into().unwrap()));
+        let vec = ScVal::Vec(Some(ScVec(vec![symbol.clone()].try_into().unwrap())));
+        assert_eq!(
+            from_scval(&vec),
+            TestValue::Array(vec![TestValue::String("balance".into())])
+        );
+
+        let map = ScVal::Map(Some(ScMap(
+            vec![ScMapEntry {
+                key: symbol,
+                val: ScVal::Bool(true),
+            }]
+            .try_into()
+            .unwrap(),
+        )));
+        match from_scval(&map) {
+            TestValue::Object(fields) => assert_eq!(fields["balance"], TestValue::Boolean(true)),
+            other => panic!("expected object, got {:?}", other),
+        }
+    }
+
+    const ADD_WASM: &[u8] = include_bytes!("../tests/fixtures/add_u64.wasm");
+    const CONSTRUCTOR_WASM: &[u8] = include_bytes!("../tests/fixtures/constructor.wasm");
+
+    fn typed(ty: &str, value: TestValue) -> TestValue {
+        TestValue::Typed(TypedValue {
+            ty: ty.into(),
+            value: Box::new(value),
+        })
+    }
+
+    #[test]
+    fn function_params_reads_contract_spec() {
+        assert_eq!(
+            function_params(ADD_WASM, "add").unwrap(),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(function_params(ADD_WASM, "missing").unwrap(), None);
+        assert!(constructor_params(ADD_WASM).unwrap().is_empty());
+        assert_eq!(
+            constructor_params(CONSTRUCTOR_WASM).unwrap(),
+            vec!["init_key", "init_value"]
+        );
+    }
+
+    #[test]
+    fn invokes_real_wasm() {
+        let mut sandbox = Sandbox::new();
+        let id = sandbox.deploy("adder", ADD_WASM, &[]).unwrap();
+        assert!(id.starts_with('C'));
+
+        let args = [
+            typed("u64", TestValue::Number(2)),
+            typed("u64", TestValue::Number(3)),
+        ];
+        let invocation = sandbox.invoke("adder", "add", &args).unwrap();
+        assert_eq!(invocation.result, Ok(TestValue::Number(5)));
+        assert!(invocation.cpu_instructions > 0);
+    }
+
+    #[test]
+    fn invoke_rejects_invalid_function_names() {
+        let mut sandbox = Sandbox::default();
+        sandbox.deploy("adder", ADD_WASM, &[]).unwrap();
+        let too_long = "a".repeat(33);
+        assert!(sandbox.invoke("adder", &too_long, &[]).is_err());
+        assert!(sandbox.invoke("adder", "add-two", &[]).is_err());
+
+        let mut fields = HashMap::new();
+        fields.insert("not a symbol".to_string(), TestValue::Number(1));
+        assert!(to_val(&sandbox.env, &TestValue::Object(fields)).is_err());
+    }
+
+    #[test]
+    fn deploy_checks_constructor_args() {
+        let mut sandbox = Sandbox::new();
+        let err = sandbox.deploy("ctor", CONSTRUCTOR_WASM, &[]).unwrap_err();
+        assert!(err.to_string().contains("init_key, init_value"));
+        assert!(sandbox
+            .deploy("adder", ADD_WASM, &[TestValue::Number(1)])
+            .is_err());
+
+        let args = [
+            typed("u32", TestValue::Number(1)),
+            typed("i64", TestValue::Number(42)),
+        ];
+        sandbox.deploy("ctor", CONSTRUCTOR_WASM, &args).unwrap();
+        let key = TestValue::Array(vec![
+            typed("symbol", TestValue::String("Persistent".into())),
+            typed("u32", TestValue::Number(1)),
+        ]);
+        let invocation = sandbox.invoke("ctor", "get_data", &[key]).unwrap();
+        assert_eq!(invocation.result, Ok(TestValue::Number(42)));
+    }
+
+    #[test]
+    fn addresses_are_checksummed() {
+        let env = Env::default();
+        let valid = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+        assert!(parse_address(&env, valid).is_ok());
+        assert!(to_val(&env, &TestValue::String(valid.into())).is_ok());
+
+        let corrupted = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHG";
+        assert!(parse_address(&env, corrupted).is_err());
+        assert!(to_val(&env, &TestValue::String(corrupted.into())).is_err());
+        assert!(typed_to_val(
+            &env,
+            &TypedValue {
+                ty: "address".into(),
+                value: Box::new(TestValue::String(corrupted.into())),
+            }
+        )
+        .is_err());
+        assert!(Sandbox::new().attach("x", valid, ADD_WASM).is_err());
+    }
+
+    #[test]
+    fn durability_parse() {
+        assert_eq!(
+            "Persistent".parse::<Durability>().unwrap(),
+            Durability::Persistent
+        );
+        assert!("forever".parse::<Durability>().is_err());
+    }
+}
@@ -1,197 +1,327 @@
-use std::collections::HashMap;
-
-use anyhow::{bail, Result};
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SlaMetrics {
-    pub contract_id: String,
-    pub uptime_percentage: f64,
-    pub avg_latency_ms: f64,
-    pub error_rate: f64,
-    pub recorded_at: DateTime<Utc>,
-}
+// cli/src/sla.rs
+// SLA commands. Uptime, latency and error rate are measured by the registry
+// from synthetic probes and indexed invocations; the CLI configures targets
+// and shows compliance, violation history and billing periods.
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SlaViolation {
-    pub metric: String,
-    pub actual: f64,
-    pub target: f64,
-}
+use anyhow::{bail, Context, Result};
+use colored::{ColoredString, Colorize};
+use serde::de::DeserializeOwned;
+use shared::models::{
+    SetSlaTargetsRequest, SlaBillingPeriod, SlaProbeResult, SlaStatus, SlaTargets, SlaViolation,
+    SlaWindowMetrics,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SlaStatus {
-    pub contract_id: String,
-    pub total_records: usize,
-    pub violations: Vec<SlaViolation>,
-    pub penalty_accrued: f64,
-    pub credits_issued: f64,
-    pub compliant: bool,
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder, action: &str) -> Result<T> {
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to {}", action))?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to {}: {}",
+            action,
+            response.text().await.unwrap_or_default()
+        );
+    }
+    response.json().await.context("Invalid response from registry")
 }
 
-#[derive(Debug, Clone)]
-pub struct SlaTargets {
-    pub min_uptime: f64,
-    pub max_latency_ms: f64,
-    pub max_error_rate: f64,
+/// Metric value for display, `—` when the window had no samples
+pub fn format_metric(value: Option<f64>, unit: &str) -> String {
+    match value {
+        Some(v) => format!("{:.2}{}", v, unit),
+        None => "—".to_string(),
+    }
 }
 
-impl Default for SlaTargets {
-    fn default() -> Self {
-        Self {
-            min_uptime: 99.5,
-            max_latency_ms: 200.0,
-            max_error_rate: 1.0,
-        }
+fn compliance_label(compliant: bool) -> ColoredString {
+    if compliant {
+        "COMPLIANT".green().bold()
+    } else {
+        "VIOLATING".red().bold()
     }
 }
 
-const PENALTY_PER_VIOLATION: f64 = 50.0;
-const CREDIT_RATE: f64 = 0.10;
+fn print_targets(targets: &SlaTargets) {
+    println!(
+        "  {}: uptime ≥ {}%, p95 latency ≤ {}ms, error rate ≤ {}%",
+        "Targets".bold(),
+        targets.min_uptime,
+        targets.max_latency_ms,
+        targets.max_error_rate
+    );
+    println!(
+        "  {}: {} min windows, {:.2} per violation, {:.0}% credit",
+        "Billing".bold(),
+        targets.window_minutes,
+        targets.penalty_per_violation,
+        targets.credit_rate * 100.0
+    );
+    match &targets.probe_function {
+        Some(function) if targets.probe_transaction.is_some() => println!(
+            "  {}: {}() every {}s",
+            "Probe".bold(),
+            function,
+            targets.probe_interval_seconds
+        ),
+        _ => println!("  {}: {}", "Probe".bold(), "not configured".yellow()),
+    }
+    if !targets.enabled {
+        println!("  {}", "Monitoring is disabled".yellow());
+    }
+}
 
-pub struct SlaManager {
-    pub targets: SlaTargets,
-    records: HashMap<String, Vec<SlaMetrics>>,
-    penalties: HashMap<String, f64>,
-    credits: HashMap<String, f64>,
+fn print_violation(v: &SlaViolation) {
+    let unit = if v.metric == "latency" { "ms" } else { "%" };
+    println!(
+        "    {} {:<10} {} (target {}{}) {}",
+        v.window_start
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+            .bright_black(),
+        v.metric,
+        format!("{:.2}{}", v.actual, unit).red(),
+        v.target,
+        unit,
+        format!("−{:.2}", v.penalty).bright_black()
+    );
 }
 
-impl SlaManager {
-    pub fn new() -> Self {
-        Self {
-            targets: SlaTargets::default(),
-            records: HashMap::new(),
-            penalties: HashMap::new(),
-            credits: HashMap::new(),
+pub async fn status(api_url: &str, contract_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let status: SlaStatus = send(
+        client.get(format!("{}/api/contracts/{}/sla", api_url, contract_id)),
+        "fetch SLA status",
+    )
+    .await?;
+
+    println!("\n{}", "SLA Compliance".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    println!("  {}: {}", "Contract".bold(), status.contract_id);
+    println!(
+        "  {}: {}",
+        "Status".bold(),
+        compliance_label(status.compliant)
+    );
+    print_targets(&status.targets);
+
+    match &status.latest_window {
+        Some(w) => {
+            println!(
+                "\n  {} ({} → {})",
+                "Latest window".bold(),
+                w.window_start
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                w.window_end
+                    .map(|t| t.format("%H:%M").to_string())
+                    .unwrap_or_default()
+            );
+            println!(
+                "    Uptime: {}  p95 latency: {}  Error rate: {}",
+                format_metric(w.uptime_percentage, "%"),
+                format_metric(w.p95_latency_ms, "ms"),
+                format_metric(w.error_rate, "%")
+            );
+            println!(
+                "    {}",
+                format!(
+                    "{} probe(s), {} invocation(s)",
+                    w.probe_count, w.invocation_count
+                )
+                .bright_black()
+            );
         }
+        None => println!("\n  {}", "No windows evaluated yet.".yellow()),
     }
 
-    pub fn record(&mut self, contract_id: &str, uptime: f64, latency: f64, error_rate: f64) {
-        let m = SlaMetrics {
-            contract_id: contract_id.to_string(),
-            uptime_percentage: uptime,
-            avg_latency_ms: latency,
-            error_rate,
-            recorded_at: Utc::now(),
-        };
-        self.records.entry(contract_id.to_string()).or_default().push(m);
-    }
-
-    pub fn check_violations(metrics: &SlaMetrics, targets: &SlaTargets) -> Vec<SlaViolation> {
-        let mut v = Vec::new();
-        if metrics.uptime_percentage < targets.min_uptime {
-            v.push(SlaViolation {
-                metric: "uptime".into(),
-                actual: metrics.uptime_percentage,
-                target: targets.min_uptime,
-            });
-        }
-        if metrics.avg_latency_ms > targets.max_latency_ms {
-            v.push(SlaViolation {
-                metric: "latency".into(),
-                actual: metrics.avg_latency_ms,
-                target: targets.max_latency_ms,
-            });
-        }
-        if metrics.error_rate > targets.max_error_rate {
-            v.push(SlaViolation {
-                metric: "error_rate".into(),
-                actual: metrics.error_rate,
-                target: targets.max_error_rate,
-            });
-        }
-        v
+    if let Some(period) = &status.current_period {
+        println!(
+            "\n  {} ({} → {})",
+            "Billing period".bold(),
+            period.period_start.format("%Y-%m-%d"),
+            period.period_end.format("%Y-%m-%d")
+        );
+        println!(
+            "    Uptime: {}  Windows: {}/{} compliant  Penalty: {:.2}  Credits: {:.2}",
+            format_metric(period.uptime_percentage(), "%"),
+            period.windows_compliant,
+            period.windows_evaluated,
+            period.penalty_accrued,
+            period.credits_issued
+        );
     }
 
-    pub fn evaluate(&mut self, contract_id: &str) -> Result<SlaStatus> {
-        let history = match self.records.get(contract_id) {
-            Some(h) if !h.is_empty() => h,
-            _ => bail!("no SLA records found for contract: {}", contract_id),
-        };
+    println!(
+        "\n  {}: penalties {:.2}, credits {:.2}",
+        "Lifetime".bold(),
+        status.penalty_accrued,
+        status.credits_issued
+    );
 
-        let latest = history.last().unwrap();
-        let violations = Self::check_violations(latest, &self.targets);
-        let penalty_increment = violations.len() as f64 * PENALTY_PER_VIOLATION;
+    if !status.recent_violations.is_empty() {
+        println!("\n  {}", "Recent violations".bold());
+        for v in &status.recent_violations {
+            print_violation(v);
+        }
+    }
+    println!();
+    Ok(())
+}
 
-        let accrued = self.penalties.entry(contract_id.to_string()).or_insert(0.0);
-        *accrued += penalty_increment;
+pub async fn set_targets(
+    api_url: &str,
+    contract_id: &str,
+    request: SetSlaTargetsRequest,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let targets: SlaTargets = send(
+        client
+            .put(format!("{}/api/contracts/{}/sla/targets", api_url, contract_id))
+            .json(&request),
+        "update SLA targets",
+    )
+    .await?;
 
-        let credit_entry = self.credits.entry(contract_id.to_string()).or_insert(0.0);
-        if violations.is_empty() && *accrued > 0.0 {
-            let credit = *accrued * CREDIT_RATE;
-            *credit_entry += credit;
-        }
+    println!("\n{} SLA targets updated", "✓".green());
+    print_targets(&targets);
+    println!();
+    Ok(())
+}
+
+pub async fn history(api_url: &str, contract_id: &str, limit: usize) -> Result<()> {
+    let client = reqwest::Client::new();
+    let windows: Vec<SlaWindowMetrics> = send(
+        client
+            .get(format!("{}/api/contracts/{}/sla/windows", api_url, contract_id))
+            .query(&[("limit", limit)]),
+        "fetch SLA windows",
+    )
+    .await?;
 
-        Ok(SlaStatus {
-            contract_id: contract_id.to_string(),
-            total_records: history.len(),
-            compliant: violations.is_empty(),
-            violations,
-            penalty_accrued: *self.penalties.get(contract_id).unwrap_or(&0.0),
-            credits_issued: *self.credits.get(contract_id).unwrap_or(&0.0),
-        })
+    println!("\n{}", "SLA Windows".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if windows.is_empty() {
+        println!("  No windows evaluated yet.\n");
+        return Ok(());
     }
+    println!(
+        "  {:<17} {:>9} {:>11} {:>9} {:>7} {:>7}  Status",
+        "Window", "Uptime", "p95", "Errors", "Probes", "Calls"
+    );
+    for w in &windows {
+        println!(
+            "  {:<17} {:>9} {:>11} {:>9} {:>7} {:>7}  {}",
+            w.window_start
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default(),
+            format_metric(w.uptime_percentage, "%"),
+            format_metric(w.p95_latency_ms, "ms"),
+            format_metric(w.error_rate, "%"),
+            w.probe_count,
+            w.invocation_count,
+            compliance_label(w.compliant)
+        );
+    }
+    println!();
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub async fn violations(api_url: &str, contract_id: &str, limit: usize) -> Result<()> {
+    let client = reqwest::Client::new();
+    let violations: Vec<SlaViolation> = send(
+        client
+            .get(format!(
+                "{}/api/contracts/{}/sla/violations",
+                api_url, contract_id
+            ))
+            .query(&[("limit", limit)]),
+        "fetch SLA violations",
+    )
+    .await?;
 
-    #[test]
-    fn test_compliant_metrics() {
-        let mut mgr = SlaManager::new();
-        mgr.record("c1", 99.9, 150.0, 0.5);
-        let status = mgr.evaluate("c1").unwrap();
-        assert!(status.compliant);
-        assert!(status.violations.is_empty());
-        assert_eq!(status.penalty_accrued, 0.0);
-        assert_eq!(status.credits_issued, 0.0);
+    println!("\n{}", "SLA Violations".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if violations.is_empty() {
+        println!("  {} No violations recorded.\n", "✓".green());
+        return Ok(());
     }
-
-    #[test]
-    fn test_single_violation() {
-        let mut mgr = SlaManager::new();
-        mgr.record("c2", 98.0, 150.0, 0.5);
-        let status = mgr.evaluate("c2").unwrap();
-        assert!(!status.compliant);
-        assert_eq!(status.violations.len(), 1);
-        assert_eq!(status.violations[0].metric, "uptime");
-        assert_eq!(status.penalty_accrued, 50.0);
+    for v in &violations {
+        print_violation(v);
     }
+    println!();
+    Ok(())
+}
 
-    #[test]
-    fn test_multiple_violations() {
-        let mut mgr = SlaManager::new();
-        mgr.record("c3", 90.0, 500.0, 5.0);
-        let status = mgr.evaluate("c3").unwrap();
-        assert!(!status.compliant);
-        assert_eq!(status.violations.len(), 3);
-        assert_eq!(status.penalty_accrued, 150.0);
+pub async fn periods(api_url: &str, contract_id: &str, limit: usize) -> Result<()> {
+    let client = reqwest::Client::new();
+    let periods: Vec<SlaBillingPeriod> = send(
+        client
+            .get(format!("{}/api/contracts/{}/sla/periods", api_url, contract_id))
+            .query(&[("limit", limit)]),
+        "fetch SLA billing periods",
+    )
+    .await?;
+
+    println!("\n{}", "SLA Billing Periods".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if periods.is_empty() {
+        println!("  No billing periods yet.\n");
+        return Ok(());
+    }
+    for p in &periods {
+        println!(
+            "  {} {:>9}  {:>4} violation(s)  penalty {:>9.2}  credits {:>8.2}  {}",
+            p.period_start.format("%Y-%m"),
+            format_metric(p.uptime_percentage(), "%"),
+            p.violation_count,
+            p.penalty_accrued,
+            p.credits_issued,
+            if p.closed_at.is_some() {
+                "closed".bright_black()
+            } else {
+                "open".cyan()
+            }
+        );
     }
+    println!();
+    Ok(())
+}
 
-    #[test]
-    fn test_penalty_accrual_across_records() {
-        let mut mgr = SlaManager::new();
-        mgr.record("c4", 98.0, 150.0, 0.5);
-        let s1 = mgr.evaluate("c4").unwrap();
-        assert_eq!(s1.penalty_accrued, 50.0);
+pub async fn probe(api_url: &str, contract_id: &str) -> Result<()> {
+    let client = reqwest::Client::new();
+    let result: SlaProbeResult = send(
+        client.post(format!("{}/api/contracts/{}/sla/probe", api_url, contract_id)),
+        "run SLA probe",
+    )
+    .await?;
 
-        mgr.record("c4", 97.0, 150.0, 0.5);
-        let s2 = mgr.evaluate("c4").unwrap();
-        assert_eq!(s2.penalty_accrued, 100.0);
+    if result.success {
+        println!(
+            "\n{} Probe {}() succeeded in {:.0}ms\n",
+            "✓".green(),
+            result.function_name.as_deref().unwrap_or("?"),
+            result.latency_ms
+        );
+    } else {
+        println!(
+            "\n{} Probe {}() failed: {}\n",
+            "✗".red(),
+            result.function_name.as_deref().unwrap_or("?"),
+            result.error.as_deref().unwrap_or("unknown error").red()
+        );
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
-    fn test_credit_issuance() {
-        let mut mgr = SlaManager::new();
-        mgr.record("c5", 98.0, 150.0, 0.5);
-        mgr.evaluate("c5").unwrap();
-
-        mgr.record("c5", 99.9, 100.0, 0.1);
-        let status = mgr.evaluate("c5").unwrap();
-        assert!(status.compliant);
-        assert_eq!(status.penalty_accrued, 50.0);
-        assert_eq!(status.credits_issued, 5.0);
+    fn metrics_without_samples_render_as_dash() {
+        assert_eq!(format_metric(Some(99.956), "%"), "99.96%");
+        assert_eq!(format_metric(Some(180.0), "ms"), "180.00ms");
+        assert_eq!(format_metric(None, "%"), "—");
     }
 }
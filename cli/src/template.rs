@@ -0,0 +1,97 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Deserialize;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct Template {
+    slug: String,
+    name: String,
+    description: Option<String>,
+    category: String,
+    version: String,
+    install_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CloneResponse {
+    template_slug: String,
+    contract_name: String,
+    source_code: String,
+    version: String,
+}
+
+pub async fn list(api_url: &str, category: Option<&str>) -> Result<()> {
+    let mut request = reqwest::Client::new().get(format!("{}/api/templates", api_url));
+    if let Some(category) = category {
+        request = request.query(&[("category", category)]);
+    }
+    let response = request.send().await.context("Failed to list templates")?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to list templates: {}",
+            response.text().await.unwrap_or_default()
+        );
+    }
+    let templates: Vec<Template> = response.json().await.context("Invalid response")?;
+
+    println!("\n{}", "Contract Templates:".bold().cyan());
+    println!("{}", "=".repeat(80).cyan());
+    if templates.is_empty() {
+        println!("{}", "No templates found.".yellow());
+        return Ok(());
+    }
+    for t in &templates {
+        println!(
+            "\n{} {} {}",
+            t.slug.bold(),
+            format!("v{}", t.version).bright_black(),
+            format!("[{}]", t.category).bright_blue()
+        );
+        println!("   {} · {} installs", t.name, t.install_count);
+        if let Some(description) = &t.description {
+            println!("   {}", description.bright_black());
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Render a template server-side and write it to `<output_dir>/<name>/src/lib.rs`.
+pub async fn clone(api_url: &str, template: &str, name: &str, output_dir: &str) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/templates/{}/clone", api_url, template))
+        .json(&json!({ "name": name }))
+        .send()
+        .await
+        .context("Failed to clone template")?;
+    if !response.status().is_success() {
+        bail!(
+            "Failed to clone template: {}",
+            response.text().await.unwrap_or_default()
+        );
+    }
+    let cloned: CloneResponse = response.json().await.context("Invalid response")?;
+
+    let src_dir = Path::new(output_dir).join(name).join("src");
+    fs::create_dir_all(&src_dir)
+        .with_context(|| format!("Failed to create {}", src_dir.display()))?;
+    let lib = src_dir.join("lib.rs");
+    fs::write(&lib, &cloned.source_code)
+        .with_context(|| format!("Failed to write {}", lib.display()))?;
+
+    println!("{}", "✓ Template cloned!".green().bold());
+    println!(
+        "  {}: {} v{}",
+        "Template".bold(),
+        cloned.template_slug,
+        cloned.version
+    );
+    println!("  {}: {}", "Contract".bold(), cloned.contract_name);
+    println!("  {}: {}\n", "Source".bold(), lib.display());
+
+    Ok(())
+}
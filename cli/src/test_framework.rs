@@ -2,9 +2,11 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+use crate::sandbox::{Durability, Invocation, Sandbox};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestScenario {
     pub name: String,
@@ -24,17 +26,35 @@ pub struct TestStep {
     pub expected_error: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A scenario value. Untagged, so variants are tried in order: a map is only
+/// a `Typed` value when it carries `$type`, which can never be a contract map
+/// key (symbols cannot contain `$`); any other map is an `Object`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum TestValue {
     String(String),
     Number(i64),
     Boolean(bool),
     Array(Vec<TestValue>),
+    Typed(TypedValue),
     Object(HashMap<String, TestValue>),
     Null,
 }
 
+/// Explicitly typed value, e.g. `{ $type: u32, $value: 7 }` or `{ $type: symbol, $value: admin }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypedValue {
+    #[serde(rename = "$type")]
+    pub ty: String,
+    #[serde(rename = "$value", default = "null_value")]
+    pub value: Box<TestValue>,
+}
+
+fn null_value() -> Box<TestValue> {
+    Box::new(TestValue::Null)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestAction {
     pub action: String,
@@ -50,6 +70,12 @@ pub struct Assertion {
     pub field: Option<String>,
     pub expected: TestValue,
     pub operator: Option<String>,
+    /// Storage key for `storage` assertions (defaults to `field` as a symbol)
+    pub key: Option<TestValue>,
+    /// Storage durability for `storage` assertions (instance|persistent|temporary)
+    pub durability: Option<String>,
+    /// Contract alias to inspect, defaults to the step's contract
+    pub contract: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +96,10 @@ pub struct StepResult {
     pub error: Option<String>,
     pub assertions_passed: usize,
     pub assertions_failed: usize,
+    #[serde(default)]
+    pub cpu_instructions: u64,
+    #[serde(default)]
+    pub memory_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,12 +116,14 @@ pub struct TestRunner {
     contract_path: String,
     contracts: HashMap<String, ContractInfo>,
     coverage: CoverageTracker,
+    sandbox: Sandbox,
 }
 
 #[derive(Debug, Clone)]
 struct ContractInfo {
     name: String,
     methods: Vec<String>,
+    wasm: Option<PathBuf>,
 }
 
 struct CoverageTracker {
@@ -111,7 +143,8 @@ impl CoverageTracker {
 
     fn record_contract_call(&mut self, contract: &str, method: &str) {
         self.contracts.insert(contract.to_string());
-        self.methods.insert((contract.to_string(), method.to_string()));
+        self.methods
+            .insert((contract.to_string(), method.to_string()));
     }
 
     fn calculate_metrics(&self, total_methods: usize) -> CoverageMetrics {
@@ -136,56 +169,97 @@ impl CoverageTracker {
 impl TestRunner {
     pub fn new(contract_path: &str) -> Result<Self> {
         let contracts = Self::discover_contracts(contract_path)?;
-        Ok(Self {
+        let mut runner = Self {
             contract_path: contract_path.to_string(),
             contracts,
             coverage: CoverageTracker::new(),
-        })
+            sandbox: Sandbox::new(),
+        };
+
+        // Deploy every discovered WASM up front. Contracts whose constructor
+        // takes arguments are left to an explicit `deploy` action with `args`.
+        let wasm_contracts: Vec<(String, PathBuf)> = runner
+            .contracts
+            .values()
+            .filter_map(|c| c.wasm.clone().map(|w| (c.name.clone(), w)))
+            .collect();
+        for (name, wasm) in wasm_contracts {
+            let bytes = fs::read(&wasm)
+                .with_context(|| format!("Failed to read contract WASM: {}", wasm.display()))?;
+            let params = crate::sandbox::constructor_params(&bytes)
+                .with_context(|| format!("Invalid contract WASM: {}", wasm.display()))?;
+            if !params.is_empty() {
+                log::info!(
+                    "Not deploying {} up front: its constructor takes ({})",
+                    name,
+                    params.join(", ")
+                );
+                continue;
+            }
+            runner.deploy(&name, &wasm, &[])?;
+        }
+
+        Ok(runner)
     }
 
     fn discover_contracts(contract_path: &str) -> Result<HashMap<String, ContractInfo>> {
         let mut contracts = HashMap::new();
         let path = Path::new(contract_path);
 
-        if path.is_file() {
-            let methods = Self::extract_methods(path)?;
-            let name = path
+        let files: Vec<PathBuf> = if path.is_file() {
+            vec![path.to_path_buf()]
+        } else if path.is_dir() {
+            fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        for file in files {
+            let name = file
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("contract")
                 .to_string();
-            contracts.insert(
-                name.clone(),
-                ContractInfo {
-                    name: name.clone(),
-                    methods,
-                },
-            );
-        } else if path.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("rs") {
-                    let methods = Self::extract_methods(&path)?;
-                    let name = path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("contract")
-                        .to_string();
-                    contracts.insert(
-                        name.clone(),
-                        ContractInfo {
-                            name: name.clone(),
-                            methods,
-                        },
-                    );
+
+            let (methods, wasm) = match file.extension().and_then(|s| s.to_str()) {
+                Some("wasm") => (Self::wasm_methods(&file)?, Some(file.clone())),
+                Some("rs") => (Self::extract_methods(&file)?, None),
+                _ => continue,
+            };
+
+            // A compiled WASM takes precedence over a source file of the same name,
+            // but source-discovered methods still count towards coverage.
+            let entry = contracts.entry(name.clone()).or_insert(ContractInfo {
+                name,
+                methods: Vec::new(),
+                wasm: None,
+            });
+            for m in methods {
+                if !entry.methods.contains(&m) {
+                    entry.methods.push(m);
                 }
             }
+            if wasm.is_some() {
+                entry.wasm = wasm;
+            }
         }
 
         Ok(contracts)
     }
 
+    fn wasm_methods(path: &Path) -> Result<Vec<String>> {
+        let wasm = fs::read(path)
+            .with_context(|| format!("Failed to read contract: {}", path.display()))?;
+        let exports = crate::sandbox::exported_functions(&wasm)
+            .with_context(|| format!("Invalid contract WASM: {}", path.display()))?;
+        Ok(exports
+            .into_iter()
+            .filter(|n| !n.starts_with('_'))
+            .collect())
+    }
+
     fn extract_methods(path: &Path) -> Result<Vec<String>> {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read contract: {}", path.display()))?;
@@ -209,6 +283,26 @@ impl TestRunner {
         Ok(methods)
     }
 
+    fn deploy(&mut self, alias: &str, wasm_path: &Path, args: &[TestValue]) -> Result<()> {
+        let wasm = fs::read(wasm_path)
+            .with_context(|| format!("Failed to read contract WASM: {}", wasm_path.display()))?;
+        let id = self.sandbox.deploy(alias, &wasm, args)?;
+        log::debug!("Deployed {} from {} at {}", alias, wasm_path.display(), id);
+
+        let methods = Self::wasm_methods(wasm_path)?;
+        let entry = self
+            .contracts
+            .entry(alias.to_string())
+            .or_insert(ContractInfo {
+                name: alias.to_string(),
+                methods: Vec::new(),
+                wasm: None,
+            });
+        entry.methods = methods;
+        entry.wasm = Some(wasm_path.to_path_buf());
+        Ok(())
+    }
+
     pub async fn run_scenario(&mut self, scenario: TestScenario) -> Result<TestResult> {
         let start_time = Instant::now();
         let mut step_results = Vec::new();
@@ -216,7 +310,7 @@ impl TestRunner {
 
         if let Some(ref setup) = scenario.setup {
             for action in setup {
-                self.execute_action(action).await?;
+                self.execute_action(action)?;
             }
         }
 
@@ -225,26 +319,55 @@ impl TestRunner {
             let mut assertions_passed = 0;
             let mut assertions_failed = 0;
             let mut step_error = None;
+            let mut cpu_instructions = 0;
+            let mut memory_bytes = 0;
+
+            self.coverage
+                .record_contract_call(&step.contract, &step.method);
+
+            match self.execute_step(step) {
+                Ok(invocation) => {
+                    cpu_instructions = invocation.cpu_instructions;
+                    memory_bytes = invocation.memory_bytes;
+
+                    match (&invocation.result, &step.expected_error) {
+                        (Ok(_), Some(_)) => {
+                            step_error = Some("Expected error but none occurred".to_string());
+                            assertions_failed += 1;
+                        }
+                        (Err(e), Some(expected_err)) => {
+                            if e.contains(expected_err.as_str()) {
+                                assertions_passed += 1;
+                            } else {
+                                step_error = Some(format!(
+                                    "Expected error '{}' but got: {}",
+                                    expected_err, e
+                                ));
+                                assertions_failed += 1;
+                            }
+                        }
+                        (Err(e), None) => {
+                            step_error = Some(e.clone());
+                            assertions_failed += 1;
+                        }
+                        (Ok(_), None) => {}
+                    }
 
-            self.coverage.record_contract_call(&step.contract, &step.method);
-
-            let step_result = self.execute_step(step).await;
-            
-            match step_result {
-                Ok(result) => {
-                    if step.expected_error.is_some() {
-                        step_error = Some("Expected error but none occurred".to_string());
-                        assertions_failed += 1;
-                    } else if let Some(ref assertions) = step.assertions {
-                        for assertion in assertions {
-                            match self.check_assertion(assertion, &result) {
+                    if step_error.is_none() {
+                        for assertion in step.assertions.iter().flatten() {
+                            match self.check_assertion(assertion, step, &invocation) {
                                 Ok(true) => assertions_passed += 1,
                                 Ok(false) => {
                                     assertions_failed += 1;
                                     if step_error.is_none() {
                                         step_error = Some(format!(
-                                            "Assertion failed: {}",
-                                            assertion.r#type
+                                            "Assertion failed: {}{}",
+                                            assertion.r#type,
+                                            assertion
+                                                .field
+                                                .as_deref()
+                                                .map(|f| format!(" ({})", f))
+                                                .unwrap_or_default()
                                         ));
                                     }
                                 }
@@ -256,23 +379,15 @@ impl TestRunner {
                                 }
                             }
                         }
-                    } else {
-                        assertions_passed += 1;
-                    }
-                }
-                Err(e) => {
-                    if let Some(ref expected_err) = step.expected_error {
-                        if e.to_string().contains(expected_err) {
+                        if step.assertions.is_none() && step.expected_error.is_none() {
                             assertions_passed += 1;
-                        } else {
-                            step_error = Some(format!("Expected error '{}' but got: {}", expected_err, e));
-                            assertions_failed += 1;
                         }
-                    } else {
-                        step_error = Some(e.to_string());
-                        assertions_failed += 1;
                     }
                 }
+                Err(e) => {
+                    step_error = Some(e.to_string());
+                    assertions_failed += 1;
+                }
             }
 
             step_results.push(StepResult {
@@ -282,6 +397,8 @@ impl TestRunner {
                 error: step_error,
                 assertions_passed,
                 assertions_failed,
+                cpu_instructions,
+                memory_bytes,
             });
 
             if step_results.last().unwrap().error.is_some() {
@@ -292,7 +409,7 @@ impl TestRunner {
 
         if let Some(ref teardown) = scenario.teardown {
             for action in teardown {
-                let _ = self.execute_action(action).await;
+                let _ = self.execute_action(action);
             }
         }
 
@@ -309,7 +426,7 @@ impl TestRunner {
         })
     }
 
-    async fn execute_step(&self, step: &TestStep) -> Result<TestValue> {
+    fn execute_step(&self, step: &TestStep) -> Result<Invocation> {
         let contract_info = self
             .contracts
             .get(&step.contract)
@@ -323,44 +440,96 @@ impl TestRunner {
             ));
         }
 
-        tokio::time::sleep(Duration::from_millis(10)).await;
+        if !self.sandbox.is_deployed(&step.contract) {
+            if contract_info.wasm.is_some() {
+                return Err(anyhow::anyhow!(
+                    "Contract '{}' is not deployed; its constructor takes arguments, \
+                     so deploy it in `setup` with `args`",
+                    step.contract
+                ));
+            }
+            return Err(anyhow::anyhow!(
+                "Contract '{}' has no WASM to execute (found only source in {})",
+                step.contract,
+                self.contract_path
+            ));
+        }
 
-        Ok(TestValue::String(format!(
-            "result_from_{}_{}",
-            step.contract, step.method
-        )))
+        self.sandbox.invoke(
+            &step.contract,
+            &step.method,
+            step.args.as_deref().unwrap_or_default(),
+        )
     }
 
-    async fn execute_action(&mut self, action: &TestAction) -> Result<()> {
+    fn execute_action(&mut self, action: &TestAction) -> Result<()> {
+        let args = action.args.as_deref().unwrap_or_default();
         match action.action.as_str() {
             "deploy" => {
-                tokio::time::sleep(Duration::from_millis(5)).await;
-                Ok(())
+                let alias = action
+                    .contract
+                    .as_deref()
+                    .context("deploy action requires `contract`")?;
+                let wasm = match &action.value {
+                    Some(TestValue::String(path)) => PathBuf::from(path),
+                    _ => self
+                        .contracts
+                        .get(alias)
+                        .and_then(|c| c.wasm.clone())
+                        .with_context(|| format!("No WASM found for contract '{}'", alias))?,
+                };
+                self.deploy(alias, &wasm, args)
             }
             "invoke" => {
-                if let (Some(contract), Some(method)) = (&action.contract, &action.method) {
-                    self.coverage.record_contract_call(contract, method);
+                let (Some(contract), Some(method)) = (&action.contract, &action.method) else {
+                    anyhow::bail!("invoke action requires `contract` and `method`");
+                };
+                self.coverage.record_contract_call(contract, method);
+                let invocation = self.sandbox.invoke(contract, method, args)?;
+                invocation
+                    .result
+                    .map(|_| ())
+                    .map_err(|e| anyhow::anyhow!("{}.{} failed: {}", contract, method, e))
+            }
+            "set" => {
+                let field = action.method.as_deref().unwrap_or("timestamp");
+                let value = action
+                    .value
+                    .as_ref()
+                    .and_then(as_i128)
+                    .context("set action requires a numeric `value`")?;
+                match field {
+                    "timestamp" => self.sandbox.set_timestamp(value as u64),
+                    "sequence" => self.sandbox.set_sequence(value as u32),
+                    other => anyhow::bail!("Unknown ledger field: {}", other),
                 }
-                tokio::time::sleep(Duration::from_millis(5)).await;
                 Ok(())
             }
-            "set" => Ok(()),
             _ => Err(anyhow::anyhow!("Unknown action: {}", action.action)),
         }
     }
 
-    fn check_assertion(&self, assertion: &Assertion, result: &TestValue) -> Result<bool> {
+    fn check_assertion(
+        &self,
+        assertion: &Assertion,
+        step: &TestStep,
+        invocation: &Invocation,
+    ) -> Result<bool> {
         let operator = assertion.operator.as_deref().unwrap_or("eq");
 
         match assertion.r#type.as_str() {
-            "equals" | "eq" => {
-                Ok(self.compare_values(result, &assertion.expected, operator))
-            }
-            "not_equals" | "ne" => {
-                Ok(!self.compare_values(result, &assertion.expected, operator))
-            }
+            "equals" | "eq" => Ok(compare_values(
+                return_value(invocation)?,
+                &assertion.expected,
+                operator,
+            )),
+            "not_equals" | "ne" => Ok(!compare_values(
+                return_value(invocation)?,
+                &assertion.expected,
+                "eq",
+            )),
             "contains" => {
-                if let TestValue::String(s) = result {
+                if let TestValue::String(s) = return_value(invocation)? {
                     if let TestValue::String(expected) = &assertion.expected {
                         Ok(s.contains(expected))
                     } else {
@@ -370,49 +539,153 @@ impl TestRunner {
                     Ok(false)
                 }
             }
-            "greater_than" | "gt" => {
-                if let (TestValue::Number(a), TestValue::Number(b)) = (result, &assertion.expected)
-                {
-                    Ok(*a > *b)
-                } else {
-                    Ok(false)
-                }
+            "greater_than" | "gt" => Ok(compare_values(
+                return_value(invocation)?,
+                &assertion.expected,
+                "gt",
+            )),
+            "less_than" | "lt" => Ok(compare_values(
+                return_value(invocation)?,
+                &assertion.expected,
+                "lt",
+            )),
+            "state" | "storage" => {
+                let contract = assertion.contract.as_deref().unwrap_or(&step.contract);
+                let key = match (&assertion.key, &assertion.field) {
+                    (Some(key), _) => key.clone(),
+                    (None, Some(field)) => TestValue::Typed(TypedValue {
+                        ty: "symbol".into(),
+                        value: Box::new(TestValue::String(field.clone())),
+                    }),
+                    (None, None) => anyhow::bail!("storage assertion requires `key` or `field`"),
+                };
+                let durability = assertion
+                    .durability
+                    .as_deref()
+                    .unwrap_or("persistent")
+                    .parse::<Durability>()?;
+                let actual = self
+                    .sandbox
+                    .storage_get(contract, &key, durability)?
+                    .unwrap_or(TestValue::Null);
+                Ok(compare_values(&actual, &assertion.expected, operator))
             }
-            "less_than" | "lt" => {
-                if let (TestValue::Number(a), TestValue::Number(b)) = (result, &assertion.expected)
-                {
-                    Ok(*a < *b)
-                } else {
-                    Ok(false)
-                }
+            "event" => {
+                let (topics, data) = match &assertion.expected {
+                    TestValue::Object(fields) => {
+                        (fields.get("topics").cloned(), fields.get("data").cloned())
+                    }
+                    other => (Some(other.clone()), None),
+                };
+                let expected_topics = match topics {
+                    Some(TestValue::Array(items)) => items,
+                    Some(single) => vec![single],
+                    None => Vec::new(),
+                };
+                Ok(invocation.events.iter().any(|event| {
+                    expected_topics.len() <= event.topics.len()
+                        && expected_topics
+                            .iter()
+                            .zip(&event.topics)
+                            .all(|(e, a)| compare_values(a, e, "eq"))
+                        && match &data {
+                            Some(d) => compare_values(&event.data, d, "eq"),
+                            None => true,
+                        }
+                }))
             }
-            "state" => {
-                if let Some(ref field) = assertion.field {
-                    Ok(true)
-                } else {
-                    Ok(false)
-                }
+            "auth" => {
+                let signers = match &assertion.expected {
+                    TestValue::Array(items) => items.clone(),
+                    other => vec![other.clone()],
+                };
+                let function = assertion.field.as_deref().unwrap_or(&step.method);
+                Ok(signers.iter().all(|signer| {
+                    invocation.auths.iter().any(|auth| {
+                        compare_values(&TestValue::String(auth.address.clone()), signer, "eq")
+                            && auth.function == function
+                    })
+                }))
             }
-            "event" => Ok(true),
-            _ => Err(anyhow::anyhow!("Unknown assertion type: {}", assertion.r#type)),
+            "resources" | "budget" => {
+                let actual = match assertion.field.as_deref().unwrap_or("cpu_instructions") {
+                    "cpu_instructions" | "cpu" => invocation.cpu_instructions,
+                    "memory_bytes" | "memory" => invocation.memory_bytes,
+                    other => anyhow::bail!("Unknown resource: {}", other),
+                };
+                let operator = assertion.operator.as_deref().unwrap_or("le");
+                Ok(compare_values(
+                    &TestValue::Number(actual as i64),
+                    &assertion.expected,
+                    operator,
+                ))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Unknown assertion type: {}",
+                assertion.r#type
+            )),
         }
     }
+}
 
-    fn compare_values(&self, a: &TestValue, b: &TestValue, op: &str) -> bool {
-        match (a, b) {
-            (TestValue::String(s1), TestValue::String(s2)) => match op {
-                "eq" => s1 == s2,
-                "ne" => s1 != s2,
-                _ => s1 == s2,
-            },
-            (TestValue::Number(n1), TestValue::Number(n2)) => match op {
-                "eq" => n1 == n2,
-                "ne" => n1 != n2,
-                _ => n1 == n2,
+fn return_value(invocation: &Invocation) -> Result<&TestValue> {
+    invocation
+        .result
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("invocation failed: {}", e))
+}
+
+/// Strip explicit type annotations so expected values compare against rendered results.
+fn untyped(value: &TestValue) -> &TestValue {
+    match value {
+        TestValue::Typed(typed) => untyped(&typed.value),
+        other => other,
+    }
+}
+
+fn as_i128(value: &TestValue) -> Option<i128> {
+    match untyped(value) {
+        TestValue::Number(n) => Some(*n as i128),
+        TestValue::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+pub(crate) fn values_equal(a: &TestValue, b: &TestValue) -> bool {
+    match (untyped(a), untyped(b)) {
+        (TestValue::Array(x), TestValue::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(a, b)| values_equal(a, b))
+        }
+        (TestValue::Object(x), TestValue::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(k, v)| y.get(k).is_some_and(|w| values_equal(v, w)))
+        }
+        (
+            a @ (TestValue::Number(_) | TestValue::String(_)),
+            b @ (TestValue::Number(_) | TestValue::String(_)),
+        ) => match (as_i128(a), as_i128(b)) {
+            (Some(x), Some(y)) => x == y,
+            _ => a == b,
+        },
+        (a, b) => a == b,
+    }
+}
+
+fn compare_values(actual: &TestValue, expected: &TestValue, op: &str) -> bool {
+    match op {
+        "eq" => values_equal(actual, expected),
+        "ne" => !values_equal(actual, expected),
+        "gt" | "gte" | "ge" | "lt" | "lte" | "le" => match (as_i128(actual), as_i128(expected)) {
+            (Some(a), Some(b)) => match op {
+                "gt" => a > b,
+                "gte" | "ge" => a >= b,
+                "lt" => a < b,
+                _ => a <= b,
             },
-            (TestValue::Boolean(b1), TestValue::Boolean(b2)) => b1 == b2,
             _ => false,
-        }
+        },
+        _ => values_equal(actual, expected),
     }
 }
 
@@ -431,48 +704,151 @@ pub fn load_test_scenario(path: &Path) -> Result<TestScenario> {
     }
 }
 
-pub fn generate_junit_xml(results: &[TestResult], output_path: &Path) -> Result<()> {
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Render results as JUnit XML: one `<testsuite>` per scenario, one `<testcase>` per step.
+pub fn render_junit_xml(results: &[TestResult]) -> String {
     let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
-    xml.push_str("<testsuites>\n");
 
-    let total_tests = results.len();
-    let total_failures = results.iter().filter(|r| !r.passed).count();
+    let total_tests: usize = results.iter().map(|r| r.steps.len()).sum();
+    let total_failures: usize = results
+        .iter()
+        .map(|r| r.steps.iter().filter(|s| !s.passed).count())
+        .sum();
     let total_time: f64 = results.iter().map(|r| r.duration.as_secs_f64()).sum();
 
     xml.push_str(&format!(
-        "  <testsuite name=\"contract-tests\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+        "<testsuites name=\"contract-tests\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
         total_tests, total_failures, total_time
     ));
 
     for result in results {
-        let _status = if result.passed { "pass" } else { "fail" };
+        let failures = result.steps.iter().filter(|s| !s.passed).count();
         xml.push_str(&format!(
-            "    <testcase name=\"{}\" classname=\"contract-test\" time=\"{:.3}\">\n",
-            result.scenario,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.3}\">\n",
+            xml_escape(&result.scenario),
+            result.steps.len(),
+            failures,
             result.duration.as_secs_f64()
         ));
+        xml.push_str("    <properties>\n");
+        xml.push_str(&format!(
+            "      <property name=\"coverage_percent\" value=\"{:.2}\"/>\n",
+            result.coverage.coverage_percent
+        ));
+        xml.push_str("    </properties>\n");
 
-        if !result.passed {
+        for step in &result.steps {
             xml.push_str(&format!(
-                "      <failure message=\"{}\"/>\n",
-                result
-                    .error
-                    .as_deref()
-                    .unwrap_or("Test failed")
-                    .replace('"', "&quot;")
-                    .replace('<', "&lt;")
-                    .replace('>', "&gt;")
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+                xml_escape(&step.step_name),
+                xml_escape(&result.scenario),
+                step.duration.as_secs_f64()
             ));
+
+            if !step.passed {
+                xml.push_str(&format!(
+                    "      <failure message=\"{}\"/>\n",
+                    xml_escape(step.error.as_deref().unwrap_or("Step failed"))
+                ));
+            }
+
+            xml.push_str(&format!(
+                "      <system-out>cpu_instructions={} memory_bytes={} assertions={}/{}</system-out>\n",
+                step.cpu_instructions,
+                step.memory_bytes,
+                step.assertions_passed,
+                step.assertions_passed + step.assertions_failed
+            ));
+            xml.push_str("    </testcase>\n");
         }
 
-        xml.push_str("    </testcase>\n");
+        xml.push_str("  </testsuite>\n");
     }
 
-    xml.push_str("  </testsuite>\n");
     xml.push_str("</testsuites>\n");
+    xml
+}
 
-    fs::write(output_path, xml)
+pub fn generate_junit_xml(results: &[TestResult], output_path: &Path) -> Result<()> {
+    fs::write(output_path, render_junit_xml(results))
         .with_context(|| format!("Failed to write JUnit XML: {}", output_path.display()))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    #[test]
+    fn typed_values_need_an_explicit_tag() {
+        let object: TestValue =
+            serde_json::from_value(serde_json::json!({ "type": "u32", "value": 7 })).unwrap();
+        match object {
+            TestValue::Object(fields) => {
+                assert_eq!(fields["type"], TestValue::String("u32".into()));
+                assert_eq!(fields["value"], TestValue::Number(7));
+            }
+            other => panic!("expected object, got {:?}", other),
+        }
+
+        let typed: TestValue =
+            serde_json::from_value(serde_json::json!({ "$type": "u32", "$value": 7 })).unwrap();
+        assert_eq!(
+            typed,
+            TestValue::Typed(TypedValue {
+                ty: "u32".into(),
+                value: Box::new(TestValue::Number(7)),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn runs_scenario_against_wasm_fixture() {
+        let mut runner = TestRunner::new(FIXTURES).unwrap();
+        let scenario: TestScenario = serde_yaml::from_str(
+            r#"
+name: fixtures
+setup:
+  - action: deploy
+    contract: constructor
+    args:
+      - { $type: u32, $value: 1 }
+      - { $type: i64, $value: 42 }
+steps:
+  - name: add
+    contract: add_u64
+    method: add
+    args:
+      - { $type: u64, $value: 2 }
+      - { $type: u64, $value: 3 }
+    assertions:
+      - type: equals
+        expected: 5
+  - name: constructor stored its value
+    contract: constructor
+    method: get_data
+    args:
+      - [{ $type: symbol, $value: Persistent }, { $type: u32, $value: 1 }]
+    assertions:
+      - type: equals
+        expected: 42
+"#,
+        )
+        .unwrap();
+
+        let result = runner.run_scenario(scenario).await.unwrap();
+        assert!(result.passed, "{:?}", result.error);
+        assert_eq!(result.steps.len(), 2);
+        assert!(result.steps[0].cpu_instructions > 0);
+    }
+}
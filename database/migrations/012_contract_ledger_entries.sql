@@ -0,0 +1,33 @@
+-- Indexed contract ledger state
+-- Contract instance, persistent and temporary entries as last seen by the indexer.
+-- Keys and entries are stored as base64 XDR (LedgerKey / LedgerEntry) so that
+-- consumers can rebuild an exact ledger snapshot without lossy conversions.
+
+CREATE TYPE ledger_entry_durability AS ENUM ('instance', 'persistent', 'temporary');
+
+CREATE TABLE contract_ledger_entries (
+    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id          UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    durability           ledger_entry_durability NOT NULL,
+    key_xdr              TEXT NOT NULL,
+    entry_xdr            TEXT NOT NULL,
+    -- Human-readable rendering of the storage key, for search and display only
+    key_display          TEXT,
+    live_until_ledger    INTEGER,
+    last_modified_ledger INTEGER NOT NULL,
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(contract_id, key_xdr)
+);
+
+CREATE INDEX idx_contract_ledger_entries_contract ON contract_ledger_entries(contract_id);
+CREATE INDEX idx_contract_ledger_entries_durability ON contract_ledger_entries(contract_id, durability);
+
+-- Ledger the indexer has processed up to, per network
+CREATE TABLE indexer_ledger_state (
+    network          network_type PRIMARY KEY,
+    ledger_sequence  INTEGER NOT NULL,
+    ledger_timestamp BIGINT NOT NULL,
+    protocol_version INTEGER NOT NULL,
+    network_passphrase TEXT NOT NULL,
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
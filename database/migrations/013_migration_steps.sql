@@ -0,0 +1,30 @@
+-- Migration plans
+-- Ordered steps attached to a migration record (upgrade WASM, call the
+-- contract's migrate function, verify invariants). Each step records its own
+-- outcome so an interrupted or failed run can resume from the first step that
+-- has not succeeded.
+
+ALTER TYPE migration_status ADD VALUE IF NOT EXISTS 'in_progress';
+
+CREATE TYPE migration_step_kind AS ENUM ('upgrade_wasm', 'invoke', 'verify');
+CREATE TYPE migration_step_status AS ENUM ('pending', 'running', 'succeeded', 'failed', 'skipped');
+
+CREATE TABLE migration_steps (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    migration_id  UUID NOT NULL REFERENCES migrations(id) ON DELETE CASCADE,
+    position      INTEGER NOT NULL CHECK (position >= 0),
+    kind          migration_step_kind NOT NULL,
+    function_name VARCHAR(255),
+    -- [{ "name": "...", "value": ... }] in call order
+    args          JSONB NOT NULL DEFAULT '[]',
+    -- Expected return value for verify steps
+    expected      JSONB,
+    status        migration_step_status NOT NULL DEFAULT 'pending',
+    output        JSONB,
+    error         TEXT,
+    started_at    TIMESTAMPTZ,
+    completed_at  TIMESTAMPTZ,
+    UNIQUE (migration_id, position)
+);
+
+CREATE INDEX idx_migration_steps_migration_id ON migration_steps(migration_id);
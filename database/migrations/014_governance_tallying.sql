@@ -0,0 +1,23 @@
+-- Token-weighted and quadratic governance tallying
+-- Voting power is taken from a designated token contract's balances as of
+-- the proposal's voting_starts_at. The indexer appends a row whenever a
+-- holder's balance changes, so the balance at any point in time is the latest
+-- row at or before it.
+
+ALTER TABLE governance_proposals
+    ADD COLUMN token_contract_id UUID REFERENCES contracts(id),
+    ADD COLUMN closed_at TIMESTAMPTZ,
+    ADD COLUMN final_tally JSONB;
+
+CREATE TABLE token_balance_history (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    holder            VARCHAR(56) NOT NULL,
+    balance           NUMERIC(39, 0) NOT NULL CHECK (balance >= 0),
+    ledger_sequence   BIGINT NOT NULL,
+    ledger_closed_at  TIMESTAMPTZ NOT NULL,
+    UNIQUE (token_contract_id, holder, ledger_sequence)
+);
+
+CREATE INDEX idx_token_balance_history_lookup
+    ON token_balance_history(token_contract_id, holder, ledger_closed_at DESC);
@@ -0,0 +1,47 @@
+-- Timelocked proposal execution
+-- Governance and multisig proposals carry a typed action (upgrade WASM, set
+-- config version, change maturity, transfer ownership). Once a proposal
+-- passes, its action is queued with an ETA of `execution_delay_hours` after
+-- approval. Until the ETA it can be cancelled; after it, the executor applies
+-- the action and records the outcome in contract_audit_log.
+
+ALTER TYPE audit_action_type ADD VALUE IF NOT EXISTS 'proposal_executed';
+ALTER TYPE audit_action_type ADD VALUE IF NOT EXISTS 'proposal_execution_failed';
+
+-- { "kind": "upgrade_wasm", "wasm_hash": "...", "version": "1.2.0" }, etc.
+-- NULL on governance proposals that only record a decision.
+ALTER TABLE governance_proposals
+    ADD COLUMN action JSONB;
+
+ALTER TABLE deploy_proposals
+    ADD COLUMN action JSONB,
+    ADD COLUMN execution_delay_hours INTEGER NOT NULL DEFAULT 0 CHECK (execution_delay_hours >= 0);
+
+CREATE TYPE proposal_source AS ENUM ('governance', 'multisig');
+CREATE TYPE proposal_execution_status AS ENUM ('queued', 'executing', 'executed', 'failed', 'cancelled');
+
+CREATE TABLE proposal_executions (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source        proposal_source NOT NULL,
+    proposal_id   UUID NOT NULL,
+    -- Registry contract the action applies to; NULL when the proposal
+    -- targets a contract that is not registered yet
+    contract_id   UUID REFERENCES contracts(id) ON DELETE CASCADE,
+    action        JSONB,
+    status        proposal_execution_status NOT NULL DEFAULT 'queued',
+    queued_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Earliest execution time; cancellation is only possible before it
+    eta           TIMESTAMPTZ NOT NULL,
+    cancelled_at  TIMESTAMPTZ,
+    cancelled_by  VARCHAR(255),
+    cancel_reason TEXT,
+    executed_at   TIMESTAMPTZ,
+    executed_by   VARCHAR(255),
+    result        JSONB,
+    error         TEXT,
+    audit_log_id  UUID,
+    UNIQUE (source, proposal_id)
+);
+
+CREATE INDEX idx_proposal_executions_status_eta ON proposal_executions(status, eta);
+CREATE INDEX idx_proposal_executions_contract_id ON proposal_executions(contract_id);
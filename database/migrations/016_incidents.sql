@@ -0,0 +1,59 @@
+-- Incident management
+-- Incidents move through detected → responding → contained → recovered →
+-- post_review. Every state change and note is kept as a timeline entry.
+-- Contracts that depend on the incident's contract (directly or
+-- transitively) are linked when the incident is triggered. While
+-- `breaker_engaged` is set, maintenance_middleware rejects writes to the
+-- incident's contract and to every linked contract.
+
+CREATE TYPE incident_severity AS ENUM ('critical', 'high', 'medium', 'low');
+CREATE TYPE incident_state AS ENUM ('detected', 'responding', 'contained', 'recovered', 'post_review');
+CREATE TYPE incident_impact AS ENUM ('origin', 'dependent');
+
+CREATE TABLE incidents (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id     UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    title           VARCHAR(255) NOT NULL,
+    description     TEXT,
+    severity        incident_severity NOT NULL,
+    state           incident_state NOT NULL DEFAULT 'detected',
+    breaker_engaged BOOLEAN NOT NULL DEFAULT FALSE,
+    triggered_by    VARCHAR(255) NOT NULL,
+    triggered_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- First time each state was entered
+    responding_at   TIMESTAMPTZ,
+    contained_at    TIMESTAMPTZ,
+    recovered_at    TIMESTAMPTZ,
+    post_review_at  TIMESTAMPTZ,
+    root_cause      TEXT,
+    resolution      TEXT,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_incidents_contract_id ON incidents(contract_id);
+CREATE INDEX idx_incidents_state ON incidents(state);
+CREATE INDEX idx_incidents_breaker ON incidents(contract_id) WHERE breaker_engaged;
+
+CREATE TABLE incident_updates (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    incident_id UUID NOT NULL REFERENCES incidents(id) ON DELETE CASCADE,
+    -- Both NULL for plain notes
+    from_state  incident_state,
+    to_state    incident_state,
+    message     TEXT NOT NULL,
+    author      VARCHAR(255) NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_incident_updates_incident_id ON incident_updates(incident_id, created_at);
+
+CREATE TABLE incident_affected_contracts (
+    incident_id UUID NOT NULL REFERENCES incidents(id) ON DELETE CASCADE,
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    impact      incident_impact NOT NULL,
+    -- Dependency hops from the origin contract (0 for the origin itself)
+    depth       INTEGER NOT NULL CHECK (depth >= 0),
+    PRIMARY KEY (incident_id, contract_id)
+);
+
+CREATE INDEX idx_incident_affected_contract_id ON incident_affected_contracts(contract_id);
@@ -0,0 +1,111 @@
+-- SLA monitoring service
+-- Replaces hand-entered SLA numbers with measurements. Uptime comes from
+-- synthetic probes (simulated calls to a read-only contract function),
+-- error rate from indexed invocation results, latency from both. The API's
+-- SLA monitor evaluates fixed windows against each contract's targets and
+-- accrues penalties and credits per monthly billing period.
+
+CREATE TABLE sla_targets (
+    contract_id               UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    min_uptime                DOUBLE PRECISION NOT NULL DEFAULT 99.5
+                              CHECK (min_uptime BETWEEN 0 AND 100),
+    -- Compared against the p95 latency of the window
+    max_latency_ms            DOUBLE PRECISION NOT NULL DEFAULT 200.0 CHECK (max_latency_ms > 0),
+    max_error_rate            DOUBLE PRECISION NOT NULL DEFAULT 1.0
+                              CHECK (max_error_rate BETWEEN 0 AND 100),
+    window_minutes            INTEGER NOT NULL DEFAULT 60 CHECK (window_minutes BETWEEN 5 AND 1440),
+    penalty_per_violation     DOUBLE PRECISION NOT NULL DEFAULT 50.0 CHECK (penalty_per_violation >= 0),
+    -- Share of a period's penalties credited back when the period as a whole met its targets
+    credit_rate               DOUBLE PRECISION NOT NULL DEFAULT 0.10 CHECK (credit_rate BETWEEN 0 AND 1),
+    probe_function            VARCHAR(255),
+    -- Base64 TransactionEnvelope invoking probe_function, sent to simulateTransaction
+    probe_transaction         TEXT,
+    probe_interval_seconds    INTEGER NOT NULL DEFAULT 300 CHECK (probe_interval_seconds >= 30),
+    enabled                   BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at                TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at                TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_sla_targets_updated_at BEFORE UPDATE ON sla_targets
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Invocation results written by the indexer (or pushed through the API)
+CREATE TABLE contract_invocations (
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id      UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    function_name    VARCHAR(255) NOT NULL,
+    success          BOOLEAN NOT NULL,
+    latency_ms       DOUBLE PRECISION CHECK (latency_ms >= 0),
+    error            TEXT,
+    transaction_hash VARCHAR(64),
+    ledger_sequence  BIGINT,
+    invoked_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_contract_invocations_contract_time ON contract_invocations(contract_id, invoked_at);
+CREATE UNIQUE INDEX idx_contract_invocations_tx
+    ON contract_invocations(contract_id, transaction_hash) WHERE transaction_hash IS NOT NULL;
+
+CREATE TABLE sla_probe_results (
+    id            UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id   UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    function_name VARCHAR(255),
+    success       BOOLEAN NOT NULL,
+    latency_ms    DOUBLE PRECISION NOT NULL CHECK (latency_ms >= 0),
+    error         TEXT,
+    probed_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_sla_probe_results_contract_time ON sla_probe_results(contract_id, probed_at);
+
+-- sla_metrics rows become one evaluated window each
+ALTER TABLE sla_metrics
+    ADD COLUMN window_start     TIMESTAMPTZ,
+    ADD COLUMN window_end       TIMESTAMPTZ,
+    ADD COLUMN probe_count      INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN invocation_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN compliant        BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Metrics that had no samples in the window are stored as NULL
+    ALTER COLUMN uptime_percentage DROP NOT NULL,
+    ALTER COLUMN avg_latency_ms DROP NOT NULL,
+    ALTER COLUMN error_rate DROP NOT NULL;
+
+ALTER TABLE sla_metrics RENAME COLUMN avg_latency_ms TO p95_latency_ms;
+
+CREATE UNIQUE INDEX idx_sla_metrics_window ON sla_metrics(contract_id, window_start);
+
+CREATE TABLE sla_billing_periods (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id       UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    period_start      TIMESTAMPTZ NOT NULL,
+    period_end        TIMESTAMPTZ NOT NULL,
+    windows_evaluated INTEGER NOT NULL DEFAULT 0,
+    windows_compliant INTEGER NOT NULL DEFAULT 0,
+    violation_count   INTEGER NOT NULL DEFAULT 0,
+    -- Probe-weighted uptime across the period
+    probe_count       INTEGER NOT NULL DEFAULT 0,
+    probe_successes   INTEGER NOT NULL DEFAULT 0,
+    penalty_accrued   DOUBLE PRECISION NOT NULL DEFAULT 0.0,
+    credits_issued    DOUBLE PRECISION NOT NULL DEFAULT 0.0,
+    closed_at         TIMESTAMPTZ,
+    UNIQUE (contract_id, period_start)
+);
+
+CREATE INDEX idx_sla_billing_periods_open ON sla_billing_periods(period_end) WHERE closed_at IS NULL;
+
+CREATE TABLE sla_violations (
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id       UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    billing_period_id UUID NOT NULL REFERENCES sla_billing_periods(id) ON DELETE CASCADE,
+    metric            VARCHAR(32) NOT NULL,
+    actual            DOUBLE PRECISION NOT NULL,
+    target            DOUBLE PRECISION NOT NULL,
+    window_start      TIMESTAMPTZ NOT NULL,
+    window_end        TIMESTAMPTZ NOT NULL,
+    penalty           DOUBLE PRECISION NOT NULL,
+    detected_at       TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_sla_violations_contract_time ON sla_violations(contract_id, window_start DESC);
+
+ALTER TABLE sla_status ADD COLUMN last_window_end TIMESTAMPTZ;
@@ -0,0 +1,102 @@
+-- Security patch campaigns
+-- A campaign rolls one security patch out to every vulnerable contract in
+-- percentage waves. Contracts running the vulnerable WASM are direct
+-- targets and are scheduled wave by wave; contracts that depend on them are
+-- tracked as dependents and resolve once their upstream is patched. Between
+-- waves a health gate compares performance metrics before and after each
+-- upgrade and pauses the campaign on regressions or anomalies.
+
+-- The CLI has always looked vulnerable contracts up by target_version as a
+-- WASM hash; keep that working while allowing an explicit hash
+ALTER TABLE security_patches ADD COLUMN vulnerable_wasm_hash VARCHAR(64);
+UPDATE security_patches SET vulnerable_wasm_hash = target_version
+ WHERE target_version ~ '^[0-9a-fA-F]{64}$';
+
+CREATE TYPE patch_campaign_status AS ENUM ('pending', 'rolling_out', 'paused', 'completed', 'aborted');
+CREATE TYPE patch_target_status AS ENUM ('pending', 'scheduled', 'applied', 'failed', 'skipped', 'resolved');
+CREATE TYPE patch_target_impact AS ENUM ('direct', 'dependent');
+
+CREATE TABLE patch_campaigns (
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    patch_id                 UUID NOT NULL REFERENCES security_patches(id) ON DELETE CASCADE,
+    status                   patch_campaign_status NOT NULL DEFAULT 'pending',
+    -- Cumulative share of direct targets covered after each wave, ending at 100
+    wave_percentages         INTEGER[] NOT NULL DEFAULT '{5,25,50,100}',
+    -- 0 before the first wave starts; waves are numbered from 1
+    current_wave             INTEGER NOT NULL DEFAULT 0,
+    wave_started_at          TIMESTAMPTZ,
+    -- Health gate
+    max_error_rate_increase  DOUBLE PRECISION NOT NULL DEFAULT 1.0,
+    max_latency_increase_pct DOUBLE PRECISION NOT NULL DEFAULT 25.0,
+    min_soak_minutes         INTEGER NOT NULL DEFAULT 60,
+    min_applied_fraction     DOUBLE PRECISION NOT NULL DEFAULT 0.8
+                             CHECK (min_applied_fraction > 0 AND min_applied_fraction <= 1),
+    auto_advance             BOOLEAN NOT NULL DEFAULT TRUE,
+    paused_reason            TEXT,
+    -- Resuming restarts health measurement so a resolved anomaly does not re-pause
+    gate_reset_at            TIMESTAMPTZ,
+    created_by               VARCHAR(255) NOT NULL,
+    created_at               TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at               TIMESTAMPTZ,
+    completed_at             TIMESTAMPTZ,
+    updated_at               TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_patch_campaigns_patch_id ON patch_campaigns(patch_id);
+CREATE INDEX idx_patch_campaigns_active ON patch_campaigns(status) WHERE status = 'rolling_out';
+-- One live campaign per patch
+CREATE UNIQUE INDEX idx_patch_campaigns_live ON patch_campaigns(patch_id)
+    WHERE status IN ('pending', 'rolling_out', 'paused');
+
+CREATE TABLE patch_campaign_targets (
+    campaign_id         UUID NOT NULL REFERENCES patch_campaigns(id) ON DELETE CASCADE,
+    contract_id         UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    impact              patch_target_impact NOT NULL,
+    -- Dependency hops from the nearest direct target (0 for direct targets)
+    depth               INTEGER NOT NULL DEFAULT 0,
+    via_contract_id     UUID REFERENCES contracts(id) ON DELETE SET NULL,
+    -- Wave the direct target is scheduled in; NULL for dependents
+    wave                INTEGER,
+    status              patch_target_status NOT NULL DEFAULT 'pending',
+    scheduled_at        TIMESTAMPTZ,
+    applied_at          TIMESTAMPTZ,
+    -- Performance before the upgrade, captured when it is applied
+    baseline_error_rate DOUBLE PRECISION,
+    baseline_latency_ms DOUBLE PRECISION,
+    failure_reason      TEXT,
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (campaign_id, contract_id)
+);
+
+CREATE INDEX idx_patch_campaign_targets_wave ON patch_campaign_targets(campaign_id, wave);
+CREATE INDEX idx_patch_campaign_targets_contract ON patch_campaign_targets(contract_id);
+
+-- Decision log: wave starts, gate results, pauses, resumes
+CREATE TABLE patch_campaign_events (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    campaign_id UUID NOT NULL REFERENCES patch_campaigns(id) ON DELETE CASCADE,
+    wave        INTEGER,
+    kind        VARCHAR(50) NOT NULL,
+    message     TEXT NOT NULL,
+    actor       VARCHAR(255),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_patch_campaign_events_campaign ON patch_campaign_events(campaign_id, created_at);
+
+-- Outbox of notifications for publishers; delivery channels read from here
+CREATE TABLE publisher_notifications (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    contract_id  UUID REFERENCES contracts(id) ON DELETE CASCADE,
+    kind         VARCHAR(50) NOT NULL,
+    subject      VARCHAR(255) NOT NULL,
+    body         TEXT NOT NULL,
+    payload      JSONB,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    read_at      TIMESTAMPTZ
+);
+
+CREATE INDEX idx_publisher_notifications_publisher ON publisher_notifications(publisher_id, created_at DESC);
+CREATE INDEX idx_publisher_notifications_undelivered ON publisher_notifications(created_at) WHERE delivered_at IS NULL;
@@ -0,0 +1,50 @@
+-- Canary release controller
+-- Canary releases advance through their rollout stages automatically when
+-- the recorded metrics meet the release's success criteria, and roll back
+-- to the stable deployment when they do not. Every transition, and every
+-- change in why a stage is being held, is written to a decision log.
+
+-- The controller decides rollbacks now; the trigger bypassed the decision
+-- log and ended rolled-back releases on the 'complete' stage
+DROP TRIGGER IF EXISTS canary_auto_rollback_trigger ON canary_releases;
+DROP FUNCTION IF EXISTS check_canary_error_rate();
+
+-- Success criteria, evaluated per stage
+ALTER TABLE canary_releases
+    ADD COLUMN max_latency_increase_pct DOUBLE PRECISION NOT NULL DEFAULT 20.0,
+    ADD COLUMN max_event_anomalies      INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN min_stage_minutes        INTEGER NOT NULL DEFAULT 30,
+    ADD COLUMN min_stage_requests       INTEGER NOT NULL DEFAULT 100,
+    -- Traffic share for stage_1..stage_4
+    ADD COLUMN stage_percentages        INTEGER[] NOT NULL DEFAULT '{1,10,50,100}'
+        CHECK (array_length(stage_percentages, 1) = 4),
+    ADD COLUMN auto_advance             BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN stage_started_at         TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_canary_releases_live
+    ON canary_releases(contract_id) WHERE status IN ('pending', 'active', 'paused');
+
+-- Metrics are recorded for both sides so latency can be compared with stable
+CREATE TYPE canary_variant AS ENUM ('canary', 'stable');
+ALTER TABLE canary_metrics ADD COLUMN variant canary_variant NOT NULL DEFAULT 'canary';
+CREATE INDEX idx_canary_metrics_variant ON canary_metrics(canary_id, variant, timestamp);
+
+CREATE TYPE canary_decision_kind AS ENUM (
+    'hold', 'advance', 'promote', 'rollback',
+    'manual_advance', 'manual_rollback', 'pause', 'resume'
+);
+
+CREATE TABLE canary_decisions (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    canary_id   UUID NOT NULL REFERENCES canary_releases(id) ON DELETE CASCADE,
+    stage       rollout_stage NOT NULL,
+    percentage  INTEGER NOT NULL,
+    kind        canary_decision_kind NOT NULL,
+    reason      TEXT NOT NULL,
+    -- Each criterion with its observed value, threshold and outcome
+    criteria    JSONB NOT NULL DEFAULT '[]',
+    actor       VARCHAR(255) NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_canary_decisions_canary ON canary_decisions(canary_id, created_at);
@@ -0,0 +1,34 @@
+-- A/B test analysis
+-- Results are computed by the API: per-variant confidence intervals,
+-- two-proportion z-tests for conversions, Welch's t-tests for means, a
+-- sample-ratio-mismatch check and sequential testing with alpha spending.
+-- Every interim analysis of the primary metric is recorded as a look so the
+-- alpha already spent is known at the next one.
+
+CREATE TYPE ab_metric_kind AS ENUM ('conversion', 'mean');
+
+ALTER TABLE ab_tests
+    ADD COLUMN primary_metric_kind ab_metric_kind NOT NULL DEFAULT 'mean',
+    -- False for metrics such as latency or fees, where lower is better
+    ADD COLUMN higher_is_better    BOOLEAN NOT NULL DEFAULT TRUE;
+
+CREATE TABLE ab_test_looks (
+    id                   UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    test_id              UUID NOT NULL REFERENCES ab_tests(id) ON DELETE CASCADE,
+    look_number          INTEGER NOT NULL,
+    information_fraction DOUBLE PRECISION NOT NULL,
+    control_n            BIGINT NOT NULL,
+    treatment_n          BIGINT NOT NULL,
+    alpha_spent          DOUBLE PRECISION NOT NULL,
+    nominal_alpha        DOUBLE PRECISION NOT NULL,
+    boundary_z           DOUBLE PRECISION NOT NULL,
+    z_statistic          DOUBLE PRECISION,
+    crossed              BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (test_id, look_number)
+);
+
+CREATE INDEX idx_ab_test_looks_test ON ab_test_looks(test_id, look_number);
+
+-- Superseded by the API's Welch test; the pooled version assumed equal variances
+DROP FUNCTION IF EXISTS calculate_statistical_significance(UUID);
@@ -0,0 +1,81 @@
+-- Feature flag targeting
+-- The feature flag API stored flags, analytics and A/B configuration in
+-- tables that no migration created; they are created here (if missing)
+-- together with the targeting model: allow/deny lists, attribute rules and
+-- prerequisite flags. Percentage rollouts hash the caller address with the
+-- flag ID, so a caller keeps its answer as the percentage grows.
+
+CREATE TABLE IF NOT EXISTS feature_flags (
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id        UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    name               VARCHAR(64) NOT NULL,
+    description        TEXT,
+    state              VARCHAR(16) NOT NULL DEFAULT 'inactive'
+        CHECK (state IN ('active', 'inactive', 'sunset')),
+    rollout_strategy   VARCHAR(16) NOT NULL DEFAULT 'full',
+    rollout_percentage INTEGER NOT NULL DEFAULT 100
+        CHECK (rollout_percentage BETWEEN 0 AND 100),
+    sunset_at          TIMESTAMPTZ,
+    created_by         VARCHAR(255),
+    ab_enabled         BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS feature_flag_analytics (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    flag_id             UUID NOT NULL UNIQUE REFERENCES feature_flags(id) ON DELETE CASCADE,
+    total_checks        BIGINT NOT NULL DEFAULT 0,
+    enabled_hits        BIGINT NOT NULL DEFAULT 0,
+    disabled_hits       BIGINT NOT NULL DEFAULT 0,
+    first_check_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_check_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    unique_users_approx BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS ab_test_configs (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id     UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    flag_name       VARCHAR(64) NOT NULL,
+    variant_a_pct   INTEGER NOT NULL,
+    variant_b_pct   INTEGER NOT NULL,
+    variant_a_label VARCHAR(255) NOT NULL,
+    variant_b_label VARCHAR(255) NOT NULL,
+    started_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ends_at         TIMESTAMPTZ,
+    UNIQUE (contract_id, flag_name)
+);
+
+-- Rules are tried in priority order; the first match decides
+CREATE TABLE feature_flag_rules (
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    flag_id            UUID NOT NULL REFERENCES feature_flags(id) ON DELETE CASCADE,
+    priority           INTEGER NOT NULL,
+    description        TEXT,
+    -- [{"attribute": "network", "operator": "in", "values": ["testnet"]}, ...]
+    conditions         JSONB NOT NULL DEFAULT '[]',
+    rollout_percentage INTEGER NOT NULL DEFAULT 100
+        CHECK (rollout_percentage BETWEEN 0 AND 100),
+    serve_enabled      BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (flag_id, priority)
+);
+
+CREATE TYPE flag_list_type AS ENUM ('allow', 'deny');
+
+CREATE TABLE feature_flag_targets (
+    flag_id    UUID NOT NULL REFERENCES feature_flags(id) ON DELETE CASCADE,
+    address    VARCHAR(56) NOT NULL,
+    list_type  flag_list_type NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (flag_id, address)
+);
+
+CREATE TABLE feature_flag_prerequisites (
+    flag_id              UUID NOT NULL REFERENCES feature_flags(id) ON DELETE CASCADE,
+    prerequisite_flag_id UUID NOT NULL REFERENCES feature_flags(id) ON DELETE CASCADE,
+    required_enabled     BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (flag_id, prerequisite_flag_id),
+    CHECK (flag_id <> prerequisite_flag_id)
+);
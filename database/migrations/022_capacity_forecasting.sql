@@ -0,0 +1,53 @@
+-- Capacity forecasting
+-- The capacity planning API read snapshots and stored alerts in tables that
+-- no migration created; they are created here together with the per-resource
+-- choice of forecasting model. 'auto' picks the model that backtests best on
+-- the resource's own snapshot history.
+
+CREATE TYPE resource_kind AS ENUM (
+    'storage_entries',
+    'cpu_instructions',
+    'unique_users',
+    'transaction_volume',
+    'wasm_size_bytes',
+    'fee_per_operation'
+);
+
+CREATE TABLE IF NOT EXISTS resource_snapshots (
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    resource    resource_kind NOT NULL,
+    value       DOUBLE PRECISION NOT NULL CHECK (value >= 0),
+    tag         VARCHAR(255),
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_resource_snapshots_contract_resource
+    ON resource_snapshots(contract_id, resource, recorded_at DESC);
+
+CREATE TABLE IF NOT EXISTS capacity_alerts (
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id         UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    resource            resource_kind NOT NULL,
+    severity            VARCHAR(16) NOT NULL,
+    current_value       DOUBLE PRECISION NOT NULL,
+    limit_value         DOUBLE PRECISION NOT NULL,
+    pct_consumed        DOUBLE PRECISION NOT NULL,
+    breach_predicted_at TIMESTAMPTZ,
+    days_until_breach   BIGINT,
+    message             TEXT NOT NULL,
+    acknowledged        BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at         TIMESTAMPTZ,
+    UNIQUE (contract_id, resource)
+);
+
+CREATE TYPE forecast_model AS ENUM ('auto', 'linear_trend', 'holt_winters', 'changepoint');
+
+CREATE TABLE capacity_model_settings (
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    resource    resource_kind NOT NULL,
+    model       forecast_model NOT NULL DEFAULT 'auto',
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (contract_id, resource)
+);
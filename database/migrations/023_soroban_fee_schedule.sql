@@ -0,0 +1,27 @@
+-- Soroban fee schedule
+-- Cost estimates are computed from the network's resource fee settings and
+-- per-method resource averages instead of fixed per-call constants. The
+-- averages are kept next to the existing gas/storage averages; the indexer
+-- records the network's ConfigSetting fee values in network_fee_configs.
+
+ALTER TABLE cost_estimates
+    ADD COLUMN IF NOT EXISTS avg_instructions  BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_read_entries  BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_write_entries BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_read_bytes    BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_write_bytes   BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_events_bytes  BIGINT,
+    ADD COLUMN IF NOT EXISTS avg_tx_size_bytes BIGINT,
+    ADD COLUMN IF NOT EXISTS resource_samples  INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS network_fee_configs (
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    network         network_type NOT NULL,
+    ledger_sequence BIGINT NOT NULL,
+    settings        JSONB NOT NULL,
+    captured_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (network, ledger_sequence)
+);
+
+CREATE INDEX IF NOT EXISTS idx_network_fee_configs_latest
+    ON network_fee_configs(network, ledger_sequence DESC);
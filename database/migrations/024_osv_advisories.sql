@@ -0,0 +1,15 @@
+-- OSV advisories
+-- Advisories are ingested in the OSV format (as published by RustSec and
+-- OSV's bulk exports). Each keeps its affected packages and SemVer ranges
+-- as JSON, so scans can tell 1.2.4 is safe when the fix landed in 1.2.3.
+-- Withdrawn advisories are kept but no longer match.
+
+ALTER TABLE cve_vulnerabilities
+    ADD COLUMN IF NOT EXISTS aliases      TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS affected     JSONB NOT NULL DEFAULT '[]',
+    ADD COLUMN IF NOT EXISTS source       VARCHAR(20) NOT NULL DEFAULT 'manual',
+    ADD COLUMN IF NOT EXISTS modified_at  TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS withdrawn_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_cve_affected ON cve_vulnerabilities USING GIN (affected jsonb_path_ops);
+CREATE INDEX IF NOT EXISTS idx_cve_aliases ON cve_vulnerabilities USING GIN (aliases);
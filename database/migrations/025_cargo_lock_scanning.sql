@@ -0,0 +1,29 @@
+-- Cargo.lock scanning
+-- Scans read the contract's Cargo.lock and check every crate in the
+-- resolved graph, so one crate can appear at several versions. The lockfile
+-- is kept so scans can be rerun when new advisories are synced, and each
+-- dependency and finding records its path from the contract's own crate.
+
+CREATE TABLE IF NOT EXISTS contract_lockfiles (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    content     TEXT NOT NULL,
+    source      VARCHAR(20) NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE contract_dependencies
+    DROP CONSTRAINT IF EXISTS contract_dependencies_contract_id_package_name_key,
+    ADD COLUMN IF NOT EXISTS dependency_path TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS is_direct BOOLEAN NOT NULL DEFAULT TRUE;
+
+ALTER TABLE contract_dependencies
+    ADD CONSTRAINT contract_dependencies_contract_package_version_key
+    UNIQUE (contract_id, package_name, version);
+
+ALTER TABLE contract_scan_results
+    DROP CONSTRAINT IF EXISTS contract_scan_results_contract_id_cve_id_key,
+    ADD COLUMN IF NOT EXISTS dependency_path TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE contract_scan_results
+    ADD CONSTRAINT contract_scan_results_contract_cve_version_key
+    UNIQUE (contract_id, cve_id, package_name, current_version);
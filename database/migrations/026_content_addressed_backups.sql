@@ -0,0 +1,36 @@
+-- Content-addressed contract backups
+-- A backup now captures the contract's indexed ledger entries as chunks in a
+-- blob store, keyed by SHA-256. The manifest lists the chunks in order; chunks
+-- shared with earlier backups are stored once. Backups can be taken more than
+-- once a day and are restored by ledger or timestamp.
+
+ALTER TABLE contract_backups
+    DROP CONSTRAINT IF EXISTS contract_backups_contract_id_backup_date_key;
+
+ALTER TABLE contract_backups
+    ADD COLUMN IF NOT EXISTS backup_kind VARCHAR(16) NOT NULL DEFAULT 'metadata',
+    ADD COLUMN IF NOT EXISTS parent_backup_id UUID REFERENCES contract_backups(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS ledger_sequence BIGINT,
+    ADD COLUMN IF NOT EXISTS manifest JSONB,
+    ADD COLUMN IF NOT EXISTS manifest_hash VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS entry_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS chunk_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS new_chunk_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS logical_size_bytes BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS blob_store VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_contract_backups_created
+    ON contract_backups(contract_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_contract_backups_ledger
+    ON contract_backups(contract_id, ledger_sequence DESC);
+
+-- Chunks known to be in the blob store, so backups skip re-uploading them
+CREATE TABLE IF NOT EXISTS backup_chunks (
+    hash            VARCHAR(64) NOT NULL,
+    size_bytes      BIGINT NOT NULL,
+    entry_count     INTEGER NOT NULL,
+    blob_store      VARCHAR(255) NOT NULL,
+    first_stored_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (hash, blob_store)
+);
@@ -0,0 +1,27 @@
+-- Cross-region backup replication
+-- One row per requested copy of a backup. The region is chosen against the
+-- contract's residency policies: a forbidden region is rerouted to a
+-- permitted one (`region` differs from `requested_region`) or refused.
+-- Replication lag is `replicated_at - contract_backups.created_at`.
+
+CREATE TABLE IF NOT EXISTS backup_replicas (
+    id               UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    backup_id        UUID NOT NULL REFERENCES contract_backups(id) ON DELETE CASCADE,
+    region           VARCHAR(64) NOT NULL,
+    requested_region VARCHAR(64) NOT NULL,
+    status           VARCHAR(16) NOT NULL DEFAULT 'pending'
+                     CHECK (status IN ('pending', 'completed', 'failed', 'refused')),
+    chunks_copied    INTEGER NOT NULL DEFAULT 0,
+    bytes_copied     BIGINT NOT NULL DEFAULT 0,
+    reason           TEXT,
+    error_message    TEXT,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    replicated_at    TIMESTAMPTZ,
+    UNIQUE(backup_id, requested_region)
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_replicas_backup ON backup_replicas(backup_id);
+CREATE INDEX IF NOT EXISTS idx_backup_replicas_region_status ON backup_replicas(region, status);
+
+-- Regions now come from the backup store configuration
+ALTER TABLE contract_backups ALTER COLUMN primary_region SET DEFAULT 'local';
@@ -0,0 +1,52 @@
+-- Residency rule expressions and data classification
+-- Policies can be scoped to data classifications, carry a deny list and
+-- ordered rule expressions (`deny US when classification = pii`), and name
+-- jurisdiction groups that expand to regions. Exceptions allow a region for
+-- a limited time and record who approved them. A policy may now be defined
+-- by rules alone, so `allowed_regions` can be empty.
+
+ALTER TABLE residency_policies DROP CONSTRAINT IF EXISTS residency_policies_allowed_regions_check;
+
+ALTER TABLE residency_policies
+    ADD COLUMN IF NOT EXISTS classifications TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS denied_regions  TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS rules           TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE residency_audit_logs
+    ADD COLUMN IF NOT EXISTS classification VARCHAR(16),
+    ADD COLUMN IF NOT EXISTS decided_by     VARCHAR(32);
+
+CREATE TABLE IF NOT EXISTS residency_jurisdictions (
+    name        VARCHAR(32)  PRIMARY KEY,
+    regions     TEXT[]       NOT NULL,
+    description TEXT,
+    updated_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO residency_jurisdictions (name, regions, description) VALUES
+    ('EU',   ARRAY['eu-central-1', 'eu-central-2', 'eu-west-1', 'eu-west-3',
+                   'eu-south-1', 'eu-south-2', 'eu-north-1'], 'European Union'),
+    ('UK',   ARRAY['eu-west-2'], 'United Kingdom'),
+    ('US',   ARRAY['us-east-1', 'us-east-2', 'us-west-1', 'us-west-2'], 'United States'),
+    ('CA',   ARRAY['ca-central-1', 'ca-west-1'], 'Canada'),
+    ('APAC', ARRAY['ap-*'], 'Asia Pacific')
+ON CONFLICT (name) DO NOTHING;
+
+CREATE TABLE IF NOT EXISTS residency_exceptions (
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    policy_id      UUID         NOT NULL REFERENCES residency_policies(id) ON DELETE CASCADE,
+    region         VARCHAR(64)  NOT NULL,
+    classification VARCHAR(16),
+    action         VARCHAR(64),
+    reason         TEXT         NOT NULL,
+    approved_by    VARCHAR(56)  NOT NULL,
+    starts_at      TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    expires_at     TIMESTAMPTZ  NOT NULL,
+    revoked_at     TIMESTAMPTZ,
+    created_at     TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    CHECK (expires_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_residency_exceptions_policy ON residency_exceptions(policy_id);
+CREATE INDEX IF NOT EXISTS idx_residency_exceptions_active
+    ON residency_exceptions(expires_at) WHERE revoked_at IS NULL;
@@ -0,0 +1,62 @@
+-- Publisher namespaces and verified organizations
+-- Contracts can be published under an organization's scope (`@org/name`);
+-- scoped names are unique per network. Organizations have members with
+-- roles, and are verified by a statement signed by their Stellar account
+-- and published in their home domain's stellar.toml. Reserved names can
+-- only be used by the organization they are reserved for.
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id                 UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slug               VARCHAR(39)  NOT NULL UNIQUE,
+    display_name       VARCHAR(255),
+    description        TEXT,
+    home_domain        VARCHAR(253),
+    stellar_address    VARCHAR(56),
+    verified           BOOLEAN      NOT NULL DEFAULT FALSE,
+    verified_at        TIMESTAMPTZ,
+    verification_nonce VARCHAR(64),
+    verification_error TEXT,
+    created_by         UUID         NOT NULL REFERENCES publishers(id),
+    created_at         TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    updated_at         TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_organizations_verified ON organizations(verified) WHERE verified;
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID         NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    publisher_id    UUID         NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    role            VARCHAR(16)  NOT NULL CHECK (role IN ('owner', 'admin', 'publisher')),
+    added_by        UUID         REFERENCES publishers(id),
+    added_at        TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, publisher_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_members_publisher ON organization_members(publisher_id);
+
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS namespace       VARCHAR(39),
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contracts_scoped_name
+    ON contracts(namespace, LOWER(name), network) WHERE namespace IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_contracts_organization ON contracts(organization_id);
+
+CREATE TABLE IF NOT EXISTS reserved_names (
+    name            VARCHAR(255) PRIMARY KEY,
+    organization_id UUID         REFERENCES organizations(id) ON DELETE SET NULL,
+    reason          TEXT,
+    created_at      TIMESTAMPTZ  NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO reserved_names (name, reason) VALUES
+    ('stellar',          'Network name'),
+    ('soroban',          'Network name'),
+    ('lumens',           'Network asset'),
+    ('xlm',              'Network asset'),
+    ('usdc',             'Issued asset'),
+    ('eurc',             'Issued asset'),
+    ('soroban-registry', 'This registry'),
+    ('official',         'Implies endorsement'),
+    ('admin',            'Implies endorsement')
+ON CONFLICT (name) DO NOTHING;
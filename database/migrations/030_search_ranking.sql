@@ -0,0 +1,121 @@
+-- Migration: 030_search_ranking.sql
+-- Ranked, faceted contract search
+--
+-- Strategy:
+--   • One trigger-maintained tsvector (search_document) covering name, tags,
+--     ABI function names, description and documentation, weighted A/A/B/B/C.
+--     A generated column cannot read the ABI JSONB array, so a BEFORE trigger
+--     extracts abi_functions/abi_docs and builds the vector in one place.
+--   • trust_score is denormalised onto contracts (refreshed hourly alongside
+--     the vocabulary) so ranking can boost on it without a join.
+--   • pg_trgm powers typo tolerance: fuzzy name matching and "did you mean"
+--     lookups against search_vocabulary.
+--   • search_synonyms holds admin-editable synonym groups expanded at query
+--     time, so edits apply without reindexing.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- ── 1. New searchable / facetable columns ───────────────────────────────────
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS license VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS documentation TEXT,
+    ADD COLUMN IF NOT EXISTS abi_functions TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS abi_docs TEXT,
+    ADD COLUMN IF NOT EXISTS trust_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS trust_score_updated_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS search_document tsvector;
+
+-- ── 2. Keep search_document in sync ─────────────────────────────────────────
+CREATE OR REPLACE FUNCTION contracts_search_document_refresh()
+RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $$
+DECLARE
+  specs JSONB;
+BEGIN
+  specs := CASE WHEN jsonb_typeof(NEW.abi) = 'array' THEN NEW.abi ELSE '[]'::jsonb END;
+
+  NEW.abi_functions := ARRAY(
+    SELECT DISTINCT spec->>'name'
+    FROM jsonb_array_elements(specs) AS spec
+    WHERE spec->>'name' IS NOT NULL
+      AND COALESCE(spec->>'type', 'function') = 'function'
+    ORDER BY 1
+  );
+
+  NEW.abi_docs := (
+    SELECT string_agg(spec->>'doc', ' ')
+    FROM jsonb_array_elements(specs) AS spec
+    WHERE COALESCE(spec->>'doc', '') <> ''
+  );
+
+  -- Underscores are split so "transfer_from" matches "transfer"
+  NEW.search_document :=
+      setweight(to_tsvector('english', NEW.name), 'A')
+   || setweight(to_tsvector('english', array_to_string(COALESCE(NEW.tags, '{}'), ' ')), 'A')
+   || setweight(to_tsvector('english',
+        regexp_replace(array_to_string(NEW.abi_functions, ' '), '_', ' ', 'g')), 'B')
+   || setweight(to_tsvector('english', COALESCE(NEW.description, '')), 'B')
+   || setweight(to_tsvector('english',
+        COALESCE(NEW.abi_docs, '') || ' ' || COALESCE(NEW.documentation, '')), 'C');
+
+  RETURN NEW;
+END;
+$$;
+
+DROP TRIGGER IF EXISTS contracts_search_document_trigger ON contracts;
+CREATE TRIGGER contracts_search_document_trigger
+    BEFORE INSERT OR UPDATE OF name, description, tags, abi, documentation ON contracts
+    FOR EACH ROW EXECUTE FUNCTION contracts_search_document_refresh();
+
+-- Backfill existing rows through the trigger
+UPDATE contracts SET abi = abi;
+
+-- ── 3. Indexes ──────────────────────────────────────────────────────────────
+CREATE INDEX IF NOT EXISTS idx_contracts_search_document
+    ON contracts USING GIN (search_document);
+
+CREATE INDEX IF NOT EXISTS idx_contracts_name_trgm
+    ON contracts USING GIN (LOWER(name) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_contracts_license ON contracts(license);
+CREATE INDEX IF NOT EXISTS idx_contracts_trust_score ON contracts(trust_score DESC);
+
+-- ── 4. Vocabulary for "did you mean" ────────────────────────────────────────
+-- Unstemmed words so suggestions read naturally. Refreshed by the hourly
+-- search index job.
+CREATE MATERIALIZED VIEW IF NOT EXISTS search_vocabulary AS
+SELECT word, ndoc
+FROM ts_stat($$
+    SELECT to_tsvector('simple',
+        name || ' ' ||
+        COALESCE(description, '') || ' ' ||
+        array_to_string(COALESCE(tags, '{}'), ' ') || ' ' ||
+        regexp_replace(array_to_string(abi_functions, ' '), '_', ' ', 'g'))
+    FROM contracts
+$$)
+WHERE length(word) >= 3;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_search_vocabulary_word ON search_vocabulary(word);
+CREATE INDEX IF NOT EXISTS idx_search_vocabulary_trgm
+    ON search_vocabulary USING GIN (word gin_trgm_ops);
+
+-- ── 5. Synonyms ─────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS search_synonyms (
+    term VARCHAR(64) PRIMARY KEY,
+    synonyms TEXT[] NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO search_synonyms (term, synonyms) VALUES
+    ('token',      ARRAY['fungible', 'asset', 'coin']),
+    ('nft',        ARRAY['collectible', 'non fungible']),
+    ('dex',        ARRAY['exchange', 'amm', 'swap']),
+    ('stablecoin', ARRAY['stable', 'pegged']),
+    ('dao',        ARRAY['governance', 'voting']),
+    ('oracle',     ARRAY['price feed']),
+    ('lending',    ARRAY['loan', 'borrow']),
+    ('multisig',   ARRAY['multi signature'])
+ON CONFLICT (term) DO NOTHING;
+
+ANALYZE contracts;
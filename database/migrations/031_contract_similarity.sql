@@ -0,0 +1,41 @@
+-- Migration: 031_contract_similarity.sql
+-- Contract similarity and fork detection
+--
+-- A fingerprint holds a hash of every function body in the contract's WASM
+-- code section. Contracts sharing many bodies are clones or forks; ABI and
+-- tag overlap come from the contracts table itself. Similarity is computed
+-- on read, so fingerprints are the only new state.
+
+CREATE TABLE IF NOT EXISTS contract_fingerprints (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    wasm_hash VARCHAR(64) NOT NULL,
+    -- Truncated SHA-256 of each function body, sorted and deduplicated
+    function_hashes TEXT[] NOT NULL DEFAULT '{}',
+    -- Functions in the code section, including ones too small to hash
+    function_count INTEGER NOT NULL,
+    wasm_size_bytes BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_fingerprints_hashes
+    ON contract_fingerprints USING GIN (function_hashes);
+CREATE INDEX IF NOT EXISTS idx_contract_fingerprints_wasm_hash
+    ON contract_fingerprints(wasm_hash);
+
+-- Candidate lookup by shared ABI functions and tags
+CREATE INDEX IF NOT EXISTS idx_contracts_abi_functions
+    ON contracts USING GIN (abi_functions);
+CREATE INDEX IF NOT EXISTS idx_contracts_tags
+    ON contracts USING GIN (tags);
+
+-- Contracts with an open critical or high advisory from dependency scans
+CREATE OR REPLACE VIEW vulnerable_contracts AS
+SELECT s.contract_id,
+       array_agg(DISTINCT s.cve_id ORDER BY s.cve_id) AS advisories,
+       CASE WHEN bool_or(UPPER(v.severity) = 'CRITICAL') THEN 'CRITICAL' ELSE 'HIGH' END AS severity
+FROM contract_scan_results s
+JOIN cve_vulnerabilities v ON v.cve_id = s.cve_id
+WHERE NOT s.is_false_positive
+  AND v.withdrawn_at IS NULL
+  AND UPPER(v.severity) IN ('CRITICAL', 'HIGH')
+GROUP BY s.contract_id;
@@ -0,0 +1,50 @@
+-- Migration: 032_trust_policies.sql
+-- Configurable trust policies and daily trust score snapshots
+--
+-- A policy is a named, versioned trust configuration: factor weights, caps,
+-- required minimums and the deploy gate threshold. Versions are immutable;
+-- publishing under an existing name adds the next version, and requests
+-- use the latest version unless they pin one. The `default` policy drives
+-- contracts.trust_score and search ranking.
+
+CREATE TABLE IF NOT EXISTS trust_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(64) NOT NULL,
+    version INTEGER NOT NULL CHECK (version > 0),
+    description TEXT,
+    config JSONB NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (name, version)
+);
+
+-- The original fixed weights
+INSERT INTO trust_policies (name, version, description, config, created_by)
+VALUES (
+    'default', 1, 'Built-in policy matching the original trust score weights',
+    '{
+        "weights": {"verification": 25, "audit": 35, "usage": 20, "age": 10, "vulnerabilities": 10},
+        "caps": {"deployments": 50, "interactions": 500, "age_days": 180, "penalty_per_vulnerability": 5},
+        "requirements": {"verified": false, "min_audit_score": null, "max_critical_vulns": null, "min_age_days": null},
+        "gate_threshold": 50
+    }'::jsonb,
+    'system'
+)
+ON CONFLICT (name, version) DO NOTHING;
+
+-- One point per contract, policy version and day; the hourly refresh
+-- overwrites the current day's point
+CREATE TABLE IF NOT EXISTS trust_score_snapshots (
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    policy_id UUID NOT NULL REFERENCES trust_policies(id) ON DELETE CASCADE,
+    snapshot_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    score DOUBLE PRECISION NOT NULL,
+    badge VARCHAR(16) NOT NULL,
+    meets_requirements BOOLEAN NOT NULL,
+    factors JSONB NOT NULL DEFAULT '[]',
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (contract_id, policy_id, snapshot_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_trust_score_snapshots_policy_date
+    ON trust_score_snapshots(policy_id, snapshot_date);